@@ -369,18 +369,20 @@ fn create_state_sync_runtimes<M: MempoolNotificationSender + 'static>(
         waypoint,
         db_rw,
         chunk_executor,
-        mempool_notifier,
+        Some(mempool_notifier),
         metadata_storage,
         consensus_listener,
         event_subscription_service,
         aptos_data_client,
         streaming_service_client,
-    );
+        vec![],
+    )
+    .map_err(|error| anyhow!("Failed to create the state sync driver: {}", error))?;
 
     // Create and return the new state sync handle
     Ok(StateSyncRuntimes::new(
         aptos_data_client_runtime,
-        state_sync,
+        state_sync.into_runtime_handle(),
         storage_service_runtime,
         streaming_service_runtime,
     ))
@@ -726,8 +728,11 @@ pub fn setup_environment(node_config: NodeConfig) -> anyhow::Result<AptosHandle>
     }
 
     // Create the telemetry service
-    let telemetry_runtime =
-        aptos_telemetry::service::start_telemetry_service(node_config.clone(), chain_id);
+    let telemetry_runtime = aptos_telemetry::service::start_telemetry_service(
+        node_config.clone(),
+        chain_id,
+        state_sync_runtimes.get_driver_metrics(),
+    );
 
     Ok(AptosHandle {
         _api: api_runtime,
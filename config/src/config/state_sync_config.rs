@@ -57,15 +57,48 @@ impl ContinuousSyncingMode {
 #[derive(Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StateSyncDriverConfig {
+    pub ack_consensus_commits_early: bool, // Whether to respond to consensus commits before notifying mempool and events (reconfigurations are always notified first)
     pub bootstrapping_mode: BootstrappingMode, // The mode by which to bootstrap
+    pub commit_backlog_high_water_mark: u64, // The commit backlog (in chunks) at which to pause feeding the storage synchronizer
+    pub commit_backlog_low_water_mark: u64, // The commit backlog (in chunks) at which to resume feeding the storage synchronizer
+    pub commit_batch_max_bytes: u64, // The max serialized size of executed chunks to commit in a single batch
+    pub commit_batch_max_chunks: u64, // The max number of executed chunks to commit in a single batch (1 disables batching)
+    pub commit_batch_max_delay_ms: u64, // The max time (ms) to wait for more executed chunks before committing a batch
     pub commit_notification_timeout_ms: u64, // The max time taken to process a commit notification
+    pub commit_retry_backoff_ms: u64, // The initial backoff (ms) between retries of transient storage commit failures
     pub continuous_syncing_mode: ContinuousSyncingMode, // The mode by which to sync after bootstrapping
+    pub execution_failure_window_secs: u64, // The time window (secs) in which repeated execution failures of the same chunk are counted
+    pub ledger_info_verification_parallelism: u64, // The number of threads used to verify ledger info signatures of queued transaction chunks
     pub progress_check_interval_ms: u64, // The interval (ms) at which to check state sync progress
+    pub max_chunk_bytes: u64, // The max serialized size of a transaction (or output) chunk accepted for storage (larger chunks are rejected as invalid)
+    pub max_chunk_cancellation_wait_ms: u64, // The max time (ms) to wait for in-flight chunk execution to stop when pending data is reset
+    pub max_commit_retries: u64, // The max number of times to retry a transient storage commit failure (0 disables retries)
     pub max_connection_deadline_secs: u64, // The max time (secs) to wait for connections from peers
     pub max_consecutive_stream_notifications: u64, // The max number of notifications to process per driver loop
-    pub max_pending_data_chunks: u64, // The max number of data chunks pending execution or commit
+    pub max_consensus_sync_request_duration_ms: u64, // The max time (ms) to spend on a consensus sync request (0 disables the timeout)
+    pub max_execution_failures_before_fallback: u64, // The max number of execution failures of the same chunk (within the window) before falling back to applying outputs (0 disables the fallback)
+    pub max_mempool_notification_retries: u64, // The max number of times to retry a failed mempool notification
+    pub max_pending_client_notifications: u64, // The max number of client notifications pending handling by the driver
+    pub max_pending_commit_notifications: u64, // The max number of undelivered commit notifications before coalescing
+    pub max_pending_data_chunks: u64, // The max number of data chunks pending execution (or state value commit)
+    pub max_pending_executed_chunks: u64, // The max number of executed chunks pending commit
+    pub max_pending_event_notifications: u64, // The max number of event notifications pending delivery to subscribers
+    pub max_pruning_pause_idle_secs: u64, // The max time (secs) to keep pruning paused while waiting for state value chunks
+    pub max_shutdown_drain_ms: u64, // The max time (ms) to wait for pending chunks to be committed on shutdown (before discarding them)
+    pub max_stream_initialization_retries: u64, // The max number of times to retry a transient failure to initialize a data stream (0 disables retries)
     pub max_stream_wait_time_ms: u64, // The max time (ms) to wait for a data stream notification
+    pub mempool_notification_batch_max_delay_ms: u64, // The max time (ms) to batch mempool notifications (0 disables batching)
+    pub mempool_notification_batch_max_transactions: u64, // The max number of transactions to batch per mempool notification
+    pub mempool_notification_retry_backoff_ms: u64, // The initial backoff (ms) between mempool notification retries
+    pub mempool_notification_retry_deadline_ms: u64, // The max time (ms) to spend retrying a mempool notification
+    pub num_committer_threads: u64, // The number of threads used by the storage synchronizer to commit executed chunks
+    pub num_executor_threads: u64, // The number of threads used by the storage synchronizer to execute/apply chunks
     pub num_versions_to_skip_snapshot_sync: u64, // The version lag we'll tolerate before snapshot syncing
+    pub output_fallback_cooldown_secs: u64, // The time (secs) to apply outputs before reverting to execution (0 never reverts)
+    pub slow_chunk_latency_threshold_ms: u64, // The execute/apply and commit time (ms) above which a transaction chunk is logged as slow
+    pub stream_initialization_retry_backoff_ms: u64, // The initial backoff (ms) between retries of transient data stream initialization failures
+    pub verify_event_root_hashes: bool, // Whether to recompute and verify the event root hash of each output before applying an output chunk
+    pub verify_state_values_in_storage_synchronizer: bool, // Whether state value chunks are verified by the storage synchronizer (instead of the bootstrapper)
 }
 
 /// The default state sync driver config will be the one that gets (and keeps)
@@ -73,15 +106,48 @@ pub struct StateSyncDriverConfig {
 impl Default for StateSyncDriverConfig {
     fn default() -> Self {
         Self {
+            ack_consensus_commits_early: false,
             bootstrapping_mode: BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+            commit_backlog_high_water_mark: 80,
+            commit_backlog_low_water_mark: 40,
+            commit_batch_max_bytes: 50 * 1024 * 1024, // 50 MiB
+            commit_batch_max_chunks: 1,
+            commit_batch_max_delay_ms: 10,
             commit_notification_timeout_ms: 5000,
+            commit_retry_backoff_ms: 100,
             continuous_syncing_mode: ContinuousSyncingMode::ApplyTransactionOutputs,
+            execution_failure_window_secs: 300,
+            ledger_info_verification_parallelism: 2,
             progress_check_interval_ms: 100,
+            max_chunk_bytes: 2 * MAX_APPLICATION_MESSAGE_SIZE as u64, // Well above any chunk that fits in a network message
+            max_chunk_cancellation_wait_ms: 1000,
+            max_commit_retries: 3,
             max_connection_deadline_secs: 10,
             max_consecutive_stream_notifications: 10,
+            max_consensus_sync_request_duration_ms: 0,
+            max_execution_failures_before_fallback: 3,
+            max_mempool_notification_retries: 5,
+            max_pending_client_notifications: 100,
+            max_pending_commit_notifications: 50,
             max_pending_data_chunks: 100,
+            max_pending_executed_chunks: 100,
+            max_pending_event_notifications: 100,
+            max_pruning_pause_idle_secs: 600,
+            max_shutdown_drain_ms: 10_000,
+            max_stream_initialization_retries: 2,
             max_stream_wait_time_ms: 5000,
+            mempool_notification_batch_max_delay_ms: 0,
+            mempool_notification_batch_max_transactions: 1000,
+            mempool_notification_retry_backoff_ms: 100,
+            mempool_notification_retry_deadline_ms: 10_000,
+            num_committer_threads: 2,
+            num_executor_threads: 8,
             num_versions_to_skip_snapshot_sync: 10_000_000, // At 1k TPS, this allows a node to fail for about 3 hours.
+            output_fallback_cooldown_secs: 3600,
+            slow_chunk_latency_threshold_ms: 1000,
+            stream_initialization_retry_backoff_ms: 100,
+            verify_event_root_hashes: false, // The executor already verifies events when applying outputs
+            verify_state_values_in_storage_synchronizer: false,
         }
     }
 }
@@ -5,7 +5,9 @@ use crate::{utils, utils::sum_all_histogram_counts};
 use aptos_config::config::NodeConfig;
 use aptos_telemetry_service::types::telemetry::TelemetryEvent;
 use prometheus::core::Collector;
-use state_sync_driver::metrics::StorageSynchronizerOperations;
+use state_sync_driver::metrics::{
+    DriverMetrics, StorageSynchronizerOperations, STORAGE_SYNCHRONIZER_OPERATIONS,
+};
 use std::collections::BTreeMap;
 
 /// Core metrics event name
@@ -30,9 +32,12 @@ const TELEMETRY_FAILURE_COUNT: &str = "telemetry_failure_count";
 const TELEMETRY_SUCCESS_COUNT: &str = "telemetry_success_count";
 
 /// Collects and sends the build information via telemetry
-pub(crate) async fn create_core_metric_telemetry_event(node_config: &NodeConfig) -> TelemetryEvent {
+pub(crate) async fn create_core_metric_telemetry_event(
+    node_config: &NodeConfig,
+    driver_metrics: DriverMetrics,
+) -> TelemetryEvent {
     // Collect the core metrics
-    let core_metrics = get_core_metrics(node_config);
+    let core_metrics = get_core_metrics(node_config, driver_metrics);
 
     // Create and return a new telemetry event
     TelemetryEvent {
@@ -41,20 +46,28 @@ pub(crate) async fn create_core_metric_telemetry_event(node_config: &NodeConfig)
     }
 }
 
-/// Used to expose core metrics for the node
-pub fn get_core_metrics(node_config: &NodeConfig) -> BTreeMap<String, String> {
+/// Used to expose core metrics for the node. The driver metrics are those of
+/// the node's state sync driver instance.
+pub fn get_core_metrics(
+    node_config: &NodeConfig,
+    driver_metrics: DriverMetrics,
+) -> BTreeMap<String, String> {
     let mut core_metrics: BTreeMap<String, String> = BTreeMap::new();
-    collect_core_metrics(&mut core_metrics, node_config);
+    collect_core_metrics(&mut core_metrics, node_config, driver_metrics);
     core_metrics
 }
 
 /// Collects the core metrics and appends them to the given map
-fn collect_core_metrics(core_metrics: &mut BTreeMap<String, String>, node_config: &NodeConfig) {
+fn collect_core_metrics(
+    core_metrics: &mut BTreeMap<String, String>,
+    node_config: &NodeConfig,
+    driver_metrics: DriverMetrics,
+) {
     // Collect the core metrics for each component
     collect_consensus_metrics(core_metrics);
     collect_mempool_metrics(core_metrics);
     collect_rest_metrics(core_metrics);
-    collect_state_sync_metrics(core_metrics, node_config);
+    collect_state_sync_metrics(core_metrics, node_config, driver_metrics);
     collect_storage_metrics(core_metrics);
     collect_telemetry_metrics(core_metrics);
 
@@ -102,6 +115,7 @@ fn collect_rest_metrics(core_metrics: &mut BTreeMap<String, String>) {
 fn collect_state_sync_metrics(
     core_metrics: &mut BTreeMap<String, String>,
     node_config: &NodeConfig,
+    driver_metrics: DriverMetrics,
 ) {
     let state_sync_driver_config = node_config.state_sync.state_sync_driver;
 
@@ -110,16 +124,20 @@ fn collect_state_sync_metrics(
 
     core_metrics.insert(
         STATE_SYNC_SYNCED_EPOCH.into(),
-        state_sync_driver::metrics::STORAGE_SYNCHRONIZER_OPERATIONS
-            .with_label_values(&[StorageSynchronizerOperations::SyncedEpoch.get_label()])
-            .get()
+        driver_metrics
+            .read_gauge(
+                &STORAGE_SYNCHRONIZER_OPERATIONS,
+                StorageSynchronizerOperations::SyncedEpoch.get_label(),
+            )
             .to_string(),
     );
     core_metrics.insert(
         STATE_SYNC_SYNCED_VERSION.into(),
-        state_sync_driver::metrics::STORAGE_SYNCHRONIZER_OPERATIONS
-            .with_label_values(&[StorageSynchronizerOperations::Synced.get_label()])
-            .get()
+        driver_metrics
+            .read_gauge(
+                &STORAGE_SYNCHRONIZER_OPERATIONS,
+                StorageSynchronizerOperations::Synced.get_label(),
+            )
             .to_string(),
     );
     core_metrics.insert(
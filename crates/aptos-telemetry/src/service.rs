@@ -26,6 +26,7 @@ use aptos_config::config::NodeConfig;
 use aptos_logger::prelude::*;
 use aptos_telemetry_service::types::telemetry::{TelemetryDump, TelemetryEvent};
 use aptos_types::chain_id::ChainId;
+use state_sync_driver::metrics::DriverMetrics;
 
 use crate::constants::{
     ENV_APTOS_DISABLE_EXPERIMENTAL_PUSH_METRICS, ENV_TELEMETRY_SERVICE_URL,
@@ -73,7 +74,11 @@ fn enable_experimental_prometheus_push_metrics() -> bool {
 
 /// Starts the telemetry service and returns the execution runtime.
 /// Note: The service will not be created if telemetry is disabled.
-pub fn start_telemetry_service(node_config: NodeConfig, chain_id: ChainId) -> Option<Runtime> {
+pub fn start_telemetry_service(
+    node_config: NodeConfig,
+    chain_id: ChainId,
+    driver_metrics: DriverMetrics,
+) -> Option<Runtime> {
     // Don't start the service if telemetry has been disabled
     if telemetry_is_disabled() {
         warn!("Aptos telemetry is disabled!");
@@ -89,9 +94,12 @@ pub fn start_telemetry_service(node_config: NodeConfig, chain_id: ChainId) -> Op
 
     // Spawn the telemetry service
     let peer_id = fetch_peer_id(&node_config);
-    telemetry_runtime
-        .handle()
-        .spawn(spawn_telemetry_service(peer_id, chain_id, node_config));
+    telemetry_runtime.handle().spawn(spawn_telemetry_service(
+        peer_id,
+        chain_id,
+        node_config,
+        driver_metrics,
+    ));
 
     Some(telemetry_runtime)
 }
@@ -117,7 +125,12 @@ where
 }
 
 /// Spawns the dedicated telemetry service that operates periodically
-async fn spawn_telemetry_service(peer_id: String, chain_id: ChainId, node_config: NodeConfig) {
+async fn spawn_telemetry_service(
+    peer_id: String,
+    chain_id: ChainId,
+    node_config: NodeConfig,
+    driver_metrics: DriverMetrics,
+) {
     let telemetry_svc_url =
         env::var(ENV_TELEMETRY_SERVICE_URL).unwrap_or_else(|_| TELEMETRY_SERVICE_URL.into());
 
@@ -140,7 +153,12 @@ async fn spawn_telemetry_service(peer_id: String, chain_id: ChainId, node_config
         }),
         // Periodically send node core metrics
         run_function_periodically(NODE_CORE_METRICS_FREQ_SECS, || {
-            send_node_core_metrics(peer_id.clone(), &node_config, telemetry_sender.clone())
+            send_node_core_metrics(
+                peer_id.clone(),
+                &node_config,
+                driver_metrics,
+                telemetry_sender.clone(),
+            )
         }),
         // Periodically send node network metrics
         run_function_periodically(NODE_NETWORK_METRICS_FREQ_SECS, || {
@@ -176,9 +194,10 @@ async fn send_build_information(
 async fn send_node_core_metrics(
     peer_id: String,
     node_config: &NodeConfig,
+    driver_metrics: DriverMetrics,
     telemetry_sender: TelemetrySender,
 ) {
-    let telemetry_event = create_core_metric_telemetry_event(node_config).await;
+    let telemetry_event = create_core_metric_telemetry_event(node_config, driver_metrics).await;
     send_telemetry_event_with_ip(peer_id, Some(telemetry_sender), telemetry_event).await;
 }
 
@@ -3,9 +3,16 @@
 
 #![forbid(unsafe_code)]
 
-use std::{cmp::max, collections::HashMap, sync::Arc};
+use std::{
+    cmp::max,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use serde::{Deserialize, Serialize};
 
 use aptos_crypto::{
@@ -34,6 +41,35 @@ mod executed_chunk;
 pub mod in_memory_state_calculator;
 mod parsed_transaction_output;
 
+/// A token used to cancel in-flight chunk execution. Cancellation is cooperative,
+/// i.e., the executor checks the token at safe checkpoints and abandons the chunk
+/// (returning an error) once the token has been cancelled.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token (and all of its clones)
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns an error if the token has been cancelled
+    pub fn ensure_not_cancelled(&self) -> Result<()> {
+        ensure!(!self.is_cancelled(), "The chunk execution was cancelled!");
+        Ok(())
+    }
+}
+
 pub trait ChunkExecutorTrait: Send + Sync {
     /// Verifies the transactions based on the provided proofs and ledger info. If the transactions
     /// are valid, executes them and returns the executed result for commit.
@@ -55,9 +91,44 @@ pub trait ChunkExecutorTrait: Send + Sync {
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> anyhow::Result<()>;
 
+    /// Similar to `execute_chunk`, but the execution is abandoned (at safe checkpoints)
+    /// once the given cancellation token is cancelled, in which case an error is returned.
+    fn execute_chunk_cancellable(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        cancellation_token.ensure_not_cancelled()?;
+        self.execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)
+    }
+
+    /// Similar to `apply_chunk`, but the application is abandoned (at safe checkpoints)
+    /// once the given cancellation token is cancelled, in which case an error is returned.
+    fn apply_chunk_cancellable(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        cancellation_token.ensure_not_cancelled()?;
+        self.apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )
+    }
+
     /// Commit a previously executed chunk. Returns a chunk commit notification.
     fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
 
+    /// Commit the next `num_chunks` previously executed chunks in a single write batch.
+    /// Only the last chunk may carry a ledger info. Returns a single chunk commit
+    /// notification covering all the committed chunks.
+    fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification>;
+
     fn execute_and_commit_chunk(
         &self,
         txn_list_with_proof: TransactionListWithProof,
@@ -8,6 +8,7 @@ use crate::{
         apply_chunk_output::{ensure_no_discard, ensure_no_retry},
         chunk_commit_queue::ChunkCommitQueue,
         chunk_output::ChunkOutput,
+        chunk_state_cache::{CachedProofFetcher, ChunkStateCache},
     },
     logging::{LogEntry, LogSchema},
     metrics::{
@@ -15,7 +16,7 @@ use crate::{
         APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS, APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS,
     },
 };
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
 use aptos_state_view::StateViewId;
@@ -27,25 +28,40 @@ use aptos_types::{
 };
 use aptos_vm::VMExecutor;
 use executor_types::{
-    ChunkCommitNotification, ChunkExecutorTrait, ExecutedChunk, TransactionReplayer,
+    CancellationToken, ChunkCommitNotification, ChunkExecutorTrait, ExecutedChunk,
+    TransactionReplayer,
 };
 use fail::fail_point;
 use std::{marker::PhantomData, sync::Arc};
 use storage_interface::{
-    cached_state_view::CachedStateView, sync_proof_fetcher::SyncProofFetcher, DbReaderWriter,
-    ExecutedTrees,
+    cached_state_view::CachedStateView, proof_fetcher::ProofFetcher,
+    sync_proof_fetcher::SyncProofFetcher, DbReaderWriter, ExecutedTrees,
 };
 
 pub struct ChunkExecutor<V> {
     db: DbReaderWriter,
     inner: RwLock<Option<ChunkExecutorInner<V>>>,
+    // The state cache kept alive across consecutive chunks (if enabled)
+    chunk_state_cache: Option<Arc<ChunkStateCache>>,
 }
 
 impl<V: VMExecutor> ChunkExecutor<V> {
     pub fn new(db: DbReaderWriter) -> Self {
+        Self::new_with_state_cache(db, true)
+    }
+
+    /// Creates a chunk executor that (optionally) caches the persisted state
+    /// read by each chunk, so that consecutive chunks avoid re-reading it.
+    pub fn new_with_state_cache(db: DbReaderWriter, enable_state_cache: bool) -> Self {
+        let chunk_state_cache = if enable_state_cache {
+            Some(Arc::new(ChunkStateCache::default()))
+        } else {
+            None
+        };
         Self {
             db,
             inner: RwLock::new(None),
+            chunk_state_cache,
         }
     }
 
@@ -55,6 +71,20 @@ impl<V: VMExecutor> ChunkExecutor<V> {
         }
         Ok(())
     }
+
+    fn invalidate_state_cache(&self) {
+        if let Some(chunk_state_cache) = &self.chunk_state_cache {
+            chunk_state_cache.invalidate();
+        }
+    }
+
+    /// Invalidates the state cache if the given result is an error
+    fn invalidate_state_cache_on_error<T>(&self, result: Result<T>) -> Result<T> {
+        if result.is_err() {
+            self.invalidate_state_cache();
+        }
+        result
+    }
 }
 
 impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
@@ -63,34 +93,85 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.execute_chunk_cancellable(
+            txn_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            &CancellationToken::new(),
+        )
+    }
+
+    fn apply_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.apply_chunk_cancellable(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            &CancellationToken::new(),
+        )
+    }
+
+    fn execute_chunk_cancellable(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
     ) -> Result<()> {
         self.maybe_initialize()?;
-        self.inner
+        let result = self
+            .inner
             .read()
             .as_ref()
             .expect("not reset")
-            .execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)
+            .execute_chunk(
+                txn_list_with_proof,
+                verified_target_li,
+                epoch_change_li,
+                cancellation_token,
+            );
+        self.invalidate_state_cache_on_error(result)
     }
 
-    fn apply_chunk(
+    fn apply_chunk_cancellable(
         &self,
         txn_output_list_with_proof: TransactionOutputListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
     ) -> Result<()> {
-        self.inner.read().as_ref().expect("not reset").apply_chunk(
+        let result = self.inner.read().as_ref().expect("not reset").apply_chunk(
             txn_output_list_with_proof,
             verified_target_li,
             epoch_change_li,
-        )
+            cancellation_token,
+        );
+        self.invalidate_state_cache_on_error(result)
     }
 
     fn commit_chunk(&self) -> Result<ChunkCommitNotification> {
-        self.inner
+        let result = self
+            .inner
             .read()
             .as_ref()
             .expect("not reset")
-            .commit_chunk()
+            .commit_chunk();
+        self.invalidate_state_cache_on_error(result)
+    }
+
+    fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        let result = self
+            .inner
+            .read()
+            .as_ref()
+            .expect("not reset")
+            .commit_chunks(num_chunks);
+        self.invalidate_state_cache_on_error(result)
     }
 
     fn execute_and_commit_chunk(
@@ -128,11 +209,16 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
     }
 
     fn reset(&self) -> Result<()> {
-        *self.inner.write() = Some(ChunkExecutorInner::new(self.db.clone())?);
+        self.invalidate_state_cache();
+        *self.inner.write() = Some(ChunkExecutorInner::new(
+            self.db.clone(),
+            self.chunk_state_cache.clone(),
+        )?);
         Ok(())
     }
 
     fn finish(&self) {
+        self.invalidate_state_cache();
         *self.inner.write() = None;
     }
 }
@@ -140,29 +226,52 @@ impl<V: VMExecutor> ChunkExecutorTrait for ChunkExecutor<V> {
 struct ChunkExecutorInner<V> {
     db: DbReaderWriter,
     commit_queue: Mutex<ChunkCommitQueue>,
+    chunk_state_cache: Option<Arc<ChunkStateCache>>,
     _phantom: PhantomData<V>,
 }
 
 impl<V: VMExecutor> ChunkExecutorInner<V> {
-    pub fn new(db: DbReaderWriter) -> Result<Self> {
+    pub fn new(
+        db: DbReaderWriter,
+        chunk_state_cache: Option<Arc<ChunkStateCache>>,
+    ) -> Result<Self> {
         let commit_queue = Mutex::new(ChunkCommitQueue::new_from_db(&db.reader)?);
         Ok(Self {
             db,
             commit_queue,
+            chunk_state_cache,
             _phantom: PhantomData,
         })
     }
 
     fn state_view(&self, latest_view: &ExecutedTrees) -> Result<CachedStateView> {
+        let proof_fetcher: Arc<dyn ProofFetcher> = match &self.chunk_state_cache {
+            Some(chunk_state_cache) => Arc::new(CachedProofFetcher::new(
+                self.db.reader.clone(),
+                chunk_state_cache.clone(),
+            )),
+            None => Arc::new(SyncProofFetcher::new(self.db.reader.clone())),
+        };
         latest_view.verified_state_view(
             StateViewId::ChunkExecution {
                 first_version: latest_view.txn_accumulator().num_leaves(),
             },
             Arc::clone(&self.db.reader),
-            Arc::new(SyncProofFetcher::new(self.db.reader.clone())),
+            proof_fetcher,
         )
     }
 
+    /// Adds the executed chunk to the commit queue. If the chunk ends the
+    /// epoch, the state cache is invalidated.
+    fn enqueue_executed_chunk(&self, executed_chunk: ExecutedChunk) {
+        if executed_chunk.has_reconfiguration() {
+            if let Some(chunk_state_cache) = &self.chunk_state_cache {
+                chunk_state_cache.invalidate();
+            }
+        }
+        self.commit_queue.lock().enqueue(executed_chunk);
+    }
+
     fn apply_chunk_output_for_state_sync(
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
@@ -203,12 +312,53 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         Ok(to_commit)
     }
 
+    /// Commits the next `num_chunks` executed chunks with a single call to
+    /// storage (i.e., as one write batch). The commit is atomic, exactly like
+    /// the single chunk commit, so the chunks are either all committed or not
+    /// committed at all.
+    fn commit_chunks_impl(&self, num_chunks: usize) -> Result<Vec<Arc<ExecutedChunk>>> {
+        let (base_view, to_commit) = self.commit_queue.lock().next_chunks_to_commit(num_chunks)?;
+        let (last_chunk, other_chunks) = to_commit
+            .split_last()
+            .expect("The commit queue returned no chunks!");
+        ensure!(
+            other_chunks.iter().all(|chunk| chunk.ledger_info.is_none()),
+            "Only the last chunk in a commit batch may carry a ledger info!"
+        );
+
+        let mut txns_to_commit = vec![];
+        for chunk in &to_commit {
+            txns_to_commit.extend(chunk.transactions_to_commit()?);
+        }
+        let ledger_info = last_chunk.ledger_info.as_ref();
+        if ledger_info.is_some() || !txns_to_commit.is_empty() {
+            fail_point!("executor::commit_chunk", |_| {
+                Err(anyhow::anyhow!("Injected error in commit_chunk"))
+            });
+            self.db.writer.save_transactions(
+                &txns_to_commit,
+                base_view.txn_accumulator().num_leaves(),
+                base_view.state().base_version,
+                ledger_info,
+                false, /* sync_commit */
+                last_chunk.result_view.state().clone(),
+            )?;
+        }
+
+        let mut commit_queue = self.commit_queue.lock();
+        for _ in 0..num_chunks {
+            commit_queue.dequeue()?;
+        }
+        Ok(to_commit)
+    }
+
     // ************************* Block Executor Implementation *************************
     fn execute_chunk(
         &self,
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
     ) -> Result<()> {
         let _timer = APTOS_EXECUTOR_EXECUTE_CHUNK_SECONDS.start_timer();
 
@@ -217,7 +367,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         let (_persisted_view, latest_view) = self.commit_queue.lock().persisted_and_latest_view();
 
         // Verify input transaction list.
+        cancellation_token.ensure_not_cancelled()?;
         txn_list_with_proof.verify(verified_target_li.ledger_info(), first_version_in_request)?;
+        cancellation_token.ensure_not_cancelled()?;
 
         // Skip transactions already in ledger.
         let txns_to_skip = txn_list_with_proof.proof.verify_extends_ledger(
@@ -240,6 +392,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             let _timer = APTOS_EXECUTOR_VM_EXECUTE_CHUNK_SECONDS.start_timer();
             ChunkOutput::by_transaction_execution::<V>(transactions, state_view)?
         };
+        cancellation_token.ensure_not_cancelled()?;
         let executed_chunk = Self::apply_chunk_output_for_state_sync(
             verified_target_li,
             epoch_change_li,
@@ -248,8 +401,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             &txn_list_with_proof.proof.transaction_infos[txns_to_skip..],
         )?;
 
-        // Add result to commit queue.
-        self.commit_queue.lock().enqueue(executed_chunk);
+        // Add result to commit queue (unless the execution was cancelled).
+        cancellation_token.ensure_not_cancelled()?;
+        self.enqueue_executed_chunk(executed_chunk);
 
         info!(
             LogSchema::new(LogEntry::ChunkExecutor)
@@ -267,6 +421,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         txn_output_list_with_proof: TransactionOutputListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
     ) -> Result<()> {
         let _timer = APTOS_EXECUTOR_APPLY_CHUNK_SECONDS.start_timer();
 
@@ -275,8 +430,10 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         let (_persisted_view, latest_view) = self.commit_queue.lock().persisted_and_latest_view();
 
         // Verify input transaction list.
+        cancellation_token.ensure_not_cancelled()?;
         txn_output_list_with_proof
             .verify(verified_target_li.ledger_info(), first_version_in_request)?;
+        cancellation_token.ensure_not_cancelled()?;
 
         // Skip transactions already in ledger.
         let txns_to_skip = txn_output_list_with_proof.proof.verify_extends_ledger(
@@ -290,6 +447,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         // Apply transaction outputs.
         let state_view = self.state_view(&latest_view)?;
         let chunk_output = ChunkOutput::by_transaction_output(txns_and_outputs, state_view)?;
+        cancellation_token.ensure_not_cancelled()?;
         let executed_chunk = Self::apply_chunk_output_for_state_sync(
             verified_target_li,
             epoch_change_li,
@@ -298,8 +456,9 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             &txn_output_list_with_proof.proof.transaction_infos[txns_to_skip..],
         )?;
 
-        // Add result to commit queue.
-        self.commit_queue.lock().enqueue(executed_chunk);
+        // Add result to commit queue (unless the application was cancelled).
+        cancellation_token.ensure_not_cancelled()?;
+        self.enqueue_executed_chunk(executed_chunk);
 
         info!(
             LogSchema::new(LogEntry::ChunkExecutor)
@@ -322,13 +481,36 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
         })
     }
 
+    fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        let _timer = APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS.start_timer();
+        let executed_chunks = self.commit_chunks_impl(num_chunks)?;
+        Ok(ChunkCommitNotification {
+            committed_events: executed_chunks
+                .iter()
+                .flat_map(|executed_chunk| executed_chunk.events_to_commit())
+                .collect(),
+            committed_transactions: executed_chunks
+                .iter()
+                .flat_map(|executed_chunk| executed_chunk.transactions())
+                .collect(),
+            reconfiguration_occurred: executed_chunks
+                .iter()
+                .any(|executed_chunk| executed_chunk.has_reconfiguration()),
+        })
+    }
+
     fn execute_and_commit_chunk(
         &self,
         txn_list_with_proof: TransactionListWithProof,
         verified_target_li: &LedgerInfoWithSignatures,
         epoch_change_li: Option<&LedgerInfoWithSignatures>,
     ) -> Result<ChunkCommitNotification> {
-        self.execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)?;
+        self.execute_chunk(
+            txn_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            &CancellationToken::new(),
+        )?;
         self.commit_chunk()
     }
 
@@ -342,6 +524,7 @@ impl<V: VMExecutor> ChunkExecutorInner<V> {
             txn_output_list_with_proof,
             verified_target_li,
             epoch_change_li,
+            &CancellationToken::new(),
         )?;
         self.commit_chunk()
     }
@@ -354,15 +537,18 @@ impl<V: VMExecutor> TransactionReplayer for ChunkExecutor<V> {
         transaction_infos: Vec<TransactionInfo>,
     ) -> Result<()> {
         self.maybe_initialize()?;
-        self.inner
+        let result = self
+            .inner
             .read()
             .as_ref()
             .expect("not reset")
-            .replay(transactions, transaction_infos)
+            .replay(transactions, transaction_infos);
+        self.invalidate_state_cache_on_error(result)
     }
 
     fn commit(&self) -> Result<Arc<ExecutedChunk>> {
-        self.inner.read().as_ref().expect("not reset").commit()
+        let result = self.inner.read().as_ref().expect("not reset").commit();
+        self.invalidate_state_cache_on_error(result)
     }
 }
 
@@ -397,7 +583,7 @@ impl<V: VMExecutor> TransactionReplayer for ChunkExecutorInner<V> {
         }
 
         // Add result to commit queue.
-        self.commit_queue.lock().enqueue(executed_chunk);
+        self.enqueue_executed_chunk(executed_chunk);
 
         Ok(())
     }
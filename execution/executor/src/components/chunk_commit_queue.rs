@@ -3,7 +3,7 @@
 
 #![forbid(unsafe_code)]
 
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, ensure, Result};
 
 use executor_types::ExecutedChunk;
 use std::{collections::VecDeque, sync::Arc};
@@ -48,6 +48,26 @@ impl ChunkCommitQueue {
         ))
     }
 
+    pub fn next_chunks_to_commit(
+        &self,
+        num_chunks: usize,
+    ) -> Result<(ExecutedTrees, Vec<Arc<ExecutedChunk>>)> {
+        ensure!(
+            num_chunks > 0 && num_chunks <= self.chunks_to_commit.len(),
+            "Not enough chunks in the commit queue. Requested: {}, queued: {}",
+            num_chunks,
+            self.chunks_to_commit.len(),
+        );
+        Ok((
+            self.persisted_view.clone(),
+            self.chunks_to_commit
+                .iter()
+                .take(num_chunks)
+                .map(Arc::clone)
+                .collect(),
+        ))
+    }
+
     pub fn enqueue(&mut self, chunk: ExecutedChunk) {
         self.chunks_to_commit.push_back(Arc::new(chunk))
     }
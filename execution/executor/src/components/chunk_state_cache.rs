@@ -0,0 +1,160 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+use crate::metrics::APTOS_EXECUTOR_CHUNK_STATE_CACHE_LOOKUPS;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_types::{
+    proof::SparseMerkleProofExt,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use lru::LruCache;
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::{
+    proof_fetcher::ProofFetcher, sync_proof_fetcher::SyncProofFetcher, DbReader,
+};
+
+type StateValueAndProof = (Option<StateValue>, Option<SparseMerkleProofExt>);
+
+/// The default max number of entries held by the chunk state cache
+pub const DEFAULT_MAX_CHUNK_STATE_CACHE_ENTRIES: usize = 10_000;
+
+/// A cache of state values (and their proofs) read from the persisted state
+/// snapshot, which is kept alive across consecutive chunks. This avoids
+/// re-reading the same base state from storage for every chunk.
+///
+/// All entries are read at the same snapshot version. Values and proofs at a
+/// given snapshot version are immutable, so a cache hit always returns exactly
+/// what storage would. If a chunk reads at a different snapshot version (e.g.,
+/// because a newer snapshot has been persisted), the cache is cleared first.
+///
+/// The cache holds at most `max_entries` entries. Once full, the least
+/// recently used entries are evicted.
+pub struct ChunkStateCache {
+    inner: Mutex<ChunkStateCacheInner>,
+}
+
+struct ChunkStateCacheInner {
+    snapshot_version: Option<Version>,
+    state_values: LruCache<HashValue, StateValueAndProof>,
+}
+
+impl Default for ChunkStateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CHUNK_STATE_CACHE_ENTRIES)
+    }
+}
+
+impl ChunkStateCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            inner: Mutex::new(ChunkStateCacheInner {
+                snapshot_version: None,
+                state_values: LruCache::new(max_entries),
+            }),
+        }
+    }
+
+    /// Clears the cache (e.g., on errors, resets and epoch changes)
+    pub fn invalidate(&self) {
+        let mut inner = self.inner.lock();
+        inner.snapshot_version = None;
+        inner.state_values.clear();
+    }
+
+    /// Returns the cached value and proof for the given key hash at the
+    /// snapshot version (if one exists).
+    pub(crate) fn get(&self, key_hash: &HashValue, version: Version) -> Option<StateValueAndProof> {
+        let mut inner = self.inner.lock();
+        if inner.snapshot_version != Some(version) {
+            return None;
+        }
+        inner.state_values.get(key_hash).cloned()
+    }
+
+    /// Inserts the value and proof for the given key hash at the snapshot
+    /// version. If the snapshot version has moved, the stale entries are
+    /// dropped. If the cache is full, the least recently used entry is evicted.
+    pub(crate) fn insert(
+        &self,
+        key_hash: HashValue,
+        version: Version,
+        value_and_proof: StateValueAndProof,
+    ) {
+        let mut inner = self.inner.lock();
+        if inner.snapshot_version != Some(version) {
+            inner.snapshot_version = Some(version);
+            inner.state_values.clear();
+        }
+        inner.state_values.put(key_hash, value_and_proof);
+    }
+}
+
+/// A proof fetcher that serves reads from the chunk state cache (if possible)
+/// and falls back to storage otherwise. Only the proofs fetched through this
+/// fetcher are returned by `get_proof_cache()`, so each chunk only carries the
+/// proofs it actually touched.
+pub struct CachedProofFetcher {
+    chunk_state_cache: Arc<ChunkStateCache>,
+    state_proof_cache: RwLock<HashMap<HashValue, SparseMerkleProofExt>>,
+    sync_proof_fetcher: SyncProofFetcher,
+}
+
+impl CachedProofFetcher {
+    pub fn new(reader: Arc<dyn DbReader>, chunk_state_cache: Arc<ChunkStateCache>) -> Self {
+        Self {
+            chunk_state_cache,
+            state_proof_cache: RwLock::new(HashMap::new()),
+            sync_proof_fetcher: SyncProofFetcher::new(reader),
+        }
+    }
+}
+
+impl ProofFetcher for CachedProofFetcher {
+    fn fetch_state_value_and_proof(
+        &self,
+        state_key: &StateKey,
+        version: Version,
+    ) -> anyhow::Result<StateValueAndProof> {
+        let key_hash = state_key.hash();
+        let (state_value, proof) = match self.chunk_state_cache.get(&key_hash, version) {
+            Some(value_and_proof) => {
+                APTOS_EXECUTOR_CHUNK_STATE_CACHE_LOOKUPS
+                    .with_label_values(&["hit"])
+                    .inc();
+                value_and_proof
+            }
+            None => {
+                APTOS_EXECUTOR_CHUNK_STATE_CACHE_LOOKUPS
+                    .with_label_values(&["miss"])
+                    .inc();
+                let value_and_proof = self
+                    .sync_proof_fetcher
+                    .fetch_state_value_and_proof(state_key, version)?;
+                self.chunk_state_cache
+                    .insert(key_hash, version, value_and_proof.clone());
+                value_and_proof
+            }
+        };
+
+        // Only track the proofs touched by this chunk
+        if let Some(proof) = &proof {
+            self.state_proof_cache
+                .write()
+                .insert(key_hash, proof.clone());
+        }
+
+        Ok((state_value, proof))
+    }
+
+    fn get_proof_cache(&self) -> HashMap<HashValue, SparseMerkleProofExt> {
+        self.state_proof_cache
+            .read()
+            .iter()
+            .map(|(x, y)| (*x, y.clone()))
+            .collect()
+    }
+}
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_int_counter, Histogram, IntCounter,
+    exponential_buckets, register_histogram, register_int_counter, register_int_counter_vec,
+    Histogram, IntCounter, IntCounterVec,
 };
 use once_cell::sync::Lazy;
 
@@ -28,6 +29,20 @@ pub static APTOS_EXECUTOR_APPLY_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counts the lookups (hits and misses) in the chunk state cache, i.e., the
+/// cache of persisted state that is kept alive across consecutive chunks.
+pub static APTOS_EXECUTOR_CHUNK_STATE_CACHE_LOOKUPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "aptos_executor_chunk_state_cache_lookups",
+        // metric description
+        "The number of state lookups (hits and misses) in the chunk executor's state cache",
+        // metric labels
+        &["result"]
+    )
+    .unwrap()
+});
+
 pub static APTOS_EXECUTOR_COMMIT_CHUNK_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
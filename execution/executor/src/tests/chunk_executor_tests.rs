@@ -6,6 +6,7 @@
 use crate::{
     block_executor::BlockExecutor,
     chunk_executor::ChunkExecutor,
+    components::chunk_state_cache::ChunkStateCache,
     db_bootstrapper::{generate_waypoint, maybe_bootstrap},
     mock_vm::{encode_mint_transaction, MockVM},
     tests,
@@ -29,13 +30,17 @@ pub struct TestExecutor {
 
 impl TestExecutor {
     pub fn new() -> TestExecutor {
+        Self::new_with_state_cache(true)
+    }
+
+    pub fn new_with_state_cache(enable_state_cache: bool) -> TestExecutor {
         let path = aptos_temppath::TempPath::new();
         path.create_as_dir().unwrap();
         let db = DbReaderWriter::new(AptosDB::new_for_test(path.path()));
         let genesis = vm_genesis::test_genesis_transaction();
         let waypoint = generate_waypoint::<MockVM>(&db, &genesis).unwrap();
         maybe_bootstrap::<MockVM>(&db, &genesis, waypoint).unwrap();
-        let executor = ChunkExecutor::new(db.clone());
+        let executor = ChunkExecutor::new_with_state_cache(db.clone(), enable_state_cache);
 
         TestExecutor {
             _path: path,
@@ -184,6 +189,118 @@ fn test_executor_execute_or_apply_and_commit_chunk() {
     assert_eq!(li, ledger_info);
 }
 
+#[test]
+fn test_executor_execute_and_commit_chunks_batched() {
+    let first_batch_size = 30;
+    let second_batch_size = 40;
+    let third_batch_size = 20;
+
+    let first_batch_start = 1;
+    let second_batch_start = first_batch_start + first_batch_size;
+    let third_batch_start = second_batch_start + second_batch_size;
+
+    let (chunks, ledger_info) = {
+        tests::create_transaction_chunks(vec![
+            first_batch_start..first_batch_start + first_batch_size,
+            second_batch_start..second_batch_start + second_batch_size,
+            third_batch_start..third_batch_start + third_batch_size,
+        ])
+    };
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new();
+
+    // Execute all chunks. Nothing should be committed yet.
+    for chunk in chunks {
+        executor.execute_chunk(chunk, &ledger_info, None).unwrap();
+    }
+    assert_eq!(db.reader.get_latest_version().unwrap(), 0);
+
+    // Committing more chunks than have been executed should fail
+    assert!(executor.commit_chunks(4).is_err());
+    assert_eq!(db.reader.get_latest_version().unwrap(), 0);
+
+    // Commit all chunks in a single batch and verify the new ledger info
+    let commit_notification = executor.commit_chunks(3).unwrap();
+    assert_eq!(
+        commit_notification.committed_transactions.len() as u64,
+        first_batch_size + second_batch_size + third_batch_size
+    );
+    let li = db.reader.get_latest_ledger_info().unwrap();
+    assert_eq!(li, ledger_info);
+}
+
+#[test]
+fn test_executor_execute_and_commit_chunks_state_cache() {
+    let (chunks, ledger_info) =
+        tests::create_transaction_chunks(vec![1..11, 11..21, 21..31, 31..41]);
+
+    // Execute the same chunk sequence with and without the state cache
+    let committed_roots_with_cache =
+        execute_and_commit_chunks_sequentially(true, chunks.clone(), &ledger_info);
+    let committed_roots_without_cache =
+        execute_and_commit_chunks_sequentially(false, chunks, &ledger_info);
+
+    // Verify the committed state roots are identical
+    assert_eq!(committed_roots_with_cache, committed_roots_without_cache);
+}
+
+/// Executes and commits the given chunks one after another (without resetting
+/// the executor between chunks, like state sync does) and returns the state
+/// and accumulator root hashes committed after each chunk.
+fn execute_and_commit_chunks_sequentially(
+    enable_state_cache: bool,
+    chunks: Vec<TransactionListWithProof>,
+    ledger_info: &LedgerInfoWithSignatures,
+) -> Vec<(HashValue, HashValue)> {
+    let TestExecutor {
+        _path,
+        db,
+        executor,
+    } = TestExecutor::new_with_state_cache(enable_state_cache);
+
+    let mut committed_roots = vec![];
+    for chunk in chunks {
+        executor.execute_chunk(chunk, ledger_info, None).unwrap();
+        executor.commit_chunk().unwrap();
+
+        let executed_trees = db.reader.get_latest_executed_trees().unwrap();
+        committed_roots.push((
+            executed_trees.state().root_hash(),
+            executed_trees.txn_accumulator().root_hash(),
+        ));
+    }
+    assert_eq!(db.reader.get_latest_ledger_info().unwrap(), *ledger_info);
+
+    committed_roots
+}
+
+#[test]
+fn test_chunk_state_cache_eviction() {
+    // Create a chunk state cache that holds at most 2 entries
+    let chunk_state_cache = ChunkStateCache::new(2);
+    let key_hashes: Vec<HashValue> = (0..3).map(|_| HashValue::random()).collect();
+
+    // Insert two entries and read the first (so the second is least recently used)
+    chunk_state_cache.insert(key_hashes[0], 10, (None, None));
+    chunk_state_cache.insert(key_hashes[1], 10, (None, None));
+    assert!(chunk_state_cache.get(&key_hashes[0], 10).is_some());
+
+    // Insert a third entry and verify the least recently used entry is evicted
+    chunk_state_cache.insert(key_hashes[2], 10, (None, None));
+    assert!(chunk_state_cache.get(&key_hashes[0], 10).is_some());
+    assert!(chunk_state_cache.get(&key_hashes[1], 10).is_none());
+    assert!(chunk_state_cache.get(&key_hashes[2], 10).is_some());
+
+    // Verify entries are dropped when the snapshot version moves
+    chunk_state_cache.insert(key_hashes[1], 20, (None, None));
+    assert!(chunk_state_cache.get(&key_hashes[0], 20).is_none());
+    assert!(chunk_state_cache.get(&key_hashes[1], 20).is_some());
+    assert!(chunk_state_cache.get(&key_hashes[1], 10).is_none());
+}
+
 #[test]
 fn test_executor_execute_and_commit_chunk_restart() {
     let first_batch_size = 30;
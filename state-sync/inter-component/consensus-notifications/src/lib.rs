@@ -266,6 +266,13 @@ impl ConsensusSyncNotification {
 
         (sync_notification, callback_receiver)
     }
+
+    /// Returns true iff the receiver for the callback has been dropped, i.e.,
+    /// nobody is waiting on a response to the notification (e.g., because
+    /// consensus restarted). This doesn't consume the callback.
+    pub fn is_callback_closed(&self) -> bool {
+        self.callback.is_canceled()
+    }
 }
 
 #[cfg(test)]
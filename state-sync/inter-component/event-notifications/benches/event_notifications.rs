@@ -0,0 +1,99 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_infallible::RwLock;
+use aptos_types::{
+    account_address::AccountAddress, contract_event::ContractEvent, event::EventKey,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use event_notifications::{
+    requires_event_notification, EventNotificationSender, EventSubscriptionService,
+};
+use move_deps::move_core_types::language_storage::TypeTag;
+use std::sync::Arc;
+use storage_interface::{mock::MockDbReaderWriter, DbReaderWriter};
+
+// The number of events contained in each benchmarked commit
+const NUM_EVENTS_PER_COMMIT: usize = 1000;
+
+/// Benchmarks the cost of handling event-heavy commits (i.e., commits where
+/// every event has a subscriber) and event-free commits (i.e., commits where
+/// no event has a subscriber). Event-free commits should be skipped by the
+/// cheap event key check, without ever notifying the subscription service.
+fn event_notification_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_notifications");
+
+    // Create the subscription service and subscribe to a set of event keys
+    let subscribed_event_keys: Vec<EventKey> = (0..NUM_EVENTS_PER_COMMIT)
+        .map(|_| create_event_key())
+        .collect();
+    let mut event_subscription_service = create_event_subscription_service();
+    let _event_listener = event_subscription_service
+        .subscribe_to_events(subscribed_event_keys.clone())
+        .unwrap();
+    let subscribed_keys = event_subscription_service.get_subscribed_event_keys();
+
+    // Create the event-heavy and event-free commits
+    let event_heavy_commit: Vec<ContractEvent> = subscribed_event_keys
+        .into_iter()
+        .map(create_event)
+        .collect();
+    let event_free_commit: Vec<ContractEvent> = (0..NUM_EVENTS_PER_COMMIT)
+        .map(|_| create_event(create_event_key()))
+        .collect();
+
+    for (commit_type, events) in [
+        ("event_heavy", event_heavy_commit),
+        ("event_free", event_free_commit),
+    ] {
+        // Benchmark the fast path check only
+        group.bench_with_input(
+            BenchmarkId::new("requires_event_notification", commit_type),
+            &events,
+            |b, events| b.iter(|| requires_event_notification(events, &subscribed_keys.read())),
+        );
+
+        // Benchmark the check followed by the notification (if required)
+        group.bench_with_input(
+            BenchmarkId::new("handle_commit", commit_type),
+            &events,
+            |b, events| {
+                b.iter_batched(
+                    || events.clone(),
+                    |events| {
+                        if requires_event_notification(&events, &subscribed_keys.read()) {
+                            event_subscription_service.notify_events(0, events).unwrap();
+                        }
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Creates an event subscription service backed by a mock database
+fn create_event_subscription_service() -> EventSubscriptionService {
+    EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(DbReaderWriter::new(MockDbReaderWriter))),
+    )
+}
+
+fn create_event(event_key: EventKey) -> ContractEvent {
+    ContractEvent::new(event_key, 0, TypeTag::Bool, bcs::to_bytes(&0).unwrap())
+}
+
+fn create_event_key() -> EventKey {
+    EventKey::new(0, AccountAddress::random())
+}
+
+criterion_group!(
+    name = event_notification_benches;
+    config = Criterion::default();
+    targets = event_notification_benchmark
+);
+criterion_main!(event_notification_benches);
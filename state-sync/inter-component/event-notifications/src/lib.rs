@@ -24,7 +24,10 @@ use std::{
     iter::FromIterator,
     ops::Deref,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 use storage_interface::{state_view::DbStateViewAtVersion, DbReaderWriter};
@@ -43,6 +46,8 @@ const RECONFIG_NOTIFICATION_CHANNEL_SIZE: usize = 1;
 pub enum Error {
     #[error("Cannot subscribe to zero event keys!")]
     CannotSubscribeToZeroEventKeys,
+    #[error("Failed to notify closed subscriptions! Subscription IDs: {0:?}")]
+    ClosedSubscriptions(Vec<SubscriptionId>),
     #[error("Missing event subscription! Subscription ID: {0}")]
     MissingEventSubscription(u64),
     #[error("Unable to send event notification! Error: {0}")]
@@ -70,6 +75,24 @@ pub trait EventNotificationSender: Send {
     fn notify_initial_configs(&mut self, version: Version) -> Result<(), Error>;
 }
 
+/// Returns true iff the given event is a reconfiguration (i.e., new epoch)
+/// event. This only compares event keys, so the event is never deserialized.
+pub fn is_reconfiguration_event(event: &ContractEvent) -> bool {
+    *event.key() == on_chain_config::new_epoch_event_key()
+}
+
+/// Returns true iff the given events must be delivered to the subscription
+/// service, i.e., at least one event is a reconfiguration event or has a
+/// subscriber in the given set of subscribed event keys.
+pub fn requires_event_notification(
+    events: &[ContractEvent],
+    subscribed_event_keys: &HashSet<EventKey>,
+) -> bool {
+    events
+        .iter()
+        .any(|event| is_reconfiguration_event(event) || subscribed_event_keys.contains(event.key()))
+}
+
 /// The subscription service offered by state sync, responsible for notifying
 /// subscribers of on-chain events.
 pub struct EventSubscriptionService {
@@ -77,9 +100,23 @@ pub struct EventSubscriptionService {
     event_key_subscriptions: HashMap<EventKey, HashSet<SubscriptionId>>,
     subscription_id_to_event_subscription: HashMap<SubscriptionId, EventSubscription>,
 
+    // The set of all event keys with at least one subscriber (shared with
+    // callers that want to cheaply skip irrelevant event notifications).
+    subscribed_event_keys: Arc<RwLock<HashSet<EventKey>>>,
+
     // Reconfig subscription registry
     reconfig_subscriptions: HashMap<SubscriptionId, ReconfigSubscription>,
 
+    // The version of the last on-chain configs sent to reconfig subscribers
+    // (if any). Reconfig subscriptions created after this are unsynced, i.e.,
+    // they have missed the latest on-chain configs.
+    last_reconfig_version: Option<Version>,
+    unsynced_reconfig_subscriptions: HashSet<SubscriptionId>,
+
+    // Whether or not there are unsynced reconfig subscriptions (shared with
+    // callers that want to cheaply check if a config resync is required).
+    reconfig_resync_required: Arc<AtomicBool>,
+
     // Database to fetch on-chain configuration data
     storage: Arc<RwLock<DbReaderWriter>>,
 
@@ -95,7 +132,11 @@ impl EventSubscriptionService {
         Self {
             event_key_subscriptions: HashMap::new(),
             subscription_id_to_event_subscription: HashMap::new(),
+            subscribed_event_keys: Arc::new(RwLock::new(HashSet::new())),
             reconfig_subscriptions: HashMap::new(),
+            last_reconfig_version: None,
+            unsynced_reconfig_subscriptions: HashSet::new(),
+            reconfig_resync_required: Arc::new(AtomicBool::new(false)),
             config_registry: config_registry.to_vec(),
             storage,
             subscription_id_generator: U64IdGenerator::new(),
@@ -139,7 +180,9 @@ impl EventSubscriptionService {
         }
 
         // Update the event key subscriptions to include the new subscription
+        let mut subscribed_event_keys = self.subscribed_event_keys.write();
         for event_key in event_keys {
+            subscribed_event_keys.insert(event_key);
             self.event_key_subscriptions
                 .entry(event_key)
                 .and_modify(|subscriptions| {
@@ -159,6 +202,9 @@ impl EventSubscriptionService {
     /// begins. Note: if the notification buffer fills up too quickly, older
     /// notifications will be dropped. As such, it is the responsibility of the
     /// subscriber to ensure notifications are processed in a timely manner.
+    /// If the on-chain configs have already been delivered, the subscription
+    /// is unsynced until the next call to `resync_reconfig_subscriptions()`
+    /// (or the next reconfiguration).
     pub fn subscribe_to_reconfigurations(&mut self) -> Result<ReconfigNotificationListener, Error> {
         let (notification_sender, notification_receiver) =
             aptos_channel::new(QueueStyle::KLAST, RECONFIG_NOTIFICATION_CHANNEL_SIZE, None);
@@ -180,23 +226,112 @@ impl EventSubscriptionService {
             );
         }
 
+        // If the configs have already been delivered, the subscription has missed them
+        if self.last_reconfig_version.is_some() {
+            self.unsynced_reconfig_subscriptions.insert(subscription_id);
+            self.reconfig_resync_required.store(true, Ordering::Relaxed);
+        }
+
         Ok(ReconfigNotificationListener {
             notification_receiver,
         })
     }
 
+    /// Returns a shared handle to the set of event keys that currently have
+    /// at least one subscriber. The set is updated as new subscriptions are
+    /// created, so it can be held and queried without locking the service.
+    pub fn get_subscribed_event_keys(&self) -> Arc<RwLock<HashSet<EventKey>>> {
+        self.subscribed_event_keys.clone()
+    }
+
+    /// Returns a shared flag that is set iff there are reconfig subscriptions
+    /// that have missed the latest on-chain configs (e.g., subscriptions that
+    /// were re-created after a subscriber was dropped).
+    pub fn get_reconfig_resync_required(&self) -> Arc<AtomicBool> {
+        self.reconfig_resync_required.clone()
+    }
+
+    /// Notifies all unsynced reconfig subscriptions of the on-chain configs at
+    /// the specified version. Returns the number of resynced subscriptions.
+    pub fn resync_reconfig_subscriptions(&mut self, version: Version) -> Result<usize, Error> {
+        if self.unsynced_reconfig_subscriptions.is_empty() {
+            return Ok(0); // No unsynced subscriptions!
+        }
+
+        let new_configs = self.read_on_chain_configs(version)?;
+        let unsynced_subscription_ids: Vec<SubscriptionId> =
+            self.unsynced_reconfig_subscriptions.drain().collect();
+        self.reconfig_resync_required
+            .store(false, Ordering::Relaxed);
+
+        let mut closed_subscription_ids = vec![];
+        let mut num_resynced_subscriptions = 0;
+        for subscription_id in unsynced_subscription_ids {
+            if let Some(reconfig_subscription) =
+                self.reconfig_subscriptions.get_mut(&subscription_id)
+            {
+                if reconfig_subscription
+                    .notify_subscriber_of_configs(version, new_configs.clone())
+                    .is_ok()
+                {
+                    num_resynced_subscriptions += 1;
+                } else {
+                    closed_subscription_ids.push(subscription_id);
+                }
+            }
+        }
+        self.remove_closed_subscriptions(closed_subscription_ids)?;
+
+        Ok(num_resynced_subscriptions)
+    }
+
+    /// Removes the given (closed) subscriptions. If any subscriptions were
+    /// removed, an error is returned containing the subscription IDs.
+    fn remove_closed_subscriptions(
+        &mut self,
+        closed_subscription_ids: Vec<SubscriptionId>,
+    ) -> Result<(), Error> {
+        if closed_subscription_ids.is_empty() {
+            return Ok(());
+        }
+
+        for subscription_id in closed_subscription_ids.iter() {
+            self.subscription_id_to_event_subscription
+                .remove(subscription_id);
+            self.reconfig_subscriptions.remove(subscription_id);
+            self.unsynced_reconfig_subscriptions.remove(subscription_id);
+        }
+
+        // Remove the event keys that no longer have any subscribers
+        let mut subscribed_event_keys = self.subscribed_event_keys.write();
+        self.event_key_subscriptions
+            .retain(|event_key, subscription_ids| {
+                subscription_ids
+                    .retain(|subscription_id| !closed_subscription_ids.contains(subscription_id));
+                if subscription_ids.is_empty() {
+                    subscribed_event_keys.remove(event_key);
+                    false
+                } else {
+                    true
+                }
+            });
+
+        Err(Error::ClosedSubscriptions(closed_subscription_ids))
+    }
+
     fn get_new_subscription_id(&mut self) -> u64 {
         self.subscription_id_generator.next()
     }
 
     /// This notifies all the event subscribers of the new events found at the
     /// specified version. If a reconfiguration event (i.e., new epoch) is found,
-    /// this method will return true.
+    /// this method will return true. Subscribers that can't be notified (i.e.,
+    /// closed subscriptions) are returned alongside.
     fn notify_event_subscribers(
         &mut self,
         version: Version,
         events: Vec<ContractEvent>,
-    ) -> Result<bool, Error> {
+    ) -> Result<(bool, Vec<SubscriptionId>), Error> {
         let mut reconfig_event_found = false;
         let mut event_subscription_ids_to_notify = HashSet::new();
 
@@ -222,39 +357,68 @@ impl EventSubscriptionService {
             }
 
             // Take note if a reconfiguration (new epoch) has occurred
-            if *event_key == on_chain_config::new_epoch_event_key() {
+            if is_reconfiguration_event(event) {
                 reconfig_event_found = true;
             }
         }
 
-        // Notify event subscribers of the new events
+        // Notify event subscribers of the new events. A failure to notify one
+        // subscriber (e.g., because the subscriber was dropped) doesn't affect
+        // the others.
+        let mut closed_subscription_ids = vec![];
         for event_subscription_id in event_subscription_ids_to_notify {
             if let Some(event_subscription) = self
                 .subscription_id_to_event_subscription
                 .get_mut(&event_subscription_id)
             {
-                event_subscription.notify_subscriber_of_events(version)?;
+                if event_subscription
+                    .notify_subscriber_of_events(version)
+                    .is_err()
+                {
+                    closed_subscription_ids.push(event_subscription_id);
+                }
             } else {
                 return Err(Error::MissingEventSubscription(event_subscription_id));
             }
         }
 
-        Ok(reconfig_event_found)
+        Ok((reconfig_event_found, closed_subscription_ids))
     }
 
     /// This notifies all the reconfiguration subscribers of the on-chain
-    /// configurations at the specified version.
-    fn notify_reconfiguration_subscribers(&mut self, version: Version) -> Result<(), Error> {
+    /// configurations at the specified version. Subscribers that can't be
+    /// notified (i.e., closed subscriptions) are returned.
+    fn notify_reconfiguration_subscribers(
+        &mut self,
+        version: Version,
+    ) -> Result<Vec<SubscriptionId>, Error> {
         if self.reconfig_subscriptions.is_empty() {
-            return Ok(()); // No reconfiguration subscribers!
+            self.mark_reconfig_subscriptions_synced(version);
+            return Ok(vec![]); // No reconfiguration subscribers!
         }
 
         let new_configs = self.read_on_chain_configs(version)?;
-        for (_, reconfig_subscription) in self.reconfig_subscriptions.iter_mut() {
-            reconfig_subscription.notify_subscriber_of_configs(version, new_configs.clone())?;
+        self.mark_reconfig_subscriptions_synced(version);
+        let mut closed_subscription_ids = vec![];
+        for (subscription_id, reconfig_subscription) in self.reconfig_subscriptions.iter_mut() {
+            if reconfig_subscription
+                .notify_subscriber_of_configs(version, new_configs.clone())
+                .is_err()
+            {
+                closed_subscription_ids.push(*subscription_id);
+            }
         }
 
-        Ok(())
+        Ok(closed_subscription_ids)
+    }
+
+    /// Marks all reconfig subscriptions as synced to the configs at the
+    /// specified version.
+    fn mark_reconfig_subscriptions_synced(&mut self, version: Version) {
+        self.last_reconfig_version = Some(version);
+        self.unsynced_reconfig_subscriptions.clear();
+        self.reconfig_resync_required
+            .store(false, Ordering::Relaxed);
     }
 
     /// Fetches the configs on-chain at the specified version.
@@ -323,24 +487,27 @@ impl EventNotificationSender for EventSubscriptionService {
         }
 
         // Notify event subscribers and check if a reconfiguration event was processed
-        let reconfig_event_processed = self.notify_event_subscribers(version, events)?;
+        let (reconfig_event_processed, mut closed_subscription_ids) =
+            self.notify_event_subscribers(version, events)?;
 
         // If a reconfiguration event was found, also notify the reconfig subscribers
         // of the new configuration values.
         if reconfig_event_processed {
-            self.notify_reconfiguration_subscribers(version)
-        } else {
-            Ok(())
+            closed_subscription_ids.extend(self.notify_reconfiguration_subscribers(version)?);
         }
+
+        // Remove any closed subscriptions
+        self.remove_closed_subscriptions(closed_subscription_ids)
     }
 
     fn notify_initial_configs(&mut self, version: Version) -> Result<(), Error> {
-        self.notify_reconfiguration_subscribers(version)
+        let closed_subscription_ids = self.notify_reconfiguration_subscribers(version)?;
+        self.remove_closed_subscriptions(closed_subscription_ids)
     }
 }
 
 /// A unique ID used to identify each subscription.
-type SubscriptionId = u64;
+pub type SubscriptionId = u64;
 
 /// A single event subscription, holding the subscription identifier, channel to
 /// send the corresponding notifications and a buffer to hold pending events.
@@ -23,7 +23,10 @@ use executor_test_helpers::bootstrap_genesis;
 use futures::{FutureExt, StreamExt};
 use move_deps::move_core_types::language_storage::TypeTag;
 use serde::{Deserialize, Serialize};
-use std::{convert::TryInto, sync::Arc};
+use std::{
+    convert::TryInto,
+    sync::{atomic::Ordering, Arc},
+};
 use storage_interface::DbReaderWriter;
 
 #[test]
@@ -63,6 +66,82 @@ fn test_all_configs_returned() {
     }
 }
 
+#[test]
+fn test_closed_subscriptions() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create event and reconfig subscribers
+    let event_key = create_random_event_key();
+    let event_listener_1 = event_service.subscribe_to_events(vec![event_key]).unwrap();
+    let mut event_listener_2 = event_service.subscribe_to_events(vec![event_key]).unwrap();
+    let reconfig_listener_1 = event_service.subscribe_to_reconfigurations().unwrap();
+    let mut reconfig_listener_2 = event_service.subscribe_to_reconfigurations().unwrap();
+
+    // Close the first event and reconfig subscribers
+    drop(event_listener_1);
+    drop(reconfig_listener_1);
+
+    // Notify the service of an event and a reconfiguration, and verify the
+    // closed subscriptions are returned (and removed).
+    let event = create_test_event(event_key);
+    let reconfig_event = create_test_event(on_chain_config::new_epoch_event_key());
+    let result = event_service.notify_events(0, vec![event.clone(), reconfig_event.clone()]);
+    assert_matches!(result, Err(Error::ClosedSubscriptions(subscription_ids)) if subscription_ids.len() == 2);
+
+    // Verify the remaining subscribers were still notified
+    verify_event_notification_received(vec![&mut event_listener_2], 0, vec![event.clone()]);
+    verify_reconfig_notifications_received(vec![&mut reconfig_listener_2], 0, 1);
+
+    // Verify the next notification succeeds (the closed subscriptions are gone)
+    notify_events(&mut event_service, 0, vec![event.clone(), reconfig_event]);
+    verify_event_notification_received(vec![&mut event_listener_2], 0, vec![event]);
+    verify_reconfig_notifications_received(vec![&mut reconfig_listener_2], 0, 1);
+
+    // Close the last event subscriber and verify the event key is no longer subscribed
+    drop(event_listener_2);
+    let result = event_service.notify_events(0, vec![create_test_event(event_key)]);
+    assert_matches!(result, Err(Error::ClosedSubscriptions(subscription_ids)) if subscription_ids.len() == 1);
+    assert!(!event_service
+        .get_subscribed_event_keys()
+        .read()
+        .contains(&event_key));
+}
+
+#[test]
+fn test_reconfig_subscription_resync() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create a reconfig subscriber and notify it of the initial configs
+    let reconfig_listener_1 = event_service.subscribe_to_reconfigurations().unwrap();
+    let resync_required = event_service.get_reconfig_resync_required();
+    notify_initial_configs(&mut event_service, 0);
+    assert!(!resync_required.load(Ordering::Relaxed));
+
+    // Close the subscriber and verify there's nothing to resync
+    drop(reconfig_listener_1);
+    assert_matches!(
+        event_service.notify_initial_configs(0),
+        Err(Error::ClosedSubscriptions(_))
+    );
+    assert_eq!(event_service.resync_reconfig_subscriptions(0), Ok(0));
+
+    // Re-create the subscription and verify it requires a resync
+    let mut reconfig_listener_2 = event_service.subscribe_to_reconfigurations().unwrap();
+    assert!(resync_required.load(Ordering::Relaxed));
+    verify_no_reconfig_notifications(vec![&mut reconfig_listener_2]);
+
+    // Resync the subscription and verify it receives the latest configs
+    assert_eq!(event_service.resync_reconfig_subscriptions(0), Ok(1));
+    verify_reconfig_notifications_received(vec![&mut reconfig_listener_2], 0, 1);
+    assert!(!resync_required.load(Ordering::Relaxed));
+
+    // Verify subsequent resyncs are no-ops
+    assert_eq!(event_service.resync_reconfig_subscriptions(0), Ok(0));
+    verify_no_reconfig_notifications(vec![&mut reconfig_listener_2]);
+}
+
 #[test]
 fn test_reconfig_notification_no_queuing() {
     // Create subscription service and mock database
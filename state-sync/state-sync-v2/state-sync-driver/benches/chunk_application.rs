@@ -0,0 +1,80 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks the throughput of applying transaction output chunks through the
+//! real driver and storage synchronizer (against a temporary AptosDB), for
+//! different chunk sizes.
+//!
+//! To record a baseline and compare a change against it, run:
+//! `cargo bench -p state-sync-driver --features testing --bench chunk_application -- --save-baseline main`
+//! and then (with the change applied):
+//! `cargo bench -p state-sync-driver --features testing --bench chunk_application -- --baseline main`
+//!
+//! Baselines (time per iteration on `main`): these haven't been measured yet,
+//! and must be filled in (along with the CPU model, core count and memory of
+//! the machine) from a run on a reference machine:
+//! - Reference machine: not yet recorded
+//! - `chunk_application/apply_transaction_outputs/{1,10,100}`: not yet recorded
+
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+use state_sync_driver::test_utils::{synthetic_chain::SyntheticChain, test_harness::TestHarness};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+// The number of transaction outputs served in each chunk
+const CHUNK_SIZES: [u64; 3] = [1, 10, 100];
+
+/// Benchmarks syncing the source chain (i.e., several epochs of executed
+/// blocks) from genesis by applying output chunks of different sizes. Each
+/// iteration syncs into a new database, which is created outside the timed
+/// section.
+fn chunk_application_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    // Create the source chain (shared by all chunk sizes)
+    let source_db = test_execution_with_storage_impl();
+
+    let mut group = c.benchmark_group("chunk_application");
+    group.sample_size(10);
+    for chunk_size in CHUNK_SIZES {
+        let chain = Arc::new(SyntheticChain::from_storage(source_db.clone(), chunk_size));
+        let latest_ledger_info = chain.get_latest_ledger_info();
+        let latest_version = chain.get_latest_version();
+        group.throughput(Throughput::Elements(latest_version));
+        group.bench_with_input(
+            BenchmarkId::new("apply_transaction_outputs", chunk_size),
+            &chain,
+            |b, chain| {
+                b.iter_batched(
+                    || {
+                        TestHarness::new_with_chain(
+                            chain.clone(),
+                            BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+                            ContinuousSyncingMode::ApplyTransactionOutputs,
+                        )
+                    },
+                    |test_harness| {
+                        runtime.block_on(async {
+                            test_harness.advertise_ledger_info(&latest_ledger_info);
+                            test_harness.wait_for_version(latest_version).await;
+                        });
+                        test_harness // Dropped outside the timed section
+                    },
+                    BatchSize::PerIteration,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(
+    name = chunk_application_benches;
+    config = Criterion::default();
+    targets = chunk_application_benchmark
+);
+criterion_main!(chunk_application_benches);
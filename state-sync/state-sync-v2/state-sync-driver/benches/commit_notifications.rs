@@ -0,0 +1,185 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks the throughput of handling bursts of consensus commit
+//! notifications (i.e., updating the driver state and notifying mempool) on a
+//! bootstrapped validator, for different burst sizes.
+//!
+//! To record a baseline and compare a change against it, run:
+//! `cargo bench -p state-sync-driver --features testing --bench commit_notifications -- --save-baseline main`
+//! and then (with the change applied):
+//! `cargo bench -p state-sync-driver --features testing --bench commit_notifications -- --baseline main`
+//!
+//! Baselines (time per iteration on `main`): these haven't been measured yet,
+//! and must be filled in (along with the CPU model, core count and memory of
+//! the machine) from a run on a reference machine:
+//! - Reference machine: not yet recorded
+//! - `commit_notifications/notify_new_commit/{1,10,100}`: not yet recorded
+
+use aptos_config::config::{NodeConfig, RoleType};
+use aptos_data_client::GlobalDataSummary;
+use aptos_infallible::{Mutex, RwLock};
+use aptos_temppath::TempPath;
+use aptos_types::{on_chain_config::ON_CHAIN_CONFIG_REGISTRY, transaction::Transaction};
+use consensus_notifications::{
+    new_consensus_notifier_listener_pair, ConsensusNotificationSender, ConsensusNotifier,
+};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use event_notifications::EventSubscriptionService;
+use state_sync_driver::{
+    driver_factory::{DriverFactory, StateSyncRuntimeHandle},
+    metadata_storage::PersistentMetadataStorage,
+    test_utils::{
+        create_epoch_ending_waypoint, create_ledger_info_at_version, create_mempool_notifier,
+        create_transaction,
+        fake_storage::FakeStorage,
+        synthetic_chain::{
+            SyntheticChain, SyntheticChainConfig, SyntheticDataClient, SyntheticStreamingClient,
+        },
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio::runtime::Runtime;
+
+// The number of commit notifications sent in each burst
+const BURST_SIZES: [u64; 3] = [1, 10, 100];
+
+// The number of transactions committed by each notification
+const TRANSACTIONS_PER_COMMIT: usize = 10;
+
+/// Benchmarks sending bursts of commit notifications (back-to-back) to a
+/// bootstrapped validator. Each notification is only sent once the previous
+/// one has been handled (as consensus does), so the benchmark measures the
+/// latency of the commit path. The committed transactions are generated
+/// outside the timed section.
+fn commit_notifications_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    // Spawn a bootstrapped validator
+    let fake_storage = FakeStorage::new();
+    let metadata_path = TempPath::new();
+    let (consensus_notifier, _driver_handle) =
+        runtime.block_on(spawn_validator(&fake_storage, &metadata_path));
+
+    let mut group = c.benchmark_group("commit_notifications");
+    for burst_size in BURST_SIZES {
+        group.throughput(Throughput::Elements(burst_size));
+        group.bench_with_input(
+            BenchmarkId::new("notify_new_commit", burst_size),
+            &burst_size,
+            |b, burst_size| {
+                b.iter_batched(
+                    || create_commit_bursts(*burst_size),
+                    |commit_bursts| {
+                        runtime.block_on(async {
+                            for transactions in commit_bursts {
+                                commit_transactions(
+                                    &fake_storage,
+                                    &consensus_notifier,
+                                    transactions,
+                                )
+                                .await;
+                            }
+                        })
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Commits the given transactions to storage (as consensus would) and
+/// notifies the driver of the commit.
+async fn commit_transactions(
+    fake_storage: &FakeStorage,
+    consensus_notifier: &ConsensusNotifier,
+    transactions: Vec<Transaction>,
+) {
+    let committed_version = fake_storage.get_latest_version() + transactions.len() as u64;
+    fake_storage.commit_transactions(
+        transactions.clone(),
+        create_ledger_info_at_version(committed_version),
+    );
+    consensus_notifier
+        .notify_new_commit(transactions, vec![])
+        .await
+        .unwrap();
+}
+
+/// Creates the transactions committed by each notification of a burst
+fn create_commit_bursts(burst_size: u64) -> Vec<Vec<Transaction>> {
+    (0..burst_size)
+        .map(|_| {
+            (0..TRANSACTIONS_PER_COMMIT)
+                .map(|_| create_transaction())
+                .collect()
+        })
+        .collect()
+}
+
+/// Creates and spawns a validator driver (on the current runtime) over the
+/// given fake storage, and waits for the validator to bootstrap. Peers
+/// advertise nothing, so the validator bootstraps immediately.
+async fn spawn_validator(
+    fake_storage: &FakeStorage,
+    metadata_path: &TempPath,
+) -> (ConsensusNotifier, StateSyncRuntimeHandle) {
+    // Create the node config
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::Validator;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .max_connection_deadline_secs = 0;
+
+    // Create the components of the driver
+    let storage = fake_storage.create_reader_writer();
+    let (consensus_notifier, consensus_listener) = new_consensus_notifier_listener_pair(5000);
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(storage.clone())),
+    );
+    metadata_path.create_as_dir().unwrap();
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig::default()));
+    let global_data_summary = Arc::new(Mutex::new(GlobalDataSummary::empty()));
+
+    // Create and spawn the driver
+    let driver_factory = DriverFactory::create_and_spawn_driver_with_clients(
+        false,
+        &node_config,
+        create_epoch_ending_waypoint(),
+        storage,
+        Arc::new(fake_storage.create_chunk_executor()),
+        Some(create_mempool_notifier()),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        consensus_listener,
+        event_subscription_service,
+        SyntheticDataClient::new(global_data_summary),
+        SyntheticStreamingClient::new(chain),
+        vec![],
+    )
+    .unwrap();
+    let driver_handle = driver_factory.into_runtime_handle();
+
+    // Wait for the validator to bootstrap
+    tokio::time::timeout(
+        Duration::from_secs(10),
+        driver_handle.driver_client().notify_once_bootstrapped(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    (consensus_notifier, driver_handle)
+}
+
+criterion_group!(
+    name = commit_notifications_benches;
+    config = Criterion::default();
+    targets = commit_notifications_benchmark
+);
+criterion_main!(commit_notifications_benches);
@@ -0,0 +1,110 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks the cost of verifying the event root hashes of transaction
+//! output chunks, for different numbers of events per output. Note: this
+//! benchmark only uses the public types (and no driver test utilities), so
+//! unlike the other driver benchmarks, it doesn't require the `testing` feature.
+//!
+//! To record a baseline and compare a change against it, run:
+//! `cargo bench -p state-sync-driver --bench event_root_hash_verification -- --save-baseline main`
+//! and then (with the change applied):
+//! `cargo bench -p state-sync-driver --bench event_root_hash_verification -- --baseline main`
+//!
+//! Baselines (time per iteration on `main`): these haven't been measured yet,
+//! and must be filled in (along with the CPU model, core count and memory of
+//! the machine) from a run on a reference machine:
+//! - Reference machine: not yet recorded
+//! - `event_root_hash_verification/verify_event_root_hashes/{0,1,10,100}`: not yet recorded
+
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    HashValue,
+};
+use aptos_types::{
+    contract_event::ContractEvent,
+    event::EventKey,
+    proof::{accumulator::InMemoryAccumulator, TransactionAccumulatorRangeProof},
+    transaction::{
+        ExecutionStatus, Transaction, TransactionInfo, TransactionInfoListWithProof,
+        TransactionOutput, TransactionOutputListWithProof, TransactionStatus,
+    },
+    write_set::WriteSet,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use move_deps::move_core_types::language_storage::TypeTag;
+
+// The number of transaction outputs contained in each benchmarked chunk
+const NUM_OUTPUTS_PER_CHUNK: usize = 1000;
+
+/// Benchmarks the cost of verifying the event root hashes of an output chunk
+/// (i.e., the optional defense-in-depth check performed by the storage
+/// synchronizer before applying outputs), for different numbers of events per
+/// output. This helps to decide whether the check should be enabled by default.
+fn event_root_hash_verification_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("event_root_hash_verification");
+    group.throughput(Throughput::Elements(NUM_OUTPUTS_PER_CHUNK as u64));
+
+    for num_events_per_output in [0, 1, 10, 100] {
+        let output_list_with_proof = create_output_list_with_proof(num_events_per_output);
+        group.bench_with_input(
+            BenchmarkId::new("verify_event_root_hashes", num_events_per_output),
+            &output_list_with_proof,
+            |b, output_list_with_proof| {
+                b.iter(|| output_list_with_proof.verify_event_root_hashes().unwrap())
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Creates an output chunk where each output holds the given number of events
+/// (and the transaction infos carry the corresponding event root hashes).
+fn create_output_list_with_proof(num_events_per_output: usize) -> TransactionOutputListWithProof {
+    let mut transactions_and_outputs = vec![];
+    let mut transaction_infos = vec![];
+    for _ in 0..NUM_OUTPUTS_PER_CHUNK {
+        let events: Vec<ContractEvent> = (0..num_events_per_output)
+            .map(|_| ContractEvent::new(EventKey::random(), 0, TypeTag::Bool, vec![0; 32]))
+            .collect();
+        let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+        let event_root_hash =
+            InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash();
+
+        let transaction_output = TransactionOutput::new(
+            WriteSet::default(),
+            events,
+            0,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+        );
+        transactions_and_outputs.push((
+            Transaction::StateCheckpoint(HashValue::random()),
+            transaction_output,
+        ));
+        transaction_infos.push(TransactionInfo::new(
+            HashValue::random(),
+            HashValue::random(),
+            event_root_hash,
+            Some(HashValue::random()),
+            0,
+            ExecutionStatus::Success,
+        ));
+    }
+
+    TransactionOutputListWithProof::new(
+        transactions_and_outputs,
+        Some(0),
+        TransactionInfoListWithProof::new(
+            TransactionAccumulatorRangeProof::new_empty(),
+            transaction_infos,
+        ),
+    )
+}
+
+criterion_group!(
+    name = event_root_hash_verification_benches;
+    config = Criterion::default();
+    targets = event_root_hash_verification_benchmark
+);
+criterion_main!(event_root_hash_verification_benches);
@@ -0,0 +1,126 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks the cost of verifying batches of epoch ending ledger infos
+//! (i.e., the signature chain verified when bootstrapping), for different
+//! validator set sizes. Also benchmarks the parallel verification of queued
+//! ledger infos by the storage synchronizer, for different thread counts.
+//!
+//! To record a baseline and compare a change against it, run:
+//! `cargo bench -p state-sync-driver --features testing --bench ledger_info_verification -- --save-baseline main`
+//! and then (with the change applied):
+//! `cargo bench -p state-sync-driver --features testing --bench ledger_info_verification -- --baseline main`
+//!
+//! Baselines (time per iteration on `main`): these haven't been measured yet,
+//! and must be filled in (along with the CPU model, core count and memory of
+//! the machine) from a run on a reference machine:
+//! - Reference machine: not yet recorded
+//! - `ledger_info_verification/verify_epoch_ending_ledger_infos/{4,16,64}`: not yet recorded
+//! - `parallel_ledger_info_verification/verify_ledger_infos/{1,2,4,8}`: not yet recorded
+
+use aptos_types::{
+    epoch_change::Verifier, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use state_sync_driver::test_utils::{
+    synthetic_chain::{SyntheticChain, SyntheticChainConfig},
+    LedgerInfoVerifier,
+};
+
+// The number of epoch ending ledger infos verified in each batch
+const NUM_EPOCHS: u64 = 10;
+
+// The number of epoch ending ledger infos verified in parallel
+const NUM_PARALLEL_EPOCHS: u64 = 64;
+
+// The number of threads used to verify ledger infos in parallel
+const NUM_VERIFICATION_THREADS: [u64; 4] = [1, 2, 4, 8];
+
+// The number of validators that sign each epoch ending ledger info
+const NUM_VALIDATORS_PER_EPOCH: [u64; 3] = [4, 16, 64];
+
+/// Benchmarks verifying the epoch ending ledger infos of a synthetic chain
+/// (starting at the first epoch after genesis), where each ledger info is
+/// verified by the validator set of the previous epoch.
+fn ledger_info_verification_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ledger_info_verification");
+    group.throughput(Throughput::Elements(NUM_EPOCHS));
+
+    for num_validators_per_epoch in NUM_VALIDATORS_PER_EPOCH {
+        let chain = SyntheticChain::new(SyntheticChainConfig {
+            num_epochs: NUM_EPOCHS,
+            num_validators_per_epoch,
+            ..Default::default()
+        });
+        let epoch_state = chain.get_epoch_state(1);
+        let epoch_ending_ledger_infos = chain.get_epoch_ending_ledger_infos(1, NUM_EPOCHS);
+        group.bench_with_input(
+            BenchmarkId::new("verify_epoch_ending_ledger_infos", num_validators_per_epoch),
+            &epoch_ending_ledger_infos,
+            |b, epoch_ending_ledger_infos| {
+                b.iter(|| verify_epoch_ending_ledger_infos(&epoch_state, epoch_ending_ledger_infos))
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Benchmarks verifying the epoch ending ledger infos of a synthetic chain in
+/// parallel (i.e., as the storage synchronizer verifies queued chunks), where
+/// each ledger info is verified by the validator set of its epoch. The
+/// speedup over a single thread should be (close to) linear.
+fn parallel_ledger_info_verification_benchmark(c: &mut Criterion) {
+    // Create the chain and the ledger infos to verify
+    let chain = SyntheticChain::new(SyntheticChainConfig {
+        num_epochs: NUM_PARALLEL_EPOCHS,
+        ..Default::default()
+    });
+    let epoch_states: Vec<EpochState> = (1..=NUM_PARALLEL_EPOCHS)
+        .map(|epoch| chain.get_epoch_state(epoch))
+        .collect();
+    let epoch_ending_ledger_infos = chain.get_epoch_ending_ledger_infos(1, NUM_PARALLEL_EPOCHS);
+    let ledger_infos_to_verify: Vec<(&EpochState, &LedgerInfoWithSignatures)> = epoch_states
+        .iter()
+        .zip(epoch_ending_ledger_infos.iter())
+        .collect();
+
+    let mut group = c.benchmark_group("parallel_ledger_info_verification");
+    group.throughput(Throughput::Elements(NUM_PARALLEL_EPOCHS));
+    for num_threads in NUM_VERIFICATION_THREADS {
+        let ledger_info_verifier = LedgerInfoVerifier::new(num_threads);
+        group.bench_function(BenchmarkId::new("verify_ledger_infos", num_threads), |b| {
+            b.iter(|| {
+                let verification_results =
+                    ledger_info_verifier.verify_ledger_infos(&ledger_infos_to_verify);
+                assert!(verification_results.iter().all(|result| result.is_ok()));
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Verifies the given epoch ending ledger infos (in order), starting with the
+/// given epoch state.
+fn verify_epoch_ending_ledger_infos(
+    epoch_state: &EpochState,
+    epoch_ending_ledger_infos: &[LedgerInfoWithSignatures],
+) {
+    let mut epoch_state = epoch_state.clone();
+    for epoch_ending_ledger_info in epoch_ending_ledger_infos {
+        epoch_state.verify(epoch_ending_ledger_info).unwrap();
+        epoch_state = epoch_ending_ledger_info
+            .ledger_info()
+            .next_epoch_state()
+            .cloned()
+            .unwrap();
+    }
+}
+
+criterion_group!(
+    name = ledger_info_verification_benches;
+    config = Criterion::default();
+    targets = ledger_info_verification_benchmark, parallel_ledger_info_verification_benchmark
+);
+criterion_main!(ledger_info_verification_benches);
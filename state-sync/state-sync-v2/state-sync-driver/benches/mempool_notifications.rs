@@ -0,0 +1,96 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Benchmarks the throughput of notifying mempool of committed transactions
+//! (i.e., filtering the user transactions, tracking the delivered versions
+//! and waiting for mempool to ack), for different numbers of transactions per
+//! notification.
+//!
+//! To record a baseline and compare a change against it, run:
+//! `cargo bench -p state-sync-driver --features testing --bench mempool_notifications -- --save-baseline main`
+//! and then (with the change applied):
+//! `cargo bench -p state-sync-driver --features testing --bench mempool_notifications -- --baseline main`
+//!
+//! Baselines (time per iteration on `main`): these haven't been measured yet,
+//! and must be filled in (along with the CPU model, core count and memory of
+//! the machine) from a run on a reference machine:
+//! - Reference machine: not yet recorded
+//! - `mempool_notifications/notify_committed_transactions/{1,100,1000}`: not yet recorded
+
+use aptos_config::config::StateSyncDriverConfig;
+use aptos_types::transaction::{Transaction, Version};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use state_sync_driver::{
+    metrics::DriverMetrics,
+    notification_handlers::{CommitOrigin, MempoolNotificationHandler},
+    test_utils::{create_mempool_notifier, create_transaction},
+};
+use tokio::runtime::Runtime;
+
+// The number of committed transactions in each notification
+const NUM_TRANSACTIONS_PER_NOTIFICATION: [u64; 3] = [1, 100, 1000];
+
+/// Benchmarks notifying mempool of committed transactions (without batching),
+/// where mempool acks each notification immediately. The committed versions
+/// increase across iterations (delivered versions are never re-sent), and
+/// the transactions are generated outside the timed section.
+fn mempool_notifications_benchmark(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let _runtime_guard = runtime.enter();
+
+    // Create the mempool notification handler
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        create_mempool_notifier(),
+        DriverMetrics::default(),
+    );
+    let mut next_version: Version = 0;
+
+    let mut group = c.benchmark_group("mempool_notifications");
+    for num_transactions in NUM_TRANSACTIONS_PER_NOTIFICATION {
+        group.throughput(Throughput::Elements(num_transactions));
+        group.bench_function(
+            BenchmarkId::new("notify_committed_transactions", num_transactions),
+            |b| {
+                b.iter_batched(
+                    || {
+                        let first_version = next_version;
+                        next_version += num_transactions;
+                        (create_transactions(num_transactions), first_version)
+                    },
+                    |(transactions, first_version)| {
+                        runtime
+                            .block_on(
+                                mempool_notification_handler
+                                    .notify_mempool_of_committed_transactions(
+                                        transactions,
+                                        0,
+                                        first_version,
+                                        first_version + num_transactions - 1,
+                                        CommitOrigin::Consensus,
+                                    ),
+                            )
+                            .unwrap()
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+/// Creates the given number of (user) transactions
+fn create_transactions(num_transactions: u64) -> Vec<Transaction> {
+    (0..num_transactions)
+        .map(|_| create_transaction())
+        .collect()
+}
+
+criterion_group!(
+    name = mempool_notifications_benches;
+    config = Criterion::default();
+    targets = mempool_notifications_benchmark
+);
+criterion_main!(mempool_notifications_benches);
@@ -3,12 +3,20 @@
 
 use crate::{
     driver::DriverConfiguration,
+    driver_client::BootstrapSubscriptionId,
     error::Error,
     logging::{LogEntry, LogSchema},
     metadata_storage::MetadataStorageInterface,
-    storage_synchronizer::StorageSynchronizerInterface,
+    notification_handlers::{CommitStateTracker, ErrorSource},
+    storage_synchronizer::{
+        verify_state_value_chunk, ApplyOrExecute, BackpressureListener,
+        StorageSynchronizerInterface,
+    },
     utils,
-    utils::{SpeculativeStreamState, PENDING_DATA_LOG_FREQ_SECS},
+    utils::{
+        OutputFallbackHandler, SpeculativeStreamState, StreamRetryPolicy,
+        PENDING_DATA_LOG_FREQ_SECS, STORAGE_EMPTY_LOG_FREQ_SECS,
+    },
 };
 use aptos_config::config::BootstrappingMode;
 use aptos_data_client::GlobalDataSummary;
@@ -27,10 +35,19 @@ use aptos_types::{
 use data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
     data_stream::DataStreamListener,
-    streaming_client::{DataStreamingClient, NotificationFeedback},
+    streaming_client::{
+        DataStreamingClient, GetAllEpochEndingLedgerInfosRequest, GetAllStatesRequest,
+        GetAllTransactionOutputsRequest, GetAllTransactionsRequest, NotificationFeedback,
+        StreamRequest,
+    },
 };
 use futures::channel::oneshot;
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
 use storage_interface::DbReader;
 
 /// The expected version of the genesis transaction
@@ -276,23 +293,42 @@ impl StateValueSyncer {
     }
 }
 
+/// The (coarse) phase of bootstrapping that the node is in
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BootstrappingPhase {
+    Bootstrapped,                   // The node has completed bootstrapping
+    FetchingEpochEndingLedgerInfos, // The node is fetching (and verifying) epoch ending ledger infos
+    SyncingStates,                  // The node is downloading a state snapshot
+    SyncingTransactions,            // The node is syncing transactions (or outputs)
+}
+
 /// A simple component that manages the bootstrapping of the node
 pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The currently active data stream (provided by the data streaming service)
     active_data_stream: Option<DataStreamListener>,
 
-    // The channel used to notify a listener of successful bootstrapping
-    bootstrap_notifier_channel: Option<oneshot::Sender<Result<(), Error>>>,
+    // The listener for backpressure state changes of the storage synchronizer
+    backpressure_listener: BackpressureListener,
+
+    // The channels used to notify listeners of successful bootstrapping
+    bootstrap_notifier_channels:
+        HashMap<BootstrapSubscriptionId, oneshot::Sender<Result<(), Error>>>,
 
     // If the node has completed bootstrapping
     bootstrapped: bool,
 
+    // The shared commit state (used to publish the completion of bootstrapping)
+    commit_state_tracker: CommitStateTracker,
+
     // The config of the state sync driver
     driver_configuration: DriverConfiguration,
 
     // The storage to write metadata about the syncing progress
     metadata_storage: MetadataStorage,
 
+    // The handler that falls back to applying outputs if execution keeps failing
+    output_fallback_handler: OutputFallbackHandler,
+
     // The speculative state tracking the active data stream
     speculative_stream_state: Option<SpeculativeStreamState>,
 
@@ -308,8 +344,16 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The storage synchronizer used to update local storage
     storage_synchronizer: StorageSyncer,
 
+    // Whether the bootstrapping mode was switched to applying outputs by the
+    // output fallback (and should be reverted to execution once it ends).
+    using_output_fallback: bool,
+
     // The epoch states verified by this node (held in memory)
     verified_epoch_states: VerifiedEpochStates,
+
+    // Whether state value chunks are verified by the bootstrapper (otherwise,
+    // they're verified by the storage synchronizer).
+    verify_state_value_chunks: bool,
 }
 
 impl<
@@ -319,29 +363,49 @@ impl<
     > Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient>
 {
     pub fn new(
+        backpressure_listener: BackpressureListener,
+        commit_state_tracker: CommitStateTracker,
         driver_configuration: DriverConfiguration,
         metadata_storage: MetadataStorage,
+        output_fallback_handler: OutputFallbackHandler,
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         storage_synchronizer: StorageSyncer,
     ) -> Self {
         // Load the latest epoch state from storage
-        let latest_epoch_state = utils::fetch_latest_epoch_state(storage.clone())
-            .expect("Unable to fetch latest epoch state!");
+        let latest_epoch_state =
+            utils::fetch_latest_epoch_state(storage.clone(), &commit_state_tracker)
+                .expect("Unable to fetch latest epoch state!");
         let verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
 
+        // Verify that exactly one of the bootstrapper and the storage
+        // synchronizer will verify state value chunks.
+        let verify_state_value_chunks = !driver_configuration
+            .config
+            .verify_state_values_in_storage_synchronizer;
+        assert_ne!(
+            verify_state_value_chunks,
+            storage_synchronizer.verifies_state_value_chunks(),
+            "Exactly one of the bootstrapper and the storage synchronizer must verify state value chunks!"
+        );
+
         Self {
             state_value_syncer: StateValueSyncer::new(),
             active_data_stream: None,
-            bootstrap_notifier_channel: None,
+            backpressure_listener,
+            bootstrap_notifier_channels: HashMap::new(),
             bootstrapped: false,
+            commit_state_tracker,
             driver_configuration,
             metadata_storage,
+            output_fallback_handler,
             speculative_stream_state: None,
             streaming_client,
             storage,
             storage_synchronizer,
+            using_output_fallback: false,
             verified_epoch_states,
+            verify_state_value_chunks,
         }
     }
 
@@ -350,35 +414,67 @@ impl<
         self.bootstrapped
     }
 
+    /// Returns the current bootstrapping mode
+    pub fn get_bootstrapping_mode(&self) -> BootstrappingMode {
+        self.driver_configuration.config.bootstrapping_mode
+    }
+
+    /// Returns the phase of bootstrapping that the node is currently in
+    pub fn get_bootstrapping_phase(&self) -> BootstrappingPhase {
+        if self.bootstrapped {
+            BootstrappingPhase::Bootstrapped
+        } else if self.should_fetch_epoch_ending_ledger_infos() {
+            BootstrappingPhase::FetchingEpochEndingLedgerInfos
+        } else if self.state_value_syncer.ledger_info_to_sync.is_some() {
+            BootstrappingPhase::SyncingStates
+        } else {
+            BootstrappingPhase::SyncingTransactions
+        }
+    }
+
     /// Marks bootstrapping as complete and notifies any listeners
     pub fn bootstrapping_complete(&mut self) -> Result<(), Error> {
         info!(LogSchema::new(LogEntry::Bootstrapper)
             .message("The node has successfully bootstrapped!"));
         self.bootstrapped = true;
+        self.commit_state_tracker.set_bootstrapped();
         self.notify_listeners_if_bootstrapped()
     }
 
-    /// Subscribes the specified channel to bootstrap completion notifications
+    /// Subscribes the specified channel (with the given subscription id) to
+    /// bootstrap completion notifications. If a subscription with the same
+    /// id already exists, it is replaced.
     pub fn subscribe_to_bootstrap_notifications(
         &mut self,
+        subscription_id: BootstrapSubscriptionId,
         bootstrap_notifier_channel: oneshot::Sender<Result<(), Error>>,
     ) -> Result<(), Error> {
-        if self.bootstrap_notifier_channel.is_some() {
-            panic!("Only one boostrap subscriber is supported at a time!");
-        }
-
-        self.bootstrap_notifier_channel = Some(bootstrap_notifier_channel);
+        self.bootstrap_notifier_channels
+            .insert(subscription_id, bootstrap_notifier_channel);
         self.notify_listeners_if_bootstrapped()
     }
 
+    /// Removes the bootstrap notification subscription with the given id (e.g.,
+    /// because the subscriber stopped waiting). Returns true iff a subscription
+    /// was removed.
+    pub fn unsubscribe_from_bootstrap_notifications(
+        &mut self,
+        subscription_id: BootstrapSubscriptionId,
+    ) -> bool {
+        self.bootstrap_notifier_channels
+            .remove(&subscription_id)
+            .is_some()
+    }
+
     /// Notifies any listeners if we've now bootstrapped
     fn notify_listeners_if_bootstrapped(&mut self) -> Result<(), Error> {
+        let mut result = Ok(());
         if self.bootstrapped {
-            if let Some(notifier_channel) = self.bootstrap_notifier_channel.take() {
+            for (subscription_id, notifier_channel) in self.bootstrap_notifier_channels.drain() {
                 if let Err(error) = notifier_channel.send(Ok(())) {
-                    return Err(Error::CallbackSendFailed(format!(
-                        "Bootstrap notification error: {:?}",
-                        error
+                    result = Err(Error::CallbackSendFailed(format!(
+                        "Bootstrap notification error for subscription {:?}: {:?}",
+                        subscription_id, error
                     )));
                 }
             }
@@ -386,7 +482,7 @@ impl<
             self.storage_synchronizer.finish_chunk_executor(); // The bootstrapper is now complete
         }
 
-        Ok(())
+        result
     }
 
     /// Checks if the bootstrapper is able to make progress
@@ -400,10 +496,14 @@ impl<
             ));
         }
 
+        // Fall back to applying outputs if execution keeps failing (or revert
+        // to execution once the fallback ends).
+        self.update_output_fallback_mode();
+
         if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
             self.process_active_stream_notifications().await?;
-        } else if self.storage_synchronizer.pending_storage_data() {
+        } else if !self.storage_synchronizer.is_idle() {
             // Wait for any pending data to be processed
             sample!(
                 SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
@@ -419,6 +519,34 @@ impl<
         self.notify_listeners_if_bootstrapped()
     }
 
+    /// Switches the bootstrapping mode to applying outputs while the output
+    /// fallback is active, and reverts to executing transactions once the
+    /// fallback ends. If the mode changes while transactions are being synced,
+    /// the active stream is reset (so that the next stream uses the new mode).
+    fn update_output_fallback_mode(&mut self) {
+        let in_fallback_mode = self.output_fallback_handler.in_fallback_mode();
+        let bootstrapping_mode = if in_fallback_mode
+            && self.get_bootstrapping_mode() == BootstrappingMode::ExecuteTransactionsFromGenesis
+        {
+            self.using_output_fallback = true;
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis
+        } else if !in_fallback_mode && self.using_output_fallback {
+            self.using_output_fallback = false;
+            BootstrappingMode::ExecuteTransactionsFromGenesis
+        } else {
+            return; // There's nothing to switch
+        };
+
+        info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Switching the bootstrapping mode to {:?} (output fallback active: {:?})!",
+            bootstrapping_mode, in_fallback_mode
+        )));
+        self.driver_configuration.config.bootstrapping_mode = bootstrapping_mode;
+        if self.get_bootstrapping_phase() == BootstrappingPhase::SyncingTransactions {
+            self.reset_active_stream();
+        }
+    }
+
     /// Returns true iff the bootstrapper should continue to fetch epoch ending
     /// ledger infos (in order to make progress).
     fn should_fetch_epoch_ending_ledger_infos(&self) -> bool {
@@ -436,6 +564,32 @@ impl<
         // Reset the chunk executor to flush any invalid state currently held in-memory
         self.storage_synchronizer.reset_chunk_executor()?;
 
+        // If a state snapshot was only partially finalized (e.g., the node crashed
+        // during finalization), complete the finalization before anything else.
+        if self
+            .storage_synchronizer
+            .finalize_pending_state_snapshot()?
+        {
+            info!(LogSchema::new(LogEntry::Bootstrapper)
+                .message("Completed the finalization of a partially finalized state snapshot!"));
+            return Ok(());
+        }
+
+        // If storage is empty (i.e., genesis hasn't been committed), there's
+        // nothing to bootstrap from yet, so wait for genesis.
+        let highest_synced_version = match utils::fetch_latest_synced_version(self.storage.clone())?
+        {
+            Some(highest_synced_version) => highest_synced_version,
+            None => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(STORAGE_EMPTY_LOG_FREQ_SECS)),
+                    info!(LogSchema::new(LogEntry::Bootstrapper)
+                        .message("Storage is empty! Waiting for genesis before bootstrapping."))
+                );
+                return Ok(());
+            }
+        };
+
         // Always fetch the new epoch ending ledger infos first
         if self.should_fetch_epoch_ending_ledger_infos() {
             return self
@@ -443,8 +597,7 @@ impl<
                 .await;
         }
 
-        // Get the highest synced and known ledger info versions
-        let highest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
+        // Get the highest known ledger info version
         let highest_known_ledger_info = self.get_highest_known_ledger_info()?;
         let highest_known_ledger_version = highest_known_ledger_info.ledger_info().version();
 
@@ -515,10 +668,20 @@ impl<
                 // validator, consensus will take control and sync depending on how it sees fit.
                 self.bootstrapping_complete()
             } else {
-                panic!("Snapshot syncing is currently unsupported for nodes with existing state! \
-                        You are currently {:?} versions behind the latest snapshot version ({:?}). Either \
-                        select a different syncing mode, or delete your storage and restart your node.",
-                       num_versions_behind, highest_known_ledger_version);
+                // Identify the versions still held locally (to help operators decide
+                // whether the existing storage is worth keeping).
+                let lowest_available_version =
+                    utils::fetch_lowest_available_version(self.storage.clone())?;
+                panic!(
+                    "Snapshot syncing is currently unsupported for nodes with existing state! \
+                        You are currently {:?} versions behind the latest snapshot version ({:?}), \
+                        and local storage holds versions {:?} to {:?}. Either select a different \
+                        syncing mode, or delete your storage and restart your node.",
+                    num_versions_behind,
+                    highest_known_ledger_version,
+                    lowest_available_version,
+                    highest_synced_version
+                );
             }
         }
     }
@@ -544,6 +707,11 @@ impl<
             .config
             .max_consecutive_stream_notifications
         {
+            // Stop pulling notifications while the storage synchronizer is backpressured
+            if utils::is_backpressured(&self.backpressure_listener) {
+                return Ok(());
+            }
+
             // Fetch and process any data notifications
             let data_notification = self.fetch_next_data_notification().await?;
             match data_notification.data_payload {
@@ -593,6 +761,37 @@ impl<
         Ok(())
     }
 
+    /// Logs the state value chunk after which the previous snapshot sync (for
+    /// the given target) resumes, as recorded by the snapshot chunk progress.
+    fn log_snapshot_resume_point(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+        last_persisted_state_value_index: u64,
+    ) -> Result<(), Error> {
+        let target_version = target_ledger_info.ledger_info().version();
+        match self.metadata_storage.get_snapshot_chunk_progress()? {
+            Some(snapshot_chunk_progress)
+                if &snapshot_chunk_progress.target_ledger_info == target_ledger_info =>
+            {
+                info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                    "Resuming the state snapshot sync at version: {:?}, after chunk: {:?} \
+                    (last persisted state value index: {:?})",
+                    target_version,
+                    snapshot_chunk_progress.last_committed_chunk_index,
+                    snapshot_chunk_progress.last_persisted_state_value_index
+                )));
+            }
+            _ => {
+                info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                    "Resuming the state snapshot sync at version: {:?}, without any committed \
+                    chunks (last persisted state value index: {:?})",
+                    target_version, last_persisted_state_value_index
+                )));
+            }
+        }
+        Ok(())
+    }
+
     /// Fetches state values (as required to bootstrap the node)
     async fn fetch_missing_state_values(
         &mut self,
@@ -616,13 +815,14 @@ impl<
         let target_ledger_info_version = target_ledger_info.ledger_info().version();
         let data_stream = if self.state_value_syncer.transaction_output_to_sync.is_none() {
             // Fetch the transaction info first, before the states
-            self.streaming_client
-                .get_all_transaction_outputs(
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                    target_ledger_info_version,
-                )
-                .await?
+            self.create_data_stream(StreamRequest::GetAllTransactionOutputs(
+                GetAllTransactionOutputsRequest {
+                    start_version: target_ledger_info_version,
+                    end_version: target_ledger_info_version,
+                    proof_version: target_ledger_info_version,
+                },
+            ))
+            .await?
         } else {
             // Identify the next state index to fetch
             let next_state_index_to_process = if existing_snapshot_progress {
@@ -630,7 +830,7 @@ impl<
                 // rewrite the last persisted index (again!). This is a limitation
                 // of how the snapshot is persisted (i.e., in-memory sibling freezing).
                 // Thus, on each stream reset, we overlap every chunk by a single item.
-                self
+                let last_persisted_state_value_index = self
                     .metadata_storage
                     .get_last_persisted_state_value_index(&target_ledger_info)
                     .map_err(|error| {
@@ -638,7 +838,12 @@ impl<
                             "Failed to get the last persisted state value index at version {:?}! Error: {:?}",
                             target_ledger_info_version, error
                         ))
-                    })?
+                    })?;
+                self.log_snapshot_resume_point(
+                    &target_ledger_info,
+                    last_persisted_state_value_index,
+                )?;
+                last_persisted_state_value_index
             } else {
                 0 // We need to start the snapshot sync from index 0
             };
@@ -646,12 +851,11 @@ impl<
             // Fetch the missing state values
             self.state_value_syncer
                 .update_next_state_index_to_process(next_state_index_to_process);
-            self.streaming_client
-                .get_all_state_values(
-                    target_ledger_info_version,
-                    Some(next_state_index_to_process),
-                )
-                .await?
+            self.create_data_stream(StreamRequest::GetAllStates(GetAllStatesRequest {
+                version: target_ledger_info_version,
+                start_index: next_state_index_to_process,
+            }))
+            .await?
         };
         self.active_data_stream = Some(data_stream);
 
@@ -672,32 +876,29 @@ impl<
             .verified_epoch_states
             .next_epoch_ending_version(highest_synced_version)
             .expect("No higher epoch ending version known!");
-        let data_stream = match self.driver_configuration.config.bootstrapping_mode {
+        let stream_request = match self.driver_configuration.config.bootstrapping_mode {
             BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
-                self.streaming_client
-                    .get_all_transaction_outputs(
-                        next_version,
-                        end_version,
-                        highest_known_ledger_version,
-                    )
-                    .await?
+                StreamRequest::GetAllTransactionOutputs(GetAllTransactionOutputsRequest {
+                    start_version: next_version,
+                    end_version,
+                    proof_version: highest_known_ledger_version,
+                })
             }
             BootstrappingMode::ExecuteTransactionsFromGenesis => {
-                self.streaming_client
-                    .get_all_transactions(
-                        next_version,
-                        end_version,
-                        highest_known_ledger_version,
-                        false,
-                    )
-                    .await?
+                StreamRequest::GetAllTransactions(GetAllTransactionsRequest {
+                    start_version: next_version,
+                    end_version,
+                    proof_version: highest_known_ledger_version,
+                    include_events: false,
+                })
             }
             bootstrapping_mode => {
                 unreachable!("Bootstrapping mode not supported: {:?}", bootstrapping_mode)
             }
         };
+        let data_stream = self.create_data_stream(stream_request).await?;
         self.speculative_stream_state = Some(SpeculativeStreamState::new(
-            utils::fetch_latest_epoch_state(self.storage.clone())?,
+            utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)?,
             Some(highest_known_ledger_info),
             highest_synced_version,
         ));
@@ -758,8 +959,11 @@ impl<
                 Error::IntegerOverflow("The next epoch end has overflown!".into())
             })?;
             let epoch_ending_stream = self
-                .streaming_client
-                .get_all_epoch_ending_ledger_infos(next_epoch_end)
+                .create_data_stream(StreamRequest::GetAllEpochEndingLedgerInfos(
+                    GetAllEpochEndingLedgerInfosRequest {
+                        start_epoch: next_epoch_end,
+                    },
+                ))
                 .await?;
             self.active_data_stream = Some(epoch_ending_stream);
         } else if self.verified_epoch_states.verified_waypoint() {
@@ -916,29 +1120,34 @@ impl<
         self.verify_states_values_indices(notification_id, &state_value_chunk_with_proof)
             .await?;
 
-        // Verify the chunk root hash matches the expected root hash
-        let expected_root_hash = transaction_output_to_sync
-            .proof
-            .transaction_infos
-            .first()
-            .expect("Target transaction info should exist!")
-            .ensure_state_checkpoint_hash()
-            .expect("Must be at state checkpoint.");
-        if state_value_chunk_with_proof.root_hash != expected_root_hash {
-            self.terminate_active_stream(notification_id, NotificationFeedback::InvalidPayloadData)
+        // Verify the chunk against the proof (unless the storage synchronizer will)
+        if self.verify_state_value_chunks {
+            let expected_root_hash = transaction_output_to_sync
+                .proof
+                .transaction_infos
+                .first()
+                .expect("Target transaction info should exist!")
+                .ensure_state_checkpoint_hash()
+                .expect("Must be at state checkpoint.");
+            if let Err(error) =
+                verify_state_value_chunk(&state_value_chunk_with_proof, expected_root_hash)
+            {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::InvalidPayloadData,
+                )
                 .await?;
-            return Err(Error::VerificationError(format!(
-                "The states chunk with proof root hash: {:?} didn't match the expected hash: {:?}!",
-                state_value_chunk_with_proof.root_hash, expected_root_hash,
-            )));
+                return Err(error);
+            }
         }
 
         // Process the state values chunk and proof
         let last_state_value_index = state_value_chunk_with_proof.last_index;
-        if let Err(error) = self
-            .storage_synchronizer
-            .save_state_values(notification_id, state_value_chunk_with_proof)
-        {
+        if let Err(error) = self.storage_synchronizer.save_state_values_with_proof(
+            notification_id,
+            state_value_chunk_with_proof,
+            !self.verify_state_value_chunks,
+        ) {
             self.terminate_active_stream(notification_id, NotificationFeedback::InvalidPayloadData)
                 .await?;
             return Err(Error::InvalidPayload(format!(
@@ -1062,61 +1271,43 @@ impl<
             .await?;
 
         // Execute/apply and commit the transactions/outputs
-        let num_transactions_or_outputs = match bootstrapping_mode {
-            BootstrappingMode::ApplyTransactionOutputsFromGenesis => {
-                if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
-                    let num_transaction_outputs = transaction_outputs_with_proof
-                        .transactions_and_outputs
-                        .len();
-                    self.storage_synchronizer.apply_transaction_outputs(
-                        notification_id,
-                        transaction_outputs_with_proof,
-                        proof_ledger_info,
-                        end_of_epoch_ledger_info,
-                    )?;
-                    num_transaction_outputs
-                } else {
-                    self.terminate_active_stream(
-                        notification_id,
-                        NotificationFeedback::PayloadTypeIsIncorrect,
-                    )
-                    .await?;
-                    return Err(Error::InvalidPayload(
-                        "Did not receive transaction outputs with proof!".into(),
-                    ));
-                }
-            }
-            BootstrappingMode::ExecuteTransactionsFromGenesis => {
-                if let Some(transaction_list_with_proof) = transaction_list_with_proof {
-                    let num_transactions = transaction_list_with_proof.transactions.len();
-                    self.storage_synchronizer.execute_transactions(
-                        notification_id,
-                        transaction_list_with_proof,
-                        proof_ledger_info,
-                        end_of_epoch_ledger_info,
-                    )?;
-                    num_transactions
-                } else {
-                    self.terminate_active_stream(
-                        notification_id,
-                        NotificationFeedback::PayloadTypeIsIncorrect,
-                    )
-                    .await?;
-                    return Err(Error::InvalidPayload(
-                        "Did not receive transactions with proof!".into(),
-                    ));
-                }
-            }
+        let apply_or_execute = match bootstrapping_mode {
+            BootstrappingMode::ApplyTransactionOutputsFromGenesis => ApplyOrExecute::Apply,
+            BootstrappingMode::ExecuteTransactionsFromGenesis => ApplyOrExecute::Execute,
             bootstrapping_mode => {
                 unreachable!("Bootstrapping mode not supported: {:?}", bootstrapping_mode)
             }
         };
-        let synced_version = payload_start_version
-            .checked_add(num_transactions_or_outputs as u64)
-            .and_then(|version| version.checked_sub(1)) // synced_version = start + num txns/outputs - 1
-            .ok_or_else(|| Error::IntegerOverflow("The synced version has overflown!".into()))?;
-        self.get_speculative_stream_state()
-            .update_synced_version(synced_version);
+        let chunk_payload = match utils::get_transaction_chunk_payload(
+            apply_or_execute,
+            transaction_list_with_proof,
+            transaction_outputs_with_proof,
+        ) {
+            Ok(chunk_payload) => chunk_payload,
+            Err(error) => {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::PayloadTypeIsIncorrect,
+                )
+                .await?;
+                return Err(error);
+            }
+        };
+        let num_transactions_or_outputs = chunk_payload.num_transactions();
+        self.storage_synchronizer.submit_transaction_chunk(
+            notification_id,
+            ErrorSource::Bootstrapper,
+            apply_or_execute,
+            chunk_payload,
+            proof_ledger_info,
+            end_of_epoch_ledger_info,
+            None,
+        )?;
+        self.get_speculative_stream_state().update_with_payload(
+            payload_start_version,
+            num_transactions_or_outputs,
+            None,
+        )?;
 
         Ok(())
     }
@@ -1322,6 +1513,16 @@ impl<
         notification_id: NotificationId,
         notification_feedback: NotificationFeedback,
     ) -> Result<(), Error> {
+        // If there's no active stream, there's nothing to terminate (e.g., the
+        // stream was already reset, or bootstrapping has since completed).
+        if !self.has_active_stream() {
+            warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Ignoring the stream termination for notification {:?}! There is no active stream.",
+                notification_id
+            )));
+            return Ok(());
+        }
+
         self.reset_active_stream();
 
         utils::terminate_stream_with_feedback(
@@ -1339,6 +1540,27 @@ impl<
             .expect("Speculative stream state does not exist!")
     }
 
+    /// Creates a new data stream for the given request (retrying transient
+    /// failures of the streaming service).
+    async fn create_data_stream(
+        &mut self,
+        stream_request: StreamRequest,
+    ) -> Result<DataStreamListener, Error> {
+        let active_stream = utils::initialize_data_stream(
+            &self.streaming_client,
+            stream_request,
+            StreamRetryPolicy::from_config(&self.driver_configuration.config),
+            &self.driver_configuration.metrics,
+        )
+        .await?;
+        Ok(active_stream.into_data_stream_listener())
+    }
+
+    /// Returns true iff there is a currently active data stream
+    pub fn has_active_stream(&self) -> bool {
+        self.active_data_stream.is_some()
+    }
+
     /// Resets the currently active data stream and speculative state
     fn reset_active_stream(&mut self) {
         self.speculative_stream_state = None;
@@ -4,22 +4,30 @@
 use crate::{
     driver::DriverConfiguration,
     error::Error,
-    notification_handlers::ConsensusSyncRequest,
-    storage_synchronizer::StorageSynchronizerInterface,
+    notification_handlers::{CommitStateTracker, ErrorSource},
+    storage_synchronizer::{ApplyOrExecute, BackpressureListener, StorageSynchronizerInterface},
     utils,
-    utils::{SpeculativeStreamState, PENDING_DATA_LOG_FREQ_SECS},
+    utils::{
+        OutputFallbackHandler, SpeculativeStreamState, StreamRetryPolicy,
+        PENDING_DATA_LOG_FREQ_SECS, STORAGE_EMPTY_LOG_FREQ_SECS,
+    },
 };
 use aptos_config::config::ContinuousSyncingMode;
-use aptos_infallible::Mutex;
 use aptos_logger::{prelude::*, sample, sample::SampleRate};
 use aptos_types::{
+    epoch_change::Verifier,
+    epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
 };
 use data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
     data_stream::DataStreamListener,
-    streaming_client::{DataStreamingClient, Epoch, NotificationFeedback},
+    streaming_client::{
+        ContinuouslyStreamTransactionOutputsRequest, ContinuouslyStreamTransactionsRequest,
+        DataStreamingClient, Epoch, GetAllEpochEndingLedgerInfosRequest, NotificationFeedback,
+        StreamRequest,
+    },
 };
 use std::{sync::Arc, time::Duration};
 use storage_interface::DbReader;
@@ -29,9 +37,22 @@ pub struct ContinuousSyncer<StorageSyncer, StreamingClient> {
     // The currently active data stream (provided by the data streaming service)
     active_data_stream: Option<DataStreamListener>,
 
+    // The listener for backpressure state changes of the storage synchronizer
+    backpressure_listener: BackpressureListener,
+
+    // The tracker of the latest commit state (and cached epoch state)
+    commit_state_tracker: CommitStateTracker,
+
     // The config of the state sync driver
     driver_configuration: DriverConfiguration,
 
+    // The future epoch sync target that the active (epoch ending) data stream
+    // is verifying. This is None if the active stream is not an epoch chase.
+    epoch_chase_target: Option<LedgerInfoWithSignatures>,
+
+    // The handler that falls back to applying outputs if execution keeps failing
+    output_fallback_handler: OutputFallbackHandler,
+
     // The speculative state tracking the active data stream
     speculative_stream_state: Option<SpeculativeStreamState>,
 
@@ -43,6 +64,14 @@ pub struct ContinuousSyncer<StorageSyncer, StreamingClient> {
 
     // The storage synchronizer used to update local storage
     storage_synchronizer: StorageSyncer,
+
+    // Whether the syncing mode was switched to applying outputs by the output
+    // fallback (and should be reverted to execution once it ends).
+    using_output_fallback: bool,
+
+    // A future epoch sync target that has been verified by the epoch chase,
+    // but hasn't yet been taken by the driver.
+    verified_sync_target: Option<LedgerInfoWithSignatures>,
 }
 
 impl<
@@ -51,31 +80,62 @@ impl<
     > ContinuousSyncer<StorageSyncer, StreamingClient>
 {
     pub fn new(
+        backpressure_listener: BackpressureListener,
+        commit_state_tracker: CommitStateTracker,
         driver_configuration: DriverConfiguration,
+        output_fallback_handler: OutputFallbackHandler,
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         storage_synchronizer: StorageSyncer,
     ) -> Self {
         Self {
             active_data_stream: None,
+            backpressure_listener,
+            commit_state_tracker,
             driver_configuration,
+            epoch_chase_target: None,
+            output_fallback_handler,
             speculative_stream_state: None,
             streaming_client,
             storage,
             storage_synchronizer,
+            using_output_fallback: false,
+            verified_sync_target: None,
         }
     }
 
-    /// Checks if the continuous syncer is able to make progress
+    /// Checks if the continuous syncer is able to make progress. The sync
+    /// request target is a snapshot of the active consensus sync request
+    /// (owned by the driver), so no state is shared with the driver. If the
+    /// target hasn't been verified (i.e., it's in a future epoch), the epoch
+    /// changes up to the target are synced and verified first.
     pub async fn drive_progress(
         &mut self,
-        consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        sync_request_target: Option<LedgerInfoWithSignatures>,
+        sync_target_verified: bool,
     ) -> Result<(), Error> {
+        if let Some(sync_request_target) = &sync_request_target {
+            if !sync_target_verified {
+                return self
+                    .drive_epoch_chase_progress(sync_request_target.clone())
+                    .await;
+            }
+        }
+
+        // If the active stream is still chasing epochs, it's no longer needed
+        if self.epoch_chase_target.is_some() {
+            self.reset_active_stream();
+        }
+
+        // Fall back to applying outputs if execution keeps failing (or revert
+        // to execution once the fallback ends).
+        self.update_output_fallback_mode();
+
         if self.active_data_stream.is_some() {
             // We have an active data stream. Process any notifications!
-            self.process_active_stream_notifications(consensus_sync_request)
+            self.process_active_stream_notifications(sync_request_target)
                 .await
-        } else if self.storage_synchronizer.pending_storage_data() {
+        } else if !self.storage_synchronizer.is_idle() {
             // Wait for any pending data to be processed
             sample!(
                 SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
@@ -84,54 +144,304 @@ impl<
             Ok(())
         } else {
             // Fetch a new data stream to start streaming data
-            self.initialize_active_data_stream(consensus_sync_request)
+            self.initialize_active_data_stream(sync_request_target)
+                .await
+        }
+    }
+
+    /// Drives progress of the epoch chase for the given (future epoch) sync
+    /// target, i.e., streams and verifies the epoch ending ledger infos up to
+    /// the target epoch, so that the target itself can be verified.
+    async fn drive_epoch_chase_progress(
+        &mut self,
+        sync_request_target: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        // If the active stream isn't chasing epochs for the target, reset it
+        if self.has_active_stream()
+            && self.epoch_chase_target.as_ref() != Some(&sync_request_target)
+        {
+            self.reset_active_stream();
+        }
+
+        if self.active_data_stream.is_some() {
+            // We have an active epoch chase. Process any notifications!
+            self.process_epoch_chase_notifications(sync_request_target)
                 .await
+        } else if !self.storage_synchronizer.is_idle() {
+            // Wait for any pending data to be processed
+            sample!(
+                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                info!("Waiting for the storage synchronizer to handle pending data!")
+            );
+            Ok(())
+        } else {
+            // Fetch a new epoch ending stream to start chasing the target epoch
+            self.initialize_epoch_chase_stream(sync_request_target)
+                .await
+        }
+    }
+
+    /// Initializes an epoch ending data stream to verify the given sync target
+    async fn initialize_epoch_chase_stream(
+        &mut self,
+        sync_request_target: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        // Fetch the highest synced version and epoch state (in storage)
+        let highest_synced_version = match utils::fetch_latest_synced_version(self.storage.clone())?
+        {
+            Some(highest_synced_version) => highest_synced_version,
+            None => {
+                log_storage_empty();
+                return Ok(());
+            }
+        };
+        let highest_epoch_state =
+            utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)?;
+
+        // If we've already reached the target epoch, verify the target directly
+        if highest_epoch_state.epoch >= sync_request_target.ledger_info().epoch() {
+            return self.verify_epoch_chase_target(&highest_epoch_state, sync_request_target);
+        }
+
+        // Stream the epoch ending ledger infos, starting with our current epoch
+        info!(
+            "Syncing the epoch changes up to the sync target! Current epoch: {:?}, target epoch: {:?}",
+            highest_epoch_state.epoch,
+            sync_request_target.ledger_info().epoch()
+        );
+        let epoch_ending_stream = self
+            .create_data_stream(StreamRequest::GetAllEpochEndingLedgerInfos(
+                GetAllEpochEndingLedgerInfosRequest {
+                    start_epoch: highest_epoch_state.epoch,
+                },
+            ))
+            .await?;
+        self.speculative_stream_state = Some(SpeculativeStreamState::new(
+            highest_epoch_state,
+            None,
+            highest_synced_version,
+        ));
+        self.active_data_stream = Some(epoch_ending_stream);
+        self.epoch_chase_target = Some(sync_request_target);
+
+        Ok(())
+    }
+
+    /// Processes any epoch ending notifications already pending on the active stream
+    async fn process_epoch_chase_notifications(
+        &mut self,
+        sync_request_target: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        for _ in 0..self
+            .driver_configuration
+            .config
+            .max_consecutive_stream_notifications
+        {
+            // Fetch and process any data notifications
+            let data_notification = self.fetch_next_data_notification().await?;
+            match data_notification.data_payload {
+                DataPayload::EpochEndingLedgerInfos(epoch_ending_ledger_infos) => {
+                    self.process_epoch_ending_payload(
+                        &sync_request_target,
+                        data_notification.notification_id,
+                        epoch_ending_ledger_infos,
+                    )
+                    .await?;
+                    if !self.has_active_stream() {
+                        return Ok(()); // The target has been verified
+                    }
+                }
+                _ => {
+                    return self
+                        .handle_end_of_stream_or_invalid_payload(data_notification)
+                        .await;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the given epoch ending ledger infos and updates the speculative
+    /// epoch state. Once the target epoch is reached, the target is verified.
+    async fn process_epoch_ending_payload(
+        &mut self,
+        sync_request_target: &LedgerInfoWithSignatures,
+        notification_id: NotificationId,
+        epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        for epoch_ending_ledger_info in epoch_ending_ledger_infos {
+            // Verify the ledger info against the current (speculative) epoch state
+            if let Err(error) = self
+                .get_speculative_stream_state()
+                .verify_ledger_info_with_signatures(&epoch_ending_ledger_info)
+            {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::PayloadProofFailed,
+                )
+                .await?;
+                return Err(error);
+            }
+
+            // Move to the next epoch
+            let next_epoch_state = match epoch_ending_ledger_info.ledger_info().next_epoch_state() {
+                Some(next_epoch_state) => next_epoch_state.clone(),
+                None => {
+                    self.terminate_active_stream(
+                        notification_id,
+                        NotificationFeedback::InvalidPayloadData,
+                    )
+                    .await?;
+                    return Err(Error::VerificationError(format!(
+                        "Received a ledger info that doesn't end the epoch! Version: {:?}",
+                        epoch_ending_ledger_info.ledger_info().version()
+                    )));
+                }
+            };
+
+            // If we've reached the target epoch, verify the target
+            if next_epoch_state.epoch >= sync_request_target.ledger_info().epoch() {
+                return self
+                    .verify_epoch_chase_target(&next_epoch_state, sync_request_target.clone());
+            }
+            if let Err(error) = self
+                .get_speculative_stream_state()
+                .update_epoch_state(next_epoch_state)
+            {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::InvalidPayloadData,
+                )
+                .await?;
+                return Err(error);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verifies the sync target against the given epoch state (derived from the
+    /// epoch chase). The active stream is reset regardless of the result.
+    fn verify_epoch_chase_target(
+        &mut self,
+        epoch_state: &EpochState,
+        sync_request_target: LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        self.reset_active_stream();
+
+        epoch_state.verify(&sync_request_target).map_err(|error| {
+            Error::InvalidSyncTarget(format!(
+                "Failed to verify the future epoch sync target at version {:?} against epoch state: {:?}. Error: {:?}",
+                sync_request_target.ledger_info().version(),
+                epoch_state,
+                error
+            ))
+        })?;
+        self.verified_sync_target = Some(sync_request_target);
+
+        Ok(())
+    }
+
+    /// Returns the current continuous syncing mode
+    pub fn get_continuous_syncing_mode(&self) -> ContinuousSyncingMode {
+        self.driver_configuration.config.continuous_syncing_mode
+    }
+
+    /// Switches the continuous syncing mode and returns the previous mode. If
+    /// the mode changes, the active stream is reset, so that the next stream
+    /// uses the new mode. Any pending data (in the storage synchronizer) is
+    /// still processed using the previous mode.
+    pub fn set_continuous_syncing_mode(
+        &mut self,
+        continuous_syncing_mode: ContinuousSyncingMode,
+    ) -> ContinuousSyncingMode {
+        let previous_mode = self.get_continuous_syncing_mode();
+        if continuous_syncing_mode != previous_mode {
+            self.driver_configuration.config.continuous_syncing_mode = continuous_syncing_mode;
+            self.reset_active_stream();
         }
+        previous_mode
+    }
+
+    /// Switches the continuous syncing mode to applying outputs while the
+    /// output fallback is active, and reverts to executing transactions once
+    /// the fallback ends (unless the mode was changed in the meantime).
+    fn update_output_fallback_mode(&mut self) {
+        let in_fallback_mode = self.output_fallback_handler.in_fallback_mode();
+        let continuous_syncing_mode = self.get_continuous_syncing_mode();
+        let new_syncing_mode = if in_fallback_mode
+            && continuous_syncing_mode == ContinuousSyncingMode::ExecuteTransactions
+        {
+            self.using_output_fallback = true;
+            ContinuousSyncingMode::ApplyTransactionOutputs
+        } else if !in_fallback_mode && self.using_output_fallback {
+            self.using_output_fallback = false;
+            if continuous_syncing_mode != ContinuousSyncingMode::ApplyTransactionOutputs {
+                return; // The mode was changed while the fallback was active
+            }
+            ContinuousSyncingMode::ExecuteTransactions
+        } else {
+            return; // There's nothing to switch
+        };
+
+        info!(
+            "Switching the continuous syncing mode to {:?} (output fallback active: {:?})!",
+            new_syncing_mode, in_fallback_mode
+        );
+        self.set_continuous_syncing_mode(new_syncing_mode);
+    }
+
+    /// Takes the future epoch sync target that was verified by the epoch chase (if any)
+    pub fn take_verified_sync_target(&mut self) -> Option<LedgerInfoWithSignatures> {
+        self.verified_sync_target.take()
     }
 
     /// Initializes an active data stream so that we can begin to process notifications
     async fn initialize_active_data_stream(
         &mut self,
-        consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        sync_request_target: Option<LedgerInfoWithSignatures>,
     ) -> Result<(), Error> {
         // Reset the chunk executor to flush any invalid state currently held in-memory
         self.storage_synchronizer.reset_chunk_executor()?;
 
         // Fetch the highest synced version and epoch (in storage)
         let (highest_synced_version, highest_synced_epoch) =
-            self.get_highest_synced_version_and_epoch()?;
+            match self.get_highest_synced_version_and_epoch()? {
+                Some(highest_synced_version_and_epoch) => highest_synced_version_and_epoch,
+                None => {
+                    log_storage_empty();
+                    return Ok(());
+                }
+            };
 
         // Fetch the highest epoch state (in storage)
-        let highest_epoch_state = utils::fetch_latest_epoch_state(self.storage.clone())?;
-
-        // Fetch the consensus sync request target (if there is one)
-        let sync_request_target = consensus_sync_request
-            .lock()
-            .as_ref()
-            .map(|sync_request| sync_request.get_sync_target());
+        let highest_epoch_state =
+            utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)?;
 
         // Initialize a new active data stream
-        let active_data_stream = match self.driver_configuration.config.continuous_syncing_mode {
+        let stream_request = match self.driver_configuration.config.continuous_syncing_mode {
             ContinuousSyncingMode::ApplyTransactionOutputs => {
-                self.streaming_client
-                    .continuously_stream_transaction_outputs(
-                        highest_synced_version,
-                        highest_synced_epoch,
-                        sync_request_target,
-                    )
-                    .await?
+                StreamRequest::ContinuouslyStreamTransactionOutputs(
+                    ContinuouslyStreamTransactionOutputsRequest {
+                        known_version: highest_synced_version,
+                        known_epoch: highest_synced_epoch,
+                        target: sync_request_target,
+                    },
+                )
             }
             ContinuousSyncingMode::ExecuteTransactions => {
-                self.streaming_client
-                    .continuously_stream_transactions(
-                        highest_synced_version,
-                        highest_synced_epoch,
-                        false,
-                        sync_request_target,
-                    )
-                    .await?
+                StreamRequest::ContinuouslyStreamTransactions(
+                    ContinuouslyStreamTransactionsRequest {
+                        known_version: highest_synced_version,
+                        known_epoch: highest_synced_epoch,
+                        include_events: false,
+                        target: sync_request_target,
+                    },
+                )
             }
         };
+        let active_data_stream = self.create_data_stream(stream_request).await?;
         self.speculative_stream_state = Some(SpeculativeStreamState::new(
             highest_epoch_state,
             None,
@@ -159,13 +469,18 @@ impl<
     /// Processes any notifications already pending on the active stream
     async fn process_active_stream_notifications(
         &mut self,
-        consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        sync_request_target: Option<LedgerInfoWithSignatures>,
     ) -> Result<(), Error> {
         for _ in 0..self
             .driver_configuration
             .config
             .max_consecutive_stream_notifications
         {
+            // Stop pulling notifications while the storage synchronizer is backpressured
+            if utils::is_backpressured(&self.backpressure_listener) {
+                return Ok(());
+            }
+
             // Fetch and process any data notifications
             let data_notification = self.fetch_next_data_notification().await?;
             match data_notification.data_payload {
@@ -176,7 +491,7 @@ impl<
                     let payload_start_version =
                         transaction_outputs_with_proof.first_transaction_output_version;
                     self.process_transaction_or_output_payload(
-                        consensus_sync_request.clone(),
+                        sync_request_target.clone(),
                         data_notification.notification_id,
                         ledger_info_with_sigs,
                         None,
@@ -191,7 +506,7 @@ impl<
                 ) => {
                     let payload_start_version = transactions_with_proof.first_transaction_version;
                     self.process_transaction_or_output_payload(
-                        consensus_sync_request.clone(),
+                        sync_request_target.clone(),
                         data_notification.notification_id,
                         ledger_info_with_sigs,
                         Some(transactions_with_proof),
@@ -211,18 +526,30 @@ impl<
         Ok(())
     }
 
-    /// Returns the highest synced version and epoch in storage
-    fn get_highest_synced_version_and_epoch(&self) -> Result<(Version, Epoch), Error> {
-        let highest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
-        let highest_synced_epoch = utils::fetch_latest_epoch_state(self.storage.clone())?.epoch;
-
-        Ok((highest_synced_version, highest_synced_epoch))
+    /// Returns the highest synced version and epoch. Any data still in
+    /// flight in the storage synchronizer is accounted for (so that the
+    /// next stream doesn't re-request it). Returns None if storage is empty.
+    fn get_highest_synced_version_and_epoch(&self) -> Result<Option<(Version, Epoch)>, Error> {
+        let highest_synced_version = match utils::fetch_latest_synced_version(self.storage.clone())?
+        {
+            Some(highest_synced_version) => highest_synced_version,
+            None => return Ok(None),
+        };
+        let highest_synced_version = self
+            .storage_synchronizer
+            .pending_storage_data()
+            .highest_known_version(highest_synced_version);
+        let highest_synced_epoch =
+            utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)?
+                .epoch;
+
+        Ok(Some((highest_synced_version, highest_synced_epoch)))
     }
 
     /// Process a single transaction or transaction output data payload
     async fn process_transaction_or_output_payload(
         &mut self,
-        consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        sync_request_target: Option<LedgerInfoWithSignatures>,
         notification_id: NotificationId,
         ledger_info_with_signatures: LedgerInfoWithSignatures,
         transaction_list_with_proof: Option<TransactionListWithProof>,
@@ -234,69 +561,56 @@ impl<
             .verify_payload_start_version(notification_id, payload_start_version)
             .await?;
 
-        // Verify the given proof ledger info
+        // Verify the given proof ledger info against the sync request target
         self.verify_proof_ledger_info(
-            consensus_sync_request.clone(),
+            sync_request_target.clone(),
             notification_id,
             &ledger_info_with_signatures,
         )
         .await?;
 
         // Execute/apply and commit the transactions/outputs
-        let num_transactions_or_outputs =
-            match self.driver_configuration.config.continuous_syncing_mode {
-                ContinuousSyncingMode::ApplyTransactionOutputs => {
-                    if let Some(transaction_outputs_with_proof) = transaction_outputs_with_proof {
-                        let num_transaction_outputs = transaction_outputs_with_proof
-                            .transactions_and_outputs
-                            .len();
-                        self.storage_synchronizer.apply_transaction_outputs(
-                            notification_id,
-                            transaction_outputs_with_proof,
-                            ledger_info_with_signatures.clone(),
-                            None,
-                        )?;
-                        num_transaction_outputs
-                    } else {
-                        self.terminate_active_stream(
-                            notification_id,
-                            NotificationFeedback::PayloadTypeIsIncorrect,
-                        )
-                        .await?;
-                        return Err(Error::InvalidPayload(
-                            "Did not receive transaction outputs with proof!".into(),
-                        ));
-                    }
-                }
-                ContinuousSyncingMode::ExecuteTransactions => {
-                    if let Some(transaction_list_with_proof) = transaction_list_with_proof {
-                        let num_transactions = transaction_list_with_proof.transactions.len();
-                        self.storage_synchronizer.execute_transactions(
-                            notification_id,
-                            transaction_list_with_proof,
-                            ledger_info_with_signatures.clone(),
-                            None,
-                        )?;
-                        num_transactions
-                    } else {
-                        self.terminate_active_stream(
-                            notification_id,
-                            NotificationFeedback::PayloadTypeIsIncorrect,
-                        )
-                        .await?;
-                        return Err(Error::InvalidPayload(
-                            "Did not receive transactions with proof!".into(),
-                        ));
-                    }
-                }
-            };
-        let synced_version = payload_start_version
-            .checked_add(num_transactions_or_outputs as u64)
-            .and_then(|version| version.checked_sub(1)) // synced_version = start + num txns/outputs - 1
-            .ok_or_else(|| Error::IntegerOverflow("The synced version has overflown!".into()))?;
-        let speculative_stream_state = self.get_speculative_stream_state();
-        speculative_stream_state.update_synced_version(synced_version);
-        speculative_stream_state.maybe_update_epoch_state(ledger_info_with_signatures);
+        let apply_or_execute = match self.driver_configuration.config.continuous_syncing_mode {
+            ContinuousSyncingMode::ApplyTransactionOutputs => ApplyOrExecute::Apply,
+            ContinuousSyncingMode::ExecuteTransactions => ApplyOrExecute::Execute,
+        };
+        let chunk_payload = match utils::get_transaction_chunk_payload(
+            apply_or_execute,
+            transaction_list_with_proof,
+            transaction_outputs_with_proof,
+        ) {
+            Ok(chunk_payload) => chunk_payload,
+            Err(error) => {
+                self.terminate_active_stream(
+                    notification_id,
+                    NotificationFeedback::PayloadTypeIsIncorrect,
+                )
+                .await?;
+                return Err(error);
+            }
+        };
+        // Note: the proof ledger info signatures are verified (against the
+        // speculative epoch state) by the storage synchronizer.
+        let num_transactions_or_outputs = chunk_payload.num_transactions();
+        let epoch_state = self.get_speculative_stream_state().get_epoch_state();
+        self.storage_synchronizer.submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            apply_or_execute,
+            chunk_payload,
+            ledger_info_with_signatures.clone(),
+            None,
+            Some(epoch_state),
+        )?;
+        if let Err(error) = self.get_speculative_stream_state().update_with_payload(
+            payload_start_version,
+            num_transactions_or_outputs,
+            Some(ledger_info_with_signatures),
+        ) {
+            self.terminate_active_stream(notification_id, NotificationFeedback::InvalidPayloadData)
+                .await?;
+            return Err(error);
+        }
 
         Ok(())
     }
@@ -335,18 +649,17 @@ impl<
     }
 
     /// Verifies the given ledger info to be used as a transaction or transaction
-    /// output chunk proof. If verification fails, the active stream is terminated.
+    /// output chunk proof isn't beyond the sync request target. If verification
+    /// fails, the active stream is terminated.
+    ///
+    /// Note: the ledger info signatures are verified by the storage synchronizer.
     async fn verify_proof_ledger_info(
         &mut self,
-        consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
+        sync_request_target: Option<LedgerInfoWithSignatures>,
         notification_id: NotificationId,
         ledger_info_with_signatures: &LedgerInfoWithSignatures,
     ) -> Result<(), Error> {
         // If we're syncing to a specific target, verify the ledger info isn't too high
-        let sync_request_target = consensus_sync_request
-            .lock()
-            .as_ref()
-            .map(|sync_request| sync_request.get_sync_target());
         if let Some(sync_request_target) = sync_request_target {
             let sync_request_version = sync_request_target.ledger_info().version();
             let proof_version = ledger_info_with_signatures.ledger_info().version();
@@ -363,17 +676,7 @@ impl<
             }
         }
 
-        // Verify the ledger info state and signatures
-        if let Err(error) = self
-            .get_speculative_stream_state()
-            .verify_ledger_info_with_signatures(ledger_info_with_signatures)
-        {
-            self.terminate_active_stream(notification_id, NotificationFeedback::PayloadProofFailed)
-                .await?;
-            Err(error)
-        } else {
-            Ok(())
-        }
+        Ok(())
     }
 
     /// Handles the end of stream notification or an invalid payload by
@@ -397,6 +700,16 @@ impl<
         notification_id: NotificationId,
         notification_feedback: NotificationFeedback,
     ) -> Result<(), Error> {
+        // If there's no active stream, there's nothing to terminate (e.g., the
+        // stream was already reset, or bootstrapping has since completed).
+        if !self.has_active_stream() {
+            warn!(
+                "Ignoring the stream termination for notification {:?}! There is no active stream.",
+                notification_id
+            );
+            return Ok(());
+        }
+
         self.reset_active_stream();
 
         utils::terminate_stream_with_feedback(
@@ -407,6 +720,22 @@ impl<
         .await
     }
 
+    /// Creates a new data stream for the given request (retrying transient
+    /// failures of the streaming service).
+    async fn create_data_stream(
+        &mut self,
+        stream_request: StreamRequest,
+    ) -> Result<DataStreamListener, Error> {
+        let active_stream = utils::initialize_data_stream(
+            &self.streaming_client,
+            stream_request,
+            StreamRetryPolicy::from_config(&self.driver_configuration.config),
+            &self.driver_configuration.metrics,
+        )
+        .await?;
+        Ok(active_stream.into_data_stream_listener())
+    }
+
     /// Returns the speculative stream state. Assumes that the state exists.
     fn get_speculative_stream_state(&mut self) -> &mut SpeculativeStreamState {
         self.speculative_stream_state
@@ -414,9 +743,24 @@ impl<
             .expect("Speculative stream state does not exist!")
     }
 
+    /// Returns true iff there is a currently active data stream
+    pub fn has_active_stream(&self) -> bool {
+        self.active_data_stream.is_some()
+    }
+
     /// Resets the currently active data stream and speculative state
     pub fn reset_active_stream(&mut self) {
+        self.epoch_chase_target = None;
         self.speculative_stream_state = None;
         self.active_data_stream = None;
     }
 }
+
+/// Logs (periodically) that storage is empty, i.e., that there's nothing
+/// to sync from until genesis has been committed.
+fn log_storage_empty() {
+    sample!(
+        SampleRate::Duration(Duration::from_secs(STORAGE_EMPTY_LOG_FREQ_SECS)),
+        info!("Storage is empty! Waiting for genesis before syncing.")
+    );
+}
@@ -2,43 +2,57 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    bootstrapper::Bootstrapper,
+    bootstrapper::{Bootstrapper, BootstrappingPhase},
     continuous_syncer::ContinuousSyncer,
-    driver_client::{ClientNotificationListener, DriverNotification},
+    driver_client::{
+        CheckProgressNowRequest, ClientNotificationListener, DriverNotification, DriverRequest,
+        GetContinuousSyncModeRequest, GetSyncStatusRequest, NotifyOnceBootstrappedRequest,
+        NotifyOnceSyncedToVersionRequest, PingRequest, RequestEnvelope,
+        SetContinuousSyncModeRequest, ShutdownRequest, SubscribeToCommitEventsRequest, SyncStatus,
+        SyncToVersionRequest, UnsubscribeFromBootstrapRequest, UnsubscribeFromSyncedVersionRequest,
+    },
     error::Error,
     logging::{LogEntry, LogSchema},
     metadata_storage::MetadataStorageInterface,
-    metrics,
-    metrics::ExecutingComponent,
+    metrics::{self, DriverMetrics, ExecutingComponent},
     notification_handlers::{
-        CommitNotification, CommitNotificationListener, CommittedTransactions,
-        ConsensusNotificationHandler, ErrorNotification, ErrorNotificationListener,
-        MempoolNotificationHandler,
+        ClientSyncRequestHandler, CommitNotification, CommitNotificationListener, CommitOrigin,
+        CommitStateTracker, CommitSubscriberRegistry, CommittedStateSnapshot,
+        CommittedStateValueChunk, CommittedTransactions, ConsensusCommitNotifier,
+        ConsensusNotificationHandler, ErrorNotification, ErrorNotificationListener, ErrorSource,
+        EventNotificationHandler, MempoolNotificationHandler, NotifiedVersionTracker,
+        TerminatedStreamTracker,
+    },
+    storage_synchronizer::{
+        BackpressureListener, FailedOperation, ShutdownSummary, StorageSynchronizerInterface,
     },
-    storage_synchronizer::StorageSynchronizerInterface,
     utils,
-    utils::PENDING_DATA_LOG_FREQ_SECS,
+    utils::{OutputFallbackHandler, PENDING_DATA_LOG_FREQ_SECS, STORAGE_EMPTY_LOG_FREQ_SECS},
+};
+use aptos_config::config::{
+    BootstrappingMode, ContinuousSyncingMode, RoleType, StateSyncDriverConfig,
 };
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
 use aptos_data_client::AptosDataClient;
-use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
-use aptos_types::waypoint::Waypoint;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, transaction::Version, waypoint::Waypoint,
+};
 use consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusSyncNotification,
 };
 use data_streaming_service::streaming_client::{DataStreamingClient, NotificationFeedback};
-use event_notifications::EventSubscriptionService;
-use futures::StreamExt;
+use fail::fail_point;
+use futures::{channel::oneshot, StreamExt};
 use mempool_notifications::MempoolNotificationSender;
-use std::{sync::Arc, time::SystemTime};
+use std::{sync::Arc, time::Instant};
 use storage_interface::DbReader;
-use tokio::task::yield_now;
-use tokio::time::{interval, Duration};
-use tokio_stream::wrappers::IntervalStream;
+use tokio::task::{spawn_blocking, yield_now, JoinHandle};
+use tokio::time::Duration;
 
 // Useful constants for the driver
 const DRIVER_ERROR_LOG_FREQ_SECS: u64 = 3;
+const STATE_SNAPSHOT_PROGRESS_LOG_FREQ_SECS: u64 = 5;
 
 /// The configuration of the state sync driver
 #[derive(Clone)]
@@ -46,23 +60,53 @@ pub struct DriverConfiguration {
     // The config file of the driver
     pub config: StateSyncDriverConfig,
 
+    // Whether the driver is a dry run (i.e., it verifies and executes data,
+    // but never commits it to storage).
+    pub dry_run: bool,
+
+    // The metrics of the driver instance (labelled by the driver instance, so
+    // that multiple drivers can run in the same process).
+    pub metrics: DriverMetrics,
+
     // The role of the node
     pub role: RoleType,
 
+    // Whether the driver runs inside a tooling binary (e.g., db-restore or
+    // replay), i.e., without consensus, mempool or event subscribers.
+    pub tooling: bool,
+
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
 }
 
 impl DriverConfiguration {
-    pub fn new(config: StateSyncDriverConfig, role: RoleType, waypoint: Waypoint) -> Self {
+    pub fn new(
+        config: StateSyncDriverConfig,
+        dry_run: bool,
+        metrics: DriverMetrics,
+        role: RoleType,
+        tooling: bool,
+        waypoint: Waypoint,
+    ) -> Self {
         Self {
             config,
+            dry_run,
+            metrics,
             role,
+            tooling,
             waypoint,
         }
     }
 }
 
+/// The action for the driver to take once a client request has been handled
+enum ClientRequestAction<Response> {
+    AwaitRespondAndShutdown(oneshot::Sender<Response>, JoinHandle<Response>), // Await the (blocking) task computing the response, respond and stop the driver
+    DeferResponse, // The handler holds the response sender (and will respond later)
+    DriveProgressAndRespond(oneshot::Sender<Response>, Response), // Drive progress, then respond
+    Respond(oneshot::Sender<Response>, Response), // Respond immediately
+}
+
 /// The state sync driver that drives synchronization progress
 pub struct StateSyncDriver<
     DataClient,
@@ -80,6 +124,20 @@ pub struct StateSyncDriver<
     // The listener for commit notifications
     commit_notification_listener: CommitNotificationListener,
 
+    // The tracker for the last commit (shared with the storage synchronizer
+    // and the driver client).
+    commit_state_tracker: CommitStateTracker,
+
+    // The registry of subscribers notified of new commits
+    commit_subscriber_registry: CommitSubscriberRegistry,
+
+    // The notifier for consensus commits handled after responding to consensus
+    // (only used if consensus commits are acknowledged early).
+    consensus_commit_notifier: Option<ConsensusCommitNotifier>,
+
+    // The handler for sync requests from driver clients (e.g., ops tooling)
+    client_sync_request_handler: ClientSyncRequestHandler,
+
     // The handler for notifications from consensus
     consensus_notification_handler: ConsensusNotificationHandler,
 
@@ -95,90 +153,159 @@ pub struct StateSyncDriver<
     // The listener for errors from the storage synchronizer
     error_notification_listener: ErrorNotificationListener,
 
-    // The event subscription service to notify listeners of on-chain events
-    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+    // The handler for notifying listeners of on-chain events
+    event_notification_handler: EventNotificationHandler,
+
+    // The label of the last error encountered by the driver (if any)
+    last_error_label: Option<&'static str>,
 
     // The handler for notifications to mempool
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
 
-    // The timestamp at which the driver started executing
-    start_time: Option<SystemTime>,
+    // The tracker for the highest version notified downstream (shared with
+    // the storage synchronizer to deduplicate commit notifications).
+    notified_version_tracker: NotifiedVersionTracker,
+
+    // The handler that falls back to applying outputs if execution keeps
+    // failing (shared with the bootstrapper and the continuous syncer).
+    output_fallback_handler: OutputFallbackHandler,
+
+    // The time at which the driver started executing
+    start_time: Option<Instant>,
 
     // The interface to read from storage
     storage: Arc<dyn DbReader>,
 
     // The storage synchronizer used to update local storage
     storage_synchronizer: StorageSyncer,
+
+    // The tracker for the last stream terminated due to an error notification
+    terminated_stream_tracker: TerminatedStreamTracker,
+
+    // The time service used for the driver's progress checks and timestamp bookkeeping
+    time_service: TimeService,
 }
 
 impl<
         DataClient: AptosDataClient + Send + Clone + 'static,
         MempoolNotifier: MempoolNotificationSender,
         MetadataStorage: MetadataStorageInterface + Clone,
-        StorageSyncer: StorageSynchronizerInterface + Clone,
+        StorageSyncer: StorageSynchronizerInterface + Clone + Send + 'static,
         StreamingClient: DataStreamingClient + Clone,
     >
     StateSyncDriver<DataClient, MempoolNotifier, MetadataStorage, StorageSyncer, StreamingClient>
 {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
+        backpressure_listener: BackpressureListener,
         client_notification_listener: ClientNotificationListener,
         commit_notification_listener: CommitNotificationListener,
+        commit_state_tracker: CommitStateTracker,
+        commit_subscriber_registry: CommitSubscriberRegistry,
         consensus_notification_handler: ConsensusNotificationHandler,
         driver_configuration: DriverConfiguration,
         error_notification_listener: ErrorNotificationListener,
-        event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+        event_notification_handler: EventNotificationHandler,
         mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
         metadata_storage: MetadataStorage,
+        notified_version_tracker: NotifiedVersionTracker,
         storage_synchronizer: StorageSyncer,
         aptos_data_client: DataClient,
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
+        time_service: TimeService,
     ) -> Self {
+        let output_fallback_handler = OutputFallbackHandler::new(
+            driver_configuration.config,
+            driver_configuration.metrics,
+            time_service.clone(),
+        );
         let bootstrapper = Bootstrapper::new(
+            backpressure_listener.clone(),
+            commit_state_tracker.clone(),
             driver_configuration.clone(),
             metadata_storage,
+            output_fallback_handler.clone(),
             streaming_client.clone(),
             storage.clone(),
             storage_synchronizer.clone(),
         );
         let continuous_syncer = ContinuousSyncer::new(
+            backpressure_listener,
+            commit_state_tracker.clone(),
             driver_configuration.clone(),
+            output_fallback_handler.clone(),
             streaming_client,
             storage.clone(),
             storage_synchronizer.clone(),
         );
+        let terminated_stream_tracker = TerminatedStreamTracker::new(driver_configuration.metrics);
 
         Self {
             bootstrapper,
             client_notification_listener,
+            client_sync_request_handler: ClientSyncRequestHandler::new(time_service.clone()),
             commit_notification_listener,
+            commit_state_tracker,
+            commit_subscriber_registry,
+            consensus_commit_notifier: None,
             consensus_notification_handler,
             continuous_syncer,
             aptos_data_client,
             driver_configuration,
             error_notification_listener,
-            event_subscription_service,
+            event_notification_handler,
+            last_error_label: None,
             mempool_notification_handler,
+            notified_version_tracker,
+            output_fallback_handler,
             start_time: None,
             storage,
             storage_synchronizer,
+            terminated_stream_tracker,
+            time_service,
         }
     }
 
     /// Starts the state sync driver
     pub async fn start_driver(mut self) {
-        let mut progress_check_interval = IntervalStream::new(interval(Duration::from_millis(
-            self.driver_configuration.config.progress_check_interval_ms,
-        )))
-        .fuse();
+        let mut progress_check_interval = self
+            .time_service
+            .interval(Duration::from_millis(
+                self.driver_configuration.config.progress_check_interval_ms,
+            ))
+            .fuse();
+
+        // Spawn the consensus commit notifier (if consensus commits are acknowledged early)
+        if self.driver_configuration.config.ack_consensus_commits_early {
+            self.consensus_commit_notifier = Some(ConsensusCommitNotifier::new(
+                self.driver_configuration.config,
+                self.storage.clone(),
+                self.mempool_notification_handler.clone(),
+                self.event_notification_handler.clone(),
+                self.commit_state_tracker.clone(),
+                self.commit_subscriber_registry.clone(),
+                self.notified_version_tracker.clone(),
+                self.driver_configuration.metrics,
+            ));
+        }
 
         // Start the driver
-        info!(LogSchema::new(LogEntry::Driver).message("Started the state sync v2 driver!"));
-        self.start_time = Some(SystemTime::now());
+        if self.driver_configuration.dry_run {
+            info!(LogSchema::new(LogEntry::Driver).message(
+                "Started the state sync v2 driver in dry-run mode! Data will be verified \
+                and executed, but never committed to storage."
+            ));
+        } else {
+            info!(LogSchema::new(LogEntry::Driver).message("Started the state sync v2 driver!"));
+        }
+        self.start_time = Some(self.time_service.now());
         loop {
             ::futures::select! {
                 notification = self.client_notification_listener.select_next_some() => {
-                    self.handle_client_notification(notification);
+                    if self.handle_client_notification(notification).await {
+                        return; // The driver has been shut down
+                    }
                 },
                 notification = self.commit_notification_listener.select_next_some() => {
                     self.handle_commit_notification(notification).await;
@@ -198,9 +325,20 @@ impl<
 
     /// Handles a notification sent by consensus
     async fn handle_consensus_notification(&mut self, notification: ConsensusNotification) {
-        // Verify the notification: full nodes shouldn't receive notifications
-        // and consensus should only send notifications after bootstrapping!
-        let result = if self.driver_configuration.role == RoleType::FullNode {
+        // Verify the notification: full nodes, dry-run and tooling drivers
+        // shouldn't receive notifications, and consensus should only send
+        // notifications after bootstrapping!
+        let result = if self.driver_configuration.dry_run {
+            Err(Error::DryRunConsensusNotification(format!(
+                "Received consensus notification: {:?}",
+                notification
+            )))
+        } else if self.driver_configuration.tooling {
+            Err(Error::ToolingConsensusNotification(format!(
+                "Received consensus notification: {:?}",
+                notification
+            )))
+        } else if self.driver_configuration.role == RoleType::FullNode {
             Err(Error::FullNodeConsensusNotification(format!(
                 "Received consensus notification: {:?}",
                 notification
@@ -236,6 +374,15 @@ impl<
             return;
         }
 
+        // If consensus is no longer waiting on the active sync request (e.g.,
+        // consensus restarted), clear the stale request before continuing.
+        if self
+            .consensus_notification_handler
+            .clear_stale_sync_request()
+        {
+            self.continuous_syncer.reset_active_stream();
+        }
+
         // Handle the notification
         let result = match notification {
             ConsensusNotification::NotifyCommit(commit_notification) => {
@@ -248,11 +395,21 @@ impl<
             }
         };
 
-        // Log any errors from notification handling
-        if let Err(error) = result {
-            error!(LogSchema::new(LogEntry::ConsensusNotification)
-                .error(&error)
-                .message("Error encountered when handling the consensus notification!"));
+        // Log any errors from notification handling. If consensus dropped
+        // the response receiver, the error is not fatal (consensus is no
+        // longer waiting on the response).
+        match result {
+            Err(error @ Error::ConsensusReceiverDropped(_)) => {
+                warn!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Consensus dropped the receiver for the notification response!"));
+            }
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Error encountered when handling the consensus notification!"));
+            }
+            Ok(()) => {}
         }
     }
 
@@ -272,27 +429,144 @@ impl<
 
         // TODO(joshlind): can we get consensus to forward the events?
 
-        // Handle the commit notification
-        let committed_transactions = CommittedTransactions {
-            events: consensus_commit_notification.reconfiguration_events.clone(),
-            transactions: consensus_commit_notification.transactions.clone(),
-        };
-        utils::handle_committed_transactions(
-            committed_transactions,
-            self.storage.clone(),
-            self.mempool_notification_handler.clone(),
-            self.event_subscription_service.clone(),
-        )
-        .await;
+        // Handle the commit notification. Consensus commits don't carry versions,
+        // but consensus has already committed the transactions to storage, so
+        // the committed transactions end at the latest synced version.
+        match utils::fetch_latest_synced_version(self.storage.clone()) {
+            Ok(Some(latest_synced_version)) => {
+                // Record the commit immediately (the downstream notifications
+                // might be deferred if consensus commits are acknowledged early).
+                self.record_consensus_commit(latest_synced_version);
+
+                let num_transactions = consensus_commit_notification.transactions.len() as u64;
+                let first_version =
+                    latest_synced_version.saturating_sub(num_transactions.saturating_sub(1));
+                let (reconfiguration_events, _) = utils::extract_reconfig_events(
+                    &consensus_commit_notification.reconfiguration_events,
+                );
+                let mut committed_transactions = CommittedTransactions::new(
+                    reconfiguration_events,
+                    consensus_commit_notification.transactions.clone(),
+                    first_version,
+                );
+                self.maybe_set_new_epoch_state(&mut committed_transactions);
+                self.notify_consensus_commit(committed_transactions).await;
+            }
+            Ok(None) => {
+                error!(LogSchema::new(LogEntry::ConsensusNotification).message(
+                    "Storage is empty! Failed to identify the versions of the consensus commit!"
+                ));
+            }
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Failed to identify the versions of the consensus commit!"));
+            }
+        }
 
         // Respond to consensus successfully
-        self.consensus_notification_handler
+        let response_result = self
+            .consensus_notification_handler
             .respond_to_commit_notification(consensus_commit_notification, Ok(()))
-            .await?;
+            .await;
 
         // Check the progress of any sync requests. We need this here because
         // consensus might issue a sync request and then commit (asynchronously).
-        self.check_sync_request_progress().await
+        // Note: this is done even if the response failed, to ensure the sync
+        // request state is handled identically in both cases.
+        let progress_result = self.check_sync_request_progress().await;
+        response_result.and(progress_result)
+    }
+
+    /// Records a consensus commit (ending at the given version) in the commit
+    /// state tracker and the committed version handle (consensus commits
+    /// bypass the storage synchronizer).
+    fn record_consensus_commit(&self, latest_synced_version: Version) {
+        let committed_version_handle = self.storage_synchronizer.committed_version_handle();
+        match utils::fetch_latest_synced_ledger_info(self.storage.clone()) {
+            Ok(latest_synced_ledger_info) => {
+                let latest_synced_epoch = latest_synced_ledger_info.ledger_info().epoch();
+                self.commit_state_tracker
+                    .update_commit_state(latest_synced_version, latest_synced_epoch);
+                committed_version_handle.update(latest_synced_version, latest_synced_epoch);
+            }
+            Err(error) => {
+                committed_version_handle.update(
+                    latest_synced_version,
+                    committed_version_handle.get_committed_epoch(),
+                );
+                error!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Failed to record the consensus commit!"));
+            }
+        }
+    }
+
+    /// Marks the committed transactions as ending the epoch if the consensus
+    /// commit ended the epoch (i.e., if the committed events contain a new
+    /// epoch event). The new epoch state is read from storage (consensus has
+    /// already committed the epoch change), but the commit is still marked if
+    /// the read fails.
+    fn maybe_set_new_epoch_state(&self, committed_transactions: &mut CommittedTransactions) {
+        let (_, contains_new_epoch_event) =
+            utils::extract_reconfig_events(&committed_transactions.events);
+        if !contains_new_epoch_event {
+            return;
+        }
+        committed_transactions.ends_epoch = true;
+        match utils::fetch_latest_epoch_state_from_storage(self.storage.clone()) {
+            Ok(new_epoch_state) => committed_transactions.set_new_epoch_state(new_epoch_state),
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Failed to fetch the new epoch state of the consensus commit!"));
+            }
+        }
+    }
+
+    /// Notifies mempool and the event subscription service of the transactions
+    /// committed by consensus. If consensus commits are acknowledged early, the
+    /// notifications are sent asynchronously (after responding to consensus),
+    /// unless the commit ends the epoch. Reconfigurations are always delivered
+    /// before responding to consensus.
+    async fn notify_consensus_commit(&mut self, committed_transactions: CommittedTransactions) {
+        if let Some(consensus_commit_notifier) = self.consensus_commit_notifier.as_mut() {
+            let wait_for_delivery = committed_transactions.ends_epoch;
+            if let Err(error) = consensus_commit_notifier
+                .notify_committed_transactions(committed_transactions, wait_for_delivery)
+                .await
+            {
+                error!(LogSchema::new(LogEntry::ConsensusNotification)
+                    .error(&error)
+                    .message("Failed to enqueue the consensus commit for notification!"));
+            }
+        } else {
+            let (first_version, last_version) = (
+                committed_transactions.first_version,
+                committed_transactions.last_version,
+            );
+            if let Err(error) = utils::handle_committed_transactions(
+                committed_transactions,
+                CommitOrigin::Consensus,
+                self.storage.clone(),
+                self.mempool_notification_handler.clone(),
+                self.event_notification_handler.clone(),
+                self.commit_state_tracker.clone(),
+                self.commit_subscriber_registry.clone(),
+                self.notified_version_tracker.clone(),
+                self.driver_configuration.metrics,
+            )
+            .await
+            {
+                utils::log_commit_notification_error(
+                    LogEntry::ConsensusNotification,
+                    CommitOrigin::Consensus,
+                    first_version,
+                    last_version,
+                    &error,
+                );
+            }
+        }
     }
 
     /// Updates the storage synchronizer metrics based on the consensus
@@ -302,7 +576,7 @@ impl<
         consensus_commit_notification: &ConsensusCommitNotification,
     ) {
         // Update the driver metrics
-        metrics::increment_counter(
+        self.driver_configuration.metrics.increment_counter(
             &metrics::DRIVER_COUNTERS,
             metrics::DRIVER_CONSENSUS_COMMIT_NOTIFICATION,
         );
@@ -313,19 +587,83 @@ impl<
             metrics::StorageSynchronizerOperations::Synced,
         ];
         for operation in operations {
-            metrics::increment_gauge(
+            self.driver_configuration.metrics.increment_gauge(
                 &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
                 operation.get_label(),
                 consensus_commit_notification.transactions.len() as u64,
             );
         }
+    }
 
-        // Update the synced epoch
-        if !consensus_commit_notification
-            .reconfiguration_events
-            .is_empty()
-        {
-            utils::update_new_epoch_metrics(self.storage.clone());
+    /// Updates the last commit metrics using the commit state tracker
+    fn update_commit_state_metrics(&self) {
+        if let Some(commit_state) = self.commit_state_tracker.get_commit_state() {
+            self.driver_configuration.metrics.set_gauge(
+                &metrics::LAST_COMMIT_STATE,
+                metrics::LAST_COMMIT_EPOCH,
+                commit_state.last_commit_epoch,
+            );
+            self.driver_configuration.metrics.set_gauge(
+                &metrics::LAST_COMMIT_STATE,
+                metrics::LAST_COMMIT_VERSION,
+                commit_state.last_commit_version,
+            );
+        }
+        if let Some(time_since_last_commit) = self.commit_state_tracker.time_since_last_commit() {
+            self.driver_configuration.metrics.set_gauge(
+                &metrics::LAST_COMMIT_STATE,
+                metrics::SECS_SINCE_LAST_COMMIT,
+                time_since_last_commit.as_secs(),
+            );
+        }
+    }
+
+    /// Updates the sync lag metric, i.e., the age of the latest synced ledger
+    /// info (according to the node's clock). Returns the sync lag (if known).
+    fn update_sync_lag_metric(&self) -> Option<Duration> {
+        let latest_synced_ledger_info =
+            match utils::fetch_latest_synced_ledger_info(self.storage.clone()) {
+                Ok(latest_synced_ledger_info) => latest_synced_ledger_info,
+                Err(error) => {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                        warn!(LogSchema::new(LogEntry::Driver)
+                            .error(&error)
+                            .message("Failed to fetch the latest synced ledger info!"))
+                    );
+                    return None;
+                }
+            };
+        let sync_lag =
+            utils::calculate_ledger_info_age(&latest_synced_ledger_info, &self.time_service)?;
+        self.driver_configuration.metrics.set_gauge(
+            &metrics::LAST_COMMIT_STATE,
+            metrics::SYNC_LAG_SECONDS,
+            sync_lag.as_secs(),
+        );
+        Some(sync_lag)
+    }
+
+    /// Updates the lowest available version metric (the version moves as
+    /// local storage is pruned).
+    fn update_lowest_available_version_metric(&self) {
+        match utils::fetch_lowest_available_version(self.storage.clone()) {
+            Ok(Some(lowest_available_version)) => {
+                self.driver_configuration.metrics.set_gauge(
+                    &metrics::LOCAL_STORAGE_VERSIONS,
+                    metrics::LOWEST_AVAILABLE_VERSION,
+                    lowest_available_version,
+                );
+            }
+            Ok(None) => {} // Storage is empty (there's nothing available yet)
+            Err(error) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                    warn!(LogSchema::new(LogEntry::Driver)
+                        .error(&error)
+                        .message("Failed to fetch the lowest available version!"))
+                );
+            }
         }
     }
 
@@ -334,102 +672,821 @@ impl<
         &mut self,
         sync_notification: ConsensusSyncNotification,
     ) -> Result<(), Error> {
-        let latest_synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
+        // Consensus sync requests can't overlap with client sync requests
+        if self.client_sync_request_handler.active_sync_request() {
+            let error = Error::ConflictingSyncRequest(format!(
+                "A client sync request is already active with target: {:?}",
+                self.client_sync_request_handler.get_sync_request_target()
+            ));
+            self.consensus_notification_handler
+                .respond_to_sync_notification(sync_notification, Err(error.clone()))
+                .await?;
+            return Err(error);
+        }
+
+        // Fetch the latest synced ledger info and epoch state. If this fails, we must still
+        // respond to consensus (otherwise the callback would be leaked).
+        let latest_synced_ledger_info =
+            match utils::fetch_latest_synced_ledger_info(self.storage.clone()) {
+                Ok(latest_synced_ledger_info) => latest_synced_ledger_info,
+                Err(error) => {
+                    self.consensus_notification_handler
+                        .respond_to_sync_notification(sync_notification, Err(error.clone()))
+                        .await?;
+                    return Err(error);
+                }
+            };
+        let latest_epoch_state =
+            match utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)
+            {
+                Ok(latest_epoch_state) => latest_epoch_state,
+                Err(error) => {
+                    self.consensus_notification_handler
+                        .respond_to_sync_notification(sync_notification, Err(error.clone()))
+                        .await?;
+                    return Err(error);
+                }
+            };
+        let sync_target_version = sync_notification.target.ledger_info().version();
         info!(
             LogSchema::new(LogEntry::ConsensusNotification).message(&format!(
             "Received a consensus sync notification! Target version: {:?}. Latest synced version: {:?}",
-            sync_notification.target, latest_synced_version,
+            sync_target_version, latest_synced_ledger_info.ledger_info().version(),
             ))
         );
-        metrics::increment_counter(
+        self.driver_configuration.metrics.increment_counter(
             &metrics::DRIVER_COUNTERS,
             metrics::DRIVER_CONSENSUS_SYNC_NOTIFICATION,
         );
 
+        // If an older sync request is still active, supersede it with the new
+        // request (and reset the stream that was syncing to the old target).
+        if self.active_sync_request() {
+            self.consensus_notification_handler
+                .supersede_sync_request(sync_target_version)
+                .await?;
+            self.continuous_syncer.reset_active_stream();
+        }
+
         // Initialize a new sync request
-        let latest_synced_ledger_info =
-            utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
         self.consensus_notification_handler
-            .initialize_sync_request(sync_notification, latest_synced_ledger_info)
+            .try_initialize_sync_request(
+                sync_notification,
+                latest_synced_ledger_info,
+                latest_epoch_state,
+            )
             .await
     }
 
-    /// Handles a client notification sent by the driver client
-    fn handle_client_notification(&mut self, notification: DriverNotification) {
-        debug!(LogSchema::new(LogEntry::ClientNotification)
-            .message("Received a notify bootstrap notification from the client!"));
-        metrics::increment_counter(
+    /// Returns the current sync status of the node
+    fn get_sync_status(&self) -> Result<SyncStatus, Error> {
+        // Fetch the latest synced version and epoch (both are unknown if
+        // storage is empty, i.e., before genesis).
+        let synced_version = utils::fetch_latest_synced_version(self.storage.clone())?;
+        let synced_epoch = match synced_version {
+            Some(_) => Some(
+                utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)?
+                    .epoch,
+            ),
+            None => None,
+        };
+        let lowest_available_version = utils::fetch_lowest_available_version(self.storage.clone())?;
+
+        // Estimate the lag using the highest data advertised by our peers
+        let global_data_summary = self.aptos_data_client.get_global_data_summary();
+        let advertised_version = global_data_summary
+            .advertised_data
+            .highest_synced_ledger_info()
+            .map(|ledger_info| ledger_info.ledger_info().version());
+        let lag_estimate =
+            utils::calculate_sync_lag(&global_data_summary, synced_version, synced_epoch)
+                .map(|sync_lag| sync_lag.version_lag);
+
+        // Fetch the target of any active sync request (the requests never overlap)
+        let active_sync_target = self
+            .client_sync_request_handler
+            .get_sync_request_target()
+            .or_else(|| {
+                self.consensus_notification_handler
+                    .get_sync_request_target()
+            })
+            .map(|sync_target| sync_target.ledger_info().version());
+
+        Ok(SyncStatus {
+            active_sync_target,
+            advertised_version,
+            bootstrapped: self.bootstrapper.is_bootstrapped(),
+            bootstrapping_phase: self.bootstrapper.get_bootstrapping_phase(),
+            continuous_syncing_mode: self.continuous_syncer.get_continuous_syncing_mode(),
+            lag_estimate,
+            last_error_label: self.last_error_label.map(|label| label.into()),
+            lowest_available_version,
+            role: self.driver_configuration.role,
+            synced_epoch,
+            synced_version,
+        })
+    }
+
+    /// Handles a client notification sent by the driver client. Returns
+    /// true iff the driver has been shut down (and should stop).
+    async fn handle_client_notification(&mut self, notification: DriverNotification) -> bool {
+        match notification {
+            DriverNotification::CheckProgressNow(envelope) => {
+                self.handle_client_request(envelope, Self::handle_check_progress_now_request)
+                    .await
+            }
+            DriverNotification::GetContinuousSyncMode(envelope) => {
+                self.handle_client_request(envelope, Self::handle_get_continuous_sync_mode_request)
+                    .await
+            }
+            DriverNotification::GetSyncStatus(envelope) => {
+                self.handle_client_request(envelope, Self::handle_get_sync_status_request)
+                    .await
+            }
+            DriverNotification::NotifyOnceBootstrapped(envelope) => {
+                self.handle_client_request(envelope, Self::handle_notify_once_bootstrapped_request)
+                    .await
+            }
+            DriverNotification::NotifyOnceSyncedToVersion(envelope) => {
+                self.handle_client_request(
+                    envelope,
+                    Self::handle_notify_once_synced_to_version_request,
+                )
+                .await
+            }
+            DriverNotification::Ping(envelope) => {
+                self.handle_client_request(envelope, Self::handle_ping_request)
+                    .await
+            }
+            DriverNotification::SetContinuousSyncMode(envelope) => {
+                self.handle_client_request(envelope, Self::handle_set_continuous_sync_mode_request)
+                    .await
+            }
+            DriverNotification::Shutdown(envelope) => {
+                self.handle_client_request(envelope, Self::handle_shutdown_request)
+                    .await
+            }
+            DriverNotification::SubscribeToCommitEvents(envelope) => {
+                self.handle_client_request(
+                    envelope,
+                    Self::handle_subscribe_to_commit_events_request,
+                )
+                .await
+            }
+            DriverNotification::SyncToVersion(envelope) => {
+                self.handle_client_request(envelope, Self::handle_sync_to_version_request)
+                    .await
+            }
+            DriverNotification::UnsubscribeFromBootstrap(envelope) => {
+                self.handle_client_request(
+                    envelope,
+                    Self::handle_unsubscribe_from_bootstrap_request,
+                )
+                .await
+            }
+            DriverNotification::UnsubscribeFromSyncedVersion(envelope) => {
+                self.handle_client_request(
+                    envelope,
+                    Self::handle_unsubscribe_from_synced_version_request,
+                )
+                .await
+            }
+        }
+    }
+
+    /// Handles the given client request using the specified request handler,
+    /// and performs the action returned by the handler (e.g., responding to
+    /// the client). Returns true iff the driver has been shut down.
+    async fn handle_client_request<Request: DriverRequest>(
+        &mut self,
+        envelope: RequestEnvelope<Request>,
+        request_handler: fn(
+            &mut Self,
+            Request,
+            oneshot::Sender<Request::Response>,
+        ) -> ClientRequestAction<Request::Response>,
+    ) -> bool {
+        self.driver_configuration.metrics.increment_counter(
             &metrics::DRIVER_COUNTERS,
             metrics::DRIVER_CLIENT_NOTIFICATION,
         );
 
-        // TODO(joshlind): refactor this if the client only supports one notification type!
-        // Extract the bootstrap notifier channel
-        let DriverNotification::NotifyOnceBootstrapped(notifier_channel) = notification;
+        // Handle the request
+        let RequestEnvelope {
+            request,
+            response_sender,
+        } = envelope;
+        let request_label = request.get_label();
+        let (response_sender, response, shutdown) =
+            match request_handler(self, request, response_sender) {
+                ClientRequestAction::AwaitRespondAndShutdown(response_sender, response_task) => {
+                    match response_task.await {
+                        Ok(response) => (response_sender, response, true),
+                        Err(error) => {
+                            // The client sees the dropped response channel
+                            error!(LogSchema::new(LogEntry::ClientNotification)
+                                .message(&format!("Failed to respond! Error: {:?}", error)));
+                            return true;
+                        }
+                    }
+                }
+                ClientRequestAction::DeferResponse => return false,
+                ClientRequestAction::DriveProgressAndRespond(response_sender, response) => {
+                    // Progress checks are only ever driven from the select
+                    // loop, so this never overlaps with another check.
+                    self.drive_progress().await;
+                    (response_sender, response, false)
+                }
+                ClientRequestAction::Respond(response_sender, response) => {
+                    (response_sender, response, false)
+                }
+            };
+
+        // Respond to the client (the client may have stopped waiting)
+        if response_sender.send(response).is_err() {
+            debug!(
+                LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                    "The client dropped the response channel for the {:?} request!",
+                    request_label
+                ))
+            );
+        }
+        shutdown
+    }
+
+    /// Handles a request to check (and drive) progress immediately
+    /// (instead of waiting for the next progress check interval).
+    fn handle_check_progress_now_request(
+        &mut self,
+        _request: CheckProgressNowRequest,
+        response_sender: oneshot::Sender<()>,
+    ) -> ClientRequestAction<()> {
+        debug!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a progress check request from the client!"));
+        ClientRequestAction::DriveProgressAndRespond(response_sender, ())
+    }
+
+    /// Handles a request for the current continuous syncing mode
+    fn handle_get_continuous_sync_mode_request(
+        &mut self,
+        _request: GetContinuousSyncModeRequest,
+        response_sender: oneshot::Sender<ContinuousSyncingMode>,
+    ) -> ClientRequestAction<ContinuousSyncingMode> {
+        debug!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a continuous syncing mode request from the client!"));
+        ClientRequestAction::Respond(
+            response_sender,
+            self.continuous_syncer.get_continuous_syncing_mode(),
+        )
+    }
+
+    /// Handles a request for the current sync status
+    fn handle_get_sync_status_request(
+        &mut self,
+        _request: GetSyncStatusRequest,
+        response_sender: oneshot::Sender<Result<SyncStatus, Error>>,
+    ) -> ClientRequestAction<Result<SyncStatus, Error>> {
+        debug!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a sync status request from the client!"));
+        ClientRequestAction::Respond(response_sender, self.get_sync_status())
+    }
+
+    /// Handles a request to be notified once the node has bootstrapped. The
+    /// response is sent by the bootstrapper (once bootstrapping completes).
+    fn handle_notify_once_bootstrapped_request(
+        &mut self,
+        request: NotifyOnceBootstrappedRequest,
+        response_sender: oneshot::Sender<Result<(), Error>>,
+    ) -> ClientRequestAction<Result<(), Error>> {
+        debug!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a notify bootstrap notification from the client!"));
 
         // Subscribe the bootstrap notifier channel
         if let Err(error) = self
             .bootstrapper
-            .subscribe_to_bootstrap_notifications(notifier_channel)
+            .subscribe_to_bootstrap_notifications(request.subscription_id, response_sender)
         {
             error!(LogSchema::new(LogEntry::ClientNotification)
                 .error(&error)
                 .message("Failed to subscribe to bootstrap notifications!"));
         }
+        ClientRequestAction::DeferResponse
     }
 
-    /// Handles a commit notification sent by the storage synchronizer for a
-    /// new state snapshot.
+    /// Handles a request to be notified once the node has synced to a
+    /// version. The response is sent once the latest synced ledger info
+    /// covers the version (which might be immediately).
+    fn handle_notify_once_synced_to_version_request(
+        &mut self,
+        request: NotifyOnceSyncedToVersionRequest,
+        response_sender: oneshot::Sender<Result<LedgerInfoWithSignatures, Error>>,
+    ) -> ClientRequestAction<Result<LedgerInfoWithSignatures, Error>> {
+        debug!(
+            LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                "Received a synced version notification request from the client! Version: {:?}",
+                request.version
+            ))
+        );
+
+        // Add the waiter and notify it immediately (if the version has been
+        // synced). Otherwise, the waiter is notified after the next commit.
+        self.commit_subscriber_registry.add_synced_version_waiter(
+            request.subscription_id,
+            request.version,
+            response_sender,
+        );
+        self.notify_synced_version_waiters();
+        ClientRequestAction::DeferResponse
+    }
+
+    /// Handles a liveness check from the client
+    fn handle_ping_request(
+        &mut self,
+        _request: PingRequest,
+        response_sender: oneshot::Sender<()>,
+    ) -> ClientRequestAction<()> {
+        trace!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a ping from the client!"));
+        ClientRequestAction::Respond(response_sender, ())
+    }
+
+    /// Handles a request to switch the continuous syncing mode. The response
+    /// is the previous mode (or an error, if the switch is rejected).
+    fn handle_set_continuous_sync_mode_request(
+        &mut self,
+        request: SetContinuousSyncModeRequest,
+        response_sender: oneshot::Sender<Result<ContinuousSyncingMode, Error>>,
+    ) -> ClientRequestAction<Result<ContinuousSyncingMode, Error>> {
+        info!(
+            LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                "Received a continuous syncing mode switch from the client! New mode: {:?}",
+                request.mode
+            ))
+        );
+
+        // Switch the mode of the continuous syncer
+        let result = self.set_continuous_syncing_mode(request.mode);
+        if let Err(error) = &result {
+            warn!(LogSchema::new(LogEntry::ClientNotification)
+                .error(error)
+                .message("Rejected the continuous syncing mode switch from the client!"));
+        }
+        ClientRequestAction::Respond(response_sender, result)
+    }
+
+    /// Handles a request to shut down the driver
+    fn handle_shutdown_request(
+        &mut self,
+        _request: ShutdownRequest,
+        response_sender: oneshot::Sender<ShutdownSummary>,
+    ) -> ClientRequestAction<ShutdownSummary> {
+        info!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a shutdown notification from the client!"));
+
+        // Drain the storage synchronizer on a blocking task (the drain can take
+        // up to `max_shutdown_drain_ms`) and notify the client once it completes.
+        let storage_synchronizer = self.storage_synchronizer.clone();
+        let drain_task = spawn_blocking(move || storage_synchronizer.shutdown());
+        ClientRequestAction::AwaitRespondAndShutdown(response_sender, drain_task)
+    }
+
+    /// Handles a request to subscribe to commit events
+    fn handle_subscribe_to_commit_events_request(
+        &mut self,
+        request: SubscribeToCommitEventsRequest,
+        response_sender: oneshot::Sender<()>,
+    ) -> ClientRequestAction<()> {
+        debug!(LogSchema::new(LogEntry::ClientNotification)
+            .message("Received a commit event subscription from the client!"));
+        self.commit_subscriber_registry
+            .subscribe_to_commit_events(request.commit_event_sender);
+        ClientRequestAction::Respond(response_sender, ())
+    }
+
+    /// Handles a request to sync to a target version. The response is sent
+    /// once the request completes (or immediately, if it is rejected).
+    fn handle_sync_to_version_request(
+        &mut self,
+        request: SyncToVersionRequest,
+        response_sender: oneshot::Sender<Result<(), Error>>,
+    ) -> ClientRequestAction<Result<(), Error>> {
+        info!(
+            LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                "Received a sync request from the client! Target version: {:?}",
+                request.target_version
+            ))
+        );
+
+        // Initialize the client sync request
+        if let Err(error) = self.handle_client_sync_request(
+            request.target_version,
+            request.target_ledger_info,
+            response_sender,
+        ) {
+            warn!(LogSchema::new(LogEntry::ClientNotification)
+                .error(&error)
+                .message("Rejected the sync request from the client!"));
+        }
+        ClientRequestAction::DeferResponse
+    }
+
+    /// Handles a request to remove a bootstrap notification subscription
+    /// (e.g., because the client stopped waiting).
+    fn handle_unsubscribe_from_bootstrap_request(
+        &mut self,
+        request: UnsubscribeFromBootstrapRequest,
+        response_sender: oneshot::Sender<bool>,
+    ) -> ClientRequestAction<bool> {
+        let removed = self
+            .bootstrapper
+            .unsubscribe_from_bootstrap_notifications(request.subscription_id);
+        debug!(
+            LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                "Received a bootstrap unsubscribe notification from the client! \
+                    Subscription id: {:?}, removed: {:?}",
+                request.subscription_id, removed
+            ))
+        );
+        ClientRequestAction::Respond(response_sender, removed)
+    }
+
+    /// Handles a request to remove a synced version waiter (e.g., because the
+    /// client timed out).
+    fn handle_unsubscribe_from_synced_version_request(
+        &mut self,
+        request: UnsubscribeFromSyncedVersionRequest,
+        response_sender: oneshot::Sender<bool>,
+    ) -> ClientRequestAction<bool> {
+        let removed = self
+            .commit_subscriber_registry
+            .remove_synced_version_waiter(request.subscription_id);
+        debug!(
+            LogSchema::new(LogEntry::ClientNotification).message(&format!(
+                "Received a synced version unsubscribe notification from the client! \
+                    Subscription id: {:?}, removed: {:?}",
+                request.subscription_id, removed
+            ))
+        );
+        ClientRequestAction::Respond(response_sender, removed)
+    }
+
+    /// Switches the continuous syncing mode and returns the previous mode.
+    /// The switch is rejected while a consensus sync request is active (as
+    /// the stream syncing to the target would be aborted), and while the
+    /// node is syncing a state snapshot.
+    fn set_continuous_syncing_mode(
+        &mut self,
+        continuous_syncing_mode: ContinuousSyncingMode,
+    ) -> Result<ContinuousSyncingMode, Error> {
+        if self.active_sync_request() {
+            return Err(Error::ModeSwitchRejected(
+                "A consensus sync request is currently active!".into(),
+            ));
+        }
+        if self.bootstrapper.get_bootstrapping_phase() == BootstrappingPhase::SyncingStates {
+            return Err(Error::ModeSwitchRejected(
+                "The node is currently syncing a state snapshot!".into(),
+            ));
+        }
+        Ok(self
+            .continuous_syncer
+            .set_continuous_syncing_mode(continuous_syncing_mode))
+    }
+
+    /// Notifies all synced version waiters whose versions are covered by the
+    /// latest synced ledger info (in storage).
+    fn notify_synced_version_waiters(&self) {
+        if self.commit_subscriber_registry.num_synced_version_waiters() == 0 {
+            return; // There's nothing to notify
+        }
+
+        match utils::fetch_latest_synced_ledger_info(self.storage.clone()) {
+            Ok(latest_synced_ledger_info) => {
+                self.commit_subscriber_registry
+                    .notify_synced_version_waiters(&latest_synced_ledger_info);
+            }
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::ClientNotification)
+                    .error(&error)
+                    .message("Failed to fetch the latest synced ledger info for version waiters!"));
+            }
+        }
+    }
+
+    /// Handles a sync request sent by the driver client (i.e., to sync to the
+    /// given target version and then stop). Requests are rejected (and answered)
+    /// if the node hasn't bootstrapped, if consensus is executing, or if the
+    /// request overlaps with an active consensus sync request.
+    fn handle_client_sync_request(
+        &mut self,
+        target_version: Version,
+        target_ledger_info: Option<LedgerInfoWithSignatures>,
+        callback: oneshot::Sender<Result<(), Error>>,
+    ) -> Result<(), Error> {
+        // Verify the node is able to handle the request
+        let result = if !self.bootstrapper.is_bootstrapped() {
+            Err(Error::BootstrapNotComplete(format!(
+                "Received a client sync request for version: {:?}",
+                target_version
+            )))
+        } else if self.check_if_consensus_executing() {
+            Err(Error::ConsensusIsExecuting(format!(
+                "Received a client sync request for version: {:?}",
+                target_version
+            )))
+        } else if self.active_sync_request() {
+            Err(Error::ConflictingSyncRequest(format!(
+                "A consensus sync request is already active with target: {:?}",
+                self.consensus_notification_handler
+                    .get_sync_request_target()
+            )))
+        } else {
+            Ok(())
+        };
+        if let Err(error) = result {
+            let _ = callback.send(Err(error.clone()));
+            return Err(error);
+        }
+
+        // Fetch the latest synced ledger info and epoch state
+        let latest_state = utils::fetch_latest_synced_ledger_info(self.storage.clone()).and_then(
+            |latest_synced_ledger_info| {
+                utils::fetch_latest_epoch_state(self.storage.clone(), &self.commit_state_tracker)
+                    .map(|latest_epoch_state| (latest_synced_ledger_info, latest_epoch_state))
+            },
+        );
+        let (latest_synced_ledger_info, latest_epoch_state) = match latest_state {
+            Ok(latest_state) => latest_state,
+            Err(error) => {
+                let _ = callback.send(Err(error.clone()));
+                return Err(error);
+            }
+        };
+
+        // Initialize the sync request and reset the active stream (so that
+        // the next stream is bounded by the sync target).
+        self.client_sync_request_handler
+            .try_initialize_sync_request(
+                target_version,
+                target_ledger_info,
+                callback,
+                latest_synced_ledger_info,
+                latest_epoch_state,
+            )?;
+        self.continuous_syncer.reset_active_stream();
+        Ok(())
+    }
+
+    /// Handles a commit notification sent by the storage synchronizer
     async fn handle_commit_notification(&mut self, commit_notification: CommitNotification) {
-        let CommitNotification::CommittedStateSnapshot(committed_snapshot) = commit_notification;
+        match commit_notification {
+            CommitNotification::CommittedStateSnapshot(committed_snapshot) => {
+                self.handle_committed_state_snapshot(committed_snapshot)
+                    .await
+            }
+            CommitNotification::CommittedStateValueChunk(committed_chunk) => {
+                self.handle_committed_state_value_chunk(committed_chunk)
+            }
+        }
+    }
+
+    /// Handles a commit notification for a state value chunk written while
+    /// syncing a state snapshot (i.e., before all states have been synced).
+    fn handle_committed_state_value_chunk(&self, committed_chunk: CommittedStateValueChunk) {
+        // Update the snapshot progress metrics
+        self.driver_configuration.metrics.observe_state_value_chunk(
+            committed_chunk.num_state_values,
+            committed_chunk.num_bytes,
+            committed_chunk.apply_duration,
+            committed_chunk.verification_duration,
+        );
+        debug!(
+            LogSchema::new(LogEntry::SynchronizerNotification).message(&format!(
+                "Verified the proof of the state value chunk ending at index {:?} in {:?}. \
+                Proven root hash: {:?}",
+                committed_chunk.last_committed_state_index,
+                committed_chunk.verification_duration,
+                committed_chunk.proven_root_hash,
+            ))
+        );
+
+        // Log the snapshot progress
+        sample!(
+            SampleRate::Duration(Duration::from_secs(STATE_SNAPSHOT_PROGRESS_LOG_FREQ_SECS)),
+            info!(
+                LogSchema::new(LogEntry::SynchronizerNotification).message(&format!(
+                    "State snapshot sync progress at version: {:?}. Last committed index: {:?}. \
+                        Latest chunk: {:?} state values, {:?} bytes, applied in {:?}.",
+                    committed_chunk.version,
+                    committed_chunk.last_committed_state_index,
+                    committed_chunk.num_state_values,
+                    committed_chunk.num_bytes,
+                    committed_chunk.apply_duration,
+                ))
+            )
+        );
+    }
+
+    /// Handles a commit notification sent by the storage synchronizer for a
+    /// new state snapshot (i.e., once all states have been synced).
+    async fn handle_committed_state_snapshot(
+        &mut self,
+        committed_snapshot: CommittedStateSnapshot,
+    ) {
         info!(
             LogSchema::new(LogEntry::SynchronizerNotification).message(&format!(
                 "Received a state snapshot commit notification from the storage synchronizer. \
-                        Snapshot version: {:?}. Last committed index: {:?}.",
-                committed_snapshot.version, committed_snapshot.last_committed_state_index,
+                        Snapshot version: {:?}. Last committed index: {:?}. \
+                        Committed transaction versions: {:?} to {:?}.",
+                committed_snapshot.version,
+                committed_snapshot.last_committed_state_index,
+                committed_snapshot.committed_transaction.first_version,
+                committed_snapshot.committed_transaction.last_version,
             ))
         );
 
+        // The snapshot may have skipped any number of epochs (without an epoch
+        // ending commit), so invalidate the cached epoch state if it's now stale.
+        match utils::fetch_latest_synced_ledger_info(self.storage.clone()) {
+            Ok(latest_synced_ledger_info) => {
+                let latest_epoch = latest_synced_ledger_info.ledger_info().next_block_epoch();
+                self.commit_state_tracker
+                    .invalidate_epoch_state(latest_epoch);
+            }
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::SynchronizerNotification)
+                    .error(&error)
+                    .message("Failed to fetch the latest synced ledger info for the snapshot!"));
+            }
+        }
+
         // Handle the committed transactions and events
-        utils::handle_committed_transactions(
-            committed_snapshot.committed_transaction,
+        let committed_transactions = committed_snapshot.committed_transaction;
+        let (first_version, last_version) = (
+            committed_transactions.first_version,
+            committed_transactions.last_version,
+        );
+        if let Err(error) = utils::handle_committed_transactions(
+            committed_transactions,
+            CommitOrigin::StateSync,
             self.storage.clone(),
             self.mempool_notification_handler.clone(),
-            self.event_subscription_service.clone(),
+            self.event_notification_handler.clone(),
+            self.commit_state_tracker.clone(),
+            self.commit_subscriber_registry.clone(),
+            self.notified_version_tracker.clone(),
+            self.driver_configuration.metrics,
         )
-        .await;
+        .await
+        {
+            utils::log_commit_notification_error(
+                LogEntry::SynchronizerNotification,
+                CommitOrigin::StateSync,
+                first_version,
+                last_version,
+                &error,
+            );
+        }
+    }
+
+    /// Records the failed chunk with the output fallback handler if it failed
+    /// execution (i.e., the component that owns the stream executes transactions).
+    fn maybe_record_execution_failure(&self, error_notification: &ErrorNotification) {
+        if error_notification.failed_operation != FailedOperation::ExecuteChunk {
+            return;
+        }
+        let executing_transactions = match error_notification.component {
+            ErrorSource::Bootstrapper => {
+                self.bootstrapper.get_bootstrapping_mode()
+                    == BootstrappingMode::ExecuteTransactionsFromGenesis
+            }
+            ErrorSource::ContinuousSyncer => {
+                self.continuous_syncer.get_continuous_syncing_mode()
+                    == ContinuousSyncingMode::ExecuteTransactions
+            }
+            ErrorSource::StorageSynchronizer => false,
+        };
+        if !executing_transactions {
+            return;
+        }
+        if let Some(version_range) = error_notification.version_range {
+            self.output_fallback_handler
+                .record_execution_failure(version_range);
+        }
     }
 
     /// Handles an error notification sent by the storage synchronizer
     async fn handle_error_notification(&mut self, error_notification: ErrorNotification) {
+        // Ignore the notification if it references an already-terminated stream
+        // (e.g., a single failing chunk can trigger several error notifications).
+        if self
+            .terminated_stream_tracker
+            .is_already_terminated(&error_notification)
+        {
+            debug!(LogSchema::new(LogEntry::SynchronizerNotification)
+                .error_notification(error_notification)
+                .message("Ignoring an error notification for an already-terminated stream!"));
+            self.driver_configuration.metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::DUPLICATE_ERROR_NOTIFICATION,
+            );
+            return;
+        }
+
+        // Transient errors have already been handled by the storage synchronizer
+        // (e.g., the data was still committed), so the stream can be left intact.
+        if !error_notification.severity.terminates_stream() {
+            warn!(LogSchema::new(LogEntry::SynchronizerNotification)
+                .error_notification(error_notification)
+                .message("Received a transient error notification from the storage synchronizer!"));
+            self.driver_configuration.metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::TRANSIENT_ERROR_NOTIFICATION,
+            );
+            return;
+        }
+        self.last_error_label = Some(error_notification.error.get_label());
         error!(LogSchema::new(LogEntry::SynchronizerNotification)
             .error_notification(error_notification.clone())
-            .message("Received an error notification from the storage synchronizer!"));
+            .message(&format!(
+                "Received an error notification from the storage synchronizer! \
+                Failed stage: {:?}, failed versions: {:?}",
+                error_notification.stage, error_notification.version_range
+            )));
+
+        // Record any execution failure (repeated failures of the same chunk
+        // trigger the fallback to applying transaction outputs).
+        self.maybe_record_execution_failure(&error_notification);
 
-        // Terminate the currently active streams
+        // Terminate the active stream of the component that owns the failed stream.
+        // Note: the component will ignore the termination if it no longer has an
+        // active stream (e.g., if bootstrapping completed in the meantime).
+        // Any data from the stream that is still queued in the storage synchronizer
+        // pipeline is discarded (but in-progress commits will complete), so the
+        // component waits for the pipeline to drain before computing the next
+        // version to request from a new stream.
         let notification_id = error_notification.notification_id;
-        let notification_feedback = NotificationFeedback::InvalidPayloadData;
-        if self.bootstrapper.is_bootstrapped() {
-            if let Err(error) = self
-                .continuous_syncer
-                .terminate_active_stream(notification_id, notification_feedback)
-                .await
-            {
-                panic!(
-                    "Failed to terminate the active stream for the continuous syncer! Error: {:?}",
-                    error
+        let notification_feedback = match error_notification.error {
+            Error::VerificationError(_) => NotificationFeedback::PayloadProofFailed,
+            _ => NotificationFeedback::InvalidPayloadData,
+        };
+        let result = match error_notification.component {
+            ErrorSource::Bootstrapper => {
+                self.storage_synchronizer.notify_stream_reset();
+                self.storage_synchronizer.reset_pending_data();
+                self.bootstrapper
+                    .terminate_active_stream(notification_id, notification_feedback)
+                    .await
+            }
+            ErrorSource::ContinuousSyncer => {
+                self.storage_synchronizer.notify_stream_reset();
+                self.storage_synchronizer.reset_pending_data();
+                self.continuous_syncer
+                    .terminate_active_stream(notification_id, notification_feedback)
+                    .await
+            }
+            ErrorSource::StorageSynchronizer => {
+                warn!(
+                    LogSchema::new(LogEntry::SynchronizerNotification).message(&format!(
+                        "Ignoring a storage synchronizer error without a stream owner! \
+                        Notification ID: {:?}",
+                        notification_id
+                    ))
                 );
+                return;
             }
-        } else if let Err(error) = self
-            .bootstrapper
-            .terminate_active_stream(notification_id, notification_feedback)
-            .await
-        {
-            panic!(
-                "Failed to terminate the active stream for the bootstrapper! Error: {:?}",
-                error
-            );
         };
+        self.terminated_stream_tracker
+            .mark_terminated(&error_notification);
+
+        // Log any termination failures (the stream has been reset regardless)
+        if let Err(error) = result {
+            error!(LogSchema::new(LogEntry::SynchronizerNotification)
+                .error(&error)
+                .message(&format!(
+                    "Failed to terminate the active stream for the {:?}! Notification ID: {:?}, \
+                    failed versions: {:?}",
+                    error_notification.component, notification_id, error_notification.version_range
+                )));
+        }
+    }
+
+    /// Waits until the storage synchronizer has handled all pending data. The
+    /// wait is driven by the pending data notifications of the synchronizer
+    /// (if the synchronizer doesn't send notifications, this yields between
+    /// checks instead, so that the storage synchronizer threads aren't starved).
+    async fn wait_for_storage_synchronizer_idle(&self) {
+        // Subscribe before checking, so that no notification can be missed
+        let mut pending_data_listener = self.storage_synchronizer.subscribe_to_pending_data();
+        while !self.storage_synchronizer.is_idle() {
+            sample!(
+                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+                info!("Waiting for the storage synchronizer to handle pending data!")
+            );
+            if pending_data_listener.changed().await.is_err() {
+                yield_now().await; // The synchronizer doesn't send notifications
+            }
+        }
     }
 
     /// Checks if the node has successfully reached the sync target
@@ -441,31 +1498,62 @@ impl<
         // There's an active sync request. Before checking if we've hit the target,
         // wait for the storage synchronizer to drain first (to avoid preemptively
         // notifying consensus).
-        while self.storage_synchronizer.pending_storage_data() {
-            sample!(
-                SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
-                info!("Waiting for the storage synchronizer to handle pending data!")
-            );
-
-            // We must yield to avoid spin locking so that the storage synchronizer
-            // threads are not starved.
-            yield_now().await;
-        }
+        self.wait_for_storage_synchronizer_idle().await;
 
-        // Check if we've hit the target
-        let latest_synced_ledger_info =
-            utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
-        self.consensus_notification_handler
-            .check_sync_request_progress(latest_synced_ledger_info)
-            .await?;
+        // Check if we've hit the target (or if the request has timed out).
+        // The committed version handle avoids reading storage on every check.
+        let latest_committed_version = self
+            .storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version();
+        let result = self
+            .consensus_notification_handler
+            .check_sync_request_progress(latest_committed_version)
+            .await;
 
-        // If the sync request was successfully handled, reset the continuous syncer
+        // If the sync request was answered, reset the continuous syncer
         // so that in the event another sync request occurs, we have a fresh state.
+        // We also flush any batched mempool notifications (consensus is now in
+        // control and latency matters).
         if !self.active_sync_request() {
             self.continuous_syncer.reset_active_stream();
             self.storage_synchronizer.finish_chunk_executor(); // Consensus is now in control
+            self.mempool_notification_handler
+                .flush_batched_notifications()
+                .await?;
         }
-        Ok(())
+        result
+    }
+
+    /// Checks if the node has successfully reached the target of the client
+    /// sync request (if any). Once the target is reached, the node halts.
+    async fn check_client_sync_request_progress(&mut self) -> Result<(), Error> {
+        // If the client is no longer waiting on the request, clear it
+        if self.client_sync_request_handler.clear_stale_sync_request() {
+            self.continuous_syncer.reset_active_stream();
+        }
+        if !self.client_sync_request_handler.active_sync_request() {
+            return Ok(());
+        }
+
+        // Wait for the storage synchronizer to drain (so that the target is
+        // only reached once all pending data has been durably committed).
+        self.wait_for_storage_synchronizer_idle().await;
+
+        // Check if we've hit the target
+        let latest_committed_version = self
+            .storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version();
+        let result = self
+            .client_sync_request_handler
+            .check_sync_request_progress(latest_committed_version);
+
+        // If the sync request was answered, reset the continuous syncer
+        if !self.client_sync_request_handler.active_sync_request() {
+            self.continuous_syncer.reset_active_stream();
+        }
+        result
     }
 
     /// Returns true iff there's an active sync request from consensus
@@ -478,9 +1566,15 @@ impl<
         self.driver_configuration.role == RoleType::Validator
     }
 
-    /// Returns true iff consensus is currently executing
+    /// Returns true iff consensus is currently executing. Consensus never
+    /// executes alongside a dry-run or tooling driver (its notifications are
+    /// rejected).
     fn check_if_consensus_executing(&self) -> bool {
-        self.is_validator() && self.bootstrapper.is_bootstrapped() && !self.active_sync_request()
+        !self.driver_configuration.dry_run
+            && !self.driver_configuration.tooling
+            && self.is_validator()
+            && self.bootstrapper.is_bootstrapped()
+            && !self.active_sync_request()
     }
 
     /// Checks if the connection deadline has passed. If so, validators with
@@ -498,18 +1592,8 @@ impl<
                         .config
                         .max_connection_deadline_secs,
                 )) {
-                    if SystemTime::now()
-                        .duration_since(connection_deadline)
-                        .is_ok()
-                    {
-                        info!(LogSchema::new(LogEntry::AutoBootstrapping).message(
-                            "Passed the connection deadline! Auto-bootstrapping the validator!"
-                        ));
-                        if let Err(error) = self.bootstrapper.bootstrapping_complete() {
-                            error!(LogSchema::new(LogEntry::AutoBootstrapping)
-                                .error(&error)
-                                .message("Failed to mark bootstrapping as complete!"));
-                        }
+                    if self.time_service.now() >= connection_deadline {
+                        self.auto_bootstrap_validator();
                     }
                 } else {
                     error!(LogSchema::new(LogEntry::AutoBootstrapping)
@@ -519,8 +1603,70 @@ impl<
         }
     }
 
+    /// Auto-bootstraps the validator (once the connection deadline has passed).
+    /// If storage is empty (i.e., genesis hasn't been committed), there's
+    /// nothing to bootstrap from yet, so the validator waits for genesis.
+    fn auto_bootstrap_validator(&mut self) {
+        match utils::fetch_latest_synced_version(self.storage.clone()) {
+            Ok(Some(_)) => {
+                info!(LogSchema::new(LogEntry::AutoBootstrapping)
+                    .message("Passed the connection deadline! Auto-bootstrapping the validator!"));
+                if let Err(error) = self.bootstrapper.bootstrapping_complete() {
+                    error!(LogSchema::new(LogEntry::AutoBootstrapping)
+                        .error(&error)
+                        .message("Failed to mark bootstrapping as complete!"));
+                }
+            }
+            Ok(None) => {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(STORAGE_EMPTY_LOG_FREQ_SECS)),
+                    info!(LogSchema::new(LogEntry::AutoBootstrapping).message(
+                        "Passed the connection deadline, but storage is empty! \
+                        Waiting for genesis before auto-bootstrapping the validator."
+                    ))
+                );
+            }
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::AutoBootstrapping)
+                    .error(&error)
+                    .message(
+                        "Failed to fetch the latest synced version! Unable to auto-bootstrap!"
+                    ));
+            }
+        }
+    }
+
     /// Checks that state sync is making progress
     async fn drive_progress(&mut self) {
+        // Failpoint "state_sync::drive_progress": skips the progress check
+        // (e.g., to simulate a stalled driver).
+        fail_point!("state_sync::drive_progress", |_| ());
+
+        // Update the last commit, sync lag and local storage metrics
+        self.update_commit_state_metrics();
+        let sync_lag = self.update_sync_lag_metric();
+        self.update_lowest_available_version_metric();
+
+        // Notify any synced version waiters (in case a commit was missed)
+        self.notify_synced_version_waiters();
+
+        // Log the data pending in the storage synchronizer
+        let pending_storage_data = self.storage_synchronizer.pending_storage_data();
+        sample!(
+            SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+            info!(LogSchema::new(LogEntry::Driver).message(&format!(
+                "Storage synchronizer progress. Chunks queued for execution: {:?}, \
+                    executing: {:?}, queued for commit: {:?}, committing: {:?}. Bytes queued: {:?}. \
+                    Sync lag (secs): {:?}",
+                pending_storage_data.chunks_queued_for_execution,
+                pending_storage_data.chunks_executing,
+                pending_storage_data.chunks_queued_for_commit,
+                pending_storage_data.chunks_committing,
+                pending_storage_data.bytes_queued,
+                sync_lag.map(|sync_lag| sync_lag.as_secs())
+            )))
+        );
+
         // Fetch the global data summary and verify we have active peers
         let global_data_summary = self.aptos_data_client.get_global_data_summary();
         if global_data_summary.is_empty() {
@@ -536,45 +1682,100 @@ impl<
                 .error(&error)
                 .message("Error found when checking the sync request progress!"));
         }
+        if let Err(error) = self.check_client_sync_request_progress().await {
+            error!(LogSchema::new(LogEntry::Driver)
+                .error(&error)
+                .message("Error found when checking the client sync request progress!"));
+        }
 
         // If consensus is executing, there's nothing to do
         if self.check_if_consensus_executing() {
             trace!(LogSchema::new(LogEntry::Driver)
                 .message("Consensus is executing. There's nothing to do."));
-            metrics::increment_counter(
+            self.driver_configuration.metrics.increment_counter(
                 &metrics::EXECUTING_COMPONENT,
                 ExecutingComponent::Consensus.get_label(),
             );
             return;
         }
 
+        // If the node has been halted by a client sync request, there's nothing to do
+        if !self.client_sync_request_handler.active_sync_request() {
+            if let Some(halted_version) = self.client_sync_request_handler.get_halted_version() {
+                trace!(LogSchema::new(LogEntry::Driver).message(&format!(
+                    "The node is halted at version {:?}. There's nothing to do.",
+                    halted_version
+                )));
+                return;
+            }
+        }
+
         // Drive progress depending on if we're bootstrapping or continuously syncing
         if self.bootstrapper.is_bootstrapped() {
-            // Fetch any consensus sync requests
-            let consensus_sync_request = self
-                .consensus_notification_handler
-                .get_consensus_sync_request();
+            // Fetch the target of any consensus (or client) sync request (and
+            // whether or not the target has been verified). The requests never
+            // overlap, so at most one of them is active.
+            let (sync_request_target, sync_target_verified) =
+                if self.client_sync_request_handler.active_sync_request() {
+                    (
+                        self.client_sync_request_handler.get_sync_request_target(),
+                        self.client_sync_request_handler.is_sync_target_verified(),
+                    )
+                } else {
+                    (
+                        self.consensus_notification_handler
+                            .get_sync_request_target(),
+                        self.consensus_notification_handler
+                            .is_sync_target_verified(),
+                    )
+                };
 
             // Attempt to continuously sync
-            metrics::increment_counter(
+            self.driver_configuration.metrics.increment_counter(
                 &metrics::EXECUTING_COMPONENT,
                 ExecutingComponent::ContinuousSyncer.get_label(),
             );
-            if let Err(error) = self
+            let result = self
                 .continuous_syncer
-                .drive_progress(consensus_sync_request)
-                .await
-            {
+                .drive_progress(sync_request_target, sync_target_verified)
+                .await;
+
+            // If a future epoch sync target was verified, mark it as verified
+            if let Some(verified_sync_target) = self.continuous_syncer.take_verified_sync_target() {
+                self.consensus_notification_handler
+                    .mark_sync_target_verified(&verified_sync_target);
+                self.client_sync_request_handler
+                    .mark_sync_target_verified(&verified_sync_target);
+            }
+
+            if let Err(error) = result {
+                // If the sync target failed verification, reject the sync request
+                if matches!(error, Error::InvalidSyncTarget(_)) {
+                    if let Err(error) = self
+                        .consensus_notification_handler
+                        .reject_sync_request(error.clone())
+                        .await
+                    {
+                        error!(LogSchema::new(LogEntry::Driver)
+                            .error(&error)
+                            .message("Failed to reject the invalid sync request!"));
+                    }
+                    self.client_sync_request_handler
+                        .reject_sync_request(error.clone());
+                }
                 sample!(
                     SampleRate::Duration(Duration::from_secs(DRIVER_ERROR_LOG_FREQ_SECS)),
                     error!(LogSchema::new(LogEntry::Driver)
                         .error(&error)
                         .message("Error found when driving progress of the continuous syncer!"));
                 );
-                metrics::increment_counter(&metrics::CONTINUOUS_SYNCER_ERRORS, error.get_label());
+                self.driver_configuration
+                    .metrics
+                    .increment_counter(&metrics::CONTINUOUS_SYNCER_ERRORS, error.get_label());
+                self.last_error_label = Some(error.get_label());
             }
         } else {
-            metrics::increment_counter(
+            self.driver_configuration.metrics.increment_counter(
                 &metrics::EXECUTING_COMPONENT,
                 ExecutingComponent::Bootstrapper.get_label(),
             );
@@ -585,7 +1786,10 @@ impl<
                             .error(&error)
                             .message("Error found when checking the bootstrapper progress!"));
                 );
-                metrics::increment_counter(&metrics::BOOTSTRAPPER_ERRORS, error.get_label());
+                self.driver_configuration
+                    .metrics
+                    .increment_counter(&metrics::BOOTSTRAPPER_ERRORS, error.get_label());
+                self.last_error_label = Some(error.get_label());
             }
         };
     }
@@ -1,57 +1,734 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::error::Error;
+use crate::{
+    bootstrapper::BootstrappingPhase,
+    error::Error,
+    notification_handlers::{CommitEvent, CommitState, CommitStateTracker},
+    storage_synchronizer::ShutdownSummary,
+};
+use aptos_config::config::{ContinuousSyncingMode, RoleType};
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
 use futures::{
     channel::{mpsc, oneshot},
     future::Future,
     stream::FusedStream,
     SinkExt, Stream,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
+    time::Duration,
 };
+use tokio::runtime::{Builder, Handle};
+
+/// A unique identifier for a bootstrap notification subscription
+pub type BootstrapSubscriptionId = u64;
+
+/// A unique identifier for a synced version notification subscription
+pub type VersionSubscriptionId = u64;
+
+/// A request that can be sent to the state sync driver. Each request has a
+/// dedicated response type, which the driver sends back along the response
+/// channel of the request envelope.
+pub trait DriverRequest: Send + Sized + 'static {
+    type Response: Send + 'static;
+
+    /// Returns a summary label for the request
+    fn get_label(&self) -> &'static str;
+
+    /// Wraps the given request envelope into a driver notification
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification;
+}
+
+/// A request to the driver, alongside the channel for the driver's response
+pub struct RequestEnvelope<Request: DriverRequest> {
+    pub request: Request,
+    pub response_sender: oneshot::Sender<Request::Response>,
+}
+
+impl<Request: DriverRequest> RequestEnvelope<Request> {
+    /// Creates a new envelope for the given request. Returns the envelope
+    /// and the receiver for the driver's response.
+    pub fn new(request: Request) -> (Self, oneshot::Receiver<Request::Response>) {
+        let (response_sender, response_receiver) = oneshot::channel();
+        let envelope = Self {
+            request,
+            response_sender,
+        };
+        (envelope, response_receiver)
+    }
+}
 
 /// Notifications that can be sent to the state sync driver
 pub enum DriverNotification {
-    NotifyOnceBootstrapped(oneshot::Sender<Result<(), Error>>),
+    CheckProgressNow(RequestEnvelope<CheckProgressNowRequest>),
+    GetContinuousSyncMode(RequestEnvelope<GetContinuousSyncModeRequest>),
+    GetSyncStatus(RequestEnvelope<GetSyncStatusRequest>),
+    NotifyOnceBootstrapped(RequestEnvelope<NotifyOnceBootstrappedRequest>),
+    NotifyOnceSyncedToVersion(RequestEnvelope<NotifyOnceSyncedToVersionRequest>),
+    Ping(RequestEnvelope<PingRequest>),
+    SetContinuousSyncMode(RequestEnvelope<SetContinuousSyncModeRequest>),
+    Shutdown(RequestEnvelope<ShutdownRequest>),
+    SubscribeToCommitEvents(RequestEnvelope<SubscribeToCommitEventsRequest>),
+    SyncToVersion(RequestEnvelope<SyncToVersionRequest>),
+    UnsubscribeFromBootstrap(RequestEnvelope<UnsubscribeFromBootstrapRequest>),
+    UnsubscribeFromSyncedVersion(RequestEnvelope<UnsubscribeFromSyncedVersionRequest>),
+}
+
+/// A request to check (and drive) sync progress immediately
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CheckProgressNowRequest;
+
+impl DriverRequest for CheckProgressNowRequest {
+    type Response = ();
+
+    fn get_label(&self) -> &'static str {
+        "check_progress_now"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::CheckProgressNow(envelope)
+    }
+}
+
+/// A request for the current continuous syncing mode of the node
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GetContinuousSyncModeRequest;
+
+impl DriverRequest for GetContinuousSyncModeRequest {
+    type Response = ContinuousSyncingMode;
+
+    fn get_label(&self) -> &'static str {
+        "get_continuous_sync_mode"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::GetContinuousSyncMode(envelope)
+    }
+}
+
+/// A request for the current sync status of the node
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GetSyncStatusRequest;
+
+impl DriverRequest for GetSyncStatusRequest {
+    type Response = Result<SyncStatus, Error>;
+
+    fn get_label(&self) -> &'static str {
+        "get_sync_status"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::GetSyncStatus(envelope)
+    }
+}
+
+/// A request to be notified once the node has bootstrapped. The driver
+/// only responds once bootstrapping completes.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotifyOnceBootstrappedRequest {
+    pub subscription_id: BootstrapSubscriptionId,
+}
+
+impl DriverRequest for NotifyOnceBootstrappedRequest {
+    type Response = Result<(), Error>;
+
+    fn get_label(&self) -> &'static str {
+        "notify_once_bootstrapped"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::NotifyOnceBootstrapped(envelope)
+    }
+}
+
+/// A request to be notified once the node has synced to the given version.
+/// The driver responds with the latest synced ledger info once it covers
+/// the version.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NotifyOnceSyncedToVersionRequest {
+    pub subscription_id: VersionSubscriptionId,
+    pub version: Version,
+}
+
+impl DriverRequest for NotifyOnceSyncedToVersionRequest {
+    type Response = Result<LedgerInfoWithSignatures, Error>;
+
+    fn get_label(&self) -> &'static str {
+        "notify_once_synced_to_version"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::NotifyOnceSyncedToVersion(envelope)
+    }
+}
+
+/// A liveness check of the driver
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PingRequest;
+
+impl DriverRequest for PingRequest {
+    type Response = ();
+
+    fn get_label(&self) -> &'static str {
+        "ping"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::Ping(envelope)
+    }
+}
+
+/// A request to switch the continuous syncing mode of the node (at runtime).
+/// The response is the previous mode, or an error if the switch is rejected.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SetContinuousSyncModeRequest {
+    pub mode: ContinuousSyncingMode,
+}
+
+impl DriverRequest for SetContinuousSyncModeRequest {
+    type Response = Result<ContinuousSyncingMode, Error>;
+
+    fn get_label(&self) -> &'static str {
+        "set_continuous_sync_mode"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::SetContinuousSyncMode(envelope)
+    }
+}
+
+/// A request to gracefully shut down the driver
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShutdownRequest;
+
+impl DriverRequest for ShutdownRequest {
+    type Response = ShutdownSummary;
+
+    fn get_label(&self) -> &'static str {
+        "shutdown"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::Shutdown(envelope)
+    }
+}
+
+/// A request to subscribe the given channel to commit events
+#[derive(Debug)]
+pub struct SubscribeToCommitEventsRequest {
+    pub commit_event_sender: mpsc::Sender<CommitEvent>,
+}
+
+impl DriverRequest for SubscribeToCommitEventsRequest {
+    type Response = ();
+
+    fn get_label(&self) -> &'static str {
+        "subscribe_to_commit_events"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::SubscribeToCommitEvents(envelope)
+    }
+}
+
+/// A request to sync to the given target version (and then stop syncing).
+/// The driver only responds once the target has been committed (or the
+/// request fails).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SyncToVersionRequest {
+    pub target_version: Version,
+    pub target_ledger_info: Option<LedgerInfoWithSignatures>,
+}
+
+impl DriverRequest for SyncToVersionRequest {
+    type Response = Result<(), Error>;
+
+    fn get_label(&self) -> &'static str {
+        "sync_to_version"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::SyncToVersion(envelope)
+    }
+}
+
+/// A request to remove a bootstrap notification subscription (e.g., because
+/// the subscriber stopped waiting). The response is true iff a subscription
+/// was removed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnsubscribeFromBootstrapRequest {
+    pub subscription_id: BootstrapSubscriptionId,
+}
+
+impl DriverRequest for UnsubscribeFromBootstrapRequest {
+    type Response = bool;
+
+    fn get_label(&self) -> &'static str {
+        "unsubscribe_from_bootstrap"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::UnsubscribeFromBootstrap(envelope)
+    }
+}
+
+/// The result of waiting (with a timeout) for the node to bootstrap
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BootstrapWaitResult {
+    Bootstrapped, // The node has successfully bootstrapped
+    DriverGone,   // The driver stopped before the node bootstrapped
+    TimedOut,     // The timeout elapsed before the node bootstrapped
+}
+
+/// A snapshot of the sync status of the node (e.g., for inspection and
+/// monitoring APIs).
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct SyncStatus {
+    pub active_sync_target: Option<Version>, // The target version of the active sync request (if any)
+    pub advertised_version: Option<Version>, // The highest version advertised by peers (if any)
+    pub bootstrapped: bool,                  // Whether the node has completed bootstrapping
+    pub bootstrapping_phase: BootstrappingPhase,
+    pub continuous_syncing_mode: ContinuousSyncingMode,
+    pub lag_estimate: Option<u64>, // The number of versions the node is behind the advertised version
+    pub last_error_label: Option<String>, // The label of the last error encountered by the driver
+    pub lowest_available_version: Option<Version>, // The lowest version still available in local storage (unknown if storage is empty)
+    pub role: RoleType,
+    pub synced_epoch: Option<u64>, // The latest synced epoch (unknown if storage is empty)
+    pub synced_version: Option<Version>, // The latest synced version (unknown if storage is empty)
+}
+
+/// The max time (ms) to wait for the driver to respond to a liveness ping
+const DRIVER_PING_TIMEOUT_MS: u64 = 5000;
+
+/// A request to remove a synced version notification subscription (e.g.,
+/// because the subscriber timed out). The response is true iff a
+/// subscription was removed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnsubscribeFromSyncedVersionRequest {
+    pub subscription_id: VersionSubscriptionId,
 }
 
-/// A client for sending notifications to the state sync driver
+impl DriverRequest for UnsubscribeFromSyncedVersionRequest {
+    type Response = bool;
+
+    fn get_label(&self) -> &'static str {
+        "unsubscribe_from_synced_version"
+    }
+
+    fn into_notification(envelope: RequestEnvelope<Self>) -> DriverNotification {
+        DriverNotification::UnsubscribeFromSyncedVersion(envelope)
+    }
+}
+
+/// A client for sending notifications to the state sync driver. All requests
+/// fail with `Error::DriverUnavailable` if the driver has stopped. Clients can
+/// be cloned freely (e.g., for each node component that needs one).
+#[derive(Clone)]
 pub struct DriverClient {
-    notification_sender: mpsc::UnboundedSender<DriverNotification>,
+    commit_state_tracker: CommitStateTracker,
+    next_subscription_id: Arc<AtomicU64>, // Shared by bootstrap and synced version subscriptions
+    notification_sender: mpsc::Sender<DriverNotification>,
 }
 
 impl DriverClient {
-    pub fn new(notification_sender: mpsc::UnboundedSender<DriverNotification>) -> Self {
+    pub fn new(
+        commit_state_tracker: CommitStateTracker,
+        next_subscription_id: Arc<AtomicU64>,
+        notification_sender: mpsc::Sender<DriverNotification>,
+    ) -> Self {
         Self {
+            commit_state_tracker,
+            next_subscription_id,
             notification_sender,
         }
     }
 
-    /// Notifies the caller once the driver has successfully bootstrapped the node
+    /// Returns the state of the last commit made by the node (or None if
+    /// nothing has been committed since the driver started). This doesn't
+    /// require a round-trip to the driver.
+    pub fn get_commit_state(&self) -> Option<CommitState> {
+        self.commit_state_tracker.get_commit_state()
+    }
+
+    /// Returns true iff the node has completed bootstrapping. This doesn't
+    /// require a round-trip to the driver.
+    pub fn is_bootstrapped(&self) -> bool {
+        self.commit_state_tracker.is_bootstrapped()
+    }
+
+    /// Notifies the caller once the driver has successfully bootstrapped the
+    /// node. The driver is pinged first, so that a wedged driver is detected
+    /// before waiting (potentially forever) for the node to bootstrap. Each
+    /// call registers an independent subscription, so any number of callers
+    /// can wait concurrently. If the node has already bootstrapped, this
+    /// returns immediately (without contacting the driver).
     pub fn notify_once_bootstrapped(&self) -> impl Future<Output = Result<(), Error>> {
+        let bootstrapped = self.is_bootstrapped();
+        let subscription_id = self.get_next_subscription_id();
         let mut notification_sender = self.notification_sender.clone();
-        let (callback_sender, callback_receiver) = oneshot::channel();
 
         async move {
+            if bootstrapped {
+                return Ok(());
+            }
+            ping_driver(&mut notification_sender).await?;
+            send_request(
+                &mut notification_sender,
+                NotifyOnceBootstrappedRequest { subscription_id },
+            )
+            .await?
+        }
+    }
+
+    /// Notifies the caller once the driver has successfully bootstrapped the
+    /// node, or once the given timeout elapses (whichever happens first). The
+    /// subscription is registered when this is called (not when the future is
+    /// first polled), and is removed from the driver if the timeout elapses.
+    /// If the node has already bootstrapped, no subscription is registered.
+    pub fn notify_once_bootstrapped_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = BootstrapWaitResult> {
+        let bootstrapped = self.is_bootstrapped();
+        let subscription_id = self.get_next_subscription_id();
+        let notification_sender = self.notification_sender.clone();
+        let (envelope, response_receiver) =
+            RequestEnvelope::new(NotifyOnceBootstrappedRequest { subscription_id });
+
+        // Note: a fresh sender always has capacity for a single notification,
+        // so this only fails if the driver has stopped.
+        let subscription_result = if bootstrapped {
+            Ok(())
+        } else {
             notification_sender
-                .send(DriverNotification::NotifyOnceBootstrapped(callback_sender))
-                .await?;
-            callback_receiver.await?
+                .clone()
+                .try_send(NotifyOnceBootstrappedRequest::into_notification(envelope))
+        };
+
+        async move {
+            if bootstrapped {
+                return BootstrapWaitResult::Bootstrapped;
+            }
+            if subscription_result.is_err() {
+                return BootstrapWaitResult::DriverGone;
+            }
+            match tokio::time::timeout(timeout, response_receiver).await {
+                Ok(Ok(Ok(()))) => BootstrapWaitResult::Bootstrapped,
+                Ok(_) => BootstrapWaitResult::DriverGone, // The driver dropped the subscription
+                Err(_) => {
+                    // Remove the subscription (the driver might have already stopped).
+                    // The response is ignored, as the client has stopped waiting.
+                    let (envelope, _) =
+                        RequestEnvelope::new(UnsubscribeFromBootstrapRequest { subscription_id });
+                    let _ = notification_sender
+                        .clone()
+                        .try_send(UnsubscribeFromBootstrapRequest::into_notification(envelope));
+                    BootstrapWaitResult::TimedOut
+                }
+            }
+        }
+    }
+
+    /// Requests that the driver checks (and drives) sync progress immediately,
+    /// instead of waiting for the next progress check interval (e.g., after
+    /// connectivity to peers has changed). Returns once the check completes.
+    pub fn check_progress_now(&self) -> impl Future<Output = Result<(), Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+        async move { send_request(&mut notification_sender, CheckProgressNowRequest).await }
+    }
+
+    /// Returns the current continuous syncing mode of the node (which might
+    /// differ from the configured mode, if the mode was switched at runtime).
+    pub fn get_continuous_sync_mode(
+        &self,
+    ) -> impl Future<Output = Result<ContinuousSyncingMode, Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+        async move { send_request(&mut notification_sender, GetContinuousSyncModeRequest).await }
+    }
+
+    /// Returns the current sync status of the node. If the driver doesn't
+    /// respond within the given timeout (e.g., because it's wedged), an
+    /// error is returned.
+    pub fn get_sync_status(
+        &self,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<SyncStatus, Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+
+        async move {
+            let sync_status_request = send_request(&mut notification_sender, GetSyncStatusRequest);
+            match tokio::time::timeout(timeout, sync_status_request).await {
+                Ok(sync_status) => sync_status?,
+                Err(_) => Err(Error::SyncStatusTimeout(timeout.as_millis() as u64)),
+            }
+        }
+    }
+
+    /// Switches the continuous syncing mode of the node without a restart
+    /// (e.g., to execute transactions on a full node that usually applies
+    /// outputs, and validate execution determinism). The active data stream
+    /// is restarted using the new mode. Returns the previous mode, or an error
+    /// if the switch is rejected (e.g., if a consensus sync request is active,
+    /// or if the node is syncing a state snapshot).
+    pub fn set_continuous_sync_mode(
+        &self,
+        mode: ContinuousSyncingMode,
+    ) -> impl Future<Output = Result<ContinuousSyncingMode, Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+
+        async move {
+            send_request(
+                &mut notification_sender,
+                SetContinuousSyncModeRequest { mode },
+            )
+            .await?
+        }
+    }
+
+    /// Gracefully shuts down the driver. The pending chunks of the storage
+    /// synchronizer are drained (or discarded, if the drain times out) before
+    /// the driver stops. Returns a summary of the drain.
+    pub fn shutdown(&self) -> impl Future<Output = Result<ShutdownSummary, Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+        async move { send_request(&mut notification_sender, ShutdownRequest).await }
+    }
+
+    /// Subscribes the given channel to the events of all commits made by state
+    /// sync (after the driver handles the subscription). Events are never
+    /// waited on: if the channel becomes full (i.e., the subscriber is too
+    /// slow), the subscription is dropped (and the channel closed). The
+    /// subscription is also removed once the receiver is dropped.
+    pub fn subscribe_to_commit_events(
+        &self,
+        commit_event_sender: mpsc::Sender<CommitEvent>,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+
+        async move {
+            send_request(
+                &mut notification_sender,
+                SubscribeToCommitEventsRequest {
+                    commit_event_sender,
+                },
+            )
+            .await
         }
     }
+
+    /// Requests that the node syncs to the given target version and then stops
+    /// syncing (e.g., for backup verification and replay tooling). The target
+    /// ledger info bounds the sync, so it must be given unless the node has
+    /// already synced to the target version. Returns once the target version
+    /// has been durably committed, or an error if the request is invalid
+    /// (e.g., if consensus is executing, or if another sync request is active).
+    /// The driver is pinged first (see `notify_once_bootstrapped()`).
+    pub fn sync_to_version(
+        &self,
+        target_version: Version,
+        target_ledger_info: Option<LedgerInfoWithSignatures>,
+    ) -> impl Future<Output = Result<(), Error>> {
+        let mut notification_sender = self.notification_sender.clone();
+
+        async move {
+            ping_driver(&mut notification_sender).await?;
+            send_request(
+                &mut notification_sender,
+                SyncToVersionRequest {
+                    target_version,
+                    target_ledger_info,
+                },
+            )
+            .await?
+        }
+    }
+
+    /// Waits until the node has synced to the given version (or the timeout
+    /// elapses) and returns the latest synced ledger info, which covers the
+    /// version (i.e., it is at or after the version). This works both before
+    /// and after the node has bootstrapped, and returns immediately (after a
+    /// round-trip to the driver) if the version has already been synced. If
+    /// the timeout elapses, the waiter is removed from the driver.
+    pub fn wait_for_version(
+        &self,
+        version: Version,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<LedgerInfoWithSignatures, Error>> {
+        let subscription_id = self.get_next_subscription_id();
+        let mut notification_sender = self.notification_sender.clone();
+
+        async move {
+            let wait_for_version = send_request(
+                &mut notification_sender,
+                NotifyOnceSyncedToVersionRequest {
+                    subscription_id,
+                    version,
+                },
+            );
+            match tokio::time::timeout(timeout, wait_for_version).await {
+                Ok(wait_result) => wait_result?,
+                Err(_) => {
+                    // Remove the waiter (the driver might have already stopped).
+                    // The response is ignored, as the client has stopped waiting.
+                    let (envelope, _) = RequestEnvelope::new(UnsubscribeFromSyncedVersionRequest {
+                        subscription_id,
+                    });
+                    let _ = notification_sender.clone().try_send(
+                        UnsubscribeFromSyncedVersionRequest::into_notification(envelope),
+                    );
+                    Err(Error::VersionWaitTimeout(
+                        version,
+                        timeout.as_millis() as u64,
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Blocking variant of `check_progress_now()` for synchronous callers.
+    /// This must not be called from within an async runtime (see `block_on_request()`).
+    pub fn blocking_check_progress_now(&self) -> Result<(), Error> {
+        block_on_request("check_progress_now", || self.check_progress_now())
+    }
+
+    /// Blocking variant of `get_sync_status()` for synchronous callers.
+    /// This must not be called from within an async runtime (see `block_on_request()`).
+    pub fn blocking_get_sync_status(&self, timeout: Duration) -> Result<SyncStatus, Error> {
+        block_on_request("get_sync_status", || self.get_sync_status(timeout))
+    }
+
+    /// Blocking variant of `shutdown()` for synchronous callers.
+    /// This must not be called from within an async runtime (see `block_on_request()`).
+    pub fn blocking_shutdown(&self) -> Result<ShutdownSummary, Error> {
+        block_on_request("shutdown", || self.shutdown())
+    }
+
+    /// Blocking variant of `sync_to_version()` for synchronous callers.
+    /// This must not be called from within an async runtime (see `block_on_request()`).
+    pub fn blocking_sync_to_version(
+        &self,
+        target_version: Version,
+        target_ledger_info: Option<LedgerInfoWithSignatures>,
+    ) -> Result<(), Error> {
+        block_on_request("sync_to_version", || {
+            self.sync_to_version(target_version, target_ledger_info)
+        })
+    }
+
+    /// Blocking variant of `notify_once_bootstrapped_with_timeout()` for
+    /// synchronous callers. This must not be called from within an async
+    /// runtime (see `block_on_request()`).
+    pub fn blocking_wait_for_bootstrap(
+        &self,
+        timeout: Duration,
+    ) -> Result<BootstrapWaitResult, Error> {
+        block_on_request("wait_for_bootstrap", || {
+            let wait_for_bootstrap = self.notify_once_bootstrapped_with_timeout(timeout);
+            async move { Ok(wait_for_bootstrap.await) }
+        })
+    }
+
+    /// Blocking variant of `wait_for_version()` for synchronous callers.
+    /// This must not be called from within an async runtime (see `block_on_request()`).
+    pub fn blocking_wait_for_version(
+        &self,
+        version: Version,
+        timeout: Duration,
+    ) -> Result<LedgerInfoWithSignatures, Error> {
+        block_on_request("wait_for_version", || {
+            self.wait_for_version(version, timeout)
+        })
+    }
+
+    /// Returns a new (unique) subscription identifier
+    fn get_next_subscription_id(&self) -> u64 {
+        self.next_subscription_id.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+/// Pings the driver to verify it is alive (i.e., that it is still handling
+/// client notifications). Fails if the driver doesn't respond in time.
+async fn ping_driver(
+    notification_sender: &mut mpsc::Sender<DriverNotification>,
+) -> Result<(), Error> {
+    let ping = send_request(notification_sender, PingRequest);
+    match tokio::time::timeout(Duration::from_millis(DRIVER_PING_TIMEOUT_MS), ping).await {
+        Ok(ping_result) => ping_result,
+        Err(_) => Err(Error::DriverUnavailable(format!(
+            "The driver didn't respond to a ping! Timeout (ms): {:?}",
+            DRIVER_PING_TIMEOUT_MS
+        ))),
+    }
+}
+
+/// Sends the given request to the driver (waiting if the notification
+/// channel is full) and waits for the driver's response.
+async fn send_request<Request: DriverRequest>(
+    notification_sender: &mut mpsc::Sender<DriverNotification>,
+    request: Request,
+) -> Result<Request::Response, Error> {
+    let request_label = request.get_label();
+    let (envelope, response_receiver) = RequestEnvelope::new(request);
+    notification_sender
+        .send(Request::into_notification(envelope))
+        .await
+        .map_err(|error| {
+            Error::DriverUnavailable(format!(
+                "Failed to send the {:?} request to the driver! Error: {:?}",
+                request_label, error
+            ))
+        })?;
+    response_receiver.await.map_err(|_| {
+        Error::DriverUnavailable(format!(
+            "The driver dropped the response channel of the {:?} request!",
+            request_label
+        ))
+    })
+}
+
+/// Creates the given client request and runs it to completion on the calling
+/// thread, using a dedicated (single-threaded) runtime, as the requests rely
+/// on tokio timers. If the caller is already within a tokio runtime, an error
+/// is returned instead (and the request is never created or sent): blocking a
+/// runtime thread on a request can deadlock (e.g., if the driver runs on the
+/// same runtime), and tokio doesn't allow nested runtimes.
+fn block_on_request<Response, Request: Future<Output = Result<Response, Error>>>(
+    request_name: &str,
+    create_request: impl FnOnce() -> Request,
+) -> Result<Response, Error> {
+    if Handle::try_current().is_ok() {
+        return Err(Error::BlockingCallInAsyncContext(format!(
+            "The blocking {:?} call must not be made from an async context! Use the async variant instead.",
+            request_name
+        )));
+    }
+
+    let runtime = Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .map_err(|error| {
+            Error::UnexpectedError(format!(
+                "Failed to create a runtime for the blocking {:?} call! Error: {:?}",
+                request_name, error
+            ))
+        })?;
+    runtime.block_on(create_request())
 }
 
 /// A simple listener for client notifications
 pub struct ClientNotificationListener {
     // The listener for notifications from clients
-    client_notifications: mpsc::UnboundedReceiver<DriverNotification>,
+    client_notifications: mpsc::Receiver<DriverNotification>,
 }
 
 impl ClientNotificationListener {
-    pub fn new(client_notifications: mpsc::UnboundedReceiver<DriverNotification>) -> Self {
+    pub fn new(client_notifications: mpsc::Receiver<DriverNotification>) -> Self {
         Self {
             client_notifications,
         }
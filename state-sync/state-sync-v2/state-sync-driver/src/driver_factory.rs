@@ -4,37 +4,68 @@
 use crate::{
     driver::{DriverConfiguration, StateSyncDriver},
     driver_client::{ClientNotificationListener, DriverClient, DriverNotification},
+    dry_run_executor::DryRunChunkExecutor,
+    error::Error,
+    logging::{LogEntry, LogSchema},
     metadata_storage::MetadataStorageInterface,
+    metrics::DriverMetrics,
     notification_handlers::{
-        CommitNotificationListener, ConsensusNotificationHandler, ErrorNotificationListener,
-        MempoolNotificationHandler,
+        CommitNotificationListener, CommitNotificationSender, CommitStateTracker, CommitSubscriber,
+        CommitSubscriberRegistry, ConsensusNotificationHandler, ErrorNotification,
+        ErrorNotificationListener, EventNotificationHandler, MempoolNotificationHandler,
+        NotifiedVersionTracker, PostCommitHook, PostCommitHookRegistry,
     },
-    storage_synchronizer::StorageSynchronizer,
+    storage_synchronizer::{
+        new_backpressure_channel, verify_concurrency_config, BackpressureListener, ShutdownSummary,
+        StorageSynchronizer, StorageSynchronizerInterface,
+    },
+    utils,
 };
-use aptos_config::config::NodeConfig;
-use aptos_data_client::aptosnet::AptosNetDataClient;
-use aptos_infallible::Mutex;
+use aptos_config::config::{BootstrappingMode, NodeConfig};
+use aptos_data_client::{aptosnet::AptosNetDataClient, AptosDataClient};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_logger::prelude::*;
+use aptos_time_service::TimeService;
 use aptos_types::move_resource::MoveStorage;
-use aptos_types::waypoint::Waypoint;
-use consensus_notifications::ConsensusNotificationListener;
-use data_streaming_service::streaming_client::StreamingServiceClient;
+use aptos_types::{on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint};
+use consensus_notifications::{
+    new_consensus_notifier_listener_pair, ConsensusNotificationListener,
+};
+use data_streaming_service::streaming_client::{DataStreamingClient, StreamingServiceClient};
 use event_notifications::{EventNotificationSender, EventSubscriptionService};
 use executor_types::ChunkExecutorTrait;
 use futures::channel::mpsc;
 use futures::executor::block_on;
 use mempool_notifications::MempoolNotificationSender;
-use std::sync::Arc;
+use std::{
+    sync::{atomic::AtomicU64, Arc},
+    time::{Duration, Instant},
+};
 use storage_interface::DbReaderWriter;
-use tokio::runtime::{Builder, Runtime};
+use tokio::{
+    runtime::{Builder, Handle, Runtime},
+    task::JoinHandle,
+};
 
 /// Creates a new state sync driver and client
 pub struct DriverFactory {
-    client_notification_sender: mpsc::UnboundedSender<DriverNotification>,
-    _driver_runtime: Option<Runtime>,
+    client_notification_sender: mpsc::Sender<DriverNotification>,
+    commit_state_tracker: CommitStateTracker,
+    driver_metrics: DriverMetrics,
+    driver_task_handles: Vec<JoinHandle<()>>,
+    next_subscription_id: Arc<AtomicU64>,
+    post_commit_hook_registry: PostCommitHookRegistry,
+    driver_runtime: Option<Runtime>,
 }
 
 impl DriverFactory {
-    /// Creates and spawns a new state sync driver
+    /// Creates and spawns a new state sync driver. If no mempool notification
+    /// sender is provided (e.g., for tooling and storage-only nodes), mempool
+    /// notifications are skipped. Returns an error if the concurrency settings
+    /// of the driver config are invalid, if the waypoint doesn't match the
+    /// ledger history in storage, or if the reconfiguration subscribers can't
+    /// be notified of the initial on-chain configs (e.g., because storage is
+    /// missing the configs), in which case nothing is spawned.
     pub fn create_and_spawn_driver<
         ChunkExecutor: ChunkExecutorTrait + 'static,
         MempoolNotifier: MempoolNotificationSender + 'static,
@@ -45,106 +76,788 @@ impl DriverFactory {
         waypoint: Waypoint,
         storage: DbReaderWriter,
         chunk_executor: Arc<ChunkExecutor>,
-        mempool_notification_sender: MempoolNotifier,
+        mempool_notification_sender: Option<MempoolNotifier>,
         metadata_storage: MetadataStorage,
         consensus_listener: ConsensusNotificationListener,
-        mut event_subscription_service: EventSubscriptionService,
+        event_subscription_service: EventSubscriptionService,
         aptos_data_client: AptosNetDataClient,
         streaming_service_client: StreamingServiceClient,
-    ) -> Self {
-        // Notify subscribers of the initial on-chain config values
-        match (&*storage.reader).fetch_latest_state_checkpoint_version() {
-            Ok(synced_version) => {
-                if let Err(error) =
-                    event_subscription_service.notify_initial_configs(synced_version)
-                {
-                    panic!(
-                        "Failed to notify subscribers of initial on-chain configs: {:?}",
-                        error
-                    )
-                }
-            }
-            Err(error) => panic!("Failed to fetch the initial synced version: {:?}", error),
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+    ) -> Result<Self, Error> {
+        let driver_runtime = DriverRuntime::new(create_runtime, None);
+        Self::create_and_spawn(
+            driver_runtime,
+            node_config,
+            waypoint,
+            storage,
+            chunk_executor,
+            mempool_notification_sender,
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_service_client,
+            commit_subscribers,
+            false,
+        )
+    }
+
+    /// Creates and spawns a new dry-run state sync driver (e.g., for auditing
+    /// and debugging). The driver runs the full pipeline (i.e., it creates
+    /// data streams, and verifies and executes the data), but never commits
+    /// anything to storage. Instead, the versions that would have been
+    /// committed are logged, counted and sent to the commit event subscribers
+    /// (flagged as a dry run). Mempool and the event subscribers are never
+    /// notified, and consensus notifications are rejected.
+    ///
+    /// Note: the metrics of the driver are labelled with a dry-run instance
+    /// label (see `DriverMetrics::new_dry_run_instance()`), so that they are
+    /// never mistaken for real progress. State snapshots are written directly
+    /// to storage, so an error is returned if the node is configured to
+    /// bootstrap by downloading the latest states.
+    pub fn create_and_spawn_dry_run_driver<
+        ChunkExecutor: ChunkExecutorTrait + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+    >(
+        create_runtime: bool,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        chunk_executor: Arc<ChunkExecutor>,
+        metadata_storage: MetadataStorage,
+        consensus_listener: ConsensusNotificationListener,
+        event_subscription_service: EventSubscriptionService,
+        aptos_data_client: AptosNetDataClient,
+        streaming_service_client: StreamingServiceClient,
+    ) -> Result<Self, Error> {
+        // Verify the node won't sync a state snapshot
+        let bootstrapping_mode = node_config.state_sync.state_sync_driver.bootstrapping_mode;
+        if bootstrapping_mode == BootstrappingMode::DownloadLatestStates {
+            return Err(Error::UnexpectedError(format!(
+                "Dry-run drivers don't support state snapshot syncing! Bootstrapping mode: {:?}",
+                bootstrapping_mode
+            )));
         }
 
-        // Create the notification handlers
-        let (client_notification_sender, client_notification_receiver) = mpsc::unbounded();
-        let client_notification_listener =
-            ClientNotificationListener::new(client_notification_receiver);
-        let (commit_notification_sender, commit_notification_listener) =
-            CommitNotificationListener::new();
-        let consensus_notification_handler = ConsensusNotificationHandler::new(consensus_listener);
-        let (error_notification_sender, error_notification_listener) =
-            ErrorNotificationListener::new();
-        let mempool_notification_handler =
-            MempoolNotificationHandler::new(mempool_notification_sender);
+        let driver_runtime = DriverRuntime::new(create_runtime, None);
+        Self::create_and_spawn(
+            driver_runtime,
+            node_config,
+            waypoint,
+            storage,
+            Arc::new(DryRunChunkExecutor::new(chunk_executor)),
+            None::<mempool_notifications::MempoolNotifier>,
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_service_client,
+            vec![],
+            true,
+        )
+    }
 
-        // Create a new runtime (if required)
-        let driver_runtime = if create_runtime {
-            Some(
-                Builder::new_multi_thread()
-                    .thread_name("state-sync-driver")
-                    .enable_all()
-                    .build()
-                    .expect("Failed to create state sync v2 driver runtime!"),
-            )
-        } else {
-            None
-        };
+    /// Creates and spawns a new state sync driver on the given runtime handle
+    /// (e.g., for embedders that manage their own runtimes). The driver, the
+    /// storage synchronizer tasks and any helper tasks are all spawned on the
+    /// handle, and the factory doesn't own a runtime. The driver tasks can
+    /// still be shut down (or aborted) through the runtime handle of the
+    /// factory (see `into_runtime_handle()`).
+    pub fn create_and_spawn_on<
+        ChunkExecutor: ChunkExecutorTrait + 'static,
+        MempoolNotifier: MempoolNotificationSender + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+    >(
+        handle: Handle,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        chunk_executor: Arc<ChunkExecutor>,
+        mempool_notification_sender: Option<MempoolNotifier>,
+        metadata_storage: MetadataStorage,
+        consensus_listener: ConsensusNotificationListener,
+        event_subscription_service: EventSubscriptionService,
+        aptos_data_client: AptosNetDataClient,
+        streaming_service_client: StreamingServiceClient,
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+    ) -> Result<Self, Error> {
+        let driver_runtime = DriverRuntime::new(false, Some(handle));
+        Self::create_and_spawn(
+            driver_runtime,
+            node_config,
+            waypoint,
+            storage,
+            chunk_executor,
+            mempool_notification_sender,
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_service_client,
+            commit_subscribers,
+            false,
+        )
+    }
 
-        // Create the storage synchronizer
-        let event_subscription_service = Arc::new(Mutex::new(event_subscription_service));
-        let (storage_synchronizer, _, _) = StorageSynchronizer::new(
-            node_config.state_sync.state_sync_driver,
+    /// Creates and spawns a new state sync driver (using the standard storage
+    /// synchronizer) that uses the given data client and streaming client
+    /// (instead of the standard implementations). This is useful for tests
+    /// that run the full pipeline against real storage (e.g., with clients
+    /// that serve the data of another database).
+    pub fn create_and_spawn_driver_with_clients<
+        ChunkExecutor: ChunkExecutorTrait + 'static,
+        DataClient: AptosDataClient + Clone + Send + Sync + 'static,
+        MempoolNotifier: MempoolNotificationSender + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+        StreamingClient: DataStreamingClient + Clone + Send + Sync + 'static,
+    >(
+        create_runtime: bool,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        chunk_executor: Arc<ChunkExecutor>,
+        mempool_notification_sender: Option<MempoolNotifier>,
+        metadata_storage: MetadataStorage,
+        consensus_listener: ConsensusNotificationListener,
+        event_subscription_service: EventSubscriptionService,
+        aptos_data_client: DataClient,
+        streaming_client: StreamingClient,
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+    ) -> Result<Self, Error> {
+        let driver_runtime = DriverRuntime::new(create_runtime, None);
+        Self::create_and_spawn(
+            driver_runtime,
+            node_config,
+            waypoint,
+            storage,
             chunk_executor,
-            commit_notification_sender,
-            error_notification_sender,
-            event_subscription_service.clone(),
-            mempool_notification_handler.clone(),
+            mempool_notification_sender,
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_client,
+            commit_subscribers,
+            false,
+        )
+    }
+
+    /// Creates and spawns a new state sync driver (using the standard storage
+    /// synchronizer) on the given driver runtime. If `dry_run` is set, the
+    /// given chunk executor must never commit (see `DryRunChunkExecutor`).
+    fn create_and_spawn<
+        ChunkExecutor: ChunkExecutorTrait + 'static,
+        DataClient: AptosDataClient + Clone + Send + Sync + 'static,
+        MempoolNotifier: MempoolNotificationSender + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+        StreamingClient: DataStreamingClient + Clone + Send + Sync + 'static,
+    >(
+        driver_runtime: DriverRuntime,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        chunk_executor: Arc<ChunkExecutor>,
+        mempool_notification_sender: Option<MempoolNotifier>,
+        metadata_storage: MetadataStorage,
+        consensus_listener: ConsensusNotificationListener,
+        event_subscription_service: EventSubscriptionService,
+        aptos_data_client: DataClient,
+        streaming_client: StreamingClient,
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+        dry_run: bool,
+    ) -> Result<Self, Error> {
+        // Verify the concurrency settings of the storage synchronizer (before
+        // anything is created or spawned).
+        let driver_config = node_config.state_sync.state_sync_driver;
+        verify_concurrency_config(&driver_config)?;
+
+        // Create the notification handlers and channels of the driver
+        let driver_components = DriverComponents::new(
+            driver_runtime,
+            node_config,
+            waypoint,
+            &storage,
+            mempool_notification_sender,
+            consensus_listener,
+            event_subscription_service,
+            commit_subscribers,
+            dry_run,
+            false,
+            TimeService::real(),
+        )?;
+
+        // Create the storage synchronizer (and the backpressure channel to the driver)
+        let (backpressure_notifier, backpressure_listener) = new_backpressure_channel();
+        let (storage_synchronizer, executor_handle, committer_handle) = StorageSynchronizer::new(
+            driver_config,
+            backpressure_notifier,
+            chunk_executor,
+            driver_components.commit_notification_sender.clone(),
+            driver_components.error_notification_sender.clone(),
+            driver_components.event_notification_handler.clone(),
+            driver_components.commit_state_tracker.clone(),
+            driver_components.commit_subscriber_registry.clone(),
+            driver_components.mempool_notification_handler.clone(),
             metadata_storage.clone(),
+            driver_components.notified_version_tracker.clone(),
             storage.clone(),
-            driver_runtime.as_ref(),
+            driver_components.driver_runtime.handle(),
+            driver_components.driver_metrics,
+            dry_run,
+        );
+        let post_commit_hook_registry = storage_synchronizer.get_post_commit_hook_registry();
+
+        // Create and spawn the state sync driver
+        Ok(Self::spawn_driver(
+            driver_components,
+            node_config,
+            waypoint,
+            storage,
+            metadata_storage,
+            backpressure_listener,
+            storage_synchronizer,
+            post_commit_hook_registry,
+            aptos_data_client,
+            streaming_client,
+            vec![executor_handle, committer_handle],
+        ))
+    }
+
+    /// Creates and spawns a new state sync driver that uses the given storage
+    /// synchronizer, data client and streaming client (instead of the standard
+    /// implementations). This is useful for tests and experimental deployments
+    /// (e.g., a storage synchronizer that writes to a remote store). All
+    /// timers of the driver (e.g., progress checks and the connection
+    /// deadline) are driven by the given time service, so tests can use a
+    /// mock time service to control them.
+    ///
+    /// Note: the given storage synchronizer is responsible for its own commit
+    /// handling, i.e., it won't be connected to the driver's commit notification
+    /// channel, and post-commit hooks registered on the factory are not run.
+    /// Errors raised by the synchronizer are received along the given error
+    /// notification listener.
+    pub fn create_and_spawn_driver_with_components<
+        DataClient: AptosDataClient + Clone + Send + Sync + 'static,
+        MempoolNotifier: MempoolNotificationSender + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+        StorageSyncer: StorageSynchronizerInterface + Clone + Send + Sync + 'static,
+        StreamingClient: DataStreamingClient + Clone + Send + Sync + 'static,
+    >(
+        create_runtime: bool,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        storage_synchronizer: StorageSyncer,
+        error_notification_listener: ErrorNotificationListener,
+        mempool_notification_sender: Option<MempoolNotifier>,
+        metadata_storage: MetadataStorage,
+        consensus_listener: ConsensusNotificationListener,
+        event_subscription_service: EventSubscriptionService,
+        aptos_data_client: DataClient,
+        streaming_client: StreamingClient,
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+        time_service: TimeService,
+    ) -> Result<Self, Error> {
+        // Create the notification handlers and channels of the driver
+        let mut driver_components = DriverComponents::new(
+            DriverRuntime::new(create_runtime, None),
+            node_config,
+            waypoint,
+            &storage,
+            mempool_notification_sender,
+            consensus_listener,
+            event_subscription_service,
+            commit_subscribers,
+            false,
+            false,
+            time_service,
+        )?;
+        driver_components.error_notification_listener = error_notification_listener;
+
+        // The given storage synchronizer doesn't report backpressure, so the
+        // driver always feeds it data.
+        let (_, backpressure_listener) = new_backpressure_channel();
+        let post_commit_hook_registry =
+            PostCommitHookRegistry::new(driver_components.driver_metrics);
+
+        // Create and spawn the state sync driver
+        Ok(Self::spawn_driver(
+            driver_components,
+            node_config,
+            waypoint,
+            storage,
+            metadata_storage,
+            backpressure_listener,
+            storage_synchronizer,
+            post_commit_hook_registry,
+            aptos_data_client,
+            streaming_client,
+            vec![],
+        ))
+    }
+
+    /// Creates and spawns a new state sync driver for tooling binaries (e.g.,
+    /// db-restore and replay tools) that only have storage, a storage
+    /// synchronizer and a data client. Inert stand-ins are created for the
+    /// rest: mempool is never notified, events are dropped (there are no
+    /// event subscribers) and consensus notifications are rejected (consensus
+    /// never runs alongside the driver, so syncing never defers to it).
+    pub fn create_for_tooling<
+        DataClient: AptosDataClient + Clone + Send + Sync + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+        StorageSyncer: StorageSynchronizerInterface + Clone + Send + Sync + 'static,
+        StreamingClient: DataStreamingClient + Clone + Send + Sync + 'static,
+    >(
+        create_runtime: bool,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        storage_synchronizer: StorageSyncer,
+        metadata_storage: MetadataStorage,
+        aptos_data_client: DataClient,
+        streaming_client: StreamingClient,
+    ) -> Result<Self, Error> {
+        // Create the stand-ins for consensus (the notifier is dropped, so no
+        // notifications are ever sent) and the event subscription service.
+        let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+        let event_subscription_service = EventSubscriptionService::new(
+            ON_CHAIN_CONFIG_REGISTRY,
+            Arc::new(RwLock::new(storage.clone())),
         );
 
+        // Create the notification handlers and channels of the driver
+        let driver_components = DriverComponents::new(
+            DriverRuntime::new(create_runtime, None),
+            node_config,
+            waypoint,
+            &storage,
+            None::<mempool_notifications::MempoolNotifier>,
+            consensus_listener,
+            event_subscription_service,
+            vec![],
+            false,
+            true,
+            TimeService::real(),
+        )?;
+
+        // The given storage synchronizer doesn't report backpressure, so the
+        // driver always feeds it data.
+        let (_, backpressure_listener) = new_backpressure_channel();
+        let post_commit_hook_registry =
+            PostCommitHookRegistry::new(driver_components.driver_metrics);
+
+        // Create and spawn the state sync driver
+        Ok(Self::spawn_driver(
+            driver_components,
+            node_config,
+            waypoint,
+            storage,
+            metadata_storage,
+            backpressure_listener,
+            storage_synchronizer,
+            post_commit_hook_registry,
+            aptos_data_client,
+            streaming_client,
+            vec![],
+        ))
+    }
+
+    /// Creates the state sync driver (from the given components) and spawns it.
+    /// The given task handles are those of any tasks spawned for the driver.
+    fn spawn_driver<
+        DataClient: AptosDataClient + Clone + Send + Sync + 'static,
+        MempoolNotifier: MempoolNotificationSender + 'static,
+        MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+        StorageSyncer: StorageSynchronizerInterface + Clone + Send + Sync + 'static,
+        StreamingClient: DataStreamingClient + Clone + Send + Sync + 'static,
+    >(
+        driver_components: DriverComponents<MempoolNotifier>,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: DbReaderWriter,
+        metadata_storage: MetadataStorage,
+        backpressure_listener: BackpressureListener,
+        storage_synchronizer: StorageSyncer,
+        post_commit_hook_registry: PostCommitHookRegistry,
+        aptos_data_client: DataClient,
+        streaming_client: StreamingClient,
+        mut driver_task_handles: Vec<JoinHandle<()>>,
+    ) -> Self {
         // Create the driver configuration
+        let driver_metrics = driver_components.driver_metrics;
         let driver_configuration = DriverConfiguration::new(
             node_config.state_sync.state_sync_driver,
+            driver_components.dry_run,
+            driver_metrics,
             node_config.base.role,
+            driver_components.tooling,
             waypoint,
         );
 
         // Create the state sync driver
+        let DriverComponents {
+            client_notification_listener,
+            client_notification_sender,
+            commit_notification_listener,
+            commit_state_tracker,
+            commit_subscriber_registry,
+            consensus_notification_handler,
+            driver_runtime,
+            error_notification_listener,
+            event_notification_handler,
+            mempool_notification_handler,
+            notified_version_tracker,
+            time_service,
+            ..
+        } = driver_components;
         let state_sync_driver = StateSyncDriver::new(
+            backpressure_listener,
             client_notification_listener,
             commit_notification_listener,
+            commit_state_tracker.clone(),
+            commit_subscriber_registry,
             consensus_notification_handler,
             driver_configuration,
             error_notification_listener,
-            event_subscription_service,
+            event_notification_handler,
             mempool_notification_handler,
             metadata_storage,
+            notified_version_tracker,
             storage_synchronizer,
             aptos_data_client,
-            streaming_service_client,
+            streaming_client,
             storage.reader,
+            time_service,
         );
 
         // Spawn the driver
-        if let Some(driver_runtime) = &driver_runtime {
-            driver_runtime.spawn(state_sync_driver.start_driver());
+        let driver_handle = if let Some(runtime_handle) = driver_runtime.handle() {
+            runtime_handle.spawn(state_sync_driver.start_driver())
         } else {
-            tokio::spawn(state_sync_driver.start_driver());
-        }
+            tokio::spawn(state_sync_driver.start_driver())
+        };
+        driver_task_handles.push(driver_handle);
 
         Self {
             client_notification_sender,
-            _driver_runtime: driver_runtime,
+            commit_state_tracker,
+            driver_metrics,
+            driver_task_handles,
+            next_subscription_id: Arc::new(AtomicU64::new(0)),
+            post_commit_hook_registry,
+            driver_runtime: driver_runtime.runtime,
         }
     }
 
+    /// Registers a hook that is run (synchronously) after each durable
+    /// transaction commit made by state sync, before any commit
+    /// notifications are sent.
+    pub fn register_post_commit_hook(&self, hook_name: &'static str, hook: PostCommitHook) {
+        self.post_commit_hook_registry
+            .register_hook(hook_name, hook);
+    }
+
+    /// Returns the metrics handle of the driver. The metrics of each driver
+    /// in the process are labelled by the instance label of the handle.
+    pub fn get_driver_metrics(&self) -> DriverMetrics {
+        self.driver_metrics
+    }
+
     /// Returns a new client that can be used to communicate with the driver
     pub fn create_driver_client(&self) -> DriverClient {
-        DriverClient::new(self.client_notification_sender.clone())
+        DriverClient::new(
+            self.commit_state_tracker.clone(),
+            self.next_subscription_id.clone(),
+            self.client_notification_sender.clone(),
+        )
+    }
+
+    /// Converts the factory into a handle that owns the driver runtime (if
+    /// any), the driver tasks and a client to the driver.
+    pub fn into_runtime_handle(self) -> StateSyncRuntimeHandle {
+        let driver_client = self.create_driver_client();
+        StateSyncRuntimeHandle {
+            commit_state_tracker: self.commit_state_tracker,
+            driver_client,
+            driver_metrics: self.driver_metrics,
+            driver_runtime: self.driver_runtime,
+            driver_task_handles: self.driver_task_handles,
+            post_commit_hook_registry: self.post_commit_hook_registry,
+            shutdown_requested: false,
+        }
+    }
+}
+
+/// A handle to a running state sync driver. The handle owns the driver
+/// runtime (if any), the driver tasks and a client to the driver, so that
+/// embedders don't need to manage their lifetimes independently.
+///
+/// Note: the handle should be shut down (see `shutdown()`) before it is
+/// dropped. Otherwise, the driver tasks are aborted (and any data pending
+/// in the storage synchronizer is lost).
+pub struct StateSyncRuntimeHandle {
+    commit_state_tracker: CommitStateTracker,
+    driver_client: DriverClient,
+    driver_metrics: DriverMetrics,
+    driver_runtime: Option<Runtime>,
+    driver_task_handles: Vec<JoinHandle<()>>,
+    post_commit_hook_registry: PostCommitHookRegistry,
+    shutdown_requested: bool,
+}
+
+impl StateSyncRuntimeHandle {
+    /// Returns the shared tracker of the commits made by the node
+    pub fn commit_state_tracker(&self) -> CommitStateTracker {
+        self.commit_state_tracker.clone()
+    }
+
+    /// Returns the client that can be used to communicate with the driver
+    pub fn driver_client(&self) -> &DriverClient {
+        &self.driver_client
+    }
+
+    /// Returns the metrics handle of the driver
+    pub fn get_driver_metrics(&self) -> DriverMetrics {
+        self.driver_metrics
+    }
+
+    /// Registers a hook that is run (synchronously) after each durable
+    /// transaction commit made by state sync, before any commit
+    /// notifications are sent.
+    pub fn register_post_commit_hook(&self, hook_name: &'static str, hook: PostCommitHook) {
+        self.post_commit_hook_registry
+            .register_hook(hook_name, hook);
+    }
+
+    /// Gracefully shuts down the driver (draining the storage synchronizer)
+    /// and joins the driver tasks. If the shutdown doesn't complete within
+    /// the given timeout, the remaining tasks are aborted and an error is
+    /// returned. Note: this must be called from within a tokio runtime.
+    pub async fn shutdown(mut self, timeout: Duration) -> Result<ShutdownSummary, Error> {
+        self.shutdown_requested = true;
+        let shutdown_start_time = Instant::now();
+
+        // Trigger the graceful shutdown of the driver
+        let shutdown_summary =
+            match tokio::time::timeout(timeout, self.driver_client.shutdown()).await {
+                Ok(shutdown_result) => shutdown_result?,
+                Err(_) => return Err(Error::ShutdownTimeout(timeout.as_millis() as u64)),
+            };
+
+        // Join the driver tasks (the remaining tasks are aborted on drop)
+        while let Some(mut task_handle) = self.driver_task_handles.pop() {
+            let remaining_time = timeout.saturating_sub(shutdown_start_time.elapsed());
+            match tokio::time::timeout(remaining_time, &mut task_handle).await {
+                Ok(Ok(())) => {}
+                Ok(Err(error)) => {
+                    return Err(Error::UnexpectedError(format!(
+                        "A driver task failed during shutdown: {:?}",
+                        error
+                    )));
+                }
+                Err(_) => {
+                    task_handle.abort();
+                    return Err(Error::ShutdownTimeout(timeout.as_millis() as u64));
+                }
+            }
+        }
+
+        info!(LogSchema::new(LogEntry::Driver).message(&format!(
+            "The state sync driver has shut down! Shutdown summary: {:?}",
+            shutdown_summary
+        )));
+        Ok(shutdown_summary)
+    }
+}
+
+impl Drop for StateSyncRuntimeHandle {
+    fn drop(&mut self) {
+        if !self.shutdown_requested {
+            warn!(LogSchema::new(LogEntry::Driver).message(
+                "The state sync runtime handle was dropped without a shutdown! \
+                Aborting the driver tasks."
+            ));
+        }
+
+        // Abort any remaining tasks and stop the runtime (without blocking)
+        for task_handle in self.driver_task_handles.drain(..) {
+            task_handle.abort();
+        }
+        if let Some(driver_runtime) = self.driver_runtime.take() {
+            driver_runtime.shutdown_background();
+        }
+    }
+}
+
+/// The notification handlers and channels shared by the state sync driver
+/// and the storage synchronizer (as well as the driver runtime, if any).
+struct DriverComponents<MempoolNotifier> {
+    client_notification_listener: ClientNotificationListener,
+    client_notification_sender: mpsc::Sender<DriverNotification>,
+    commit_notification_listener: CommitNotificationListener,
+    commit_notification_sender: CommitNotificationSender,
+    commit_state_tracker: CommitStateTracker,
+    commit_subscriber_registry: CommitSubscriberRegistry,
+    consensus_notification_handler: ConsensusNotificationHandler,
+    driver_metrics: DriverMetrics,
+    driver_runtime: DriverRuntime,
+    dry_run: bool,
+    error_notification_listener: ErrorNotificationListener,
+    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    event_notification_handler: EventNotificationHandler,
+    mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
+    notified_version_tracker: NotifiedVersionTracker,
+    time_service: TimeService,
+    tooling: bool,
+}
+
+impl<MempoolNotifier: MempoolNotificationSender> DriverComponents<MempoolNotifier> {
+    /// Creates the driver components. The waypoint is verified against the
+    /// ledger history in storage, and the reconfiguration subscribers are
+    /// notified of the initial on-chain configs here, so that a misconfigured
+    /// node (e.g., a waypoint for another network, or storage that is missing
+    /// the on-chain configs) fails construction, instead of failing later
+    /// (or silently leaving the subscribers without configs).
+    fn new(
+        driver_runtime: DriverRuntime,
+        node_config: &NodeConfig,
+        waypoint: Waypoint,
+        storage: &DbReaderWriter,
+        mempool_notification_sender: Option<MempoolNotifier>,
+        consensus_listener: ConsensusNotificationListener,
+        mut event_subscription_service: EventSubscriptionService,
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+        dry_run: bool,
+        tooling: bool,
+        time_service: TimeService,
+    ) -> Result<Self, Error> {
+        // Verify the waypoint is consistent with storage
+        utils::verify_waypoint_against_storage(storage.reader.clone(), &waypoint)?;
+
+        // Notify subscribers of the initial on-chain config values
+        let synced_version = (&*storage.reader)
+            .fetch_latest_state_checkpoint_version()
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to fetch the initial synced version: {:?}",
+                    error
+                ))
+            })?;
+        event_subscription_service
+            .notify_initial_configs(synced_version)
+            .map_err(|error| Error::InitialConfigsUnavailable(synced_version, error.to_string()))?;
+
+        // Create the metrics handle of the driver (to namespace the metrics
+        // of each driver instance in the process).
+        let driver_metrics = if dry_run {
+            DriverMetrics::new_dry_run_instance()
+        } else {
+            DriverMetrics::new_instance()
+        };
+
+        // Create the notification handlers
+        let (client_notification_sender, client_notification_receiver) = mpsc::channel(
+            node_config
+                .state_sync
+                .state_sync_driver
+                .max_pending_client_notifications as usize,
+        );
+        let client_notification_listener =
+            ClientNotificationListener::new(client_notification_receiver);
+        let (commit_notification_sender, commit_notification_listener) =
+            CommitNotificationListener::new(
+                node_config
+                    .state_sync
+                    .state_sync_driver
+                    .max_pending_commit_notifications,
+                driver_metrics,
+            );
+        let consensus_notification_handler = ConsensusNotificationHandler::new(
+            node_config.state_sync.state_sync_driver,
+            consensus_listener,
+            time_service.clone(),
+            driver_metrics,
+        );
+        let (error_notification_sender, error_notification_listener) =
+            ErrorNotificationListener::new();
+        let mempool_notification_handler = MempoolNotificationHandler::new_with_optional_sender(
+            node_config.state_sync.state_sync_driver,
+            mempool_notification_sender,
+            time_service.clone(),
+            driver_metrics,
+        );
+        let notified_version_tracker = NotifiedVersionTracker::new(driver_metrics);
+        let commit_state_tracker = CommitStateTracker::new(time_service.clone());
+        let commit_subscriber_registry =
+            CommitSubscriberRegistry::new(commit_subscribers, driver_metrics);
+
+        // Create the event notification handler
+        let event_notification_handler = EventNotificationHandler::new(
+            Arc::new(Mutex::new(event_subscription_service)),
+            node_config
+                .state_sync
+                .state_sync_driver
+                .max_pending_event_notifications,
+            driver_runtime.handle(),
+            driver_metrics,
+        );
+
+        Ok(Self {
+            client_notification_listener,
+            client_notification_sender,
+            commit_notification_listener,
+            commit_notification_sender,
+            commit_state_tracker,
+            commit_subscriber_registry,
+            consensus_notification_handler,
+            driver_metrics,
+            driver_runtime,
+            dry_run,
+            error_notification_listener,
+            error_notification_sender,
+            event_notification_handler,
+            mempool_notification_handler,
+            notified_version_tracker,
+            time_service,
+            tooling,
+        })
+    }
+}
+
+/// The runtime on which the driver tasks are spawned. This is either a
+/// dedicated runtime (owned by the driver), a caller-provided runtime handle,
+/// or neither (in which case tasks are spawned on the current runtime).
+struct DriverRuntime {
+    handle: Option<Handle>,
+    runtime: Option<Runtime>,
+}
+
+impl DriverRuntime {
+    fn new(create_runtime: bool, handle: Option<Handle>) -> Self {
+        // Create a new runtime (if required)
+        let runtime = if create_runtime {
+            Some(
+                Builder::new_multi_thread()
+                    .thread_name("state-sync-driver")
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create state sync v2 driver runtime!"),
+            )
+        } else {
+            None
+        };
+
+        // Use the handle of the dedicated runtime (if one was created)
+        let handle = runtime
+            .as_ref()
+            .map(|runtime| runtime.handle().clone())
+            .or(handle);
+        Self { handle, runtime }
+    }
+
+    /// Returns the handle on which to spawn the driver tasks (if any)
+    fn handle(&self) -> Option<Handle> {
+        self.handle.clone()
     }
 }
 
@@ -153,7 +866,7 @@ impl DriverFactory {
 /// can prepend all logs with the runtime thread name.
 pub struct StateSyncRuntimes {
     _aptos_data_client: Runtime,
-    state_sync: DriverFactory,
+    state_sync: StateSyncRuntimeHandle,
     _storage_service: Runtime,
     _streaming_service: Runtime,
 }
@@ -161,7 +874,7 @@ pub struct StateSyncRuntimes {
 impl StateSyncRuntimes {
     pub fn new(
         aptos_data_client: Runtime,
-        state_sync: DriverFactory,
+        state_sync: StateSyncRuntimeHandle,
         storage_service: Runtime,
         streaming_service: Runtime,
     ) -> Self {
@@ -174,8 +887,13 @@ impl StateSyncRuntimes {
     }
 
     pub fn block_until_initialized(&self) {
-        let state_sync_client = self.state_sync.create_driver_client();
+        let state_sync_client = self.state_sync.driver_client();
         block_on(state_sync_client.notify_once_bootstrapped())
             .expect("State sync v2 initialization failure");
     }
+
+    /// Returns the metrics handle of the state sync driver
+    pub fn get_driver_metrics(&self) -> DriverMetrics {
+        self.state_sync.get_driver_metrics()
+    }
 }
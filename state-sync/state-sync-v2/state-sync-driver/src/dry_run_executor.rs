@@ -0,0 +1,181 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{format_err, Result};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{Transaction, TransactionListWithProof, TransactionOutputListWithProof},
+};
+use executor_types::{CancellationToken, ChunkCommitNotification, ChunkExecutorTrait};
+use std::{collections::VecDeque, sync::Arc};
+
+/// A chunk executor adapter for dry-run drivers. Chunks are verified and
+/// executed (or applied) by the wrapped executor, but are never committed:
+/// commits return synthetic notifications (containing the transactions that
+/// would have been committed), and storage is never advanced.
+///
+/// Note: the executed chunks are held in memory by the wrapped executor (so
+/// that the next chunk is executed on top of them) until the executor is
+/// reset, i.e., until the next data stream is created. As storage never
+/// advances, each new data stream restarts from the latest version in storage.
+pub struct DryRunChunkExecutor<ChunkExecutor> {
+    chunk_executor: Arc<ChunkExecutor>,
+    executed_chunks: Mutex<VecDeque<Vec<Transaction>>>, // The chunks pending (synthetic) commit
+}
+
+impl<ChunkExecutor: ChunkExecutorTrait> DryRunChunkExecutor<ChunkExecutor> {
+    pub fn new(chunk_executor: Arc<ChunkExecutor>) -> Self {
+        Self {
+            chunk_executor,
+            executed_chunks: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Records the transactions of a successfully executed (or applied) chunk
+    fn record_executed_chunk(&self, transactions: Vec<Transaction>) {
+        self.executed_chunks.lock().push_back(transactions);
+    }
+
+    /// Returns a synthetic commit notification for the next `num_chunks`
+    /// executed chunks. Nothing is committed by the wrapped executor.
+    fn commit(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        let mut executed_chunks = self.executed_chunks.lock();
+        if executed_chunks.len() < num_chunks {
+            return Err(format_err!(
+                "Not enough executed chunks to commit (dry run)! Requested: {:?}, executed: {:?}",
+                num_chunks,
+                executed_chunks.len()
+            ));
+        }
+
+        let committed_transactions = executed_chunks.drain(..num_chunks).flatten().collect();
+        Ok(ChunkCommitNotification {
+            committed_events: vec![],
+            committed_transactions,
+            reconfiguration_occurred: false,
+        })
+    }
+}
+
+impl<ChunkExecutor: ChunkExecutorTrait> ChunkExecutorTrait for DryRunChunkExecutor<ChunkExecutor> {
+    fn execute_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        let transactions = txn_list_with_proof.transactions.clone();
+        self.chunk_executor.execute_chunk(
+            txn_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        self.record_executed_chunk(transactions);
+        Ok(())
+    }
+
+    fn apply_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        let transactions = get_output_transactions(&txn_output_list_with_proof);
+        self.chunk_executor.apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        self.record_executed_chunk(transactions);
+        Ok(())
+    }
+
+    fn execute_chunk_cancellable(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        let transactions = txn_list_with_proof.transactions.clone();
+        self.chunk_executor.execute_chunk_cancellable(
+            txn_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            cancellation_token,
+        )?;
+        self.record_executed_chunk(transactions);
+        Ok(())
+    }
+
+    fn apply_chunk_cancellable(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+        cancellation_token: &CancellationToken,
+    ) -> Result<()> {
+        let transactions = get_output_transactions(&txn_output_list_with_proof);
+        self.chunk_executor.apply_chunk_cancellable(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+            cancellation_token,
+        )?;
+        self.record_executed_chunk(transactions);
+        Ok(())
+    }
+
+    fn commit_chunk(&self) -> Result<ChunkCommitNotification> {
+        self.commit(1)
+    }
+
+    fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        self.commit(num_chunks)
+    }
+
+    fn execute_and_commit_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<ChunkCommitNotification> {
+        self.execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)?;
+        self.commit_chunk()
+    }
+
+    fn apply_and_commit_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<ChunkCommitNotification> {
+        self.apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        self.commit_chunk()
+    }
+
+    fn reset(&self) -> Result<()> {
+        self.executed_chunks.lock().clear();
+        self.chunk_executor.reset()
+    }
+
+    fn finish(&self) {
+        self.chunk_executor.finish()
+    }
+}
+
+/// Returns the transactions of the given output list
+fn get_output_transactions(
+    txn_output_list_with_proof: &TransactionOutputListWithProof,
+) -> Vec<Transaction> {
+    txn_output_list_with_proof
+        .transactions_and_outputs
+        .iter()
+        .map(|(transaction, _)| transaction.clone())
+        .collect()
+}
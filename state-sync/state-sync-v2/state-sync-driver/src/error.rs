@@ -12,36 +12,90 @@ pub enum Error {
     AlreadyBootstrapped(String),
     #[error("Advertised data error: {0}")]
     AdvertisedDataError(String),
+    #[error("A blocking client call was made from within an async runtime: {0}")]
+    BlockingCallInAsyncContext(String),
     #[error("State sync has not yet finished bootstrapping! Error: {0}")]
     BootstrapNotComplete(String),
     #[error("Failed to send callback: {0}")]
     CallbackSendFailed(String),
+    #[error("Failed to notify mempool ({0}) and the event subscription service ({1})")]
+    CommitNotificationErrors(String, String),
+    #[error("The sync request conflicts with an active sync request: {0}")]
+    ConflictingSyncRequest(String),
+    #[error("Consensus is currently executing: {0}")]
+    ConsensusIsExecuting(String),
+    #[error("The consensus receiver was dropped before the response was sent: {0}")]
+    ConsensusReceiverDropped(String),
+    #[error("Timed-out responding to consensus. Timeout (ms): {0}")]
+    ConsensusResponseTimeout(u64),
     #[error("Timed-out waiting for a data stream too many times.")]
     CriticalDataStreamTimeout(String),
     #[error("Timed-out waiting for a notification from the data stream. Timeout: {0}")]
     DataStreamNotificationTimeout(String),
+    #[error("The state sync driver is unavailable: {0}")]
+    DriverUnavailable(String),
+    #[error("A consensus notification was sent to a dry-run driver: {0}")]
+    DryRunConsensusNotification(String),
     #[error("Error encountered in the event subscription service: {0}")]
     EventNotificationError(String),
     #[error("A consensus notification was sent to a full node: {0}")]
     FullNodeConsensusNotification(String),
+    #[error(
+        "Failed to deliver the initial on-chain configs (at version {0}) to the reconfiguration subscribers: {1}"
+    )]
+    InitialConfigsUnavailable(Version, String),
     #[error("An integer overflow has occurred: {0}")]
     IntegerOverflow(String),
     #[error("An invalid payload was received: {0}")]
     InvalidPayload(String),
+    #[error("Received an invalid sync target: {0}")]
+    InvalidSyncTarget(String),
+    #[error("The continuous syncing mode switch was rejected: {0}")]
+    ModeSwitchRejected(String),
     #[error("Failed to notify mempool of the new commit: {0}")]
     NotifyMempoolError(String),
     #[error("Received an old sync request for version {0}, but our committed version is: {1}")]
     OldSyncRequest(Version, Version),
     #[error("Received oneshot::canceled. The sender of a channel was dropped: {0}")]
     SenderDroppedError(String),
+    #[error("Timed-out shutting down the state sync driver. Timeout (ms): {0}")]
+    ShutdownTimeout(u64),
+    #[error("The storage synchronizer is shutting down: {0}")]
+    ShuttingDown(String),
     #[error("Unexpected storage error: {0}")]
     StorageError(String),
-    #[error("Synced beyond the target version. Committed version: {0}, target version: {1}")]
-    SyncedBeyondTarget(Version, Version),
+    #[error("The sync request was aborted: {0}")]
+    SyncRequestAborted(String),
+    #[error("A consensus sync request is already active with target version: {0}")]
+    SyncRequestSlotOccupied(Version),
+    #[error(
+        "The consensus sync request for version {0} was superseded by a request for version: {1}"
+    )]
+    SyncRequestSuperseded(Version, Version),
+    #[error(
+        "Timed-out waiting for the consensus sync request for version {0}. Elapsed time (ms): {1}, versions advanced: {2}"
+    )]
+    SyncRequestTimeout(Version, u64, u64),
+    #[error("Timed-out waiting for the sync status. Timeout (ms): {0}")]
+    SyncStatusTimeout(u64),
+    #[error(
+        "Synced beyond the target version. Committed version: {0}, target version: {1}. Elapsed time (ms): {2}, versions advanced: {3}"
+    )]
+    SyncedBeyondTarget(Version, Version, u64, u64),
+    #[error("A consensus notification was sent to a tooling driver: {0}")]
+    ToolingConsensusNotification(String),
     #[error("Verification error: {0}")]
     VerificationError(String),
+    #[error("The wait for version {0} to be committed was cancelled: {1}")]
+    VersionWaitCancelled(Version, String),
+    #[error("Timed-out waiting for the node to sync to version {0}. Timeout (ms): {1}")]
+    VersionWaitTimeout(Version, u64),
     #[error("Unexpected error: {0}")]
     UnexpectedError(String),
+    #[error(
+        "The configured waypoint doesn't match the ledger history in storage (is it for another network?). Configured waypoint: {0}, stored waypoint: {1}"
+    )]
+    WaypointMismatch(String, String),
 }
 
 impl Error {
@@ -50,21 +104,43 @@ impl Error {
         match self {
             Error::AlreadyBootstrapped(_) => "already_boostrapped",
             Error::AdvertisedDataError(_) => "advertised_data_error",
+            Error::BlockingCallInAsyncContext(_) => "blocking_call_in_async_context",
             Error::BootstrapNotComplete(_) => "bootstrap_not_complete",
             Error::CallbackSendFailed(_) => "callback_send_failed",
+            Error::CommitNotificationErrors(..) => "commit_notification_errors",
+            Error::ConflictingSyncRequest(_) => "conflicting_sync_request",
+            Error::ConsensusIsExecuting(_) => "consensus_is_executing",
+            Error::ConsensusReceiverDropped(_) => "consensus_receiver_dropped",
+            Error::ConsensusResponseTimeout(_) => "consensus_response_timeout",
             Error::CriticalDataStreamTimeout(_) => "critical_data_stream_timeout",
             Error::DataStreamNotificationTimeout(_) => "data_stream_notification_timeout",
+            Error::DriverUnavailable(_) => "driver_unavailable",
+            Error::DryRunConsensusNotification(_) => "dry_run_consensus_notification",
             Error::EventNotificationError(_) => "event_notification_error",
             Error::FullNodeConsensusNotification(_) => "full_node_consensus_notification",
+            Error::InitialConfigsUnavailable(_, _) => "initial_configs_unavailable",
             Error::IntegerOverflow(_) => "integer_overflow",
             Error::InvalidPayload(_) => "invalid_payload",
+            Error::InvalidSyncTarget(_) => "invalid_sync_target",
+            Error::ModeSwitchRejected(_) => "mode_switch_rejected",
             Error::NotifyMempoolError(_) => "notify_mempool_error",
             Error::OldSyncRequest(_, _) => "old_sync_request",
             Error::SenderDroppedError(_) => "sender_dropped_error",
+            Error::ShutdownTimeout(_) => "shutdown_timeout",
+            Error::ShuttingDown(_) => "shutting_down",
             Error::StorageError(_) => "storage_error",
-            Error::SyncedBeyondTarget(_, _) => "synced_beyond_target",
+            Error::SyncRequestAborted(_) => "sync_request_aborted",
+            Error::SyncRequestSlotOccupied(_) => "sync_request_slot_occupied",
+            Error::SyncRequestSuperseded(_, _) => "sync_request_superseded",
+            Error::SyncRequestTimeout(_, _, _) => "sync_request_timeout",
+            Error::SyncStatusTimeout(_) => "sync_status_timeout",
+            Error::SyncedBeyondTarget(_, _, _, _) => "synced_beyond_target",
+            Error::ToolingConsensusNotification(_) => "tooling_consensus_notification",
             Error::VerificationError(_) => "verification_error",
+            Error::VersionWaitCancelled(_, _) => "version_wait_cancelled",
+            Error::VersionWaitTimeout(_, _) => "version_wait_timeout",
             Error::UnexpectedError(_) => "unexpected_error",
+            Error::WaypointMismatch(_, _) => "waypoint_mismatch",
         }
     }
 }
@@ -8,13 +8,17 @@ mod continuous_syncer;
 mod driver;
 mod driver_client;
 pub mod driver_factory;
+mod dry_run_executor;
 mod error;
 mod logging;
 pub mod metadata_storage;
 pub mod metrics;
-mod notification_handlers;
+pub mod notification_handlers;
 mod storage_synchronizer;
 mod utils;
 
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
+
 #[cfg(test)]
 mod tests;
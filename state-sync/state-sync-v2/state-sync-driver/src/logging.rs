@@ -32,6 +32,7 @@ pub enum LogEntry {
     ClientNotification,
     ConsensusNotification,
     Driver,
+    EpochTransition,
     NotificationHandler,
     StorageSynchronizer,
     SynchronizerNotification,
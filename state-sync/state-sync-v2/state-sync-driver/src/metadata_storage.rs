@@ -7,7 +7,9 @@ use crate::{
 };
 use anyhow::{anyhow, Result};
 use aptos_logger::prelude::*;
-use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, transaction::TransactionOutputListWithProof,
+};
 use schemadb::{
     define_schema,
     schema::{KeyCodec, ValueCodec},
@@ -21,6 +23,15 @@ use std::{path::Path, sync::Arc, time::Instant};
 /// of the syncing process, where a failure may cause an inconsistent
 /// state to remain in the database on startup.
 pub trait MetadataStorageInterface {
+    /// Atomically marks the state snapshot sync at the finalization target as
+    /// complete and removes the finalization marker (see
+    /// `start_snapshot_finalization`). If no finalization marker is found for
+    /// the target, an error is returned.
+    fn complete_snapshot_finalization(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<(), Error>;
+
     /// Returns true iff a state snapshot was successfully committed for the
     /// specified target. If no snapshot progress is found, an error is returned.
     fn is_snapshot_sync_complete(
@@ -35,10 +46,37 @@ pub trait MetadataStorageInterface {
         target_ledger_info: &LedgerInfoWithSignatures,
     ) -> Result<u64, Error>;
 
+    /// Returns the last state value chunk fully committed by any state snapshot
+    /// sync that is still in progress (together with the snapshot target). If no
+    /// chunk has been committed, or the snapshot was finalized, None is returned.
+    fn get_snapshot_chunk_progress(&self) -> Result<Option<StateSnapshotChunkProgress>, Error>;
+
     /// Returns the target ledger info of any state snapshot sync that has previously
     /// started. If no snapshot sync started, None is returned.
     fn previous_snapshot_sync_target(&self) -> Result<Option<LedgerInfoWithSignatures>, Error>;
 
+    /// Returns the finalization of any state snapshot sync that was started
+    /// but never completed (e.g., because the node crashed while finalizing).
+    /// If no finalization is pending, None is returned.
+    fn pending_snapshot_finalization(&self) -> Result<Option<StateSnapshotFinalization>, Error>;
+
+    /// Persists a finalization marker (holding everything required to finalize
+    /// the state snapshot) once all state values have been written to storage.
+    /// This allows the finalization to be resumed if the node crashes before
+    /// `complete_snapshot_finalization` is called.
+    fn start_snapshot_finalization(
+        &self,
+        snapshot_finalization: StateSnapshotFinalization,
+    ) -> Result<(), Error>;
+
+    /// Records that the specified state value chunk has been fully committed.
+    /// The chunk progress and the last persisted state value index are updated
+    /// in a single write (so that a crash can't separate them).
+    fn update_snapshot_chunk_progress(
+        &self,
+        snapshot_chunk_progress: StateSnapshotChunkProgress,
+    ) -> Result<(), Error>;
+
     /// Updates the last persisted state value index for the state snapshot
     /// sync at the specified target ledger info.
     fn update_last_persisted_state_value_index(
@@ -93,23 +131,61 @@ impl PersistentMetadataStorage {
         Self { database }
     }
 
+    /// Returns the metadata value for the given key. Returns None if no value is found.
+    fn get_metadata_value(
+        &self,
+        metadata_key: MetadataKey,
+    ) -> Result<Option<MetadataValue>, Error> {
+        self.database
+            .get::<MetadataSchema>(&metadata_key)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to read metadata value for key: {:?}. Error: {:?}",
+                    metadata_key, error
+                ))
+            })
+    }
+
     /// Returns the existing snapshot sync progress. Returns None if no progress is found.
     fn get_snapshot_progress(&self) -> Result<Option<StateSnapshotProgress>, Error> {
-        let metadata_key = MetadataKey::StateSnapshotSync;
-        let maybe_metadata_value =
-            self.database
-                .get::<MetadataSchema>(&metadata_key)
-                .map_err(|error| {
-                    Error::StorageError(format!(
-                        "Failed to read metadata value for key: {:?}. Error: {:?}",
-                        metadata_key, error
-                    ))
-                })?;
-        match maybe_metadata_value {
-            Some(metadata_value) => {
-                let MetadataValue::StateSnapshotSync(snapshot_progress) = metadata_value;
+        match self.get_metadata_value(MetadataKey::StateSnapshotSync)? {
+            Some(MetadataValue::StateSnapshotSync(snapshot_progress)) => {
                 Ok(Some(snapshot_progress))
             }
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Found an unexpected metadata value for the snapshot sync progress: {:?}",
+                metadata_value
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the existing snapshot chunk progress. Returns None if no chunk
+    /// progress is found.
+    fn get_chunk_progress(&self) -> Result<Option<StateSnapshotChunkProgress>, Error> {
+        match self.get_metadata_value(MetadataKey::StateSnapshotChunkProgress)? {
+            Some(MetadataValue::StateSnapshotChunkProgress(snapshot_chunk_progress)) => {
+                Ok(Some(snapshot_chunk_progress))
+            }
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Found an unexpected metadata value for the snapshot chunk progress: {:?}",
+                metadata_value
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the existing snapshot finalization marker. Returns None if no
+    /// finalization is pending.
+    fn get_snapshot_finalization(&self) -> Result<Option<StateSnapshotFinalization>, Error> {
+        match self.get_metadata_value(MetadataKey::StateSnapshotFinalization)? {
+            Some(MetadataValue::StateSnapshotFinalization(snapshot_finalization)) => {
+                Ok(Some(snapshot_finalization))
+            }
+            Some(metadata_value) => Err(Error::StorageError(format!(
+                "Found an unexpected metadata value for the snapshot finalization: {:?}",
+                metadata_value
+            ))),
             None => Ok(None),
         }
     }
@@ -137,23 +213,52 @@ impl PersistentMetadataStorage {
         }
     }
 
-    /// Write the key value pair to the database
-    fn commit_key_value(
+    /// Verifies that any existing snapshot progress has the given target
+    fn verify_snapshot_progress_target(
         &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        if let Some(snapshot_progress) = self.get_snapshot_progress()? {
+            if target_ledger_info != &snapshot_progress.target_ledger_info {
+                return Err(Error::StorageError(format!("Failed to update the state snapshot progress! \
+                The given target does not match the previously stored target. Given target: {:?}, stored target: {:?}",
+                    target_ledger_info, snapshot_progress.target_ledger_info
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the key value pair to the given schema batch
+    fn put_key_value(
+        &self,
+        batch: &SchemaBatch,
         metadata_key: MetadataKey,
         metadata_value: MetadataValue,
     ) -> Result<(), Error> {
-        // Create the schema batch
-        let batch = SchemaBatch::new();
         batch
             .put::<MetadataSchema>(&metadata_key, &metadata_value)
             .map_err(|error| {
                 Error::StorageError(format!(
                     "Failed to batch put the metadata key and value. Key: {:?}, Value: {:?}. Error: {:?}", metadata_key, metadata_value, error
                 ))
-            })?;
+            })
+    }
 
-        // Write the schema batch to the database
+    /// Adds the key deletion to the given schema batch
+    fn delete_key(&self, batch: &SchemaBatch, metadata_key: MetadataKey) -> Result<(), Error> {
+        batch
+            .delete::<MetadataSchema>(&metadata_key)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to batch delete the metadata key: {:?}. Error: {:?}",
+                    metadata_key, error
+                ))
+            })
+    }
+
+    /// Write the schema batch to the database (atomically)
+    fn write_batch(&self, batch: SchemaBatch) -> Result<(), Error> {
         self.database.write_schemas(batch).map_err(|error| {
             Error::StorageError(format!(
                 "Failed to write the metadata schema. Error: {:?}",
@@ -161,9 +266,58 @@ impl PersistentMetadataStorage {
             ))
         })
     }
+
+    /// Write the key value pair to the database
+    fn commit_key_value(
+        &self,
+        metadata_key: MetadataKey,
+        metadata_value: MetadataValue,
+    ) -> Result<(), Error> {
+        let batch = SchemaBatch::new();
+        self.put_key_value(&batch, metadata_key, metadata_value)?;
+        self.write_batch(batch)
+    }
 }
 
 impl MetadataStorageInterface for PersistentMetadataStorage {
+    fn complete_snapshot_finalization(
+        &self,
+        target_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<(), Error> {
+        // Ensure the finalization marker exists for the given target
+        let snapshot_finalization = match self.get_snapshot_finalization()? {
+            Some(snapshot_finalization) => snapshot_finalization,
+            None => return Err(Error::StorageError(
+                "Failed to complete the snapshot finalization! No finalization marker was found!"
+                    .into(),
+            )),
+        };
+        if target_ledger_info != &snapshot_finalization.target_ledger_info {
+            return Err(Error::StorageError(format!("Failed to complete the snapshot finalization! \
+                The given target does not match the finalization target. Given target: {:?}, finalization target: {:?}",
+                target_ledger_info, snapshot_finalization.target_ledger_info
+            )));
+        }
+
+        // Mark the snapshot sync as complete and remove the finalization
+        // marker and chunk progress in a single write (so that a crash can't
+        // separate them).
+        let batch = SchemaBatch::new();
+        self.put_key_value(
+            &batch,
+            MetadataKey::StateSnapshotSync,
+            MetadataValue::StateSnapshotSync(StateSnapshotProgress {
+                last_persisted_state_value_index: snapshot_finalization
+                    .last_persisted_state_value_index,
+                snapshot_sync_completed: true,
+                target_ledger_info: target_ledger_info.clone(),
+            }),
+        )?;
+        self.delete_key(&batch, MetadataKey::StateSnapshotFinalization)?;
+        self.delete_key(&batch, MetadataKey::StateSnapshotChunkProgress)?;
+        self.write_batch(batch)
+    }
+
     fn is_snapshot_sync_complete(&self, target: &LedgerInfoWithSignatures) -> Result<bool, Error> {
         let snapshot_progress = self.get_snapshot_progress_at_target(target)?;
         Ok(snapshot_progress.snapshot_sync_completed)
@@ -177,12 +331,77 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
         Ok(snapshot_progress.last_persisted_state_value_index)
     }
 
+    fn get_snapshot_chunk_progress(&self) -> Result<Option<StateSnapshotChunkProgress>, Error> {
+        self.get_chunk_progress()
+    }
+
     fn previous_snapshot_sync_target(&self) -> Result<Option<LedgerInfoWithSignatures>, Error> {
         Ok(self
             .get_snapshot_progress()?
             .map(|snapshot_progress| snapshot_progress.target_ledger_info))
     }
 
+    fn pending_snapshot_finalization(&self) -> Result<Option<StateSnapshotFinalization>, Error> {
+        self.get_snapshot_finalization()
+    }
+
+    fn start_snapshot_finalization(
+        &self,
+        snapshot_finalization: StateSnapshotFinalization,
+    ) -> Result<(), Error> {
+        // Ensure that if any previous snapshot progress exists, it has the same target
+        let target_ledger_info = snapshot_finalization.target_ledger_info.clone();
+        self.verify_snapshot_progress_target(&target_ledger_info)?;
+
+        // Record the final state value index and the finalization marker in a
+        // single write (the snapshot is only marked complete once finalized).
+        let batch = SchemaBatch::new();
+        self.put_key_value(
+            &batch,
+            MetadataKey::StateSnapshotSync,
+            MetadataValue::StateSnapshotSync(StateSnapshotProgress {
+                last_persisted_state_value_index: snapshot_finalization
+                    .last_persisted_state_value_index,
+                snapshot_sync_completed: false,
+                target_ledger_info,
+            }),
+        )?;
+        self.put_key_value(
+            &batch,
+            MetadataKey::StateSnapshotFinalization,
+            MetadataValue::StateSnapshotFinalization(snapshot_finalization),
+        )?;
+        self.write_batch(batch)
+    }
+
+    fn update_snapshot_chunk_progress(
+        &self,
+        snapshot_chunk_progress: StateSnapshotChunkProgress,
+    ) -> Result<(), Error> {
+        // Ensure that if any previous snapshot progress exists, it has the same target
+        let target_ledger_info = snapshot_chunk_progress.target_ledger_info.clone();
+        self.verify_snapshot_progress_target(&target_ledger_info)?;
+
+        // Record the last persisted state value index and the chunk progress in a single write
+        let batch = SchemaBatch::new();
+        self.put_key_value(
+            &batch,
+            MetadataKey::StateSnapshotSync,
+            MetadataValue::StateSnapshotSync(StateSnapshotProgress {
+                last_persisted_state_value_index: snapshot_chunk_progress
+                    .last_persisted_state_value_index,
+                snapshot_sync_completed: false,
+                target_ledger_info,
+            }),
+        )?;
+        self.put_key_value(
+            &batch,
+            MetadataKey::StateSnapshotChunkProgress,
+            MetadataValue::StateSnapshotChunkProgress(snapshot_chunk_progress),
+        )?;
+        self.write_batch(batch)
+    }
+
     fn update_last_persisted_state_value_index(
         &self,
         target_ledger_info: &LedgerInfoWithSignatures,
@@ -190,14 +409,7 @@ impl MetadataStorageInterface for PersistentMetadataStorage {
         snapshot_sync_completed: bool,
     ) -> Result<(), Error> {
         // Ensure that if any previous snapshot progress exists, it has the same target
-        if let Some(snapshot_progress) = self.get_snapshot_progress()? {
-            if target_ledger_info != &snapshot_progress.target_ledger_info {
-                return Err(Error::StorageError(format!("Failed to update the last persisted state value index! \
-                The given target does not match the previously stored target. Given target: {:?}, stored target: {:?}",
-                    target_ledger_info, snapshot_progress.target_ledger_info
-                )));
-            }
-        }
+        self.verify_snapshot_progress_target(target_ledger_info)?;
 
         // Create the key/value pair
         let metadata_key = MetadataKey::StateSnapshotSync;
@@ -220,6 +432,28 @@ pub struct StateSnapshotProgress {
     pub snapshot_sync_completed: bool,
 }
 
+/// A marker recording that all state values of a state snapshot have been
+/// written to storage and that the snapshot is being finalized. It holds
+/// everything required to (re-)run the finalization after a crash.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotFinalization {
+    pub target_ledger_info: LedgerInfoWithSignatures,
+    pub target_output_with_proof: TransactionOutputListWithProof,
+    pub epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
+    pub last_persisted_state_value_index: u64,
+}
+
+/// A small progress record for a state snapshot sync, written each time a
+/// state value chunk is fully committed. This allows the bootstrapper to
+/// resume the snapshot sync after the last committed chunk (without guessing).
+/// The record is removed once the snapshot is finalized.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotChunkProgress {
+    pub target_ledger_info: LedgerInfoWithSignatures,
+    pub last_committed_chunk_index: u64,
+    pub last_persisted_state_value_index: u64,
+}
+
 /// The raw schema format used by the database
 pub mod database_schema {
     use super::*;
@@ -237,7 +471,9 @@ pub mod database_schema {
     #[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
     #[repr(u8)]
     pub enum MetadataKey {
-        StateSnapshotSync, // A state snapshot sync that was started
+        StateSnapshotSync,          // A state snapshot sync that was started
+        StateSnapshotFinalization,  // A state snapshot that is being finalized
+        StateSnapshotChunkProgress, // The last state value chunk committed by a state snapshot sync
     }
 
     /// A metadata value that can be inserted into the database
@@ -245,6 +481,8 @@ pub mod database_schema {
     #[repr(u8)]
     pub enum MetadataValue {
         StateSnapshotSync(StateSnapshotProgress), // A state snapshot sync progress marker
+        StateSnapshotFinalization(StateSnapshotFinalization), // A state snapshot finalization marker
+        StateSnapshotChunkProgress(StateSnapshotChunkProgress), // A state snapshot chunk progress marker
     }
 
     impl KeyCodec<MetadataSchema> for MetadataKey {
@@ -1,16 +1,149 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_infallible::Mutex;
 use aptos_metrics_core::{
-    register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// The label that separates the metrics of each driver instance in the process
+const DRIVER_INSTANCE_LABEL: &str = "driver";
+
+/// The maximum number of distinct instance labels (per kind of driver) in the
+/// process. Instance ids wrap around this bound, so that the interned labels
+/// (and the metric series) remain bounded, even if drivers are repeatedly
+/// created and dropped (e.g., by tooling and tests).
+pub(crate) const MAX_DRIVER_INSTANCE_LABELS: u64 = 256;
+
+/// The prefix shared by the names of all state sync driver metrics
+const METRIC_NAME_PREFIX: &str = "aptos_state_sync_";
+
+/// The number of driver instances created in the process (used to assign
+/// each driver instance a unique metric label).
+static NUM_DRIVER_INSTANCES: AtomicU64 = AtomicU64::new(0);
+
+/// The number of dry-run driver instances created in the process. These are
+/// counted separately, so that dry-run drivers never take the default label.
+static NUM_DRY_RUN_DRIVER_INSTANCES: AtomicU64 = AtomicU64::new(0);
+
+/// The interned driver instance labels. Metric label values are stored for the
+/// lifetime of the process, so each distinct label is only allocated once.
+static INTERNED_INSTANCE_LABELS: Lazy<Mutex<HashMap<String, &'static str>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// Useful metric labels
+pub const BLOCK_METADATA_TRANSACTION: &str = "block_metadata_transaction";
+pub const CANCELLED_CHUNK_EXECUTION: &str = "cancelled_chunk_execution";
+pub const CHUNK_CANCELLATION_TIMEOUT: &str = "chunk_cancellation_timeout";
+pub const CLOSED_COMMIT_EVENT_SUBSCRIPTION: &str = "closed_commit_event_subscription";
+pub const CLOSED_EVENT_SUBSCRIPTION: &str = "closed_subscription";
+pub const COALESCED_COMMIT_NOTIFICATION: &str = "coalesced_commit_notification";
+pub const COMMIT_NOTIFICATION_CHANNEL_OCCUPANCY: &str = "commit_notification_channel_occupancy";
+pub const COMMIT_NOTIFICATION_GAP: &str = "commit_notification_gap";
+pub const COMMIT_RETRY: &str = "commit_retry";
+pub const COMMIT_SUMMARY_EVENTS: &str = "events";
+pub const COMMIT_SUMMARY_MEMPOOL: &str = "mempool";
+pub const COMMIT_SUMMARY_RECONFIG_EVENTS: &str = "reconfig_events";
+pub const COMMIT_SUMMARY_USER_TRANSACTIONS: &str = "user_transactions";
+pub const COMMIT_SUBSCRIBER_FAILURE: &str = "failure";
+pub const COMMIT_SUBSCRIBER_SUCCESS: &str = "success";
+pub const CONSENSUS_RECEIVER_DROPPED: &str = "consensus_receiver_dropped";
+pub const CONSENSUS_RESPONSE_TIMEOUT: &str = "consensus_response_timeout";
+pub const DISCARDED_STORAGE_DATA_CHUNK: &str = "discarded_storage_data_chunk";
 pub const DRIVER_CLIENT_NOTIFICATION: &str = "driver_client_notification";
 pub const DRIVER_CONSENSUS_COMMIT_NOTIFICATION: &str = "driver_consensus_commit_notification";
 pub const DRIVER_CONSENSUS_SYNC_NOTIFICATION: &str = "driver_consensus_sync_notification";
+pub const DRY_RUN_VERIFIED_VERSIONS: &str = "dry_run_verified_versions";
+pub const DUPLICATE_COMMIT_NOTIFICATION: &str = "duplicate_commit_notification";
+pub const DUPLICATE_ERROR_NOTIFICATION: &str = "duplicate_error_notification";
+pub const DUPLICATE_MEMPOOL_NOTIFICATION: &str = "duplicate_mempool_notification";
+pub const DUPLICATE_TRANSACTION_CHUNK: &str = "duplicate_transaction_chunk";
+pub const EPOCH_STATE_CACHE_INVALIDATION: &str = "epoch_state_cache_invalidation";
+pub const EPOCH_TRANSITION: &str = "epoch_transition";
+pub const EVENT_ROOT_HASH_MISMATCH: &str = "event_root_hash_mismatch";
+pub const GENESIS_TRANSACTION: &str = "genesis_transaction";
+pub const LAST_COMMIT_EPOCH: &str = "last_commit_epoch";
+pub const LAST_COMMIT_VERSION: &str = "last_commit_version";
+pub const LOWEST_AVAILABLE_VERSION: &str = "lowest_available_version";
+pub const MAX_PENDING_DATA_CHUNKS: &str = "max_pending_data_chunks";
+pub const MAX_PENDING_EXECUTED_CHUNKS: &str = "max_pending_executed_chunks";
+pub const MEMPOOL_NOTIFICATION_DROPPED: &str = "mempool_notification_dropped";
+pub const MEMPOOL_NOTIFICATION_RETRY: &str = "mempool_notification_retry";
+pub const MEMPOOL_NOTIFICATION_SEND_FAILURE: &str = "send_failure";
+pub const MEMPOOL_NOTIFICATION_SUCCESS: &str = "success";
+pub const MEMPOOL_NOTIFICATION_TIMEOUT: &str = "timeout";
+pub const NUM_COMMITTER_THREADS: &str = "num_committer_threads";
+pub const NUM_EXECUTOR_THREADS: &str = "num_executor_threads";
+pub const OUTPUT_FALLBACK_REVERTED: &str = "output_fallback_reverted";
+pub const OUTPUT_FALLBACK_STARTED: &str = "output_fallback_started";
+pub const PENDING_EVENT_NOTIFICATIONS: &str = "pending_event_notifications";
+pub const POST_RESET_COMMIT_NOTIFICATION: &str = "post_reset_commit_notification";
+pub const PRUNING_PAUSED: &str = "pruning_paused";
+pub const PRUNING_PAUSE_EXPIRED: &str = "pruning_pause_expired";
+pub const PRUNING_RESUMED: &str = "pruning_resumed";
+pub const RESYNCED_RECONFIG_SUBSCRIPTION: &str = "resynced_subscription";
+pub const SECS_SINCE_LAST_COMMIT: &str = "secs_since_last_commit";
+pub const SKIPPED_EVENT_NOTIFICATION: &str = "skipped_event_notification";
+pub const SKIPPED_MEMPOOL_NOTIFICATION: &str = "skipped_mempool_notification";
+pub const SLOW_COMMIT_EVENT_SUBSCRIPTION: &str = "slow_commit_event_subscription";
+pub const SLOW_TRANSACTION_CHUNK: &str = "slow_transaction_chunk";
+pub const STALE_SYNC_REQUEST: &str = "stale_sync_request";
+pub const STATE_CHECKPOINT_TRANSACTION: &str = "state_checkpoint_transaction";
+pub const STATE_SNAPSHOT_BYTES: &str = "state_snapshot_bytes";
+pub const STATE_SNAPSHOT_STATE_VALUES: &str = "state_snapshot_state_values";
+pub const STORAGE_SYNCHRONIZER_BACKPRESSURED: &str = "storage_synchronizer_backpressured";
+pub const STORAGE_SYNCHRONIZER_BYTES_QUEUED: &str = "storage_synchronizer_bytes_queued";
+pub const STORAGE_SYNCHRONIZER_CHUNKS_COMMITTING: &str = "storage_synchronizer_chunks_committing";
+pub const STORAGE_SYNCHRONIZER_CHUNKS_EXECUTING: &str = "storage_synchronizer_chunks_executing";
+pub const STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_COMMIT: &str =
+    "storage_synchronizer_chunks_queued_for_commit";
+pub const STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_EXECUTION: &str =
+    "storage_synchronizer_chunks_queued_for_execution";
 pub const STORAGE_SYNCHRONIZER_PENDING_DATA: &str = "storage_synchronizer_pending_data";
+pub const STREAM_INITIALIZATION_FAILURE: &str = "failure";
+pub const STREAM_INITIALIZATION_RETRY: &str = "stream_initialization_retry";
+pub const STREAM_INITIALIZATION_SUCCESS: &str = "success";
+pub const SYNC_LAG_SECONDS: &str = "sync_lag_seconds";
+pub const TRANSIENT_ERROR_NOTIFICATION: &str = "transient_error_notification";
+pub const TRIMMED_MEMPOOL_NOTIFICATION: &str = "trimmed_mempool_notification";
+
+/// The transaction chunk size buckets (i.e., the max number of versions in
+/// each bucket, and the bucket label). Larger chunks use `LARGEST_CHUNK_SIZE_BUCKET`.
+const CHUNK_SIZE_BUCKETS: [(u64, &str); 5] = [
+    (10, "1-10"),
+    (100, "11-100"),
+    (500, "101-500"),
+    (1000, "501-1000"),
+    (5000, "1001-5000"),
+];
+const LARGEST_CHUNK_SIZE_BUCKET: &str = "5001+";
+
+/// An enum of the data types written to storage by the storage synchronizer
+#[derive(Clone, Copy, Debug)]
+pub enum CommittedDataType {
+    Events,       // The events emitted by the committed transactions
+    Outputs,      // The transaction outputs (i.e., write sets) of applied chunks
+    StateValues,  // The state values of committed state snapshot chunks
+    Transactions, // The committed transactions
+}
+
+impl CommittedDataType {
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            CommittedDataType::Events => "events",
+            CommittedDataType::Outputs => "outputs",
+            CommittedDataType::StateValues => "state_values",
+            CommittedDataType::Transactions => "transactions",
+        }
+    }
+}
 
 /// An enum representing the component currently executing
 pub enum ExecutingComponent {
@@ -29,6 +162,14 @@ impl ExecutingComponent {
     }
 }
 
+/// A single metric series of a driver instance, as scraped from the registry
+#[derive(Clone, Debug, PartialEq)]
+pub struct MetricSample {
+    pub name: String,                     // The name of the metric family
+    pub labels: BTreeMap<String, String>, // The labels of the series (excluding the instance label)
+    pub value: f64, // The counter or gauge value (or the number of histogram observations)
+}
+
 /// An enum of storage synchronizer operations performed by state sync
 pub enum StorageSynchronizerOperations {
     AppliedTransactionOutputs, // Applied a chunk of transactions outputs.
@@ -57,7 +198,70 @@ pub static BOOTSTRAPPER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_state_sync_bootstrapper_errors",
         "Counters related to state sync bootstrapper errors",
-        &["error_label"]
+        &[DRIVER_INSTANCE_LABEL, "error_label"]
+    )
+    .unwrap()
+});
+
+/// Counters for the bytes written to storage by the storage synchronizer
+pub static COMMITTED_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_committed_bytes",
+        "Counters for the bytes written to storage by the storage synchronizer (by data type)",
+        &[DRIVER_INSTANCE_LABEL, "data_type"]
+    )
+    .unwrap()
+});
+
+/// Counters for the transaction chunks committed by the storage synchronizer
+/// (by whether the chunk was applied or executed).
+pub static COMMITTED_TRANSACTION_CHUNKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_committed_transaction_chunks",
+        "Counters for the transaction chunks committed by the storage synchronizer",
+        &[DRIVER_INSTANCE_LABEL, "apply_or_execute"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the bytes written to storage by each commit (i.e., commit
+/// batch or state value chunk). Buckets range from 1 KiB to 4 GiB.
+pub static COMMIT_BYTES: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_commit_bytes",
+        "Bytes written to storage by each commit (by data type)",
+        &[DRIVER_INSTANCE_LABEL, "data_type"],
+        exponential_buckets(1024.0, 4.0, 12).unwrap()
+    )
+    .unwrap()
+});
+
+/// Counters for the transactions and events notified downstream after commits
+pub static COMMIT_SUMMARY_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_commit_summary_counters",
+        "Counters for the transactions and events notified downstream after commits (by commit origin)",
+        &[DRIVER_INSTANCE_LABEL, "origin", "label"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the time (secs) spent handling downstream commit notifications
+pub static COMMIT_SUMMARY_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_commit_summary_latency",
+        "Time (secs) spent handling downstream commit notifications (by commit origin)",
+        &[DRIVER_INSTANCE_LABEL, "origin", "label"]
+    )
+    .unwrap()
+});
+
+/// Counters for the results of commit subscriber notifications
+pub static COMMIT_SUBSCRIBER_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_commit_subscriber_notifications",
+        "Counters for the results of commit subscriber notifications (by subscriber)",
+        &[DRIVER_INSTANCE_LABEL, "subscriber", "result"]
     )
     .unwrap()
 });
@@ -67,7 +271,17 @@ pub static CONTINUOUS_SYNCER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_state_sync_continuous_syncer_errors",
         "Counters related to state sync continuous syncer errors",
-        &["error_label"]
+        &[DRIVER_INSTANCE_LABEL, "error_label"]
+    )
+    .unwrap()
+});
+
+/// Counters for the data stream initialization attempts (by request type and result)
+pub static DATA_STREAM_INITIALIZATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_data_stream_initializations",
+        "Counters for the data stream initialization attempts (by request type and result)",
+        &[DRIVER_INSTANCE_LABEL, "request_type", "result"]
     )
     .unwrap()
 });
@@ -77,7 +291,18 @@ pub static DRIVER_COUNTERS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_state_sync_driver_counters",
         "Counters related to the state sync driver",
-        &["label"]
+        &[DRIVER_INSTANCE_LABEL, "label"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the time (secs) spent in each epoch (i.e., between epoch transitions)
+pub static EPOCH_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_epoch_duration",
+        "Time (secs) spent in each epoch (i.e., between epoch transitions)",
+        &[DRIVER_INSTANCE_LABEL],
+        exponential_buckets(1.0, 2.0, 18).unwrap()
     )
     .unwrap()
 });
@@ -87,7 +312,32 @@ pub static EPOCH_STATE: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_state_sync_epoch_state_gauges",
         "Gauges related to the storage synchronizer",
-        &["epoch", "validator_address", "validator_weight"]
+        &[
+            DRIVER_INSTANCE_LABEL,
+            "epoch",
+            "validator_address",
+            "validator_weight"
+        ]
+    )
+    .unwrap()
+});
+
+/// Histogram of the latency (secs) between enqueuing and delivering event notifications
+pub static EVENT_NOTIFICATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_event_notification_latency",
+        "Latency (secs) between enqueuing and delivering event notifications",
+        &[DRIVER_INSTANCE_LABEL]
+    )
+    .unwrap()
+});
+
+/// Counters for closed event subscriptions and resynced reconfig subscriptions
+pub static EVENT_SUBSCRIPTION_RECOVERY: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_event_subscription_recovery",
+        "Counters for closed event subscriptions and resynced reconfig subscriptions",
+        &[DRIVER_INSTANCE_LABEL, "label"]
     )
     .unwrap()
 });
@@ -97,7 +347,117 @@ pub static EXECUTING_COMPONENT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_state_sync_executing_component_counters",
         "Counters related to the currently executing component",
-        &["label"]
+        &[DRIVER_INSTANCE_LABEL, "label"]
+    )
+    .unwrap()
+});
+
+/// Gauges for the last commit (made by either consensus or state sync)
+pub static LAST_COMMIT_STATE: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_sync_last_commit_state",
+        "Gauges for the epoch, version, age (secs) and sync lag (secs) of the last commit",
+        &[DRIVER_INSTANCE_LABEL, "label"]
+    )
+    .unwrap()
+});
+
+/// Gauges for the versions available in local storage
+pub static LOCAL_STORAGE_VERSIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_sync_local_storage_versions",
+        "Gauges for the versions available in local storage (e.g., the lowest unpruned version)",
+        &[DRIVER_INSTANCE_LABEL, "label"]
+    )
+    .unwrap()
+});
+
+/// Counters for committed transactions filtered out of mempool notifications
+pub static MEMPOOL_FILTERED_TRANSACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_mempool_filtered_transactions",
+        "Counters for committed transactions filtered out of mempool notifications (by type)",
+        &[DRIVER_INSTANCE_LABEL, "transaction_type"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the round-trip time (secs) of mempool commit notifications
+pub static MEMPOOL_NOTIFICATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_mempool_notification_latency",
+        "Round-trip time (secs) of mempool commit notifications (by commit origin)",
+        &[DRIVER_INSTANCE_LABEL, "origin"]
+    )
+    .unwrap()
+});
+
+/// Counters for the results of mempool commit notifications
+pub static MEMPOOL_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_mempool_notifications",
+        "Counters for the results of mempool commit notifications (by commit origin)",
+        &[DRIVER_INSTANCE_LABEL, "origin", "result"]
+    )
+    .unwrap()
+});
+
+/// Gauges for the mempool commit notifications currently in flight
+pub static MEMPOOL_NOTIFICATIONS_IN_FLIGHT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_sync_mempool_notifications_in_flight",
+        "Gauges for the mempool commit notifications currently in flight (by commit origin)",
+        &[DRIVER_INSTANCE_LABEL, "origin"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the time (secs) taken to run each post-commit hook (by hook name)
+pub static POST_COMMIT_HOOK_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_post_commit_hook_latency",
+        "Time (secs) taken to run each post-commit hook (by hook name)",
+        &[DRIVER_INSTANCE_LABEL, "hook_name"]
+    )
+    .unwrap()
+});
+
+/// Counters for the post-commit hooks that panicked (by hook name)
+pub static POST_COMMIT_HOOK_PANICS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_post_commit_hook_panics",
+        "Counters for the post-commit hooks that panicked (by hook name)",
+        &[DRIVER_INSTANCE_LABEL, "hook_name"]
+    )
+    .unwrap()
+});
+
+/// Counters for the progress of state snapshot synchronization
+pub static STATE_SNAPSHOT_PROGRESS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_state_snapshot_progress",
+        "Counters for the state values and bytes committed while syncing a state snapshot",
+        &[DRIVER_INSTANCE_LABEL, "label"]
+    )
+    .unwrap()
+});
+
+/// Histogram of the time (secs) taken to write state value chunks to storage
+pub static STATE_VALUE_CHUNK_APPLY_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_state_value_chunk_apply_latency",
+        "Time (secs) taken to write state value chunks to storage",
+        &[DRIVER_INSTANCE_LABEL]
+    )
+    .unwrap()
+});
+
+/// Histogram of the time (secs) taken by storage to verify state value chunk proofs
+pub static STATE_VALUE_CHUNK_VERIFICATION_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_state_value_chunk_verification_latency",
+        "Time (secs) taken by storage to verify state value chunk proofs",
+        &[DRIVER_INSTANCE_LABEL]
     )
     .unwrap()
 });
@@ -107,7 +467,17 @@ pub static STORAGE_SYNCHRONIZER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_state_sync_storage_synchronizer_errors",
         "Counters related to storage synchronizer errors",
-        &["error_label"]
+        &[DRIVER_INSTANCE_LABEL, "error_label"]
+    )
+    .unwrap()
+});
+
+/// Gauges for the (effective) concurrency settings of the storage synchronizer
+pub static STORAGE_SYNCHRONIZER_CONCURRENCY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_state_sync_storage_synchronizer_concurrency",
+        "Gauges for the concurrency settings of the storage synchronizer",
+        &[DRIVER_INSTANCE_LABEL, "label"]
     )
     .unwrap()
 });
@@ -117,7 +487,7 @@ pub static STORAGE_SYNCHRONIZER_GAUGES: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_state_sync_storage_synchronizer_gauges",
         "Gauges related to the storage synchronizer",
-        &["label"]
+        &[DRIVER_INSTANCE_LABEL, "label"]
     )
     .unwrap()
 });
@@ -127,39 +497,280 @@ pub static STORAGE_SYNCHRONIZER_OPERATIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
         "aptos_state_sync_version",
         "The versions processed by the storage synchronizer operations",
-        &["type"]
+        &[DRIVER_INSTANCE_LABEL, "type"]
     )
     .unwrap()
 });
 
-/// Increments the given counter with the provided label values.
-pub fn increment_counter(counter: &Lazy<IntCounterVec>, label: &str) {
-    counter.with_label_values(&[label]).inc();
-}
+/// Counters for the data streams terminated due to storage synchronizer errors
+/// (by the component that owned the stream and the stage that failed).
+pub static TERMINATED_DATA_STREAMS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_state_sync_terminated_data_streams",
+        "Counters for the data streams terminated due to storage synchronizer errors",
+        &[DRIVER_INSTANCE_LABEL, "component", "stage"]
+    )
+    .unwrap()
+});
 
-/// Increments the gauge with the specific label by the given delta
-pub fn increment_gauge(gauge: &Lazy<IntGaugeVec>, label: &str, delta: u64) {
-    gauge.with_label_values(&[label]).add(delta as i64);
-}
+/// Histogram of the time (secs) taken to commit transaction chunks
+pub static TRANSACTION_CHUNK_COMMIT_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_transaction_chunk_commit_latency",
+        "Time (secs) taken to commit transaction chunks (by apply or execute, and chunk size)",
+        &[DRIVER_INSTANCE_LABEL, "apply_or_execute", "chunk_size"]
+    )
+    .unwrap()
+});
 
-/// Decrements the gauge with the specific label by the given delta
-pub fn decrement_gauge(gauge: &Lazy<IntGaugeVec>, label: &str, delta: u64) {
-    gauge.with_label_values(&[label]).sub(delta as i64);
+/// Histogram of the time (secs) taken to execute or apply transaction chunks
+pub static TRANSACTION_CHUNK_EXECUTE_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_state_sync_transaction_chunk_execute_latency",
+        "Time (secs) taken to execute or apply transaction chunks (by apply or execute, and chunk size)",
+        &[DRIVER_INSTANCE_LABEL, "apply_or_execute", "chunk_size"]
+    )
+    .unwrap()
+});
+
+/// Returns the size bucket label for a transaction chunk with the given number of versions
+pub fn get_chunk_size_bucket(num_versions: u64) -> &'static str {
+    CHUNK_SIZE_BUCKETS
+        .iter()
+        .find(|(max_num_versions, _)| num_versions <= *max_num_versions)
+        .map(|(_, bucket_label)| *bucket_label)
+        .unwrap_or(LARGEST_CHUNK_SIZE_BUCKET)
 }
 
-/// Reads the gauge with the specific label
-pub fn read_gauge(gauge: &Lazy<IntGaugeVec>, label: &str) -> i64 {
-    gauge.with_label_values(&[label]).get()
+/// Returns the interned (static) copy of the given driver instance label
+fn intern_instance_label(instance_label: String) -> &'static str {
+    let mut interned_instance_labels = INTERNED_INSTANCE_LABELS.lock();
+    *interned_instance_labels
+        .entry(instance_label)
+        .or_insert_with_key(|instance_label| Box::leak(instance_label.clone().into_boxed_str()))
 }
 
-/// Sets the gauge with the specific label to the given value
-pub fn set_gauge(gauge: &Lazy<IntGaugeVec>, label: &str, value: u64) {
-    gauge.with_label_values(&[label]).set(value as i64);
+/// A handle to the metrics of a single driver instance. The metrics are only
+/// registered once per process, and each driver instance updates its own
+/// series (using a unique driver instance label, for up to
+/// `MAX_DRIVER_INSTANCE_LABELS` instances). This allows multiple drivers
+/// to coexist in the same process (e.g., in tests and tooling).
+///
+/// Note: the default handle uses an empty instance label, which Prometheus
+/// treats as a missing label. Thus, the series of the first driver in the
+/// process are identical to those of a single-driver process.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DriverMetrics {
+    instance_label: &'static str,
 }
 
-/// Sets the gauge for the epoch state
-pub fn set_epoch_state_gauge(epoch: &str, validator_address: &str, validator_weight: &str) {
-    EPOCH_STATE
-        .with_label_values(&[epoch, validator_address, validator_weight])
-        .set(1);
+impl DriverMetrics {
+    /// Returns a handle for a new driver instance in the process. The first
+    /// instance uses the default (empty) instance label.
+    pub fn new_instance() -> Self {
+        let instance_id = NUM_DRIVER_INSTANCES.fetch_add(1, Ordering::Relaxed);
+        if instance_id == 0 {
+            return Self::default();
+        }
+
+        // The default label is never reused, so the later ids wrap around 1..=MAX
+        let label_id = 1 + (instance_id - 1) % MAX_DRIVER_INSTANCE_LABELS;
+        let instance_label = intern_instance_label(label_id.to_string());
+        Self { instance_label }
+    }
+
+    /// Returns a handle for a new dry-run driver instance in the process. Dry-run
+    /// instances are always labelled with a "dry_run_" prefix, so that their
+    /// series are never mistaken for the progress of a real driver.
+    pub fn new_dry_run_instance() -> Self {
+        let instance_id = NUM_DRY_RUN_DRIVER_INSTANCES.fetch_add(1, Ordering::Relaxed);
+        let label_id = instance_id % MAX_DRIVER_INSTANCE_LABELS;
+        let instance_label = intern_instance_label(format!("dry_run_{}", label_id));
+        Self { instance_label }
+    }
+
+    /// Returns the instance label of the driver
+    pub fn get_instance_label(&self) -> &'static str {
+        self.instance_label
+    }
+
+    /// Returns the counter with the given label values (for this driver instance)
+    pub fn counter(&self, counter: &Lazy<IntCounterVec>, label_values: &[&str]) -> IntCounter {
+        counter.with_label_values(&self.get_label_values(label_values))
+    }
+
+    /// Returns the gauge with the given label values (for this driver instance)
+    pub fn gauge(&self, gauge: &Lazy<IntGaugeVec>, label_values: &[&str]) -> IntGauge {
+        gauge.with_label_values(&self.get_label_values(label_values))
+    }
+
+    /// Returns the histogram with the given label values (for this driver instance)
+    pub fn histogram(&self, histogram: &Lazy<HistogramVec>, label_values: &[&str]) -> Histogram {
+        histogram.with_label_values(&self.get_label_values(label_values))
+    }
+
+    /// Prepends the driver instance label to the given label values
+    fn get_label_values<'a>(&self, label_values: &[&'a str]) -> Vec<&'a str> {
+        let mut instance_label_values = Vec::with_capacity(label_values.len() + 1);
+        instance_label_values.push(self.instance_label);
+        instance_label_values.extend_from_slice(label_values);
+        instance_label_values
+    }
+
+    /// Increments the given counter with the provided label values.
+    pub fn increment_counter(&self, counter: &Lazy<IntCounterVec>, label: &str) {
+        self.counter(counter, &[label]).inc();
+    }
+
+    /// Reads the counter with the specific label
+    pub fn read_counter(&self, counter: &Lazy<IntCounterVec>, label: &str) -> u64 {
+        self.counter(counter, &[label]).get()
+    }
+
+    /// Increments the gauge with the specific label by the given delta
+    pub fn increment_gauge(&self, gauge: &Lazy<IntGaugeVec>, label: &str, delta: u64) {
+        self.gauge(gauge, &[label]).add(delta as i64);
+    }
+
+    /// Decrements the gauge with the specific label by the given delta
+    pub fn decrement_gauge(&self, gauge: &Lazy<IntGaugeVec>, label: &str, delta: u64) {
+        self.gauge(gauge, &[label]).sub(delta as i64);
+    }
+
+    /// Reads the gauge with the specific label
+    pub fn read_gauge(&self, gauge: &Lazy<IntGaugeVec>, label: &str) -> i64 {
+        self.gauge(gauge, &[label]).get()
+    }
+
+    /// Sets the gauge with the specific label to the given value
+    pub fn set_gauge(&self, gauge: &Lazy<IntGaugeVec>, label: &str, value: u64) {
+        self.gauge(gauge, &[label]).set(value as i64);
+    }
+
+    /// Updates the latency and result metrics for a completed mempool notification
+    pub fn observe_mempool_notification(&self, origin: &str, result: &str, latency: Duration) {
+        self.histogram(&MEMPOOL_NOTIFICATION_LATENCY, &[origin])
+            .observe(latency.as_secs_f64());
+        self.counter(&MEMPOOL_NOTIFICATIONS, &[origin, result])
+            .inc();
+    }
+
+    /// Increments the data stream initialization counter for the given request type and result
+    pub fn increment_stream_initialization(&self, request_type: &str, result: &str) {
+        self.counter(&DATA_STREAM_INITIALIZATIONS, &[request_type, result])
+            .inc();
+    }
+
+    /// Increments the terminated data streams counter for the given component and failed stage
+    pub fn increment_terminated_stream(&self, component: &str, stage: &str) {
+        self.counter(&TERMINATED_DATA_STREAMS, &[component, stage])
+            .inc();
+    }
+
+    /// Observes the latency of a transaction chunk stage (e.g., execute or commit)
+    pub fn observe_transaction_chunk_latency(
+        &self,
+        histogram: &Lazy<HistogramVec>,
+        apply_or_execute: &str,
+        num_versions: u64,
+        latency: Duration,
+    ) {
+        self.histogram(
+            histogram,
+            &[apply_or_execute, get_chunk_size_bucket(num_versions)],
+        )
+        .observe(latency.as_secs_f64());
+    }
+
+    /// Updates the committed bytes metrics for a single commit of the given data type
+    pub fn observe_committed_bytes(&self, data_type: CommittedDataType, num_bytes: u64) {
+        self.counter(&COMMITTED_BYTES, &[data_type.get_label()])
+            .inc_by(num_bytes);
+        self.histogram(&COMMIT_BYTES, &[data_type.get_label()])
+            .observe(num_bytes as f64);
+    }
+
+    /// Updates the state snapshot progress metrics for a committed state value chunk
+    pub fn observe_state_value_chunk(
+        &self,
+        num_state_values: u64,
+        num_bytes: u64,
+        apply_duration: Duration,
+        verification_duration: Duration,
+    ) {
+        self.counter(&STATE_SNAPSHOT_PROGRESS, &[STATE_SNAPSHOT_STATE_VALUES])
+            .inc_by(num_state_values);
+        self.counter(&STATE_SNAPSHOT_PROGRESS, &[STATE_SNAPSHOT_BYTES])
+            .inc_by(num_bytes);
+        self.observe_committed_bytes(CommittedDataType::StateValues, num_bytes);
+        self.histogram(&STATE_VALUE_CHUNK_APPLY_LATENCY, &[])
+            .observe(apply_duration.as_secs_f64());
+        self.histogram(&STATE_VALUE_CHUNK_VERIFICATION_LATENCY, &[])
+            .observe(verification_duration.as_secs_f64());
+    }
+
+    /// Scrapes the metric registry and returns the state sync series of this
+    /// driver instance (sorted by name and labels). This allows tests and
+    /// tooling to verify the metrics emitted by a driver.
+    pub fn scrape_metrics(&self) -> Vec<MetricSample> {
+        let mut metric_samples = vec![];
+        for metric_family in aptos_metrics_core::gather() {
+            let name = metric_family.get_name();
+            if !name.starts_with(METRIC_NAME_PREFIX) {
+                continue;
+            }
+
+            for metric in metric_family.get_metric() {
+                // Separate the instance label from the other labels. Empty
+                // label values might be missing entirely.
+                let mut instance_label = "";
+                let mut labels = BTreeMap::new();
+                for label_pair in metric.get_label() {
+                    if label_pair.get_name() == DRIVER_INSTANCE_LABEL {
+                        instance_label = label_pair.get_value();
+                    } else {
+                        labels.insert(
+                            label_pair.get_name().to_string(),
+                            label_pair.get_value().to_string(),
+                        );
+                    }
+                }
+                if instance_label != self.instance_label {
+                    continue; // The series belongs to another driver instance
+                }
+
+                let value = if metric.has_counter() {
+                    metric.get_counter().get_value()
+                } else if metric.has_gauge() {
+                    metric.get_gauge().get_value()
+                } else if metric.has_histogram() {
+                    metric.get_histogram().get_sample_count() as f64
+                } else {
+                    continue; // The driver doesn't emit any other metric types
+                };
+                metric_samples.push(MetricSample {
+                    name: name.to_string(),
+                    labels,
+                    value,
+                });
+            }
+        }
+
+        metric_samples.sort_by(|first_sample, second_sample| {
+            (&first_sample.name, &first_sample.labels)
+                .cmp(&(&second_sample.name, &second_sample.labels))
+        });
+        metric_samples
+    }
+
+    /// Sets the gauge for the epoch state
+    pub fn set_epoch_state_gauge(
+        &self,
+        epoch: &str,
+        validator_address: &str,
+        validator_weight: &str,
+    ) {
+        self.gauge(&EPOCH_STATE, &[epoch, validator_address, validator_weight])
+            .set(1);
+    }
 }
@@ -2,38 +2,71 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    driver_client::VersionSubscriptionId,
     error::Error,
     logging::{LogEntry, LogSchema},
+    metrics::{self, DriverMetrics},
+    storage_synchronizer::{ApplyOrExecute, FailedOperation, StorageSynchronizerStage},
+    utils,
 };
-use aptos_infallible::Mutex;
+use aptos_config::config::StateSyncDriverConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::prelude::*;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     contract_event::ContractEvent,
+    epoch_change::Verifier,
+    epoch_state::EpochState,
+    event::EventKey,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{Transaction, Version},
 };
+use async_trait::async_trait;
 use consensus_notifications::{
     ConsensusCommitNotification, ConsensusNotification, ConsensusNotificationListener,
     ConsensusSyncNotification,
 };
 use data_streaming_service::data_notification::NotificationId;
-use event_notifications::{EventNotificationSender, EventSubscriptionService};
-use futures::{channel::mpsc, stream::FusedStream, Stream};
+use event_notifications::{
+    requires_event_notification, EventNotificationSender, EventSubscriptionService,
+};
+use fail::fail_point;
+use futures::{
+    channel::{mpsc, oneshot},
+    lock::Mutex as AsyncMutex,
+    stream::FusedStream,
+    FutureExt, SinkExt, Stream, StreamExt,
+};
 use mempool_notifications::MempoolNotificationSender;
 use serde::Serialize;
 use std::{
+    collections::{BTreeMap, HashSet, VecDeque},
+    future::Future,
+    panic::{catch_unwind, AssertUnwindSafe},
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
+use storage_interface::DbReader;
+use tokio::{runtime::Handle, time::timeout};
 
 // TODO(joshlind): make these configurable!
+const CONSENSUS_RESPONSE_TIMEOUT_MS: u64 = 5000; // 5 seconds
 const MEMPOOL_COMMIT_ACK_TIMEOUT_MS: u64 = 5000; // 5 seconds
 
+// The max number of times the mempool notification backoff is doubled
+const MAX_BACKOFF_DOUBLINGS: u64 = 10;
+
 /// A notification for new data that has been committed to storage
 #[derive(Clone, Debug)]
 pub enum CommitNotification {
     CommittedStateSnapshot(CommittedStateSnapshot),
+    CommittedStateValueChunk(CommittedStateValueChunk),
 }
 
 /// A commit notification for the new state snapshot
@@ -44,24 +77,156 @@ pub struct CommittedStateSnapshot {
     pub version: Version,
 }
 
-/// A commit notification for new transactions
+impl CommittedStateSnapshot {
+    /// Merges the given (newer) snapshot notification into this notification.
+    /// The committed events and transactions are appended, and the snapshot
+    /// version and state index are updated to the latest values.
+    fn merge(&mut self, committed_snapshot: CommittedStateSnapshot) {
+        let committed_transactions = &mut self.committed_transaction;
+        let new_committed_transactions = committed_snapshot.committed_transaction;
+        committed_transactions
+            .events
+            .extend(new_committed_transactions.events);
+        committed_transactions
+            .transactions
+            .extend(new_committed_transactions.transactions);
+        committed_transactions.last_version = new_committed_transactions.last_version;
+        self.last_committed_state_index = committed_snapshot.last_committed_state_index;
+        self.version = committed_snapshot.version;
+    }
+}
+
+/// A commit notification for a (non-final) chunk of state values that was
+/// written to storage while syncing a state snapshot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommittedStateValueChunk {
+    pub apply_duration: Duration, // The time taken to write the chunk to storage
+    pub last_committed_state_index: u64,
+    pub num_bytes: u64, // The total size of the state keys and values in the chunk
+    pub num_state_values: u64, // The number of state values in the chunk
+    pub proven_root_hash: HashValue, // The root hash the chunk proof was verified against
+    pub verification_duration: Duration, // The time taken (by storage) to verify the chunk proof
+    pub version: Version,
+}
+
+impl CommittedStateValueChunk {
+    /// Merges the given (newer) chunk notification into this notification.
+    /// The chunk progress (and verification time) is accumulated, and the
+    /// state index, root hash and version are updated to the latest values.
+    fn merge(&mut self, committed_chunk: CommittedStateValueChunk) {
+        self.apply_duration += committed_chunk.apply_duration;
+        self.last_committed_state_index = committed_chunk.last_committed_state_index;
+        self.num_bytes += committed_chunk.num_bytes;
+        self.num_state_values += committed_chunk.num_state_values;
+        self.proven_root_hash = committed_chunk.proven_root_hash;
+        self.verification_duration += committed_chunk.verification_duration;
+        self.version = committed_chunk.version;
+    }
+}
+
+/// A commit notification for new transactions. The transactions were
+/// committed at the (inclusive) version range [first_version, last_version].
+/// If `post_reset` is true, the transactions were committed from a data
+/// stream that has since been reset (e.g., terminated after an error). If
+/// `ends_epoch` is true, the last version ended the epoch and the state of
+/// the new epoch is attached. If the transactions were committed by the
+/// storage synchronizer, the committed chunks are also attached.
 #[derive(Clone, Debug, PartialEq)]
 pub struct CommittedTransactions {
+    pub committed_chunks: Vec<CommittedTransactionChunk>,
+    pub ends_epoch: bool,
     pub events: Vec<ContractEvent>,
+    pub first_version: Version,
+    pub last_version: Version,
+    pub new_epoch_state: Option<EpochState>,
+    pub post_reset: bool,
     pub transactions: Vec<Transaction>,
 }
 
+impl CommittedTransactions {
+    /// Creates a new notification for the given transactions (and events),
+    /// where the first transaction was committed at the specified version.
+    pub fn new(
+        events: Vec<ContractEvent>,
+        transactions: Vec<Transaction>,
+        first_version: Version,
+    ) -> Self {
+        let num_transactions = transactions.len() as u64;
+        let last_version = first_version + num_transactions.saturating_sub(1);
+        Self {
+            committed_chunks: vec![],
+            ends_epoch: false,
+            events,
+            first_version,
+            last_version,
+            new_epoch_state: None,
+            post_reset: false,
+            transactions,
+        }
+    }
+
+    /// Marks the committed transactions as ending the epoch (where the
+    /// given epoch state is the state of the new epoch).
+    pub fn set_new_epoch_state(&mut self, new_epoch_state: EpochState) {
+        self.ends_epoch = true;
+        self.new_epoch_state = Some(new_epoch_state);
+    }
+}
+
+/// A summary of a transaction chunk committed by the storage synchronizer
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CommittedTransactionChunk {
+    pub apply_or_execute: ApplyOrExecute, // Whether the chunk was applied or executed
+    pub commit_bytes: u64, // The bytes written by the commit of the chunk (i.e., its commit batch)
+    pub commit_duration: Duration, // The time taken to commit the chunk (i.e., its commit batch)
+    pub execute_duration: Duration, // The time taken to execute/apply the chunk
+    pub first_version: Option<Version>, // The first version in the chunk (if any)
+    pub num_versions: u64, // The number of versions in the chunk
+}
+
+impl CommittedTransactionChunk {
+    /// Returns the total time taken to store the chunk (i.e., execute/apply and commit)
+    pub fn get_total_duration(&self) -> Duration {
+        self.execute_duration + self.commit_duration
+    }
+}
+
+/// The origin of committed transactions (i.e., the component that committed them)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommitOrigin {
+    Consensus,
+    StateSync,
+}
+
+impl CommitOrigin {
+    /// Returns a summary label for the commit origin
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            CommitOrigin::Consensus => "consensus",
+            CommitOrigin::StateSync => "state_sync",
+        }
+    }
+}
+
+/// A summary of the downstream notifications sent for committed transactions
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CommitSummary {
+    pub user_txns_notified: usize, // The number of user transactions sent to mempool
+    pub events_notified: usize,    // The number of events sent to the event subscription service
+    pub reconfig_events: usize,    // The number of reconfiguration events in the notified events
+    pub mempool_latency: Duration, // The time spent notifying mempool
+    pub events_latency: Duration,  // The time spent notifying the event subscription service
+}
+
 impl CommitNotification {
     pub fn new_committed_state_snapshot(
         events: Vec<ContractEvent>,
         transactions: Vec<Transaction>,
+        first_version: Version,
         last_committed_state_index: u64,
         version: Version,
     ) -> Self {
-        let committed_transaction = CommittedTransactions {
-            events,
-            transactions,
-        };
+        let committed_transaction = CommittedTransactions::new(events, transactions, first_version);
         let committed_states = CommittedStateSnapshot {
             committed_transaction,
             last_committed_state_index,
@@ -70,352 +235,2963 @@ impl CommitNotification {
         CommitNotification::CommittedStateSnapshot(committed_states)
     }
 
-    /// Handles the commit notification by notifying mempool and the event
-    /// subscription service.
+    pub fn new_committed_state_value_chunk(
+        apply_duration: Duration,
+        last_committed_state_index: u64,
+        num_bytes: u64,
+        num_state_values: u64,
+        proven_root_hash: HashValue,
+        verification_duration: Duration,
+        version: Version,
+    ) -> Self {
+        let committed_chunk = CommittedStateValueChunk {
+            apply_duration,
+            last_committed_state_index,
+            num_bytes,
+            num_state_values,
+            proven_root_hash,
+            verification_duration,
+            version,
+        };
+        CommitNotification::CommittedStateValueChunk(committed_chunk)
+    }
+
+    /// Merges the given (newer) commit notification into this notification.
+    /// For snapshot notifications, the committed events and transactions are
+    /// appended, and the snapshot version and state index are updated to the
+    /// latest values. Chunk notifications accumulate their progress, and are
+    /// superseded by any (newer) snapshot notification.
+    pub fn merge(&mut self, commit_notification: CommitNotification) {
+        match (self, commit_notification) {
+            (
+                CommitNotification::CommittedStateSnapshot(committed_snapshot),
+                CommitNotification::CommittedStateSnapshot(new_committed_snapshot),
+            ) => committed_snapshot.merge(new_committed_snapshot),
+            (
+                CommitNotification::CommittedStateValueChunk(committed_chunk),
+                CommitNotification::CommittedStateValueChunk(new_committed_chunk),
+            ) => committed_chunk.merge(new_committed_chunk),
+            (notification, CommitNotification::CommittedStateSnapshot(new_committed_snapshot)) => {
+                *notification = CommitNotification::CommittedStateSnapshot(new_committed_snapshot);
+            }
+            (
+                CommitNotification::CommittedStateSnapshot(_),
+                CommitNotification::CommittedStateValueChunk(_),
+            ) => {
+                // The snapshot notification already covers the chunk progress
+            }
+        }
+    }
+
+    /// Handles the commit notification by notifying mempool, the event
+    /// subscription service and any registered commit subscribers. Returns
+    /// a summary of the notifications sent.
     pub async fn handle_transaction_notification<M: MempoolNotificationSender>(
-        events: Vec<ContractEvent>,
-        transactions: Vec<Transaction>,
-        latest_synced_version: Version,
+        committed_transactions: CommittedTransactions,
+        commit_origin: CommitOrigin,
         latest_synced_ledger_info: LedgerInfoWithSignatures,
         mut mempool_notification_handler: MempoolNotificationHandler<M>,
-        event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
-    ) -> Result<(), Error> {
-        // Notify mempool of the committed transactions
-        debug!(
-            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
-                "Notifying mempool of transactions at version: {:?}",
-                latest_synced_version
-            ))
+        mut event_notification_handler: EventNotificationHandler,
+        commit_subscriber_registry: CommitSubscriberRegistry,
+        notified_version_tracker: NotifiedVersionTracker,
+    ) -> Result<CommitSummary, Error> {
+        let mut commit_summary = CommitSummary::default();
+
+        // Remove any transactions that have already been notified downstream
+        // (e.g., commits are notified by both consensus and the synchronizer).
+        let (first_version, last_version) = (
+            committed_transactions.first_version,
+            committed_transactions.last_version,
         );
-        let blockchain_timestamp_usecs = latest_synced_ledger_info.ledger_info().timestamp_usecs();
-        mempool_notification_handler
-            .notify_mempool_of_committed_transactions(
-                transactions.clone(),
-                blockchain_timestamp_usecs,
-            )
-            .await?;
+        let committed_transactions =
+            match notified_version_tracker.remove_notified_transactions(committed_transactions) {
+                Some(committed_transactions) => committed_transactions,
+                None => {
+                    debug!(
+                        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                            "Ignoring a duplicate commit notification for versions {:?} to {:?}",
+                            first_version, last_version
+                        ))
+                    );
+                    return Ok(commit_summary);
+                }
+            };
 
-        // Notify the event subscription service of the events
-        debug!(
-            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
-                "Notifying the event subscription service of events at version: {:?}",
-                latest_synced_version
-            ))
+        // Notify mempool and the event subscription service concurrently. Both
+        // notifications complete before returning, so any reconfiguration
+        // events are still delivered before the commit is acknowledged.
+        let CommittedTransactions {
+            ends_epoch,
+            events,
+            first_version,
+            last_version,
+            transactions,
+            ..
+        } = committed_transactions;
+        let notify_mempool = notify_mempool_of_transactions(
+            &mut mempool_notification_handler,
+            transactions,
+            latest_synced_ledger_info.ledger_info().timestamp_usecs(),
+            first_version,
+            last_version,
+            commit_origin,
         );
-        event_subscription_service
-            .lock()
-            .notify_events(latest_synced_version, events.clone())
-            .map_err(|error| error.into())
+        let (reconfig_events, _) = utils::extract_reconfig_events(&events);
+        let notify_events = notify_event_subscribers_of_events(
+            &mut event_notification_handler,
+            last_version,
+            events,
+            reconfig_events.len(),
+        );
+        let (mempool_result, events_result) = futures::join!(notify_mempool, notify_events);
+
+        // Update the commit summary (or return all notification errors)
+        match (mempool_result, events_result) {
+            (
+                Ok((user_txns_notified, mempool_latency)),
+                Ok((events_notified, reconfig_events, events_latency)),
+            ) => {
+                commit_summary.user_txns_notified = user_txns_notified;
+                commit_summary.mempool_latency = mempool_latency;
+                commit_summary.events_notified = events_notified;
+                commit_summary.reconfig_events = reconfig_events;
+                commit_summary.events_latency = events_latency;
+            }
+            (Err(mempool_error), Ok(_)) => return Err(mempool_error),
+            (Ok(_), Err(events_error)) => return Err(events_error),
+            (Err(mempool_error), Err(events_error)) => {
+                return Err(Error::CommitNotificationErrors(
+                    mempool_error.to_string(),
+                    events_error.to_string(),
+                ))
+            }
+        }
+
+        // Notify any registered commit subscribers of the committed versions
+        commit_subscriber_registry
+            .notify_subscribers(first_version, last_version, &latest_synced_ledger_info)
+            .await;
+        commit_subscriber_registry.notify_commit_event_subscribers(CommitEvent {
+            dry_run: false,
+            epoch: latest_synced_ledger_info.ledger_info().epoch(),
+            first_version,
+            last_version,
+            reconfiguration_occurred: ends_epoch,
+            timestamp_usecs: latest_synced_ledger_info.ledger_info().timestamp_usecs(),
+        });
+
+        Ok(commit_summary)
     }
 }
 
-/// A simple wrapper for a commit notification listener
-pub struct CommitNotificationListener {
-    // The listener for commit notifications
-    commit_notification_listener: mpsc::UnboundedReceiver<CommitNotification>,
-}
+/// Notifies mempool of the committed transactions (if there is a mempool) and
+/// returns the number of user transactions notified and the notification latency.
+async fn notify_mempool_of_transactions<M: MempoolNotificationSender>(
+    mempool_notification_handler: &mut MempoolNotificationHandler<M>,
+    transactions: Vec<Transaction>,
+    blockchain_timestamp_usecs: u64,
+    first_version: Version,
+    last_version: Version,
+    commit_origin: CommitOrigin,
+) -> Result<(usize, Duration), Error> {
+    if !mempool_notification_handler.mempool_enabled() {
+        mempool_notification_handler
+            .driver_metrics
+            .increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::SKIPPED_MEMPOOL_NOTIFICATION,
+            );
+        return Ok((0, Duration::from_secs(0)));
+    }
 
-impl CommitNotificationListener {
-    pub fn new() -> (mpsc::UnboundedSender<CommitNotification>, Self) {
-        // Create a channel to send and receive commit notifications
-        let (commit_notification_sender, commit_notification_listener) = mpsc::unbounded();
+    debug!(
+        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+            "Notifying mempool of transactions for versions {:?} to {:?}",
+            first_version, last_version
+        ))
+    );
+    let mempool_start_time = Instant::now();
+    let user_txns_notified = mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            transactions,
+            blockchain_timestamp_usecs,
+            first_version,
+            last_version,
+            commit_origin,
+        )
+        .await?;
+    Ok((user_txns_notified, mempool_start_time.elapsed()))
+}
 
-        // Create and return the sender and listener
-        let commit_notification_listener = Self {
-            commit_notification_listener,
-        };
-        (commit_notification_sender, commit_notification_listener)
+/// Notifies the event subscription service of the events committed at the
+/// given version (where `num_reconfig_events` of the events are
+/// reconfigurations) and returns the number of events, the number of
+/// reconfiguration events and the notification latency. If the events contain
+/// no reconfigurations and have no subscribers, the notification is skipped
+/// entirely (this is the common case for most commits).
+async fn notify_event_subscribers_of_events(
+    event_notification_handler: &mut EventNotificationHandler,
+    version: Version,
+    events: Vec<ContractEvent>,
+    num_reconfig_events: usize,
+) -> Result<(usize, usize, Duration), Error> {
+    if !event_notification_handler.requires_notification(&events) {
+        event_notification_handler.driver_metrics.increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::SKIPPED_EVENT_NOTIFICATION,
+        );
+        return Ok((0, 0, Duration::from_secs(0)));
     }
+
+    debug!(
+        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+            "Notifying the event subscription service of events at version: {:?}",
+            version
+        ))
+    );
+    let events_notified = events.len();
+    let events_start_time = Instant::now();
+    event_notification_handler
+        .notify_events(version, events)
+        .await?;
+    Ok((
+        events_notified,
+        num_reconfig_events,
+        events_start_time.elapsed(),
+    ))
 }
 
-impl Stream for CommitNotificationListener {
-    type Item = CommitNotification;
+/// A subscriber (e.g., another node component) that wants to learn about
+/// newly committed versions from state sync.
+#[async_trait]
+pub trait CommitSubscriber: Send + Sync {
+    /// Returns the name of the subscriber (used for logging and metrics)
+    fn name(&self) -> &'static str;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.get_mut().commit_notification_listener).poll_next(cx)
-    }
+    /// Notifies the subscriber of the newly committed versions in the
+    /// (inclusive) range [first_version, last_version], along with the
+    /// latest synced ledger info.
+    async fn notify_commit(
+        &self,
+        first_version: Version,
+        last_version: Version,
+        latest_synced_ledger_info: &LedgerInfoWithSignatures,
+    ) -> anyhow::Result<()>;
 }
 
-impl FusedStream for CommitNotificationListener {
-    fn is_terminated(&self) -> bool {
-        self.commit_notification_listener.is_terminated()
-    }
+/// A lightweight event describing the versions committed by state sync. These
+/// are sent to the commit event subscribers registered through the driver client.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommitEvent {
+    pub dry_run: bool, // Whether the versions were only verified and executed (by a dry-run driver)
+    pub epoch: u64,    // The epoch of the latest synced ledger info
+    pub first_version: Version, // The first committed version (inclusive)
+    pub last_version: Version, // The last committed version (inclusive)
+    pub reconfiguration_occurred: bool, // Whether the committed versions ended the epoch
+    pub timestamp_usecs: u64, // The timestamp of the latest synced ledger info
 }
 
-/// A consensus sync request for a specified target ledger info
-pub struct ConsensusSyncRequest {
-    consensus_sync_notification: ConsensusSyncNotification,
+/// A waiter for the node to sync to a version
+type SyncedVersionWaiter = (
+    VersionSubscriptionId,
+    oneshot::Sender<Result<LedgerInfoWithSignatures, Error>>,
+);
+
+/// A registry of commit subscribers. Subscribers are notified (in order)
+/// after mempool and the event subscription service have been notified.
+/// Commit event subscribers (i.e., channels registered through the driver
+/// client) are notified last, and can be added while the driver is running.
+///
+/// The registry also holds the synced version waiters (i.e., clients waiting
+/// for the node to sync to specific versions). Waiters are notified with the
+/// latest synced ledger info once it covers the awaited version.
+#[derive(Clone, Default)]
+pub struct CommitSubscriberRegistry {
+    commit_event_subscribers: Arc<Mutex<Vec<mpsc::Sender<CommitEvent>>>>,
+    commit_subscribers: Arc<Vec<Arc<dyn CommitSubscriber>>>,
+    driver_metrics: DriverMetrics,
+    synced_version_waiters: Arc<Mutex<BTreeMap<Version, Vec<SyncedVersionWaiter>>>>, // Waiters keyed by the awaited version
 }
 
-impl ConsensusSyncRequest {
-    pub fn new(consensus_sync_notification: ConsensusSyncNotification) -> Self {
+impl CommitSubscriberRegistry {
+    pub fn new(
+        commit_subscribers: Vec<Arc<dyn CommitSubscriber>>,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
         Self {
-            consensus_sync_notification,
+            commit_event_subscribers: Arc::new(Mutex::new(vec![])),
+            commit_subscribers: Arc::new(commit_subscribers),
+            driver_metrics,
+            synced_version_waiters: Arc::new(Mutex::new(BTreeMap::new())),
         }
     }
 
-    pub fn get_sync_target(&self) -> LedgerInfoWithSignatures {
-        self.consensus_sync_notification.target.clone()
+    /// Adds a waiter (with the given subscription id) for the node to sync
+    /// to the given version. The waiter is only notified by the next call to
+    /// `notify_synced_version_waiters()` (i.e., it is not checked here).
+    pub fn add_synced_version_waiter(
+        &self,
+        subscription_id: VersionSubscriptionId,
+        version: Version,
+        waiter_sender: oneshot::Sender<Result<LedgerInfoWithSignatures, Error>>,
+    ) {
+        self.synced_version_waiters
+            .lock()
+            .entry(version)
+            .or_insert_with(Vec::new)
+            .push((subscription_id, waiter_sender));
     }
-}
 
-/// A simple handler for consensus notifications
-pub struct ConsensusNotificationHandler {
-    // The listener for notifications from consensus
-    consensus_listener: ConsensusNotificationListener,
+    /// Returns the number of synced version waiters
+    pub fn num_synced_version_waiters(&self) -> usize {
+        self.synced_version_waiters
+            .lock()
+            .values()
+            .map(Vec::len)
+            .sum()
+    }
 
-    // The latest consensus sync request that has been received
-    consensus_sync_request: Arc<Mutex<Option<ConsensusSyncRequest>>>,
-}
+    /// Notifies (and removes) all synced version waiters for versions covered
+    /// by the given latest synced ledger info. Returns the number notified.
+    pub fn notify_synced_version_waiters(
+        &self,
+        latest_synced_ledger_info: &LedgerInfoWithSignatures,
+    ) -> usize {
+        let synced_version = latest_synced_ledger_info.ledger_info().version();
+        let covered_waiters = {
+            let mut synced_version_waiters = self.synced_version_waiters.lock();
+            let uncovered_waiters =
+                synced_version_waiters.split_off(&synced_version.saturating_add(1));
+            std::mem::replace(&mut *synced_version_waiters, uncovered_waiters)
+        };
 
-impl ConsensusNotificationHandler {
-    pub fn new(consensus_listener: ConsensusNotificationListener) -> Self {
-        Self {
-            consensus_listener,
-            consensus_sync_request: Arc::new(Mutex::new(None)),
+        let mut num_waiters_notified = 0;
+        for (_, waiter_sender) in covered_waiters.into_values().flatten() {
+            let _ = waiter_sender.send(Ok(latest_synced_ledger_info.clone())); // The waiter may have timed out
+            num_waiters_notified += 1;
+        }
+        if num_waiters_notified > 0 {
+            debug!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                    "Notified {:?} synced version waiters! Synced version: {:?}",
+                    num_waiters_notified, synced_version
+                ))
+            );
         }
+        num_waiters_notified
     }
 
-    /// Returns true iff there is a sync request currently blocking consensus
-    pub fn active_sync_request(&self) -> bool {
-        self.consensus_sync_request.lock().is_some()
+    /// Removes the synced version waiter with the given subscription id (e.g.,
+    /// because the client timed out). Returns true iff a waiter was removed.
+    pub fn remove_synced_version_waiter(&self, subscription_id: VersionSubscriptionId) -> bool {
+        let mut removed = false;
+        self.synced_version_waiters.lock().retain(|_, waiters| {
+            waiters.retain(|(waiter_id, _)| {
+                let remove_waiter = *waiter_id == subscription_id;
+                removed |= remove_waiter;
+                !remove_waiter
+            });
+            !waiters.is_empty()
+        });
+        removed
     }
 
-    /// Returns the active sync request that consensus is waiting on
-    pub fn get_consensus_sync_request(&self) -> Arc<Mutex<Option<ConsensusSyncRequest>>> {
-        self.consensus_sync_request.clone()
+    /// Returns the number of commit event subscribers
+    pub fn num_commit_event_subscribers(&self) -> usize {
+        self.commit_event_subscribers.lock().len()
     }
 
-    /// Initializes the sync request received from consensus
-    pub async fn initialize_sync_request(
-        &mut self,
-        sync_notification: ConsensusSyncNotification,
-        latest_synced_ledger_info: LedgerInfoWithSignatures,
-    ) -> Result<(), Error> {
-        // Get the latest committed version and the target sync version
-        let sync_target_version = sync_notification.target.ledger_info().version();
-        let latest_committed_version = latest_synced_ledger_info.ledger_info().version();
+    /// Subscribes the given channel to commit events. The subscription is
+    /// removed once the channel is closed (or becomes full).
+    pub fn subscribe_to_commit_events(&self, commit_event_sender: mpsc::Sender<CommitEvent>) {
+        self.commit_event_subscribers
+            .lock()
+            .push(commit_event_sender);
+    }
 
-        // If the target version is old, return an error to consensus (something is wrong!)
-        if sync_target_version < latest_committed_version {
-            let error = Err(Error::OldSyncRequest(
-                sync_target_version,
-                latest_committed_version,
-            ));
-            self.respond_to_sync_notification(sync_notification, error.clone())
-                .await?;
-            return error;
-        }
+    /// Sends the given event to all commit event subscribers. Subscribers
+    /// are never waited on: if a subscriber's channel is full (i.e., the
+    /// subscriber is too slow), or closed, the subscriber is removed.
+    pub fn notify_commit_event_subscribers(&self, commit_event: CommitEvent) {
+        self.commit_event_subscribers
+            .lock()
+            .retain_mut(|commit_event_sender| {
+                match commit_event_sender.try_send(commit_event.clone()) {
+                    Ok(()) => true,
+                    Err(error) if error.is_full() => {
+                        warn!(
+                            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                                "Dropping a slow commit event subscriber! The channel is full. \
+                            Failed to send the event for versions {:?} to {:?}.",
+                                commit_event.first_version, commit_event.last_version
+                            ))
+                        );
+                        self.driver_metrics.increment_counter(
+                            &metrics::DRIVER_COUNTERS,
+                            metrics::SLOW_COMMIT_EVENT_SUBSCRIPTION,
+                        );
+                        false
+                    }
+                    Err(_) => {
+                        debug!(LogSchema::new(LogEntry::NotificationHandler)
+                            .message("Removing a closed commit event subscription!"));
+                        self.driver_metrics.increment_counter(
+                            &metrics::DRIVER_COUNTERS,
+                            metrics::CLOSED_COMMIT_EVENT_SUBSCRIPTION,
+                        );
+                        false
+                    }
+                }
+            });
+    }
 
-        // If we're now at the target, return successfully
-        if sync_target_version == latest_committed_version {
-            info!(LogSchema::new(LogEntry::NotificationHandler)
-                .message("We're already at the requested sync target version! Returning early"));
-            let result = Ok(());
-            self.respond_to_sync_notification(sync_notification, result.clone())
-                .await?;
-            return result;
+    /// Notifies all subscribers of the committed versions. A failure to notify
+    /// one subscriber is logged and metered, but doesn't affect the others.
+    pub async fn notify_subscribers(
+        &self,
+        first_version: Version,
+        last_version: Version,
+        latest_synced_ledger_info: &LedgerInfoWithSignatures,
+    ) {
+        for commit_subscriber in self.commit_subscribers.iter() {
+            let result = commit_subscriber
+                .notify_commit(first_version, last_version, latest_synced_ledger_info)
+                .await;
+            let result_label = match result {
+                Ok(()) => metrics::COMMIT_SUBSCRIBER_SUCCESS,
+                Err(error) => {
+                    warn!(
+                        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                            "Failed to notify commit subscriber {:?} of versions {:?} to {:?}! Error: {:?}",
+                            commit_subscriber.name(),
+                            first_version,
+                            last_version,
+                            error
+                        ))
+                    );
+                    metrics::COMMIT_SUBSCRIBER_FAILURE
+                }
+            };
+            self.driver_metrics
+                .counter(
+                    &metrics::COMMIT_SUBSCRIBER_NOTIFICATIONS,
+                    &[commit_subscriber.name(), result_label],
+                )
+                .inc();
         }
+    }
+}
 
-        // Save the request so we can notify consensus once we've hit the target
-        let consensus_sync_request = ConsensusSyncRequest::new(sync_notification);
-        self.consensus_sync_request = Arc::new(Mutex::new(Some(consensus_sync_request)));
+/// A hook that is run after each durable transaction commit
+pub type PostCommitHook = Box<dyn Fn(&CommittedTransactions) + Send + Sync>;
 
-        Ok(())
+/// A registry of post-commit hooks. Hooks are run synchronously (in the order
+/// they were registered) by the committer after each durable transaction
+/// commit, but before any commit notifications are sent. A panic in one hook
+/// is logged and metered, but doesn't affect the other hooks (or the commit).
+/// Note: hooks should still avoid panicking, as the process panic handler
+/// (e.g., the node's crash handler) runs before the panic is caught.
+#[derive(Clone, Default)]
+pub struct PostCommitHookRegistry {
+    driver_metrics: DriverMetrics,
+    post_commit_hooks: Arc<RwLock<Vec<(&'static str, PostCommitHook)>>>,
+}
+
+impl PostCommitHookRegistry {
+    pub fn new(driver_metrics: DriverMetrics) -> Self {
+        Self {
+            driver_metrics,
+            post_commit_hooks: Arc::new(RwLock::new(vec![])),
+        }
     }
 
-    /// Checks to see if the sync request has been successfully fulfilled
-    pub async fn check_sync_request_progress(
-        &mut self,
-        latest_synced_ledger_info: LedgerInfoWithSignatures,
-    ) -> Result<(), Error> {
-        // Fetch the sync target version
-        let consensus_sync_request = self.get_consensus_sync_request();
-        let sync_target_version = consensus_sync_request.lock().as_ref().map(|sync_request| {
-            sync_request
-                .consensus_sync_notification
-                .target
-                .ledger_info()
-                .version()
-        });
+    /// Registers the given hook (the name is used for logging and metrics)
+    pub fn register_hook(&self, hook_name: &'static str, hook: PostCommitHook) {
+        self.post_commit_hooks.write().push((hook_name, hook));
+    }
 
-        // Compare our local state to the target version
-        if let Some(sync_target_version) = sync_target_version {
-            let latest_committed_version = latest_synced_ledger_info.ledger_info().version();
-
-            // Check if we've synced beyond the target
-            if latest_committed_version > sync_target_version {
-                return Err(Error::SyncedBeyondTarget(
-                    latest_committed_version,
-                    sync_target_version,
-                ));
+    /// Runs all registered hooks for the given committed transactions
+    pub fn run_hooks(&self, committed_transactions: &CommittedTransactions) {
+        for (hook_name, hook) in self.post_commit_hooks.read().iter() {
+            let hook_start_time = Instant::now();
+            let hook_result = catch_unwind(AssertUnwindSafe(|| hook(committed_transactions)));
+            self.driver_metrics
+                .histogram(&metrics::POST_COMMIT_HOOK_LATENCY, &[*hook_name])
+                .observe(hook_start_time.elapsed().as_secs_f64());
+            if hook_result.is_err() {
+                error!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "The post-commit hook {:?} panicked for versions {:?} to {:?}!",
+                        hook_name,
+                        committed_transactions.first_version,
+                        committed_transactions.last_version
+                    ))
+                );
+                self.driver_metrics
+                    .counter(&metrics::POST_COMMIT_HOOK_PANICS, &[*hook_name])
+                    .inc();
             }
+        }
+    }
+}
 
-            // Check if we've hit the target
-            if latest_committed_version == sync_target_version {
-                let consensus_sync_request = self.get_consensus_sync_request().lock().take();
-                if let Some(consensus_sync_request) = consensus_sync_request {
-                    self.respond_to_sync_notification(
-                        consensus_sync_request.consensus_sync_notification,
-                        Ok(()),
-                    )
-                    .await?;
-                }
-                return Ok(());
-            }
+/// A snapshot of the last commit recorded by the commit state tracker
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CommitState {
+    pub last_commit_epoch: u64,
+    pub last_commit_timestamp_usecs: u64,
+    pub last_commit_version: Version,
+}
+
+/// An epoch transition recorded by the commit state tracker
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EpochTransition {
+    pub epoch: u64,                                // The epoch that was entered
+    pub previous_epoch_duration: Option<Duration>, // The time spent in the previous epoch (if known)
+}
+
+/// Tracks when the node last committed anything (regardless of whether the
+/// commit was made by consensus or state sync), along with the highest
+/// committed version and epoch. This is shared between the driver, the
+/// storage synchronizer and the driver client, and can be read without locks.
+/// All values only ever increase. The tracker also caches the state of the
+/// latest epoch (if known), whether the node has completed bootstrapping,
+/// and when the node last transitioned epochs. The cached epoch state is
+/// invalidated by epoch ending commits.
+#[derive(Clone)]
+pub struct CommitStateTracker {
+    bootstrapped: Arc<AtomicBool>,
+    last_commit_epoch: Arc<AtomicU64>,
+    last_commit_timestamp_usecs: Arc<AtomicU64>,
+    last_commit_version: Arc<AtomicU64>,
+    last_epoch_transition: Arc<Mutex<Option<(u64, Instant)>>>,
+    latest_epoch_state: Arc<RwLock<Option<EpochState>>>,
+    time_service: TimeService,
+}
+
+impl CommitStateTracker {
+    pub fn new(time_service: TimeService) -> Self {
+        Self {
+            bootstrapped: Arc::new(AtomicBool::new(false)),
+            last_commit_epoch: Arc::new(AtomicU64::new(0)),
+            last_commit_timestamp_usecs: Arc::new(AtomicU64::new(0)),
+            last_commit_version: Arc::new(AtomicU64::new(0)),
+            last_epoch_transition: Arc::new(Mutex::new(None)),
+            latest_epoch_state: Arc::new(RwLock::new(None)),
+            time_service,
         }
+    }
 
-        Ok(())
+    /// Returns true iff the node has completed bootstrapping
+    pub fn is_bootstrapped(&self) -> bool {
+        self.bootstrapped.load(Ordering::Acquire)
     }
 
-    /// Responds to consensus for a sync notification using the specified result
-    pub async fn respond_to_sync_notification(
-        &mut self,
-        sync_notification: ConsensusSyncNotification,
-        result: Result<(), Error>,
-    ) -> Result<(), Error> {
-        // Wrap the result in an error that consensus can process
-        let message = result.map_err(|error| {
-            consensus_notifications::Error::UnexpectedErrorEncountered(format!("{:?}", error))
+    /// Marks the node as bootstrapped (this can never be undone)
+    pub fn set_bootstrapped(&self) {
+        self.bootstrapped.store(true, Ordering::Release);
+    }
+
+    /// Caches the given epoch state (committed by an epoch ending commit).
+    /// Returns true iff the epoch state is newer than the cached state.
+    pub fn update_epoch_state(&self, new_epoch_state: EpochState) -> bool {
+        let mut latest_epoch_state = self.latest_epoch_state.write();
+        let is_newer = latest_epoch_state.as_ref().map_or(true, |epoch_state| {
+            new_epoch_state.epoch > epoch_state.epoch
         });
+        if is_newer {
+            *latest_epoch_state = Some(new_epoch_state);
+        }
+        is_newer
+    }
 
-        info!(
-            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
-                "Responding to consensus sync notification with message: {:?}",
-                message
-            ))
-        );
+    /// Returns the cached epoch state (if one exists) for the given epoch
+    pub fn get_epoch_state(&self, epoch: u64) -> Option<EpochState> {
+        self.latest_epoch_state
+            .read()
+            .as_ref()
+            .filter(|epoch_state| epoch_state.epoch == epoch)
+            .cloned()
+    }
 
-        // Send the result
-        self.consensus_listener
-            .respond_to_sync_notification(sync_notification, message)
-            .await
-            .map_err(|error| {
-                Error::CallbackSendFailed(format!(
-                    "Consensus sync request response error: {:?}",
-                    error
-                ))
-            })
+    /// Returns the cached epoch state (if one exists), regardless of its epoch
+    pub fn get_latest_epoch_state(&self) -> Option<EpochState> {
+        self.latest_epoch_state.read().clone()
     }
 
-    /// Responds successfully to consensus for a commit notification
-    pub async fn respond_to_commit_notification(
-        &mut self,
-        commit_notification: ConsensusCommitNotification,
-        result: Result<(), Error>,
-    ) -> Result<(), Error> {
-        // Wrap the result in an error that consensus can process
-        let message = result.map_err(|error| {
-            consensus_notifications::Error::UnexpectedErrorEncountered(format!("{:?}", error))
-        });
+    /// Invalidates the cached epoch state if it's older than the given
+    /// (latest) epoch. Returns true iff a cached epoch state was dropped.
+    pub fn invalidate_epoch_state(&self, latest_epoch: u64) -> bool {
+        let mut latest_epoch_state = self.latest_epoch_state.write();
+        let is_stale = latest_epoch_state
+            .as_ref()
+            .map_or(false, |epoch_state| epoch_state.epoch < latest_epoch);
+        if is_stale {
+            *latest_epoch_state = None;
+        }
+        is_stale
+    }
 
-        debug!(
-            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
-                "Responding to consensus commit notification with message: {:?}",
-                message
-            ))
-        );
+    /// Records a transition into the given epoch at the current time. Returns
+    /// None if the transition has already been recorded (e.g., commits might
+    /// be notified multiple times), or if the epoch is older than the last
+    /// recorded transition. The duration of the previous epoch is only known
+    /// if the previous transition was recorded by this tracker.
+    pub fn record_epoch_transition(&self, epoch: u64) -> Option<EpochTransition> {
+        let mut last_epoch_transition = self.last_epoch_transition.lock();
+        let previous_epoch_duration = match *last_epoch_transition {
+            Some((last_epoch, _)) if epoch <= last_epoch => return None,
+            Some((_, last_transition_time)) => {
+                Some(self.time_service.now().duration_since(last_transition_time))
+            }
+            None => None,
+        };
+        *last_epoch_transition = Some((epoch, self.time_service.now()));
+        Some(EpochTransition {
+            epoch,
+            previous_epoch_duration,
+        })
+    }
 
-        // Send the result
-        self.consensus_listener
-            .respond_to_commit_notification(commit_notification, message)
-            .await
-            .map_err(|error| {
-                Error::CallbackSendFailed(format!("Consensus commit response error: {:?}", error))
-            })
+    /// Records a commit of the given version and epoch at the current time
+    pub fn update_commit_state(&self, version: Version, epoch: u64) {
+        let timestamp_usecs = self.time_service.now_unix_time().as_micros() as u64;
+        self.last_commit_version
+            .fetch_max(version, Ordering::Relaxed);
+        self.last_commit_epoch.fetch_max(epoch, Ordering::Relaxed);
+        self.last_commit_timestamp_usecs
+            .fetch_max(timestamp_usecs, Ordering::Release);
     }
-}
 
-impl Stream for ConsensusNotificationHandler {
-    type Item = ConsensusNotification;
+    /// Returns the state of the last commit (or None if nothing has been
+    /// committed since the tracker was created).
+    pub fn get_commit_state(&self) -> Option<CommitState> {
+        let last_commit_timestamp_usecs = self.last_commit_timestamp_usecs.load(Ordering::Acquire);
+        if last_commit_timestamp_usecs == 0 {
+            return None;
+        }
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.get_mut().consensus_listener).poll_next(cx)
+        Some(CommitState {
+            last_commit_epoch: self.last_commit_epoch.load(Ordering::Relaxed),
+            last_commit_timestamp_usecs,
+            last_commit_version: self.last_commit_version.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Returns the time elapsed since the last commit (or None if nothing
+    /// has been committed since the tracker was created).
+    pub fn time_since_last_commit(&self) -> Option<Duration> {
+        self.get_commit_state().map(|commit_state| {
+            self.time_service
+                .now_unix_time()
+                .saturating_sub(Duration::from_micros(
+                    commit_state.last_commit_timestamp_usecs,
+                ))
+        })
     }
 }
 
-impl FusedStream for ConsensusNotificationHandler {
-    fn is_terminated(&self) -> bool {
-        self.consensus_listener.is_terminated()
+impl Default for CommitStateTracker {
+    fn default() -> Self {
+        Self::new(TimeService::real())
     }
 }
 
-/// A notification for error transactions and events that have been committed to
+/// Tracks the highest version that has been notified downstream (i.e., to
+/// mempool and the event subscription service). This is shared between the
+/// driver and the storage synchronizer so that overlapping commit
+/// notifications (regardless of source) are only notified once.
+#[derive(Clone, Default)]
+pub struct NotifiedVersionTracker {
+    driver_metrics: DriverMetrics,
+    highest_notified_version: Arc<Mutex<Option<Version>>>,
+}
+
+impl NotifiedVersionTracker {
+    pub fn new(driver_metrics: DriverMetrics) -> Self {
+        Self {
+            driver_metrics,
+            highest_notified_version: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Returns the highest version notified downstream (if any)
+    pub fn highest_notified_version(&self) -> Option<Version> {
+        *self.highest_notified_version.lock()
+    }
+
+    /// Removes all committed transactions that have already been notified
+    /// downstream and marks the remaining transactions as notified. If all
+    /// transactions have already been notified, None is returned. Note: events
+    /// are only dropped if all transactions have already been notified.
+    fn remove_notified_transactions(
+        &self,
+        mut committed_transactions: CommittedTransactions,
+    ) -> Option<CommittedTransactions> {
+        let mut highest_notified_version = self.highest_notified_version.lock();
+        if committed_transactions.transactions.is_empty() {
+            return Some(committed_transactions); // There's nothing to deduplicate
+        }
+
+        // Remove the transactions that have already been notified
+        let first_version = committed_transactions.first_version;
+        let last_version = committed_transactions.last_version;
+        if let Some(highest_notified_version) = *highest_notified_version {
+            if highest_notified_version >= last_version {
+                self.driver_metrics.increment_counter(
+                    &metrics::DRIVER_COUNTERS,
+                    metrics::DUPLICATE_COMMIT_NOTIFICATION,
+                );
+                return None;
+            } else if highest_notified_version >= first_version {
+                self.driver_metrics.increment_counter(
+                    &metrics::DRIVER_COUNTERS,
+                    metrics::DUPLICATE_COMMIT_NOTIFICATION,
+                );
+                let num_notified_transactions = highest_notified_version - first_version + 1;
+                committed_transactions
+                    .transactions
+                    .drain(0..num_notified_transactions as usize);
+                committed_transactions.first_version = highest_notified_version + 1;
+            } else if highest_notified_version + 1 < first_version {
+                if committed_transactions.post_reset {
+                    // Late notifications from a reset stream are expected to
+                    // be non-contiguous, so this isn't treated as an anomaly.
+                    debug!(
+                        LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                            "Found a gap before a post-reset commit notification! Highest \
+                            notified version: {:?}, first committed version: {:?}",
+                            highest_notified_version, first_version
+                        ))
+                    );
+                } else {
+                    // The committed transactions aren't contiguous with those
+                    // already notified (e.g., because notifications raced).
+                    warn!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "Found a gap in the committed transactions! Highest notified version: {:?}, \
+                        first committed version: {:?}",
+                        highest_notified_version, first_version
+                    ))
+                );
+                    self.driver_metrics.increment_counter(
+                        &metrics::DRIVER_COUNTERS,
+                        metrics::COMMIT_NOTIFICATION_GAP,
+                    );
+                }
+            }
+        }
+        if committed_transactions.post_reset {
+            self.driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::POST_RESET_COMMIT_NOTIFICATION,
+            );
+        }
+
+        // Update the highest notified version
+        *highest_notified_version = Some(last_version);
+        Some(committed_transactions)
+    }
+}
+
+/// Filters the given transactions (starting at the specified version) down to
+/// the user transactions (alongside their versions) and updates the metrics
+/// for all transactions that were filtered out.
+fn filter_user_transactions(
+    transactions: Vec<Transaction>,
+    first_version: Version,
+    driver_metrics: &DriverMetrics,
+) -> Vec<(Version, Transaction)> {
+    (first_version..)
+        .zip(transactions)
+        .filter(|(_, transaction)| {
+            let filtered_transaction_label = match transaction {
+                Transaction::UserTransaction(_) => return true,
+                Transaction::BlockMetadata(_) => metrics::BLOCK_METADATA_TRANSACTION,
+                Transaction::GenesisTransaction(_) => metrics::GENESIS_TRANSACTION,
+                Transaction::StateCheckpoint(_) => metrics::STATE_CHECKPOINT_TRANSACTION,
+            };
+            driver_metrics.increment_counter(
+                &metrics::MEMPOOL_FILTERED_TRANSACTIONS,
+                filtered_transaction_label,
+            );
+            false
+        })
+        .collect()
+}
+
+/// A sender for commit notifications. The underlying channel is bounded, so if
+/// the listener falls behind (e.g., the driver is blocked on mempool), any new
+/// notifications are coalesced with the undelivered (overflow) notification.
+/// This avoids blocking the commit path and dropping data.
+#[derive(Clone)]
+pub struct CommitNotificationSender {
+    // The sender for commit notifications
+    commit_notification_sender: mpsc::Sender<CommitNotification>,
+
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The notification that could not be sent because the channel was full
+    overflow_notification: Arc<Mutex<Option<CommitNotification>>>,
+}
+
+impl CommitNotificationSender {
+    /// Sends the commit notification to the listener. If the channel is full,
+    /// the notification is coalesced with any undelivered notification.
+    pub fn send(&mut self, commit_notification: CommitNotification) -> Result<(), Error> {
+        let mut overflow_notification = self.overflow_notification.lock();
+
+        // If there's an undelivered notification, we must coalesce the new
+        // notification with it (to preserve notification ordering).
+        let commit_notification = match overflow_notification.take() {
+            Some(mut undelivered_notification) => {
+                self.driver_metrics.increment_counter(
+                    &metrics::DRIVER_COUNTERS,
+                    metrics::COALESCED_COMMIT_NOTIFICATION,
+                );
+                undelivered_notification.merge(commit_notification);
+                undelivered_notification
+            }
+            None => commit_notification,
+        };
+
+        // Send the notification or hold onto it if the channel is full
+        match self
+            .commit_notification_sender
+            .try_send(commit_notification)
+        {
+            Ok(()) => {
+                self.driver_metrics.increment_gauge(
+                    &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+                    metrics::COMMIT_NOTIFICATION_CHANNEL_OCCUPANCY,
+                    1,
+                );
+                Ok(())
+            }
+            Err(error) if error.is_full() => {
+                *overflow_notification = Some(error.into_inner());
+                Ok(())
+            }
+            Err(error) => Err(error.into_send_error().into()),
+        }
+    }
+}
+
+/// A simple wrapper for a commit notification listener
+pub struct CommitNotificationListener {
+    // The listener for commit notifications
+    commit_notification_listener: mpsc::Receiver<CommitNotification>,
+
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The notification that could not be sent because the channel was full
+    overflow_notification: Arc<Mutex<Option<CommitNotification>>>,
+}
+
+impl CommitNotificationListener {
+    pub fn new(
+        max_pending_commit_notifications: u64,
+        driver_metrics: DriverMetrics,
+    ) -> (CommitNotificationSender, Self) {
+        // Create a channel to send and receive commit notifications
+        let (commit_notification_sender, commit_notification_listener) =
+            mpsc::channel(max_pending_commit_notifications as usize);
+        let overflow_notification = Arc::new(Mutex::new(None));
+
+        // Create and return the sender and listener
+        let commit_notification_sender = CommitNotificationSender {
+            commit_notification_sender,
+            driver_metrics,
+            overflow_notification: overflow_notification.clone(),
+        };
+        let commit_notification_listener = Self {
+            commit_notification_listener,
+            driver_metrics,
+            overflow_notification,
+        };
+        (commit_notification_sender, commit_notification_listener)
+    }
+}
+
+impl Stream for CommitNotificationListener {
+    type Item = CommitNotification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let listener = self.get_mut();
+        match Pin::new(&mut listener.commit_notification_listener).poll_next(cx) {
+            Poll::Ready(Some(commit_notification)) => {
+                listener.driver_metrics.decrement_gauge(
+                    &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+                    metrics::COMMIT_NOTIFICATION_CHANNEL_OCCUPANCY,
+                    1,
+                );
+                Poll::Ready(Some(commit_notification))
+            }
+            poll_result => {
+                // The channel has been drained, so deliver any undelivered notification.
+                // Any new notifications sent to the channel will wake the listener.
+                match listener.overflow_notification.lock().take() {
+                    Some(commit_notification) => Poll::Ready(Some(commit_notification)),
+                    None => poll_result,
+                }
+            }
+        }
+    }
+}
+
+impl FusedStream for CommitNotificationListener {
+    fn is_terminated(&self) -> bool {
+        self.commit_notification_listener.is_terminated()
+            && self.overflow_notification.lock().is_none()
+    }
+}
+
+/// A consensus sync request for a specified target ledger info. The request
+/// holds the consensus callback until the request is answered, as well as the
+/// time and synced version at which the request was initiated.
+pub struct ConsensusSyncRequest {
+    consensus_sync_notification: Option<ConsensusSyncNotification>,
+    initial_synced_version: Version,
+    start_time: Instant,
+    sync_target_verified: bool, // False iff the target is in a future (unverified) epoch
+}
+
+impl ConsensusSyncRequest {
+    pub fn new(
+        consensus_sync_notification: ConsensusSyncNotification,
+        initial_synced_version: Version,
+        sync_target_verified: bool,
+        start_time: Instant,
+    ) -> Self {
+        Self {
+            consensus_sync_notification: Some(consensus_sync_notification),
+            initial_synced_version,
+            start_time,
+            sync_target_verified,
+        }
+    }
+
+    pub fn get_initial_synced_version(&self) -> Version {
+        self.initial_synced_version
+    }
+
+    pub fn get_sync_target(&self) -> LedgerInfoWithSignatures {
+        self.get_sync_notification().target.clone()
+    }
+
+    pub fn get_sync_target_version(&self) -> Version {
+        self.get_sync_notification().target.ledger_info().version()
+    }
+
+    pub fn get_start_time(&self) -> Instant {
+        self.start_time
+    }
+
+    pub fn is_sync_target_verified(&self) -> bool {
+        self.sync_target_verified
+    }
+
+    /// Returns true iff consensus is no longer waiting on the request (i.e.,
+    /// the callback receiver was dropped).
+    pub fn is_callback_closed(&self) -> bool {
+        self.get_sync_notification().is_callback_closed()
+    }
+
+    /// Marks the sync target as verified. This also resets the start time of
+    /// the request, so that the time spent verifying the target (i.e., syncing
+    /// the epoch changes up to the target) doesn't count towards the timeout.
+    fn mark_sync_target_verified(&mut self, verification_time: Instant) {
+        self.sync_target_verified = true;
+        self.start_time = verification_time;
+    }
+
+    fn get_sync_notification(&self) -> &ConsensusSyncNotification {
+        self.consensus_sync_notification
+            .as_ref()
+            .expect("The consensus sync request has already been answered!")
+    }
+
+    /// Takes the sync notification from the request (so it can be answered)
+    fn take_sync_notification(&mut self) -> ConsensusSyncNotification {
+        self.consensus_sync_notification
+            .take()
+            .expect("The consensus sync request has already been answered!")
+    }
+}
+
+impl Drop for ConsensusSyncRequest {
+    fn drop(&mut self) {
+        // Catch any leaked callbacks (i.e., requests dropped without an answer)
+        if !std::thread::panicking() {
+            debug_assert!(
+                self.consensus_sync_notification.is_none(),
+                "A consensus sync request was dropped without being answered! Target: {:?}",
+                self.consensus_sync_notification
+                    .as_ref()
+                    .map(|notification| notification.target.ledger_info().version())
+            );
+        }
+    }
+}
+
+/// The slot holding the consensus sync request that is currently active (if
+/// any). At most one request can occupy the slot, and every request accepted
+/// into the slot is answered exactly once: when the sync target is reached,
+/// or when the request is superseded, times out or is aborted (on shutdown).
+#[derive(Default)]
+pub struct ConsensusSyncRequestSlot {
+    active_sync_request: Option<ConsensusSyncRequest>,
+}
+
+impl ConsensusSyncRequestSlot {
+    /// Returns the sync request occupying the slot (if any)
+    pub fn active_sync_request(&self) -> Option<&ConsensusSyncRequest> {
+        self.active_sync_request.as_ref()
+    }
+
+    /// Returns a mutable reference to the sync request occupying the slot (if any)
+    fn active_sync_request_mut(&mut self) -> Option<&mut ConsensusSyncRequest> {
+        self.active_sync_request.as_mut()
+    }
+
+    /// Places the given sync request into the (empty) slot
+    fn occupy(&mut self, sync_request: ConsensusSyncRequest) {
+        if let Some(active_sync_request) = &self.active_sync_request {
+            panic!(
+                "The consensus sync request slot is already occupied! Active target: {:?}",
+                active_sync_request.get_sync_target_version()
+            );
+        }
+        self.active_sync_request = Some(sync_request);
+    }
+
+    /// Removes and returns the sync request occupying the slot (if any)
+    fn take(&mut self) -> Option<ConsensusSyncRequest> {
+        self.active_sync_request.take()
+    }
+}
+
+/// A simple handler for consensus notifications
+pub struct ConsensusNotificationHandler {
+    // The listener for notifications from consensus
+    consensus_listener: ConsensusNotificationListener,
+
+    // The slot holding the active consensus sync request (if any)
+    consensus_sync_request_slot: ConsensusSyncRequestSlot,
+
+    // The configuration of the state sync driver
+    driver_config: StateSyncDriverConfig,
+
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The time service used to start (and time out) sync requests
+    time_service: TimeService,
+}
+
+impl ConsensusNotificationHandler {
+    pub fn new(
+        driver_config: StateSyncDriverConfig,
+        consensus_listener: ConsensusNotificationListener,
+        time_service: TimeService,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        Self {
+            consensus_listener,
+            consensus_sync_request_slot: ConsensusSyncRequestSlot::default(),
+            driver_config,
+            driver_metrics,
+            time_service,
+        }
+    }
+
+    /// Returns true iff there is a sync request currently blocking consensus
+    pub fn active_sync_request(&self) -> bool {
+        self.consensus_sync_request_slot
+            .active_sync_request()
+            .is_some()
+    }
+
+    /// Returns the target of the sync request that consensus is waiting on (if any)
+    pub fn get_sync_request_target(&self) -> Option<LedgerInfoWithSignatures> {
+        self.consensus_sync_request_slot
+            .active_sync_request()
+            .map(|sync_request| sync_request.get_sync_target())
+    }
+
+    /// Returns true iff the target of the active sync request has been verified.
+    /// If there's no active sync request, this trivially returns true.
+    pub fn is_sync_target_verified(&self) -> bool {
+        self.consensus_sync_request_slot
+            .active_sync_request()
+            .map(|sync_request| sync_request.is_sync_target_verified())
+            .unwrap_or(true)
+    }
+
+    /// Marks the target of the active sync request as verified (e.g., once the
+    /// epoch changes up to a future epoch target have been verified). This is
+    /// ignored if the active sync request is for a different target.
+    pub fn mark_sync_target_verified(&mut self, verified_sync_target: &LedgerInfoWithSignatures) {
+        if let Some(sync_request) = self.consensus_sync_request_slot.active_sync_request_mut() {
+            if !sync_request.is_sync_target_verified()
+                && &sync_request.get_sync_target() == verified_sync_target
+            {
+                info!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "The future epoch sync target has been verified! Target version: {:?}",
+                        sync_request.get_sync_target_version()
+                    ))
+                );
+                sync_request.mark_sync_target_verified(self.time_service.now());
+            }
+        }
+    }
+
+    /// Attempts to initialize the sync request received from consensus. If
+    /// another sync request is already active, the new request is rejected
+    /// (and answered) with a SyncRequestSlotOccupied error. Likewise, if the
+    /// sync target fails verification against the latest epoch state, the
+    /// request is rejected (and answered) with an InvalidSyncTarget error.
+    pub async fn try_initialize_sync_request(
+        &mut self,
+        sync_notification: ConsensusSyncNotification,
+        latest_synced_ledger_info: LedgerInfoWithSignatures,
+        latest_epoch_state: EpochState,
+    ) -> Result<(), Error> {
+        // If the slot is already occupied, reject the new request
+        if let Some(active_sync_request) = self.consensus_sync_request_slot.active_sync_request() {
+            let error = Err(Error::SyncRequestSlotOccupied(
+                active_sync_request.get_sync_target_version(),
+            ));
+            self.respond_to_sync_notification(sync_notification, error.clone())
+                .await?;
+            return error;
+        }
+
+        // Get the latest committed version and the target sync version
+        let sync_target_version = sync_notification.target.ledger_info().version();
+        let latest_committed_version = latest_synced_ledger_info.ledger_info().version();
+
+        // If the target version is old, return an error to consensus (something is wrong!)
+        if sync_target_version < latest_committed_version {
+            let error = Err(Error::OldSyncRequest(
+                sync_target_version,
+                latest_committed_version,
+            ));
+            self.respond_to_sync_notification(sync_notification, error.clone())
+                .await?;
+            return error;
+        }
+
+        // If we're now at the target, return successfully
+        if sync_target_version == latest_committed_version {
+            info!(LogSchema::new(LogEntry::NotificationHandler)
+                .message("We're already at the requested sync target version! Returning early"));
+            let result = Ok(());
+            self.respond_to_sync_notification(sync_notification, result.clone())
+                .await?;
+            return result;
+        }
+
+        // Verify the sync target before accepting the request. Targets in future
+        // epochs are accepted, but they must be verified before we sync to them.
+        let sync_target_verified =
+            match verify_sync_target(&sync_notification.target, &latest_epoch_state) {
+                Ok(sync_target_verified) => sync_target_verified,
+                Err(error) => {
+                    self.respond_to_sync_notification(sync_notification, Err(error.clone()))
+                        .await?;
+                    return Err(error);
+                }
+            };
+
+        // Save the request so we can notify consensus once we've hit the target
+        let consensus_sync_request = ConsensusSyncRequest::new(
+            sync_notification,
+            latest_committed_version,
+            sync_target_verified,
+            self.time_service.now(),
+        );
+        self.consensus_sync_request_slot
+            .occupy(consensus_sync_request);
+
+        Ok(())
+    }
+
+    /// Clears the active sync request (if any) if consensus is no longer
+    /// waiting on it, e.g., if consensus crashed and restarted while the
+    /// request was outstanding. Returns true iff a stale request was cleared.
+    pub fn clear_stale_sync_request(&mut self) -> bool {
+        let stale_sync_request = match self.consensus_sync_request_slot.active_sync_request() {
+            Some(sync_request) if sync_request.is_callback_closed() => {
+                self.consensus_sync_request_slot.take()
+            }
+            _ => None,
+        };
+
+        if let Some(mut stale_sync_request) = stale_sync_request {
+            warn!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                    "Consensus is no longer waiting on the sync request! Discarding the \
+                    request with target version: {:?}",
+                    stale_sync_request.get_sync_target_version()
+                ))
+            );
+            self.driver_metrics
+                .increment_counter(&metrics::DRIVER_COUNTERS, metrics::STALE_SYNC_REQUEST);
+
+            // Nobody is waiting on the callback, so it can't be answered
+            let _ = stale_sync_request.take_sync_notification();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Supersedes the active sync request (if any) by answering it with a
+    /// SyncRequestSuperseded error. This frees the slot for the new request.
+    pub async fn supersede_sync_request(
+        &mut self,
+        new_sync_target_version: Version,
+    ) -> Result<(), Error> {
+        if let Some(sync_request) = self.consensus_sync_request_slot.take() {
+            let error = Error::SyncRequestSuperseded(
+                sync_request.get_sync_target_version(),
+                new_sync_target_version,
+            );
+            self.respond_to_sync_request(sync_request, Err(error))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Rejects the active sync request (if any) by answering it with the given
+    /// error (e.g., if the sync target failed verification).
+    pub async fn reject_sync_request(&mut self, error: Error) -> Result<(), Error> {
+        if let Some(sync_request) = self.consensus_sync_request_slot.take() {
+            self.respond_to_sync_request(sync_request, Err(error))
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Checks to see if the sync request has been successfully fulfilled (or
+    /// has timed out)
+    pub async fn check_sync_request_progress(
+        &mut self,
+        latest_committed_version: Version,
+    ) -> Result<(), Error> {
+        // Fetch the sync target version, start time, initial synced version
+        // and target verification status.
+        let (sync_target_version, start_time, initial_synced_version, sync_target_verified) =
+            match self.consensus_sync_request_slot.active_sync_request() {
+                Some(sync_request) => (
+                    sync_request.get_sync_target_version(),
+                    sync_request.get_start_time(),
+                    sync_request.get_initial_synced_version(),
+                    sync_request.is_sync_target_verified(),
+                ),
+                None => return Ok(()), // There's no active sync request
+            };
+
+        // Compare our local state to the target version
+        let elapsed_time = self
+            .time_service
+            .now()
+            .saturating_duration_since(start_time);
+        let elapsed_time_ms = elapsed_time.as_millis() as u64;
+        let versions_advanced = latest_committed_version.saturating_sub(initial_synced_version);
+
+        // Check if we've synced beyond the target
+        if latest_committed_version > sync_target_version {
+            return Err(Error::SyncedBeyondTarget(
+                latest_committed_version,
+                sync_target_version,
+                elapsed_time_ms,
+                versions_advanced,
+            ));
+        }
+
+        // Check if we've hit the target
+        if latest_committed_version == sync_target_version {
+            info!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                    "The consensus sync request for version {:?} was fulfilled! \
+                    Elapsed time (ms): {:?}, versions advanced: {:?}",
+                    sync_target_version, elapsed_time_ms, versions_advanced
+                ))
+            );
+            if let Some(sync_request) = self.consensus_sync_request_slot.take() {
+                self.respond_to_sync_request(sync_request, Ok(())).await?;
+            }
+            return Ok(());
+        }
+
+        // Check if the sync request has timed out. Requests with unverified
+        // targets can't time out (the epoch changes up to the target are
+        // still being synced), so the timeout only starts once verified.
+        let max_sync_request_duration_ms =
+            self.driver_config.max_consensus_sync_request_duration_ms;
+        if sync_target_verified
+            && max_sync_request_duration_ms > 0
+            && elapsed_time >= Duration::from_millis(max_sync_request_duration_ms)
+        {
+            let error =
+                Error::SyncRequestTimeout(sync_target_version, elapsed_time_ms, versions_advanced);
+            if let Some(sync_request) = self.consensus_sync_request_slot.take() {
+                self.respond_to_sync_request(sync_request, Err(error.clone()))
+                    .await?;
+            }
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    /// Responds to consensus for the given sync request using the specified
+    /// result. The request has already been removed from the slot, so if the
+    /// consensus receiver was dropped (i.e., nobody is waiting on the request),
+    /// the failed response is not treated as an error.
+    async fn respond_to_sync_request(
+        &mut self,
+        mut sync_request: ConsensusSyncRequest,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        let sync_notification = sync_request.take_sync_notification();
+        match self
+            .respond_to_sync_notification(sync_notification, result)
+            .await
+        {
+            Err(Error::ConsensusReceiverDropped(_)) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Responds to consensus for a sync notification using the specified result
+    pub async fn respond_to_sync_notification(
+        &mut self,
+        sync_notification: ConsensusSyncNotification,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        // Wrap the result in an error that consensus can process
+        let message = create_consensus_response_message(result);
+
+        info!(
+            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "Responding to consensus sync notification with message: {:?}",
+                message
+            ))
+        );
+
+        // Send the result
+        let response = self
+            .consensus_listener
+            .respond_to_sync_notification(sync_notification, message);
+        handle_consensus_response(response, "sync", &self.driver_metrics).await
+    }
+
+    /// Responds successfully to consensus for a commit notification
+    pub async fn respond_to_commit_notification(
+        &mut self,
+        commit_notification: ConsensusCommitNotification,
+        result: Result<(), Error>,
+    ) -> Result<(), Error> {
+        // Wrap the result in an error that consensus can process
+        let message = create_consensus_response_message(result);
+
+        debug!(
+            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "Responding to consensus commit notification with message: {:?}",
+                message
+            ))
+        );
+
+        // Send the result
+        let response = self
+            .consensus_listener
+            .respond_to_commit_notification(commit_notification, message);
+        handle_consensus_response(response, "commit", &self.driver_metrics).await
+    }
+}
+
+/// Converts the given result into a response message that consensus can process
+fn create_consensus_response_message(
+    result: Result<(), Error>,
+) -> Result<(), consensus_notifications::Error> {
+    result.map_err(|error| {
+        consensus_notifications::Error::UnexpectedErrorEncountered(format!("{:?}", error))
+    })
+}
+
+/// Waits (with a timeout) for the given response to be sent to consensus, and
+/// classifies any failures. Responses only fail if consensus has dropped the
+/// receiver (e.g., consensus was torn down), or if the response times out.
+async fn handle_consensus_response(
+    response: impl Future<Output = Result<(), consensus_notifications::Error>>,
+    notification_type: &str,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), Error> {
+    // Failpoint "state_sync::before_consensus_response": drops the response
+    // before it is sent (so consensus observes a dropped callback, as it
+    // would if the driver crashed).
+    fail_point!("state_sync::before_consensus_response", |_| {
+        Err(Error::UnexpectedError(format!(
+            "Injected error before consensus {} response!",
+            notification_type
+        )))
+    });
+
+    let error = match timeout(
+        Duration::from_millis(CONSENSUS_RESPONSE_TIMEOUT_MS),
+        response,
+    )
+    .await
+    {
+        Ok(Ok(())) => return Ok(()),
+        Ok(Err(error)) => {
+            driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::CONSENSUS_RECEIVER_DROPPED,
+            );
+            Error::ConsensusReceiverDropped(format!(
+                "Consensus {} response error: {:?}",
+                notification_type, error
+            ))
+        }
+        Err(_) => {
+            driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::CONSENSUS_RESPONSE_TIMEOUT,
+            );
+            Error::ConsensusResponseTimeout(CONSENSUS_RESPONSE_TIMEOUT_MS)
+        }
+    };
+    warn!(LogSchema::new(LogEntry::NotificationHandler)
+        .error(&error)
+        .message(&format!(
+            "Failed to respond to the consensus {} notification!",
+            notification_type
+        )));
+    Err(error)
+}
+
+/// Verifies the given sync target against the latest epoch state. Targets in
+/// the current epoch must carry a valid quorum of signatures, and targets in
+/// older epochs are always rejected. Targets in future epochs can't be verified
+/// until the epoch-ending ledger infos leading to them have been synced, so
+/// they're accepted here (but returned as unverified, i.e., false).
+fn verify_sync_target(
+    sync_target: &LedgerInfoWithSignatures,
+    latest_epoch_state: &EpochState,
+) -> Result<bool, Error> {
+    let sync_target_epoch = sync_target.ledger_info().epoch();
+    if sync_target_epoch > latest_epoch_state.epoch {
+        info!(
+            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "The sync target is in a future epoch and can't be verified yet! \
+                Target epoch: {:?}, latest epoch: {:?}",
+                sync_target_epoch, latest_epoch_state.epoch
+            ))
+        );
+        return Ok(false);
+    }
+
+    latest_epoch_state.verify(sync_target).map(|_| true).map_err(|error| {
+        Error::InvalidSyncTarget(format!(
+            "Failed to verify the sync target at version {:?} against the latest epoch state: {:?}. Error: {:?}",
+            sync_target.ledger_info().version(),
+            latest_epoch_state,
+            error
+        ))
+    })
+}
+
+impl Drop for ConsensusNotificationHandler {
+    fn drop(&mut self) {
+        // Abort any active sync request so that consensus isn't left waiting.
+        // Note: responding only sends the result along a oneshot channel, so
+        // the response completes immediately (without blocking). We avoid the
+        // response timeout here, as the handler may be dropped outside of a runtime.
+        if let Some(mut sync_request) = self.consensus_sync_request_slot.take() {
+            let error = Error::SyncRequestAborted("The state sync driver has shut down!".into());
+            let message = create_consensus_response_message(Err(error));
+            let _ = self
+                .consensus_listener
+                .respond_to_sync_notification(sync_request.take_sync_notification(), message)
+                .now_or_never();
+        }
+    }
+}
+
+/// A sync request sent by a driver client (e.g., backup verification or replay
+/// tooling), asking the node to sync to a specific target and then stop. The
+/// request holds the client callback until the request is answered.
+pub struct ClientSyncRequest {
+    callback: oneshot::Sender<Result<(), Error>>,
+    initial_synced_version: Version,
+    start_time: Instant,
+    sync_target: LedgerInfoWithSignatures,
+    sync_target_verified: bool, // False iff the target is in a future (unverified) epoch
+}
+
+impl ClientSyncRequest {
+    pub fn get_sync_target_version(&self) -> Version {
+        self.sync_target.ledger_info().version()
+    }
+
+    /// Answers the request (i.e., notifies the client) with the given result
+    fn respond(self, result: Result<(), Error>) {
+        if self.callback.send(result).is_err() {
+            warn!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "The client dropped the callback for the sync request with target version: {:?}",
+                self.sync_target.ledger_info().version()
+            ))
+            );
+        }
+    }
+}
+
+/// A simple handler for client sync requests. At most one client sync request
+/// can be active at a time. Once a request has been fulfilled, the node halts
+/// at the target version (i.e., it stops syncing) until the next request.
+pub struct ClientSyncRequestHandler {
+    // The client sync request that is currently active (if any)
+    active_sync_request: Option<ClientSyncRequest>,
+
+    // The version the node is halted at (if the last request was fulfilled)
+    halted_version: Option<Version>,
+
+    // The time service used to time the sync requests
+    time_service: TimeService,
+}
+
+impl ClientSyncRequestHandler {
+    pub fn new(time_service: TimeService) -> Self {
+        Self {
+            active_sync_request: None,
+            halted_version: None,
+            time_service,
+        }
+    }
+
+    /// Returns true iff there is an active client sync request
+    pub fn active_sync_request(&self) -> bool {
+        self.active_sync_request.is_some()
+    }
+
+    /// Returns the version the node is halted at (if any), i.e., the target
+    /// of the last fulfilled client sync request.
+    pub fn get_halted_version(&self) -> Option<Version> {
+        self.halted_version
+    }
+
+    /// Returns the target of the active client sync request (if any)
+    pub fn get_sync_request_target(&self) -> Option<LedgerInfoWithSignatures> {
+        self.active_sync_request
+            .as_ref()
+            .map(|sync_request| sync_request.sync_target.clone())
+    }
+
+    /// Returns true iff the target of the active sync request has been verified.
+    /// If there's no active sync request, this trivially returns true.
+    pub fn is_sync_target_verified(&self) -> bool {
+        self.active_sync_request
+            .as_ref()
+            .map(|sync_request| sync_request.sync_target_verified)
+            .unwrap_or(true)
+    }
+
+    /// Marks the target of the active sync request as verified. This is
+    /// ignored if the active sync request is for a different target.
+    pub fn mark_sync_target_verified(&mut self, verified_sync_target: &LedgerInfoWithSignatures) {
+        if let Some(sync_request) = self.active_sync_request.as_mut() {
+            if &sync_request.sync_target == verified_sync_target {
+                sync_request.sync_target_verified = true;
+            }
+        }
+    }
+
+    /// Attempts to initialize a client sync request to the given target
+    /// version. The target ledger info (which bounds the data stream) must be
+    /// given, unless the node has already synced to the target version. If the
+    /// request is invalid, it is rejected (and answered) with an error.
+    pub fn try_initialize_sync_request(
+        &mut self,
+        target_version: Version,
+        target_ledger_info: Option<LedgerInfoWithSignatures>,
+        callback: oneshot::Sender<Result<(), Error>>,
+        latest_synced_ledger_info: LedgerInfoWithSignatures,
+        latest_epoch_state: EpochState,
+    ) -> Result<(), Error> {
+        match self.verify_sync_request(
+            target_version,
+            target_ledger_info,
+            latest_synced_ledger_info,
+            latest_epoch_state,
+        ) {
+            Ok(Some((sync_target, sync_target_verified, initial_synced_version))) => {
+                info!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "Accepted a client sync request for version {:?}! Latest synced version: {:?}",
+                        target_version, initial_synced_version
+                    ))
+                );
+                self.active_sync_request = Some(ClientSyncRequest {
+                    callback,
+                    initial_synced_version,
+                    start_time: self.time_service.now(),
+                    sync_target,
+                    sync_target_verified,
+                });
+                self.halted_version = None;
+                Ok(())
+            }
+            Ok(None) => {
+                // We're already at the target, so halt here and return successfully
+                info!(LogSchema::new(LogEntry::NotificationHandler).message(
+                    "We're already at the requested client sync target version! Returning early"
+                ));
+                self.halted_version = Some(target_version);
+                let _ = callback.send(Ok(()));
+                Ok(())
+            }
+            Err(error) => {
+                let _ = callback.send(Err(error.clone()));
+                Err(error)
+            }
+        }
+    }
+
+    /// Verifies the given client sync request. Returns the sync target, its
+    /// verification status and the latest synced version, or None if the
+    /// node is already at the target version.
+    fn verify_sync_request(
+        &self,
+        target_version: Version,
+        target_ledger_info: Option<LedgerInfoWithSignatures>,
+        latest_synced_ledger_info: LedgerInfoWithSignatures,
+        latest_epoch_state: EpochState,
+    ) -> Result<Option<(LedgerInfoWithSignatures, bool, Version)>, Error> {
+        // If another client sync request is active, reject the new request
+        if let Some(active_sync_request) = &self.active_sync_request {
+            return Err(Error::ConflictingSyncRequest(format!(
+                "A client sync request is already active with target version: {:?}",
+                active_sync_request.get_sync_target_version()
+            )));
+        }
+
+        // Verify the target version isn't old, and that we're not already there
+        let latest_synced_version = latest_synced_ledger_info.ledger_info().version();
+        if target_version < latest_synced_version {
+            return Err(Error::OldSyncRequest(target_version, latest_synced_version));
+        }
+        if target_version == latest_synced_version {
+            return Ok(None);
+        }
+
+        // Verify the target ledger info exists and matches the target version
+        let sync_target = target_ledger_info.ok_or_else(|| {
+            Error::InvalidSyncTarget(format!(
+                "A target ledger info is required to sync beyond the latest synced version! \
+                Target version: {:?}, latest synced version: {:?}",
+                target_version, latest_synced_version
+            ))
+        })?;
+        if sync_target.ledger_info().version() != target_version {
+            return Err(Error::InvalidSyncTarget(format!(
+                "The target ledger info version doesn't match the target version! \
+                Ledger info version: {:?}, target version: {:?}",
+                sync_target.ledger_info().version(),
+                target_version
+            )));
+        }
+
+        // Verify the target (targets in future epochs are verified later)
+        let sync_target_verified = verify_sync_target(&sync_target, &latest_epoch_state)?;
+        Ok(Some((
+            sync_target,
+            sync_target_verified,
+            latest_synced_version,
+        )))
+    }
+
+    /// Clears the active sync request (if any) if the client is no longer
+    /// waiting on it. Returns true iff a stale request was cleared.
+    pub fn clear_stale_sync_request(&mut self) -> bool {
+        let callback_closed = self
+            .active_sync_request
+            .as_ref()
+            .map(|sync_request| sync_request.callback.is_canceled())
+            .unwrap_or(false);
+        if callback_closed {
+            if let Some(stale_sync_request) = self.active_sync_request.take() {
+                warn!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "The client is no longer waiting on the sync request! Discarding the \
+                        request with target version: {:?}",
+                        stale_sync_request.get_sync_target_version()
+                    ))
+                );
+            }
+        }
+        callback_closed
+    }
+
+    /// Rejects the active sync request (if any) by answering it with the given
+    /// error (e.g., if the sync target failed verification).
+    pub fn reject_sync_request(&mut self, error: Error) {
+        if let Some(sync_request) = self.active_sync_request.take() {
+            sync_request.respond(Err(error));
+        }
+    }
+
+    /// Checks to see if the active sync request has been fulfilled. If the
+    /// node has synced beyond the target, the request is answered with an
+    /// error (and the node isn't halted).
+    pub fn check_sync_request_progress(
+        &mut self,
+        latest_committed_version: Version,
+    ) -> Result<(), Error> {
+        let sync_request = match self.active_sync_request.take() {
+            Some(sync_request) => sync_request,
+            None => return Ok(()), // There's no active sync request
+        };
+
+        // Compare our local state to the target version
+        let sync_target_version = sync_request.get_sync_target_version();
+        let elapsed_time_ms = self
+            .time_service
+            .now()
+            .saturating_duration_since(sync_request.start_time)
+            .as_millis() as u64;
+        let versions_advanced =
+            latest_committed_version.saturating_sub(sync_request.initial_synced_version);
+
+        // Check if we've synced beyond the target
+        if latest_committed_version > sync_target_version {
+            let error = Error::SyncedBeyondTarget(
+                latest_committed_version,
+                sync_target_version,
+                elapsed_time_ms,
+                versions_advanced,
+            );
+            sync_request.respond(Err(error.clone()));
+            return Err(error);
+        }
+
+        // Check if we've hit the target
+        if latest_committed_version == sync_target_version {
+            info!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                    "The client sync request for version {:?} was fulfilled! Halting at the \
+                    target. Elapsed time (ms): {:?}, versions advanced: {:?}",
+                    sync_target_version, elapsed_time_ms, versions_advanced
+                ))
+            );
+            self.halted_version = Some(sync_target_version);
+            sync_request.respond(Ok(()));
+            return Ok(());
+        }
+
+        // The request is still in progress
+        self.active_sync_request = Some(sync_request);
+        Ok(())
+    }
+}
+
+impl Drop for ClientSyncRequestHandler {
+    fn drop(&mut self) {
+        // Abort any active sync request so that the client isn't left waiting
+        self.reject_sync_request(Error::SyncRequestAborted(
+            "The state sync driver has shut down!".into(),
+        ));
+    }
+}
+
+impl Stream for ConsensusNotificationHandler {
+    type Item = ConsensusNotification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().consensus_listener).poll_next(cx)
+    }
+}
+
+impl FusedStream for ConsensusNotificationHandler {
+    fn is_terminated(&self) -> bool {
+        self.consensus_listener.is_terminated()
+    }
+}
+
+/// A notification for error transactions and events that have been committed to
 /// storage.
 #[derive(Clone, Debug, Serialize)]
 pub struct ErrorNotification {
+    pub component: ErrorSource,
     pub error: Error,
+    pub expected_version: Option<Version>, // The version the failed chunk should have started at (if the chunk was discontinuous)
+    pub failed_operation: FailedOperation, // The storage synchronizer operation that failed
     pub notification_id: NotificationId,
+    pub severity: ErrorSeverity,
+    pub stage: StorageSynchronizerStage, // The storage synchronizer stage that raised the error
+    pub version_range: Option<(Version, Version)>, // The (inclusive) versions of the failed chunk (if known)
+}
+
+/// The severity of an error notification. Only recoverable and fatal errors
+/// require the owning component to terminate its active data stream.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ErrorSeverity {
+    Transient,   // The error was handled (e.g., the data was still committed)
+    Recoverable, // The data wasn't committed, so it must be refetched
+    Fatal,       // Storage may have been left in an unexpected state
+}
+
+impl ErrorSeverity {
+    /// Returns a summary label for the error severity
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ErrorSeverity::Transient => "transient",
+            ErrorSeverity::Recoverable => "recoverable",
+            ErrorSeverity::Fatal => "fatal",
+        }
+    }
+
+    /// Returns true iff errors of this severity require the active data
+    /// stream to be terminated.
+    pub fn terminates_stream(&self) -> bool {
+        !matches!(self, ErrorSeverity::Transient)
+    }
+}
+
+/// The component that owns the data stream for which an error was raised
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum ErrorSource {
+    Bootstrapper,
+    ContinuousSyncer,
+    StorageSynchronizer, // The error is not associated with a syncing component's stream
+}
+
+impl ErrorSource {
+    /// Returns a summary label for the error source
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ErrorSource::Bootstrapper => "bootstrapper",
+            ErrorSource::ContinuousSyncer => "continuous_syncer",
+            ErrorSource::StorageSynchronizer => "storage_synchronizer",
+        }
+    }
+}
+
+/// A simple wrapper for an error notification listener
+pub struct ErrorNotificationListener {
+    // The listener for error notifications
+    error_notification_listener: mpsc::UnboundedReceiver<ErrorNotification>,
+}
+
+impl ErrorNotificationListener {
+    pub fn new() -> (mpsc::UnboundedSender<ErrorNotification>, Self) {
+        // Create a channel to send and receive error notifications
+        let (error_notification_sender, error_notification_listener) = mpsc::unbounded();
+
+        // Create and return the sender and listener
+        let error_notification_listener = Self {
+            error_notification_listener,
+        };
+        (error_notification_sender, error_notification_listener)
+    }
+}
+
+impl Stream for ErrorNotificationListener {
+    type Item = ErrorNotification;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().error_notification_listener).poll_next(cx)
+    }
+}
+
+impl FusedStream for ErrorNotificationListener {
+    fn is_terminated(&self) -> bool {
+        self.error_notification_listener.is_terminated()
+    }
+}
+
+/// Tracks the last stream terminated due to an error notification. A single
+/// failing chunk can produce several error notifications in quick succession
+/// (e.g., from the executor and then the committer), so this allows the
+/// driver to identify notifications that reference an already-terminated
+/// stream. Note: notification IDs are monotonically increasing across streams.
+#[derive(Clone, Debug, Default)]
+pub struct TerminatedStreamTracker {
+    driver_metrics: DriverMetrics,
+    last_terminated_stream: Option<(ErrorSource, NotificationId)>,
+    last_terminated_version_range: Option<(Version, Version)>, // The versions of the chunk that caused the last termination
+}
+
+impl TerminatedStreamTracker {
+    pub fn new(driver_metrics: DriverMetrics) -> Self {
+        Self {
+            driver_metrics,
+            ..Self::default()
+        }
+    }
+
+    /// Returns true iff the given error notification references a stream
+    /// that has already been terminated.
+    pub fn is_already_terminated(&self, error_notification: &ErrorNotification) -> bool {
+        match self.last_terminated_stream {
+            Some((component, notification_id)) => {
+                component == error_notification.component
+                    && error_notification.notification_id <= notification_id
+            }
+            None => false,
+        }
+    }
+
+    /// Records that the stream referenced by the given error notification
+    /// has been terminated (including the versions and stage that failed).
+    pub fn mark_terminated(&mut self, error_notification: &ErrorNotification) {
+        self.last_terminated_stream = Some((
+            error_notification.component,
+            error_notification.notification_id,
+        ));
+        self.last_terminated_version_range = error_notification.version_range;
+        self.driver_metrics.increment_terminated_stream(
+            error_notification.component.get_label(),
+            error_notification.stage.get_label(),
+        );
+    }
+
+    /// Returns the versions of the chunk that caused the last stream
+    /// termination (if known).
+    pub fn get_last_terminated_version_range(&self) -> Option<(Version, Version)> {
+        self.last_terminated_version_range
+    }
+}
+
+/// A notifier that handles consensus commits (i.e., notifies mempool and the
+/// event subscription service) on a dedicated task, fed by an ordered, bounded
+/// channel. This allows the driver to respond to consensus before downstream
+/// components have been notified, while still preserving the commit order.
+#[derive(Clone)]
+pub struct ConsensusCommitNotifier {
+    // The sender for pending consensus commits
+    pending_commit_sender: mpsc::Sender<PendingConsensusCommit>,
 }
 
-/// A simple wrapper for an error notification listener
-pub struct ErrorNotificationListener {
-    // The listener for error notifications
-    error_notification_listener: mpsc::UnboundedReceiver<ErrorNotification>,
+impl ConsensusCommitNotifier {
+    pub fn new<M: MempoolNotificationSender>(
+        driver_config: StateSyncDriverConfig,
+        storage: Arc<dyn DbReader>,
+        mempool_notification_handler: MempoolNotificationHandler<M>,
+        event_notification_handler: EventNotificationHandler,
+        commit_state_tracker: CommitStateTracker,
+        commit_subscriber_registry: CommitSubscriberRegistry,
+        notified_version_tracker: NotifiedVersionTracker,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        // Create a channel to send and receive pending consensus commits
+        let (pending_commit_sender, pending_commit_listener) =
+            mpsc::channel(driver_config.max_pending_commit_notifications as usize);
+
+        // Spawn the task that handles the pending commits
+        tokio::spawn(handle_pending_consensus_commits(
+            pending_commit_listener,
+            storage,
+            mempool_notification_handler,
+            event_notification_handler,
+            commit_state_tracker,
+            commit_subscriber_registry,
+            notified_version_tracker,
+            driver_metrics,
+        ));
+
+        Self {
+            pending_commit_sender,
+        }
+    }
+
+    /// Enqueues the committed transactions for handling. If `wait_for_delivery`
+    /// is true, this waits until the transactions (and all previously enqueued
+    /// transactions) have been handled.
+    pub async fn notify_committed_transactions(
+        &mut self,
+        committed_transactions: CommittedTransactions,
+        wait_for_delivery: bool,
+    ) -> Result<(), Error> {
+        let (notification_callback, callback_receiver) = if wait_for_delivery {
+            let (callback, callback_receiver) = oneshot::channel();
+            (Some(callback), Some(callback_receiver))
+        } else {
+            (None, None)
+        };
+
+        // Enqueue the commit
+        let pending_commit = PendingConsensusCommit {
+            committed_transactions,
+            notification_callback,
+        };
+        self.pending_commit_sender.send(pending_commit).await?;
+
+        // Wait for the commit to be handled (if required)
+        if let Some(callback_receiver) = callback_receiver {
+            callback_receiver.await?;
+        }
+        Ok(())
+    }
 }
 
-impl ErrorNotificationListener {
-    pub fn new() -> (mpsc::UnboundedSender<ErrorNotification>, Self) {
-        // Create a channel to send and receive error notifications
-        let (error_notification_sender, error_notification_listener) = mpsc::unbounded();
+/// A consensus commit that is pending downstream notification
+struct PendingConsensusCommit {
+    committed_transactions: CommittedTransactions,
+    notification_callback: Option<oneshot::Sender<()>>,
+}
 
-        // Create and return the sender and listener
-        let error_notification_listener = Self {
-            error_notification_listener,
+/// Handles all pending consensus commits (in order)
+async fn handle_pending_consensus_commits<M: MempoolNotificationSender>(
+    mut pending_commit_listener: mpsc::Receiver<PendingConsensusCommit>,
+    storage: Arc<dyn DbReader>,
+    mempool_notification_handler: MempoolNotificationHandler<M>,
+    event_notification_handler: EventNotificationHandler,
+    commit_state_tracker: CommitStateTracker,
+    commit_subscriber_registry: CommitSubscriberRegistry,
+    notified_version_tracker: NotifiedVersionTracker,
+    driver_metrics: DriverMetrics,
+) {
+    while let Some(pending_commit) = pending_commit_listener.next().await {
+        let committed_transactions = pending_commit.committed_transactions;
+        let (first_version, last_version) = (
+            committed_transactions.first_version,
+            committed_transactions.last_version,
+        );
+        if let Err(error) = utils::handle_committed_transactions(
+            committed_transactions,
+            CommitOrigin::Consensus,
+            storage.clone(),
+            mempool_notification_handler.clone(),
+            event_notification_handler.clone(),
+            commit_state_tracker.clone(),
+            commit_subscriber_registry.clone(),
+            notified_version_tracker.clone(),
+            driver_metrics,
+        )
+        .await
+        {
+            utils::log_commit_notification_error(
+                LogEntry::ConsensusNotification,
+                CommitOrigin::Consensus,
+                first_version,
+                last_version,
+                &error,
+            );
+        }
+
+        // Respond to the callback (if one exists)
+        if let Some(notification_callback) = pending_commit.notification_callback {
+            let _ = notification_callback.send(());
+        }
+    }
+}
+
+/// A handler for notifying the event subscription service of committed events.
+/// Events are notified by a dedicated task (fed by an ordered, bounded channel)
+/// so that slow subscribers can't block the driver.
+#[derive(Clone)]
+pub struct EventNotificationHandler {
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The sender for pending event notifications
+    event_notification_sender: mpsc::Sender<PendingEventNotification>,
+
+    // Whether or not the service has reconfig subscriptions that must be
+    // resynced to the latest on-chain configs (owned by the service).
+    reconfig_resync_required: Arc<AtomicBool>,
+
+    // The event keys with at least one subscriber (owned by the service)
+    subscribed_event_keys: Arc<RwLock<HashSet<EventKey>>>,
+}
+
+impl EventNotificationHandler {
+    pub fn new(
+        event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+        max_pending_event_notifications: u64,
+        runtime: Option<Handle>,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        // Fetch the shared service state before handing the service to the notifier
+        let (reconfig_resync_required, subscribed_event_keys) = {
+            let event_subscription_service = event_subscription_service.lock();
+            (
+                event_subscription_service.get_reconfig_resync_required(),
+                event_subscription_service.get_subscribed_event_keys(),
+            )
         };
-        (error_notification_sender, error_notification_listener)
+
+        // Create a channel to send and receive pending event notifications
+        let (event_notification_sender, event_notification_listener) =
+            mpsc::channel(max_pending_event_notifications as usize);
+
+        // Spawn the task that notifies the event subscription service
+        let event_notifier = notify_event_subscribers(
+            event_subscription_service,
+            event_notification_listener,
+            driver_metrics,
+        );
+        if let Some(runtime) = runtime {
+            runtime.spawn(event_notifier);
+        } else {
+            tokio::spawn(event_notifier);
+        }
+
+        Self {
+            driver_metrics,
+            event_notification_sender,
+            reconfig_resync_required,
+            subscribed_event_keys,
+        }
+    }
+
+    /// Returns true iff the given events must be delivered to the event
+    /// subscription service, i.e., they contain a reconfiguration event or
+    /// an event with at least one subscriber (or the service has reconfig
+    /// subscriptions to resync). This only compares event keys (and never
+    /// locks the subscription service) so it's cheap to call.
+    pub fn requires_notification(&self, events: &[ContractEvent]) -> bool {
+        self.reconfig_resync_required()
+            || requires_event_notification(events, &self.subscribed_event_keys.read())
+    }
+
+    /// Returns true iff the service has reconfig subscriptions that have
+    /// missed the latest on-chain configs (e.g., re-created subscriptions).
+    fn reconfig_resync_required(&self) -> bool {
+        self.reconfig_resync_required.load(Ordering::Relaxed)
+    }
+
+    /// Notifies the event subscription service of the events committed at the
+    /// given version. If the events contain a reconfiguration, this waits for
+    /// the notification to be delivered (to preserve config-update ordering).
+    /// Otherwise, the notification is delivered asynchronously.
+    pub async fn notify_events(
+        &mut self,
+        version: Version,
+        events: Vec<ContractEvent>,
+    ) -> Result<(), Error> {
+        if events.is_empty() && !self.reconfig_resync_required() {
+            return Ok(()); // No events (and no reconfig subscriptions to resync)!
+        }
+
+        // Only wait for delivery if a reconfiguration event was committed
+        let (_, contains_new_epoch_event) = utils::extract_reconfig_events(&events);
+        let (notification_callback, callback_receiver) = if contains_new_epoch_event {
+            let (callback, callback_receiver) = oneshot::channel();
+            (Some(callback), Some(callback_receiver))
+        } else {
+            (None, None)
+        };
+
+        // Enqueue the notification
+        let pending_notification = PendingEventNotification {
+            enqueue_time: Instant::now(),
+            events,
+            notification_callback,
+            version,
+        };
+        self.event_notification_sender
+            .send(pending_notification)
+            .await?;
+        self.driver_metrics.increment_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::PENDING_EVENT_NOTIFICATIONS,
+            1,
+        );
+
+        // Wait for the notification to be delivered (if required)
+        if let Some(callback_receiver) = callback_receiver {
+            callback_receiver.await??;
+        }
+        Ok(())
     }
 }
 
-impl Stream for ErrorNotificationListener {
-    type Item = ErrorNotification;
+/// An event notification that is pending delivery to the subscription service
+struct PendingEventNotification {
+    enqueue_time: Instant,
+    events: Vec<ContractEvent>,
+    notification_callback: Option<oneshot::Sender<Result<(), Error>>>,
+    version: Version,
+}
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.get_mut().error_notification_listener).poll_next(cx)
+/// Notifies the event subscription service of all pending event notifications (in order)
+async fn notify_event_subscribers(
+    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+    mut event_notification_listener: mpsc::Receiver<PendingEventNotification>,
+    driver_metrics: DriverMetrics,
+) {
+    while let Some(pending_notification) = event_notification_listener.next().await {
+        driver_metrics.decrement_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::PENDING_EVENT_NOTIFICATIONS,
+            1,
+        );
+
+        // Notify the event subscription service
+        let result = notify_event_subscription_service(
+            &mut event_subscription_service.lock(),
+            pending_notification.version,
+            pending_notification.events,
+            &driver_metrics,
+        );
+        driver_metrics
+            .histogram(&metrics::EVENT_NOTIFICATION_LATENCY, &[])
+            .observe(pending_notification.enqueue_time.elapsed().as_secs_f64());
+        if let Err(error) = &result {
+            error!(LogSchema::new(LogEntry::NotificationHandler)
+                .error(error)
+                .message(&format!(
+                    "Failed to notify the event subscription service of events at version: {:?}",
+                    pending_notification.version
+                )));
+        }
+
+        // Respond to the callback (if one exists)
+        if let Some(notification_callback) = pending_notification.notification_callback {
+            let _ = notification_callback.send(result);
+        }
     }
 }
 
-impl FusedStream for ErrorNotificationListener {
-    fn is_terminated(&self) -> bool {
-        self.error_notification_listener.is_terminated()
+/// Notifies the event subscription service of the events at the given version.
+/// Closed subscriptions (e.g., subscribers whose channels were dropped) are
+/// removed by the service, and don't affect the other subscribers. Any reconfig
+/// subscriptions that have missed the latest on-chain configs (e.g., those that
+/// were re-created by the subscriber) are resynced to the configs at the version.
+fn notify_event_subscription_service(
+    event_subscription_service: &mut EventSubscriptionService,
+    version: Version,
+    events: Vec<ContractEvent>,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), Error> {
+    // Notify the subscribers of the events
+    let notify_result = event_subscription_service.notify_events(version, events);
+    handle_closed_subscriptions(notify_result, version, driver_metrics)?;
+
+    // Resync any unsynced reconfig subscriptions
+    match event_subscription_service.resync_reconfig_subscriptions(version) {
+        Ok(num_resynced_subscriptions) => {
+            if num_resynced_subscriptions > 0 {
+                info!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "Resynced {:?} reconfig subscriptions to the on-chain configs at version: {:?}",
+                        num_resynced_subscriptions, version
+                    ))
+                );
+                driver_metrics
+                    .counter(
+                        &metrics::EVENT_SUBSCRIPTION_RECOVERY,
+                        &[metrics::RESYNCED_RECONFIG_SUBSCRIPTION],
+                    )
+                    .inc_by(num_resynced_subscriptions as u64);
+            }
+            Ok(())
+        }
+        Err(error) => handle_closed_subscriptions(Err(error), version, driver_metrics),
+    }
+}
+
+/// Handles the result of an event subscription service notification. If the
+/// only failures were closed subscriptions (which have been removed by the
+/// service), the failures are logged and metered, and the notification is
+/// treated as successful.
+fn handle_closed_subscriptions(
+    result: Result<(), event_notifications::Error>,
+    version: Version,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), Error> {
+    match result {
+        Err(event_notifications::Error::ClosedSubscriptions(closed_subscription_ids)) => {
+            warn!(
+                LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                    "Removed closed event subscriptions when notifying version: {:?}. \
+                    Subscription IDs: {:?}",
+                    version, closed_subscription_ids
+                ))
+            );
+            driver_metrics
+                .counter(
+                    &metrics::EVENT_SUBSCRIPTION_RECOVERY,
+                    &[metrics::CLOSED_EVENT_SUBSCRIPTION],
+                )
+                .inc_by(closed_subscription_ids.len() as u64);
+            Ok(())
+        }
+        result => result.map_err(|error| error.into()),
     }
 }
 
 /// A simple handler for sending notifications to mempool
 #[derive(Clone)]
 pub struct MempoolNotificationHandler<M> {
-    mempool_notification_sender: M,
+    // The configuration of the state sync driver
+    driver_config: StateSyncDriverConfig,
+
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The sender used to notify mempool of committed transactions (if
+    // there is no mempool, e.g., for tooling, all notifications are skipped).
+    mempool_notification_sender: Option<M>,
+
+    // The batch of committed transactions not yet sent to mempool (if batching is enabled)
+    notification_batch: Arc<Mutex<MempoolNotificationBatch>>,
+
+    // The highest version successfully delivered to mempool (if any). This is
+    // used to skip (or trim) notifications that have already been delivered.
+    highest_delivered_version: Arc<Mutex<Option<Version>>>,
+
+    // The notifications queued behind a failed notification that is being retried
+    pending_notifications: Arc<Mutex<PendingMempoolNotifications>>,
+
+    // The lock that serializes all sends across handler clones (i.e., batch
+    // updates, expired batch flushes and direct sends). This ensures that a
+    // newer notification is never delivered while an older one is in flight.
+    send_lock: Arc<AsyncMutex<()>>,
+
+    // The time service used for batching delays and retry backoffs (and deadlines)
+    time_service: TimeService,
 }
 
 impl<M: MempoolNotificationSender> MempoolNotificationHandler<M> {
-    pub fn new(mempool_notification_sender: M) -> Self {
+    pub fn new(
+        driver_config: StateSyncDriverConfig,
+        mempool_notification_sender: M,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        Self::new_with_optional_sender(
+            driver_config,
+            Some(mempool_notification_sender),
+            TimeService::real(),
+            driver_metrics,
+        )
+    }
+
+    /// Creates a handler that isn't connected to mempool (e.g., for tooling
+    /// and storage-only nodes). All mempool notifications are skipped.
+    pub fn new_without_mempool(
+        driver_config: StateSyncDriverConfig,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        Self::new_with_optional_sender(driver_config, None, TimeService::real(), driver_metrics)
+    }
+
+    /// Creates a handler for the given mempool notification sender (if any)
+    /// using the specified time service and driver metrics.
+    pub fn new_with_optional_sender(
+        driver_config: StateSyncDriverConfig,
+        mempool_notification_sender: Option<M>,
+        time_service: TimeService,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
         Self {
+            driver_config,
+            driver_metrics,
             mempool_notification_sender,
+            highest_delivered_version: Arc::new(Mutex::new(None)),
+            notification_batch: Arc::new(Mutex::new(MempoolNotificationBatch::default())),
+            pending_notifications: Arc::new(Mutex::new(PendingMempoolNotifications::default())),
+            send_lock: Arc::new(AsyncMutex::new(())),
+            time_service,
         }
     }
-    /// Notifies mempool that transactions have been committed.
+
+    /// Returns true iff the handler is connected to mempool
+    pub fn mempool_enabled(&self) -> bool {
+        self.mempool_notification_sender.is_some()
+    }
+
+    /// Notifies mempool that transactions have been committed. Mempool only
+    /// tracks user transactions, so all other transactions are filtered out
+    /// (the number of user transactions is returned). If batching is enabled,
+    /// the transactions are merged into the current batch and only sent once
+    /// the batch is full or has expired. If the notification fails transiently
+    /// (e.g., mempool is busy), it is retried with backoff on a dedicated task.
+    /// Notifications are always delivered in version order, and transactions
+    /// that have already been delivered are never re-sent.
     pub async fn notify_mempool_of_committed_transactions(
         &mut self,
         committed_transactions: Vec<Transaction>,
         block_timestamp_usecs: u64,
-    ) -> Result<(), Error> {
-        let result = self
-            .mempool_notification_sender
-            .notify_new_commit(
-                committed_transactions,
-                block_timestamp_usecs,
-                MEMPOOL_COMMIT_ACK_TIMEOUT_MS,
-            )
-            .await;
+        first_version: Version,
+        last_version: Version,
+        commit_origin: CommitOrigin,
+    ) -> Result<usize, Error> {
+        // If there's no mempool, there's nothing to notify
+        if !self.mempool_enabled() {
+            return Ok(0);
+        }
 
-        if let Err(error) = result {
-            let error = Error::NotifyMempoolError(format!("{:?}", error));
-            error!(LogSchema::new(LogEntry::NotificationHandler)
-                .error(&error)
-                .message("Failed to notify mempool of committed transactions!"));
-            Err(error)
+        let user_transactions =
+            filter_user_transactions(committed_transactions, first_version, &self.driver_metrics);
+        let num_user_transactions = user_transactions.len();
+        let notification = PendingMempoolNotification::new(
+            user_transactions,
+            block_timestamp_usecs,
+            first_version,
+            last_version,
+            commit_origin,
+        );
+
+        // Hold the send lock until the notification is batched or sent
+        let _send_guard = self.send_lock.lock().await;
+
+        // If batching is disabled, send the notification immediately
+        if !self.batching_enabled() {
+            self.send_notification(notification).await?;
+            return Ok(num_user_transactions);
+        }
+
+        // Otherwise, add the notification to the current batch. Batches only
+        // span contiguous versions (from the same origin), so an incompatible
+        // notification first forces the current batch to be sent.
+        let (incompatible_batch, full_batch, new_batch_id) = {
+            let mut notification_batch = self.notification_batch.lock();
+            let incompatible_batch =
+                notification_batch.take_incompatible_notification(&notification);
+            let new_batch_id = notification_batch.add_notification(notification);
+            let full_batch = if notification_batch.num_transactions()
+                >= self
+                    .driver_config
+                    .mempool_notification_batch_max_transactions
+            {
+                notification_batch.take_notification()
+            } else {
+                None
+            };
+            (incompatible_batch, full_batch, new_batch_id)
+        };
+        if let Some(incompatible_batch) = incompatible_batch {
+            self.send_notification(incompatible_batch).await?;
+        }
+
+        // If a new batch was started, flush it once the max batching delay expires
+        if let Some(new_batch_id) = new_batch_id {
+            self.spawn_batch_expiration_timer(new_batch_id);
+        }
+
+        // Send the batch if it's full
+        if let Some(full_batch) = full_batch {
+            self.send_notification(full_batch).await?;
+        }
+        Ok(num_user_transactions)
+    }
+
+    /// Immediately sends any batched notification to mempool (e.g., when
+    /// latency matters because a consensus sync request has completed).
+    pub async fn flush_batched_notifications(&mut self) -> Result<(), Error> {
+        let _send_guard = self.send_lock.lock().await;
+        let batched_notification = self.notification_batch.lock().take_notification();
+        if let Some(batched_notification) = batched_notification {
+            self.send_notification(batched_notification).await
         } else {
             Ok(())
         }
     }
+
+    /// Returns true iff mempool notifications should be batched
+    fn batching_enabled(&self) -> bool {
+        self.driver_config.mempool_notification_batch_max_delay_ms > 0
+    }
+
+    /// Spawns a timer that flushes the specified batch once the max
+    /// batching delay has expired (if the batch hasn't already been sent).
+    fn spawn_batch_expiration_timer(&self, batch_id: u64) {
+        let mempool_notification_handler = self.clone();
+        let max_batch_delay =
+            Duration::from_millis(self.driver_config.mempool_notification_batch_max_delay_ms);
+        tokio::spawn(async move {
+            mempool_notification_handler
+                .time_service
+                .sleep(max_batch_delay)
+                .await;
+
+            // The batch must be taken while holding the send lock, otherwise a
+            // newer notification could be delivered before the expired batch.
+            let _send_guard = mempool_notification_handler.send_lock.lock().await;
+            let expired_batch = mempool_notification_handler
+                .notification_batch
+                .lock()
+                .take_notification_for_batch(batch_id);
+            if let Some(expired_batch) = expired_batch {
+                let _ = mempool_notification_handler
+                    .send_notification(expired_batch)
+                    .await;
+            }
+        });
+    }
+
+    /// Sends the given notification to mempool. The caller must hold the send
+    /// lock until this returns, i.e., until the notification has either been
+    /// delivered or queued for retry.
+    async fn send_notification(
+        &self,
+        mut notification: PendingMempoolNotification,
+    ) -> Result<(), Error> {
+        // If older notifications are still being retried, queue the
+        // notification behind them (to preserve version ordering).
+        {
+            let mut pending_notifications = self.pending_notifications.lock();
+            if pending_notifications.retry_task_active {
+                pending_notifications.notifications.push_back(notification);
+                return Ok(());
+            }
+        }
+
+        // Otherwise, notify mempool directly (skipping anything already delivered)
+        let mempool_notification_sender = match &self.mempool_notification_sender {
+            Some(mempool_notification_sender) => mempool_notification_sender,
+            None => return Ok(()), // There's no mempool to notify
+        };
+        if !trim_delivered_transactions(
+            &mut notification,
+            &self.highest_delivered_version,
+            &self.driver_metrics,
+        ) {
+            return Ok(());
+        }
+        let result = notify_mempool(
+            mempool_notification_sender,
+            &notification,
+            &self.driver_metrics,
+        )
+        .await;
+
+        match result {
+            Ok(()) => {
+                record_delivered_notification(&notification, &self.highest_delivered_version);
+                Ok(())
+            }
+            Err(error) if is_transient_mempool_error(&error) => {
+                warn!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "Failed to notify mempool of committed transactions for versions {:?} to {:?}! \
+                        Retrying the notification. Error: {:?}",
+                        notification.first_version, notification.last_version, error
+                    ))
+                );
+                self.retry_notification(notification);
+                Ok(())
+            }
+            Err(error) => {
+                let error = Error::NotifyMempoolError(format!("{:?}", error));
+                error!(LogSchema::new(LogEntry::NotificationHandler)
+                    .error(&error)
+                    .message("Failed to notify mempool of committed transactions!"));
+                Err(error)
+            }
+        }
+    }
+
+    /// Queues the failed notification (at the front of the pending queue)
+    /// and spawns a retry task if one isn't already running.
+    fn retry_notification(&self, mut notification: PendingMempoolNotification) {
+        notification.record_failed_attempt(self.time_service.now());
+
+        let mempool_notification_sender = match &self.mempool_notification_sender {
+            Some(mempool_notification_sender) => mempool_notification_sender.clone(),
+            None => return, // There's no mempool to retry against
+        };
+
+        let mut pending_notifications = self.pending_notifications.lock();
+        pending_notifications.notifications.push_front(notification);
+        if !pending_notifications.retry_task_active {
+            pending_notifications.retry_task_active = true;
+            tokio::spawn(retry_pending_notifications(
+                self.driver_config,
+                self.driver_metrics,
+                mempool_notification_sender,
+                self.highest_delivered_version.clone(),
+                self.pending_notifications.clone(),
+                self.time_service.clone(),
+            ));
+        }
+    }
+}
+
+/// A mempool commit notification that is pending delivery
+struct PendingMempoolNotification {
+    block_timestamp_usecs: u64,
+    commit_origin: CommitOrigin,
+    committed_transactions: Vec<(Version, Transaction)>,
+    first_failure_time: Option<Instant>,
+    first_version: Version,
+    last_version: Version,
+    num_failed_attempts: u64,
+}
+
+impl PendingMempoolNotification {
+    fn new(
+        committed_transactions: Vec<(Version, Transaction)>,
+        block_timestamp_usecs: u64,
+        first_version: Version,
+        last_version: Version,
+        commit_origin: CommitOrigin,
+    ) -> Self {
+        Self {
+            block_timestamp_usecs,
+            commit_origin,
+            committed_transactions,
+            first_failure_time: None,
+            first_version,
+            last_version,
+            num_failed_attempts: 0,
+        }
+    }
+
+    /// Records a failed delivery attempt (made at the given time) for the notification
+    fn record_failed_attempt(&mut self, failure_time: Instant) {
+        if self.first_failure_time.is_none() {
+            self.first_failure_time = Some(failure_time);
+        }
+        self.num_failed_attempts += 1;
+    }
+
+    /// Removes all transactions at or below the given version. This assumes
+    /// the version is within the notification's version range.
+    fn remove_transactions_up_to(&mut self, version: Version) {
+        self.committed_transactions
+            .retain(|(transaction_version, _)| *transaction_version > version);
+        self.first_version = version + 1;
+    }
+
+    /// Merges the given (newer) notification into this notification
+    fn merge(&mut self, notification: PendingMempoolNotification) {
+        self.block_timestamp_usecs = self
+            .block_timestamp_usecs
+            .max(notification.block_timestamp_usecs);
+        self.committed_transactions
+            .extend(notification.committed_transactions);
+        self.last_version = notification.last_version;
+    }
+
+    /// Returns true iff the notification should no longer be retried (at the given time)
+    fn retries_exhausted(&self, driver_config: &StateSyncDriverConfig, now: Instant) -> bool {
+        if self.num_failed_attempts > driver_config.max_mempool_notification_retries {
+            return true;
+        }
+
+        let retry_deadline =
+            Duration::from_millis(driver_config.mempool_notification_retry_deadline_ms);
+        self.first_failure_time
+            .map(|first_failure_time| {
+                now.saturating_duration_since(first_failure_time) >= retry_deadline
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the backoff to wait before the next delivery attempt. The
+    /// backoff doubles with every failed attempt.
+    fn next_backoff(&self, driver_config: &StateSyncDriverConfig) -> Duration {
+        let num_doublings = self
+            .num_failed_attempts
+            .saturating_sub(1)
+            .min(MAX_BACKOFF_DOUBLINGS);
+        let backoff_ms = driver_config
+            .mempool_notification_retry_backoff_ms
+            .saturating_mul(1 << num_doublings);
+        Duration::from_millis(backoff_ms)
+    }
+}
+
+/// A batch of committed transactions that have not yet been sent to mempool
+#[derive(Default)]
+struct MempoolNotificationBatch {
+    batch_id: u64,
+    notification: Option<PendingMempoolNotification>,
+}
+
+impl MempoolNotificationBatch {
+    /// Adds the notification to the batch. If a new batch was started,
+    /// the ID of the new batch is returned.
+    fn add_notification(&mut self, notification: PendingMempoolNotification) -> Option<u64> {
+        if let Some(batched_notification) = self.notification.as_mut() {
+            batched_notification.merge(notification);
+            None
+        } else {
+            self.batch_id = self.batch_id.wrapping_add(1);
+            self.notification = Some(notification);
+            Some(self.batch_id)
+        }
+    }
+
+    /// Removes and returns the batched notification (if any) iff the given
+    /// notification can't be merged into it, i.e., the given notification
+    /// doesn't directly follow the batch or has a different commit origin.
+    fn take_incompatible_notification(
+        &mut self,
+        notification: &PendingMempoolNotification,
+    ) -> Option<PendingMempoolNotification> {
+        let batched_notification = self.notification.as_ref()?;
+        if batched_notification.last_version + 1 != notification.first_version
+            || batched_notification.commit_origin != notification.commit_origin
+        {
+            self.take_notification()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the number of transactions in the batch
+    fn num_transactions(&self) -> u64 {
+        self.notification
+            .as_ref()
+            .map(|notification| notification.committed_transactions.len() as u64)
+            .unwrap_or(0)
+    }
+
+    /// Removes and returns the batched notification (if any)
+    fn take_notification(&mut self) -> Option<PendingMempoolNotification> {
+        self.notification.take()
+    }
+
+    /// Removes and returns the batched notification iff it belongs to the
+    /// specified batch.
+    fn take_notification_for_batch(&mut self, batch_id: u64) -> Option<PendingMempoolNotification> {
+        if self.batch_id == batch_id {
+            self.take_notification()
+        } else {
+            None
+        }
+    }
+}
+
+/// The queue of mempool notifications waiting on a retry task
+#[derive(Default)]
+struct PendingMempoolNotifications {
+    notifications: VecDeque<PendingMempoolNotification>,
+    retry_task_active: bool,
+}
+
+/// Delivers all pending mempool notifications (in order). Once the queue
+/// is empty, the task exits and new notifications are sent directly.
+async fn retry_pending_notifications<M: MempoolNotificationSender>(
+    driver_config: StateSyncDriverConfig,
+    driver_metrics: DriverMetrics,
+    mempool_notification_sender: M,
+    highest_delivered_version: Arc<Mutex<Option<Version>>>,
+    pending_notifications: Arc<Mutex<PendingMempoolNotifications>>,
+    time_service: TimeService,
+) {
+    loop {
+        // Fetch the next pending notification
+        let notification = {
+            let mut pending_notifications = pending_notifications.lock();
+            match pending_notifications.notifications.pop_front() {
+                Some(notification) => notification,
+                None => {
+                    pending_notifications.retry_task_active = false;
+                    return;
+                }
+            }
+        };
+
+        // Deliver the notification (retrying if required)
+        deliver_pending_notification(
+            &driver_config,
+            &driver_metrics,
+            &mempool_notification_sender,
+            &highest_delivered_version,
+            &time_service,
+            notification,
+        )
+        .await;
+    }
+}
+
+/// Delivers a single pending notification to mempool. Transient failures
+/// are retried with backoff until the retries or deadline are exhausted.
+async fn deliver_pending_notification<M: MempoolNotificationSender>(
+    driver_config: &StateSyncDriverConfig,
+    driver_metrics: &DriverMetrics,
+    mempool_notification_sender: &M,
+    highest_delivered_version: &Mutex<Option<Version>>,
+    time_service: &TimeService,
+    mut notification: PendingMempoolNotification,
+) {
+    loop {
+        // Wait for the backoff if a previous attempt failed
+        if notification.num_failed_attempts > 0 {
+            if notification.retries_exhausted(driver_config, time_service.now()) {
+                return drop_pending_notification(
+                    notification,
+                    "all retries were exhausted",
+                    driver_metrics,
+                );
+            }
+            time_service
+                .sleep(notification.next_backoff(driver_config))
+                .await;
+            driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::MEMPOOL_NOTIFICATION_RETRY,
+            );
+        }
+
+        // Attempt to notify mempool (skipping anything already delivered)
+        if !trim_delivered_transactions(
+            &mut notification,
+            highest_delivered_version,
+            driver_metrics,
+        ) {
+            return;
+        }
+        let result =
+            notify_mempool(mempool_notification_sender, &notification, driver_metrics).await;
+        match result {
+            Ok(()) => {
+                record_delivered_notification(&notification, highest_delivered_version);
+                info!(
+                    LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                        "Notified mempool of committed transactions for versions {:?} to {:?} \
+                        after {:?} failed attempt(s).",
+                        notification.first_version,
+                        notification.last_version,
+                        notification.num_failed_attempts
+                    ))
+                );
+                return;
+            }
+            Err(error) if is_transient_mempool_error(&error) => {
+                notification.record_failed_attempt(time_service.now());
+            }
+            Err(error) => {
+                return drop_pending_notification(
+                    notification,
+                    &format!("a permanent error was encountered: {:?}", error),
+                    driver_metrics,
+                );
+            }
+        }
+    }
+}
+
+/// Logs and drops a notification that could not be delivered to mempool
+fn drop_pending_notification(
+    notification: PendingMempoolNotification,
+    reason: &str,
+    driver_metrics: &DriverMetrics,
+) {
+    let error = Error::NotifyMempoolError(format!(
+        "Dropped the notification for versions {:?} to {:?} after {:?} failed attempt(s): {}",
+        notification.first_version,
+        notification.last_version,
+        notification.num_failed_attempts,
+        reason
+    ));
+    error!(LogSchema::new(LogEntry::NotificationHandler)
+        .error(&error)
+        .message("Failed to notify mempool of committed transactions!"));
+    driver_metrics.increment_counter(
+        &metrics::DRIVER_COUNTERS,
+        metrics::MEMPOOL_NOTIFICATION_DROPPED,
+    );
+}
+
+/// Removes all transactions in the notification that have already been
+/// delivered to mempool. Returns false iff the entire notification has
+/// already been delivered (in which case it should be skipped).
+fn trim_delivered_transactions(
+    notification: &mut PendingMempoolNotification,
+    highest_delivered_version: &Mutex<Option<Version>>,
+    driver_metrics: &DriverMetrics,
+) -> bool {
+    let highest_delivered_version = match *highest_delivered_version.lock() {
+        Some(highest_delivered_version) => highest_delivered_version,
+        None => return true, // Nothing has been delivered yet
+    };
+
+    if highest_delivered_version >= notification.last_version {
+        debug!(
+            LogSchema::new(LogEntry::NotificationHandler).message(&format!(
+                "Skipping a duplicate mempool notification for versions {:?} to {:?}",
+                notification.first_version, notification.last_version
+            ))
+        );
+        driver_metrics.increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DUPLICATE_MEMPOOL_NOTIFICATION,
+        );
+        false
+    } else {
+        if highest_delivered_version >= notification.first_version {
+            notification.remove_transactions_up_to(highest_delivered_version);
+            driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::TRIMMED_MEMPOOL_NOTIFICATION,
+            );
+        }
+        true
+    }
+}
+
+/// Records that the given notification was successfully delivered to mempool
+fn record_delivered_notification(
+    notification: &PendingMempoolNotification,
+    highest_delivered_version: &Mutex<Option<Version>>,
+) {
+    let mut highest_delivered_version = highest_delivered_version.lock();
+    if highest_delivered_version.map_or(true, |version| version < notification.last_version) {
+        *highest_delivered_version = Some(notification.last_version);
+    }
+}
+
+/// Notifies mempool of the given notification and updates the
+/// notification metrics (i.e., latency, results and in-flight count).
+async fn notify_mempool<M: MempoolNotificationSender>(
+    mempool_notification_sender: &M,
+    notification: &PendingMempoolNotification,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), mempool_notifications::Error> {
+    let commit_origin = notification.commit_origin.get_label();
+    driver_metrics.increment_gauge(&metrics::MEMPOOL_NOTIFICATIONS_IN_FLIGHT, commit_origin, 1);
+
+    // Send the notification to mempool
+    let start_time = Instant::now();
+    let result = mempool_notification_sender
+        .notify_new_commit(
+            notification
+                .committed_transactions
+                .iter()
+                .map(|(_, transaction)| transaction.clone())
+                .collect(),
+            notification.block_timestamp_usecs,
+            MEMPOOL_COMMIT_ACK_TIMEOUT_MS,
+        )
+        .await;
+
+    // Update the notification metrics
+    driver_metrics.decrement_gauge(&metrics::MEMPOOL_NOTIFICATIONS_IN_FLIGHT, commit_origin, 1);
+    let notification_result = match &result {
+        Ok(()) => metrics::MEMPOOL_NOTIFICATION_SUCCESS,
+        Err(mempool_notifications::Error::TimeoutWaitingForMempool) => {
+            metrics::MEMPOOL_NOTIFICATION_TIMEOUT
+        }
+        Err(_) => metrics::MEMPOOL_NOTIFICATION_SEND_FAILURE,
+    };
+    driver_metrics.observe_mempool_notification(
+        commit_origin,
+        notification_result,
+        start_time.elapsed(),
+    );
+
+    result
+}
+
+/// Returns true iff the mempool error is transient (e.g., mempool is busy)
+fn is_transient_mempool_error(error: &mempool_notifications::Error) -> bool {
+    matches!(
+        error,
+        mempool_notifications::Error::CommitNotificationError(_)
+            | mempool_notifications::Error::TimeoutWaitingForMempool
+    )
 }
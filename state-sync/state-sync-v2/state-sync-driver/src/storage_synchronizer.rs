@@ -2,68 +2,102 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bootstrapper::GENESIS_TRANSACTION_VERSION,
     error::Error,
     logging::{LogEntry, LogSchema},
-    metadata_storage::MetadataStorageInterface,
-    metrics,
+    metadata_storage::{
+        MetadataStorageInterface, StateSnapshotChunkProgress, StateSnapshotFinalization,
+    },
+    metrics::{self, DriverMetrics},
     notification_handlers::{
-        CommitNotification, CommittedTransactions, ErrorNotification, MempoolNotificationHandler,
+        CommitEvent, CommitNotification, CommitNotificationSender, CommitOrigin,
+        CommitStateTracker, CommitSubscriberRegistry, CommittedTransactionChunk,
+        CommittedTransactions, ErrorNotification, ErrorSeverity, ErrorSource,
+        EventNotificationHandler, MempoolNotificationHandler, NotifiedVersionTracker,
+        PostCommitHook, PostCommitHookRegistry,
     },
     utils,
 };
 use aptos_config::config::StateSyncDriverConfig;
+use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
 use aptos_types::{
+    epoch_change::Verifier,
+    epoch_state::EpochState,
     ledger_info::LedgerInfoWithSignatures,
-    state_store::state_value::StateValueChunkWithProof,
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
     transaction::{
         Transaction, TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
+        Version,
     },
 };
 use data_streaming_service::data_notification::NotificationId;
-use event_notifications::EventSubscriptionService;
-use executor_types::ChunkExecutorTrait;
-use futures::{channel::mpsc, SinkExt, StreamExt};
+use executor_types::{CancellationToken, ChunkCommitNotification, ChunkExecutorTrait};
+use fail::fail_point;
+use futures::{
+    channel::{mpsc, oneshot},
+    SinkExt, StreamExt,
+};
 use mempool_notifications::MempoolNotificationSender;
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+use schemadb::{RocksDbError, RocksDbErrorKind};
+use serde::Serialize;
 use std::{
+    collections::BTreeMap,
     future::Future,
+    io,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
+    time::{Duration, Instant},
 };
-use storage_interface::{DbReader, DbReaderWriter};
+use storage_interface::{DbReader, DbReaderWriter, DbWriter};
 use tokio::{
-    runtime::{Handle, Runtime},
+    runtime::Handle,
+    sync::watch,
     task::JoinHandle,
+    time::{sleep, timeout},
 };
 
+/// The listener for backpressure state changes of the storage synchronizer
+pub type BackpressureListener = watch::Receiver<BackpressureState>;
+
+/// The notifier for backpressure state changes of the storage synchronizer
+pub type BackpressureNotifier = watch::Sender<BackpressureState>;
+
+/// The listener for pending data changes of the storage synchronizer (i.e.,
+/// a notification is sent every time a chunk leaves the pipeline).
+pub type PendingDataListener = watch::Receiver<()>;
+
+// The interval (ms) at which to check if a cancelled chunk execution has stopped
+const CANCELLATION_POLL_INTERVAL_MS: u64 = 5;
+
+// The max number of threads in the executor and committer thread pools
+pub const MAX_STAGE_THREADS: u64 = 256;
+
+// The max depth of the channels feeding the executor and committer
+pub const MAX_STAGE_CHANNEL_DEPTH: u64 = 100_000;
+
+// The max number of failed chunks remembered for error notification deduplication
+const MAX_FAILED_CHUNKS_TO_TRACK: usize = 1000;
+
+// The max number of times the commit retry backoff is doubled
+const MAX_COMMIT_BACKOFF_DOUBLINGS: u64 = 10;
+
+/// Returns a new backpressure notifier and listener pair. The initial
+/// state allows data to be fed into the storage synchronizer.
+pub fn new_backpressure_channel() -> (BackpressureNotifier, BackpressureListener) {
+    watch::channel(BackpressureState::Accepting)
+}
+
 /// Synchronizes the storage of the node by verifying and storing new data
 /// (e.g., transactions and outputs).
 pub trait StorageSynchronizerInterface {
-    /// Applies a batch of transaction outputs.
-    ///
-    /// Note: this assumes that the ledger infos have already been verified.
-    fn apply_transaction_outputs(
-        &mut self,
-        notification_id: NotificationId,
-        output_list_with_proof: TransactionOutputListWithProof,
-        target_ledger_info: LedgerInfoWithSignatures,
-        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-    ) -> Result<(), Error>;
-
-    /// Executes a batch of transactions.
-    ///
-    /// Note: this assumes that the ledger infos have already been verified.
-    fn execute_transactions(
-        &mut self,
-        notification_id: NotificationId,
-        transaction_list_with_proof: TransactionListWithProof,
-        target_ledger_info: LedgerInfoWithSignatures,
-        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-    ) -> Result<(), Error>;
-
     /// Initializes a state synchronizer with the specified
     /// `target_ledger_info` and `target_output_with_proof` at the target
     /// syncing version. Returns a join handle to the state synchronizer.
@@ -77,18 +111,121 @@ pub trait StorageSynchronizerInterface {
         target_output_with_proof: TransactionOutputListWithProof,
     ) -> Result<JoinHandle<()>, Error>;
 
-    /// Returns true iff there is storage data that is still waiting
-    /// to be executed/applied or committed.
-    fn pending_storage_data(&self) -> bool;
+    /// Completes the finalization of any state snapshot that was only partially
+    /// finalized (e.g., because the node crashed during finalization). This is
+    /// idempotent, i.e., finalization writes that were already persisted are
+    /// skipped. Returns true iff a pending finalization was completed.
+    fn finalize_pending_state_snapshot(&mut self) -> Result<bool, Error>;
+
+    /// Notifies the storage synchronizer that the data stream feeding it has
+    /// been reset (e.g., terminated after an error). Any data already in the
+    /// pipeline is still executed/applied and committed (committed data is
+    /// durable), so the resulting commit notifications are still processed,
+    /// but they are marked as post-reset.
+    ///
+    /// Note: callers must wait for `is_idle()` to return true before
+    /// computing the next version to request from a new stream.
+    fn notify_stream_reset(&self);
+
+    /// Returns a summary of the storage data that is still waiting to be
+    /// executed/applied or committed. This is cheap to call (i.e., it only
+    /// reads atomic counters) and so can be polled.
+    fn pending_storage_data(&self) -> PendingDataSummary;
+
+    /// Returns a handle to the highest version (and epoch) durably committed
+    /// to storage. This is cheap to read (i.e., it only reads atomics) and so
+    /// can be used on hot paths instead of querying storage.
+    fn committed_version_handle(&self) -> CommittedVersionHandle;
+
+    /// Discards all transaction (and output) chunks sent before the latest
+    /// stream reset (see `notify_stream_reset`) that haven't started
+    /// committing, i.e., chunks still queued for execute/apply, or executed
+    /// chunks waiting for the committer. Chunks already being committed are
+    /// allowed to complete (to avoid partial writes). No commit notifications
+    /// are sent for discarded chunks.
+    ///
+    /// If a discarded chunk is currently being executed/applied, the execution
+    /// is cancelled (at the next safe checkpoint). This call doesn't wait for
+    /// the execution to stop (a timeout is reported if it hasn't stopped
+    /// within `max_chunk_cancellation_wait_ms`).
+    ///
+    /// Note: discarded chunks might have already been executed/applied, so
+    /// callers must wait for `is_idle()` to return true and then reset the
+    /// chunk executor before sending any new chunks.
+    fn reset_pending_data(&self);
+
+    /// Shuts down the storage synchronizer, i.e., stops accepting new chunks
+    /// and waits (up to `max_shutdown_drain_ms`) for the pending chunks to be
+    /// committed. Any chunks still pending once the drain times out are
+    /// discarded (see `reset_pending_data`), while chunks already being
+    /// committed are allowed to complete. Returns a summary of the drain.
+    ///
+    /// Note: this blocks until the drain completes (so async callers should
+    /// call this from a blocking task), and all chunks submitted after
+    /// shutdown are rejected.
+    fn shutdown(&self) -> ShutdownSummary;
+
+    /// Returns true iff there is no storage data waiting to be
+    /// executed/applied or committed.
+    fn is_idle(&self) -> bool {
+        self.pending_storage_data().is_idle()
+    }
+
+    /// Returns a listener that is notified every time a chunk leaves the
+    /// pipeline (e.g., so that callers can wait for `is_idle()` without
+    /// polling). By default, the listener is closed (i.e., no notifications
+    /// are ever sent), so callers must fall back to polling.
+    fn subscribe_to_pending_data(&self) -> PendingDataListener {
+        let (_, pending_data_listener) = watch::channel(());
+        pending_data_listener
+    }
 
-    /// Saves the given state values to storage.
+    /// Saves the given state values (and proof) to storage. If `verify` is
+    /// true, the storage synchronizer verifies the chunk against its proof
+    /// (see `verify_state_value_chunk`) before writing it. Otherwise, the
+    /// caller must have already verified the chunk.
+    ///
+    /// Each chunk must be verified exactly once, so `verify` must match
+    /// `verifies_state_value_chunks()` (otherwise the chunk is rejected).
+    /// The range proof itself is always verified by storage on write.
     ///
     /// Note: this requires that `initialize_state_synchronizer` has been
     /// called.
-    fn save_state_values(
+    fn save_state_values_with_proof(
         &mut self,
         notification_id: NotificationId,
         state_value_chunk_with_proof: StateValueChunkWithProof,
+        verify: bool,
+    ) -> Result<(), Error>;
+
+    /// Returns true iff the storage synchronizer is configured to verify
+    /// state value chunks (i.e., callers must not verify them first).
+    fn verifies_state_value_chunks(&self) -> bool;
+
+    /// Submits a chunk of transactions to be stored, either by applying the
+    /// transaction outputs or by executing the transactions (as instructed).
+    /// Applying requires a transaction output payload, while executing
+    /// accepts either payload. Any errors will be reported against the given
+    /// `error_source` (i.e., the owner of the data stream).
+    ///
+    /// The chunk must start exactly one version past the highest committed
+    /// (or in-flight) version, and must not exceed the max chunk size. Gapped,
+    /// overlapping or oversized chunks are rejected (and an error notification
+    /// is sent), while an exact repeat of the last submitted chunk is ignored.
+    ///
+    /// If a `ledger_info_verifier` is given (i.e., the epoch state of the
+    /// chunk), the ledger info signatures are verified by the executor before
+    /// the chunk is executed/applied (in parallel with other queued chunks).
+    /// Otherwise, this assumes that the ledger infos have already been verified.
+    fn submit_transaction_chunk(
+        &mut self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        apply_or_execute: ApplyOrExecute,
+        chunk_payload: TransactionChunkPayload,
+        target_ledger_info: LedgerInfoWithSignatures,
+        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+        ledger_info_verifier: Option<EpochState>,
     ) -> Result<(), Error>;
 
     /// Resets the chunk executor. This is required to support continuous
@@ -100,28 +237,689 @@ pub trait StorageSynchronizerInterface {
     fn finish_chunk_executor(&self);
 }
 
+/// A snapshot of the storage data currently held by the storage synchronizer
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct PendingDataSummary {
+    pub bytes_queued: u64, // The serialized size of the chunks queued (but not yet dequeued) for execute/apply
+    pub chunks_committing: u64, // The number of chunks being committed (or batched for commit)
+    pub chunks_executing: u64, // The number of chunks being executed/applied (or state values being written)
+    pub chunks_in_flight: u64, // The number of chunks pending execute/apply, or commit
+    pub chunks_queued_for_commit: u64, // The number of executed chunks waiting for the committer
+    pub chunks_queued_for_execution: u64, // The number of chunks waiting for the executor (or state snapshot receiver)
+    pub highest_version_in_flight: Option<Version>, // The highest transaction version in the pending chunks
+}
+
+impl PendingDataSummary {
+    /// Returns true iff there are no chunks in flight
+    pub fn is_idle(&self) -> bool {
+        self.chunks_in_flight == 0
+    }
+
+    /// Returns the highest version known to the node, i.e., the maximum of
+    /// the given highest synced version (in storage) and the highest
+    /// version still in flight.
+    pub fn highest_known_version(&self, highest_synced_version: Version) -> Version {
+        self.highest_version_in_flight
+            .map_or(highest_synced_version, |highest_version_in_flight| {
+                highest_version_in_flight.max(highest_synced_version)
+            })
+    }
+}
+
+/// A summary of the pending chunks drained by the storage synchronizer on shutdown
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ShutdownSummary {
+    pub chunks_committed: u64, // The number of pending chunks committed during the drain
+    pub chunks_discarded: u64, // The number of pending chunks discarded (or that failed) during the drain
+    pub drained: bool, // Whether the pending chunks left the pipeline before the drain timed out (i.e., without being discarded)
+}
+
+/// The instruction for how a transaction chunk should be stored
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum ApplyOrExecute {
+    Apply,   // Apply the transaction outputs (requires an output payload)
+    Execute, // Execute the transactions
+}
+
+impl ApplyOrExecute {
+    /// Returns a summary label for the instruction
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            ApplyOrExecute::Apply => "apply",
+            ApplyOrExecute::Execute => "execute",
+        }
+    }
+
+    /// Returns the storage synchronizer operation label for the instruction
+    fn get_operation_label(&self) -> &'static str {
+        match self {
+            ApplyOrExecute::Apply => {
+                metrics::StorageSynchronizerOperations::AppliedTransactionOutputs.get_label()
+            }
+            ApplyOrExecute::Execute => {
+                metrics::StorageSynchronizerOperations::ExecutedTransactions.get_label()
+            }
+        }
+    }
+}
+
+/// The payload of a transaction chunk submitted to the storage synchronizer
+#[derive(Clone, Debug)]
+pub enum TransactionChunkPayload {
+    TransactionOutputs(TransactionOutputListWithProof),
+    Transactions(TransactionListWithProof),
+}
+
+impl TransactionChunkPayload {
+    /// Returns the version of the first transaction in the payload (if any)
+    pub fn first_version(&self) -> Option<Version> {
+        match self {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                output_list_with_proof.first_transaction_output_version
+            }
+            TransactionChunkPayload::Transactions(transaction_list_with_proof) => {
+                transaction_list_with_proof.first_transaction_version
+            }
+        }
+    }
+
+    /// Returns the number of transactions in the payload
+    pub fn num_transactions(&self) -> usize {
+        match self {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                output_list_with_proof.transactions_and_outputs.len()
+            }
+            TransactionChunkPayload::Transactions(transaction_list_with_proof) => {
+                transaction_list_with_proof.transactions.len()
+            }
+        }
+    }
+
+    /// Returns the serialized size of the transactions (and outputs)
+    fn get_serialized_size(&self) -> u64 {
+        match self {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                get_serialized_size(&output_list_with_proof.transactions_and_outputs)
+            }
+            TransactionChunkPayload::Transactions(transaction_list_with_proof) => {
+                get_serialized_size(&transaction_list_with_proof.transactions)
+            }
+        }
+    }
+
+    /// Returns the serialized size of the outputs (i.e., zero for transactions)
+    fn get_output_size(&self) -> u64 {
+        match self {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                output_list_with_proof
+                    .transactions_and_outputs
+                    .iter()
+                    .map(|(_, output)| get_serialized_size(output))
+                    .sum()
+            }
+            TransactionChunkPayload::Transactions(_) => 0,
+        }
+    }
+
+    /// Converts the payload into a transaction list (for execution). Any
+    /// outputs are dropped, as the transactions will be re-executed.
+    fn into_transaction_list(self) -> TransactionListWithProof {
+        match self {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                let transactions = output_list_with_proof
+                    .transactions_and_outputs
+                    .into_iter()
+                    .map(|(transaction, _)| transaction)
+                    .collect();
+                TransactionListWithProof::new(
+                    transactions,
+                    None,
+                    output_list_with_proof.first_transaction_output_version,
+                    output_list_with_proof.proof,
+                )
+            }
+            TransactionChunkPayload::Transactions(transaction_list_with_proof) => {
+                transaction_list_with_proof
+            }
+        }
+    }
+}
+
+/// The backpressure state of the storage synchronizer, i.e., whether new
+/// data should be fed into the pipeline. The state is paused once the commit
+/// backlog reaches the high water mark, and only resumes once the backlog
+/// drains to the low water mark (to prevent the state from flapping).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BackpressureState {
+    Accepting, // New data can be fed into the pipeline
+    Paused,    // The commit backlog is too deep, so no new data should be fed
+}
+
+/// The stages of the storage synchronizer pipeline at which chunks are tracked
+#[derive(Clone, Copy, Debug)]
+enum PipelineStage {
+    Committing,
+    Executing,
+    QueuedForCommit,
+    QueuedForExecution,
+}
+
+impl PipelineStage {
+    /// Returns the gauge label for the number of chunks in the stage
+    fn get_label(&self) -> &'static str {
+        match self {
+            PipelineStage::Committing => metrics::STORAGE_SYNCHRONIZER_CHUNKS_COMMITTING,
+            PipelineStage::Executing => metrics::STORAGE_SYNCHRONIZER_CHUNKS_EXECUTING,
+            PipelineStage::QueuedForCommit => {
+                metrics::STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_COMMIT
+            }
+            PipelineStage::QueuedForExecution => {
+                metrics::STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_EXECUTION
+            }
+        }
+    }
+}
+
+/// Tracks the storage data chunks pending execute/apply, or commit, using
+/// shared atomic counters (so that it can be read without blocking the pipeline).
+/// The counters are mirrored by the storage synchronizer gauges.
+#[derive(Clone)]
+struct PendingDataTracker {
+    // The backpressure notifier and last notified state (guarded by a lock
+    // so that concurrent updates are serialized).
+    backpressure_notifier: Arc<Mutex<(BackpressureNotifier, BackpressureState)>>,
+
+    // The serialized size of the chunks that haven't yet been dequeued
+    bytes_queued: Arc<AtomicU64>,
+
+    // The total number of chunks committed (or state value chunks written)
+    chunks_committed: Arc<AtomicU64>,
+
+    // The number of chunks being committed (or batched for commit)
+    chunks_committing: Arc<AtomicU64>,
+
+    // The number of chunks being executed/applied (or written to storage)
+    chunks_executing: Arc<AtomicU64>,
+
+    // The number of chunks pending execute/apply, or commit
+    chunks_in_flight: Arc<AtomicU64>,
+
+    // The total number of chunks that have left the pipeline
+    chunks_processed: Arc<AtomicU64>,
+
+    // The number of executed chunks waiting for the committer
+    chunks_queued_for_commit: Arc<AtomicU64>,
+
+    // The number of chunks waiting for the executor (or state snapshot receiver)
+    chunks_queued_for_execution: Arc<AtomicU64>,
+
+    // The commit backlog (in chunks) at which to pause feeding the pipeline
+    commit_backlog_high_water_mark: u64,
+
+    // The commit backlog (in chunks) at which to resume feeding the pipeline
+    commit_backlog_low_water_mark: u64,
+
+    // The metrics of the driver instance (mirroring the counters)
+    driver_metrics: DriverMetrics,
+
+    // The highest transaction version in flight plus one (zero means none)
+    highest_version_in_flight: Arc<AtomicU64>,
+
+    // The notifier used to send a notification every time a chunk leaves the pipeline
+    pending_data_notifier: Arc<watch::Sender<()>>,
+}
+
+impl PendingDataTracker {
+    fn new(
+        driver_config: &StateSyncDriverConfig,
+        backpressure_notifier: BackpressureNotifier,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        driver_metrics.set_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_BACKPRESSURED,
+            0,
+        );
+        Self {
+            backpressure_notifier: Arc::new(Mutex::new((
+                backpressure_notifier,
+                BackpressureState::Accepting,
+            ))),
+            bytes_queued: Arc::new(AtomicU64::new(0)),
+            chunks_committed: Arc::new(AtomicU64::new(0)),
+            chunks_committing: Arc::new(AtomicU64::new(0)),
+            chunks_executing: Arc::new(AtomicU64::new(0)),
+            chunks_in_flight: Arc::new(AtomicU64::new(0)),
+            chunks_processed: Arc::new(AtomicU64::new(0)),
+            chunks_queued_for_commit: Arc::new(AtomicU64::new(0)),
+            chunks_queued_for_execution: Arc::new(AtomicU64::new(0)),
+            commit_backlog_high_water_mark: driver_config.commit_backlog_high_water_mark,
+            commit_backlog_low_water_mark: driver_config.commit_backlog_low_water_mark,
+            driver_metrics,
+            highest_version_in_flight: Arc::new(AtomicU64::new(0)),
+            pending_data_notifier: Arc::new(watch::channel(()).0),
+        }
+    }
+
+    /// Records that a new chunk has been sent into the pipeline
+    fn chunk_sent(&self, highest_version: Option<Version>, num_bytes: u64) {
+        let encoded_version = highest_version.map_or(0, |version| version.saturating_add(1));
+        if self.chunks_in_flight.load(Ordering::Relaxed) == 0 {
+            // The pipeline has drained, so any previously tracked version is stale
+            self.highest_version_in_flight
+                .store(encoded_version, Ordering::Relaxed);
+        } else {
+            self.highest_version_in_flight
+                .fetch_max(encoded_version, Ordering::Relaxed);
+        }
+        self.bytes_queued.fetch_add(num_bytes, Ordering::Relaxed);
+        self.driver_metrics.increment_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_BYTES_QUEUED,
+            num_bytes,
+        );
+
+        let delta = 1;
+        self.chunks_in_flight.fetch_add(delta, Ordering::Relaxed);
+        self.driver_metrics.increment_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_PENDING_DATA,
+            delta,
+        );
+        self.enter_stage(PipelineStage::QueuedForExecution);
+    }
+
+    /// Records that a chunk (of the given size) has been dequeued for
+    /// processing (i.e., execute/apply, or writing state values).
+    fn chunk_dequeued(&self, num_bytes: u64) {
+        self.bytes_queued.fetch_sub(num_bytes, Ordering::Relaxed);
+        self.driver_metrics.decrement_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_BYTES_QUEUED,
+            num_bytes,
+        );
+        self.exit_stage(PipelineStage::QueuedForExecution);
+        self.enter_stage(PipelineStage::Executing);
+    }
+
+    /// Records that a chunk has been executed/applied (and is being sent to the committer)
+    fn chunk_executed(&self) {
+        self.exit_stage(PipelineStage::Executing);
+        self.enter_stage(PipelineStage::QueuedForCommit);
+        self.update_backpressure_state();
+    }
+
+    /// Records that an executed chunk has been received by the committer
+    fn chunk_committing(&self) {
+        self.exit_stage(PipelineStage::QueuedForCommit);
+        self.enter_stage(PipelineStage::Committing);
+    }
+
+    /// Records that a chunk (in the given stage) has left the pipeline
+    /// (e.g., it was committed or it failed).
+    fn chunk_processed(&self, stage: PipelineStage) {
+        self.exit_stage(stage);
+
+        let delta = 1;
+        self.chunks_processed.fetch_add(delta, Ordering::Relaxed);
+        self.chunks_in_flight.fetch_sub(delta, Ordering::Relaxed);
+        self.driver_metrics.decrement_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_PENDING_DATA,
+            delta,
+        );
+        self.update_backpressure_state();
+        let _ = self.pending_data_notifier.send(()); // There may be no listeners
+    }
+
+    /// Records that the given number of chunks have been durably committed
+    /// (before they are marked as processed).
+    fn chunks_committed(&self, num_chunks: u64) {
+        self.chunks_committed
+            .fetch_add(num_chunks, Ordering::Relaxed);
+    }
+
+    /// Returns the total number of chunks committed, and the total number of
+    /// chunks that have left the pipeline (i.e., committed or not).
+    fn get_processed_chunk_totals(&self) -> (u64, u64) {
+        (
+            self.chunks_committed.load(Ordering::Relaxed),
+            self.chunks_processed.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Returns the chunk counter for the given stage
+    fn get_stage_counter(&self, stage: PipelineStage) -> &AtomicU64 {
+        match stage {
+            PipelineStage::Committing => &self.chunks_committing,
+            PipelineStage::Executing => &self.chunks_executing,
+            PipelineStage::QueuedForCommit => &self.chunks_queued_for_commit,
+            PipelineStage::QueuedForExecution => &self.chunks_queued_for_execution,
+        }
+    }
+
+    fn enter_stage(&self, stage: PipelineStage) {
+        self.get_stage_counter(stage)
+            .fetch_add(1, Ordering::Relaxed);
+        self.driver_metrics.increment_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            stage.get_label(),
+            1,
+        );
+    }
+
+    fn exit_stage(&self, stage: PipelineStage) {
+        self.get_stage_counter(stage)
+            .fetch_sub(1, Ordering::Relaxed);
+        self.driver_metrics.decrement_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            stage.get_label(),
+            1,
+        );
+    }
+
+    /// Returns a listener that is notified every time a chunk leaves the pipeline
+    fn subscribe(&self) -> PendingDataListener {
+        self.pending_data_notifier.subscribe()
+    }
+
+    /// Waits until there is no data in flight. This waits on the pending
+    /// data notifications (instead of polling the counters).
+    async fn wait_until_idle(&self) {
+        // Subscribe before checking, so that no notification can be missed
+        let mut pending_data_listener = self.subscribe();
+        while !self.summary().is_idle() {
+            let _ = pending_data_listener.changed().await; // The notifier is never dropped
+        }
+    }
+
+    /// Returns a summary of the data currently in flight
+    fn summary(&self) -> PendingDataSummary {
+        let chunks_in_flight = self.chunks_in_flight.load(Ordering::Relaxed);
+        let highest_version_in_flight = if chunks_in_flight > 0 {
+            self.highest_version_in_flight
+                .load(Ordering::Relaxed)
+                .checked_sub(1)
+        } else {
+            None
+        };
+        PendingDataSummary {
+            bytes_queued: self.bytes_queued.load(Ordering::Relaxed),
+            chunks_committing: self.chunks_committing.load(Ordering::Relaxed),
+            chunks_executing: self.chunks_executing.load(Ordering::Relaxed),
+            chunks_in_flight,
+            chunks_queued_for_commit: self.chunks_queued_for_commit.load(Ordering::Relaxed),
+            chunks_queued_for_execution: self.chunks_queued_for_execution.load(Ordering::Relaxed),
+            highest_version_in_flight,
+        }
+    }
+
+    /// Updates the backpressure state using the current commit backlog, and
+    /// notifies the listener if the state has changed.
+    fn update_backpressure_state(&self) {
+        let mut backpressure_notifier = self.backpressure_notifier.lock();
+        let (notifier, backpressure_state) = &mut *backpressure_notifier;
+
+        // Calculate the new state (only changing state at the water marks)
+        let commit_backlog = self.chunks_queued_for_commit.load(Ordering::Relaxed)
+            + self.chunks_committing.load(Ordering::Relaxed);
+        let new_backpressure_state = match backpressure_state {
+            BackpressureState::Accepting
+                if commit_backlog >= self.commit_backlog_high_water_mark =>
+            {
+                BackpressureState::Paused
+            }
+            BackpressureState::Paused if commit_backlog <= self.commit_backlog_low_water_mark => {
+                BackpressureState::Accepting
+            }
+            _ => return, // The state is unchanged
+        };
+
+        // Update the state and notify the listener
+        info!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "The backpressure state has changed to: {:?}. Commit backlog: {:?}",
+                new_backpressure_state, commit_backlog
+            ))
+        );
+        *backpressure_state = new_backpressure_state;
+        let _ = notifier.send(new_backpressure_state); // The listener may have been dropped
+        self.driver_metrics.set_gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            metrics::STORAGE_SYNCHRONIZER_BACKPRESSURED,
+            (new_backpressure_state == BackpressureState::Paused) as u64,
+        );
+    }
+}
+
+/// Tracks the resets of the data stream feeding the pipeline. Chunks record
+/// the number of resets when they're sent, so that chunks sent before a reset
+/// can be identified (and discarded, if the pending data was reset).
+#[derive(Clone, Default)]
+struct StreamResetTracker {
+    // The number of resets before which all uncommitted chunks are discarded
+    discarded_resets: Arc<AtomicU64>,
+
+    // The cancellation token of the chunk currently being executed/applied
+    // (if any), tagged with the number of stream resets when it was sent.
+    executing_chunk: Arc<Mutex<Option<(u64, CancellationToken)>>>,
+
+    // The number of times the data stream feeding the pipeline has been reset
+    stream_resets: Arc<AtomicU64>,
+}
+
+impl StreamResetTracker {
+    /// Cancels the execution of the chunk currently being executed/applied
+    /// iff the chunk has been discarded. Returns true iff an execution was
+    /// cancelled.
+    fn cancel_discarded_execution(&self) -> bool {
+        match self.executing_chunk.lock().as_ref() {
+            Some((num_stream_resets, cancellation_token))
+                if self.is_discarded(*num_stream_resets) =>
+            {
+                cancellation_token.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Marks all chunks sent before the latest stream reset as discarded
+    fn discard_reset_chunks(&self) {
+        let stream_resets = self.stream_resets.load(Ordering::Relaxed);
+        self.discarded_resets
+            .fetch_max(stream_resets, Ordering::Relaxed);
+    }
+
+    /// Returns the number of times the data stream has been reset
+    fn get_stream_resets(&self) -> u64 {
+        self.stream_resets.load(Ordering::Relaxed)
+    }
+
+    /// Returns true iff a chunk sent with the given number of stream
+    /// resets must be discarded.
+    fn is_discarded(&self, num_stream_resets: u64) -> bool {
+        num_stream_resets < self.discarded_resets.load(Ordering::Relaxed)
+    }
+
+    /// Records that the executor has started executing/applying a chunk (sent
+    /// with the given number of stream resets). Returns the cancellation token
+    /// for the execution, which is already cancelled if the chunk has been
+    /// discarded in the meantime.
+    fn execution_started(&self, num_stream_resets: u64) -> CancellationToken {
+        let cancellation_token = CancellationToken::new();
+        let mut executing_chunk = self.executing_chunk.lock();
+        if self.is_discarded(num_stream_resets) {
+            cancellation_token.cancel();
+        }
+        *executing_chunk = Some((num_stream_resets, cancellation_token.clone()));
+        cancellation_token
+    }
+
+    /// Records that the executor has stopped executing/applying the chunk
+    fn execution_stopped(&self) {
+        *self.executing_chunk.lock() = None;
+    }
+
+    /// Records a reset of the data stream
+    fn notify_stream_reset(&self) {
+        self.stream_resets.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Waits (up to the given duration) for any cancelled chunk execution to
+    /// stop. Returns true iff no cancelled execution is still running.
+    async fn wait_for_cancelled_execution(&self, max_wait: Duration) -> bool {
+        let wait_start_time = Instant::now();
+        loop {
+            let cancelled_execution_running = self
+                .executing_chunk
+                .lock()
+                .as_ref()
+                .map_or(false, |(_, cancellation_token)| {
+                    cancellation_token.is_cancelled()
+                });
+            if !cancelled_execution_running {
+                return true;
+            }
+            if wait_start_time.elapsed() >= max_wait {
+                return false;
+            }
+            sleep(Duration::from_millis(CANCELLATION_POLL_INTERVAL_MS)).await;
+        }
+    }
+}
+
+/// Sends error notifications to the driver. At most one notification is sent
+/// for each chunk (i.e., notification ID), recording the first stage that
+/// failed. Subsequent failures for the same chunk are only logged.
+#[derive(Clone)]
+struct ErrorNotifier {
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The channel through which to notify the driver of storage errors
+    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+
+    // The first failing stage of each failed chunk (bounded by evicting the
+    // lowest notification IDs first).
+    failed_chunks: Arc<Mutex<BTreeMap<NotificationId, StorageSynchronizerStage>>>,
+}
+
+impl ErrorNotifier {
+    fn new(
+        error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+        driver_metrics: DriverMetrics,
+    ) -> Self {
+        Self {
+            driver_metrics,
+            error_notification_sender,
+            failed_chunks: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Notifies the driver of the failed operation (unless an error has
+    /// already been notified for the chunk). Returns the error of the failure.
+    fn notify_error(
+        &self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        failed_operation: FailedOperation,
+        version_range: Option<(Version, Version)>,
+        expected_version: Option<Version>,
+        error_message: String,
+    ) -> Error {
+        // Record the failure and check if the chunk has already failed
+        let stage = failed_operation.get_stage();
+        let first_failed_stage = {
+            let mut failed_chunks = self.failed_chunks.lock();
+            let first_failed_stage = failed_chunks.get(&notification_id).copied();
+            if first_failed_stage.is_none() {
+                failed_chunks.insert(notification_id, stage);
+                if failed_chunks.len() > MAX_FAILED_CHUNKS_TO_TRACK {
+                    let lowest_notification_id = *failed_chunks.keys().next().unwrap();
+                    failed_chunks.remove(&lowest_notification_id);
+                }
+            }
+            first_failed_stage
+        };
+
+        // Only log subsequent failures of the same chunk
+        if let Some(first_failed_stage) = first_failed_stage {
+            let error = create_failed_operation_error(failed_operation, error_message);
+            debug!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "Not notifying a subsequent failure of chunk {:?} (the {:?} stage \
+                    already failed). The {:?} stage failed for versions: {:?}. Error: {:?}",
+                    notification_id, first_failed_stage, stage, version_range, error
+                ))
+            );
+            return error;
+        }
+
+        // Notify the driver of the error
+        let error_notification = create_error_notification(
+            notification_id,
+            error_source,
+            failed_operation,
+            version_range,
+            expected_version,
+            error_message,
+            &self.driver_metrics,
+        );
+        let error = error_notification.error.clone();
+        if let Err(error) = self
+            .error_notification_sender
+            .unbounded_send(error_notification)
+        {
+            panic!("Failed to send error notification! Error: {:?}", error);
+        }
+        error
+    }
+}
+
 /// The implementation of the `StorageSynchronizerInterface` used by state sync
 pub struct StorageSynchronizer<ChunkExecutor, MetadataStorage> {
     // The executor for transaction and transaction output chunks
     chunk_executor: Arc<ChunkExecutor>,
 
     // A channel through which to notify the driver of committed data
-    commit_notification_sender: mpsc::UnboundedSender<CommitNotification>,
+    commit_notification_sender: CommitNotificationSender,
+
+    // The tracker of the latest committed version and epoch
+    commit_state_tracker: CommitStateTracker,
+
+    // The handle to the highest version (and epoch) durably committed to storage
+    committed_version_handle: CommittedVersionHandle,
 
     // The configuration of the state sync driver
     driver_config: StateSyncDriverConfig,
 
-    // A channel through which to notify the driver of storage errors
-    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    // The metrics of the driver instance
+    driver_metrics: DriverMetrics,
+
+    // The notifier through which to notify the driver of storage errors
+    error_notification_sender: ErrorNotifier,
 
     // A channel through which to notify the executor of new data chunks
-    executor_notifier: mpsc::Sender<StorageDataChunk>,
+    // (tagged with the number of stream resets at the time of sending, and
+    // the serialized size of the chunk).
+    executor_notifier: mpsc::Sender<(StorageDataChunk, u64, u64)>,
+
+    // The (inclusive) version range of the last transaction chunk accepted
+    // by the pipeline (used to identify duplicate chunks).
+    last_submitted_chunk: Arc<Mutex<Option<(Version, Version)>>>,
+
+    // The verifier for the ledger info signatures of queued transaction chunks
+    ledger_info_verifier: LedgerInfoVerifier,
 
     // The storage to write metadata about the syncing progress
     metadata_storage: MetadataStorage,
 
-    // The number of storage data chunks pending execute/apply, or commit
-    pending_data_chunks: Arc<AtomicU64>,
+    // The tracker of storage data chunks pending execute/apply, or commit
+    pending_data_tracker: PendingDataTracker,
+
+    // The hooks to run after each durable transaction commit
+    post_commit_hook_registry: PostCommitHookRegistry,
 
     // An optional runtime on which to spawn the storage synchronizer threads
     runtime: Option<Handle>,
@@ -129,8 +927,14 @@ pub struct StorageSynchronizer<ChunkExecutor, MetadataStorage> {
     // The channel through which to notify the state snapshot receiver of new data chunks
     state_snapshot_notifier: Option<mpsc::Sender<StorageDataChunk>>,
 
+    // Whether the storage synchronizer has been shut down (i.e., new chunks are rejected)
+    shutdown: Arc<AtomicBool>,
+
     // The reader and writer for storage (required for state syncing)
     storage: DbReaderWriter,
+
+    // The tracker for resets of the data stream feeding the pipeline
+    stream_reset_tracker: StreamResetTracker,
 }
 
 // TODO(joshlind): this cannot currently be derived because of limitations around
@@ -144,14 +948,22 @@ impl<
         Self {
             chunk_executor: self.chunk_executor.clone(),
             commit_notification_sender: self.commit_notification_sender.clone(),
+            commit_state_tracker: self.commit_state_tracker.clone(),
+            committed_version_handle: self.committed_version_handle.clone(),
             driver_config: self.driver_config,
+            driver_metrics: self.driver_metrics,
             error_notification_sender: self.error_notification_sender.clone(),
             executor_notifier: self.executor_notifier.clone(),
-            pending_data_chunks: self.pending_data_chunks.clone(),
+            last_submitted_chunk: self.last_submitted_chunk.clone(),
+            ledger_info_verifier: self.ledger_info_verifier.clone(),
+            pending_data_tracker: self.pending_data_tracker.clone(),
             metadata_storage: self.metadata_storage.clone(),
+            post_commit_hook_registry: self.post_commit_hook_registry.clone(),
             runtime: self.runtime.clone(),
+            shutdown: self.shutdown.clone(),
             state_snapshot_notifier: self.state_snapshot_notifier.clone(),
             storage: self.storage.clone(),
+            stream_reset_tracker: self.stream_reset_tracker.clone(),
         }
     }
 }
@@ -161,80 +973,340 @@ impl<
         MetadataStorage: MetadataStorageInterface + Clone,
     > StorageSynchronizer<ChunkExecutor, MetadataStorage>
 {
-    /// Returns a new storage synchronizer alongside the executor and committer handles
+    /// Returns a new storage synchronizer alongside the executor and committer
+    /// handles. Changes to the backpressure state (based on the commit backlog)
+    /// are sent along the given backpressure notifier. If `dry_run` is set, the
+    /// chunk executor is expected to never commit (see `DryRunChunkExecutor`),
+    /// and commits are only reported (instead of being notified to mempool, etc.).
+    #[allow(clippy::too_many_arguments)]
     pub fn new<MempoolNotifier: MempoolNotificationSender>(
         driver_config: StateSyncDriverConfig,
+        backpressure_notifier: BackpressureNotifier,
         chunk_executor: Arc<ChunkExecutor>,
-        commit_notification_sender: mpsc::UnboundedSender<CommitNotification>,
+        commit_notification_sender: CommitNotificationSender,
         error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
-        event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+        event_notification_handler: EventNotificationHandler,
+        commit_state_tracker: CommitStateTracker,
+        commit_subscriber_registry: CommitSubscriberRegistry,
         mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
         metadata_storage: MetadataStorage,
+        notified_version_tracker: NotifiedVersionTracker,
         storage: DbReaderWriter,
-        runtime: Option<&Runtime>,
+        runtime: Option<Handle>,
+        driver_metrics: DriverMetrics,
+        dry_run: bool,
     ) -> (Self, JoinHandle<()>, JoinHandle<()>) {
         // Create a channel to notify the executor when data chunks are ready
         let max_pending_data_chunks = driver_config.max_pending_data_chunks as usize;
         let (executor_notifier, executor_listener) = mpsc::channel(max_pending_data_chunks);
 
-        // Create a channel to notify the committer when executed chunks are ready
-        let (committer_notifier, committer_listener) = mpsc::channel(max_pending_data_chunks);
+        // Create a channel to notify the committer when executed chunks are ready.
+        // The executor waits on this channel when it's full, i.e., execution
+        // can run ahead of commit by at most the channel capacity.
+        let max_pending_executed_chunks = driver_config.max_pending_executed_chunks as usize;
+        let (committer_notifier, committer_listener) = mpsc::channel(max_pending_executed_chunks);
+
+        // Create the dedicated thread pools for the executor and committer
+        let executor_thread_pool =
+            create_stage_thread_pool("chunk-executor", driver_config.num_executor_threads);
+        let committer_thread_pool =
+            create_stage_thread_pool("chunk-committer", driver_config.num_committer_threads);
+        log_concurrency_config(&driver_config, &driver_metrics);
 
-        // Create a shared pending data chunk counter
-        let pending_transaction_chunks = Arc::new(AtomicU64::new(0));
+        // Create a shared pending data tracker
+        let pending_data_tracker =
+            PendingDataTracker::new(&driver_config, backpressure_notifier, driver_metrics);
+
+        // Create a shared stream reset tracker
+        let stream_reset_tracker = StreamResetTracker::default();
+
+        // Create the error notifier (shared by all pipeline stages)
+        let error_notification_sender =
+            ErrorNotifier::new(error_notification_sender, driver_metrics);
+
+        // Create the verifier for the ledger info signatures of queued chunks
+        let ledger_info_verifier =
+            LedgerInfoVerifier::new(driver_config.ledger_info_verification_parallelism);
+
+        // Create the (initially empty) registry of post-commit hooks
+        let post_commit_hook_registry = PostCommitHookRegistry::new(driver_metrics);
+
+        // Create the committed version handle (initialized from storage)
+        let committed_version_handle =
+            CommittedVersionHandle::new_from_storage(storage.reader.clone())
+                .expect("Failed to initialize the committed version handle!");
 
         // Spawn the executor that executes/applies storage data chunks
-        let runtime = runtime.map(|runtime| runtime.handle().clone());
         let executor_handle = spawn_executor(
             chunk_executor.clone(),
             error_notification_sender.clone(),
             executor_listener,
             committer_notifier,
-            pending_transaction_chunks.clone(),
+            executor_thread_pool,
+            ledger_info_verifier.clone(),
+            pending_data_tracker.clone(),
             runtime.clone(),
+            stream_reset_tracker.clone(),
+            driver_config.verify_event_root_hashes,
+            driver_metrics,
         );
 
         // Spawn the committer that commits executed (but pending) chunks
         let committer_handle = spawn_committer(
             chunk_executor.clone(),
             committer_listener,
+            committer_thread_pool,
+            committed_version_handle.clone(),
+            driver_config,
             error_notification_sender.clone(),
-            event_subscription_service,
+            event_notification_handler,
+            commit_state_tracker.clone(),
+            commit_subscriber_registry,
             mempool_notification_handler,
-            pending_transaction_chunks.clone(),
+            notified_version_tracker,
+            pending_data_tracker.clone(),
+            post_commit_hook_registry.clone(),
             runtime.clone(),
             storage.reader.clone(),
+            stream_reset_tracker.clone(),
+            driver_metrics,
+            dry_run,
         );
 
         // Initialize the metric gauges
-        utils::initialize_sync_gauges(storage.reader.clone())
+        utils::initialize_sync_gauges(storage.reader.clone(), &driver_metrics)
             .expect("Failed to initialize the metric gauges!");
 
         let storage_synchronizer = Self {
             chunk_executor,
             commit_notification_sender,
+            commit_state_tracker,
+            committed_version_handle,
             driver_config,
+            driver_metrics,
             error_notification_sender,
             executor_notifier,
-            pending_data_chunks: pending_transaction_chunks,
+            last_submitted_chunk: Arc::new(Mutex::new(None)),
+            ledger_info_verifier,
+            pending_data_tracker,
             metadata_storage,
+            post_commit_hook_registry,
             runtime,
+            shutdown: Arc::new(AtomicBool::new(false)),
             state_snapshot_notifier: None,
             storage,
+            stream_reset_tracker,
         };
 
         (storage_synchronizer, executor_handle, committer_handle)
     }
 
+    /// Registers a hook that is run (synchronously) after each durable
+    /// transaction commit, before any commit notifications are sent.
+    pub fn register_post_commit_hook(&self, hook_name: &'static str, hook: PostCommitHook) {
+        self.post_commit_hook_registry
+            .register_hook(hook_name, hook);
+    }
+
+    /// Returns the registry of post-commit hooks (e.g., so that hooks can be
+    /// registered after the synchronizer has been handed to the driver).
+    pub fn get_post_commit_hook_registry(&self) -> PostCommitHookRegistry {
+        self.post_commit_hook_registry.clone()
+    }
+
+    /// Verifies that a transaction chunk with the given version range starts
+    /// exactly one version past the highest committed (or in-flight) version.
+    /// Returns true iff the chunk is an exact repeat of the last submitted
+    /// chunk (and so can be ignored). If the chunk is discontinuous, an error
+    /// notification is sent and an error is returned.
+    fn verify_chunk_continuity(
+        &self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        version_range: Option<(Version, Version)>,
+    ) -> Result<bool, Error> {
+        // Chunks without a known version range can't be checked
+        let (first_version, last_version) = match version_range {
+            Some(version_range) => version_range,
+            None => return Ok(false),
+        };
+
+        // Identify the version the chunk should start at (i.e., one past the
+        // highest version committed to storage, or still in flight). The
+        // committed version is read from the committed version handle (to
+        // avoid a storage read per chunk). If nothing has been committed or
+        // is in flight (i.e., storage is empty), the chunk should start at
+        // genesis.
+        let highest_known_version = [
+            self.committed_version_handle
+                .get_highest_committed_version(),
+            self.commit_state_tracker
+                .get_commit_state()
+                .map(|commit_state| commit_state.last_commit_version),
+            self.pending_data_tracker
+                .summary()
+                .highest_version_in_flight,
+        ]
+        .iter()
+        .flatten()
+        .max()
+        .copied();
+        let expected_version = match highest_known_version {
+            Some(highest_known_version) => {
+                highest_known_version.checked_add(1).ok_or_else(|| {
+                    Error::IntegerOverflow("The expected version has overflown!".into())
+                })?
+            }
+            None => GENESIS_TRANSACTION_VERSION,
+        };
+        if first_version == expected_version {
+            return Ok(false);
+        }
+
+        // Ignore exact repeats of the last submitted chunk
+        let last_submitted_chunk = *self.last_submitted_chunk.lock();
+        if last_submitted_chunk == Some((first_version, last_version))
+            && last_version < expected_version
+        {
+            info!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "Ignoring a duplicate transaction chunk for versions: {:?} (notification id: {:?})",
+                    version_range, notification_id
+                ))
+            );
+            self.driver_metrics.increment_counter(
+                &metrics::DRIVER_COUNTERS,
+                metrics::DUPLICATE_TRANSACTION_CHUNK,
+            );
+            return Ok(true);
+        }
+
+        // Otherwise, the chunk is gapped or overlapping, so reject it
+        let error_message = format!(
+            "The transaction chunk is not continuous! Expected version: {:?}, received versions: {:?}",
+            expected_version, version_range
+        );
+        Err(self.reject_transaction_chunk(
+            notification_id,
+            error_source,
+            FailedOperation::VerifyChunkContinuity,
+            version_range,
+            Some(expected_version),
+            error_message,
+        ))
+    }
+
+    /// Verifies that a transaction chunk (of the given serialized size) does
+    /// not exceed the max chunk size. If it does, an error notification is
+    /// sent and an error is returned.
+    fn verify_chunk_size(
+        &self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        version_range: Option<(Version, Version)>,
+        num_bytes: u64,
+    ) -> Result<(), Error> {
+        let max_chunk_bytes = self.driver_config.max_chunk_bytes;
+        if num_bytes <= max_chunk_bytes {
+            return Ok(());
+        }
+
+        let error_message = format!(
+            "The transaction chunk is too large! Chunk size: {:?} bytes, max chunk size: {:?} bytes",
+            num_bytes, max_chunk_bytes
+        );
+        Err(self.reject_transaction_chunk(
+            notification_id,
+            error_source,
+            FailedOperation::VerifyChunkSize,
+            version_range,
+            None,
+            error_message,
+        ))
+    }
+
+    /// Rejects a submitted transaction chunk by notifying the listener of
+    /// the failed operation. Returns the error that was notified.
+    fn reject_transaction_chunk(
+        &self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        failed_operation: FailedOperation,
+        version_range: Option<(Version, Version)>,
+        expected_version: Option<Version>,
+        error_message: String,
+    ) -> Error {
+        self.error_notification_sender.notify_error(
+            notification_id,
+            error_source,
+            failed_operation,
+            version_range,
+            expected_version,
+            error_message,
+        )
+    }
+
+    /// Returns a future that resolves once the given version has been durably
+    /// committed (see `CommittedVersionHandle::await_version_committed`).
+    pub fn await_version_committed(
+        &self,
+        version: Version,
+    ) -> impl Future<Output = Result<(), Error>> {
+        self.committed_version_handle
+            .await_version_committed(version)
+    }
+
+    /// Returns an error if the storage synchronizer has been shut down
+    fn verify_not_shutdown(&self) -> Result<(), Error> {
+        if self.shutdown.load(Ordering::Relaxed) {
+            Err(Error::ShuttingDown(
+                "New data chunks are no longer accepted!".into(),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Waits (up to the given duration) for all pending data to leave the
+    /// pipeline. Returns true iff the pipeline is idle. The wait is driven by
+    /// the pending data notifications (on the runtime of the synchronizer),
+    /// while the caller blocks until the pipeline is idle or the wait expires.
+    fn wait_for_idle(&self, max_wait: Duration) -> bool {
+        if self.pending_data_tracker.summary().is_idle() {
+            return true;
+        }
+
+        let (idle_sender, idle_receiver) = std::sync::mpsc::channel();
+        let pending_data_tracker = self.pending_data_tracker.clone();
+        let wait_task = spawn(self.runtime.clone(), async move {
+            pending_data_tracker.wait_until_idle().await;
+            let _ = idle_sender.send(()); // The caller may have stopped waiting
+        });
+        let idle = idle_receiver.recv_timeout(max_wait).is_ok();
+        wait_task.abort();
+
+        idle || self.pending_data_tracker.summary().is_idle()
+    }
+
     /// Notifies the executor of new data chunks
-    fn notify_executor(&mut self, storage_data_chunk: StorageDataChunk) -> Result<(), Error> {
-        if let Err(error) = self.executor_notifier.try_send(storage_data_chunk) {
+    fn notify_executor(
+        &mut self,
+        storage_data_chunk: StorageDataChunk,
+        highest_version: Option<Version>,
+        num_bytes: u64,
+    ) -> Result<(), Error> {
+        let num_stream_resets = self.stream_reset_tracker.get_stream_resets();
+        if let Err(error) =
+            self.executor_notifier
+                .try_send((storage_data_chunk, num_stream_resets, num_bytes))
+        {
             Err(Error::UnexpectedError(format!(
                 "Failed to send storage data chunk to executor: {:?}",
                 error
             )))
         } else {
-            increment_pending_data_chunks(self.pending_data_chunks.clone());
+            self.pending_data_tracker
+                .chunk_sent(highest_version, num_bytes);
             Ok(())
         }
     }
@@ -245,38 +1317,6 @@ impl<
         MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
     > StorageSynchronizerInterface for StorageSynchronizer<ChunkExecutor, MetadataStorage>
 {
-    fn apply_transaction_outputs(
-        &mut self,
-        notification_id: NotificationId,
-        output_list_with_proof: TransactionOutputListWithProof,
-        target_ledger_info: LedgerInfoWithSignatures,
-        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-    ) -> Result<(), Error> {
-        let storage_data_chunk = StorageDataChunk::TransactionOutputs(
-            notification_id,
-            output_list_with_proof,
-            target_ledger_info,
-            end_of_epoch_ledger_info,
-        );
-        self.notify_executor(storage_data_chunk)
-    }
-
-    fn execute_transactions(
-        &mut self,
-        notification_id: NotificationId,
-        transaction_list_with_proof: TransactionListWithProof,
-        target_ledger_info: LedgerInfoWithSignatures,
-        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-    ) -> Result<(), Error> {
-        let storage_data_chunk = StorageDataChunk::Transactions(
-            notification_id,
-            transaction_list_with_proof,
-            target_ledger_info,
-            end_of_epoch_ledger_info,
-        );
-        self.notify_executor(storage_data_chunk)
-    }
-
     fn initialize_state_synchronizer(
         &mut self,
         epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
@@ -293,33 +1333,183 @@ impl<
             self.chunk_executor.clone(),
             state_snapshot_listener,
             self.commit_notification_sender.clone(),
+            self.committed_version_handle.clone(),
+            self.driver_config.clone(),
             self.error_notification_sender.clone(),
-            self.pending_data_chunks.clone(),
+            self.pending_data_tracker.clone(),
             self.metadata_storage.clone(),
             self.storage.clone(),
             epoch_change_proofs,
             target_ledger_info,
             target_output_with_proof,
             self.runtime.clone(),
+            self.driver_metrics,
         );
         self.state_snapshot_notifier = Some(state_snapshot_notifier);
 
         Ok(receiver_handle)
     }
 
-    fn pending_storage_data(&self) -> bool {
-        load_pending_data_chunks(self.pending_data_chunks.clone()) > 0
+    fn finalize_pending_state_snapshot(&mut self) -> Result<bool, Error> {
+        // Check if there's a partially finalized state snapshot
+        let snapshot_finalization = match self.metadata_storage.pending_snapshot_finalization()? {
+            Some(snapshot_finalization) => snapshot_finalization,
+            None => return Ok(false),
+        };
+        info!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "Found a partially finalized state snapshot at version: {:?}. Completing the finalization!",
+                snapshot_finalization.target_ledger_info.ledger_info().version()
+            ))
+        );
+
+        // Complete the finalization
+        finalize_state_snapshot(
+            self.chunk_executor.clone(),
+            &mut self.commit_notification_sender,
+            &self.committed_version_handle,
+            self.metadata_storage.clone(),
+            self.storage.clone(),
+            snapshot_finalization,
+            &self.driver_metrics,
+        )
+        .map_err(Error::UnexpectedError)?;
+        Ok(true)
+    }
+
+    fn notify_stream_reset(&self) {
+        self.stream_reset_tracker.notify_stream_reset();
+    }
+
+    fn pending_storage_data(&self) -> PendingDataSummary {
+        self.pending_data_tracker.summary()
+    }
+
+    fn subscribe_to_pending_data(&self) -> PendingDataListener {
+        self.pending_data_tracker.subscribe()
+    }
+
+    fn committed_version_handle(&self) -> CommittedVersionHandle {
+        self.committed_version_handle.clone()
+    }
+
+    fn reset_pending_data(&self) {
+        self.stream_reset_tracker.discard_reset_chunks();
+
+        // Cancel all version waiters (the versions might never be committed)
+        self.committed_version_handle
+            .cancel_version_waiters("the pending data was reset");
+
+        // Cancel the execution of any discarded chunk. The caller isn't blocked
+        // while the execution stops (callers wait for the pipeline to drain
+        // before sending new chunks), so the cancellation is monitored by a
+        // background task.
+        if self.stream_reset_tracker.cancel_discarded_execution() {
+            let driver_metrics = self.driver_metrics;
+            let max_wait = Duration::from_millis(self.driver_config.max_chunk_cancellation_wait_ms);
+            let stream_reset_tracker = self.stream_reset_tracker.clone();
+            spawn(self.runtime.clone(), async move {
+                if !stream_reset_tracker
+                    .wait_for_cancelled_execution(max_wait)
+                    .await
+                {
+                    warn!(
+                        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                            "The cancelled chunk execution failed to stop within {:?}!",
+                            max_wait
+                        ))
+                    );
+                    driver_metrics.increment_counter(
+                        &metrics::DRIVER_COUNTERS,
+                        metrics::CHUNK_CANCELLATION_TIMEOUT,
+                    );
+                }
+            });
+        }
+    }
+
+    fn shutdown(&self) -> ShutdownSummary {
+        // Stop accepting new chunks
+        self.shutdown.store(true, Ordering::Relaxed);
+        let (committed_before_drain, processed_before_drain) =
+            self.pending_data_tracker.get_processed_chunk_totals();
+        info!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "Shutting down the storage synchronizer! Pending data: {:?}",
+                self.pending_storage_data()
+            ))
+        );
+
+        // Wait for the pending chunks to be committed. If the drain times out,
+        // discard the remaining chunks (and wait for them to leave the pipeline).
+        let max_drain_time = Duration::from_millis(self.driver_config.max_shutdown_drain_ms);
+        let drained = self.wait_for_idle(max_drain_time);
+        if !drained {
+            warn!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "The pending data failed to drain within {:?}! Discarding the remaining chunks: {:?}",
+                    max_drain_time,
+                    self.pending_storage_data()
+                ))
+            );
+            self.notify_stream_reset();
+            self.reset_pending_data();
+            let max_discard_time =
+                Duration::from_millis(self.driver_config.max_chunk_cancellation_wait_ms);
+            if !self.wait_for_idle(max_discard_time) {
+                warn!(
+                    LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                        "The discarded chunks failed to leave the pipeline within {:?}! Abandoning them: {:?}",
+                        max_discard_time,
+                        self.pending_storage_data()
+                    ))
+                );
+            }
+        }
+
+        // Summarize the drain (chunks still in the pipeline are abandoned)
+        let (committed_after_drain, processed_after_drain) =
+            self.pending_data_tracker.get_processed_chunk_totals();
+        let chunks_committed = committed_after_drain.saturating_sub(committed_before_drain);
+        let chunks_processed = processed_after_drain.saturating_sub(processed_before_drain);
+        let shutdown_summary = ShutdownSummary {
+            chunks_committed,
+            chunks_discarded: chunks_processed.saturating_sub(chunks_committed)
+                + self.pending_storage_data().chunks_in_flight,
+            drained,
+        };
+        info!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "The storage synchronizer has shut down! Summary: {:?}",
+                shutdown_summary
+            ))
+        );
+        shutdown_summary
     }
 
-    fn save_state_values(
+    fn save_state_values_with_proof(
         &mut self,
         notification_id: NotificationId,
         state_value_chunk_with_proof: StateValueChunkWithProof,
+        verify: bool,
     ) -> Result<(), Error> {
+        self.verify_not_shutdown()?;
+
+        // Verify the chunk will be verified exactly once
+        if verify != self.verifies_state_value_chunks() {
+            return Err(Error::UnexpectedError(format!(
+                "The state value chunk verification doesn't match the storage synchronizer config! \
+                Verify: {:?}, verified by the storage synchronizer: {:?}",
+                verify,
+                self.verifies_state_value_chunks()
+            )));
+        }
+
         let state_snapshot_notifier = &mut self
             .state_snapshot_notifier
             .as_mut()
             .expect("The state snapshot receiver has not been initialized!");
+        let num_bytes = get_state_values_size(&state_value_chunk_with_proof.raw_values);
         let storage_data_chunk =
             StorageDataChunk::States(notification_id, state_value_chunk_with_proof);
         if let Err(error) = state_snapshot_notifier.try_send(storage_data_chunk) {
@@ -328,11 +1518,72 @@ impl<
                 error
             )))
         } else {
-            increment_pending_data_chunks(self.pending_data_chunks.clone());
+            self.pending_data_tracker.chunk_sent(None, num_bytes);
             Ok(())
         }
     }
 
+    fn verifies_state_value_chunks(&self) -> bool {
+        self.driver_config
+            .verify_state_values_in_storage_synchronizer
+    }
+
+    fn submit_transaction_chunk(
+        &mut self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        apply_or_execute: ApplyOrExecute,
+        chunk_payload: TransactionChunkPayload,
+        target_ledger_info: LedgerInfoWithSignatures,
+        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+        ledger_info_verifier: Option<EpochState>,
+    ) -> Result<(), Error> {
+        self.verify_not_shutdown()?;
+
+        // Verify the payload can be stored as instructed
+        if apply_or_execute == ApplyOrExecute::Apply
+            && !matches!(
+                chunk_payload,
+                TransactionChunkPayload::TransactionOutputs(_)
+            )
+        {
+            return Err(Error::InvalidPayload(
+                "Unable to apply a transaction chunk without transaction outputs!".into(),
+            ));
+        }
+
+        // Verify the chunk isn't too large (to protect memory)
+        let version_range = get_chunk_version_range(
+            chunk_payload.first_version(),
+            chunk_payload.num_transactions(),
+        );
+        let num_bytes = chunk_payload.get_serialized_size();
+        self.verify_chunk_size(notification_id, error_source, version_range, num_bytes)?;
+
+        // Verify the chunk continues from the highest committed (or in-flight) version
+        if self.verify_chunk_continuity(notification_id, error_source, version_range)? {
+            return Ok(()); // The chunk is a duplicate
+        }
+
+        // Send the chunk to the executor
+        let highest_version = get_highest_chunk_version(
+            chunk_payload.first_version(),
+            chunk_payload.num_transactions(),
+        );
+        let storage_data_chunk = StorageDataChunk::TransactionChunk(
+            notification_id,
+            error_source,
+            apply_or_execute,
+            chunk_payload,
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+            ledger_info_verifier,
+        );
+        self.notify_executor(storage_data_chunk, highest_version, num_bytes)?;
+        *self.last_submitted_chunk.lock() = version_range;
+        Ok(())
+    }
+
     fn reset_chunk_executor(&self) -> Result<(), Error> {
         self.chunk_executor.reset().map_err(|error| {
             Error::UnexpectedError(format!(
@@ -353,125 +1604,597 @@ impl<
 #[derive(Debug)]
 enum StorageDataChunk {
     States(NotificationId, StateValueChunkWithProof),
-    Transactions(
-        NotificationId,
-        TransactionListWithProof,
-        LedgerInfoWithSignatures,
-        Option<LedgerInfoWithSignatures>,
-    ),
-    TransactionOutputs(
+    TransactionChunk(
         NotificationId,
-        TransactionOutputListWithProof,
+        ErrorSource,
+        ApplyOrExecute,
+        TransactionChunkPayload,
         LedgerInfoWithSignatures,
         Option<LedgerInfoWithSignatures>,
+        Option<EpochState>,
     ),
 }
 
-/// Spawns a dedicated executor that executes/applies storage data chunks
+/// A shared handle to the highest version (and the epoch of that version)
+/// durably committed to storage. The handle is initialized from storage, and
+/// is then updated after each commit. Both values only ever increase.
+///
+/// Note: the version and epoch are updated independently, so a reader might
+/// briefly observe a new version with the previous epoch.
+#[derive(Clone, Debug)]
+pub struct CommittedVersionHandle {
+    committed_epoch: Arc<AtomicU64>,
+    committed_version: Arc<AtomicU64>,
+    nothing_committed: Arc<AtomicBool>, // True iff storage was empty (pre-genesis) and nothing has been committed since
+    version_waiters: Arc<Mutex<BTreeMap<Version, Vec<VersionWaiter>>>>, // Waiters (keyed by version) for uncommitted versions
+}
+
+/// A waiter for a version to be committed
+type VersionWaiter = oneshot::Sender<Result<(), Error>>;
+
+impl CommittedVersionHandle {
+    pub fn new(committed_version: Version, committed_epoch: u64) -> Self {
+        Self {
+            committed_epoch: Arc::new(AtomicU64::new(committed_epoch)),
+            committed_version: Arc::new(AtomicU64::new(committed_version)),
+            nothing_committed: Arc::new(AtomicBool::new(false)),
+            version_waiters: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Creates a handle initialized to the latest version (and epoch) in
+    /// storage. If storage is empty (i.e., before genesis), the handle is
+    /// initialized to the genesis version and epoch.
+    ///
+    /// Note: waits for the genesis version on an empty storage resolve
+    /// immediately (use `get_highest_committed_version()` to distinguish
+    /// "nothing committed").
+    pub fn new_from_storage(storage: Arc<dyn DbReader>) -> Result<Self, Error> {
+        match utils::fetch_latest_synced_version(storage.clone())? {
+            Some(committed_version) => {
+                let committed_epoch = utils::fetch_latest_synced_ledger_info(storage)?
+                    .ledger_info()
+                    .epoch();
+                Ok(Self::new(committed_version, committed_epoch))
+            }
+            None => {
+                let committed_version_handle = Self::new(GENESIS_TRANSACTION_VERSION, 0);
+                committed_version_handle
+                    .nothing_committed
+                    .store(true, Ordering::Release);
+                Ok(committed_version_handle)
+            }
+        }
+    }
+
+    /// Returns the highest committed epoch
+    pub fn get_committed_epoch(&self) -> u64 {
+        self.committed_epoch.load(Ordering::Acquire)
+    }
+
+    /// Returns the highest committed version
+    pub fn get_committed_version(&self) -> Version {
+        self.committed_version.load(Ordering::Acquire)
+    }
+
+    /// Returns the highest committed version, or None if nothing has been
+    /// committed (i.e., storage was empty when the handle was created, and
+    /// the handle hasn't been updated since).
+    pub fn get_highest_committed_version(&self) -> Option<Version> {
+        if self.nothing_committed.load(Ordering::Acquire) {
+            None
+        } else {
+            Some(self.get_committed_version())
+        }
+    }
+
+    /// Records a commit up to the given version (in the given epoch). Stale
+    /// updates are ignored, i.e., the handle never moves backwards. Any
+    /// waiters for versions up to the committed version are notified.
+    pub fn update(&self, committed_version: Version, committed_epoch: u64) {
+        self.committed_epoch
+            .fetch_max(committed_epoch, Ordering::AcqRel);
+        self.committed_version
+            .fetch_max(committed_version, Ordering::AcqRel);
+        self.nothing_committed.store(false, Ordering::Release);
+
+        // Notify the waiters for all committed versions
+        let committed_waiters = {
+            let mut version_waiters = self.version_waiters.lock();
+            let uncommitted_waiters =
+                version_waiters.split_off(&committed_version.saturating_add(1));
+            std::mem::replace(&mut *version_waiters, uncommitted_waiters)
+        };
+        for waiter in committed_waiters.into_values().flatten() {
+            let _ = waiter.send(Ok(())); // The waiter may have been dropped
+        }
+    }
+
+    /// Returns a future that resolves once the given version has been durably
+    /// committed. The future resolves immediately if the version has already
+    /// been committed, and returns an error if the wait is cancelled (e.g., the
+    /// pending data is reset or the committer shuts down) before then.
+    ///
+    /// Note: the waiter is registered when this is called (not when the
+    /// future is first polled).
+    pub fn await_version_committed(
+        &self,
+        version: Version,
+    ) -> impl Future<Output = Result<(), Error>> {
+        // Register a waiter (if the version hasn't been committed). The lock is
+        // held while checking the committed version so that a concurrent update
+        // either sees the registered waiter or is seen by the check.
+        let waiter_receiver = {
+            let mut version_waiters = self.version_waiters.lock();
+            if version <= self.get_committed_version() {
+                None
+            } else {
+                let (waiter_sender, waiter_receiver) = oneshot::channel();
+                version_waiters
+                    .entry(version)
+                    .or_insert_with(Vec::new)
+                    .push(waiter_sender);
+                Some(waiter_receiver)
+            }
+        };
+
+        async move {
+            match waiter_receiver {
+                Some(waiter_receiver) => waiter_receiver.await?,
+                None => Ok(()), // The version has already been committed
+            }
+        }
+    }
+
+    /// Cancels all outstanding version waiters (for the given reason)
+    pub fn cancel_version_waiters(&self, reason: &str) {
+        let version_waiters = std::mem::take(&mut *self.version_waiters.lock());
+        if version_waiters.is_empty() {
+            return;
+        }
+
+        info!(
+            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                "Cancelling the waiters for {:?} uncommitted versions: {}",
+                version_waiters.len(),
+                reason
+            ))
+        );
+        for (version, waiters) in version_waiters {
+            for waiter in waiters {
+                let _ = waiter.send(Err(Error::VersionWaitCancelled(version, reason.into())));
+            }
+        }
+    }
+}
+
+/// Verifies the ledger info signatures of transaction chunks. Verification
+/// is performed on a dedicated (bounded) thread pool so that the ledger
+/// infos of all chunks queued for the executor can be verified in parallel.
+#[derive(Clone)]
+pub struct LedgerInfoVerifier {
+    thread_pool: Arc<ThreadPool>,
+}
+
+impl LedgerInfoVerifier {
+    pub fn new(num_threads: u64) -> Self {
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads.max(1) as usize)
+            .thread_name(|index| format!("ledger-info-verifier-{}", index))
+            .build()
+            .expect("Failed to create the ledger info verification thread pool!");
+        Self {
+            thread_pool: Arc::new(thread_pool),
+        }
+    }
+
+    /// Verifies each ledger info against its epoch state (i.e., the verifier
+    /// for the epoch of the ledger info). Returns the verification result
+    /// of each ledger info, in the given order.
+    pub fn verify_ledger_infos(
+        &self,
+        ledger_infos: &[(&EpochState, &LedgerInfoWithSignatures)],
+    ) -> Vec<Result<(), Error>> {
+        self.thread_pool.install(|| {
+            ledger_infos
+                .par_iter()
+                .map(|(epoch_state, ledger_info_with_signatures)| {
+                    verify_ledger_info(epoch_state, ledger_info_with_signatures)
+                })
+                .collect()
+        })
+    }
+
+    /// Verifies the ledger infos of the given queued chunks (together) and
+    /// returns the verification result of each chunk, in order. Chunks
+    /// without a verifier (i.e., their ledger infos are already verified)
+    /// and discarded chunks are skipped. Chunks discarded while the ledger
+    /// infos are being verified are skipped between verifications.
+    fn verify_queued_chunks(
+        &self,
+        queued_chunks: &[(StorageDataChunk, u64, u64)],
+        stream_reset_tracker: &StreamResetTracker,
+    ) -> Vec<Result<(), Error>> {
+        // Gather the ledger infos to verify (tagged with the chunk index)
+        let mut chunk_indices = vec![];
+        let mut ledger_infos = vec![];
+        for (chunk_index, (storage_data_chunk, num_stream_resets, _)) in
+            queued_chunks.iter().enumerate()
+        {
+            if let StorageDataChunk::TransactionChunk(
+                _,
+                _,
+                _,
+                _,
+                target_ledger_info,
+                end_of_epoch_ledger_info,
+                Some(epoch_state),
+            ) = storage_data_chunk
+            {
+                if !stream_reset_tracker.is_discarded(*num_stream_resets) {
+                    for ledger_info in
+                        std::iter::once(target_ledger_info).chain(end_of_epoch_ledger_info)
+                    {
+                        chunk_indices.push(chunk_index);
+                        ledger_infos.push((*num_stream_resets, epoch_state, ledger_info));
+                    }
+                }
+            }
+        }
+
+        // Verify the ledger infos and fail any chunk with an invalid ledger info
+        let mut chunk_results: Vec<Result<(), Error>> =
+            queued_chunks.iter().map(|_| Ok(())).collect();
+        if !ledger_infos.is_empty() {
+            let verification_results: Vec<Result<(), Error>> = self.thread_pool.install(|| {
+                ledger_infos
+                    .par_iter()
+                    .map(|(num_stream_resets, epoch_state, ledger_info)| {
+                        // Skip the verification if the chunk has since been discarded
+                        if stream_reset_tracker.is_discarded(*num_stream_resets) {
+                            return Ok(());
+                        }
+                        verify_ledger_info(epoch_state, ledger_info)
+                    })
+                    .collect()
+            });
+            for (chunk_index, result) in chunk_indices.into_iter().zip(verification_results) {
+                if let Err(error) = result {
+                    if chunk_results[chunk_index].is_ok() {
+                        chunk_results[chunk_index] = Err(error);
+                    }
+                }
+            }
+        }
+        chunk_results
+    }
+}
+
+/// A chunk that has been executed/applied (but not yet committed). This is
+/// sent from the executor to the committer.
+#[derive(Debug)]
+struct ExecutedChunk {
+    apply_or_execute: ApplyOrExecute, // Whether the chunk was applied or executed
+    ends_with_ledger_info: bool, // If the chunk ends at its target (or epoch ending) ledger info
+    epoch: u64,                  // The epoch of the chunk (i.e., of its target ledger info)
+    error_source: ErrorSource,
+    execute_duration: Duration, // The time taken to execute/apply the chunk
+    first_version: Option<Version>,
+    new_epoch_state: Option<EpochState>, // The new epoch state (if the chunk ends the epoch)
+    notification_id: NotificationId,
+    num_bytes: u64,
+    num_output_bytes: u64, // The serialized size of the applied outputs (if any)
+    num_stream_resets: u64, // The number of stream resets when the chunk was sent
+    num_versions: u64,
+    timestamp_usecs: u64, // The timestamp of the chunk's target ledger info
+}
+
+impl ExecutedChunk {
+    /// Returns a summary of the chunk (once committed in the given
+    /// duration, with the given number of bytes written by the commit).
+    fn get_committed_chunk(
+        &self,
+        commit_duration: Duration,
+        commit_bytes: u64,
+    ) -> CommittedTransactionChunk {
+        CommittedTransactionChunk {
+            apply_or_execute: self.apply_or_execute,
+            commit_bytes,
+            commit_duration,
+            execute_duration: self.execute_duration,
+            first_version: self.first_version,
+            num_versions: self.num_versions,
+        }
+    }
+
+    /// Returns the (inclusive) version range of the chunk (if known)
+    fn get_version_range(&self) -> Option<(Version, Version)> {
+        get_chunk_version_range(self.first_version, self.num_versions as usize)
+    }
+}
+
+/// A batch of executed chunks that are committed to storage together (i.e.,
+/// in a single write batch).
+#[derive(Debug, Default)]
+struct CommitBatch {
+    executed_chunks: Vec<ExecutedChunk>,
+    num_bytes: u64,
+    start_time: Option<Instant>,
+}
+
+impl CommitBatch {
+    /// Adds the executed chunk to the batch
+    fn add_chunk(&mut self, executed_chunk: ExecutedChunk) {
+        if self.executed_chunks.is_empty() {
+            self.start_time = Some(Instant::now());
+        }
+        self.num_bytes += executed_chunk.num_bytes;
+        self.executed_chunks.push(executed_chunk);
+    }
+
+    /// Returns the time left before the batch must be committed
+    fn get_remaining_delay(&self, driver_config: &StateSyncDriverConfig) -> Duration {
+        let max_delay = Duration::from_millis(driver_config.commit_batch_max_delay_ms);
+        self.start_time
+            .map(|start_time| max_delay.saturating_sub(start_time.elapsed()))
+            .unwrap_or(max_delay)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.executed_chunks.is_empty()
+    }
+
+    /// Returns true iff the batch must be committed now, i.e., if: (i) the
+    /// last chunk ends at a ledger info (e.g., a consensus sync request target
+    /// or an epoch change); (ii) the batch has hit the count, byte or time
+    /// bound; or (iii) the pipeline has drained (i.e., all data in flight is
+    /// already in the batch).
+    fn should_commit(&self, driver_config: &StateSyncDriverConfig, chunks_in_flight: u64) -> bool {
+        let last_chunk = match self.executed_chunks.last() {
+            Some(last_chunk) => last_chunk,
+            None => return false,
+        };
+        let num_chunks = self.executed_chunks.len() as u64;
+        last_chunk.ends_with_ledger_info
+            || num_chunks >= driver_config.commit_batch_max_chunks
+            || self.num_bytes >= driver_config.commit_batch_max_bytes
+            || chunks_in_flight <= num_chunks
+            || self.get_remaining_delay(driver_config).is_zero()
+    }
+
+    /// Takes the executed chunks from the batch (leaving it empty)
+    fn take_chunks(&mut self) -> Vec<ExecutedChunk> {
+        self.num_bytes = 0;
+        self.start_time = None;
+        std::mem::take(&mut self.executed_chunks)
+    }
+}
+
+/// Spawns a dedicated executor that executes/applies storage data chunks.
+/// All chunks queued for the executor are dequeued together, so that their
+/// ledger infos can be verified in parallel (see `LedgerInfoVerifier`).
 fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     chunk_executor: Arc<ChunkExecutor>,
-    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
-    mut executor_listener: mpsc::Receiver<StorageDataChunk>,
-    mut committer_notifier: mpsc::Sender<NotificationId>,
-    pending_transaction_chunks: Arc<AtomicU64>,
+    error_notification_sender: ErrorNotifier,
+    mut executor_listener: mpsc::Receiver<(StorageDataChunk, u64, u64)>,
+    mut committer_notifier: mpsc::Sender<ExecutedChunk>,
+    executor_thread_pool: Arc<ThreadPool>,
+    ledger_info_verifier: LedgerInfoVerifier,
+    pending_data_tracker: PendingDataTracker,
     runtime: Option<Handle>,
+    stream_reset_tracker: StreamResetTracker,
+    verify_event_root_hashes: bool,
+    driver_metrics: DriverMetrics,
 ) -> JoinHandle<()> {
     // Create an executor
     let executor = async move {
-        while let Some(storage_data_chunk) = executor_listener.next().await {
-            // Execute/apply the storage data chunk
-            let (notification_id, result) = match storage_data_chunk {
-                StorageDataChunk::Transactions(
-                    notification_id,
-                    transactions_with_proof,
-                    target_ledger_info,
-                    end_of_epoch_ledger_info,
-                ) => {
-                    let num_transactions = transactions_with_proof.transactions.len();
-                    let result = chunk_executor.execute_chunk(
-                        transactions_with_proof,
-                        &target_ledger_info,
-                        end_of_epoch_ledger_info.as_ref(),
-                    );
-                    if result.is_ok() {
-                        info!(
+        while let Some(queued_chunk) = executor_listener.next().await {
+            // Take all other chunks waiting for the executor. Note: the chunks
+            // are only marked as dequeued once they're executed/applied.
+            let mut queued_chunks = vec![queued_chunk];
+            while let Ok(Some(queued_chunk)) = executor_listener.try_next() {
+                queued_chunks.push(queued_chunk);
+            }
+
+            // Verify the ledger infos of the queued chunks
+            let verification_results =
+                ledger_info_verifier.verify_queued_chunks(&queued_chunks, &stream_reset_tracker);
+
+            for ((storage_data_chunk, num_stream_resets, num_bytes), verification_result) in
+                queued_chunks.into_iter().zip(verification_results)
+            {
+                pending_data_tracker.chunk_dequeued(num_bytes);
+
+                // Discard the chunk if it was sent before the pending data was reset
+                if stream_reset_tracker.is_discarded(num_stream_resets) {
+                    log_discarded_chunks(1, &driver_metrics);
+                    pending_data_tracker.chunk_processed(PipelineStage::Executing);
+                    continue;
+                }
+
+                // Execute/apply the storage data chunk
+                let (executed_chunk, result) = match storage_data_chunk {
+                    StorageDataChunk::TransactionChunk(
+                        notification_id,
+                        error_source,
+                        apply_or_execute,
+                        chunk_payload,
+                        target_ledger_info,
+                        end_of_epoch_ledger_info,
+                        _,
+                    ) => {
+                        let num_transactions = chunk_payload.num_transactions();
+                        let first_version = chunk_payload.first_version();
+
+                        // Fail the chunk if its ledger infos failed verification
+                        if let Err(error) = verification_result {
+                            let error = format!(
+                                "Failed to verify the chunk ledger infos! Error: {:?}",
+                                error
+                            );
+                            send_storage_synchronizer_error(
+                                error_notification_sender.clone(),
+                                notification_id,
+                                error_source,
+                                FailedOperation::VerifyLedgerInfo,
+                                get_chunk_version_range(first_version, num_transactions),
+                                error,
+                            )
+                            .await;
+                            pending_data_tracker.chunk_processed(PipelineStage::Executing);
+                            continue;
+                        }
+
+                        // Fail the chunk if the output events don't match the event root hashes
+                        if verify_event_root_hashes {
+                            if let Err(error) =
+                                verify_output_event_root_hashes(apply_or_execute, &chunk_payload)
+                            {
+                                driver_metrics.increment_counter(
+                                    &metrics::DRIVER_COUNTERS,
+                                    metrics::EVENT_ROOT_HASH_MISMATCH,
+                                );
+                                let error = format!(
+                                    "Failed to verify the event root hashes of the chunk! Error: {:?}",
+                                    error
+                                );
+                                send_storage_synchronizer_error(
+                                    error_notification_sender.clone(),
+                                    notification_id,
+                                    error_source,
+                                    FailedOperation::VerifyEventRootHash,
+                                    get_chunk_version_range(first_version, num_transactions),
+                                    error,
+                                )
+                                .await;
+                                pending_data_tracker.chunk_processed(PipelineStage::Executing);
+                                continue;
+                            }
+                        }
+
+                        let ends_with_ledger_info = chunk_ends_with_ledger_info(
+                            first_version,
+                            num_transactions,
+                            &target_ledger_info,
+                            end_of_epoch_ledger_info.as_ref(),
+                        );
+                        let new_epoch_state = get_new_epoch_state(
+                            first_version,
+                            num_transactions,
+                            &target_ledger_info,
+                            end_of_epoch_ledger_info.as_ref(),
+                        );
+                        let num_output_bytes = chunk_payload.get_output_size();
+                        let execute_start_time = Instant::now();
+                        let cancellation_token =
+                            stream_reset_tracker.execution_started(num_stream_resets);
+                        let result = executor_thread_pool.install(|| {
+                            apply_or_execute_chunk(
+                                chunk_executor.as_ref(),
+                                apply_or_execute,
+                                chunk_payload,
+                                &target_ledger_info,
+                                end_of_epoch_ledger_info.as_ref(),
+                                &cancellation_token,
+                            )
+                        });
+                        stream_reset_tracker.execution_stopped();
+                        let execute_duration = execute_start_time.elapsed();
+
+                        // Discard the chunk if the execution was cancelled
+                        if cancellation_token.is_cancelled() {
+                            log_cancelled_chunk_execution(
+                                get_chunk_version_range(first_version, num_transactions),
+                                execute_duration,
+                                &driver_metrics,
+                            );
+                            pending_data_tracker.chunk_processed(PipelineStage::Executing);
+                            continue;
+                        }
+                        if result.is_ok() {
+                            info!(
                             LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
-                                "Executed a new transaction chunk! Transaction total: {:?}.",
-                                num_transactions
+                                "Stored a new transaction chunk! Instruction: {:?}, transaction total: {:?}.",
+                                apply_or_execute, num_transactions
                             ))
                         );
-                        metrics::increment_gauge(
-                            &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                            metrics::StorageSynchronizerOperations::ExecutedTransactions
-                                .get_label(),
-                            num_transactions as u64,
-                        );
+                            driver_metrics.increment_gauge(
+                                &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
+                                apply_or_execute.get_operation_label(),
+                                num_transactions as u64,
+                            );
+                            driver_metrics.observe_transaction_chunk_latency(
+                                &metrics::TRANSACTION_CHUNK_EXECUTE_LATENCY,
+                                apply_or_execute.get_label(),
+                                num_transactions as u64,
+                                execute_duration,
+                            );
+                        }
+                        let executed_chunk = ExecutedChunk {
+                            apply_or_execute,
+                            ends_with_ledger_info,
+                            epoch: target_ledger_info.ledger_info().epoch(),
+                            error_source,
+                            execute_duration,
+                            first_version,
+                            new_epoch_state,
+                            notification_id,
+                            num_bytes,
+                            num_output_bytes,
+                            num_stream_resets,
+                            num_versions: num_transactions as u64,
+                            timestamp_usecs: target_ledger_info.ledger_info().timestamp_usecs(),
+                        };
+                        (executed_chunk, result)
                     }
-                    (notification_id, result)
-                }
-                StorageDataChunk::TransactionOutputs(
-                    notification_id,
-                    outputs_with_proof,
-                    target_ledger_info,
-                    end_of_epoch_ledger_info,
-                ) => {
-                    let num_outputs = outputs_with_proof.transactions_and_outputs.len();
-                    let result = chunk_executor.apply_chunk(
-                        outputs_with_proof,
-                        &target_ledger_info,
-                        end_of_epoch_ledger_info.as_ref(),
-                    );
-                    if result.is_ok() {
-                        info!(
-                            LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
-                                "Applied a new transaction output chunk! Transaction total: {:?}.",
-                                num_outputs
-                            ))
-                        );
-                        metrics::increment_gauge(
-                            &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                            metrics::StorageSynchronizerOperations::AppliedTransactionOutputs
-                                .get_label(),
-                            num_outputs as u64,
+                    storage_data_chunk => {
+                        panic!(
+                            "Invalid storage data chunk sent to executor: {:?}",
+                            storage_data_chunk
                         );
                     }
-                    (notification_id, result)
-                }
-                storage_data_chunk => {
-                    panic!(
-                        "Invalid storage data chunk sent to executor: {:?}",
-                        storage_data_chunk
-                    );
-                }
-            };
+                };
 
-            // Notify the committer of new executed chunks
-            match result {
-                Ok(()) => {
-                    if let Err(error) = committer_notifier.try_send(notification_id) {
-                        let error = format!("Failed to notify the committer! Error: {:?}", error);
+                // Notify the committer of new executed chunks. If the committer
+                // is behind, this waits until there's space in the queue.
+                let notification_id = executed_chunk.notification_id;
+                let error_source = executed_chunk.error_source;
+                let version_range = executed_chunk.get_version_range();
+                match result {
+                    Ok(()) => {
+                        pending_data_tracker.chunk_executed();
+                        if let Err(error) = committer_notifier.send(executed_chunk).await {
+                            let error =
+                                format!("Failed to notify the committer! Error: {:?}", error);
+                            send_storage_synchronizer_error(
+                                error_notification_sender.clone(),
+                                notification_id,
+                                error_source,
+                                FailedOperation::NotifyCommitter,
+                                version_range,
+                                error,
+                            )
+                            .await;
+                            pending_data_tracker.chunk_processed(PipelineStage::QueuedForCommit);
+                        }
+                    }
+                    Err(error) => {
+                        let error = format!(
+                            "Failed to execute/apply the storage data chunk! Error: {:?}",
+                            error
+                        );
                         send_storage_synchronizer_error(
                             error_notification_sender.clone(),
                             notification_id,
+                            error_source,
+                            FailedOperation::ExecuteChunk,
+                            version_range,
                             error,
                         )
                         .await;
-                        decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                        pending_data_tracker.chunk_processed(PipelineStage::Executing);
                     }
                 }
-                Err(error) => {
-                    let error = format!(
-                        "Failed to execute/apply the storage data chunk! Error: {:?}",
-                        error
-                    );
-                    send_storage_synchronizer_error(
-                        error_notification_sender.clone(),
-                        notification_id,
-                        error,
-                    )
-                    .await;
-                    decrement_pending_data_chunks(pending_transaction_chunks.clone());
-                }
             }
         }
     };
@@ -480,93 +2203,534 @@ fn spawn_executor<ChunkExecutor: ChunkExecutorTrait + 'static>(
     spawn(runtime, executor)
 }
 
-/// Spawns a dedicated committer that commits executed (but pending) chunks
+/// Spawns a dedicated committer that commits executed (but pending) chunks.
+/// Executed chunks may be accumulated and committed together in a single
+/// write batch (see `CommitBatch`).
+#[allow(clippy::too_many_arguments)]
 fn spawn_committer<
     ChunkExecutor: ChunkExecutorTrait + 'static,
     MempoolNotifier: MempoolNotificationSender,
 >(
     chunk_executor: Arc<ChunkExecutor>,
-    mut committer_listener: mpsc::Receiver<NotificationId>,
-    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
-    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+    mut committer_listener: mpsc::Receiver<ExecutedChunk>,
+    committer_thread_pool: Arc<ThreadPool>,
+    committed_version_handle: CommittedVersionHandle,
+    driver_config: StateSyncDriverConfig,
+    error_notification_sender: ErrorNotifier,
+    event_notification_handler: EventNotificationHandler,
+    commit_state_tracker: CommitStateTracker,
+    commit_subscriber_registry: CommitSubscriberRegistry,
     mempool_notification_handler: MempoolNotificationHandler<MempoolNotifier>,
-    pending_transaction_chunks: Arc<AtomicU64>,
+    notified_version_tracker: NotifiedVersionTracker,
+    pending_data_tracker: PendingDataTracker,
+    post_commit_hook_registry: PostCommitHookRegistry,
     runtime: Option<Handle>,
     storage: Arc<dyn DbReader>,
+    stream_reset_tracker: StreamResetTracker,
+    driver_metrics: DriverMetrics,
+    dry_run: bool,
 ) -> JoinHandle<()> {
     // Create a committer
     let committer = async move {
-        while let Some(notification_id) = committer_listener.next().await {
-            // Commit the executed chunk
-            match chunk_executor.commit_chunk() {
+        let mut commit_batch = CommitBatch::default();
+        let mut listener_terminated = false;
+        while !listener_terminated {
+            // Wait for the next executed chunk. If a batch is being
+            // accumulated, only wait until the batch delay expires.
+            let next_executed_chunk = if commit_batch.is_empty() {
+                Ok(committer_listener.next().await)
+            } else {
+                timeout(
+                    commit_batch.get_remaining_delay(&driver_config),
+                    committer_listener.next(),
+                )
+                .await
+            };
+
+            // Determine if the batch should be committed now
+            let commit_now = match next_executed_chunk {
+                Ok(Some(executed_chunk)) => {
+                    pending_data_tracker.chunk_committing();
+                    let discarded =
+                        stream_reset_tracker.is_discarded(executed_chunk.num_stream_resets);
+                    commit_batch.add_chunk(executed_chunk);
+                    let chunks_in_flight = pending_data_tracker.summary().chunks_in_flight;
+                    discarded || commit_batch.should_commit(&driver_config, chunks_in_flight)
+                }
+                Ok(None) => {
+                    listener_terminated = true;
+                    !commit_batch.is_empty()
+                }
+                Err(_) => true, // The batch delay has expired
+            };
+            if !commit_now {
+                continue;
+            }
+            let executed_chunks = commit_batch.take_chunks();
+            let num_chunks = executed_chunks.len();
+
+            // If the pending data was reset, discard the executed chunks. Chunks
+            // are received in the order they were sent, so if the last chunk was
+            // sent before the reset, all chunks in the batch were too.
+            let batch_discarded = executed_chunks.last().map_or(false, |executed_chunk| {
+                stream_reset_tracker.is_discarded(executed_chunk.num_stream_resets)
+            });
+            if batch_discarded {
+                log_discarded_chunks(num_chunks, &driver_metrics);
+                for _ in 0..num_chunks {
+                    pending_data_tracker.chunk_processed(PipelineStage::Committing);
+                }
+                continue;
+            }
+            let first_version = executed_chunks
+                .iter()
+                .find_map(|executed_chunk| executed_chunk.first_version);
+            let last_version = executed_chunks
+                .last()
+                .and_then(|executed_chunk| executed_chunk.get_version_range())
+                .map(|(_, last_version)| last_version);
+
+            // Commit the executed chunks (retrying transient storage failures)
+            let commit_start_time = Instant::now();
+            let commit_result = commit_executed_chunks(
+                chunk_executor.as_ref(),
+                &committer_thread_pool,
+                &driver_config,
+                num_chunks,
+                first_version.zip(last_version),
+                &driver_metrics,
+            )
+            .await;
+            let commit_duration = commit_start_time.elapsed();
+            match commit_result {
+                Ok(notification) if dry_run => {
+                    // Nothing was committed, so only report the executed chunks
+                    pending_data_tracker.chunks_committed(num_chunks as u64);
+                    report_dry_run_commit(
+                        &executed_chunks,
+                        notification,
+                        first_version,
+                        &commit_subscriber_registry,
+                        &driver_metrics,
+                    );
+                }
                 Ok(notification) => {
+                    // Record the committed chunks and update the committed
+                    // version handle (the chunks are now durable).
+                    pending_data_tracker.chunks_committed(num_chunks as u64);
+                    if let Some(executed_chunk) = executed_chunks.last() {
+                        if let Some((_, last_version)) = executed_chunk.get_version_range() {
+                            committed_version_handle.update(last_version, executed_chunk.epoch);
+                        }
+                    }
+
+                    // The chunks in a batch are committed together, so each
+                    // chunk is attributed the commit time of the whole batch.
+                    for executed_chunk in &executed_chunks {
+                        driver_metrics.observe_transaction_chunk_latency(
+                            &metrics::TRANSACTION_CHUNK_COMMIT_LATENCY,
+                            executed_chunk.apply_or_execute.get_label(),
+                            executed_chunk.num_versions,
+                            commit_duration,
+                        );
+                    }
+
+                    // Update the committed bytes metrics (by data type). Data
+                    // types that weren't written by the commit are skipped.
+                    let committed_bytes = [
+                        (
+                            metrics::CommittedDataType::Events,
+                            get_serialized_size(&notification.committed_events),
+                        ),
+                        (
+                            metrics::CommittedDataType::Outputs,
+                            executed_chunks
+                                .iter()
+                                .map(|executed_chunk| executed_chunk.num_output_bytes)
+                                .sum(),
+                        ),
+                        (
+                            metrics::CommittedDataType::Transactions,
+                            get_serialized_size(&notification.committed_transactions),
+                        ),
+                    ];
+                    let mut commit_bytes = 0;
+                    for (data_type, num_bytes) in committed_bytes {
+                        if num_bytes > 0 {
+                            driver_metrics.observe_committed_bytes(data_type, num_bytes);
+                            commit_bytes += num_bytes;
+                        }
+                    }
+
                     // Log the event and update the metrics
                     info!(
                         LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
                             "Committed a new transaction chunk! \
-                                    Transaction total: {:?}, event total: {:?}",
+                                    Transaction total: {:?}, event total: {:?}, first version: {:?}, chunk total: {:?}, bytes written: {:?}",
                             notification.committed_transactions.len(),
-                            notification.committed_events.len()
+                            notification.committed_events.len(),
+                            first_version,
+                            num_chunks,
+                            commit_bytes
                         ))
                     );
-                    metrics::increment_gauge(
+                    driver_metrics.increment_gauge(
                         &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
                         metrics::StorageSynchronizerOperations::Synced.get_label(),
                         notification.committed_transactions.len() as u64,
                     );
-                    if notification.reconfiguration_occurred {
-                        utils::update_new_epoch_metrics(storage.clone());
-                    }
 
                     // Handle the committed transaction notification (e.g., notify mempool).
                     // We do this here due to synchronization issues with mempool and
                     // storage. See: https://github.com/aptos-labs/aptos-core/issues/553
-                    let committed_transactions = CommittedTransactions {
-                        events: notification.committed_events,
-                        transactions: notification.committed_transactions,
-                    };
-                    utils::handle_committed_transactions(
-                        committed_transactions,
-                        storage.clone(),
-                        mempool_notification_handler.clone(),
-                        event_subscription_service.clone(),
-                    )
-                    .await;
+                    // Note: the committed transactions are exactly those of the
+                    // executed chunks (in order), so a single merged notification
+                    // starting at the first chunk's version is sent. If the stream
+                    // was reset after any of the chunks were sent, the data is still
+                    // committed, so the notification is processed (but marked).
+                    if let Some(first_version) = first_version {
+                        let mut committed_transactions = CommittedTransactions::new(
+                            notification.committed_events,
+                            notification.committed_transactions,
+                            first_version,
+                        );
+                        committed_transactions.committed_chunks = executed_chunks
+                            .iter()
+                            .map(|executed_chunk| {
+                                executed_chunk.get_committed_chunk(commit_duration, commit_bytes)
+                            })
+                            .collect();
+                        utils::log_slow_transaction_chunks(
+                            &committed_transactions.committed_chunks,
+                            Duration::from_millis(driver_config.slow_chunk_latency_threshold_ms),
+                            &driver_metrics,
+                        );
+                        let num_stream_resets = stream_reset_tracker.get_stream_resets();
+                        committed_transactions.post_reset =
+                            executed_chunks.iter().any(|executed_chunk| {
+                                executed_chunk.num_stream_resets < num_stream_resets
+                            });
+
+                        // Only the last chunk in a batch can end the epoch (as
+                        // batches are committed at ledger info boundaries).
+                        if let Some(new_epoch_state) = executed_chunks
+                            .last()
+                            .and_then(|executed_chunk| executed_chunk.new_epoch_state.clone())
+                        {
+                            committed_transactions.set_new_epoch_state(new_epoch_state);
+                        }
+
+                        // Flag the epoch change if the committed events contain a
+                        // new epoch event (even if the new epoch state is unknown).
+                        let (_, contains_new_epoch_event) =
+                            utils::extract_reconfig_events(&committed_transactions.events);
+                        committed_transactions.ends_epoch |= contains_new_epoch_event;
+
+                        // Run the post-commit hooks (the commit is already durable)
+                        post_commit_hook_registry.run_hooks(&committed_transactions);
+                        let last_version = committed_transactions.last_version;
+                        if let Err(error) = utils::handle_committed_transactions(
+                            committed_transactions,
+                            CommitOrigin::StateSync,
+                            storage.clone(),
+                            mempool_notification_handler.clone(),
+                            event_notification_handler.clone(),
+                            commit_state_tracker.clone(),
+                            commit_subscriber_registry.clone(),
+                            notified_version_tracker.clone(),
+                            driver_metrics,
+                        )
+                        .await
+                        {
+                            utils::log_commit_notification_error(
+                                LogEntry::SynchronizerNotification,
+                                CommitOrigin::StateSync,
+                                first_version,
+                                last_version,
+                                &error,
+                            );
+                        }
+                    }
                 }
                 Err(error) => {
+                    // The chunks are committed atomically, so all of them failed
                     let error = format!("Failed to commit executed chunk! Error: {:?}", error);
-                    send_storage_synchronizer_error(
-                        error_notification_sender.clone(),
-                        notification_id,
-                        error,
-                    )
-                    .await;
+                    for executed_chunk in &executed_chunks {
+                        send_storage_synchronizer_error(
+                            error_notification_sender.clone(),
+                            executed_chunk.notification_id,
+                            executed_chunk.error_source,
+                            FailedOperation::CommitChunk,
+                            executed_chunk.get_version_range(),
+                            error.clone(),
+                        )
+                        .await;
+                    }
                 }
             };
-            decrement_pending_data_chunks(pending_transaction_chunks.clone());
+            for _ in 0..num_chunks {
+                pending_data_tracker.chunk_processed(PipelineStage::Committing);
+            }
         }
+
+        // No more versions will be committed, so cancel any outstanding waiters
+        committed_version_handle.cancel_version_waiters("the committer has shut down");
     };
 
     // Spawn the committer
     spawn(runtime, committer)
 }
 
+/// Reports the executed chunks that a dry-run driver would have committed.
+/// Nothing was written to storage, so mempool, the event subscription service
+/// and the commit state aren't notified (and the post-commit hooks aren't run).
+/// Instead, a synthetic commit event (flagged as a dry run) is sent to the
+/// commit event subscribers.
+fn report_dry_run_commit(
+    executed_chunks: &[ExecutedChunk],
+    notification: ChunkCommitNotification,
+    first_version: Option<Version>,
+    commit_subscriber_registry: &CommitSubscriberRegistry,
+    driver_metrics: &DriverMetrics,
+) {
+    // Log the event and update the metrics
+    let num_transactions = notification.committed_transactions.len() as u64;
+    info!(
+        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+            "[Dry run] Verified and executed a new transaction chunk (but didn't commit it)! \
+                Transaction total: {:?}, first version: {:?}, chunk total: {:?}",
+            num_transactions,
+            first_version,
+            executed_chunks.len()
+        ))
+    );
+    driver_metrics
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::DRY_RUN_VERIFIED_VERSIONS],
+        )
+        .inc_by(num_transactions);
+
+    // Notify the commit event subscribers
+    if let (Some(first_version), Some(last_executed_chunk)) =
+        (first_version, executed_chunks.last())
+    {
+        if num_transactions > 0 {
+            commit_subscriber_registry.notify_commit_event_subscribers(CommitEvent {
+                dry_run: true,
+                epoch: last_executed_chunk.epoch,
+                first_version,
+                last_version: first_version + num_transactions - 1,
+                reconfiguration_occurred: last_executed_chunk.new_epoch_state.is_some(),
+                timestamp_usecs: last_executed_chunk.timestamp_usecs,
+            });
+        }
+    }
+}
+
+/// Commits the given number of executed chunks (a single chunk is committed as
+/// before). Transient storage failures (e.g., write stalls) are retried with a
+/// bounded backoff. Permanent failures, and failures that persist once all
+/// retries are exhausted, are returned.
+///
+/// Note: a failed commit leaves the chunks at the front of the executor's
+/// commit queue, so the same chunks are committed on each retry.
+async fn commit_executed_chunks<ChunkExecutor: ChunkExecutorTrait>(
+    chunk_executor: &ChunkExecutor,
+    committer_thread_pool: &ThreadPool,
+    driver_config: &StateSyncDriverConfig,
+    num_chunks: usize,
+    version_range: Option<(Version, Version)>,
+    driver_metrics: &DriverMetrics,
+) -> anyhow::Result<ChunkCommitNotification> {
+    // Failpoint "state_sync::before_commit": fails the commit before
+    // anything is written to storage.
+    fail_point!("state_sync::before_commit", |_| {
+        Err(anyhow::anyhow!("Injected error before commit!"))
+    });
+
+    let mut num_failed_attempts: u64 = 0;
+    loop {
+        let commit_result = committer_thread_pool.install(|| {
+            if num_chunks == 1 {
+                chunk_executor.commit_chunk()
+            } else {
+                chunk_executor.commit_chunks(num_chunks)
+            }
+        });
+        let error = match commit_result {
+            Ok(notification) => {
+                // Failpoint "state_sync::after_commit": fails the commit after
+                // the chunks are durable, but before the commit is processed
+                // (e.g., before the committed version and mempool are updated).
+                fail_point!("state_sync::after_commit", |_| {
+                    Err(anyhow::anyhow!("Injected error after commit!"))
+                });
+                return Ok(notification);
+            }
+            Err(error) => error,
+        };
+
+        // Only retry transient errors (and only until the retries are exhausted)
+        num_failed_attempts += 1;
+        if !is_transient_storage_error(&error)
+            || num_failed_attempts > driver_config.max_commit_retries
+        {
+            return Err(error);
+        }
+
+        // Backoff before retrying (the backoff doubles with every failed attempt)
+        let num_doublings = (num_failed_attempts - 1).min(MAX_COMMIT_BACKOFF_DOUBLINGS);
+        let backoff = Duration::from_millis(
+            driver_config
+                .commit_retry_backoff_ms
+                .saturating_mul(1 << num_doublings),
+        );
+        warn!(LogSchema::new(LogEntry::StorageSynchronizer)
+            .error(&Error::UnexpectedError(format!("{:?}", error)))
+            .message(&format!(
+                "Failed to commit the executed chunk(s) due to a transient storage error! \
+                Versions: {:?}, failed attempts: {:?}, retrying in: {:?}",
+                version_range, num_failed_attempts, backoff
+            )));
+        driver_metrics.increment_counter(&metrics::DRIVER_COUNTERS, metrics::COMMIT_RETRY);
+        sleep(backoff).await;
+    }
+}
+
+/// Verifies the concurrency settings of the storage synchronizer, i.e., that
+/// the thread pool sizes and channel depths are non-zero and below sane maxima.
+pub fn verify_concurrency_config(driver_config: &StateSyncDriverConfig) -> Result<(), Error> {
+    let concurrency_settings = [
+        (
+            "num_executor_threads",
+            driver_config.num_executor_threads,
+            MAX_STAGE_THREADS,
+        ),
+        (
+            "num_committer_threads",
+            driver_config.num_committer_threads,
+            MAX_STAGE_THREADS,
+        ),
+        (
+            "max_pending_data_chunks",
+            driver_config.max_pending_data_chunks,
+            MAX_STAGE_CHANNEL_DEPTH,
+        ),
+        (
+            "max_pending_executed_chunks",
+            driver_config.max_pending_executed_chunks,
+            MAX_STAGE_CHANNEL_DEPTH,
+        ),
+    ];
+    for (setting_name, value, max_value) in concurrency_settings {
+        if value == 0 || value > max_value {
+            return Err(Error::UnexpectedError(format!(
+                "Invalid storage synchronizer config! {} must be in the range [1, {}], but got: {}",
+                setting_name, max_value, value
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Creates a dedicated thread pool (of the given size) for a stage of the
+/// storage synchronizer. Any (rayon) parallel work performed while running
+/// the stage is bound to the pool, so the stages can be sized independently.
+fn create_stage_thread_pool(stage_name: &'static str, num_threads: u64) -> Arc<ThreadPool> {
+    let thread_pool = ThreadPoolBuilder::new()
+        .num_threads(num_threads.max(1) as usize)
+        .thread_name(move |index| format!("{}-{}", stage_name, index))
+        .build()
+        .unwrap_or_else(|error| {
+            panic!(
+                "Failed to create the {} thread pool! Error: {:?}",
+                stage_name, error
+            )
+        });
+    Arc::new(thread_pool)
+}
+
+/// Logs the effective concurrency settings of the storage synchronizer and
+/// updates the corresponding gauges.
+fn log_concurrency_config(driver_config: &StateSyncDriverConfig, driver_metrics: &DriverMetrics) {
+    info!(
+        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+            "Starting the storage synchronizer with {:?} executor threads, {:?} committer threads, \
+            max pending data chunks: {:?}, max pending executed chunks: {:?}",
+            driver_config.num_executor_threads,
+            driver_config.num_committer_threads,
+            driver_config.max_pending_data_chunks,
+            driver_config.max_pending_executed_chunks
+        ))
+    );
+    for (label, value) in [
+        (
+            metrics::NUM_EXECUTOR_THREADS,
+            driver_config.num_executor_threads,
+        ),
+        (
+            metrics::NUM_COMMITTER_THREADS,
+            driver_config.num_committer_threads,
+        ),
+        (
+            metrics::MAX_PENDING_DATA_CHUNKS,
+            driver_config.max_pending_data_chunks,
+        ),
+        (
+            metrics::MAX_PENDING_EXECUTED_CHUNKS,
+            driver_config.max_pending_executed_chunks,
+        ),
+    ] {
+        driver_metrics.set_gauge(&metrics::STORAGE_SYNCHRONIZER_CONCURRENCY, label, value);
+    }
+}
+
+/// Returns true iff the storage error is transient (e.g., the database is
+/// busy, or a write stalled or timed out). Errors are classified by their type
+/// (and kind), so any error that isn't known to be transient (e.g., corruption,
+/// running out of space, or an `AptosDbError`) is treated as permanent.
+fn is_transient_storage_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|error| {
+        if let Some(rocksdb_error) = error.downcast_ref::<RocksDbError>() {
+            matches!(
+                rocksdb_error.kind(),
+                RocksDbErrorKind::Busy
+                    | RocksDbErrorKind::Incomplete
+                    | RocksDbErrorKind::TimedOut
+                    | RocksDbErrorKind::TryAgain
+            )
+        } else if let Some(io_error) = error.downcast_ref::<io::Error>() {
+            matches!(
+                io_error.kind(),
+                io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+            )
+        } else {
+            false
+        }
+    })
+}
+
 /// Spawns a dedicated receiver that commits state values from a state snapshot
+#[allow(clippy::too_many_arguments)]
 fn spawn_state_snapshot_receiver<
     ChunkExecutor: ChunkExecutorTrait + 'static,
     MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
 >(
     chunk_executor: Arc<ChunkExecutor>,
     mut state_snapshot_listener: mpsc::Receiver<StorageDataChunk>,
-    mut commit_notification_sender: mpsc::UnboundedSender<CommitNotification>,
-    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
-    pending_transaction_chunks: Arc<AtomicU64>,
+    mut commit_notification_sender: CommitNotificationSender,
+    committed_version_handle: CommittedVersionHandle,
+    driver_config: StateSyncDriverConfig,
+    error_notification_sender: ErrorNotifier,
+    pending_data_tracker: PendingDataTracker,
     metadata_storage: MetadataStorage,
     storage: DbReaderWriter,
     epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
     target_ledger_info: LedgerInfoWithSignatures,
     target_output_with_proof: TransactionOutputListWithProof,
     runtime: Option<Handle>,
+    driver_metrics: DriverMetrics,
 ) -> JoinHandle<()> {
     // Create a state snapshot receiver
     let receiver = async move {
@@ -580,29 +2744,117 @@ fn spawn_state_snapshot_receiver<
             .ensure_state_checkpoint_hash()
             .expect("Must be at state checkpoint.");
 
+        // Pause pruning for the duration of the snapshot sync. The pause is
+        // released at finalization, or if the snapshot is abandoned.
+        let mut pruning_pause = Some(PruningPauseGuard::new(
+            storage.writer.clone(),
+            version,
+            driver_metrics,
+        ));
+        let pruning_pause_idle_timeout =
+            Duration::from_secs(driver_config.max_pruning_pause_idle_secs);
+
         // Create the snapshot receiver
         let mut state_snapshot_receiver = storage
             .writer
             .get_state_snapshot_receiver(version, expected_root_hash)
             .expect("Failed to initialize the state snapshot receiver!");
 
+        // Identify the index of the next state value chunk to commit (chunks
+        // committed before a restart are recorded in the chunk progress).
+        let mut next_chunk_index = match metadata_storage.get_snapshot_chunk_progress() {
+            Ok(Some(snapshot_chunk_progress))
+                if snapshot_chunk_progress.target_ledger_info == target_ledger_info =>
+            {
+                snapshot_chunk_progress.last_committed_chunk_index + 1
+            }
+            Ok(_) => 0,
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::StorageSynchronizer)
+                    .error(&error)
+                    .message("Failed to read the snapshot chunk progress! Starting at chunk 0."));
+                0
+            }
+        };
+
         // Handle state value chunks
         let target_ledger_info = &target_ledger_info;
-        while let Some(storage_data_chunk) = state_snapshot_listener.next().await {
+        loop {
+            // Wait for the next chunk. If none arrives for too long (e.g., the
+            // bootstrapper is stuck), release the pruning pause until it does.
+            let storage_data_chunk =
+                match timeout(pruning_pause_idle_timeout, state_snapshot_listener.next()).await {
+                    Ok(Some(storage_data_chunk)) => storage_data_chunk,
+                    Ok(None) => break, // The snapshot was abandoned
+                    Err(_) => {
+                        if let Some(pruning_pause_guard) = pruning_pause.take() {
+                            warn!(
+                                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                            "No state value chunks were received for {:?}! Resuming pruning.",
+                            pruning_pause_idle_timeout
+                        ))
+                            );
+                            driver_metrics.increment_counter(
+                                &metrics::DRIVER_COUNTERS,
+                                metrics::PRUNING_PAUSE_EXPIRED,
+                            );
+                            drop(pruning_pause_guard);
+                        }
+                        continue;
+                    }
+                };
+            if pruning_pause.is_none() {
+                pruning_pause = Some(PruningPauseGuard::new(
+                    storage.writer.clone(),
+                    version,
+                    driver_metrics,
+                ));
+            }
+
             // Process the chunk
             match storage_data_chunk {
                 StorageDataChunk::States(notification_id, states_with_proof) => {
                     let all_states_synced = states_with_proof.is_last_chunk();
                     let last_committed_state_index = states_with_proof.last_index;
+                    let proven_root_hash = states_with_proof.root_hash;
+                    let num_state_values = states_with_proof.raw_values.len();
+                    let num_bytes = get_state_values_size(&states_with_proof.raw_values);
+                    pending_data_tracker.chunk_dequeued(num_bytes);
+
+                    // Verify the chunk (if the bootstrapper hasn't already)
+                    if driver_config.verify_state_values_in_storage_synchronizer {
+                        if let Err(error) =
+                            verify_state_value_chunk(&states_with_proof, expected_root_hash)
+                        {
+                            send_storage_synchronizer_error(
+                                error_notification_sender.clone(),
+                                notification_id,
+                                ErrorSource::Bootstrapper,
+                                FailedOperation::VerifyStateValues,
+                                Some((version, version)),
+                                format!("{:?}", error),
+                            )
+                            .await;
+                            pending_data_tracker.chunk_processed(PipelineStage::Executing);
+                            continue;
+                        }
+                    }
 
                     // Attempt to commit the chunk
-                    let num_state_values = states_with_proof.raw_values.len();
+                    let apply_start_time = Instant::now();
                     let commit_result = state_snapshot_receiver.add_chunk(
                         states_with_proof.raw_values,
                         states_with_proof.proof.clone(),
                     );
+                    let apply_duration = apply_start_time.elapsed();
+                    let verification_duration = state_snapshot_receiver
+                        .last_chunk_verification_duration()
+                        .unwrap_or_default();
                     match commit_result {
                         Ok(()) => {
+                            // Record the committed chunk (the state values are now durable)
+                            pending_data_tracker.chunks_committed(1);
+
                             // Update the logs and metrics
                             info!(
                                 LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
@@ -611,43 +2863,75 @@ fn spawn_state_snapshot_receiver<
                                     last_committed_state_index
                                 ))
                             );
-                            metrics::set_gauge(
+                            driver_metrics.set_gauge(
                                 &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
                                 metrics::StorageSynchronizerOperations::SyncedStates.get_label(),
                                 last_committed_state_index as u64,
                             );
 
                             if !all_states_synced {
-                                // Update the metadata storage with the last committed state index
+                                // Update the metadata storage with the committed chunk
+                                // (and the last committed state index).
+                                let snapshot_chunk_progress = StateSnapshotChunkProgress {
+                                    target_ledger_info: target_ledger_info.clone(),
+                                    last_committed_chunk_index: next_chunk_index,
+                                    last_persisted_state_value_index: last_committed_state_index,
+                                };
+                                next_chunk_index += 1;
                                 if let Err(error) = metadata_storage
                                     .clone()
-                                    .update_last_persisted_state_value_index(
-                                        target_ledger_info,
-                                        last_committed_state_index,
-                                        all_states_synced,
+                                    .update_snapshot_chunk_progress(snapshot_chunk_progress)
+                                {
+                                    let error = format!("Failed to update the snapshot chunk progress at version: {:?}! Error: {:?}", version, error);
+                                    send_storage_synchronizer_error(
+                                        error_notification_sender.clone(),
+                                        notification_id,
+                                        ErrorSource::Bootstrapper,
+                                        FailedOperation::PersistStateValueIndex,
+                                        Some((version, version)),
+                                        error,
                                     )
+                                    .await;
+                                }
+
+                                // Notify the listener of the committed chunk
+                                let commit_notification =
+                                    CommitNotification::new_committed_state_value_chunk(
+                                        apply_duration,
+                                        last_committed_state_index,
+                                        num_bytes,
+                                        num_state_values as u64,
+                                        proven_root_hash,
+                                        verification_duration,
+                                        version,
+                                    );
+                                if let Err(error) =
+                                    commit_notification_sender.send(commit_notification)
                                 {
-                                    let error = format!("Failed to update the last persisted state index at version: {:?}! Error: {:?}", version, error);
+                                    let error = format!("Failed to send the state value chunk commit notification! Error: {:?}", error);
                                     send_storage_synchronizer_error(
                                         error_notification_sender.clone(),
                                         notification_id,
+                                        ErrorSource::Bootstrapper,
+                                        FailedOperation::NotifyStateValueChunk,
+                                        Some((version, version)),
                                         error,
                                     )
                                     .await;
                                 }
-                                decrement_pending_data_chunks(pending_transaction_chunks.clone());
+                                pending_data_tracker.chunk_processed(PipelineStage::Executing);
                                 continue; // Wait for the next chunk
                             }
 
-                            // All states have been synced! Create a new commit notification
-                            let commit_notification = create_commit_notification(
-                                &target_output_with_proof,
-                                last_committed_state_index,
-                                version,
-                            );
-
-                            // Finalize storage, reset the executor and send a commit
-                            // notification to the listener.
+                            // All states have been synced! Persist a finalization
+                            // marker (so that the finalization can be resumed after
+                            // a crash) and finalize the state snapshot.
+                            let snapshot_finalization = StateSnapshotFinalization {
+                                target_ledger_info: target_ledger_info.clone(),
+                                target_output_with_proof: target_output_with_proof.clone(),
+                                epoch_change_proofs: epoch_change_proofs.clone(),
+                                last_persisted_state_value_index: last_committed_state_index,
+                            };
                             let finalized_result = if let Err(error) =
                                 state_snapshot_receiver.finish_box()
                             {
@@ -655,35 +2939,21 @@ fn spawn_state_snapshot_receiver<
                                     "Failed to finish the state value synchronization! Error: {:?}",
                                     error
                                 ))
-                            } else if let Err(error) = storage.writer.finalize_state_snapshot(
-                                version,
-                                target_output_with_proof,
-                                &epoch_change_proofs,
-                            ) {
-                                Err(format!(
-                                    "Failed to finalize the state snapshot! Error: {:?}",
-                                    error
-                                ))
                             } else if let Err(error) = metadata_storage
                                 .clone()
-                                .update_last_persisted_state_value_index(
-                                    target_ledger_info,
-                                    last_committed_state_index,
-                                    all_states_synced,
-                                )
-                            {
-                                Err(format!("All states have synced, but failed to update the metadata storage at version {:?}! Error: {:?}", version, error))
-                            } else if let Err(error) = chunk_executor.reset() {
-                                Err(format!("Failed to reset the chunk executor after state snapshot synchronization! Error: {:?}", error))
-                            } else if let Err(error) =
-                                commit_notification_sender.send(commit_notification).await
+                                .start_snapshot_finalization(snapshot_finalization.clone())
                             {
-                                Err(format!("Failed to send the final state commit notification! Error: {:?}", error))
-                            } else if let Err(error) = utils::initialize_sync_gauges(storage.reader)
-                            {
-                                Err(format!("Failed to initialize the state sync version gauges! Error: {:?}", error))
+                                Err(format!("All states have synced, but failed to persist the finalization marker at version {:?}! Error: {:?}", version, error))
                             } else {
-                                Ok(())
+                                finalize_state_snapshot(
+                                    chunk_executor.clone(),
+                                    &mut commit_notification_sender,
+                                    &committed_version_handle,
+                                    metadata_storage.clone(),
+                                    storage.clone(),
+                                    snapshot_finalization,
+                                    &driver_metrics,
+                                )
                             };
 
                             // Notify the state sync driver of any errors
@@ -691,11 +2961,17 @@ fn spawn_state_snapshot_receiver<
                                 send_storage_synchronizer_error(
                                     error_notification_sender.clone(),
                                     notification_id,
+                                    ErrorSource::Bootstrapper,
+                                    FailedOperation::FinalizeStateSnapshot,
+                                    Some((version, version)),
                                     error,
                                 )
                                 .await;
                             }
-                            decrement_pending_data_chunks(pending_transaction_chunks.clone());
+
+                            // Resume pruning now that the snapshot sync is complete
+                            drop(pruning_pause.take());
+                            pending_data_tracker.chunk_processed(PipelineStage::Executing);
                             return; // There's nothing left to do!
                         }
                         Err(error) => {
@@ -704,6 +2980,9 @@ fn spawn_state_snapshot_receiver<
                             send_storage_synchronizer_error(
                                 error_notification_sender.clone(),
                                 notification_id,
+                                ErrorSource::Bootstrapper,
+                                FailedOperation::CommitStateValues,
+                                Some((version, version)),
                                 error,
                             )
                             .await;
@@ -717,7 +2996,7 @@ fn spawn_state_snapshot_receiver<
                     );
                 }
             }
-            decrement_pending_data_chunks(pending_transaction_chunks.clone());
+            pending_data_tracker.chunk_processed(PipelineStage::Executing);
         }
     };
 
@@ -725,12 +3004,180 @@ fn spawn_state_snapshot_receiver<
     spawn(runtime, receiver)
 }
 
+/// A guard that pauses storage pruning while a state snapshot is restored
+/// (so that the pruner doesn't move while state values are written at an old
+/// version). Pruning is resumed when the guard is dropped.
+struct PruningPauseGuard {
+    driver_metrics: DriverMetrics,
+    paused: bool,
+    storage: Arc<dyn DbWriter>,
+    version: Version, // The version of the state snapshot being restored
+}
+
+impl PruningPauseGuard {
+    fn new(storage: Arc<dyn DbWriter>, version: Version, driver_metrics: DriverMetrics) -> Self {
+        let paused = match storage.pause_pruning() {
+            Ok(()) => {
+                info!(
+                    LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                        "Paused pruning for the state snapshot sync at version: {:?}",
+                        version
+                    ))
+                );
+                driver_metrics
+                    .increment_counter(&metrics::DRIVER_COUNTERS, metrics::PRUNING_PAUSED);
+                true
+            }
+            Err(error) => {
+                warn!(LogSchema::new(LogEntry::StorageSynchronizer)
+                    .message(&format!(
+                        "Failed to pause pruning for the state snapshot sync at version: {:?}! Error: {:?}",
+                        version, error
+                    )));
+                false
+            }
+        };
+
+        Self {
+            driver_metrics,
+            paused,
+            storage,
+            version,
+        }
+    }
+}
+
+impl Drop for PruningPauseGuard {
+    fn drop(&mut self) {
+        if !self.paused {
+            return; // Pruning was never paused
+        }
+
+        match self.storage.resume_pruning() {
+            Ok(()) => {
+                info!(
+                    LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                        "Resumed pruning for the state snapshot sync at version: {:?}",
+                        self.version
+                    ))
+                );
+                self.driver_metrics
+                    .increment_counter(&metrics::DRIVER_COUNTERS, metrics::PRUNING_RESUMED);
+            }
+            Err(error) => {
+                error!(LogSchema::new(LogEntry::StorageSynchronizer)
+                    .message(&format!(
+                        "Failed to resume pruning for the state snapshot sync at version: {:?}! Error: {:?}",
+                        self.version, error
+                    )));
+            }
+        }
+    }
+}
+
+/// Finalizes the state snapshot recorded by the given finalization marker:
+/// writes the target output and ledger infos to storage, marks the snapshot
+/// sync as complete (removing the marker), resets the executor and notifies
+/// the listener. Each write is skipped if it was already persisted, so the
+/// finalization can be safely re-run after a crash.
+fn finalize_state_snapshot<
+    ChunkExecutor: ChunkExecutorTrait + 'static,
+    MetadataStorage: MetadataStorageInterface + Clone + Send + Sync + 'static,
+>(
+    chunk_executor: Arc<ChunkExecutor>,
+    commit_notification_sender: &mut CommitNotificationSender,
+    committed_version_handle: &CommittedVersionHandle,
+    metadata_storage: MetadataStorage,
+    storage: DbReaderWriter,
+    snapshot_finalization: StateSnapshotFinalization,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), String> {
+    let target_ledger_info = &snapshot_finalization.target_ledger_info;
+    let version = target_ledger_info.ledger_info().version();
+
+    // Failpoint "state_sync::before_snapshot_finalization": fails the
+    // finalization before anything is written (the marker is left in place).
+    fail_point!("state_sync::before_snapshot_finalization", |_| {
+        Err("Injected error before snapshot finalization!".to_string())
+    });
+
+    // Finalize storage (unless the snapshot was already written before a crash)
+    let highest_synced_version = utils::fetch_latest_synced_version(storage.reader.clone())
+        .map_err(|error| {
+            format!(
+                "Failed to fetch the highest synced version! Error: {:?}",
+                error
+            )
+        })?;
+    if highest_synced_version.map_or(true, |highest_synced_version| {
+        highest_synced_version < version
+    }) {
+        storage
+            .writer
+            .finalize_state_snapshot(
+                version,
+                snapshot_finalization.target_output_with_proof.clone(),
+                &snapshot_finalization.epoch_change_proofs,
+            )
+            .map_err(|error| {
+                format!("Failed to finalize the state snapshot! Error: {:?}", error)
+            })?;
+    }
+    committed_version_handle.update(version, target_ledger_info.ledger_info().epoch());
+
+    // Failpoint "state_sync::before_snapshot_completion": fails the
+    // finalization between the storage write and the removal of the marker.
+    fail_point!("state_sync::before_snapshot_completion", |_| {
+        Err("Injected error before snapshot completion!".to_string())
+    });
+
+    // Mark the snapshot sync as complete (this atomically removes the marker)
+    metadata_storage
+        .complete_snapshot_finalization(target_ledger_info)
+        .map_err(|error| {
+            format!(
+                "Failed to complete the snapshot finalization at version {:?}! Error: {:?}",
+                version, error
+            )
+        })?;
+
+    // Reset the executor, notify the listener and update the gauges
+    chunk_executor.reset().map_err(|error| {
+        format!(
+            "Failed to reset the chunk executor after state snapshot synchronization! Error: {:?}",
+            error
+        )
+    })?;
+    let commit_notification = create_commit_notification(
+        &snapshot_finalization.target_output_with_proof,
+        snapshot_finalization.last_persisted_state_value_index,
+        version,
+    );
+    commit_notification_sender
+        .send(commit_notification)
+        .map_err(|error| {
+            format!(
+                "Failed to send the final state commit notification! Error: {:?}",
+                error
+            )
+        })?;
+    utils::initialize_sync_gauges(storage.reader, driver_metrics).map_err(|error| {
+        format!(
+            "Failed to initialize the state sync version gauges! Error: {:?}",
+            error
+        )
+    })
+}
+
 /// Creates a commit notification for the new committed state snapshot
 fn create_commit_notification(
     target_output_with_proof: &TransactionOutputListWithProof,
     last_committed_state_index: u64,
     version: u64,
 ) -> CommitNotification {
+    let first_version = target_output_with_proof
+        .first_transaction_output_version
+        .unwrap_or(version);
     let (transactions, outputs): (Vec<Transaction>, Vec<TransactionOutput>) =
         target_output_with_proof
             .transactions_and_outputs
@@ -744,6 +3191,7 @@ fn create_commit_notification(
     CommitNotification::new_committed_state_snapshot(
         events,
         transactions,
+        first_version,
         last_committed_state_index,
         version,
     )
@@ -762,52 +3210,378 @@ fn spawn(
     }
 }
 
-/// Returns the value currently held by the pending chunk counter
-fn load_pending_data_chunks(pending_data_chunks: Arc<AtomicU64>) -> u64 {
-    pending_data_chunks.load(Ordering::Relaxed)
+/// Returns the (inclusive) version range of a chunk with the given first
+/// version and length. Returns None if the chunk is empty.
+fn get_chunk_version_range(
+    first_version: Option<Version>,
+    num_versions: usize,
+) -> Option<(Version, Version)> {
+    first_version.and_then(|first_version| {
+        (num_versions as u64)
+            .checked_sub(1)
+            .map(|offset| (first_version, first_version.saturating_add(offset)))
+    })
+}
+
+/// Returns true iff a chunk (with the given first version and length) ends
+/// at the target ledger info or carries an end of epoch ledger info. Such
+/// chunks are committed with their ledger info, so they must end a commit batch.
+fn chunk_ends_with_ledger_info(
+    first_version: Option<Version>,
+    num_versions: usize,
+    target_ledger_info: &LedgerInfoWithSignatures,
+    end_of_epoch_ledger_info: Option<&LedgerInfoWithSignatures>,
+) -> bool {
+    end_of_epoch_ledger_info.is_some()
+        || get_highest_chunk_version(first_version, num_versions)
+            == Some(target_ledger_info.ledger_info().version())
+}
+
+/// Applies or executes the given transaction chunk (as instructed). The
+/// execution is abandoned if the given cancellation token is cancelled.
+fn apply_or_execute_chunk<ChunkExecutor: ChunkExecutorTrait>(
+    chunk_executor: &ChunkExecutor,
+    apply_or_execute: ApplyOrExecute,
+    chunk_payload: TransactionChunkPayload,
+    target_ledger_info: &LedgerInfoWithSignatures,
+    end_of_epoch_ledger_info: Option<&LedgerInfoWithSignatures>,
+    cancellation_token: &CancellationToken,
+) -> anyhow::Result<()> {
+    match (apply_or_execute, chunk_payload) {
+        (
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof),
+        ) => chunk_executor.apply_chunk_cancellable(
+            output_list_with_proof,
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+            cancellation_token,
+        ),
+        (ApplyOrExecute::Apply, TransactionChunkPayload::Transactions(_)) => Err(
+            anyhow::format_err!("Unable to apply a transaction chunk without transaction outputs!"),
+        ),
+        (ApplyOrExecute::Execute, chunk_payload) => chunk_executor.execute_chunk_cancellable(
+            chunk_payload.into_transaction_list(),
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+            cancellation_token,
+        ),
+    }
+}
+
+/// Verifies that the events of each transaction output in the chunk match the
+/// event root hash of the corresponding transaction info. This is a defense-in-depth
+/// check (the executor also verifies the events), which catches malformed event
+/// lists before the chunk is applied. Only output chunks that are applied are checked.
+fn verify_output_event_root_hashes(
+    apply_or_execute: ApplyOrExecute,
+    chunk_payload: &TransactionChunkPayload,
+) -> anyhow::Result<()> {
+    match (apply_or_execute, chunk_payload) {
+        (
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof),
+        ) => output_list_with_proof.verify_event_root_hashes(),
+        _ => Ok(()),
+    }
 }
 
-/// Increments the pending data chunks
-fn increment_pending_data_chunks(pending_data_chunks: Arc<AtomicU64>) {
-    let delta = 1;
-    pending_data_chunks.fetch_add(delta, Ordering::Relaxed);
-    metrics::increment_gauge(
-        &metrics::STORAGE_SYNCHRONIZER_GAUGES,
-        metrics::STORAGE_SYNCHRONIZER_PENDING_DATA,
-        delta,
+/// Logs and counts a chunk execution that was cancelled because the pending
+/// data was reset.
+fn log_cancelled_chunk_execution(
+    version_range: Option<(Version, Version)>,
+    execute_duration: Duration,
+    driver_metrics: &DriverMetrics,
+) {
+    info!(
+        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+            "Cancelled the execution of a discarded chunk! Versions: {:?}, time spent: {:?}",
+            version_range, execute_duration
+        ))
+    );
+    driver_metrics.increment_counter(
+        &metrics::DRIVER_COUNTERS,
+        metrics::CANCELLED_CHUNK_EXECUTION,
     );
 }
 
-/// Decrements the pending data chunks
-fn decrement_pending_data_chunks(atomic_u64: Arc<AtomicU64>) {
-    let delta = 1;
-    atomic_u64.fetch_sub(delta, Ordering::Relaxed);
-    metrics::decrement_gauge(
-        &metrics::STORAGE_SYNCHRONIZER_GAUGES,
-        metrics::STORAGE_SYNCHRONIZER_PENDING_DATA,
-        delta,
+/// Logs and counts the given number of discarded storage data chunks
+fn log_discarded_chunks(num_chunks: usize, driver_metrics: &DriverMetrics) {
+    debug!(
+        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+            "Discarded {:?} storage data chunk(s) sent before the pending data was reset!",
+            num_chunks
+        ))
     );
+    for _ in 0..num_chunks {
+        driver_metrics.increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DISCARDED_STORAGE_DATA_CHUNK,
+        );
+    }
+}
+
+/// Verifies the given ledger info against the epoch state
+fn verify_ledger_info(
+    epoch_state: &EpochState,
+    ledger_info_with_signatures: &LedgerInfoWithSignatures,
+) -> Result<(), Error> {
+    epoch_state
+        .verify(ledger_info_with_signatures)
+        .map_err(|error| {
+            Error::VerificationError(format!("Ledger info failed verification: {:?}", error))
+        })
+}
+
+/// Returns the new epoch state if a chunk (with the given first version and
+/// length) ends the epoch, i.e., if it carries an end of epoch ledger info or
+/// ends at a target ledger info that ends the epoch.
+fn get_new_epoch_state(
+    first_version: Option<Version>,
+    num_versions: usize,
+    target_ledger_info: &LedgerInfoWithSignatures,
+    end_of_epoch_ledger_info: Option<&LedgerInfoWithSignatures>,
+) -> Option<EpochState> {
+    let epoch_ending_ledger_info = match end_of_epoch_ledger_info {
+        Some(end_of_epoch_ledger_info) => end_of_epoch_ledger_info,
+        None if get_highest_chunk_version(first_version, num_versions)
+            == Some(target_ledger_info.ledger_info().version()) =>
+        {
+            target_ledger_info
+        }
+        None => return None,
+    };
+    epoch_ending_ledger_info
+        .ledger_info()
+        .next_epoch_state()
+        .cloned()
+}
+
+/// Returns the highest version in a chunk with the given first version and length
+fn get_highest_chunk_version(
+    first_version: Option<Version>,
+    num_versions: usize,
+) -> Option<Version> {
+    get_chunk_version_range(first_version, num_versions).map(|(_, last_version)| last_version)
+}
+
+/// Returns the serialized size of the given chunk data (or zero if serialization fails)
+fn get_serialized_size<T: Serialize>(chunk_data: &T) -> u64 {
+    bcs::serialized_size(chunk_data).unwrap_or(0) as u64
+}
+
+/// Verifies that the state value chunk is consistent with its proof, i.e.,
+/// the proof is against the expected root hash, and the state values are
+/// sorted (by hashed key) and bounded by the proven first and last keys.
+///
+/// Note: the range proof itself can only be verified against the state
+/// values already written, and so is verified by storage on write.
+pub fn verify_state_value_chunk(
+    state_value_chunk_with_proof: &StateValueChunkWithProof,
+    expected_root_hash: HashValue,
+) -> Result<(), Error> {
+    // Verify the chunk root hash matches the expected root hash
+    if state_value_chunk_with_proof.root_hash != expected_root_hash {
+        return Err(Error::VerificationError(format!(
+            "The states chunk with proof root hash: {:?} didn't match the expected hash: {:?}!",
+            state_value_chunk_with_proof.root_hash, expected_root_hash,
+        )));
+    }
+
+    // Verify the state values are strictly sorted by hashed key
+    let hashed_keys: Vec<HashValue> = state_value_chunk_with_proof
+        .raw_values
+        .iter()
+        .map(|(state_key, _)| state_key.hash())
+        .collect();
+    if let Some(index) = hashed_keys
+        .windows(2)
+        .position(|hashed_keys| hashed_keys[0] >= hashed_keys[1])
+    {
+        return Err(Error::VerificationError(format!(
+            "The states chunk isn't sorted by hashed key! Unsorted key index: {:?}",
+            index + 1
+        )));
+    }
+
+    // Verify the first and last keys match the proven keys
+    let first_and_last_keys = hashed_keys.first().zip(hashed_keys.last());
+    if first_and_last_keys
+        != Some((
+            &state_value_chunk_with_proof.first_key,
+            &state_value_chunk_with_proof.last_key,
+        ))
+    {
+        return Err(Error::VerificationError(format!(
+            "The states chunk keys didn't match the proven keys! Chunk keys: {:?}, proven keys: {:?}",
+            first_and_last_keys,
+            (
+                state_value_chunk_with_proof.first_key,
+                state_value_chunk_with_proof.last_key
+            ),
+        )));
+    }
+
+    Ok(())
+}
+
+/// Returns the total size of the given state keys and values
+fn get_state_values_size(raw_values: &[(StateKey, StateValue)]) -> u64 {
+    raw_values
+        .iter()
+        .map(|(state_key, state_value)| state_key.size() + state_value.size())
+        .sum::<usize>() as u64
 }
 
-/// Sends an error notification to the notification listener
+/// The stages of the storage synchronizer pipeline. Each stage runs on its
+/// own task, and stages are connected by bounded channels.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum StorageSynchronizerStage {
+    Committer,             // Commits executed chunks and handles the commit notifications
+    Executor,              // Verifies and executes/applies transaction and output chunks
+    StateSnapshotReceiver, // Verifies and commits state value chunks
+}
+
+impl StorageSynchronizerStage {
+    /// Returns a summary label for the stage
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            StorageSynchronizerStage::Committer => "committer",
+            StorageSynchronizerStage::Executor => "executor",
+            StorageSynchronizerStage::StateSnapshotReceiver => "state_snapshot_receiver",
+        }
+    }
+}
+
+/// The storage synchronizer operations that can fail. Each failed operation
+/// determines the severity of the resulting error notification, and the
+/// pipeline stage that raised it.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum FailedOperation {
+    CommitChunk,            // Committing an executed transaction chunk to storage
+    CommitStateValues,      // Writing a state value chunk to storage (e.g., the proof is invalid)
+    ExecuteChunk, // Executing or applying a transaction chunk (e.g., the proof is invalid)
+    FinalizeStateSnapshot, // Finalizing the state snapshot once all states have been synced
+    NotifyCommitter, // Handing an executed chunk to the committer
+    NotifyStateValueChunk, // Notifying the listener of a committed state value chunk
+    PersistStateValueIndex, // Persisting the index of the last committed state value
+    VerifyChunkContinuity, // Verifying a submitted transaction chunk follows the highest committed (or in-flight) version
+    VerifyChunkSize, // Verifying a submitted transaction chunk doesn't exceed the max chunk size
+    VerifyEventRootHash, // Verifying the output events of a transaction chunk match the event root hashes
+    VerifyLedgerInfo,    // Verifying the ledger info signatures of a transaction chunk
+    VerifyStateValues,   // Verifying a state value chunk against its proof
+}
+
+impl FailedOperation {
+    /// Returns the severity of an error raised by the failed operation.
+    /// Errors are transient if the data was still committed to storage
+    /// (i.e., only the bookkeeping failed). Errors are fatal if storage
+    /// may have been left in an unexpected state. All other errors are
+    /// recoverable, i.e., the data wasn't committed and must be refetched.
+    pub fn get_error_severity(&self) -> ErrorSeverity {
+        match self {
+            FailedOperation::NotifyStateValueChunk | FailedOperation::PersistStateValueIndex => {
+                ErrorSeverity::Transient
+            }
+            FailedOperation::CommitStateValues
+            | FailedOperation::ExecuteChunk
+            | FailedOperation::NotifyCommitter
+            | FailedOperation::VerifyChunkContinuity
+            | FailedOperation::VerifyChunkSize
+            | FailedOperation::VerifyEventRootHash
+            | FailedOperation::VerifyLedgerInfo
+            | FailedOperation::VerifyStateValues => ErrorSeverity::Recoverable,
+            FailedOperation::CommitChunk | FailedOperation::FinalizeStateSnapshot => {
+                ErrorSeverity::Fatal
+            }
+        }
+    }
+
+    /// Returns the pipeline stage that performs the operation
+    pub fn get_stage(&self) -> StorageSynchronizerStage {
+        match self {
+            FailedOperation::CommitChunk => StorageSynchronizerStage::Committer,
+            FailedOperation::ExecuteChunk
+            | FailedOperation::NotifyCommitter
+            | FailedOperation::VerifyChunkContinuity
+            | FailedOperation::VerifyChunkSize
+            | FailedOperation::VerifyEventRootHash
+            | FailedOperation::VerifyLedgerInfo => StorageSynchronizerStage::Executor,
+            FailedOperation::CommitStateValues
+            | FailedOperation::FinalizeStateSnapshot
+            | FailedOperation::NotifyStateValueChunk
+            | FailedOperation::PersistStateValueIndex
+            | FailedOperation::VerifyStateValues => StorageSynchronizerStage::StateSnapshotReceiver,
+        }
+    }
+}
+
+/// Sends an error notification to the notification listener (unless the
+/// chunk has already failed at another stage).
 async fn send_storage_synchronizer_error(
-    mut error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    error_notification_sender: ErrorNotifier,
     notification_id: NotificationId,
+    error_source: ErrorSource,
+    failed_operation: FailedOperation,
+    version_range: Option<(Version, Version)>,
     error_message: String,
 ) {
+    error_notification_sender.notify_error(
+        notification_id,
+        error_source,
+        failed_operation,
+        version_range,
+        None,
+        error_message,
+    );
+}
+
+/// Creates the error for the failed operation. Verification failures indicate
+/// an invalid proof, and event root hash mismatches indicate invalid payload data.
+fn create_failed_operation_error(
+    failed_operation: FailedOperation,
+    error_message: String,
+) -> Error {
+    match failed_operation {
+        FailedOperation::VerifyEventRootHash => Error::InvalidPayload(error_message),
+        FailedOperation::VerifyLedgerInfo | FailedOperation::VerifyStateValues => {
+            Error::VerificationError(error_message)
+        }
+        _ => Error::UnexpectedError(error_message),
+    }
+}
+
+/// Creates (and logs) an error notification for the failed operation
+fn create_error_notification(
+    notification_id: NotificationId,
+    error_source: ErrorSource,
+    failed_operation: FailedOperation,
+    version_range: Option<(Version, Version)>,
+    expected_version: Option<Version>,
+    error_message: String,
+    driver_metrics: &DriverMetrics,
+) -> ErrorNotification {
+    let stage = failed_operation.get_stage();
     let error_message = format!("Storage synchronizer error: {:?}", error_message);
-    error!(LogSchema::new(LogEntry::StorageSynchronizer).message(&error_message));
+    error!(
+        LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+            "{} The {:?} stage failed for versions: {:?}",
+            error_message, stage, version_range
+        ))
+    );
+
+    // Create the error and update the metrics
+    let error = create_failed_operation_error(failed_operation, error_message);
+    driver_metrics.increment_counter(&metrics::STORAGE_SYNCHRONIZER_ERRORS, error.get_label());
 
-    // Send an error notification
-    let error = Error::UnexpectedError(error_message);
-    let error_notification = ErrorNotification {
-        error: error.clone(),
+    ErrorNotification {
+        component: error_source,
+        error,
+        expected_version,
+        failed_operation,
         notification_id,
-    };
-    if let Err(error) = error_notification_sender.send(error_notification).await {
-        panic!("Failed to send error notification! Error: {:?}", error);
+        severity: failed_operation.get_error_severity(),
+        stage,
+        version_range,
     }
-
-    // Update the metrics
-    metrics::increment_counter(&metrics::STORAGE_SYNCHRONIZER_ERRORS, error.get_label());
 }
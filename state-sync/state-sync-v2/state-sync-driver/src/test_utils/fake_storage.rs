@@ -0,0 +1,265 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::test_utils::{create_epoch_ending_ledger_info, create_transaction_info};
+use anyhow::{format_err, Result};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{
+        Transaction, TransactionInfo, TransactionListWithProof, TransactionOutputListWithProof,
+        Version,
+    },
+};
+use executor_types::{ChunkCommitNotification, ChunkExecutorTrait};
+use std::{collections::VecDeque, sync::Arc};
+use storage_interface::{DbReader, DbReaderWriter, DbWriter};
+
+/// An in-memory storage backend (i.e., a database and chunk executor) for
+/// tests that want to exercise the storage synchronizer (or the driver)
+/// without a real database. The fake records every committed chunk and
+/// supports scripted commit failures.
+#[derive(Clone, Default)]
+pub struct FakeStorage {
+    state: Arc<Mutex<FakeStorageState>>,
+}
+
+/// The (shared) state of the fake storage backend
+#[derive(Default)]
+struct FakeStorageState {
+    commit_failures: VecDeque<anyhow::Error>, // The scripted failures for the next commits
+    committed_chunks: Vec<Vec<Transaction>>,  // The chunks committed (in order)
+    executed_chunks: VecDeque<(Vec<Transaction>, LedgerInfoWithSignatures)>, // The chunks pending commit
+    first_available_version: Option<Version>, // The lowest unpruned version (None if there's no pruning metadata)
+    latest_ledger_info: Option<LedgerInfoWithSignatures>, // The latest committed ledger info
+    latest_version: Version,                  // The highest committed version
+}
+
+impl FakeStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a chunk executor that executes and commits into the fake
+    pub fn create_chunk_executor(&self) -> FakeChunkExecutor {
+        FakeChunkExecutor {
+            storage: self.clone(),
+        }
+    }
+
+    /// Returns a reader writer that reads from (and writes to) the fake
+    pub fn create_reader_writer(&self) -> DbReaderWriter {
+        DbReaderWriter {
+            reader: Arc::new(self.clone()),
+            writer: Arc::new(self.clone()),
+        }
+    }
+
+    /// Commits the given transactions (and ledger info) directly to the fake,
+    /// bypassing the chunk executor (e.g., as consensus would).
+    pub fn commit_transactions(
+        &self,
+        transactions: Vec<Transaction>,
+        ledger_info: LedgerInfoWithSignatures,
+    ) {
+        let mut state = self.state.lock();
+        state.latest_version += transactions.len() as u64;
+        state.latest_ledger_info = Some(ledger_info);
+    }
+
+    /// Fails the next commit with the given error. The failed chunks remain
+    /// queued for commit (as with the real chunk executor).
+    pub fn fail_next_commit(&self, error: anyhow::Error) {
+        self.state.lock().commit_failures.push_back(error);
+    }
+
+    /// Returns all chunks committed to the fake (in commit order)
+    pub fn get_committed_chunks(&self) -> Vec<Vec<Transaction>> {
+        self.state.lock().committed_chunks.clone()
+    }
+
+    /// Prunes the fake up to (but excluding) the given version, i.e., the
+    /// version becomes the lowest available version.
+    pub fn prune_to_version(&self, version: Version) {
+        self.state.lock().first_available_version = Some(version);
+    }
+
+    /// Returns the highest committed version
+    pub fn get_latest_version(&self) -> Version {
+        self.state.lock().latest_version
+    }
+
+    /// Queues the given transactions (and target ledger info) for commit
+    fn execute(
+        &self,
+        transactions: Vec<Transaction>,
+        verified_target_li: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        self.state
+            .lock()
+            .executed_chunks
+            .push_back((transactions, verified_target_li.clone()));
+        Ok(())
+    }
+
+    /// Commits the next `num_chunks` executed chunks (unless a commit
+    /// failure has been scripted).
+    fn commit(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        let mut state = self.state.lock();
+        if let Some(error) = state.commit_failures.pop_front() {
+            return Err(error);
+        }
+        if state.executed_chunks.len() < num_chunks {
+            return Err(format_err!(
+                "Not enough executed chunks to commit! Requested: {:?}, executed: {:?}",
+                num_chunks,
+                state.executed_chunks.len()
+            ));
+        }
+
+        let mut committed_transactions = vec![];
+        let executed_chunks: Vec<_> = state.executed_chunks.drain(..num_chunks).collect();
+        for (transactions, target_ledger_info) in executed_chunks {
+            state.latest_version += transactions.len() as u64;
+            state.latest_ledger_info = Some(target_ledger_info);
+            committed_transactions.extend(transactions.iter().cloned());
+            state.committed_chunks.push(transactions);
+        }
+        Ok(ChunkCommitNotification {
+            committed_events: vec![],
+            committed_transactions,
+            reconfiguration_occurred: false,
+        })
+    }
+}
+
+impl DbReader for FakeStorage {
+    fn get_first_txn_version(&self) -> Result<Option<Version>> {
+        Ok(self.state.lock().first_available_version)
+    }
+
+    fn get_latest_epoch_state(&self) -> Result<EpochState> {
+        Ok(EpochState::empty())
+    }
+
+    fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
+        let latest_ledger_info = self
+            .state
+            .lock()
+            .latest_ledger_info
+            .clone()
+            .unwrap_or_else(create_epoch_ending_ledger_info);
+        Ok(Some(latest_ledger_info))
+    }
+
+    fn get_epoch_ending_ledger_info(&self, known_version: u64) -> Result<LedgerInfoWithSignatures> {
+        // The fake only holds the epoch ending ledger info at version 0
+        if known_version == 0 {
+            Ok(create_epoch_ending_ledger_info())
+        } else {
+            Err(format_err!(
+                "Not an epoch change at version {}",
+                known_version
+            ))
+        }
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
+        Ok(Some(self.get_latest_version()))
+    }
+
+    fn get_latest_transaction_info_option(&self) -> Result<Option<(Version, TransactionInfo)>> {
+        Ok(Some((self.get_latest_version(), create_transaction_info())))
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        _state_key: &StateKey,
+        _version: Version,
+    ) -> Result<Option<StateValue>> {
+        Ok(None) // The fake holds no state (e.g., no on-chain configs)
+    }
+}
+
+impl DbWriter for FakeStorage {
+    fn pause_pruning(&self) -> Result<()> {
+        Ok(())
+    }
+
+    fn resume_pruning(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A chunk executor that executes and commits chunks into a fake storage
+/// backend. Proofs are not verified, and transactions are not run.
+pub struct FakeChunkExecutor {
+    storage: FakeStorage,
+}
+
+impl ChunkExecutorTrait for FakeChunkExecutor {
+    fn execute_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        _epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        self.storage
+            .execute(txn_list_with_proof.transactions, verified_target_li)
+    }
+
+    fn apply_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        _epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<()> {
+        let transactions = txn_output_list_with_proof
+            .transactions_and_outputs
+            .into_iter()
+            .map(|(transaction, _)| transaction)
+            .collect();
+        self.storage.execute(transactions, verified_target_li)
+    }
+
+    fn commit_chunk(&self) -> Result<ChunkCommitNotification> {
+        self.storage.commit(1)
+    }
+
+    fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification> {
+        self.storage.commit(num_chunks)
+    }
+
+    fn execute_and_commit_chunk(
+        &self,
+        txn_list_with_proof: TransactionListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<ChunkCommitNotification> {
+        self.execute_chunk(txn_list_with_proof, verified_target_li, epoch_change_li)?;
+        self.commit_chunk()
+    }
+
+    fn apply_and_commit_chunk(
+        &self,
+        txn_output_list_with_proof: TransactionOutputListWithProof,
+        verified_target_li: &LedgerInfoWithSignatures,
+        epoch_change_li: Option<&LedgerInfoWithSignatures>,
+    ) -> Result<ChunkCommitNotification> {
+        self.apply_chunk(
+            txn_output_list_with_proof,
+            verified_target_li,
+            epoch_change_li,
+        )?;
+        self.commit_chunk()
+    }
+
+    fn reset(&self) -> Result<()> {
+        self.storage.state.lock().executed_chunks.clear();
+        Ok(())
+    }
+
+    fn finish(&self) {}
+}
@@ -0,0 +1,141 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Test utilities that are shared by the unit and integration tests of the
+//! driver, and by the benchmarks (i.e., the data generators, the synthetic
+//! chain and the temporary database harness). The benchmarks require the
+//! `testing` feature.
+
+pub mod fake_storage;
+pub mod synthetic_chain;
+pub mod test_harness;
+
+// The parallel ledger info verifier of the storage synchronizer (benchmarked
+// by the ledger_info_verification benchmark).
+pub use crate::storage_synchronizer::LedgerInfoVerifier;
+
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    HashValue, PrivateKey, Uniform,
+};
+use aptos_data_client::GlobalDataSummary;
+use aptos_types::{
+    account_address::AccountAddress,
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    chain_id::ChainId,
+    contract_event::ContractEvent,
+    event::EventKey,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    on_chain_config::ValidatorSet,
+    transaction::{
+        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction, TransactionInfo,
+        TransactionPayload, Version,
+    },
+    waypoint::Waypoint,
+};
+use data_streaming_service::streaming_client::Epoch;
+use futures::StreamExt;
+use mempool_notifications::{new_mempool_notifier_listener_pair, MempoolNotifier};
+use move_deps::move_core_types::language_storage::TypeTag;
+use storage_service_types::responses::CompleteDataRange;
+
+/// Creates a test epoch ending ledger info
+pub fn create_epoch_ending_ledger_info() -> LedgerInfoWithSignatures {
+    let ledger_info = LedgerInfo::genesis(HashValue::zero(), ValidatorSet::empty());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
+
+/// Creates a waypoint for the test epoch ending ledger info
+pub fn create_epoch_ending_waypoint() -> Waypoint {
+    Waypoint::new_epoch_boundary(create_epoch_ending_ledger_info().ledger_info()).unwrap()
+}
+
+/// Creates a single test event
+pub fn create_event(event_key: Option<EventKey>) -> ContractEvent {
+    let event_key = event_key.unwrap_or_else(EventKey::random);
+    ContractEvent::new(event_key, 0, TypeTag::Bool, bcs::to_bytes(&0).unwrap())
+}
+
+/// Creates a global data summary with the highest ended epoch
+pub fn create_global_summary(highest_ended_epoch: Epoch) -> GlobalDataSummary {
+    let mut global_data_summary = GlobalDataSummary::empty();
+    global_data_summary
+        .advertised_data
+        .epoch_ending_ledger_infos = vec![CompleteDataRange::new(0, highest_ended_epoch).unwrap()];
+    global_data_summary
+}
+
+/// Creates a global data summary that advertises the chain up to (and
+/// including) the given ledger info, i.e., the ledger info is the highest
+/// synced ledger info, and all data up to it is available.
+pub fn create_global_summary_at_ledger_info(
+    ledger_info: &LedgerInfoWithSignatures,
+) -> GlobalDataSummary {
+    let highest_ended_epoch = ledger_info.ledger_info().next_block_epoch() - 1;
+    let version_range = CompleteDataRange::new(0, ledger_info.ledger_info().version()).unwrap();
+
+    let mut global_data_summary = create_global_summary(highest_ended_epoch);
+    let advertised_data = &mut global_data_summary.advertised_data;
+    advertised_data.states = vec![version_range];
+    advertised_data.synced_ledger_infos = vec![ledger_info.clone()];
+    advertised_data.transaction_outputs = vec![version_range];
+    advertised_data.transactions = vec![version_range];
+    global_data_summary
+}
+
+/// Creates a new ledger info with signatures at the specified version
+pub fn create_ledger_info_at_version(version: Version) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), version, 0, None);
+    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
+
+/// Creates a mempool notifier (and spawns its listener on the current
+/// runtime). The listener acks every notification immediately, i.e., as if
+/// mempool processed each notification without delay.
+pub fn create_mempool_notifier() -> MempoolNotifier {
+    let (mempool_notifier, mut mempool_listener) = new_mempool_notifier_listener_pair();
+    tokio::spawn(async move {
+        while let Some(mempool_notification) = mempool_listener.next().await {
+            let _ = mempool_listener.ack_commit_notification(mempool_notification);
+        }
+    });
+    mempool_notifier
+}
+
+/// Creates a single test transaction
+pub fn create_transaction() -> Transaction {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let public_key = private_key.public_key();
+
+    let transaction_payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+    let raw_transaction = RawTransaction::new(
+        AccountAddress::random(),
+        0,
+        transaction_payload,
+        0,
+        0,
+        0,
+        ChainId::new(10),
+    );
+    let signed_transaction = SignedTransaction::new(
+        raw_transaction,
+        public_key,
+        Ed25519Signature::dummy_signature(),
+    );
+
+    Transaction::UserTransaction(signed_transaction)
+}
+
+/// Creates a test transaction info
+pub fn create_transaction_info() -> TransactionInfo {
+    TransactionInfo::new(
+        HashValue::random(),
+        HashValue::random(),
+        HashValue::random(),
+        Some(HashValue::random()),
+        0,
+        ExecutionStatus::Success,
+    )
+}
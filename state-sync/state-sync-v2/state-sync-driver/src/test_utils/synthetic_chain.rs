@@ -0,0 +1,1030 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::test_utils::{create_event, create_transaction};
+use accumulator::{HashReader, MerkleAccumulator};
+use anyhow::format_err;
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
+    HashValue,
+};
+use aptos_data_client::{AptosDataClient, GlobalDataSummary, Response};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
+    proof::{accumulator::InMemoryAccumulator, position::Position, TransactionInfoListWithProof},
+    state_store::state_value::StateValueChunkWithProof,
+    transaction::{
+        ExecutionStatus, Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionStatus, Version,
+    },
+    validator_signer::ValidatorSigner,
+    validator_verifier::generate_validator_verifier,
+    waypoint::Waypoint,
+    write_set::WriteSet,
+};
+use async_trait::async_trait;
+use channel::{aptos_channel, message_queues::QueueStyle};
+use data_streaming_service::{
+    data_notification::{DataNotification, DataPayload, NotificationId},
+    data_stream::DataStreamListener,
+    error::Error,
+    streaming_client::{
+        ContinuouslyStreamTransactionOutputsRequest, ContinuouslyStreamTransactionsRequest,
+        DataStreamingClient, Epoch, GetAllEpochEndingLedgerInfosRequest, GetAllStatesRequest,
+        GetAllTransactionOutputsRequest, GetAllTransactionsRequest, NotificationFeedback,
+        StreamRequest,
+    },
+};
+use std::{collections::HashMap, sync::Arc};
+use storage_interface::DbReader;
+
+/// The transaction accumulator of a synthetic chain
+type TransactionAccumulator = MerkleAccumulator<AccumulatorStore, TransactionAccumulatorHasher>;
+
+/// The configuration of a synthetic chain
+#[derive(Clone, Copy, Debug)]
+pub struct SyntheticChainConfig {
+    pub num_epochs: u64, // The number of (ended) epochs after genesis
+    pub num_validators_per_epoch: u64,
+    pub transactions_per_chunk: u64, // The max number of transactions (ledger infos or states) per notification
+    pub transactions_per_epoch: u64, // Only used when generating chains (see `SyntheticChain::new()`)
+}
+
+impl Default for SyntheticChainConfig {
+    fn default() -> Self {
+        Self {
+            num_epochs: 3,
+            num_validators_per_epoch: 4,
+            transactions_per_chunk: 10,
+            transactions_per_epoch: 25,
+        }
+    }
+}
+
+/// A synthetic (multi-epoch) chain for tests. The genesis transaction is at
+/// version 0 (ending epoch 0), and each subsequent epoch holds a fixed number
+/// of transactions, where the last transaction ends the epoch. Every epoch
+/// has a new (deterministically generated) validator set, and every epoch
+/// ending ledger info is signed by the validators of its epoch. The ledger
+/// infos commit to a real transaction accumulator, so the generated chunks
+/// (and their proofs) verify against the ledger infos.
+///
+/// Note: the transactions of generated chains are not executable (i.e., the
+/// transaction outputs have empty write sets), and generated chains have no
+/// state values. Chains loaded from storage don't have these limitations
+/// (see `SyntheticChain::from_storage()`).
+pub struct SyntheticChain {
+    accumulator_store: AccumulatorStore,
+    config: SyntheticChainConfig,
+    epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>, // The ledger infos (indexed by epoch)
+    epoch_states: Vec<EpochState>, // The validator sets (indexed by epoch)
+    latest_ledger_info: LedgerInfoWithSignatures, // The latest ledger info (which might not end an epoch)
+    state_value_reader: Option<Arc<dyn DbReader>>, // The reader of the state values (if any)
+    transaction_infos: Vec<TransactionInfo>,
+    transaction_outputs: Vec<TransactionOutput>,
+    transactions: Vec<Transaction>,
+}
+
+impl SyntheticChain {
+    pub fn new(config: SyntheticChainConfig) -> Self {
+        // Create the validator signers of each epoch (including the epoch
+        // that starts once the last epoch ends).
+        let epoch_signers: Vec<Vec<ValidatorSigner>> = (0..=config.num_epochs + 1)
+            .map(|epoch| create_epoch_signers(epoch, config.num_validators_per_epoch))
+            .collect();
+
+        // Create the transactions, outputs and transaction infos
+        let num_versions = config.num_epochs * config.transactions_per_epoch + 1;
+        let mut transactions = vec![];
+        let mut transaction_outputs = vec![];
+        let mut transaction_infos = vec![];
+        for _ in 0..num_versions {
+            let transaction = create_transaction();
+            let transaction_output = TransactionOutput::new(
+                WriteSet::default(),
+                vec![create_event(None)],
+                0,
+                TransactionStatus::Keep(ExecutionStatus::Success),
+            );
+            transaction_infos.push(create_transaction_info(&transaction, &transaction_output));
+            transactions.push(transaction);
+            transaction_outputs.push(transaction_output);
+        }
+
+        // Build the transaction accumulator
+        let accumulator_store = AccumulatorStore::new(&transaction_infos);
+
+        // Create the epoch ending ledger infos
+        let epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures> = (0..=config.num_epochs)
+            .map(|epoch| {
+                let version = epoch * config.transactions_per_epoch;
+                let next_epoch_state = EpochState {
+                    epoch: epoch + 1,
+                    verifier: generate_validator_verifier(&epoch_signers[epoch as usize + 1]),
+                };
+                let block_info = BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    TransactionAccumulator::get_root_hash(&accumulator_store, version + 1).unwrap(),
+                    version,
+                    version,
+                    Some(next_epoch_state),
+                );
+                generate_ledger_info_with_sig(
+                    &epoch_signers[epoch as usize],
+                    LedgerInfo::new(block_info, HashValue::zero()),
+                )
+            })
+            .collect();
+        let epoch_states = epoch_signers
+            .iter()
+            .enumerate()
+            .map(|(epoch, signers)| EpochState {
+                epoch: epoch as u64,
+                verifier: generate_validator_verifier(signers),
+            })
+            .collect();
+        let latest_ledger_info = epoch_ending_ledger_infos.last().unwrap().clone();
+
+        Self {
+            accumulator_store,
+            config,
+            epoch_ending_ledger_infos,
+            epoch_states,
+            latest_ledger_info,
+            state_value_reader: None,
+            transaction_infos,
+            transaction_outputs,
+            transactions,
+        }
+    }
+
+    /// Loads the chain from the given storage (e.g., a database populated by
+    /// executing real blocks), so that the served data can be executed (and
+    /// committed) by a real storage synchronizer. The latest ledger info of
+    /// the chain might not end an epoch, and the state values are served
+    /// directly from storage (at the versions of persisted state snapshots).
+    pub fn from_storage(db_reader: Arc<dyn DbReader>, transactions_per_chunk: u64) -> Self {
+        // Read the transactions, outputs and transaction infos
+        let latest_ledger_info = db_reader.get_latest_ledger_info().unwrap();
+        let latest_version = latest_ledger_info.ledger_info().version();
+        let outputs_with_proof = db_reader
+            .get_transaction_outputs(0, latest_version + 1, latest_version)
+            .unwrap();
+        let (transactions, transaction_outputs) = outputs_with_proof
+            .transactions_and_outputs
+            .into_iter()
+            .unzip();
+        let transaction_infos = outputs_with_proof.proof.transaction_infos;
+
+        // Rebuild the transaction accumulator (and verify it matches storage)
+        let accumulator_store = AccumulatorStore::new(&transaction_infos);
+        assert_eq!(
+            TransactionAccumulator::get_root_hash(&accumulator_store, latest_version + 1).unwrap(),
+            latest_ledger_info
+                .ledger_info()
+                .transaction_accumulator_hash()
+        );
+
+        // Read the epoch ending ledger infos (and the validator sets they start).
+        // The genesis ledger info isn't signed by any validator set.
+        let epoch_ending_ledger_infos = db_reader
+            .get_epoch_ending_ledger_infos(0, latest_ledger_info.ledger_info().next_block_epoch())
+            .unwrap()
+            .ledger_info_with_sigs;
+        let mut epoch_states = vec![EpochState::empty()];
+        epoch_states.extend(epoch_ending_ledger_infos.iter().map(|ledger_info| {
+            ledger_info
+                .ledger_info()
+                .next_epoch_state()
+                .cloned()
+                .unwrap()
+        }));
+
+        let config = SyntheticChainConfig {
+            num_epochs: epoch_ending_ledger_infos.len() as u64 - 1,
+            num_validators_per_epoch: epoch_states.last().unwrap().verifier.len() as u64,
+            transactions_per_chunk,
+            transactions_per_epoch: 0, // The epochs are defined by storage
+        };
+        Self {
+            accumulator_store,
+            config,
+            epoch_ending_ledger_infos,
+            epoch_states,
+            latest_ledger_info,
+            state_value_reader: Some(db_reader),
+            transaction_infos,
+            transaction_outputs,
+            transactions,
+        }
+    }
+
+    /// Returns the configuration of the chain
+    pub fn get_config(&self) -> SyntheticChainConfig {
+        self.config
+    }
+
+    /// Returns the ledger info that ends the given epoch
+    pub fn get_epoch_ending_ledger_info(&self, epoch: Epoch) -> LedgerInfoWithSignatures {
+        self.epoch_ending_ledger_infos[epoch as usize].clone()
+    }
+
+    /// Returns the ledger infos that end the given (inclusive) range of epochs
+    pub fn get_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Vec<LedgerInfoWithSignatures> {
+        self.epoch_ending_ledger_infos[start_epoch as usize..=end_epoch as usize].to_vec()
+    }
+
+    /// Returns the epoch state (i.e., the validator set) of the given epoch
+    pub fn get_epoch_state(&self, epoch: Epoch) -> EpochState {
+        self.epoch_states[epoch as usize].clone()
+    }
+
+    /// Returns the epoch that contains the given version
+    pub fn get_epoch_at_version(&self, version: Version) -> Epoch {
+        self.epoch_ending_ledger_infos
+            .iter()
+            .position(|ledger_info| ledger_info.ledger_info().version() >= version)
+            .map(|epoch| epoch as Epoch)
+            .unwrap_or_else(|| self.get_latest_epoch() + 1)
+    }
+
+    /// Returns the ledger info that ends the epoch containing the given version
+    pub fn get_epoch_ending_ledger_info_at_version(
+        &self,
+        version: Version,
+    ) -> LedgerInfoWithSignatures {
+        self.get_epoch_ending_ledger_info(self.get_epoch_at_version(version))
+    }
+
+    /// Returns the genesis waypoint of the chain
+    pub fn get_genesis_waypoint(&self) -> Waypoint {
+        self.get_waypoint(0)
+    }
+
+    /// Returns the highest (i.e., last ended) epoch of the chain
+    pub fn get_latest_epoch(&self) -> Epoch {
+        self.epoch_ending_ledger_infos.len() as u64 - 1
+    }
+
+    /// Returns the highest ledger info (and version) of the chain
+    pub fn get_latest_ledger_info(&self) -> LedgerInfoWithSignatures {
+        self.latest_ledger_info.clone()
+    }
+
+    /// Returns the highest version of the chain
+    pub fn get_latest_version(&self) -> Version {
+        self.transactions.len() as u64 - 1
+    }
+
+    /// Returns the ledger info at the given version (if the version ends an
+    /// epoch, or is the latest version of the chain).
+    pub fn get_ledger_info_at_version(&self, version: Version) -> Option<LedgerInfoWithSignatures> {
+        self.epoch_ending_ledger_infos
+            .iter()
+            .chain(std::iter::once(&self.latest_ledger_info))
+            .find(|ledger_info| ledger_info.ledger_info().version() == version)
+            .cloned()
+    }
+
+    /// Returns the ledger info that proves the given version, i.e., the ledger
+    /// info that ends the epoch containing the version (or the latest ledger
+    /// info, if the epoch hasn't ended).
+    pub fn get_proof_ledger_info_at_version(&self, version: Version) -> LedgerInfoWithSignatures {
+        let epoch = self.get_epoch_at_version(version);
+        if epoch > self.get_latest_epoch() {
+            self.get_latest_ledger_info()
+        } else {
+            self.get_epoch_ending_ledger_info(epoch)
+        }
+    }
+
+    /// Returns the state values at the given version (from the given index)
+    /// in chunks, with proofs against the state root at the version. Returns
+    /// None if the chain has no state values.
+    pub fn get_state_value_chunks_with_proof(
+        &self,
+        version: Version,
+        start_index: u64,
+    ) -> Option<Vec<StateValueChunkWithProof>> {
+        let state_value_reader = self.state_value_reader.as_ref()?;
+        let num_state_values = state_value_reader.get_state_leaf_count(version).unwrap() as u64;
+
+        let mut state_value_chunks = vec![];
+        let mut chunk_start_index = start_index;
+        while chunk_start_index < num_state_values {
+            let chunk_size =
+                (num_state_values - chunk_start_index).min(self.config.transactions_per_chunk);
+            state_value_chunks.push(
+                state_value_reader
+                    .get_state_value_chunk_with_proof(
+                        version,
+                        chunk_start_index as usize,
+                        chunk_size as usize,
+                    )
+                    .unwrap(),
+            );
+            chunk_start_index += chunk_size;
+        }
+        Some(state_value_chunks)
+    }
+
+    /// Returns the transaction info at the given version
+    pub fn get_transaction_info(&self, version: Version) -> TransactionInfo {
+        self.transaction_infos[version as usize].clone()
+    }
+
+    /// Returns the transactions at the given (inclusive) version range, with
+    /// a proof against the ledger info at the given proof version.
+    pub fn get_transactions_with_proof(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+        include_events: bool,
+    ) -> TransactionListWithProof {
+        let events = include_events.then(|| {
+            self.transaction_outputs[start_version as usize..=end_version as usize]
+                .iter()
+                .map(|transaction_output| transaction_output.events().to_vec())
+                .collect()
+        });
+        TransactionListWithProof::new(
+            self.transactions[start_version as usize..=end_version as usize].to_vec(),
+            events,
+            Some(start_version),
+            self.get_transaction_info_list_with_proof(start_version, end_version, proof_version),
+        )
+    }
+
+    /// Returns the transaction outputs at the given (inclusive) version range,
+    /// with a proof against the ledger info at the given proof version.
+    pub fn get_transaction_outputs_with_proof(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+    ) -> TransactionOutputListWithProof {
+        let transactions_and_outputs = (start_version..=end_version)
+            .map(|version| {
+                (
+                    self.transactions[version as usize].clone(),
+                    self.transaction_outputs[version as usize].clone(),
+                )
+            })
+            .collect();
+        TransactionOutputListWithProof::new(
+            transactions_and_outputs,
+            Some(start_version),
+            self.get_transaction_info_list_with_proof(start_version, end_version, proof_version),
+        )
+    }
+
+    /// Returns the waypoint for the ledger info that ends the given epoch
+    pub fn get_waypoint(&self, epoch: Epoch) -> Waypoint {
+        Waypoint::new_epoch_boundary(self.get_epoch_ending_ledger_info(epoch).ledger_info())
+            .unwrap()
+    }
+
+    /// Returns a ledger info that ends the given epoch, but is signed by
+    /// validators that aren't in the epoch's validator set.
+    pub fn create_forged_ledger_info(&self, epoch: Epoch) -> LedgerInfoWithSignatures {
+        let ledger_info = self
+            .get_epoch_ending_ledger_info(epoch)
+            .ledger_info()
+            .clone();
+        let forged_signers =
+            create_epoch_signers(u64::MAX - epoch, self.config.num_validators_per_epoch);
+        generate_ledger_info_with_sig(&forged_signers, ledger_info)
+    }
+
+    /// Returns the transaction infos at the given (inclusive) version range,
+    /// with a proof against the ledger info at the given proof version.
+    fn get_transaction_info_list_with_proof(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+    ) -> TransactionInfoListWithProof {
+        assert!(
+            start_version <= end_version && end_version <= proof_version,
+            "Invalid version range! Start: {:?}, end: {:?}, proof: {:?}",
+            start_version,
+            end_version,
+            proof_version
+        );
+        let range_proof = TransactionAccumulator::get_range_proof(
+            &self.accumulator_store,
+            proof_version + 1,
+            Some(start_version),
+            end_version - start_version + 1,
+        )
+        .unwrap();
+        TransactionInfoListWithProof::new(
+            range_proof,
+            self.transaction_infos[start_version as usize..=end_version as usize].to_vec(),
+        )
+    }
+}
+
+/// An in-memory store for the (frozen) nodes of a transaction accumulator
+struct AccumulatorStore {
+    nodes: HashMap<Position, HashValue>,
+}
+
+impl AccumulatorStore {
+    /// Creates a store holding the accumulator of the given transaction infos
+    fn new(transaction_infos: &[TransactionInfo]) -> Self {
+        let transaction_info_hashes: Vec<HashValue> =
+            transaction_infos.iter().map(CryptoHash::hash).collect();
+        let mut accumulator_store = Self {
+            nodes: HashMap::new(),
+        };
+        let (_, frozen_nodes) =
+            TransactionAccumulator::append(&accumulator_store, 0, &transaction_info_hashes)
+                .unwrap();
+        accumulator_store.nodes.extend(frozen_nodes);
+        accumulator_store
+    }
+}
+
+impl HashReader for AccumulatorStore {
+    fn get(&self, position: Position) -> anyhow::Result<HashValue> {
+        self.nodes
+            .get(&position)
+            .cloned()
+            .ok_or_else(|| format_err!("Position {:?} is missing!", position))
+    }
+}
+
+/// A fault to inject into a single notification served by the
+/// `SyntheticStreamingClient`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NotificationFault {
+    CorruptProof, // Corrupt the proof of the payload (i.e., the signatures or accumulator proof)
+    Drop,         // Drop the notification (i.e., never send it along the stream)
+    StaleRange,   // Serve the payload of the previous notification (instead of the next range)
+}
+
+/// A data streaming client for tests that serves data streams from a synthetic
+/// chain. All notifications of a stream are sent when the stream is created.
+/// Notification IDs are monotonically increasing across streams (starting at
+/// 0), and faults can be injected into the notifications with specific IDs.
+/// All stream requests and terminations are recorded.
+///
+/// Note: state value streams are only supported by chains loaded from storage.
+#[derive(Clone)]
+pub struct SyntheticStreamingClient {
+    chain: Arc<SyntheticChain>,
+    state: Arc<Mutex<StreamingClientState>>,
+}
+
+/// The (shared) state of the synthetic streaming client
+#[derive(Default)]
+struct StreamingClientState {
+    notification_faults: HashMap<NotificationId, NotificationFault>,
+    notification_senders: Vec<aptos_channel::Sender<(), DataNotification>>, // Held to keep the streams open
+    next_notification_id: NotificationId,
+    stream_requests: Vec<StreamRequest>,
+    terminated_streams: Vec<(NotificationId, NotificationFeedback)>,
+}
+
+impl SyntheticStreamingClient {
+    pub fn new(chain: Arc<SyntheticChain>) -> Self {
+        Self {
+            chain,
+            state: Arc::new(Mutex::new(StreamingClientState::default())),
+        }
+    }
+
+    /// Returns all stream requests received by the client (in order)
+    pub fn get_stream_requests(&self) -> Vec<StreamRequest> {
+        self.state.lock().stream_requests.clone()
+    }
+
+    /// Returns all stream terminations (and feedback) received by the client
+    pub fn get_terminated_streams(&self) -> Vec<(NotificationId, NotificationFeedback)> {
+        self.state.lock().terminated_streams.clone()
+    }
+
+    /// Injects the given fault into the notification with the specified ID
+    pub fn inject_fault(&self, notification_id: NotificationId, fault: NotificationFault) {
+        self.state
+            .lock()
+            .notification_faults
+            .insert(notification_id, fault);
+    }
+
+    /// Creates a data stream for the given request that serves the given
+    /// payloads (in order). Faults are injected into the notifications.
+    fn create_data_stream(
+        &self,
+        stream_request: StreamRequest,
+        data_payloads: Vec<DataPayload>,
+    ) -> Result<DataStreamListener, Error> {
+        let mut state = self.state.lock();
+        state.stream_requests.push(stream_request);
+
+        // Create the notification channel (large enough to hold all notifications)
+        let (notification_sender, notification_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, data_payloads.len().max(1), None);
+
+        // Send the notifications (and inject any faults)
+        let mut previous_payload: Option<DataPayload> = None;
+        for data_payload in data_payloads {
+            let notification_id = state.next_notification_id;
+            state.next_notification_id += 1;
+            let served_payload = match state.notification_faults.get(&notification_id) {
+                Some(NotificationFault::CorruptProof) => {
+                    corrupt_payload(&self.chain, &data_payload)
+                }
+                Some(NotificationFault::Drop) => {
+                    previous_payload = Some(data_payload);
+                    continue;
+                }
+                Some(NotificationFault::StaleRange) => previous_payload
+                    .clone()
+                    .unwrap_or_else(|| data_payload.clone()),
+                None => data_payload.clone(),
+            };
+            notification_sender
+                .push(
+                    (),
+                    DataNotification {
+                        notification_id,
+                        data_payload: served_payload,
+                    },
+                )
+                .unwrap();
+            previous_payload = Some(data_payload);
+        }
+        state.notification_senders.push(notification_sender);
+
+        Ok(DataStreamListener::new(notification_receiver))
+    }
+
+    /// Returns the payloads for a continuous stream of transactions (or
+    /// outputs) that starts after the known version. Each payload is proven
+    /// by the ledger info that ends its epoch, i.e., the latest ledger info
+    /// if the epoch hasn't ended (or by the target, if the payload ends at
+    /// the target).
+    fn create_continuous_payloads(
+        &self,
+        known_version: Version,
+        target: Option<LedgerInfoWithSignatures>,
+        create_payload: impl Fn(Version, Version, LedgerInfoWithSignatures) -> DataPayload,
+    ) -> Vec<DataPayload> {
+        let target_version = target
+            .as_ref()
+            .map(|target| target.ledger_info().version())
+            .unwrap_or_else(|| self.chain.get_latest_version());
+
+        let mut data_payloads = vec![];
+        let mut start_version = known_version + 1;
+        while start_version <= target_version {
+            // Payloads never cross epoch boundaries (or the target)
+            let epoch_proof_ledger_info =
+                self.chain.get_proof_ledger_info_at_version(start_version);
+            let epoch_proof_version = epoch_proof_ledger_info.ledger_info().version();
+            let end_version = (start_version + self.chain.get_config().transactions_per_chunk - 1)
+                .min(epoch_proof_version)
+                .min(target_version);
+            let proof_ledger_info = match &target {
+                Some(target) if end_version == target_version => target.clone(),
+                _ => epoch_proof_ledger_info,
+            };
+            data_payloads.push(create_payload(
+                start_version,
+                end_version,
+                proof_ledger_info,
+            ));
+            start_version = end_version + 1;
+        }
+        data_payloads
+    }
+
+    /// Returns the payloads for a stream of transactions (or outputs) at the
+    /// given (inclusive) version range, followed by the end of the stream.
+    fn create_version_range_payloads(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        create_payload: impl Fn(Version, Version) -> DataPayload,
+    ) -> Vec<DataPayload> {
+        let transactions_per_chunk = self.chain.get_config().transactions_per_chunk;
+        let mut data_payloads = vec![];
+        let mut chunk_start_version = start_version;
+        while chunk_start_version <= end_version {
+            let chunk_end_version =
+                (chunk_start_version + transactions_per_chunk - 1).min(end_version);
+            data_payloads.push(create_payload(chunk_start_version, chunk_end_version));
+            chunk_start_version = chunk_end_version + 1;
+        }
+        data_payloads.push(DataPayload::EndOfStream);
+        data_payloads
+    }
+
+    /// Verifies the requested version range can be served (and proven)
+    fn verify_version_range(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+    ) -> Result<(), Error> {
+        if start_version > end_version
+            || end_version > proof_version
+            || self
+                .chain
+                .get_ledger_info_at_version(proof_version)
+                .is_none()
+        {
+            return Err(Error::DataIsUnavailable(format!(
+                "The synthetic chain can't serve the range! Start: {:?}, end: {:?}, proof: {:?}",
+                start_version, end_version, proof_version
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DataStreamingClient for SyntheticStreamingClient {
+    async fn get_all_state_values(
+        &self,
+        version: Version,
+        start_index: Option<u64>,
+    ) -> Result<DataStreamListener, Error> {
+        let start_index = start_index.unwrap_or(0);
+        let state_value_chunks = self
+            .chain
+            .get_state_value_chunks_with_proof(version, start_index)
+            .ok_or_else(|| {
+                Error::UnsupportedRequestEncountered(
+                    "The synthetic chain has no state values!".into(),
+                )
+            })?;
+
+        // Serve the state values in chunks, followed by the end of the stream
+        let mut data_payloads: Vec<DataPayload> = state_value_chunks
+            .into_iter()
+            .map(DataPayload::StateValuesWithProof)
+            .collect();
+        data_payloads.push(DataPayload::EndOfStream);
+
+        let stream_request = StreamRequest::GetAllStates(GetAllStatesRequest {
+            version,
+            start_index,
+        });
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn get_all_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: Epoch,
+    ) -> Result<DataStreamListener, Error> {
+        let latest_epoch = self.chain.get_latest_epoch();
+        if start_epoch > latest_epoch {
+            return Err(Error::DataIsUnavailable(format!(
+                "The start epoch is too high! Start epoch: {:?}, latest epoch: {:?}",
+                start_epoch, latest_epoch
+            )));
+        }
+
+        // Serve the ledger infos in chunks, followed by the end of the stream
+        let mut data_payloads: Vec<DataPayload> = self
+            .chain
+            .get_epoch_ending_ledger_infos(start_epoch, latest_epoch)
+            .chunks(self.chain.get_config().transactions_per_chunk as usize)
+            .map(|ledger_infos| DataPayload::EpochEndingLedgerInfos(ledger_infos.to_vec()))
+            .collect();
+        data_payloads.push(DataPayload::EndOfStream);
+
+        let stream_request =
+            StreamRequest::GetAllEpochEndingLedgerInfos(GetAllEpochEndingLedgerInfosRequest {
+                start_epoch,
+            });
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn get_all_transaction_outputs(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+    ) -> Result<DataStreamListener, Error> {
+        self.verify_version_range(start_version, end_version, proof_version)?;
+        let data_payloads = self.create_version_range_payloads(
+            start_version,
+            end_version,
+            |chunk_start_version, chunk_end_version| {
+                DataPayload::TransactionOutputsWithProof(
+                    self.chain.get_transaction_outputs_with_proof(
+                        chunk_start_version,
+                        chunk_end_version,
+                        proof_version,
+                    ),
+                )
+            },
+        );
+
+        let stream_request =
+            StreamRequest::GetAllTransactionOutputs(GetAllTransactionOutputsRequest {
+                start_version,
+                end_version,
+                proof_version,
+            });
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn get_all_transactions(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+        include_events: bool,
+    ) -> Result<DataStreamListener, Error> {
+        self.verify_version_range(start_version, end_version, proof_version)?;
+        let data_payloads = self.create_version_range_payloads(
+            start_version,
+            end_version,
+            |chunk_start_version, chunk_end_version| {
+                DataPayload::TransactionsWithProof(self.chain.get_transactions_with_proof(
+                    chunk_start_version,
+                    chunk_end_version,
+                    proof_version,
+                    include_events,
+                ))
+            },
+        );
+
+        let stream_request = StreamRequest::GetAllTransactions(GetAllTransactionsRequest {
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+        });
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn continuously_stream_transaction_outputs(
+        &self,
+        known_version: u64,
+        known_epoch: u64,
+        target: Option<LedgerInfoWithSignatures>,
+    ) -> Result<DataStreamListener, Error> {
+        let data_payloads = self.create_continuous_payloads(
+            known_version,
+            target.clone(),
+            |start_version, end_version, ledger_info| {
+                let proof_version = ledger_info.ledger_info().version();
+                DataPayload::ContinuousTransactionOutputsWithProof(
+                    ledger_info,
+                    self.chain.get_transaction_outputs_with_proof(
+                        start_version,
+                        end_version,
+                        proof_version,
+                    ),
+                )
+            },
+        );
+
+        let stream_request = StreamRequest::ContinuouslyStreamTransactionOutputs(
+            ContinuouslyStreamTransactionOutputsRequest {
+                known_version,
+                known_epoch,
+                target,
+            },
+        );
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn continuously_stream_transactions(
+        &self,
+        start_version: Version,
+        start_epoch: Epoch,
+        include_events: bool,
+        target: Option<LedgerInfoWithSignatures>,
+    ) -> Result<DataStreamListener, Error> {
+        let data_payloads = self.create_continuous_payloads(
+            start_version,
+            target.clone(),
+            |start_version, end_version, ledger_info| {
+                let proof_version = ledger_info.ledger_info().version();
+                DataPayload::ContinuousTransactionsWithProof(
+                    ledger_info,
+                    self.chain.get_transactions_with_proof(
+                        start_version,
+                        end_version,
+                        proof_version,
+                        include_events,
+                    ),
+                )
+            },
+        );
+
+        let stream_request =
+            StreamRequest::ContinuouslyStreamTransactions(ContinuouslyStreamTransactionsRequest {
+                known_version: start_version,
+                known_epoch: start_epoch,
+                include_events,
+                target,
+            });
+        self.create_data_stream(stream_request, data_payloads)
+    }
+
+    async fn terminate_stream_with_feedback(
+        &self,
+        notification_id: NotificationId,
+        notification_feedback: NotificationFeedback,
+    ) -> Result<(), Error> {
+        self.state
+            .lock()
+            .terminated_streams
+            .push((notification_id, notification_feedback));
+        Ok(())
+    }
+}
+
+/// A data client for tests that advertises a (shared) global data summary.
+/// The data itself is served by the `SyntheticStreamingClient`, so all data
+/// requests are rejected (the driver never sends them directly).
+#[derive(Clone)]
+pub struct SyntheticDataClient {
+    global_data_summary: Arc<Mutex<GlobalDataSummary>>,
+}
+
+impl SyntheticDataClient {
+    pub fn new(global_data_summary: Arc<Mutex<GlobalDataSummary>>) -> Self {
+        Self {
+            global_data_summary,
+        }
+    }
+}
+
+#[async_trait]
+impl AptosDataClient for SyntheticDataClient {
+    fn get_global_data_summary(&self) -> GlobalDataSummary {
+        self.global_data_summary.lock().clone()
+    }
+
+    async fn get_epoch_ending_ledger_infos(
+        &self,
+        _start_epoch: Epoch,
+        _expected_end_epoch: Epoch,
+    ) -> aptos_data_client::Result<Response<Vec<LedgerInfoWithSignatures>>> {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_new_transaction_outputs_with_proof(
+        &self,
+        _known_version: Version,
+        _known_epoch: Epoch,
+    ) -> aptos_data_client::Result<
+        Response<(TransactionOutputListWithProof, LedgerInfoWithSignatures)>,
+    > {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_new_transactions_with_proof(
+        &self,
+        _known_version: Version,
+        _known_epoch: Epoch,
+        _include_events: bool,
+    ) -> aptos_data_client::Result<Response<(TransactionListWithProof, LedgerInfoWithSignatures)>>
+    {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_number_of_states(
+        &self,
+        _version: Version,
+    ) -> aptos_data_client::Result<Response<u64>> {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_state_values_with_proof(
+        &self,
+        _version: u64,
+        _start_index: u64,
+        _end_index: u64,
+    ) -> aptos_data_client::Result<Response<StateValueChunkWithProof>> {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_transaction_outputs_with_proof(
+        &self,
+        _proof_version: Version,
+        _start_version: Version,
+        _end_version: Version,
+    ) -> aptos_data_client::Result<Response<TransactionOutputListWithProof>> {
+        Err(unsupported_data_request())
+    }
+
+    async fn get_transactions_with_proof(
+        &self,
+        _proof_version: Version,
+        _start_version: Version,
+        _end_version: Version,
+        _include_events: bool,
+    ) -> aptos_data_client::Result<Response<TransactionListWithProof>> {
+        Err(unsupported_data_request())
+    }
+}
+
+/// Returns the error for data requests sent to the `SyntheticDataClient`
+fn unsupported_data_request() -> aptos_data_client::Error {
+    aptos_data_client::Error::DataIsUnavailable(
+        "The synthetic data client only serves the global data summary!".into(),
+    )
+}
+
+/// Returns a copy of the given payload with a corrupted proof, i.e., the
+/// ledger infos are signed by the wrong validators, or the first transaction
+/// info doesn't match the accumulator proof.
+fn corrupt_payload(chain: &SyntheticChain, data_payload: &DataPayload) -> DataPayload {
+    match data_payload.clone() {
+        DataPayload::ContinuousTransactionOutputsWithProof(ledger_info, mut outputs_with_proof) => {
+            corrupt_transaction_infos(&mut outputs_with_proof.proof);
+            DataPayload::ContinuousTransactionOutputsWithProof(ledger_info, outputs_with_proof)
+        }
+        DataPayload::ContinuousTransactionsWithProof(ledger_info, mut transactions_with_proof) => {
+            corrupt_transaction_infos(&mut transactions_with_proof.proof);
+            DataPayload::ContinuousTransactionsWithProof(ledger_info, transactions_with_proof)
+        }
+        DataPayload::EpochEndingLedgerInfos(mut ledger_infos) => {
+            if let Some(ledger_info) = ledger_infos.first_mut() {
+                *ledger_info = chain.create_forged_ledger_info(ledger_info.ledger_info().epoch());
+            }
+            DataPayload::EpochEndingLedgerInfos(ledger_infos)
+        }
+        DataPayload::TransactionOutputsWithProof(mut outputs_with_proof) => {
+            corrupt_transaction_infos(&mut outputs_with_proof.proof);
+            DataPayload::TransactionOutputsWithProof(outputs_with_proof)
+        }
+        DataPayload::TransactionsWithProof(mut transactions_with_proof) => {
+            corrupt_transaction_infos(&mut transactions_with_proof.proof);
+            DataPayload::TransactionsWithProof(transactions_with_proof)
+        }
+        data_payload => data_payload, // There's no proof to corrupt
+    }
+}
+
+/// Corrupts the first transaction info in the list (the gas used is
+/// modified, so the info no longer matches the accumulator proof).
+fn corrupt_transaction_infos(transaction_info_list_with_proof: &mut TransactionInfoListWithProof) {
+    if let Some(transaction_info) = transaction_info_list_with_proof
+        .transaction_infos
+        .first_mut()
+    {
+        *transaction_info = TransactionInfo::new(
+            transaction_info.transaction_hash(),
+            transaction_info.state_change_hash(),
+            transaction_info.event_root_hash(),
+            transaction_info.state_checkpoint_hash(),
+            transaction_info.gas_used() + 1,
+            transaction_info.status().clone(),
+        );
+    }
+}
+
+/// Creates the (deterministic) validator signers for the given epoch
+fn create_epoch_signers(epoch: Epoch, num_validators: u64) -> Vec<ValidatorSigner> {
+    (0..num_validators)
+        .map(|validator_index| {
+            let mut seed = [0u8; 32];
+            seed[..8].copy_from_slice(&epoch.to_le_bytes());
+            seed[8..16].copy_from_slice(&validator_index.to_le_bytes());
+            ValidatorSigner::random(seed)
+        })
+        .collect()
+}
+
+/// Creates a transaction info for the given transaction and output
+fn create_transaction_info(
+    transaction: &Transaction,
+    transaction_output: &TransactionOutput,
+) -> TransactionInfo {
+    let event_hashes: Vec<HashValue> = transaction_output
+        .events()
+        .iter()
+        .map(CryptoHash::hash)
+        .collect();
+    let event_root_hash =
+        InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes).root_hash();
+    TransactionInfo::new(
+        transaction.hash(),
+        CryptoHash::hash(transaction_output.write_set()),
+        event_root_hash,
+        Some(HashValue::zero()),
+        transaction_output.gas_used(),
+        ExecutionStatus::Success,
+    )
+}
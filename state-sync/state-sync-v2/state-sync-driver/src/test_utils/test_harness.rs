@@ -0,0 +1,330 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    driver_client::DriverClient,
+    driver_factory::{DriverFactory, StateSyncRuntimeHandle},
+    metadata_storage::PersistentMetadataStorage,
+    metrics::DriverMetrics,
+    test_utils::{
+        create_global_summary_at_ledger_info,
+        synthetic_chain::{SyntheticChain, SyntheticDataClient, SyntheticStreamingClient},
+    },
+};
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode, NodeConfig, RoleType};
+use aptos_data_client::GlobalDataSummary;
+use aptos_infallible::{Mutex, RwLock};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures, on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    transaction::Version, waypoint::Waypoint,
+};
+use aptos_vm::AptosVM;
+use aptosdb::AptosDB;
+use consensus_notifications::{new_consensus_notifier_listener_pair, ConsensusNotifier};
+use data_streaming_service::streaming_client::StreamRequest;
+use event_notifications::EventSubscriptionService;
+use executor::chunk_executor::ChunkExecutor;
+use executor_test_helpers::{
+    bootstrap_genesis, integration_test_impl::test_execution_with_storage_impl,
+};
+use mempool_notifications::MempoolNotifier;
+use std::{sync::Arc, time::Duration};
+use storage_interface::DbReaderWriter;
+use tokio::time::timeout;
+
+// The max number of transactions (or states) served in each notification
+// (when the harness creates the source chain).
+const CHUNK_SIZE: u64 = 5;
+
+// The timeout (of real time) for the driver to sync the source chain
+const SYNC_TIMEOUT_SECS: u64 = 60;
+
+/// An integration test harness that runs the real driver (and storage
+/// synchronizer) of a fullnode against a temporary AptosDB. The node syncs
+/// the chain of a source database (i.e., several epochs of executed blocks,
+/// see `test_execution_with_storage_impl()`), which is served by a synthetic
+/// streaming client and advertised by a mock data client. Once a scenario
+/// completes, the state of the database can be verified against the chain.
+/// The driver can also be restarted (e.g., to simulate a node crash), in which
+/// case the new driver syncs from the database left behind by the old one.
+pub struct TestHarness {
+    chain: Arc<SyntheticChain>,
+    _consensus_notifier: ConsensusNotifier,
+    _db_path: TempPath,
+    driver_handle: Option<StateSyncRuntimeHandle>,
+    global_data_summary: Arc<Mutex<GlobalDataSummary>>,
+    metadata_storage: PersistentMetadataStorage,
+    _metadata_path: TempPath,
+    node_config: NodeConfig,
+    storage: DbReaderWriter,
+    streaming_client: SyntheticStreamingClient,
+    waypoint: Waypoint,
+}
+
+impl TestHarness {
+    /// Creates a harness for a fullnode with the given syncing modes and
+    /// spawns the driver (on the current runtime). The database of the node
+    /// only holds the genesis of the source chain, and peers advertise
+    /// nothing until a ledger info is advertised (see `advertise_ledger_info()`).
+    pub fn new(
+        bootstrapping_mode: BootstrappingMode,
+        continuous_syncing_mode: ContinuousSyncingMode,
+    ) -> Self {
+        let source_db = test_execution_with_storage_impl();
+        let chain = Arc::new(SyntheticChain::from_storage(source_db, CHUNK_SIZE));
+        Self::new_with_chain(chain, bootstrapping_mode, continuous_syncing_mode)
+    }
+
+    /// Creates a harness (see `new()`) for a fullnode that syncs the given
+    /// chain. The chain must be loaded from storage (i.e., the data must be
+    /// executable), e.g., to reuse a source database across harnesses.
+    pub fn new_with_chain(
+        chain: Arc<SyntheticChain>,
+        bootstrapping_mode: BootstrappingMode,
+        continuous_syncing_mode: ContinuousSyncingMode,
+    ) -> Self {
+        // Create the database of the node and commit the genesis of the chain
+        let db_path = TempPath::new();
+        db_path.create_as_dir().unwrap();
+        let (_, storage) = DbReaderWriter::wrap(AptosDB::new_for_test(&db_path));
+        let genesis_transaction = chain
+            .get_transactions_with_proof(0, 0, 0, false)
+            .transactions[0]
+            .clone();
+        let waypoint = bootstrap_genesis::<AptosVM>(&storage, &genesis_transaction).unwrap();
+        assert_eq!(waypoint, chain.get_genesis_waypoint());
+
+        // Create the node config
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = RoleType::FullNode;
+        let driver_config = &mut node_config.state_sync.state_sync_driver;
+        driver_config.bootstrapping_mode = bootstrapping_mode;
+        driver_config.continuous_syncing_mode = continuous_syncing_mode;
+
+        // Create the shared global data summary (served by the data client),
+        // the streaming client (which serves the chain) and the metadata storage.
+        let global_data_summary = Arc::new(Mutex::new(GlobalDataSummary::empty()));
+        let streaming_client = SyntheticStreamingClient::new(chain.clone());
+        let metadata_path = TempPath::new();
+        metadata_path.create_as_dir().unwrap();
+        let metadata_storage = PersistentMetadataStorage::new(metadata_path.path());
+
+        // Create and spawn the driver
+        let (consensus_notifier, driver_handle) = spawn_driver(
+            &node_config,
+            waypoint,
+            storage.clone(),
+            metadata_storage.clone(),
+            global_data_summary.clone(),
+            streaming_client.clone(),
+        );
+
+        Self {
+            chain,
+            _consensus_notifier: consensus_notifier,
+            _db_path: db_path,
+            driver_handle: Some(driver_handle),
+            global_data_summary,
+            metadata_storage,
+            _metadata_path: metadata_path,
+            node_config,
+            storage,
+            streaming_client,
+            waypoint,
+        }
+    }
+
+    /// Advertises the chain up to (and including) the given ledger info,
+    /// i.e., peers advertise the ledger info as their highest synced ledger
+    /// info, and all data up to it.
+    pub fn advertise_ledger_info(&self, ledger_info: &LedgerInfoWithSignatures) {
+        *self.global_data_summary.lock() = create_global_summary_at_ledger_info(ledger_info);
+    }
+
+    /// Returns the client of the running driver
+    pub fn driver_client(&self) -> &DriverClient {
+        self.driver_handle
+            .as_ref()
+            .expect("The driver should be running!")
+            .driver_client()
+    }
+
+    /// Returns the metrics handle of the running driver
+    pub fn driver_metrics(&self) -> DriverMetrics {
+        self.driver_handle
+            .as_ref()
+            .expect("The driver should be running!")
+            .get_driver_metrics()
+    }
+
+    /// Returns the chain synced by the node
+    pub fn get_chain(&self) -> Arc<SyntheticChain> {
+        self.chain.clone()
+    }
+
+    /// Returns the version of the latest transaction in the database
+    pub fn get_latest_version(&self) -> Version {
+        self.storage.reader.get_latest_version().unwrap()
+    }
+
+    /// Returns all data streams requested by the driver (in order)
+    pub fn get_stream_requests(&self) -> Vec<StreamRequest> {
+        self.streaming_client.get_stream_requests()
+    }
+
+    /// Restarts the driver without a graceful shutdown (i.e., as if the node
+    /// crashed). The tasks of the running driver are aborted, and a new
+    /// driver (with a new executor) is spawned over the same database and
+    /// metadata storage. Data that was never committed is lost.
+    pub fn restart_driver(&mut self) {
+        // Drop the running driver (this aborts all driver tasks)
+        self.driver_handle.take();
+
+        // Spawn a new driver
+        let (consensus_notifier, driver_handle) = spawn_driver(
+            &self.node_config,
+            self.waypoint,
+            self.storage.clone(),
+            self.metadata_storage.clone(),
+            self.global_data_summary.clone(),
+            self.streaming_client.clone(),
+        );
+        self._consensus_notifier = consensus_notifier;
+        self.driver_handle = Some(driver_handle);
+    }
+
+    /// Verifies the database holds the epoch ending ledger infos of the chain
+    /// (up to the latest synced epoch), and the validator set of the latest epoch.
+    pub fn verify_epoch_ending_ledger_infos(&self) {
+        let latest_ledger_info = self.storage.reader.get_latest_ledger_info().unwrap();
+        let next_epoch = latest_ledger_info.ledger_info().next_block_epoch();
+        let epoch_ending_ledger_infos = self
+            .storage
+            .reader
+            .get_epoch_ending_ledger_infos(0, next_epoch)
+            .unwrap()
+            .ledger_info_with_sigs;
+        assert_eq!(
+            epoch_ending_ledger_infos,
+            self.chain.get_epoch_ending_ledger_infos(0, next_epoch - 1)
+        );
+        assert_eq!(
+            self.storage.reader.get_latest_epoch_state().unwrap(),
+            self.chain.get_epoch_state(next_epoch)
+        );
+    }
+
+    /// Verifies the database holds a (persisted) state snapshot at the given
+    /// version, with the state root of the chain at the version.
+    pub fn verify_state_snapshot(&self, version: Version) {
+        let state_root = self
+            .chain
+            .get_transaction_info(version)
+            .state_checkpoint_hash()
+            .unwrap();
+        assert_eq!(
+            self.storage
+                .reader
+                .get_state_snapshot_before(version + 1)
+                .unwrap(),
+            Some((version, state_root))
+        );
+    }
+
+    /// Verifies the database is synced to the given ledger info of the chain,
+    /// i.e., the latest ledger info and the transaction accumulator match.
+    pub fn verify_synced_ledger_info(&self, ledger_info: &LedgerInfoWithSignatures) {
+        assert_eq!(
+            &self.storage.reader.get_latest_ledger_info().unwrap(),
+            ledger_info
+        );
+        assert_eq!(
+            self.storage
+                .reader
+                .get_accumulator_root_hash(ledger_info.ledger_info().version())
+                .unwrap(),
+            ledger_info.ledger_info().transaction_accumulator_hash()
+        );
+    }
+
+    /// Verifies the transaction infos in the database (i.e., the hashes of
+    /// the transactions, state changes and events, and the state roots)
+    /// match the chain at the given (inclusive) version range.
+    pub fn verify_transaction_infos(&self, start_version: Version, end_version: Version) {
+        let latest_version = self.storage.reader.get_latest_version().unwrap();
+        let outputs_with_proof = self
+            .storage
+            .reader
+            .get_transaction_outputs(
+                start_version,
+                end_version - start_version + 1,
+                latest_version,
+            )
+            .unwrap();
+        let expected_transaction_infos: Vec<_> = (start_version..=end_version)
+            .map(|version| self.chain.get_transaction_info(version))
+            .collect();
+        assert_eq!(
+            outputs_with_proof.proof.transaction_infos,
+            expected_transaction_infos
+        );
+    }
+
+    /// Waits until the node has bootstrapped
+    pub async fn wait_for_bootstrapping(&self) {
+        timeout(
+            Duration::from_secs(SYNC_TIMEOUT_SECS),
+            self.driver_client().notify_once_bootstrapped(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    }
+
+    /// Waits until the node has synced (i.e., committed) the given version
+    pub async fn wait_for_version(&self, version: Version) {
+        self.driver_client()
+            .wait_for_version(version, Duration::from_secs(SYNC_TIMEOUT_SECS))
+            .await
+            .unwrap();
+    }
+}
+
+/// Creates and spawns a fullnode driver (on the current runtime) over the given
+/// storage, and returns the driver handle (and the notifier of the consensus
+/// listener given to the driver).
+fn spawn_driver(
+    node_config: &NodeConfig,
+    waypoint: Waypoint,
+    storage: DbReaderWriter,
+    metadata_storage: PersistentMetadataStorage,
+    global_data_summary: Arc<Mutex<GlobalDataSummary>>,
+    streaming_client: SyntheticStreamingClient,
+) -> (ConsensusNotifier, StateSyncRuntimeHandle) {
+    // Create the components of the driver
+    let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(storage.clone()));
+    let (consensus_notifier, consensus_listener) = new_consensus_notifier_listener_pair(5000);
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(storage.clone())),
+    );
+
+    // Create and spawn the driver
+    let driver_factory = DriverFactory::create_and_spawn_driver_with_clients(
+        false,
+        node_config,
+        waypoint,
+        storage,
+        chunk_executor,
+        None::<MempoolNotifier>,
+        metadata_storage,
+        consensus_listener,
+        event_subscription_service,
+        SyntheticDataClient::new(global_data_summary),
+        streaming_client,
+        vec![],
+    )
+    .unwrap();
+    (consensus_notifier, driver_factory.into_runtime_handle())
+}
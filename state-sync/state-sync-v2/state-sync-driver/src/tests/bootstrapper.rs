@@ -6,26 +6,32 @@ use crate::{
     bootstrapper::Bootstrapper,
     driver::DriverConfiguration,
     error::Error,
+    notification_handlers::CommitStateTracker,
+    storage_synchronizer::{new_backpressure_channel, PendingDataSummary},
     tests::{
         mocks::{
-            create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
-            MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient,
+            create_mock_db_reader, create_mock_storage_synchronizer, create_mock_streaming_client,
+            create_ready_storage_synchronizer, MockMetadataStorage, MockStorageSynchronizer,
+            MockStreamingClient,
         },
         utils::{
             create_data_stream_listener, create_empty_epoch_state, create_epoch_ending_ledger_info,
             create_full_node_driver_configuration, create_global_summary,
-            create_output_list_with_proof, create_random_epoch_ending_ledger_info,
-            create_transaction_info, create_transaction_list_with_proof,
+            create_output_fallback_handler, create_output_list_with_proof,
+            create_random_epoch_ending_ledger_info, create_transaction_info,
+            create_transaction_list_with_proof,
         },
     },
 };
 use aptos_config::config::BootstrappingMode;
 use aptos_data_client::GlobalDataSummary;
+use aptos_temppath::TempPath;
 use aptos_types::{
     transaction::{TransactionOutputListWithProof, Version},
     waypoint::Waypoint,
 };
-use claim::{assert_matches, assert_none, assert_ok};
+use aptosdb::AptosDB;
+use claim::{assert_err, assert_matches, assert_none, assert_ok};
 use data_streaming_service::{
     data_notification::{DataNotification, DataPayload},
     streaming_client::NotificationFeedback,
@@ -34,6 +40,35 @@ use futures::{channel::oneshot, FutureExt};
 use mockall::{predicate::eq, Sequence};
 use std::sync::Arc;
 
+#[tokio::test]
+async fn test_bootstrap_empty_storage() {
+    // Create an empty database (i.e., genesis hasn't been committed)
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+
+    // Create the bootstrapper (no data streams are expected)
+    let (_, backpressure_listener) = new_backpressure_channel();
+    let mut bootstrapper = Bootstrapper::new(
+        backpressure_listener,
+        CommitStateTracker::default(),
+        create_full_node_driver_configuration(),
+        MockMetadataStorage::new(),
+        create_output_fallback_handler(),
+        create_mock_streaming_client(),
+        Arc::new(db),
+        create_ready_storage_synchronizer(true),
+    );
+
+    // Drive progress and verify the bootstrapper waits for genesis (without an error)
+    let global_data_summary = create_global_summary(1);
+    for _ in 0..3 {
+        drive_progress(&mut bootstrapper, &global_data_summary, false)
+            .await
+            .unwrap();
+        assert!(!bootstrapper.is_bootstrapped());
+    }
+}
+
 #[tokio::test]
 async fn test_bootstrap_genesis_waypoint() {
     // Create a driver configuration with a genesis waypoint
@@ -49,7 +84,7 @@ async fn test_bootstrap_genesis_waypoint() {
     // Subscribe to a bootstrapped notification
     let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
     bootstrapper
-        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .subscribe_to_bootstrap_notifications(0, bootstrap_notification_sender)
         .unwrap();
 
     // Create a global data summary where only epoch 0 has ended
@@ -92,11 +127,49 @@ async fn test_bootstrap_immediate_notification() {
     // Subscribe to a bootstrapped notification and verify immediate notification
     let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
     bootstrapper
-        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .subscribe_to_bootstrap_notifications(0, bootstrap_notification_sender)
         .unwrap();
     verify_bootstrap_notification(bootstrap_notification_receiver);
 }
 
+#[tokio::test]
+async fn test_bootstrap_multiple_subscribers() {
+    // Create a driver configuration with a genesis waypoint
+    let driver_configuration = create_full_node_driver_configuration();
+
+    // Create the mock streaming client
+    let mock_streaming_client = create_mock_streaming_client();
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper(driver_configuration, mock_streaming_client, true);
+
+    // Subscribe to bootstrapped notifications (with three subscribers)
+    let mut bootstrap_notification_receivers = vec![];
+    for subscription_id in 0..3 {
+        let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
+        bootstrapper
+            .subscribe_to_bootstrap_notifications(subscription_id, bootstrap_notification_sender)
+            .unwrap();
+        bootstrap_notification_receivers.push(bootstrap_notification_receiver);
+    }
+
+    // Unsubscribe the second subscriber and verify its channel is dropped
+    assert!(bootstrapper.unsubscribe_from_bootstrap_notifications(1));
+    assert!(!bootstrapper.unsubscribe_from_bootstrap_notifications(1));
+    let unsubscribed_receiver = bootstrap_notification_receivers.remove(1);
+    assert_err!(unsubscribed_receiver.now_or_never().unwrap());
+
+    // Drive progress and verify the remaining subscribers are notified
+    let global_data_summary = create_global_summary(0);
+    drive_progress(&mut bootstrapper, &global_data_summary, true)
+        .await
+        .unwrap();
+    assert!(bootstrapper.is_bootstrapped());
+    for bootstrap_notification_receiver in bootstrap_notification_receivers {
+        verify_bootstrap_notification(bootstrap_notification_receiver);
+    }
+}
+
 #[tokio::test]
 async fn test_bootstrap_no_notification() {
     // Create a driver configuration with a genesis waypoint
@@ -119,7 +192,7 @@ async fn test_bootstrap_no_notification() {
     // Subscribe to a bootstrapped notification
     let (bootstrap_notification_sender, bootstrap_notification_receiver) = oneshot::channel();
     bootstrapper
-        .subscribe_to_bootstrap_notifications(bootstrap_notification_sender)
+        .subscribe_to_bootstrap_notifications(0, bootstrap_notification_sender)
         .unwrap();
 
     // Drive progress
@@ -466,6 +539,9 @@ async fn test_snapshot_sync_epoch_change() {
     metadata_storage
         .expect_get_last_persisted_state_value_index()
         .returning(move |_| Ok(last_persisted_index_clone));
+    metadata_storage
+        .expect_get_snapshot_chunk_progress()
+        .returning(|| Ok(None));
 
     // Create the bootstrapper
     let mut bootstrapper = create_bootstrapper_with_storage(
@@ -547,6 +623,9 @@ async fn test_snapshot_sync_existing_state() {
     metadata_storage
         .expect_get_last_persisted_state_value_index()
         .returning(move |_| Ok(last_persisted_index_clone));
+    metadata_storage
+        .expect_get_snapshot_chunk_progress()
+        .returning(|| Ok(None));
 
     // Create the bootstrapper
     let mut bootstrapper = create_bootstrapper_with_storage(
@@ -793,6 +872,139 @@ async fn test_snapshot_sync_lag_panic() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_snapshot_sync_pending_finalization() {
+    // Create a driver configuration with a genesis waypoint and state syncing
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+
+    // Create a mock storage synchronizer with a partially finalized state snapshot
+    let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
+    mock_storage_synchronizer
+        .expect_pending_storage_data()
+        .return_const(PendingDataSummary::default());
+    mock_storage_synchronizer
+        .expect_reset_chunk_executor()
+        .return_const(Ok(()));
+    mock_storage_synchronizer
+        .expect_verifies_state_value_chunks()
+        .return_const(false);
+    mock_storage_synchronizer
+        .expect_finalize_pending_state_snapshot()
+        .times(1)
+        .return_const(Ok(true));
+
+    // Create the mock db reader with only genesis loaded
+    let mut mock_database_reader = create_mock_db_reader();
+    mock_database_reader
+        .expect_get_latest_epoch_state()
+        .returning(|| Ok(create_empty_epoch_state()));
+    mock_database_reader
+        .expect_get_latest_ledger_info()
+        .returning(|| Ok(create_epoch_ending_ledger_info()));
+    mock_database_reader
+        .expect_get_latest_transaction_info_option()
+        .returning(|| Ok(Some((0, create_transaction_info()))));
+
+    // Create the bootstrapper (no data streams or metadata reads are expected)
+    aptos_logger::Logger::init_for_testing();
+    let (_, backpressure_listener) = new_backpressure_channel();
+    let mut bootstrapper = Bootstrapper::new(
+        backpressure_listener,
+        CommitStateTracker::default(),
+        driver_configuration,
+        MockMetadataStorage::new(),
+        create_output_fallback_handler(),
+        create_mock_streaming_client(),
+        Arc::new(mock_database_reader),
+        mock_storage_synchronizer,
+    );
+
+    // Drive progress and verify the finalization is completed before anything else
+    let global_data_summary = create_global_summary(1);
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_state_value_chunk_verification() {
+    for verify_in_storage_synchronizer in [false, true] {
+        // Create a driver configuration that verifies state value chunks
+        // in the bootstrapper (or the storage synchronizer).
+        let mut driver_configuration = create_full_node_driver_configuration();
+        driver_configuration
+            .config
+            .verify_state_values_in_storage_synchronizer = verify_in_storage_synchronizer;
+
+        // Create a mock storage synchronizer with the same configuration
+        let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
+        mock_storage_synchronizer
+            .expect_verifies_state_value_chunks()
+            .times(1)
+            .return_const(verify_in_storage_synchronizer);
+
+        // Create the bootstrapper and verify construction succeeds
+        let _ = create_bootstrapper_with_storage_synchronizer(
+            driver_configuration,
+            create_mock_streaming_client(),
+            mock_storage_synchronizer,
+        );
+    }
+}
+
+#[tokio::test]
+#[should_panic(expected = "Exactly one of the bootstrapper and the storage synchronizer")]
+async fn test_state_value_chunk_verification_mismatch() {
+    // Create a driver configuration that verifies state value chunks in the
+    // storage synchronizer (but the storage synchronizer doesn't verify them).
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration
+        .config
+        .verify_state_values_in_storage_synchronizer = true;
+    let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
+    mock_storage_synchronizer
+        .expect_verifies_state_value_chunks()
+        .return_const(false);
+
+    // Creating the bootstrapper should panic (no chunks would be verified)
+    let _ = create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        create_mock_streaming_client(),
+        mock_storage_synchronizer,
+    );
+}
+
+#[tokio::test]
+async fn test_terminate_stream_after_bootstrapping() {
+    // Create a driver configuration with a genesis waypoint
+    let driver_configuration = create_full_node_driver_configuration();
+
+    // Create the mock streaming client (no stream terminations are expected)
+    let mock_streaming_client = create_mock_streaming_client();
+
+    // Create the bootstrapper
+    let mut bootstrapper = create_bootstrapper(driver_configuration, mock_streaming_client, true);
+
+    // Create a global data summary where only epoch 0 has ended
+    let global_data_summary = create_global_summary(0);
+
+    // Drive progress and verify we're now bootstrapped (without an active stream)
+    drive_progress(&mut bootstrapper, &global_data_summary, true)
+        .await
+        .unwrap();
+    assert!(bootstrapper.is_bootstrapped());
+    assert!(!bootstrapper.has_active_stream());
+
+    // Handle an error for a bootstrapper stream that arrives after bootstrapping
+    // and verify that the termination is ignored.
+    bootstrapper
+        .terminate_active_stream(0, NotificationFeedback::InvalidPayloadData)
+        .await
+        .unwrap();
+}
+
 #[tokio::test]
 async fn test_waypoint_mismatch() {
     // Create a waypoint
@@ -924,13 +1136,23 @@ fn create_bootstrapper(
     driver_configuration: DriverConfiguration,
     mock_streaming_client: MockStreamingClient,
     expect_reset_executor: bool,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    create_bootstrapper_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        create_ready_storage_synchronizer(expect_reset_executor),
+    )
+}
+
+/// Creates a bootstrapper for testing with a mock storage synchronizer
+fn create_bootstrapper_with_storage_synchronizer(
+    driver_configuration: DriverConfiguration,
+    mock_streaming_client: MockStreamingClient,
+    mock_storage_synchronizer: MockStorageSynchronizer,
 ) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
     // Initialize the logger for tests
     aptos_logger::Logger::init_for_testing();
 
-    // Create the mock storage synchronizer
-    let mock_storage_synchronizer = create_ready_storage_synchronizer(expect_reset_executor);
-
     // Create the mock metadata storage
     let mut metadata_storage = MockMetadataStorage::new();
     metadata_storage
@@ -949,9 +1171,15 @@ fn create_bootstrapper(
         .expect_get_latest_transaction_info_option()
         .returning(|| Ok(Some((0, create_transaction_info()))));
 
+    // Create a backpressure listener (the storage synchronizer is mocked)
+    let (_, backpressure_listener) = new_backpressure_channel();
+
     Bootstrapper::new(
+        backpressure_listener,
+        CommitStateTracker::default(),
         driver_configuration,
         metadata_storage,
+        create_output_fallback_handler(),
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
@@ -983,10 +1211,19 @@ fn create_bootstrapper_with_storage(
     mock_database_reader
         .expect_get_latest_transaction_info_option()
         .returning(move || Ok(Some((latest_synced_version, create_transaction_info()))));
+    mock_database_reader
+        .expect_get_first_txn_version()
+        .returning(|| Ok(Some(0)));
+
+    // Create a backpressure listener (the storage synchronizer is mocked)
+    let (_, backpressure_listener) = new_backpressure_channel();
 
     Bootstrapper::new(
+        backpressure_listener,
+        CommitStateTracker::default(),
         driver_configuration,
         mock_metadata_storage,
+        create_output_fallback_handler(),
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
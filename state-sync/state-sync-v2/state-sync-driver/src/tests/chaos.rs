@@ -0,0 +1,961 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Chaos tests that run the driver against a synthetic chain while injecting
+//! random faults into the data streams (e.g., stream terminations, corrupted
+//! payloads and peer disappearances) and the storage synchronizer (e.g.,
+//! delayed commits and storage errors). The node must still sync the entire
+//! chain, without committing any gaps or duplicates.
+//!
+//! All faults are drawn from a seeded RNG. The seed is printed by each test
+//! (and shown if the test fails), and can be fixed by setting the
+//! `CHAOS_SEED` environment variable. Note: the driver runs in real time, so
+//! a seed reproduces the injected faults, but not necessarily their timing.
+
+use crate::{
+    driver_factory::DriverFactory,
+    error::Error,
+    metadata_storage::PersistentMetadataStorage,
+    notification_handlers::{
+        CommitStateTracker, CommittedTransactions, ErrorSeverity, ErrorSource,
+    },
+    storage_synchronizer::{
+        ApplyOrExecute, CommittedVersionHandle, PendingDataSummary, ShutdownSummary,
+        StorageSynchronizerInterface, TransactionChunkPayload,
+    },
+    test_utils::synthetic_chain::{SyntheticChain, SyntheticChainConfig, SyntheticStreamingClient},
+    tests::{
+        driver_harness::create_data_client,
+        scripted_storage_synchronizer::{ChunkBehavior, ScriptedStorageSynchronizer},
+        utils::create_global_summary_at_ledger_info,
+    },
+};
+use anyhow::format_err;
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode, NodeConfig, RoleType};
+use aptos_infallible::{Mutex, RwLock};
+use aptos_temppath::TempPath;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    epoch_change::Verifier,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    state_store::{
+        state_key::StateKey,
+        state_value::{StateValue, StateValueChunkWithProof},
+    },
+    transaction::{TransactionInfo, TransactionOutputListWithProof, Version},
+};
+use async_trait::async_trait;
+use channel::{aptos_channel, message_queues::QueueStyle};
+use consensus_notifications::new_consensus_notifier_listener_pair;
+use data_streaming_service::{
+    data_notification::{DataNotification, DataPayload, NotificationId},
+    data_stream::DataStreamListener,
+    streaming_client::{DataStreamingClient, Epoch, NotificationFeedback},
+};
+use event_notifications::EventSubscriptionService;
+use futures::{channel::mpsc, FutureExt, StreamExt};
+use mempool_notifications::MempoolNotifier;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use storage_interface::{DbReader, DbReaderWriter, DbWriter};
+use tokio::{
+    task::JoinHandle,
+    time::{sleep, timeout},
+};
+
+// The environment variable that fixes the seed of the chaos tests
+const CHAOS_SEED_ENV_VAR: &str = "CHAOS_SEED";
+
+// The timeout (of real time) for client requests sent to the driver
+const CLIENT_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+// The max delay (of real time) injected into a commit
+const MAX_COMMIT_DELAY_MS: u64 = 50;
+
+// The max number of stream requests that fail while the peers are gone
+const MAX_PEER_DISAPPEARANCE_REQUESTS: u64 = 3;
+
+// The max time (of real time) the driver waits for a stream notification
+const MAX_STREAM_WAIT_TIME_MS: u64 = 200;
+
+// The interval (of real time) at which the driver checks progress
+const PROGRESS_CHECK_INTERVAL_MS: u64 = 10;
+
+// The interval (of real time) at which the tests check the synced version
+const SYNC_POLL_INTERVAL_MS: u64 = 50;
+
+#[tokio::test]
+async fn test_chaos_bootstrapping() {
+    run_chaos_test(ChaosConfig {
+        continuous_syncing: false,
+        fault_probability: 0.1,
+        num_epochs: 4,
+        sync_timeout_secs: 120,
+        transactions_per_chunk: 25,
+        transactions_per_epoch: 250,
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn test_chaos_continuous_syncing() {
+    run_chaos_test(ChaosConfig {
+        continuous_syncing: true,
+        fault_probability: 0.1,
+        num_epochs: 4,
+        sync_timeout_secs: 120,
+        transactions_per_chunk: 25,
+        transactions_per_epoch: 250,
+    })
+    .await;
+}
+
+#[tokio::test]
+#[ignore] // A long running soak test (run it with `--ignored`)
+async fn test_chaos_soak() {
+    for continuous_syncing in [false, true].iter() {
+        run_chaos_test(ChaosConfig {
+            continuous_syncing: *continuous_syncing,
+            fault_probability: 0.05,
+            num_epochs: 20,
+            sync_timeout_secs: 1800,
+            transactions_per_chunk: 50,
+            transactions_per_epoch: 500,
+        })
+        .await;
+    }
+}
+
+/// The configuration of a single chaos test run
+#[derive(Clone, Copy, Debug)]
+struct ChaosConfig {
+    continuous_syncing: bool, // Whether the chain is synced by the continuous syncer (instead of the bootstrapper)
+    fault_probability: f64,   // The probability of injecting a fault at each injection point
+    num_epochs: u64,
+    sync_timeout_secs: u64, // The max time (of real time) for the node to sync the chain
+    transactions_per_chunk: u64,
+    transactions_per_epoch: u64,
+}
+
+/// A fault injected by a chaos test
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum ChaosFault {
+    CorruptPayload,          // Serve a payload with a forged ledger info
+    DelayedCommit,           // Delay the commit of a chunk (and all chunks behind it)
+    DropNotification,        // Drop a notification (i.e., never send it along the stream)
+    PeerDisappearance,       // Fail the next stream requests (the peers have disappeared)
+    RecoverableStorageError, // Fail a chunk without committing it
+    StaleNotification,       // Serve the payload of the previous notification (again)
+    StreamTermination,       // End the stream early (i.e., drop the remaining notifications)
+    TransientStorageError,   // Commit a chunk, but notify a transient error
+}
+
+/// Injects faults at random (using a seeded RNG) and counts the injected
+/// faults. Each component should use its own (forked) injector, so that the
+/// faults injected into one component don't depend on the other components.
+#[derive(Clone)]
+struct ChaosInjector {
+    fault_probability: f64,
+    injected_faults: Arc<Mutex<HashMap<ChaosFault, u64>>>, // Shared by all forked injectors
+    rng: Arc<Mutex<StdRng>>,
+}
+
+impl ChaosInjector {
+    fn new(seed: u64, fault_probability: f64) -> Self {
+        Self {
+            fault_probability,
+            injected_faults: Arc::new(Mutex::new(HashMap::new())),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Returns a new injector (with an RNG seeded by this injector) that
+    /// shares the counts of the injected faults.
+    fn fork(&self) -> Self {
+        let seed = self.rng.lock().gen();
+        Self {
+            fault_probability: self.fault_probability,
+            injected_faults: self.injected_faults.clone(),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        }
+    }
+
+    /// Returns a random number in the given (half-open) range
+    fn gen_range(&self, low: u64, high: u64) -> u64 {
+        self.rng.lock().gen_range(low, high)
+    }
+
+    /// Returns the number of faults injected (by fault type)
+    fn get_injected_faults(&self) -> HashMap<ChaosFault, u64> {
+        self.injected_faults.lock().clone()
+    }
+
+    /// Decides whether to inject a fault (with the fault probability) and, if
+    /// so, returns one of the given faults (chosen uniformly).
+    fn maybe_inject_fault(&self, faults: &[ChaosFault]) -> Option<ChaosFault> {
+        let fault = {
+            let mut rng = self.rng.lock();
+            if !rng.gen_bool(self.fault_probability) {
+                return None;
+            }
+            *faults.choose(&mut *rng)?
+        };
+        *self.injected_faults.lock().entry(fault).or_insert(0) += 1;
+        Some(fault)
+    }
+}
+
+/// A data streaming client that serves the data streams of a synthetic
+/// streaming client, but injects faults into the notifications of each
+/// stream, and fails stream requests while the peers have disappeared.
+#[derive(Clone)]
+struct ChaosStreamingClient {
+    chain: Arc<SyntheticChain>,
+    chaos_injector: ChaosInjector,
+    state: Arc<Mutex<ChaosStreamingState>>,
+    streaming_client: SyntheticStreamingClient,
+}
+
+/// The (shared) state of the chaos streaming client
+#[derive(Default)]
+struct ChaosStreamingState {
+    notification_senders: Vec<aptos_channel::Sender<(), DataNotification>>, // Held to keep the streams open
+    num_unavailable_requests: u64, // The number of stream requests still to fail (while the peers are gone)
+}
+
+impl ChaosStreamingClient {
+    fn new(chain: Arc<SyntheticChain>, chaos_injector: ChaosInjector) -> Self {
+        let streaming_client = SyntheticStreamingClient::new(chain.clone());
+        Self {
+            chain,
+            chaos_injector,
+            state: Arc::new(Mutex::new(ChaosStreamingState::default())),
+            streaming_client,
+        }
+    }
+
+    /// Returns an error if the peers have disappeared (in which case, no
+    /// data is available). Peers might disappear on any stream request.
+    fn check_peers_available(&self) -> Result<(), data_streaming_service::error::Error> {
+        let mut state = self.state.lock();
+        if state.num_unavailable_requests == 0
+            && self
+                .chaos_injector
+                .maybe_inject_fault(&[ChaosFault::PeerDisappearance])
+                .is_some()
+        {
+            state.num_unavailable_requests = self
+                .chaos_injector
+                .gen_range(1, MAX_PEER_DISAPPEARANCE_REQUESTS + 1);
+        }
+
+        if state.num_unavailable_requests > 0 {
+            state.num_unavailable_requests -= 1;
+            return Err(data_streaming_service::error::Error::DataIsUnavailable(
+                "The peers have disappeared!".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the given payload where the (first) ledger info is
+    /// forged, i.e., signed by validators that aren't in the epoch's validator
+    /// set. Payloads without ledger infos are returned unchanged.
+    fn forge_payload(&self, data_payload: DataPayload) -> DataPayload {
+        let forge_ledger_info = |ledger_info: &LedgerInfoWithSignatures| {
+            self.chain
+                .create_forged_ledger_info(ledger_info.ledger_info().epoch())
+        };
+        match data_payload {
+            DataPayload::ContinuousTransactionOutputsWithProof(ledger_info, outputs_with_proof) => {
+                DataPayload::ContinuousTransactionOutputsWithProof(
+                    forge_ledger_info(&ledger_info),
+                    outputs_with_proof,
+                )
+            }
+            DataPayload::ContinuousTransactionsWithProof(ledger_info, transactions_with_proof) => {
+                DataPayload::ContinuousTransactionsWithProof(
+                    forge_ledger_info(&ledger_info),
+                    transactions_with_proof,
+                )
+            }
+            DataPayload::EpochEndingLedgerInfos(mut ledger_infos) => {
+                if let Some(ledger_info) = ledger_infos.first_mut() {
+                    *ledger_info = forge_ledger_info(ledger_info);
+                }
+                DataPayload::EpochEndingLedgerInfos(ledger_infos)
+            }
+            data_payload => data_payload,
+        }
+    }
+
+    /// Re-serves the notifications of the given data stream (i.e., all of
+    /// the notifications sent by the synthetic streaming client) along a new
+    /// stream, and injects faults into the notifications.
+    ///
+    /// Note: only the ledger infos of payloads are corrupted, as the scripted
+    /// storage synchronizer doesn't verify the transaction proofs.
+    fn inject_stream_faults(
+        &self,
+        mut data_stream_listener: DataStreamListener,
+    ) -> DataStreamListener {
+        let mut data_notifications = vec![];
+        while let Some(Some(data_notification)) = data_stream_listener.next().now_or_never() {
+            data_notifications.push(data_notification);
+        }
+
+        // Create the notification channel (large enough to hold all notifications)
+        let (notification_sender, notification_receiver) =
+            aptos_channel::new(QueueStyle::FIFO, data_notifications.len().max(1), None);
+
+        // Send the notifications (and inject any faults)
+        let mut previous_payload: Option<DataPayload> = None;
+        for data_notification in data_notifications {
+            let DataNotification {
+                notification_id,
+                data_payload,
+            } = data_notification;
+
+            // Identify the faults that can be injected into the notification
+            let mut faults = vec![ChaosFault::DropNotification, ChaosFault::StreamTermination];
+            if matches!(
+                data_payload,
+                DataPayload::ContinuousTransactionOutputsWithProof(..)
+                    | DataPayload::ContinuousTransactionsWithProof(..)
+                    | DataPayload::EpochEndingLedgerInfos(_)
+            ) {
+                faults.push(ChaosFault::CorruptPayload);
+            }
+            if previous_payload.is_some() {
+                faults.push(ChaosFault::StaleNotification);
+            }
+
+            // Inject a fault (if any)
+            let served_payload = match self.chaos_injector.maybe_inject_fault(&faults) {
+                Some(ChaosFault::CorruptPayload) => self.forge_payload(data_payload.clone()),
+                Some(ChaosFault::DropNotification) => {
+                    previous_payload = Some(data_payload);
+                    continue;
+                }
+                Some(ChaosFault::StaleNotification) => previous_payload
+                    .clone()
+                    .unwrap_or_else(|| data_payload.clone()),
+                Some(ChaosFault::StreamTermination) => {
+                    // End the stream (the remaining notifications are never sent)
+                    notification_sender
+                        .push(
+                            (),
+                            DataNotification {
+                                notification_id,
+                                data_payload: DataPayload::EndOfStream,
+                            },
+                        )
+                        .unwrap();
+                    break;
+                }
+                _ => data_payload.clone(),
+            };
+            notification_sender
+                .push(
+                    (),
+                    DataNotification {
+                        notification_id,
+                        data_payload: served_payload,
+                    },
+                )
+                .unwrap();
+            previous_payload = Some(data_payload);
+        }
+        self.state
+            .lock()
+            .notification_senders
+            .push(notification_sender);
+
+        DataStreamListener::new(notification_receiver)
+    }
+}
+
+#[async_trait]
+impl DataStreamingClient for ChaosStreamingClient {
+    async fn get_all_state_values(
+        &self,
+        version: Version,
+        start_index: Option<u64>,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .get_all_state_values(version, start_index)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn get_all_epoch_ending_ledger_infos(
+        &self,
+        start_epoch: Epoch,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .get_all_epoch_ending_ledger_infos(start_epoch)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn get_all_transaction_outputs(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .get_all_transaction_outputs(start_version, end_version, proof_version)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn get_all_transactions(
+        &self,
+        start_version: Version,
+        end_version: Version,
+        proof_version: Version,
+        include_events: bool,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .get_all_transactions(start_version, end_version, proof_version, include_events)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn continuously_stream_transaction_outputs(
+        &self,
+        known_version: u64,
+        known_epoch: u64,
+        target: Option<LedgerInfoWithSignatures>,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .continuously_stream_transaction_outputs(known_version, known_epoch, target)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn continuously_stream_transactions(
+        &self,
+        start_version: Version,
+        start_epoch: Epoch,
+        include_events: bool,
+        target: Option<LedgerInfoWithSignatures>,
+    ) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+        self.check_peers_available()?;
+        let data_stream_listener = self
+            .streaming_client
+            .continuously_stream_transactions(start_version, start_epoch, include_events, target)
+            .await?;
+        Ok(self.inject_stream_faults(data_stream_listener))
+    }
+
+    async fn terminate_stream_with_feedback(
+        &self,
+        notification_id: NotificationId,
+        notification_feedback: NotificationFeedback,
+    ) -> Result<(), data_streaming_service::error::Error> {
+        self.streaming_client
+            .terminate_stream_with_feedback(notification_id, notification_feedback)
+            .await
+    }
+}
+
+/// A storage synchronizer that commits chunks using a scripted storage
+/// synchronizer, but injects delayed commits and storage errors. Unlike the
+/// scripted synchronizer, chunks are committed in order, and chunks that
+/// don't follow the committed (or in-flight) data, or that fail ledger info
+/// verification, are rejected (as with the real storage synchronizer).
+#[derive(Clone)]
+struct ChaosStorageSynchronizer {
+    chaos_injector: ChaosInjector,
+    storage_synchronizer: ScriptedStorageSynchronizer,
+}
+
+impl ChaosStorageSynchronizer {
+    fn new(
+        storage_synchronizer: ScriptedStorageSynchronizer,
+        chaos_injector: ChaosInjector,
+    ) -> Self {
+        Self {
+            chaos_injector,
+            storage_synchronizer,
+        }
+    }
+
+    /// Returns the behavior of the next submitted chunk (given the first
+    /// version of the chunk, and the ledger info verification result).
+    fn get_chunk_behavior(
+        &self,
+        first_version: Option<Version>,
+        ledger_info_verified: bool,
+    ) -> ChunkBehavior {
+        // Reject chunks that fail verification, or that don't follow the
+        // committed (or in-flight) data.
+        let committed_version = self
+            .storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version();
+        let pending_data = self.storage_synchronizer.pending_storage_data();
+        let expected_version = pending_data.highest_known_version(committed_version) + 1;
+        if !ledger_info_verified || first_version != Some(expected_version) {
+            return ChunkBehavior::Fail(ErrorSeverity::Recoverable);
+        }
+
+        // Hold the chunk behind any delayed chunks (so that chunks commit in order)
+        if !pending_data.is_idle() {
+            return ChunkBehavior::Hold;
+        }
+
+        // Inject a storage fault (if any)
+        match self.chaos_injector.maybe_inject_fault(&[
+            ChaosFault::DelayedCommit,
+            ChaosFault::RecoverableStorageError,
+            ChaosFault::TransientStorageError,
+        ]) {
+            Some(ChaosFault::DelayedCommit) => ChunkBehavior::Hold,
+            Some(ChaosFault::RecoverableStorageError) => {
+                ChunkBehavior::Fail(ErrorSeverity::Recoverable)
+            }
+            Some(ChaosFault::TransientStorageError) => ChunkBehavior::CommitWithTransientError,
+            _ => ChunkBehavior::Commit,
+        }
+    }
+}
+
+impl StorageSynchronizerInterface for ChaosStorageSynchronizer {
+    fn initialize_state_synchronizer(
+        &mut self,
+        epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
+        target_ledger_info: LedgerInfoWithSignatures,
+        target_output_with_proof: TransactionOutputListWithProof,
+    ) -> Result<JoinHandle<()>, Error> {
+        self.storage_synchronizer.initialize_state_synchronizer(
+            epoch_change_proofs,
+            target_ledger_info,
+            target_output_with_proof,
+        )
+    }
+
+    fn finalize_pending_state_snapshot(&mut self) -> Result<bool, Error> {
+        self.storage_synchronizer.finalize_pending_state_snapshot()
+    }
+
+    fn notify_stream_reset(&self) {
+        self.storage_synchronizer.notify_stream_reset()
+    }
+
+    fn pending_storage_data(&self) -> PendingDataSummary {
+        self.storage_synchronizer.pending_storage_data()
+    }
+
+    fn committed_version_handle(&self) -> CommittedVersionHandle {
+        self.storage_synchronizer.committed_version_handle()
+    }
+
+    fn reset_pending_data(&self) {
+        self.storage_synchronizer.reset_pending_data()
+    }
+
+    fn shutdown(&self) -> ShutdownSummary {
+        self.storage_synchronizer.shutdown()
+    }
+
+    fn save_state_values_with_proof(
+        &mut self,
+        notification_id: NotificationId,
+        state_value_chunk_with_proof: StateValueChunkWithProof,
+        verify: bool,
+    ) -> Result<(), Error> {
+        self.storage_synchronizer.save_state_values_with_proof(
+            notification_id,
+            state_value_chunk_with_proof,
+            verify,
+        )
+    }
+
+    fn verifies_state_value_chunks(&self) -> bool {
+        self.storage_synchronizer.verifies_state_value_chunks()
+    }
+
+    fn submit_transaction_chunk(
+        &mut self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        apply_or_execute: ApplyOrExecute,
+        chunk_payload: TransactionChunkPayload,
+        target_ledger_info: LedgerInfoWithSignatures,
+        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+        ledger_info_verifier: Option<EpochState>,
+    ) -> Result<(), Error> {
+        // Script the behavior of the chunk (the scripted storage synchronizer
+        // doesn't verify anything, so the ledger info is verified here).
+        let ledger_info_verified = ledger_info_verifier.as_ref().map_or(true, |epoch_state| {
+            epoch_state.verify(&target_ledger_info).is_ok()
+        });
+        let behavior = self.get_chunk_behavior(chunk_payload.first_version(), ledger_info_verified);
+        let commit_delayed = behavior == ChunkBehavior::Hold && self.storage_synchronizer.is_idle();
+        self.storage_synchronizer.add_behaviors(vec![behavior]);
+
+        // Submit the chunk
+        self.storage_synchronizer.submit_transaction_chunk(
+            notification_id,
+            error_source,
+            apply_or_execute,
+            chunk_payload,
+            target_ledger_info,
+            end_of_epoch_ledger_info,
+            ledger_info_verifier,
+        )?;
+
+        // If the commit was delayed, release the held chunks once the delay elapses
+        if commit_delayed {
+            let commit_delay = self.chaos_injector.gen_range(1, MAX_COMMIT_DELAY_MS + 1);
+            let storage_synchronizer = self.storage_synchronizer.clone();
+            tokio::spawn(async move {
+                sleep(Duration::from_millis(commit_delay)).await;
+                storage_synchronizer.release_held_chunks();
+            });
+        }
+
+        Ok(())
+    }
+
+    fn reset_chunk_executor(&self) -> Result<(), Error> {
+        self.storage_synchronizer.reset_chunk_executor()
+    }
+
+    fn finish_chunk_executor(&self) {
+        self.storage_synchronizer.finish_chunk_executor()
+    }
+}
+
+/// A read-only view of the synthetic chain, up to the version committed by
+/// the storage synchronizer. This is the storage that the driver reads its
+/// progress from (the scripted storage synchronizer never writes anything).
+#[derive(Clone)]
+struct ChainStorage {
+    chain: Arc<SyntheticChain>,
+    committed_version_handle: CommittedVersionHandle,
+}
+
+impl ChainStorage {
+    fn new(chain: Arc<SyntheticChain>, committed_version_handle: CommittedVersionHandle) -> Self {
+        Self {
+            chain,
+            committed_version_handle,
+        }
+    }
+
+    /// Returns a reader writer that reads from the chain view
+    fn create_reader_writer(&self) -> DbReaderWriter {
+        DbReaderWriter {
+            reader: Arc::new(self.clone()),
+            writer: Arc::new(self.clone()),
+        }
+    }
+
+    /// Returns the highest ledger info at (or below) the committed version
+    fn get_committed_ledger_info(&self) -> LedgerInfoWithSignatures {
+        let committed_version = self.committed_version_handle.get_committed_version();
+        self.chain
+            .get_ledger_info_at_version(committed_version)
+            .unwrap_or_else(|| {
+                let epoch = self.chain.get_epoch_at_version(committed_version);
+                self.chain.get_epoch_ending_ledger_info(epoch - 1)
+            })
+    }
+}
+
+impl DbReader for ChainStorage {
+    fn get_first_txn_version(&self) -> anyhow::Result<Option<Version>> {
+        Ok(Some(0)) // Nothing is ever pruned
+    }
+
+    fn get_latest_epoch_state(&self) -> anyhow::Result<EpochState> {
+        self.get_committed_ledger_info()
+            .ledger_info()
+            .next_epoch_state()
+            .cloned()
+            .ok_or_else(|| format_err!("The committed ledger info doesn't end an epoch!"))
+    }
+
+    fn get_latest_ledger_info_option(&self) -> anyhow::Result<Option<LedgerInfoWithSignatures>> {
+        Ok(Some(self.get_committed_ledger_info()))
+    }
+
+    fn get_epoch_ending_ledger_info(
+        &self,
+        known_version: u64,
+    ) -> anyhow::Result<LedgerInfoWithSignatures> {
+        self.chain
+            .get_ledger_info_at_version(known_version)
+            .filter(|ledger_info| ledger_info.ledger_info().ends_epoch())
+            .ok_or_else(|| format_err!("Not an epoch change at version {}", known_version))
+    }
+
+    fn get_latest_state_checkpoint_version(&self) -> anyhow::Result<Option<Version>> {
+        Ok(Some(self.committed_version_handle.get_committed_version()))
+    }
+
+    fn get_latest_transaction_info_option(
+        &self,
+    ) -> anyhow::Result<Option<(Version, TransactionInfo)>> {
+        let committed_version = self.committed_version_handle.get_committed_version();
+        Ok(Some((
+            committed_version,
+            self.chain.get_transaction_info(committed_version),
+        )))
+    }
+
+    fn get_state_value_by_version(
+        &self,
+        _state_key: &StateKey,
+        _version: Version,
+    ) -> anyhow::Result<Option<StateValue>> {
+        Ok(None) // Synthetic chains hold no state (e.g., no on-chain configs)
+    }
+}
+
+impl DbWriter for ChainStorage {
+    fn pause_pruning(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn resume_pruning(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs a single chaos test with the given config, i.e., spawns a fullnode
+/// driver (with chaos injected into the data streams and the storage
+/// synchronizer), waits for the node to sync the entire synthetic chain and
+/// verifies the committed data.
+async fn run_chaos_test(chaos_config: ChaosConfig) {
+    // Fetch the seed (every failure reports it, so that failed runs can be
+    // reproduced by setting the CHAOS_SEED environment variable).
+    let seed = match std::env::var(CHAOS_SEED_ENV_VAR) {
+        Ok(seed) => seed.parse().expect("The chaos seed should be a u64!"),
+        Err(_) => rand::random(),
+    };
+
+    // Create the chain and the chaos injectors
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig {
+        num_epochs: chaos_config.num_epochs,
+        transactions_per_chunk: chaos_config.transactions_per_chunk,
+        transactions_per_epoch: chaos_config.transactions_per_epoch,
+        ..SyntheticChainConfig::default()
+    }));
+    let chaos_injector = ChaosInjector::new(seed, chaos_config.fault_probability);
+
+    // Create the streaming client, storage synchronizer and storage
+    let streaming_client = ChaosStreamingClient::new(chain.clone(), chaos_injector.fork());
+    let (storage_synchronizer, committed_transactions_listener, error_notification_listener) =
+        ScriptedStorageSynchronizer::new(vec![], 0, 0, TimeService::real());
+    let storage_synchronizer =
+        ChaosStorageSynchronizer::new(storage_synchronizer, chaos_injector.fork());
+    let committed_version_handle = storage_synchronizer.committed_version_handle();
+    let storage =
+        ChainStorage::new(chain.clone(), committed_version_handle.clone()).create_reader_writer();
+
+    // Create the node config (check progress frequently, time out streams
+    // quickly, and never retry failed stream initializations).
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::FullNode;
+    let driver_config = &mut node_config.state_sync.state_sync_driver;
+    if chaos_config.continuous_syncing {
+        driver_config.bootstrapping_mode = BootstrappingMode::ExecuteTransactionsFromGenesis;
+        driver_config.continuous_syncing_mode = ContinuousSyncingMode::ExecuteTransactions;
+    } else {
+        driver_config.bootstrapping_mode = BootstrappingMode::ApplyTransactionOutputsFromGenesis;
+        driver_config.continuous_syncing_mode = ContinuousSyncingMode::ApplyTransactionOutputs;
+    }
+    driver_config.max_stream_initialization_retries = 0;
+    driver_config.max_stream_wait_time_ms = MAX_STREAM_WAIT_TIME_MS;
+    driver_config.progress_check_interval_ms = PROGRESS_CHECK_INTERVAL_MS;
+
+    // Advertise the chain (or only genesis, if the node should bootstrap
+    // at genesis and sync the rest of the chain continuously).
+    let advertised_ledger_info = if chaos_config.continuous_syncing {
+        chain.get_epoch_ending_ledger_info(0)
+    } else {
+        chain.get_latest_ledger_info()
+    };
+    let global_data_summary = Arc::new(Mutex::new(create_global_summary_at_ledger_info(
+        &advertised_ledger_info,
+    )));
+
+    // Create and spawn the driver
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let (_consensus_notifier, consensus_listener) = new_consensus_notifier_listener_pair(5000);
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(storage.clone())),
+    );
+    let driver_handle = DriverFactory::create_and_spawn_driver_with_components(
+        false,
+        &node_config,
+        chain.get_genesis_waypoint(),
+        storage,
+        storage_synchronizer,
+        error_notification_listener,
+        None::<MempoolNotifier>,
+        PersistentMetadataStorage::new(metadata_path.path()),
+        consensus_listener,
+        event_subscription_service,
+        create_data_client(global_data_summary.clone()),
+        streaming_client,
+        vec![],
+        TimeService::real(),
+    )
+    .unwrap()
+    .into_runtime_handle();
+    let committed_chunks = spawn_commit_listener(
+        chain.clone(),
+        driver_handle.commit_state_tracker(),
+        committed_transactions_listener,
+    );
+
+    // If the node syncs continuously, wait for it to bootstrap (there's
+    // nothing to sync) and advertise the rest of the chain.
+    if chaos_config.continuous_syncing {
+        timeout(
+            Duration::from_secs(chaos_config.sync_timeout_secs),
+            driver_handle.driver_client().notify_once_bootstrapped(),
+        )
+        .await
+        .unwrap_or_else(|_| panic!("The node failed to bootstrap! Seed: {:?}", seed))
+        .unwrap();
+        *global_data_summary.lock() =
+            create_global_summary_at_ledger_info(&chain.get_latest_ledger_info());
+    }
+
+    // Wait for the node to sync (and record the commits of) the entire chain
+    let latest_version = chain.get_latest_version();
+    let sync_result = timeout(Duration::from_secs(chaos_config.sync_timeout_secs), async {
+        while get_highest_committed_version(&committed_chunks.lock()) < latest_version {
+            sleep(Duration::from_millis(SYNC_POLL_INTERVAL_MS)).await;
+        }
+    })
+    .await;
+    assert!(
+        sync_result.is_ok(),
+        "The node failed to sync the chain! Seed: {:?}, committed version: {:?}, latest version: {:?}",
+        seed,
+        committed_version_handle.get_committed_version(),
+        latest_version
+    );
+
+    // Verify the driver is still alive (and synced)
+    let sync_status = driver_handle
+        .driver_client()
+        .get_sync_status(Duration::from_secs(CLIENT_REQUEST_TIMEOUT_SECS))
+        .await
+        .unwrap_or_else(|error| {
+            panic!(
+                "Failed to get the sync status: {:?}! Seed: {:?}",
+                error, seed
+            )
+        });
+    assert_eq!(
+        sync_status.synced_version,
+        Some(latest_version),
+        "The node isn't synced! Seed: {:?}",
+        seed
+    );
+
+    // Verify the committed data and that faults were actually injected
+    verify_committed_chunks(&chain, &committed_chunks.lock(), seed);
+    assert!(
+        !chaos_injector.get_injected_faults().is_empty(),
+        "No faults were injected! Seed: {:?}",
+        seed
+    );
+}
+
+/// Returns the highest version committed by the given chunks
+fn get_highest_committed_version(committed_chunks: &[CommittedTransactions]) -> Version {
+    committed_chunks
+        .last()
+        .map(|committed_chunk| committed_chunk.last_version)
+        .unwrap_or(0)
+}
+
+/// Spawns a task that records every chunk committed by the storage
+/// synchronizer (in commit order), and caches the epoch state of each new
+/// epoch in the commit state tracker (as the driver would for its own commits).
+fn spawn_commit_listener(
+    chain: Arc<SyntheticChain>,
+    commit_state_tracker: CommitStateTracker,
+    mut committed_transactions_listener: mpsc::UnboundedReceiver<CommittedTransactions>,
+) -> Arc<Mutex<Vec<CommittedTransactions>>> {
+    let committed_chunks = Arc::new(Mutex::new(vec![]));
+    let recorded_chunks = committed_chunks.clone();
+    tokio::spawn(async move {
+        while let Some(committed_transactions) = committed_transactions_listener.next().await {
+            if let Some(ledger_info) =
+                chain.get_ledger_info_at_version(committed_transactions.last_version)
+            {
+                if let Some(next_epoch_state) = ledger_info.ledger_info().next_epoch_state() {
+                    commit_state_tracker.update_epoch_state(next_epoch_state.clone());
+                }
+            }
+            recorded_chunks.lock().push(committed_transactions);
+        }
+    });
+    committed_chunks
+}
+
+/// Verifies the given chunks (in commit order) cover the entire chain after
+/// genesis without any gaps or duplicates, and match the transactions of the chain.
+/// Failures report the seed of the chaos test.
+fn verify_committed_chunks(
+    chain: &SyntheticChain,
+    committed_chunks: &[CommittedTransactions],
+    seed: u64,
+) {
+    let latest_version = chain.get_latest_version();
+    let mut next_version = 1;
+    for committed_chunk in committed_chunks {
+        assert_eq!(
+            committed_chunk.first_version,
+            next_version,
+            "The committed chunks have a gap (or overlap)! Chunk: {:?}, seed: {:?}",
+            (committed_chunk.first_version, committed_chunk.last_version),
+            seed
+        );
+        let expected_transactions = chain
+            .get_transactions_with_proof(
+                committed_chunk.first_version,
+                committed_chunk.last_version,
+                latest_version,
+                false,
+            )
+            .transactions;
+        assert_eq!(
+            committed_chunk.transactions, expected_transactions,
+            "The committed transactions don't match the chain! Seed: {:?}",
+            seed
+        );
+        next_version = committed_chunk.last_version + 1;
+    }
+    assert_eq!(
+        next_version,
+        latest_version + 1,
+        "The committed chunks don't cover the chain! Seed: {:?}",
+        seed
+    );
+}
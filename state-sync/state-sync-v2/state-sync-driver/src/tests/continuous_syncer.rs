@@ -5,23 +5,38 @@ use crate::{
     continuous_syncer::ContinuousSyncer,
     driver::DriverConfiguration,
     error::Error,
-    notification_handlers::ConsensusSyncRequest,
+    metrics::DriverMetrics,
+    notification_handlers::CommitStateTracker,
+    storage_synchronizer::{
+        new_backpressure_channel, BackpressureListener, BackpressureState, PendingDataSummary,
+    },
     tests::{
         mocks::{
-            create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
-            MockStorageSynchronizer, MockStreamingClient,
+            create_mock_db_reader, create_mock_storage_synchronizer, create_mock_streaming_client,
+            create_ready_storage_synchronizer, MockStorageSynchronizer, MockStreamingClient,
         },
         utils::{
             create_data_stream_listener, create_epoch_ending_ledger_info, create_epoch_state,
-            create_full_node_driver_configuration, create_transaction_info,
+            create_full_node_driver_configuration, create_output_fallback_handler,
+            create_signed_ledger_info, create_transaction_info, create_validator_signers,
         },
     },
+    utils::OutputFallbackHandler,
 };
 use aptos_config::config::ContinuousSyncingMode;
-use aptos_infallible::Mutex;
-use aptos_types::transaction::{TransactionOutputListWithProof, Version};
+use aptos_crypto::HashValue;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::{TransactionOutputListWithProof, Version},
+    validator_signer::ValidatorSigner,
+    validator_verifier::{generate_validator_verifier, ValidatorVerifier},
+};
+use channel::aptos_channel::Sender;
 use claim::assert_matches;
-use consensus_notifications::ConsensusSyncNotification;
 use data_streaming_service::{
     data_notification::{DataNotification, DataPayload},
     streaming_client::NotificationFeedback,
@@ -70,16 +85,12 @@ async fn test_critical_timeout() {
     );
 
     // Drive progress to initialize the transaction output stream
-    let no_sync_request = Arc::new(Mutex::new(None));
-    continuous_syncer
-        .drive_progress(no_sync_request.clone())
-        .await
-        .unwrap();
+    continuous_syncer.drive_progress(None, true).await.unwrap();
 
     // Drive progress twice and verify we get non-critical timeouts
     for _ in 0..2 {
         let error = continuous_syncer
-            .drive_progress(no_sync_request.clone())
+            .drive_progress(None, true)
             .await
             .unwrap_err();
         assert_matches!(error, Error::DataStreamNotificationTimeout(_));
@@ -87,25 +98,102 @@ async fn test_critical_timeout() {
 
     // Drive progress again and verify we get a critical timeout
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(None, true)
         .await
         .unwrap_err();
     assert_matches!(error, Error::CriticalDataStreamTimeout(_));
 
     // Drive progress to initialize the transaction output stream again
-    continuous_syncer
-        .drive_progress(no_sync_request.clone())
-        .await
-        .unwrap();
+    continuous_syncer.drive_progress(None, true).await.unwrap();
 
     // Drive progress again and verify we get a non-critical timeout
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(None, true)
         .await
         .unwrap_err();
     assert_matches!(error, Error::DataStreamNotificationTimeout(_));
 }
 
+#[tokio::test]
+async fn test_data_stream_paused_by_backpressure() {
+    // Create test data
+    let current_synced_epoch = 10;
+    let current_synced_version = 1000;
+    let notification_id = 900;
+
+    // Create a driver configuration
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ApplyTransactionOutputs;
+
+    // Create the mock streaming client
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (notification_sender, data_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .times(1)
+        .with(
+            eq(current_synced_version),
+            eq(current_synced_epoch),
+            eq(None),
+        )
+        .return_once(move |_, _, _| Ok(data_stream_listener));
+    mock_streaming_client
+        .expect_terminate_stream_with_feedback()
+        .with(
+            eq(notification_id),
+            eq(NotificationFeedback::InvalidPayloadData),
+        )
+        .return_const(Ok(()));
+
+    // Create the continuous syncer with a backpressured storage synchronizer
+    let (backpressure_notifier, backpressure_listener) = new_backpressure_channel();
+    backpressure_notifier
+        .send(BackpressureState::Paused)
+        .unwrap();
+    let mut continuous_syncer = create_continuous_syncer_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        create_ready_storage_synchronizer(true),
+        current_synced_version,
+        current_synced_epoch,
+        backpressure_listener,
+        create_output_fallback_handler(),
+    );
+
+    // Drive progress to initialize the transaction output stream
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+
+    // Send an invalid output along the stream
+    let mut transaction_output_with_proof = TransactionOutputListWithProof::new_empty();
+    transaction_output_with_proof.first_transaction_output_version =
+        Some(current_synced_version - 1);
+    let data_notification = DataNotification {
+        notification_id,
+        data_payload: DataPayload::ContinuousTransactionOutputsWithProof(
+            create_epoch_ending_ledger_info(),
+            transaction_output_with_proof,
+        ),
+    };
+    notification_sender.push((), data_notification).unwrap();
+
+    // Drive progress and verify the notification isn't pulled while paused
+    for _ in 0..3 {
+        continuous_syncer.drive_progress(None, true).await.unwrap();
+        assert!(continuous_syncer.has_active_stream());
+    }
+
+    // Resume the storage synchronizer and verify the notification is now processed
+    backpressure_notifier
+        .send(BackpressureState::Accepting)
+        .unwrap();
+    let error = continuous_syncer
+        .drive_progress(None, true)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+}
+
 #[tokio::test]
 async fn test_data_stream_transactions_with_target() {
     // Create test data
@@ -155,12 +243,9 @@ async fn test_data_stream_transactions_with_target() {
     );
 
     // Drive progress to initialize the transaction output stream
-    let (consensus_sync_notification, _) = ConsensusSyncNotification::new(target_ledger_info);
-    let sync_request = Arc::new(Mutex::new(Some(ConsensusSyncRequest::new(
-        consensus_sync_notification,
-    ))));
+    let sync_request = Some(target_ledger_info);
     continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), true)
         .await
         .unwrap();
 
@@ -176,14 +261,14 @@ async fn test_data_stream_transactions_with_target() {
 
     // Drive progress again and ensure we get a verification error
     let error = continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), true)
         .await
         .unwrap_err();
     assert_matches!(error, Error::VerificationError(_));
 
     // Drive progress to initialize the transaction output stream
     continuous_syncer
-        .drive_progress(sync_request.clone())
+        .drive_progress(sync_request.clone(), true)
         .await
         .unwrap();
 }
@@ -235,11 +320,7 @@ async fn test_data_stream_transaction_outputs() {
     );
 
     // Drive progress to initialize the transaction output stream
-    let no_sync_request = Arc::new(Mutex::new(None));
-    continuous_syncer
-        .drive_progress(no_sync_request.clone())
-        .await
-        .unwrap();
+    continuous_syncer.drive_progress(None, true).await.unwrap();
 
     // Send an invalid output along the stream
     let mut transaction_output_with_proof = TransactionOutputListWithProof::new_empty();
@@ -256,16 +337,377 @@ async fn test_data_stream_transaction_outputs() {
 
     // Drive progress again and ensure we get a verification error
     let error = continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(None, true)
         .await
         .unwrap_err();
     assert_matches!(error, Error::VerificationError(_));
 
     // Drive progress to initialize the transaction output stream
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_data_stream_with_in_flight_data() {
+    // Create test data
+    let current_synced_epoch = 3;
+    let current_synced_version = 50;
+    let highest_version_in_flight = 60;
+
+    // Create a driver configuration
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ApplyTransactionOutputs;
+
+    // Create the mock streaming client and expect the stream to start after the in-flight data
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (_notification_sender, data_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .times(1)
+        .with(
+            eq(highest_version_in_flight),
+            eq(current_synced_epoch),
+            eq(None),
+        )
+        .return_once(move |_, _, _| Ok(data_stream_listener));
+
+    // Create a mock storage synchronizer that receives new data between the
+    // idle check and the computation of the next version to request.
+    let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
+    let mut expectation_sequence = Sequence::new();
+    mock_storage_synchronizer
+        .expect_pending_storage_data()
+        .times(1)
+        .return_const(PendingDataSummary::default())
+        .in_sequence(&mut expectation_sequence);
+    mock_storage_synchronizer
+        .expect_pending_storage_data()
+        .times(1)
+        .return_const(PendingDataSummary {
+            bytes_queued: 1024,
+            chunks_in_flight: 1,
+            chunks_queued_for_execution: 1,
+            highest_version_in_flight: Some(highest_version_in_flight),
+            ..Default::default()
+        })
+        .in_sequence(&mut expectation_sequence);
+    mock_storage_synchronizer
+        .expect_reset_chunk_executor()
+        .return_const(Ok(()));
+
+    // Create the continuous syncer
+    let (_, backpressure_listener) = new_backpressure_channel();
+    let mut continuous_syncer = create_continuous_syncer_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        mock_storage_synchronizer,
+        current_synced_version,
+        current_synced_epoch,
+        backpressure_listener,
+        create_output_fallback_handler(),
+    );
+
+    // Drive progress and verify the stream is initialized at the highest in-flight version
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert!(continuous_syncer.has_active_stream());
+}
+
+#[tokio::test]
+async fn test_switch_continuous_syncing_mode() {
+    // Create test data
+    let current_synced_epoch = 10;
+    let current_synced_version = 500;
+
+    // Create a driver configuration
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ApplyTransactionOutputs;
+
+    // Create the mock streaming client (that expects an output and a transaction stream)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let mut expectation_sequence = Sequence::new();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let (_notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .times(1)
+        .with(
+            eq(current_synced_version),
+            eq(current_synced_epoch),
+            eq(None),
+        )
+        .return_once(move |_, _, _| Ok(data_stream_listener_1))
+        .in_sequence(&mut expectation_sequence);
+    mock_streaming_client
+        .expect_continuously_stream_transactions()
+        .times(1)
+        .with(
+            eq(current_synced_version),
+            eq(current_synced_epoch),
+            eq(false),
+            eq(None),
+        )
+        .return_once(move |_, _, _, _| Ok(data_stream_listener_2))
+        .in_sequence(&mut expectation_sequence);
+
+    // Create the continuous syncer
+    let mut continuous_syncer = create_continuous_syncer(
+        driver_configuration,
+        mock_streaming_client,
+        true,
+        current_synced_version,
+        current_synced_epoch,
+    );
+
+    // Drive progress to initialize the transaction output stream
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert!(continuous_syncer.has_active_stream());
+
+    // Switch to the same mode and verify the output stream is still active
+    let previous_mode = continuous_syncer
+        .set_continuous_syncing_mode(ContinuousSyncingMode::ApplyTransactionOutputs);
+    assert_eq!(
+        previous_mode,
+        ContinuousSyncingMode::ApplyTransactionOutputs
+    );
+    assert!(continuous_syncer.has_active_stream());
+
+    // Switch to executing transactions and verify the output stream is reset
+    let previous_mode =
+        continuous_syncer.set_continuous_syncing_mode(ContinuousSyncingMode::ExecuteTransactions);
+    assert_eq!(
+        previous_mode,
+        ContinuousSyncingMode::ApplyTransactionOutputs
+    );
+    assert_eq!(
+        continuous_syncer.get_continuous_syncing_mode(),
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+    assert!(!continuous_syncer.has_active_stream());
+
+    // Drive progress and verify a transaction stream is initialized
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert!(continuous_syncer.has_active_stream());
+}
+
+#[tokio::test]
+async fn test_output_fallback_mode() {
+    // Create test data
+    let current_synced_epoch = 10;
+    let current_synced_version = 500;
+    let failed_version_range = (501, 600);
+
+    // Create a driver configuration that falls back after two execution failures
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ExecuteTransactions;
+    driver_configuration
+        .config
+        .max_execution_failures_before_fallback = 2;
+    driver_configuration.config.output_fallback_cooldown_secs = 60;
+
+    // Create the mock streaming client (that expects a transaction stream, an
+    // output stream and then a transaction stream again).
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let mut expectation_sequence = Sequence::new();
+    let (_notification_sender_1, data_stream_listener_1) = create_data_stream_listener();
+    let (_notification_sender_2, data_stream_listener_2) = create_data_stream_listener();
+    let (_notification_sender_3, data_stream_listener_3) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transactions()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(data_stream_listener_1))
+        .in_sequence(&mut expectation_sequence);
+    mock_streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .times(1)
+        .with(
+            eq(current_synced_version),
+            eq(current_synced_epoch),
+            eq(None),
+        )
+        .return_once(move |_, _, _| Ok(data_stream_listener_2))
+        .in_sequence(&mut expectation_sequence);
+    mock_streaming_client
+        .expect_continuously_stream_transactions()
+        .times(1)
+        .return_once(move |_, _, _, _| Ok(data_stream_listener_3))
+        .in_sequence(&mut expectation_sequence);
+
+    // Create the continuous syncer with an output fallback handler
+    let time_service = TimeService::mock();
+    let output_fallback_handler = OutputFallbackHandler::new(
+        driver_configuration.config,
+        DriverMetrics::default(),
+        time_service.clone(),
+    );
+    let (_, backpressure_listener) = new_backpressure_channel();
+    let mut continuous_syncer = create_continuous_syncer_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        create_ready_storage_synchronizer(true),
+        current_synced_version,
+        current_synced_epoch,
+        backpressure_listener,
+        output_fallback_handler.clone(),
+    );
+
+    // Drive progress to initialize the transaction stream
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert!(continuous_syncer.has_active_stream());
+
+    // Simulate a single execution failure and verify there's no fallback
+    output_fallback_handler.record_execution_failure(failed_version_range);
+    assert!(!output_fallback_handler.in_fallback_mode());
+
+    // Simulate a repeated execution failure and verify an output stream is initialized
+    output_fallback_handler.record_execution_failure(failed_version_range);
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert_eq!(
+        continuous_syncer.get_continuous_syncing_mode(),
+        ContinuousSyncingMode::ApplyTransactionOutputs
+    );
+    assert!(continuous_syncer.has_active_stream());
+
+    // Elapse the cool-down and verify a transaction stream is initialized again
+    time_service.into_mock().advance_secs(60);
+    continuous_syncer.drive_progress(None, true).await.unwrap();
+    assert_eq!(
+        continuous_syncer.get_continuous_syncing_mode(),
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+    assert!(continuous_syncer.has_active_stream());
+}
+
+#[tokio::test]
+async fn test_epoch_chase_future_sync_target() {
+    // Create test data (the sync target is two epochs ahead)
+    let current_synced_epoch = 5;
+    let current_synced_version = 1000;
+    let validator_signers = create_validator_signers(0);
+    let sync_target = create_signed_ledger_info(&validator_signers, 2000, current_synced_epoch + 2);
+
+    // Create a driver configuration
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.continuous_syncing_mode =
+        ContinuousSyncingMode::ExecuteTransactions;
+
+    // Create the mock streaming client (expect an epoch ending stream, followed
+    // by a transaction stream to the verified target).
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let mut expectation_sequence = Sequence::new();
+    let (notification_sender, epoch_ending_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(current_synced_epoch))
+        .return_once(move |_| Ok(epoch_ending_stream_listener))
+        .in_sequence(&mut expectation_sequence);
+    let (_notification_sender, transaction_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_continuously_stream_transactions()
+        .times(1)
+        .with(
+            eq(current_synced_version),
+            eq(current_synced_epoch),
+            eq(false),
+            eq(Some(sync_target.clone())),
+        )
+        .return_once(move |_, _, _, _| Ok(transaction_stream_listener))
+        .in_sequence(&mut expectation_sequence);
+
+    // Create the continuous syncer
+    let mut continuous_syncer = create_continuous_syncer(
+        driver_configuration,
+        mock_streaming_client,
+        true,
+        current_synced_version,
+        current_synced_epoch,
+    );
+
+    // Drive progress to initialize the epoch ending stream
+    continuous_syncer
+        .drive_progress(Some(sync_target.clone()), false)
+        .await
+        .unwrap();
+    assert!(continuous_syncer.take_verified_sync_target().is_none());
+
+    // Send the epoch changes (up to the target epoch) along the stream
+    send_epoch_ending_ledger_infos(
+        &notification_sender,
+        current_synced_epoch,
+        current_synced_version,
+        &validator_signers,
+    );
+
+    // Drive progress again and verify the target is verified
+    continuous_syncer
+        .drive_progress(Some(sync_target.clone()), false)
+        .await
+        .unwrap();
+    assert_eq!(
+        continuous_syncer.take_verified_sync_target(),
+        Some(sync_target.clone())
+    );
+    assert!(!continuous_syncer.has_active_stream());
+
+    // Drive progress to initialize the transaction stream to the (verified) target
     continuous_syncer
-        .drive_progress(no_sync_request.clone())
+        .drive_progress(Some(sync_target), true)
         .await
         .unwrap();
+    assert!(continuous_syncer.has_active_stream());
+}
+
+#[tokio::test]
+async fn test_epoch_chase_forged_sync_target() {
+    // Create test data (the sync target is two epochs ahead, but forged)
+    let current_synced_epoch = 10;
+    let current_synced_version = 500;
+    let validator_signers = create_validator_signers(0);
+    let forged_signers = create_validator_signers(100);
+    let sync_target = create_signed_ledger_info(&forged_signers, 900, current_synced_epoch + 2);
+
+    // Create the mock streaming client (expect only an epoch ending stream)
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let (notification_sender, epoch_ending_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(current_synced_epoch))
+        .return_once(move |_| Ok(epoch_ending_stream_listener));
+
+    // Create the continuous syncer
+    let mut continuous_syncer = create_continuous_syncer(
+        create_full_node_driver_configuration(),
+        mock_streaming_client,
+        true,
+        current_synced_version,
+        current_synced_epoch,
+    );
+
+    // Drive progress to initialize the epoch ending stream
+    continuous_syncer
+        .drive_progress(Some(sync_target.clone()), false)
+        .await
+        .unwrap();
+
+    // Send the epoch changes (up to the target epoch) along the stream
+    send_epoch_ending_ledger_infos(
+        &notification_sender,
+        current_synced_epoch,
+        current_synced_version,
+        &validator_signers,
+    );
+
+    // Drive progress again and verify the target fails verification
+    let error = continuous_syncer
+        .drive_progress(Some(sync_target), false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidSyncTarget(_));
+    assert!(continuous_syncer.take_verified_sync_target().is_none());
+    assert!(!continuous_syncer.has_active_stream());
 }
 
 /// Creates a continuous syncer for testing
@@ -275,13 +717,32 @@ fn create_continuous_syncer(
     expect_reset_executor: bool,
     synced_version: Version,
     current_epoch: Epoch,
+) -> ContinuousSyncer<MockStorageSynchronizer, MockStreamingClient> {
+    let (_, backpressure_listener) = new_backpressure_channel();
+    create_continuous_syncer_with_storage_synchronizer(
+        driver_configuration,
+        mock_streaming_client,
+        create_ready_storage_synchronizer(expect_reset_executor),
+        synced_version,
+        current_epoch,
+        backpressure_listener,
+        create_output_fallback_handler(),
+    )
+}
+
+/// Creates a continuous syncer (that uses the given storage synchronizer) for testing
+fn create_continuous_syncer_with_storage_synchronizer(
+    driver_configuration: DriverConfiguration,
+    mock_streaming_client: MockStreamingClient,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    synced_version: Version,
+    current_epoch: Epoch,
+    backpressure_listener: BackpressureListener,
+    output_fallback_handler: OutputFallbackHandler,
 ) -> ContinuousSyncer<MockStorageSynchronizer, MockStreamingClient> {
     // Initialize the logger for tests
     aptos_logger::Logger::init_for_testing();
 
-    // Create the mock storage synchronizer
-    let mock_storage_synchronizer = create_ready_storage_synchronizer(expect_reset_executor);
-
     // Create the mock db reader with the given synced version
     let mut mock_database_reader = create_mock_db_reader();
     mock_database_reader
@@ -292,9 +753,63 @@ fn create_continuous_syncer(
         .returning(move || Ok(create_epoch_state(current_epoch)));
 
     ContinuousSyncer::new(
+        backpressure_listener,
+        CommitStateTracker::default(),
         driver_configuration,
+        output_fallback_handler,
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
     )
 }
+
+/// Creates an epoch ending ledger info for the given epoch, with the specified next epoch state
+fn create_epoch_ending_ledger_info_for_epoch(
+    epoch: Epoch,
+    version: Version,
+    next_epoch_state: EpochState,
+) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::random(),
+        version,
+        0,
+        Some(next_epoch_state),
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
+
+/// Sends the epoch ending ledger infos for two consecutive epochs (starting at
+/// the current epoch) along the stream. The last epoch change moves to the
+/// epoch governed by the given validator signers.
+fn send_epoch_ending_ledger_infos(
+    notification_sender: &Sender<(), DataNotification>,
+    current_epoch: Epoch,
+    current_version: Version,
+    validator_signers: &[ValidatorSigner],
+) {
+    for (index, epoch) in (current_epoch..current_epoch + 2).enumerate() {
+        let next_verifier = if epoch == current_epoch {
+            ValidatorVerifier::new(vec![])
+        } else {
+            generate_validator_verifier(validator_signers)
+        };
+        let next_epoch_state = EpochState {
+            epoch: epoch + 1,
+            verifier: next_verifier,
+        };
+        let epoch_ending_ledger_info = create_epoch_ending_ledger_info_for_epoch(
+            epoch,
+            current_version + (index as u64 * 100),
+            next_epoch_state,
+        );
+        let data_notification = DataNotification {
+            notification_id: index as u64,
+            data_payload: DataPayload::EpochEndingLedgerInfos(vec![epoch_ending_ledger_info]),
+        };
+        notification_sender.push((), data_notification).unwrap();
+    }
+}
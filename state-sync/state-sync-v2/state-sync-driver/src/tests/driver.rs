@@ -3,13 +3,23 @@
 
 use crate::metadata_storage::PersistentMetadataStorage;
 use crate::{
+    bootstrapper::BootstrappingPhase,
+    driver_client::{BootstrapWaitResult, DriverClient, DriverNotification, SyncStatus},
     driver_factory::DriverFactory,
-    tests::utils::{
-        create_event, create_ledger_info_at_version, create_transaction,
-        verify_mempool_and_event_notification,
+    error::Error,
+    metrics,
+    notification_handlers::CommitStateTracker,
+    storage_synchronizer::ShutdownSummary,
+    test_utils::fake_storage::FakeStorage,
+    tests::{
+        driver_harness::{create_bootstrapped_validator_harness, DriverHarnessBuilder},
+        utils::{
+            create_epoch_ending_waypoint, create_event, create_ledger_info_at_version,
+            create_transaction, verify_mempool_and_event_notification,
+        },
     },
 };
-use aptos_config::config::{NodeConfig, RoleType};
+use aptos_config::config::{ContinuousSyncingMode, NodeConfig, RoleType};
 use aptos_data_client::aptosnet::AptosNetDataClient;
 use aptos_infallible::RwLock;
 use aptos_time_service::TimeService;
@@ -21,7 +31,7 @@ use aptos_types::{
 };
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
-use claim::{assert_err, assert_none};
+use claim::{assert_err, assert_matches, assert_none, assert_ok};
 use consensus_notifications::{ConsensusNotificationSender, ConsensusNotifier};
 use data_streaming_service::streaming_client::new_streaming_service_client_listener_pair;
 use event_notifications::{
@@ -29,44 +39,455 @@ use event_notifications::{
 };
 use executor::chunk_executor::ChunkExecutor;
 use executor_test_helpers::bootstrap_genesis;
-use futures::{FutureExt, StreamExt};
+use executor_types::ChunkExecutorTrait;
+use futures::{channel::mpsc, FutureExt, StreamExt};
 use mempool_notifications::MempoolNotificationListener;
 use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{atomic::AtomicU64, Arc},
+    time::{Duration, Instant},
+};
 use storage_interface::DbReaderWriter;
 use storage_service_client::StorageServiceClient;
 
 // TODO(joshlind): extend these tests to cover more functionality!
 
-#[tokio::test(flavor = "multi_thread")]
+#[tokio::test]
 async fn test_auto_bootstrapping() {
     // Create a driver for a validator with a waypoint at version 0
-    let (validator_driver, _, _, _, _) = create_validator_driver(None).await;
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::Validator).build().await;
 
-    // Wait until the validator is bootstrapped (auto-bootstrapping should occur)
-    let driver_client = validator_driver.create_driver_client();
+    // Verify the validator doesn't bootstrap before the connection deadline
+    let connection_deadline = Duration::from_secs(
+        NodeConfig::default()
+            .state_sync
+            .state_sync_driver
+            .max_connection_deadline_secs,
+    );
+    driver_harness
+        .advance_time(connection_deadline - Duration::from_secs(1))
+        .await;
+    assert!(!driver_harness.driver_client().is_bootstrapped());
+
+    // Pass the connection deadline and verify auto-bootstrapping occurs
+    driver_harness.advance_time(Duration::from_secs(1)).await;
+    assert!(driver_harness.driver_client().is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_driver_shutdown() {
+    // Create a bootstrapped validator (with no pending data to drain)
+    let shutdown_summary = ShutdownSummary {
+        chunks_committed: 0,
+        chunks_discarded: 0,
+        drained: true,
+    };
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .with_shutdown_summary(shutdown_summary)
+        .build()
+        .await;
+
+    // Shut down the driver and verify the storage synchronizer was drained
+    let driver_client = driver_harness.driver_client();
+    assert!(driver_client.is_bootstrapped());
+    assert_eq!(driver_client.shutdown().await.unwrap(), shutdown_summary);
+
+    // Verify the driver no longer handles client notifications
+    assert_err!(driver_client.notify_once_bootstrapped().await);
+}
+
+#[tokio::test]
+async fn test_client_sync_request_full_node_not_bootstrapped() {
+    // Create a driver for a full node (that has no peers to bootstrap from)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Verify the client sync request is rejected (the node hasn't bootstrapped)
+    let error = driver_harness
+        .driver_client()
+        .sync_to_version(10, Some(create_ledger_info_at_version(10)))
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BootstrapNotComplete(_));
+}
+
+#[tokio::test]
+async fn test_client_sync_request_validator_rejected() {
+    // Create a bootstrapped validator
+    let driver_harness = create_bootstrapped_validator_harness().await;
+
+    // Verify the client sync request is rejected (consensus is executing)
+    let error = driver_harness
+        .driver_client()
+        .sync_to_version(10, Some(create_ledger_info_at_version(10)))
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::ConsensusIsExecuting(_));
+}
+
+#[test]
+fn test_sync_status_serde() {
+    // Create a sync status
+    let sync_status = create_sync_status();
+
+    // Verify the sync status survives a serialization round-trip
+    let serialized_sync_status = bcs::to_bytes(&sync_status).unwrap();
+    let deserialized_sync_status: SyncStatus = bcs::from_bytes(&serialized_sync_status).unwrap();
+    assert_eq!(deserialized_sync_status, sync_status);
+}
+
+#[tokio::test]
+async fn test_get_sync_status_timeout() {
+    // Create a driver client for a driver that never responds (i.e., is wedged)
+    let (notification_sender, _notification_receiver) = mpsc::channel(10);
+    let driver_client = DriverClient::new(
+        CommitStateTracker::new(TimeService::mock()),
+        Arc::new(AtomicU64::new(0)),
+        notification_sender,
+    );
+
+    // Verify the sync status request times out
+    let error = driver_client
+        .get_sync_status(Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert_eq!(error, Error::SyncStatusTimeout(100));
+}
+
+#[tokio::test]
+async fn test_driver_client_driver_gone() {
+    // Create a driver client for a driver that has stopped
+    let (notification_sender, notification_receiver) = mpsc::channel(10);
+    drop(notification_receiver);
+    let driver_client = DriverClient::new(
+        CommitStateTracker::new(TimeService::mock()),
+        Arc::new(AtomicU64::new(0)),
+        notification_sender,
+    );
+
+    // Verify all client requests fail (instead of hanging)
+    let (commit_event_sender, _) = mpsc::channel(10);
+    let errors = vec![
+        driver_client.check_progress_now().await.unwrap_err(),
+        driver_client
+            .get_sync_status(Duration::from_secs(60))
+            .await
+            .unwrap_err(),
+        driver_client.notify_once_bootstrapped().await.unwrap_err(),
+        driver_client.shutdown().await.unwrap_err(),
+        driver_client
+            .subscribe_to_commit_events(commit_event_sender)
+            .await
+            .unwrap_err(),
+        driver_client.sync_to_version(10, None).await.unwrap_err(),
+    ];
+    for error in errors {
+        assert_matches!(error, Error::DriverUnavailable(_));
+    }
+    assert_eq!(
+        driver_client
+            .notify_once_bootstrapped_with_timeout(Duration::from_secs(60))
+            .await,
+        BootstrapWaitResult::DriverGone
+    );
+}
+
+#[tokio::test]
+async fn test_driver_client_blocking_calls_in_async_context() {
+    // Create a driver client for a stub driver
+    let (notification_sender, mut notification_receiver) = mpsc::channel(10);
+    let driver_client = DriverClient::new(
+        CommitStateTracker::new(TimeService::mock()),
+        Arc::new(AtomicU64::new(0)),
+        notification_sender,
+    );
+
+    // Verify the blocking calls fail (instead of deadlocking) within tokio
+    let timeout = Duration::from_secs(60);
+    let errors = vec![
+        driver_client.blocking_check_progress_now().unwrap_err(),
+        driver_client.blocking_get_sync_status(timeout).unwrap_err(),
+        driver_client.blocking_shutdown().unwrap_err(),
+        driver_client
+            .blocking_sync_to_version(10, None)
+            .unwrap_err(),
+        driver_client
+            .blocking_wait_for_bootstrap(timeout)
+            .unwrap_err(),
+        driver_client
+            .blocking_wait_for_version(10, timeout)
+            .unwrap_err(),
+    ];
+    for error in errors {
+        assert_matches!(error, Error::BlockingCallInAsyncContext(_));
+    }
+
+    // Verify no requests were sent to the driver
+    assert!(notification_receiver.next().now_or_never().is_none());
+}
+
+#[tokio::test]
+async fn test_driver_client_request_round_trips() {
+    // Create a driver client for a stub driver that answers every request
+    let (notification_sender, mut notification_receiver) = mpsc::channel(10);
+    let driver_client = DriverClient::new(
+        CommitStateTracker::new(TimeService::mock()),
+        Arc::new(AtomicU64::new(0)),
+        notification_sender,
+    );
+    let sync_status = create_sync_status();
+    let shutdown_summary = ShutdownSummary {
+        chunks_committed: 5,
+        chunks_discarded: 2,
+        drained: true,
+    };
+    let expected_sync_status = sync_status.clone();
+    tokio::spawn(async move {
+        while let Some(notification) = notification_receiver.next().await {
+            match notification {
+                DriverNotification::CheckProgressNow(envelope) => {
+                    let _ = envelope.response_sender.send(());
+                }
+                DriverNotification::GetContinuousSyncMode(envelope) => {
+                    let _ = envelope
+                        .response_sender
+                        .send(ContinuousSyncingMode::ExecuteTransactions);
+                }
+                DriverNotification::GetSyncStatus(envelope) => {
+                    let _ = envelope.response_sender.send(Ok(sync_status.clone()));
+                }
+                DriverNotification::NotifyOnceBootstrapped(envelope) => {
+                    let _ = envelope.response_sender.send(Ok(()));
+                }
+                DriverNotification::NotifyOnceSyncedToVersion(envelope) => {
+                    let ledger_info = create_ledger_info_at_version(envelope.request.version);
+                    let _ = envelope.response_sender.send(Ok(ledger_info));
+                }
+                DriverNotification::Ping(envelope) => {
+                    let _ = envelope.response_sender.send(());
+                }
+                DriverNotification::SetContinuousSyncMode(envelope) => {
+                    let error = Error::ModeSwitchRejected(format!(
+                        "Switch to mode: {:?}",
+                        envelope.request.mode
+                    ));
+                    let _ = envelope.response_sender.send(Err(error));
+                }
+                DriverNotification::Shutdown(envelope) => {
+                    let _ = envelope.response_sender.send(shutdown_summary);
+                }
+                DriverNotification::SubscribeToCommitEvents(envelope) => {
+                    let _ = envelope.response_sender.send(());
+                }
+                DriverNotification::SyncToVersion(envelope) => {
+                    let error = Error::ConsensusIsExecuting(format!(
+                        "Sync request for version: {:?}",
+                        envelope.request.target_version
+                    ));
+                    let _ = envelope.response_sender.send(Err(error));
+                }
+                DriverNotification::UnsubscribeFromBootstrap(envelope) => {
+                    let _ = envelope.response_sender.send(true);
+                }
+                DriverNotification::UnsubscribeFromSyncedVersion(envelope) => {
+                    let _ = envelope.response_sender.send(true);
+                }
+            }
+        }
+    });
+
+    // Verify each client request receives the response of the driver
+    driver_client.check_progress_now().await.unwrap();
+    assert_eq!(
+        driver_client.get_continuous_sync_mode().await.unwrap(),
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+    assert_eq!(
+        driver_client
+            .get_sync_status(Duration::from_secs(10))
+            .await
+            .unwrap(),
+        expected_sync_status
+    );
     driver_client.notify_once_bootstrapped().await.unwrap();
+    assert_eq!(
+        driver_client
+            .notify_once_bootstrapped_with_timeout(Duration::from_secs(10))
+            .await,
+        BootstrapWaitResult::Bootstrapped
+    );
+    assert_eq!(
+        driver_client
+            .set_continuous_sync_mode(ContinuousSyncingMode::ApplyTransactionOutputs)
+            .await
+            .unwrap_err(),
+        Error::ModeSwitchRejected("Switch to mode: ApplyTransactionOutputs".into())
+    );
+    let (commit_event_sender, _) = mpsc::channel(10);
+    driver_client
+        .subscribe_to_commit_events(commit_event_sender)
+        .await
+        .unwrap();
+    assert_eq!(
+        driver_client.sync_to_version(10, None).await.unwrap_err(),
+        Error::ConsensusIsExecuting("Sync request for version: 10".into())
+    );
+    let ledger_info = driver_client
+        .wait_for_version(10, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(ledger_info.ledger_info().version(), 10);
+    assert_eq!(driver_client.shutdown().await.unwrap(), shutdown_summary);
+}
+
+#[tokio::test]
+async fn test_notify_once_bootstrapped_fast_path() {
+    // Create a driver client for a bootstrapped node (whose driver has stopped)
+    let commit_state_tracker = CommitStateTracker::new(TimeService::mock());
+    commit_state_tracker.set_bootstrapped();
+    let (notification_sender, notification_receiver) = mpsc::channel(10);
+    drop(notification_receiver);
+    let driver_client = DriverClient::new(
+        commit_state_tracker,
+        Arc::new(AtomicU64::new(0)),
+        notification_sender,
+    );
+
+    // Verify the bootstrap waits complete without contacting the driver
+    assert!(driver_client.is_bootstrapped());
+    driver_client.notify_once_bootstrapped().await.unwrap();
+    assert_eq!(
+        driver_client
+            .notify_once_bootstrapped_with_timeout(Duration::from_secs(60))
+            .await,
+        BootstrapWaitResult::Bootstrapped
+    );
+}
+
+#[tokio::test]
+async fn test_notify_once_bootstrapped_timeout() {
+    // Create a driver for a full node (that has no peers to bootstrap from)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Verify the bootstrap wait times out
+    let wait_result = driver_harness
+        .driver_client()
+        .notify_once_bootstrapped_with_timeout(Duration::from_millis(100))
+        .await;
+    assert_eq!(wait_result, BootstrapWaitResult::TimedOut);
+}
+
+#[tokio::test]
+async fn test_notify_once_bootstrapped_with_timeout() {
+    // Create a driver for a validator with a waypoint at version 0
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::Validator).build().await;
+
+    // Start waiting for the validator to bootstrap
+    let driver_client = driver_harness.driver_client().clone();
+    let bootstrap_wait = tokio::spawn(async move {
+        driver_client
+            .notify_once_bootstrapped_with_timeout(Duration::from_secs(60))
+            .await
+    });
+
+    // Pass the connection deadline and verify the validator bootstraps
+    // before the timeout elapses.
+    let max_connection_deadline_secs = NodeConfig::default()
+        .state_sync
+        .state_sync_driver
+        .max_connection_deadline_secs;
+    driver_harness
+        .advance_time(Duration::from_secs(max_connection_deadline_secs))
+        .await;
+    let wait_result = bootstrap_wait.await.unwrap();
+    assert_eq!(wait_result, BootstrapWaitResult::Bootstrapped);
+    let driver_client = driver_harness.driver_client();
+
+    // Verify any new waits are notified immediately
+    let wait_result = driver_client
+        .notify_once_bootstrapped_with_timeout(Duration::from_millis(100))
+        .await;
+    assert_eq!(wait_result, BootstrapWaitResult::Bootstrapped);
+}
+
+#[tokio::test]
+async fn test_notify_once_bootstrapped_driver_shutdown() {
+    // Create a driver for a full node (that has no peers to bootstrap from)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Start waiting for the node to bootstrap
+    let driver_client = driver_harness.driver_client();
+    let bootstrap_wait =
+        driver_client.notify_once_bootstrapped_with_timeout(Duration::from_secs(60));
+
+    // Shut down the driver and verify the wait ends (without timing out)
+    driver_client.shutdown().await.unwrap();
+    let wait_result = timeout(Duration::from_secs(10), bootstrap_wait)
+        .await
+        .unwrap();
+    assert_eq!(wait_result, BootstrapWaitResult::DriverGone);
 }
 
 #[tokio::test]
 async fn test_consensus_commit_notification() {
     // Create a driver for a full node
-    let (_full_node_driver, consensus_notifier, _, _, _) = create_full_node_driver(None).await;
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
 
     // Verify that full nodes can't process commit notifications
-    let result = consensus_notifier
+    let result = driver_harness
         .notify_new_commit(vec![create_transaction()], vec![])
         .await;
     assert_err!(result);
 
     // Create a driver for a validator with a waypoint at version 0
-    let (_validator_driver, consensus_notifier, _, _, _) = create_validator_driver(None).await;
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator).build().await;
 
     // Send a new commit notification and verify the node isn't bootstrapped
-    let result = consensus_notifier
+    let result = driver_harness
         .notify_new_commit(vec![create_transaction()], vec![])
         .await;
     assert_err!(result);
+    assert!(driver_harness.get_mempool_notifications().is_empty());
+}
+
+#[tokio::test]
+async fn test_consensus_commit_notification_harness() {
+    // Create a bootstrapped validator
+    let driver_harness = create_bootstrapped_validator_harness().await;
+
+    // Commit several batches of transactions (as consensus would)
+    let transaction_batches = vec![
+        vec![create_transaction()],
+        vec![create_transaction(), create_transaction()],
+    ];
+    for transactions in transaction_batches.clone() {
+        assert_ok!(
+            driver_harness
+                .commit_transactions(transactions, vec![])
+                .await
+        );
+    }
+
+    // Verify mempool was notified of each batch (in order)
+    assert_eq!(
+        driver_harness.get_mempool_notifications(),
+        transaction_batches
+    );
+
+    // Verify the driver reports the new synced version
+    let sync_status = driver_harness
+        .driver_client()
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(sync_status.synced_version, Some(3));
+
+    // Verify consensus commits bypass the chunk executor
+    assert!(driver_harness
+        .get_storage()
+        .get_committed_chunks()
+        .is_empty());
 }
 
 #[tokio::test]
@@ -110,6 +531,79 @@ async fn test_mempool_commit_notifications() {
     join_handle.await.unwrap();
 }
 
+#[tokio::test]
+async fn test_mempool_commit_notifications_fake_storage() {
+    // Create a fake storage backend and subscribe to events
+    let fake_storage = FakeStorage::new();
+    let db_rw = fake_storage.create_reader_writer();
+    let mut event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(db_rw.clone())),
+    );
+    let subscription_event_key = EventKey::random();
+    let mut event_listener = event_subscription_service
+        .subscribe_to_events(vec![subscription_event_key])
+        .unwrap();
+
+    // Create a driver for a validator (that auto-bootstraps immediately)
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::Validator;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .max_connection_deadline_secs = 0;
+    let metadata_path = aptos_temppath::TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let (validator_driver, consensus_notifier, mut mempool_listener) = spawn_driver(
+        &node_config,
+        create_epoch_ending_waypoint(),
+        db_rw,
+        fake_storage.create_chunk_executor(),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        event_subscription_service,
+    );
+
+    // Wait until the validator is bootstrapped
+    let driver_client = validator_driver.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Send a new consensus commit notification to the driver
+    let transactions = vec![create_transaction(), create_transaction()];
+    let events = vec![create_event(Some(subscription_event_key))];
+    let committed_transactions = transactions.clone();
+    let committed_events = events.clone();
+    let join_handle = tokio::spawn(async move {
+        consensus_notifier
+            .notify_new_commit(committed_transactions, committed_events)
+            .await
+            .unwrap();
+    });
+
+    // Verify mempool is notified and that the event listener is notified
+    verify_mempool_and_event_notification(
+        Some(&mut event_listener),
+        &mut mempool_listener,
+        transactions,
+        events,
+    )
+    .await;
+    join_handle.await.unwrap();
+
+    // Shut down the driver and verify there was no pending data to drain
+    let shutdown_summary = driver_client.shutdown().await.unwrap();
+    assert_eq!(
+        shutdown_summary,
+        ShutdownSummary {
+            chunks_committed: 0,
+            chunks_discarded: 0,
+            drained: true,
+        }
+    );
+
+    // Verify consensus commits bypass the chunk executor
+    assert!(fake_storage.get_committed_chunks().is_empty());
+}
+
 #[tokio::test]
 async fn test_reconfiguration_notifications() {
     // Create a driver for a validator with a waypoint at version 0
@@ -160,25 +654,274 @@ async fn test_reconfiguration_notifications() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_consensus_commit_early_ack_latency() {
+    // Measure the consensus ack latency (with a slow mempool) for both orderings
+    let mempool_delay = Duration::from_millis(500);
+    let conservative_ack_latency = measure_consensus_commit_ack_latency(false, mempool_delay).await;
+    let early_ack_latency = measure_consensus_commit_ack_latency(true, mempool_delay).await;
+
+    // Verify consensus only waits for mempool when commits aren't acknowledged early
+    assert!(conservative_ack_latency >= mempool_delay);
+    assert!(early_ack_latency < conservative_ack_latency);
+}
+
+#[tokio::test]
+async fn test_consensus_commit_early_ack_reconfiguration() {
+    // Create a driver for a validator that acknowledges consensus commits early
+    let (validator_driver, consensus_notifier, mut mempool_listener, mut reconfig_listener, _) =
+        create_validator_driver_with_early_acks().await;
+
+    // Wait until the validator is bootstrapped
+    let driver_client = validator_driver.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Send a new consensus commit notification with a reconfiguration event
+    let transactions = vec![create_transaction()];
+    let events = vec![create_event(Some(new_epoch_event_key()))];
+    let mut join_handle = tokio::spawn(async move {
+        consensus_notifier
+            .notify_new_commit(transactions, events)
+            .await
+            .unwrap();
+    });
+
+    // Verify the commit isn't acknowledged while mempool hasn't responded
+    let mempool_notification = mempool_listener.select_next_some().await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_none!((&mut join_handle).now_or_never());
+
+    // Respond to mempool and verify the reconfiguration is delivered before the ack
+    let _ = mempool_listener.ack_commit_notification(mempool_notification);
+    reconfig_listener.select_next_some().await;
+    join_handle.await.unwrap();
+}
+
 #[tokio::test]
 async fn test_consensus_sync_request() {
     // Create a driver for a full node
-    let (_full_node_driver, consensus_notifier, _, _, _) = create_full_node_driver(None).await;
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
 
     // Verify that full nodes can't process sync requests
-    let result = consensus_notifier
-        .sync_to_target(create_ledger_info_at_version(0))
-        .await;
-    assert_err!(result);
+    assert_err!(driver_harness.sync_to_target(0).await);
 
     // Create a driver for a validator with a waypoint at version 0
-    let (_validator_driver, consensus_notifier, _, _, _) = create_validator_driver(None).await;
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator).build().await;
 
     // Send a new sync request and verify the node isn't bootstrapped
-    let result = consensus_notifier
-        .sync_to_target(create_ledger_info_at_version(0))
-        .await;
-    assert_err!(result);
+    assert_err!(driver_harness.sync_to_target(0).await);
+}
+
+#[tokio::test]
+async fn test_consensus_sync_request_harness() {
+    // Create a bootstrapped validator
+    let mut driver_harness = create_bootstrapped_validator_harness().await;
+
+    // Verify a sync request for the synced version is answered immediately
+    assert_ok!(driver_harness.sync_to_target(0).await);
+
+    // Send a sync request for a future version and verify it remains active
+    // (across several progress checks).
+    let mut sync_request = driver_harness.spawn_sync_to_target(1000);
+    driver_harness.wait_for_sync_target(1000).await;
+    driver_harness.advance_time(Duration::from_secs(5)).await;
+    assert_none!((&mut sync_request).now_or_never());
+
+    // Send a sync request for the synced version and verify both requests are answered
+    assert_ok!(driver_harness.sync_to_target(0).await);
+    assert_err!(sync_request.await.unwrap());
+}
+
+#[tokio::test]
+async fn test_stale_consensus_sync_request() {
+    // Create a bootstrapped validator
+    let driver_harness = create_bootstrapped_validator_harness().await;
+
+    // Send a sync request for a future version and drop the response
+    // receiver (i.e., emulate consensus restarting mid-request).
+    let join_handle = driver_harness.spawn_sync_to_target(1000);
+    driver_harness.wait_for_sync_target(1000).await;
+    join_handle.abort();
+    assert!(join_handle.await.unwrap_err().is_cancelled());
+
+    // Send a new consensus notification and verify the stale request is purged
+    let driver_metrics = driver_harness.driver_metrics();
+    let num_stale_sync_requests =
+        driver_metrics.read_counter(&metrics::DRIVER_COUNTERS, metrics::STALE_SYNC_REQUEST);
+    assert_ok!(driver_harness.sync_to_target(0).await);
+    assert!(
+        driver_metrics.read_counter(&metrics::DRIVER_COUNTERS, metrics::STALE_SYNC_REQUEST)
+            > num_stale_sync_requests
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_switch_continuous_sync_mode() {
+    // Create a driver for a validator with a waypoint at version 0
+    let (validator_driver, consensus_notifier, _, _, _) = create_validator_driver(None).await;
+
+    // Wait until the validator is bootstrapped
+    let driver_client = validator_driver.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Switch the continuous syncing mode and verify the previous mode is returned
+    assert_eq!(
+        driver_client.get_continuous_sync_mode().await.unwrap(),
+        ContinuousSyncingMode::ApplyTransactionOutputs
+    );
+    let previous_mode = driver_client
+        .set_continuous_sync_mode(ContinuousSyncingMode::ExecuteTransactions)
+        .await
+        .unwrap();
+    assert_eq!(
+        previous_mode,
+        ContinuousSyncingMode::ApplyTransactionOutputs
+    );
+
+    // Verify the new mode is reported by the driver (and the sync status)
+    assert_eq!(
+        driver_client.get_continuous_sync_mode().await.unwrap(),
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+    let sync_status = driver_client
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(
+        sync_status.continuous_syncing_mode,
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+
+    // Send a sync request for a future version (that remains active)
+    let _join_handle = tokio::spawn(async move {
+        consensus_notifier
+            .sync_to_target(create_ledger_info_at_version(1000))
+            .await
+    });
+    loop {
+        let sync_status = driver_client
+            .get_sync_status(Duration::from_secs(10))
+            .await
+            .unwrap();
+        if sync_status.active_sync_target == Some(1000) {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    // Verify the mode can't be switched while the sync request is active
+    let error = driver_client
+        .set_continuous_sync_mode(ContinuousSyncingMode::ApplyTransactionOutputs)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::ModeSwitchRejected(_));
+    assert_eq!(
+        driver_client.get_continuous_sync_mode().await.unwrap(),
+        ContinuousSyncingMode::ExecuteTransactions
+    );
+}
+
+#[tokio::test]
+async fn test_wait_for_version() {
+    // Create a driver for a validator (that auto-bootstraps immediately) on fake storage
+    let fake_storage = FakeStorage::new();
+    let db_rw = fake_storage.create_reader_writer();
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(db_rw.clone())),
+    );
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::Validator;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .max_connection_deadline_secs = 0;
+    let metadata_path = aptos_temppath::TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let (validator_driver, consensus_notifier, mut mempool_listener) = spawn_driver(
+        &node_config,
+        create_epoch_ending_waypoint(),
+        db_rw,
+        fake_storage.create_chunk_executor(),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        event_subscription_service,
+    );
+
+    // Wait until the validator is bootstrapped
+    let driver_client = validator_driver.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Verify waiting for a version that has already been synced returns immediately
+    let ledger_info = driver_client
+        .wait_for_version(0, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(ledger_info.ledger_info().version(), 0);
+
+    // Verify waiting for a version that isn't synced in time fails
+    let error = driver_client
+        .wait_for_version(2, Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert_eq!(error, Error::VersionWaitTimeout(2, 100));
+
+    // Wait for a new version (while consensus commits it)
+    let transactions = vec![create_transaction(), create_transaction()];
+    let synced_ledger_info = create_ledger_info_at_version(2);
+    let (wait_result, _) = futures::join!(
+        driver_client.wait_for_version(1, Duration::from_secs(10)),
+        async {
+            // Verify the waiter is registered (the driver handles requests in order)
+            driver_client
+                .get_sync_status(Duration::from_secs(10))
+                .await
+                .unwrap();
+
+            // Commit the transactions (as consensus would) and notify the driver
+            fake_storage.commit_transactions(transactions.clone(), synced_ledger_info.clone());
+            let consensus_notifier = consensus_notifier.clone();
+            let committed_transactions = transactions.clone();
+            let join_handle = tokio::spawn(async move {
+                consensus_notifier
+                    .notify_new_commit(committed_transactions, vec![])
+                    .await
+                    .unwrap();
+            });
+            verify_mempool_and_event_notification(
+                None,
+                &mut mempool_listener,
+                transactions.clone(),
+                vec![],
+            )
+            .await;
+            join_handle.await.unwrap();
+        }
+    );
+
+    // Verify the waiter received the latest synced ledger info (covering the version)
+    assert_eq!(wait_result.unwrap(), synced_ledger_info);
+}
+
+#[tokio::test]
+async fn test_wait_for_version_before_bootstrapping() {
+    // Create a driver for a full node (that has no peers to bootstrap from)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Verify the node can wait for versions before bootstrapping
+    let driver_client = driver_harness.driver_client();
+    let ledger_info = driver_client
+        .wait_for_version(0, Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(ledger_info.ledger_info().version(), 0);
+    assert!(!driver_client.is_bootstrapped());
+
+    // Verify waiting for a version that isn't synced in time fails
+    let error = driver_client
+        .wait_for_version(10, Duration::from_millis(100))
+        .await
+        .unwrap_err();
+    assert_eq!(error, Error::VersionWaitTimeout(10, 100));
 }
 
 /// Creates a state sync driver for a validator node
@@ -194,13 +937,12 @@ async fn create_validator_driver(
     let mut node_config = NodeConfig::default();
     node_config.base.role = RoleType::Validator;
 
-    create_driver_for_tests(node_config, Waypoint::default(), event_key_subscriptions).await
+    create_driver_for_tests(node_config, event_key_subscriptions).await
 }
 
-/// Creates a state sync driver for a full node
-async fn create_full_node_driver(
-    event_key_subscriptions: Option<Vec<EventKey>>,
-) -> (
+/// Creates a state sync driver for a validator node that acknowledges
+/// consensus commits before notifying mempool and the event subscribers.
+async fn create_validator_driver_with_early_acks() -> (
     DriverFactory,
     ConsensusNotifier,
     MempoolNotificationListener,
@@ -208,15 +950,56 @@ async fn create_full_node_driver(
     EventNotificationListener,
 ) {
     let mut node_config = NodeConfig::default();
-    node_config.base.role = RoleType::FullNode;
+    node_config.base.role = RoleType::Validator;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .ack_consensus_commits_early = true;
 
-    create_driver_for_tests(node_config, Waypoint::default(), event_key_subscriptions).await
+    create_driver_for_tests(node_config, None).await
 }
 
-/// Creates a state sync driver using the given node config and waypoint
+/// Sends a consensus commit notification to a validator (while mempool is
+/// slow to respond) and returns the time taken for consensus to receive
+/// a response.
+async fn measure_consensus_commit_ack_latency(
+    ack_consensus_commits_early: bool,
+    mempool_delay: Duration,
+) -> Duration {
+    // Create a driver for a validator
+    let (validator_driver, consensus_notifier, mut mempool_listener, _, _) =
+        if ack_consensus_commits_early {
+            create_validator_driver_with_early_acks().await
+        } else {
+            create_validator_driver(None).await
+        };
+
+    // Wait until the validator is bootstrapped
+    let driver_client = validator_driver.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Send a new consensus commit notification to the driver
+    let join_handle = tokio::spawn(async move {
+        let start_time = Instant::now();
+        consensus_notifier
+            .notify_new_commit(vec![create_transaction()], vec![])
+            .await
+            .unwrap();
+        start_time.elapsed()
+    });
+
+    // Respond to mempool slowly
+    let mempool_notification = mempool_listener.select_next_some().await;
+    tokio::time::sleep(mempool_delay).await;
+    let _ = mempool_listener.ack_commit_notification(mempool_notification);
+
+    join_handle.await.unwrap()
+}
+
+/// Creates a state sync driver using the given node config (and a
+/// waypoint for the genesis ledger info)
 async fn create_driver_for_tests(
     node_config: NodeConfig,
-    waypoint: Waypoint,
     event_key_subscriptions: Option<Vec<EventKey>>,
 ) -> (
     DriverFactory,
@@ -234,6 +1017,8 @@ async fn create_driver_for_tests(
     let (genesis, _) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
     let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
     bootstrap_genesis::<AptosVM>(&db_rw, &genesis_txn).unwrap();
+    let genesis_ledger_info = db_rw.reader.get_latest_ledger_info().unwrap();
+    let waypoint = Waypoint::new_epoch_boundary(genesis_ledger_info.ledger_info()).unwrap();
 
     // Create the event subscription service and subscribe to events and reconfigurations
     let mut event_subscription_service = EventSubscriptionService::new(
@@ -249,15 +1034,53 @@ async fn create_driver_for_tests(
         .subscribe_to_events(event_key_subscriptions)
         .unwrap();
 
+    // Create the chunk executor and metadata storage
+    let chunk_executor = ChunkExecutor::<AptosVM>::new(db_rw.clone());
+    let metadata_storage = PersistentMetadataStorage::new(db_path.path());
+
+    // Create and spawn the driver
+    let (driver_factory, consensus_notifier, mempool_listener) = spawn_driver(
+        &node_config,
+        waypoint,
+        db_rw,
+        chunk_executor,
+        metadata_storage,
+        event_subscription_service,
+    );
+
+    // The driver will notify reconfiguration subscribers of the initial configs.
+    // Verify we've received this notification.
+    reconfiguration_subscriber.select_next_some().await;
+
+    (
+        driver_factory,
+        consensus_notifier,
+        mempool_listener,
+        reconfiguration_subscriber,
+        event_subscriber,
+    )
+}
+
+/// Creates and spawns a state sync driver (for the given node config and
+/// waypoint) that runs on top of the given storage and chunk executor.
+fn spawn_driver<ChunkExecutorType: ChunkExecutorTrait + 'static>(
+    node_config: &NodeConfig,
+    waypoint: Waypoint,
+    db_rw: DbReaderWriter,
+    chunk_executor: ChunkExecutorType,
+    metadata_storage: PersistentMetadataStorage,
+    event_subscription_service: EventSubscriptionService,
+) -> (
+    DriverFactory,
+    ConsensusNotifier,
+    MempoolNotificationListener,
+) {
     // Create consensus and mempool notifiers and listeners
     let (consensus_notifier, consensus_listener) =
         consensus_notifications::new_consensus_notifier_listener_pair(5000);
     let (mempool_notifier, mempool_listener) =
         mempool_notifications::new_mempool_notifier_listener_pair();
 
-    // Create the chunk executor
-    let chunk_executor = Arc::new(ChunkExecutor::<AptosVM>::new(db_rw.clone()));
-
     // Create a streaming service client
     let (streaming_service_client, _) = new_streaming_service_client_listener_pair();
 
@@ -275,33 +1098,39 @@ async fn create_driver_for_tests(
         None,
     );
 
-    // Create the metadata storage
-    let metadata_storage = PersistentMetadataStorage::new(db_path.path());
-
     // Create and spawn the driver
     let driver_factory = DriverFactory::create_and_spawn_driver(
         false,
-        &node_config,
+        node_config,
         waypoint,
         db_rw,
-        chunk_executor,
-        mempool_notifier,
+        Arc::new(chunk_executor),
+        Some(mempool_notifier),
         metadata_storage,
         consensus_listener,
         event_subscription_service,
         aptos_data_client,
         streaming_service_client,
-    );
+        vec![],
+    )
+    .unwrap();
 
-    // The driver will notify reconfiguration subscribers of the initial configs.
-    // Verify we've received this notification.
-    reconfiguration_subscriber.select_next_some().await;
+    (driver_factory, consensus_notifier, mempool_listener)
+}
 
-    (
-        driver_factory,
-        consensus_notifier,
-        mempool_listener,
-        reconfiguration_subscriber,
-        event_subscriber,
-    )
+/// Creates a sync status for a full node that is still bootstrapping
+fn create_sync_status() -> SyncStatus {
+    SyncStatus {
+        active_sync_target: Some(150),
+        advertised_version: Some(200),
+        bootstrapped: false,
+        bootstrapping_phase: BootstrappingPhase::SyncingTransactions,
+        continuous_syncing_mode: ContinuousSyncingMode::ExecuteTransactions,
+        lag_estimate: Some(100),
+        last_error_label: Some("verification_error".into()),
+        lowest_available_version: Some(0),
+        role: RoleType::FullNode,
+        synced_epoch: Some(3),
+        synced_version: Some(100),
+    }
 }
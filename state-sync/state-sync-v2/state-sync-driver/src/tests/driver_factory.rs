@@ -1,33 +1,70 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{driver_factory::DriverFactory, metadata_storage::PersistentMetadataStorage};
+use crate::{
+    bootstrapper::BootstrappingPhase,
+    driver_client::{BootstrapWaitResult, SyncStatus},
+    driver_factory::{DriverFactory, StateSyncRuntimeHandle},
+    error::Error,
+    metadata_storage::PersistentMetadataStorage,
+    metrics::{self, DriverMetrics},
+    storage_synchronizer::{CommittedVersionHandle, PendingDataSummary, ShutdownSummary},
+    test_utils::fake_storage::FakeStorage,
+    tests::{
+        driver_harness::DriverHarnessBuilder,
+        mocks::{
+            create_mock_aptos_data_client, create_mock_storage_synchronizer,
+            create_mock_streaming_client, create_ready_storage_synchronizer,
+            MockStorageSynchronizer, MockStreamingClient,
+        },
+        utils::{
+            create_empty_epoch_state, create_epoch_ending_waypoint, create_global_summary,
+            create_ledger_info_at_version, create_transaction,
+        },
+    },
+    utils,
+};
 use aptos_config::{
     config::{
-        RocksdbConfigs, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
+        BootstrappingMode, NodeConfig, RocksdbConfigs, RoleType,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, NO_OP_STORAGE_PRUNER_CONFIG,
         TARGET_SNAPSHOT_SIZE,
     },
     utils::get_genesis_txn,
 };
-use aptos_data_client::aptosnet::AptosNetDataClient;
+use aptos_data_client::{aptosnet::AptosNetDataClient, GlobalDataSummary};
 use aptos_genesis::test_utils::test_config;
 use aptos_infallible::RwLock;
 use aptos_temppath::TempPath;
 use aptos_time_service::TimeService;
-use aptos_types::on_chain_config::ON_CHAIN_CONFIG_REGISTRY;
+use aptos_types::{on_chain_config::ON_CHAIN_CONFIG_REGISTRY, waypoint::Waypoint};
 use aptos_vm::AptosVM;
 use aptosdb::AptosDB;
-use consensus_notifications::new_consensus_notifier_listener_pair;
-use data_streaming_service::streaming_client::new_streaming_service_client_listener_pair;
+use claim::{assert_err, assert_matches, assert_none, assert_ok, assert_some};
+use consensus_notifications::{
+    new_consensus_notifier_listener_pair, ConsensusNotificationListener,
+    ConsensusNotificationSender,
+};
+use data_streaming_service::streaming_client::{
+    new_streaming_service_client_listener_pair, GetAllEpochEndingLedgerInfosRequest, StreamRequest,
+};
 use event_notifications::EventSubscriptionService;
 use executor::chunk_executor::ChunkExecutor;
 use executor_test_helpers::bootstrap_genesis;
-use futures::{FutureExt, StreamExt};
+use futures::{channel::mpsc, future::join_all, FutureExt, StreamExt};
 use mempool_notifications::new_mempool_notifier_listener_pair;
 use network::application::{interface::MultiNetworkSender, storage::PeerMetadataStorage};
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 use storage_interface::DbReaderWriter;
 use storage_service_client::StorageServiceClient;
+use tokio::{
+    runtime::{Builder, Handle},
+    time::timeout,
+};
 
 #[test]
 fn test_new_initialized_configs() {
@@ -88,13 +125,15 @@ fn test_new_initialized_configs() {
         node_config.base.waypoint.waypoint(),
         db_rw,
         chunk_executor,
-        mempool_notifier,
+        Some(mempool_notifier),
         metadata_storage,
         consensus_listener,
         event_subscription_service,
         aptos_data_client,
         streaming_service_client,
-    );
+        vec![],
+    )
+    .unwrap();
 
     // Verify the initial configs were notified
     assert!(reconfiguration_subscriber
@@ -102,3 +141,948 @@ fn test_new_initialized_configs() {
         .now_or_never()
         .is_some());
 }
+
+#[test]
+fn test_new_missing_initial_configs() {
+    // Create a fake storage backend (that holds no on-chain configs)
+    let fake_storage = FakeStorage::new();
+
+    // Create the event subscription service and a reconfig subscriber
+    let mut event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(fake_storage.create_reader_writer())),
+    );
+    let _reconfiguration_subscriber = event_subscription_service
+        .subscribe_to_reconfigurations()
+        .unwrap();
+
+    // Create the state sync driver factory and verify the configs error is returned
+    let error = create_driver_factory_with_waypoint(
+        &fake_storage,
+        event_subscription_service,
+        create_epoch_ending_waypoint(),
+    )
+    .err()
+    .unwrap();
+    assert_matches!(error, Error::InitialConfigsUnavailable(0, _));
+    assert!(error
+        .to_string()
+        .contains("Configuration resource does not exist"));
+}
+
+#[test]
+fn test_new_waypoint_mismatch() {
+    // Create a fake storage backend and the event subscription service
+    let fake_storage = FakeStorage::new();
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(fake_storage.create_reader_writer())),
+    );
+
+    // Create the state sync driver factory with a waypoint for another network
+    let waypoint = Waypoint::default();
+    let error =
+        create_driver_factory_with_waypoint(&fake_storage, event_subscription_service, waypoint)
+            .err()
+            .unwrap();
+
+    // Verify the error names both the configured and stored waypoints
+    assert_eq!(
+        error,
+        Error::WaypointMismatch(
+            waypoint.to_string(),
+            create_epoch_ending_waypoint().to_string()
+        )
+    );
+}
+
+#[test]
+fn test_verify_waypoint_against_storage() {
+    // Verify a matching waypoint is accepted
+    let storage = FakeStorage::new().create_reader_writer().reader;
+    let waypoint = create_epoch_ending_waypoint();
+    assert_ok!(utils::verify_waypoint_against_storage(
+        storage.clone(),
+        &waypoint
+    ));
+
+    // Verify a waypoint with a different value is rejected
+    let waypoint = Waypoint::default();
+    let error = utils::verify_waypoint_against_storage(storage.clone(), &waypoint).unwrap_err();
+    assert_matches!(error, Error::WaypointMismatch(..));
+
+    // Verify a waypoint (ahead of storage) that can't be verified yet is accepted
+    let ledger_info = create_ledger_info_at_version(100);
+    let waypoint = Waypoint::new_any(ledger_info.ledger_info());
+    assert_ok!(utils::verify_waypoint_against_storage(
+        storage.clone(),
+        &waypoint
+    ));
+
+    // Verify a waypoint at a version that doesn't end an epoch is rejected
+    let fake_storage = FakeStorage::new();
+    fake_storage.commit_transactions(vec![create_transaction()], ledger_info);
+    let error = utils::verify_waypoint_against_storage(
+        fake_storage.create_reader_writer().reader,
+        &Waypoint::new_any(create_ledger_info_at_version(1).ledger_info()),
+    )
+    .unwrap_err();
+    assert_matches!(error, Error::WaypointMismatch(..));
+}
+
+#[test]
+fn test_fetch_lowest_available_version() {
+    // Verify all versions are available if there's no pruning metadata
+    let fake_storage = FakeStorage::new();
+    let storage = fake_storage.create_reader_writer().reader;
+    assert_eq!(
+        utils::fetch_lowest_available_version(storage.clone()).unwrap(),
+        Some(0)
+    );
+
+    // Commit several transactions and prune some of them
+    let transactions = (0..100).map(|_| create_transaction()).collect();
+    fake_storage.commit_transactions(transactions, create_ledger_info_at_version(100));
+    fake_storage.prune_to_version(50);
+    assert_eq!(
+        utils::fetch_lowest_available_version(storage.clone()).unwrap(),
+        Some(50)
+    );
+
+    // Verify the lowest available version never exceeds the synced version
+    fake_storage.prune_to_version(200);
+    assert_eq!(
+        utils::fetch_lowest_available_version(storage).unwrap(),
+        Some(100)
+    );
+}
+
+#[test]
+fn test_fetch_lowest_available_version_database() {
+    // Verify nothing is available in an empty database
+    let tmp_dir = TempPath::new();
+    let (_, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(&tmp_dir));
+    assert_none!(utils::fetch_lowest_available_version(db_rw.reader.clone()).unwrap());
+
+    // Commit genesis and verify it's available (nothing has been pruned)
+    let (genesis, _) = test_config();
+    let genesis_transaction = get_genesis_txn(&genesis).unwrap().clone();
+    bootstrap_genesis::<AptosVM>(&db_rw, &genesis_transaction).unwrap();
+    assert_eq!(
+        utils::fetch_lowest_available_version(db_rw.reader).unwrap(),
+        Some(0)
+    );
+}
+
+#[test]
+fn test_verify_waypoint_against_empty_storage() {
+    // Create an empty database (i.e., without a genesis)
+    let tmp_dir = TempPath::new();
+    let (_, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(&tmp_dir));
+
+    // Verify the check is skipped for any waypoint
+    for waypoint in [Waypoint::default(), create_epoch_ending_waypoint()] {
+        assert_ok!(utils::verify_waypoint_against_storage(
+            db_rw.reader.clone(),
+            &waypoint
+        ));
+    }
+}
+
+#[test]
+fn test_empty_storage_helpers() {
+    // Create an empty database (i.e., without a genesis)
+    let tmp_dir = TempPath::new();
+    let (_, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(&tmp_dir));
+
+    // Verify the synced version is unknown (instead of an error)
+    assert_none!(utils::fetch_latest_synced_version(db_rw.reader.clone()).unwrap());
+
+    // Verify the metric gauges and committed version handle can be initialized
+    assert_ok!(utils::initialize_sync_gauges(
+        db_rw.reader.clone(),
+        &DriverMetrics::default()
+    ));
+    let committed_version_handle =
+        CommittedVersionHandle::new_from_storage(db_rw.reader.clone()).unwrap();
+    assert_eq!(committed_version_handle.get_committed_version(), 0);
+    assert_eq!(committed_version_handle.get_committed_epoch(), 0);
+
+    // Commit genesis and verify the synced version is now known
+    let (node_config, _) = test_config();
+    bootstrap_genesis::<AptosVM>(&db_rw, get_genesis_txn(&node_config).unwrap()).unwrap();
+    assert_eq!(
+        utils::fetch_latest_synced_version(db_rw.reader.clone()).unwrap(),
+        Some(0)
+    );
+}
+
+#[tokio::test]
+async fn test_driver_with_injected_components() {
+    // Create a mock storage synchronizer that expects a single shutdown
+    let shutdown_summary = ShutdownSummary {
+        chunks_committed: 1,
+        chunks_discarded: 2,
+        drained: true,
+    };
+    // Create and spawn a driver for a validator (that auto-bootstraps immediately)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .with_shutdown_summary(shutdown_summary)
+        .build()
+        .await;
+
+    // Verify the driver bootstraps using the injected components
+    let driver_client = driver_harness.driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Verify the driver drains the injected storage synchronizer on shutdown
+    assert_eq!(driver_client.shutdown().await.unwrap(), shutdown_summary);
+}
+
+#[tokio::test]
+async fn test_check_progress_now() {
+    // Create a driver for a full node (after the initial progress check,
+    // there are no peers, so no stream is created).
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+    assert_none!(driver_harness.next_stream_request());
+
+    // Advertise a new epoch ending ledger info (i.e., peers have connected)
+    driver_harness.set_global_data_summary(create_global_summary(1));
+
+    // Kick the driver and verify a stream is created without advancing time
+    driver_harness.check_progress_now().await;
+    assert_eq!(
+        driver_harness.next_stream_request(),
+        Some(StreamRequest::GetAllEpochEndingLedgerInfos(
+            GetAllEpochEndingLedgerInfosRequest { start_epoch: 1 }
+        ))
+    );
+    assert_none!(driver_harness.next_stream_request());
+
+    // Verify a new stream is requested at the next progress check
+    driver_harness.advance_to_next_progress_check().await;
+    assert_some!(driver_harness.next_stream_request());
+}
+
+#[tokio::test]
+async fn test_client_requests() {
+    // Create and spawn a driver for a full node (that has no peers)
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Verify each client request is handled (and answered) by the driver
+    driver_harness.check_progress_now().await;
+    let driver_client = driver_harness.driver_client();
+    let sync_status = driver_client
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert!(!sync_status.bootstrapped);
+    let (commit_event_sender, _commit_event_receiver) = mpsc::channel(10);
+    driver_client
+        .subscribe_to_commit_events(commit_event_sender)
+        .await
+        .unwrap();
+    assert_matches!(
+        driver_client.sync_to_version(10, None).await.unwrap_err(),
+        Error::BootstrapNotComplete(_)
+    );
+
+    // Verify bootstrap waiters time out (and unsubscribe) without bootstrapping
+    assert_eq!(
+        driver_client
+            .notify_once_bootstrapped_with_timeout(Duration::from_millis(100))
+            .await,
+        BootstrapWaitResult::TimedOut
+    );
+
+    // Verify the driver handles the shutdown request
+    assert_eq!(
+        driver_client.shutdown().await.unwrap(),
+        ShutdownSummary::default()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_bootstrap_waiters() {
+    // Create and spawn a driver for a validator (with peers advertising
+    // data, so the validator can't auto-bootstrap).
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .with_global_data_summary(create_global_summary(1))
+        .build()
+        .await;
+    let driver_client = driver_harness.driver_client().clone();
+
+    // Register many bootstrap waiters
+    let num_waiters = 100;
+    let early_waiters: Vec<_> = (0..num_waiters)
+        .map(|_| {
+            driver_client
+                .clone()
+                .notify_once_bootstrapped_with_timeout(Duration::from_secs(60))
+        })
+        .collect();
+
+    // Verify the node hasn't bootstrapped
+    let sync_status = driver_client
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert!(!sync_status.bootstrapped);
+    assert!(!driver_client.is_bootstrapped());
+
+    // Remove all peers (so that the validator auto-bootstraps) and kick the driver
+    driver_harness.set_global_data_summary(GlobalDataSummary::empty());
+    driver_harness.check_progress_now().await;
+    assert!(driver_client.is_bootstrapped());
+
+    // Verify all early waiters were notified
+    for wait_result in join_all(early_waiters).await {
+        assert_eq!(wait_result, BootstrapWaitResult::Bootstrapped);
+    }
+
+    // Verify many concurrent late waiters (on cloned clients) are also notified
+    let late_waiters: Vec<_> = (0..num_waiters)
+        .map(|_| {
+            let driver_client = driver_client.clone();
+            tokio::spawn(async move { driver_client.notify_once_bootstrapped().await })
+        })
+        .collect();
+    for wait_result in join_all(late_waiters).await {
+        wait_result.unwrap().unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_driver_metrics() {
+    // Create two drivers in the same process (each with its own storage)
+    let (fake_storage_1, fake_storage_2) = (FakeStorage::new(), FakeStorage::new());
+    let (metadata_path_1, metadata_path_2) = (TempPath::new(), TempPath::new());
+    metadata_path_1.create_as_dir().unwrap();
+    metadata_path_2.create_as_dir().unwrap();
+    let driver_factory_1 = create_driver_factory(&fake_storage_1, &metadata_path_1, None);
+    let driver_factory_2 = create_driver_factory(&fake_storage_2, &metadata_path_2, None);
+
+    // Verify the drivers have different instance labels
+    let driver_metrics_1 = driver_factory_1.get_driver_metrics();
+    let driver_metrics_2 = driver_factory_2.get_driver_metrics();
+    assert_ne!(
+        driver_metrics_1.get_instance_label(),
+        driver_metrics_2.get_instance_label()
+    );
+    assert!(!driver_metrics_2.get_instance_label().is_empty());
+
+    // Send several client requests to the first driver only
+    let num_client_notifications = driver_metrics_1.read_counter(
+        &metrics::DRIVER_COUNTERS,
+        metrics::DRIVER_CLIENT_NOTIFICATION,
+    );
+    let num_requests = 5;
+    let driver_client_1 = driver_factory_1.create_driver_client();
+    for _ in 0..num_requests {
+        driver_client_1.check_progress_now().await.unwrap();
+    }
+
+    // Verify the requests are only counted for the first driver
+    assert!(
+        driver_metrics_1.read_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DRIVER_CLIENT_NOTIFICATION
+        ) >= num_client_notifications + num_requests
+    );
+    assert_eq!(
+        driver_metrics_2.read_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DRIVER_CLIENT_NOTIFICATION
+        ),
+        0
+    );
+
+    // Shut down both drivers
+    for driver_factory in [driver_factory_1, driver_factory_2] {
+        driver_factory
+            .into_runtime_handle()
+            .shutdown(Duration::from_secs(10))
+            .await
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_driver_metrics_bounded_labels() {
+    // Create more dry-run driver metrics than there are distinct instance labels
+    let num_instances = 2 * metrics::MAX_DRIVER_INSTANCE_LABELS;
+    let instance_labels: Vec<_> = (0..num_instances)
+        .map(|_| DriverMetrics::new_dry_run_instance().get_instance_label())
+        .collect();
+
+    // Verify the labels are bounded and interned (i.e., reused labels share a single copy)
+    let distinct_labels: HashSet<_> = instance_labels.iter().map(|label| label.as_ptr()).collect();
+    assert!(distinct_labels.len() as u64 <= metrics::MAX_DRIVER_INSTANCE_LABELS);
+    for instance_label in instance_labels {
+        let label_id: u64 = instance_label
+            .strip_prefix("dry_run_")
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(label_id < metrics::MAX_DRIVER_INSTANCE_LABELS);
+    }
+}
+
+#[tokio::test]
+async fn test_dry_run_driver() {
+    // Create a dry-run driver for a validator (using a fake storage backend)
+    let fake_storage = FakeStorage::new();
+    let (consensus_notifier, consensus_listener) = new_consensus_notifier_listener_pair(5000);
+    let mut node_config = create_validator_node_config();
+    let driver_factory =
+        create_dry_run_driver_factory(&fake_storage, &node_config, consensus_listener).unwrap();
+
+    // Verify the driver metrics are labelled as a dry run
+    assert!(driver_factory
+        .get_driver_metrics()
+        .get_instance_label()
+        .starts_with("dry_run_"));
+
+    // Wait until the driver is bootstrapped and verify consensus notifications are rejected
+    let driver_client = driver_factory.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+    assert_err!(
+        consensus_notifier
+            .sync_to_target(create_ledger_info_at_version(0))
+            .await
+    );
+    assert_err!(
+        consensus_notifier
+            .notify_new_commit(vec![create_transaction()], vec![])
+            .await
+    );
+
+    // Verify nothing was committed to storage
+    assert!(fake_storage.get_committed_chunks().is_empty());
+    assert_eq!(fake_storage.get_latest_version(), 0);
+
+    // Verify dry-run drivers can't be created to sync state snapshots
+    node_config.state_sync.state_sync_driver.bootstrapping_mode =
+        BootstrappingMode::DownloadLatestStates;
+    let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+    assert_matches!(
+        create_dry_run_driver_factory(&fake_storage, &node_config, consensus_listener),
+        Err(Error::UnexpectedError(_))
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_concurrency_config() {
+    // Create a node config with an invalid number of executor threads
+    let fake_storage = FakeStorage::new();
+    let mut node_config = create_validator_node_config();
+    node_config
+        .state_sync
+        .state_sync_driver
+        .num_executor_threads = 0;
+
+    // Verify the driver can't be created (and an error is returned)
+    let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+    assert_matches!(
+        create_dry_run_driver_factory(&fake_storage, &node_config, consensus_listener),
+        Err(Error::UnexpectedError(_))
+    );
+}
+
+#[tokio::test]
+async fn test_get_sync_status_full_node() {
+    // Create and spawn a driver for a full node (that has no peers)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::FullNode).build().await;
+
+    // Verify the sync status of the full node (it can't bootstrap without peers)
+    let sync_status = driver_harness
+        .driver_client()
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(
+        sync_status,
+        SyncStatus {
+            active_sync_target: None,
+            advertised_version: None,
+            bootstrapped: false,
+            bootstrapping_phase: BootstrappingPhase::FetchingEpochEndingLedgerInfos,
+            continuous_syncing_mode: NodeConfig::default()
+                .state_sync
+                .state_sync_driver
+                .continuous_syncing_mode,
+            lag_estimate: None,
+            last_error_label: None,
+            lowest_available_version: Some(0),
+            role: RoleType::FullNode,
+            synced_epoch: Some(create_empty_epoch_state().epoch),
+            synced_version: Some(0),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_get_sync_status_validator() {
+    // Create and spawn a driver for a validator (that auto-bootstraps immediately)
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .build()
+        .await;
+
+    // Wait until the validator is bootstrapped
+    let driver_client = driver_harness.driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Verify the sync status of the validator
+    let sync_status = driver_client
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert!(sync_status.bootstrapped);
+    assert_eq!(
+        sync_status.bootstrapping_phase,
+        BootstrappingPhase::Bootstrapped
+    );
+    assert_eq!(sync_status.role, RoleType::Validator);
+    assert_eq!(sync_status.synced_version, Some(0));
+    assert_none!(sync_status.active_sync_target);
+    assert_none!(sync_status.lag_estimate);
+    assert_none!(sync_status.last_error_label);
+}
+
+#[tokio::test]
+async fn test_tooling_driver() {
+    // Create a mock data client for a mock chain (where peers advertise the genesis epoch end)
+    let mut aptos_data_client = create_mock_aptos_data_client();
+    aptos_data_client
+        .expect_get_global_data_summary()
+        .returning(|| create_global_summary(0));
+
+    // Create a mock streaming client that notifies us of new continuous streams
+    let (stream_sender, mut stream_receiver) = mpsc::unbounded();
+    let streaming_client = create_continuous_streaming_client(stream_sender);
+
+    // Create and spawn a tooling driver for a validator (without consensus, mempool or events)
+    let fake_storage = FakeStorage::new();
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::Validator;
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let driver_factory = DriverFactory::create_for_tooling(
+        false,
+        &node_config,
+        create_epoch_ending_waypoint(),
+        fake_storage.create_reader_writer(),
+        create_driver_storage_synchronizer(),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        aptos_data_client,
+        streaming_client,
+    )
+    .unwrap();
+
+    // Verify the driver bootstraps using the advertised data
+    let driver_client = driver_factory.create_driver_client();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+    let sync_status = driver_client
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert!(sync_status.bootstrapped);
+    assert_eq!(sync_status.role, RoleType::Validator);
+
+    // Verify the driver continues to sync (i.e., it doesn't wait for consensus)
+    let highest_synced_version = timeout(Duration::from_secs(10), stream_receiver.next())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(highest_synced_version, 0);
+}
+
+#[tokio::test]
+async fn test_runtime_handle_shutdown() {
+    // Create, use and shut down a runtime handle (twice, to verify that
+    // nothing, e.g., the metrics, prevents the driver from restarting).
+    for _ in 0..2 {
+        // Create the runtime handle
+        let fake_storage = FakeStorage::new();
+        let metadata_path = TempPath::new();
+        metadata_path.create_as_dir().unwrap();
+        let runtime_handle = create_runtime_handle(&fake_storage, &metadata_path);
+
+        // Wait until the validator is bootstrapped
+        runtime_handle
+            .driver_client()
+            .notify_once_bootstrapped()
+            .await
+            .unwrap();
+        assert!(runtime_handle
+            .commit_state_tracker()
+            .get_commit_state()
+            .is_none());
+
+        // Shut down the runtime handle and verify the storage synchronizer was drained
+        let shutdown_summary = runtime_handle
+            .shutdown(Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(
+            shutdown_summary,
+            ShutdownSummary {
+                chunks_committed: 0,
+                chunks_discarded: 0,
+                drained: true,
+            }
+        );
+        assert!(fake_storage.get_committed_chunks().is_empty());
+    }
+}
+
+#[test]
+fn test_blocking_client_requests() {
+    // Create and spawn a driver (on its own runtime)
+    let fake_storage = FakeStorage::new();
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let driver_factory = create_driver_factory(&fake_storage, &metadata_path, None);
+    let driver_client = driver_factory.create_driver_client();
+
+    // Verify the blocking client requests work from a plain thread
+    let shutdown_summary = std::thread::spawn(move || {
+        let timeout = Duration::from_secs(10);
+        assert_eq!(
+            driver_client.blocking_wait_for_bootstrap(timeout).unwrap(),
+            BootstrapWaitResult::Bootstrapped
+        );
+        driver_client.blocking_check_progress_now().unwrap();
+        let sync_status = driver_client.blocking_get_sync_status(timeout).unwrap();
+        assert!(sync_status.bootstrapped);
+        let ledger_info = driver_client.blocking_wait_for_version(0, timeout).unwrap();
+        assert_eq!(ledger_info.ledger_info().version(), 0);
+        driver_client.blocking_shutdown().unwrap()
+    })
+    .join()
+    .unwrap();
+    assert!(shutdown_summary.drained);
+}
+
+#[test]
+fn test_driver_on_current_thread_runtime() {
+    // Create a current thread runtime (on which any blocking call in the
+    // driver, e.g., a hidden block_on, would deadlock or panic).
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+
+    // Create and spawn the driver on the runtime handle
+    let fake_storage = FakeStorage::new();
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let driver_factory = create_driver_factory(
+        &fake_storage,
+        &metadata_path,
+        Some(runtime.handle().clone()),
+    );
+    let runtime_handle = driver_factory.into_runtime_handle();
+
+    // Verify the validator bootstraps and the driver shuts down gracefully
+    let shutdown_summary = runtime.block_on(async move {
+        let driver_client = runtime_handle.driver_client();
+        timeout(
+            Duration::from_secs(10),
+            driver_client.notify_once_bootstrapped(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        let sync_status = driver_client
+            .get_sync_status(Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert!(sync_status.bootstrapped);
+        runtime_handle
+            .shutdown(Duration::from_secs(10))
+            .await
+            .unwrap()
+    });
+    assert!(shutdown_summary.drained);
+}
+
+#[tokio::test]
+async fn test_runtime_handle_drop() {
+    // Create a driver factory and a client (that outlives the runtime handle)
+    let fake_storage = FakeStorage::new();
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let driver_factory = create_driver_factory(&fake_storage, &metadata_path, None);
+    let driver_client = driver_factory.create_driver_client();
+
+    // Wait until the validator is bootstrapped
+    let runtime_handle = driver_factory.into_runtime_handle();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Drop the runtime handle (without a shutdown) and verify the driver is gone
+    drop(runtime_handle);
+    assert_err!(driver_client.shutdown().await);
+}
+
+#[tokio::test]
+async fn test_runtime_handle_drop_client_errors() {
+    // Create a driver factory and a client (that outlives the runtime handle)
+    let fake_storage = FakeStorage::new();
+    let metadata_path = TempPath::new();
+    metadata_path.create_as_dir().unwrap();
+    let driver_factory = create_driver_factory(&fake_storage, &metadata_path, None);
+    let driver_client = driver_factory.create_driver_client();
+
+    // Wait until the validator is bootstrapped
+    let runtime_handle = driver_factory.into_runtime_handle();
+    driver_client.notify_once_bootstrapped().await.unwrap();
+
+    // Kill the driver tasks (by dropping the runtime handle)
+    drop(runtime_handle);
+
+    // Verify the client requests fail promptly (instead of hanging)
+    let client_requests = async {
+        let (commit_event_sender, _) = mpsc::channel(10);
+        vec![
+            driver_client.check_progress_now().await.unwrap_err(),
+            driver_client
+                .get_sync_status(Duration::from_secs(60))
+                .await
+                .unwrap_err(),
+            driver_client.notify_once_bootstrapped().await.unwrap_err(),
+            driver_client.shutdown().await.unwrap_err(),
+            driver_client
+                .subscribe_to_commit_events(commit_event_sender)
+                .await
+                .unwrap_err(),
+            driver_client.sync_to_version(10, None).await.unwrap_err(),
+        ]
+    };
+    let errors = timeout(Duration::from_secs(1), client_requests)
+        .await
+        .unwrap();
+    for error in errors {
+        assert_matches!(error, Error::DriverUnavailable(_));
+    }
+}
+
+/// Creates a driver factory for a validator (that auto-bootstraps
+/// immediately) using the given fake storage backend. The driver runs
+/// on the given runtime handle (or on its own runtime, if none is given).
+fn create_driver_factory(
+    fake_storage: &FakeStorage,
+    metadata_path: &TempPath,
+    runtime_handle: Option<Handle>,
+) -> DriverFactory {
+    // Create the storage and event subscription service
+    let db_rw = fake_storage.create_reader_writer();
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(db_rw.clone())),
+    );
+
+    // Create mempool and consensus notifiers
+    let (mempool_notifier, _) = new_mempool_notifier_listener_pair();
+    let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+
+    // Create a test streaming service client
+    let (streaming_service_client, _) = new_streaming_service_client_listener_pair();
+
+    // Create the node config and a test aptos data client
+    let node_config = create_validator_node_config();
+    let network_client = StorageServiceClient::new(
+        MultiNetworkSender::new(HashMap::new()),
+        PeerMetadataStorage::new(&[]),
+    );
+    let (aptos_data_client, _) = AptosNetDataClient::new(
+        node_config.state_sync.aptos_data_client,
+        node_config.base.clone(),
+        node_config.state_sync.storage_service,
+        TimeService::mock(),
+        network_client,
+        None,
+    );
+
+    // Create and spawn the driver
+    let chunk_executor = Arc::new(fake_storage.create_chunk_executor());
+    let metadata_storage = PersistentMetadataStorage::new(metadata_path.path());
+    let driver_factory = match runtime_handle {
+        Some(runtime_handle) => DriverFactory::create_and_spawn_on(
+            runtime_handle,
+            &node_config,
+            create_epoch_ending_waypoint(),
+            db_rw,
+            chunk_executor,
+            Some(mempool_notifier),
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_service_client,
+            vec![],
+        ),
+        None => DriverFactory::create_and_spawn_driver(
+            true,
+            &node_config,
+            create_epoch_ending_waypoint(),
+            db_rw,
+            chunk_executor,
+            Some(mempool_notifier),
+            metadata_storage,
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_service_client,
+            vec![],
+        ),
+    };
+    driver_factory.unwrap()
+}
+
+/// Creates and spawns a driver factory (for a full node that runs on its own
+/// runtime) using the given fake storage, event subscription service and waypoint.
+fn create_driver_factory_with_waypoint(
+    fake_storage: &FakeStorage,
+    event_subscription_service: EventSubscriptionService,
+    waypoint: Waypoint,
+) -> Result<DriverFactory, Error> {
+    // Create mempool and consensus notifiers
+    let (mempool_notifier, _) = new_mempool_notifier_listener_pair();
+    let (_, consensus_listener) = new_consensus_notifier_listener_pair(0);
+
+    // Create a test streaming service client
+    let (streaming_service_client, _) = new_streaming_service_client_listener_pair();
+
+    // Create a test aptos data client
+    let node_config = NodeConfig::default();
+    let network_client = StorageServiceClient::new(
+        MultiNetworkSender::new(HashMap::new()),
+        PeerMetadataStorage::new(&[]),
+    );
+    let (aptos_data_client, _) = AptosNetDataClient::new(
+        node_config.state_sync.aptos_data_client,
+        node_config.base.clone(),
+        node_config.state_sync.storage_service,
+        TimeService::mock(),
+        network_client,
+        None,
+    );
+
+    // Create and spawn the driver
+    let metadata_path = TempPath::new();
+    DriverFactory::create_and_spawn_driver(
+        true,
+        &node_config,
+        waypoint,
+        fake_storage.create_reader_writer(),
+        Arc::new(fake_storage.create_chunk_executor()),
+        Some(mempool_notifier),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        consensus_listener,
+        event_subscription_service,
+        aptos_data_client,
+        streaming_service_client,
+        vec![],
+    )
+}
+
+/// Creates and spawns a dry-run driver factory (that runs on its own runtime)
+/// using the given fake storage, node config and consensus listener.
+fn create_dry_run_driver_factory(
+    fake_storage: &FakeStorage,
+    node_config: &NodeConfig,
+    consensus_listener: ConsensusNotificationListener,
+) -> Result<DriverFactory, Error> {
+    // Create the storage and event subscription service
+    let db_rw = fake_storage.create_reader_writer();
+    let event_subscription_service = EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(db_rw.clone())),
+    );
+
+    // Create a test streaming service client and aptos data client
+    let (streaming_service_client, _) = new_streaming_service_client_listener_pair();
+    let network_client = StorageServiceClient::new(
+        MultiNetworkSender::new(HashMap::new()),
+        PeerMetadataStorage::new(&[]),
+    );
+    let (aptos_data_client, _) = AptosNetDataClient::new(
+        node_config.state_sync.aptos_data_client,
+        node_config.base.clone(),
+        node_config.state_sync.storage_service,
+        TimeService::mock(),
+        network_client,
+        None,
+    );
+
+    // Create and spawn the driver
+    let metadata_path = TempPath::new();
+    DriverFactory::create_and_spawn_dry_run_driver(
+        true,
+        node_config,
+        create_epoch_ending_waypoint(),
+        db_rw,
+        Arc::new(fake_storage.create_chunk_executor()),
+        PersistentMetadataStorage::new(metadata_path.path()),
+        consensus_listener,
+        event_subscription_service,
+        aptos_data_client,
+        streaming_service_client,
+    )
+}
+
+/// Creates a node config for a validator that auto-bootstraps immediately
+fn create_validator_node_config() -> NodeConfig {
+    let mut node_config = NodeConfig::default();
+    node_config.base.role = RoleType::Validator;
+    node_config
+        .state_sync
+        .state_sync_driver
+        .max_connection_deadline_secs = 0;
+    node_config
+}
+
+/// Creates a runtime handle for a validator (see `create_driver_factory()`)
+fn create_runtime_handle(
+    fake_storage: &FakeStorage,
+    metadata_path: &TempPath,
+) -> StateSyncRuntimeHandle {
+    create_driver_factory(fake_storage, metadata_path, None).into_runtime_handle()
+}
+
+/// Creates a mock storage synchronizer for a driver. The driver's
+/// synchronizer (and the clones held by the bootstrapper and continuous
+/// syncer) are always ready for new data.
+fn create_driver_storage_synchronizer() -> MockStorageSynchronizer {
+    let mut storage_synchronizer = create_mock_storage_synchronizer();
+    storage_synchronizer
+        .expect_pending_storage_data()
+        .return_const(PendingDataSummary::default());
+    storage_synchronizer
+        .expect_clone()
+        .returning(|| create_ready_storage_synchronizer(true));
+    storage_synchronizer
+}
+
+/// Creates a mock streaming client (and clones) that fails to create
+/// continuous transaction output streams. The highest synced version of each
+/// stream request is sent along the given channel.
+fn create_continuous_streaming_client(
+    stream_sender: mpsc::UnboundedSender<u64>,
+) -> MockStreamingClient {
+    let mut streaming_client = create_mock_streaming_client();
+    let clone_stream_sender = stream_sender.clone();
+    streaming_client
+        .expect_clone()
+        .returning(move || create_continuous_streaming_client(clone_stream_sender.clone()));
+    streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .returning(move |highest_synced_version, _, _| {
+            let _ = stream_sender.unbounded_send(highest_synced_version);
+            Err(data_streaming_service::error::Error::DataIsUnavailable(
+                "No continuous streams for tests!".into(),
+            ))
+        });
+    streaming_client
+}
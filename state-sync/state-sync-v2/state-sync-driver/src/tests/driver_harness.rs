@@ -0,0 +1,564 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    driver_client::DriverClient,
+    driver_factory::DriverFactory,
+    metadata_storage::PersistentMetadataStorage,
+    metrics::DriverMetrics,
+    notification_handlers::{ErrorNotification, ErrorNotificationListener},
+    storage_synchronizer::{CommittedVersionHandle, PendingDataSummary, ShutdownSummary},
+    test_utils::fake_storage::FakeStorage,
+    tests::{
+        mocks::{
+            create_mock_aptos_data_client, create_mock_storage_synchronizer,
+            create_mock_streaming_client, create_ready_storage_synchronizer, FlakyMempoolNotifier,
+            MockAptosDataClient, MockStorageSynchronizer, MockStreamingClient,
+        },
+        utils::{create_epoch_ending_waypoint, create_ledger_info_at_version},
+    },
+};
+use aptos_config::config::{NodeConfig, RoleType, StateSyncDriverConfig};
+use aptos_data_client::GlobalDataSummary;
+use aptos_infallible::{Mutex, RwLock};
+use aptos_temppath::TempPath;
+use aptos_time_service::{MockTimeService, TimeService};
+use aptos_types::{
+    contract_event::ContractEvent,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    transaction::{Transaction, Version},
+};
+use consensus_notifications::{
+    new_consensus_notifier_listener_pair, ConsensusNotificationSender, ConsensusNotifier,
+};
+use data_streaming_service::{
+    data_notification::NotificationId,
+    streaming_client::{
+        ContinuouslyStreamTransactionOutputsRequest, ContinuouslyStreamTransactionsRequest,
+        GetAllEpochEndingLedgerInfosRequest, GetAllTransactionOutputsRequest,
+        GetAllTransactionsRequest, NotificationFeedback, StreamRequest,
+    },
+};
+use event_notifications::EventSubscriptionService;
+use futures::{channel::mpsc, StreamExt};
+use std::{sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+// The interval (of simulated time) at which harness drivers check progress
+const PROGRESS_CHECK_INTERVAL_MS: u64 = 1000;
+
+// The timeout (of real time) for client requests sent to harness drivers
+const CLIENT_REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// A builder for driver test harnesses (see `DriverHarness`)
+pub struct DriverHarnessBuilder {
+    bootstrapped_global_data_summary: Option<GlobalDataSummary>,
+    global_data_summary: GlobalDataSummary,
+    node_config: NodeConfig,
+    shutdown_summary: ShutdownSummary,
+}
+
+impl DriverHarnessBuilder {
+    /// Creates a builder for a driver with the given role. By default, the
+    /// driver has no peers, and storage holds the genesis ledger info.
+    pub fn new(role: RoleType) -> Self {
+        let mut node_config = NodeConfig::default();
+        node_config.base.role = role;
+
+        // Check progress on a fixed (simulated) interval, and never retry
+        // failed stream initializations (the retries sleep in real time).
+        let driver_config = &mut node_config.state_sync.state_sync_driver;
+        driver_config.max_stream_initialization_retries = 0;
+        driver_config.progress_check_interval_ms = PROGRESS_CHECK_INTERVAL_MS;
+
+        Self {
+            bootstrapped_global_data_summary: None,
+            global_data_summary: GlobalDataSummary::empty(),
+            node_config,
+            shutdown_summary: ShutdownSummary::default(),
+        }
+    }
+
+    /// Updates the driver config using the given function
+    pub fn with_driver_config(
+        mut self,
+        update_driver_config: impl FnOnce(&mut StateSyncDriverConfig),
+    ) -> Self {
+        update_driver_config(&mut self.node_config.state_sync.state_sync_driver);
+        self
+    }
+
+    /// Sets the global data summary initially advertised by peers
+    pub fn with_global_data_summary(mut self, global_data_summary: GlobalDataSummary) -> Self {
+        self.global_data_summary = global_data_summary;
+        self
+    }
+
+    /// Sets the global data summary advertised by peers once the initial
+    /// progress check completes (e.g., once a validator has auto-bootstrapped)
+    pub fn with_bootstrapped_global_data_summary(
+        mut self,
+        global_data_summary: GlobalDataSummary,
+    ) -> Self {
+        self.bootstrapped_global_data_summary = Some(global_data_summary);
+        self
+    }
+
+    /// Sets the summary returned when the storage synchronizer is shut down
+    pub fn with_shutdown_summary(mut self, shutdown_summary: ShutdownSummary) -> Self {
+        self.shutdown_summary = shutdown_summary;
+        self
+    }
+
+    /// Builds the harness and spawns the driver (on the current runtime).
+    /// Once this returns, the driver has completed its initial progress
+    /// check, and the next check is due after a single progress interval.
+    pub async fn build(self) -> DriverHarness {
+        // Create the fake storage backend and the event subscription service
+        let fake_storage = FakeStorage::new();
+        let db_rw = fake_storage.create_reader_writer();
+        let event_subscription_service = EventSubscriptionService::new(
+            ON_CHAIN_CONFIG_REGISTRY,
+            Arc::new(RwLock::new(db_rw.clone())),
+        );
+
+        // Create the mock components of the driver
+        let global_data_summary = Arc::new(Mutex::new(self.global_data_summary));
+        let aptos_data_client = create_data_client(global_data_summary.clone());
+        let (progress_check_sender, progress_check_receiver) = mpsc::unbounded();
+        let storage_synchronizer =
+            create_storage_synchronizer(progress_check_sender, self.shutdown_summary);
+        let (error_notification_sender, error_notification_listener) =
+            ErrorNotificationListener::new();
+        let (stream_request_sender, stream_request_receiver) = mpsc::unbounded();
+        let (stream_termination_sender, stream_termination_receiver) = mpsc::unbounded();
+        let streaming_client =
+            create_streaming_client(stream_request_sender, stream_termination_sender);
+
+        // Create the consensus notifier and mempool notifier
+        let (consensus_notifier, consensus_listener) = new_consensus_notifier_listener_pair(5000);
+        let mempool_notifier = FlakyMempoolNotifier::new(0);
+
+        // Create and spawn the driver
+        let metadata_path = TempPath::new();
+        metadata_path.create_as_dir().unwrap();
+        let time_service = TimeService::mock();
+        let driver_factory = DriverFactory::create_and_spawn_driver_with_components(
+            false,
+            &self.node_config,
+            create_epoch_ending_waypoint(),
+            db_rw,
+            storage_synchronizer,
+            error_notification_listener,
+            Some(mempool_notifier.clone()),
+            PersistentMetadataStorage::new(metadata_path.path()),
+            consensus_listener,
+            event_subscription_service,
+            aptos_data_client,
+            streaming_client,
+            vec![],
+            time_service.clone(),
+        )
+        .unwrap();
+
+        // Wait until the driver is handling notifications (i.e., the
+        // progress check interval has been created).
+        let driver_client = driver_factory.create_driver_client();
+        driver_client
+            .get_sync_status(Duration::from_secs(CLIENT_REQUEST_TIMEOUT_SECS))
+            .await
+            .unwrap();
+        let mut driver_harness = DriverHarness {
+            consensus_notifier,
+            driver_client,
+            driver_factory,
+            error_notification_sender,
+            fake_storage,
+            global_data_summary,
+            mempool_notifier,
+            _metadata_path: metadata_path,
+            progress_check_receiver,
+            stream_request_receiver,
+            stream_termination_receiver,
+            time_service: time_service.into_mock(),
+            time_until_progress_check: Duration::from_millis(PROGRESS_CHECK_INTERVAL_MS),
+        };
+
+        // The first progress check is due immediately (at the current time)
+        driver_harness
+            .advance_and_wait_for_progress_check(Duration::from_secs(0))
+            .await;
+
+        // Update the data advertised by peers (if required)
+        if let Some(global_data_summary) = self.bootstrapped_global_data_summary {
+            driver_harness.set_global_data_summary(global_data_summary);
+        }
+
+        driver_harness
+    }
+}
+
+/// A deterministic harness for driver tests. The driver runs on fake storage
+/// (with mock data and streaming clients, a mock storage synchronizer and a
+/// recording mempool notifier), and all of its timers (e.g., progress checks
+/// and the connection deadline) use a mock time service. This allows tests to
+/// script consensus notifications, storage synchronizer errors and client
+/// requests, advance time, and verify the responses of the driver without
+/// sleeping.
+pub struct DriverHarness {
+    consensus_notifier: ConsensusNotifier,
+    driver_client: DriverClient,
+    driver_factory: DriverFactory,
+    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    fake_storage: FakeStorage,
+    global_data_summary: Arc<Mutex<GlobalDataSummary>>,
+    mempool_notifier: FlakyMempoolNotifier,
+    _metadata_path: TempPath,
+    progress_check_receiver: mpsc::UnboundedReceiver<()>,
+    stream_request_receiver: mpsc::UnboundedReceiver<StreamRequest>,
+    stream_termination_receiver: mpsc::UnboundedReceiver<(NotificationId, NotificationFeedback)>,
+    time_service: MockTimeService,
+    time_until_progress_check: Duration,
+}
+
+impl DriverHarness {
+    /// Advances the simulated time by the given duration, and waits for the
+    /// driver to complete each progress check that falls due along the way.
+    pub async fn advance_time(&mut self, duration: Duration) {
+        let mut remaining_time = duration;
+        while remaining_time >= self.time_until_progress_check {
+            remaining_time -= self.time_until_progress_check;
+            self.advance_to_next_progress_check().await;
+        }
+        self.time_service.advance_async(remaining_time).await;
+        self.time_until_progress_check -= remaining_time;
+    }
+
+    /// Advances the simulated time to the next progress check, and waits
+    /// for the driver to complete the check.
+    pub async fn advance_to_next_progress_check(&mut self) {
+        self.advance_and_wait_for_progress_check(self.time_until_progress_check)
+            .await;
+    }
+
+    /// Asks the driver to check progress immediately, and waits for the
+    /// driver to complete the check.
+    pub async fn check_progress_now(&mut self) {
+        self.drain_progress_check_reads();
+        self.driver_client.check_progress_now().await.unwrap();
+        self.progress_check_receiver.next().await.unwrap();
+    }
+
+    /// Commits the given transactions to storage (as consensus would) and
+    /// notifies the driver of the commit. Returns the driver's response.
+    pub async fn commit_transactions(
+        &self,
+        transactions: Vec<Transaction>,
+        events: Vec<ContractEvent>,
+    ) -> Result<(), consensus_notifications::Error> {
+        let synced_version = self.fake_storage.get_latest_version() + transactions.len() as u64;
+        self.fake_storage.commit_transactions(
+            transactions.clone(),
+            create_ledger_info_at_version(synced_version),
+        );
+        self.notify_new_commit(transactions, events).await
+    }
+
+    /// Returns the client of the driver
+    pub fn driver_client(&self) -> &DriverClient {
+        &self.driver_client
+    }
+
+    /// Returns the metrics handle of the driver
+    pub fn driver_metrics(&self) -> DriverMetrics {
+        self.driver_factory.get_driver_metrics()
+    }
+
+    /// Returns all mempool notifications delivered by the driver (in order)
+    pub fn get_mempool_notifications(&self) -> Vec<Vec<Transaction>> {
+        self.mempool_notifier.get_delivered_notifications()
+    }
+
+    /// Returns the fake storage backend of the driver
+    pub fn get_storage(&self) -> &FakeStorage {
+        &self.fake_storage
+    }
+
+    /// Returns the next data stream requested by the driver (if any).
+    /// All stream requests fail, so the driver requests a new stream on
+    /// each progress check.
+    pub fn next_stream_request(&mut self) -> Option<StreamRequest> {
+        self.stream_request_receiver.try_next().ok().flatten()
+    }
+
+    /// Returns the next data stream terminated by the driver (if any), i.e.,
+    /// the notification ID and feedback sent to the streaming service.
+    pub fn next_stream_termination(&mut self) -> Option<(NotificationId, NotificationFeedback)> {
+        self.stream_termination_receiver.try_next().ok().flatten()
+    }
+
+    /// Sends a commit notification to the driver (without committing the
+    /// transactions to storage). Returns the driver's response.
+    pub async fn notify_new_commit(
+        &self,
+        transactions: Vec<Transaction>,
+        events: Vec<ContractEvent>,
+    ) -> Result<(), consensus_notifications::Error> {
+        self.consensus_notifier
+            .notify_new_commit(transactions, events)
+            .await
+    }
+
+    /// Sends an error notification to the driver (as the storage synchronizer
+    /// would), and waits for the driver to handle it.
+    pub async fn notify_error(&self, error_notification: ErrorNotification) {
+        self.error_notification_sender
+            .unbounded_send(error_notification)
+            .unwrap();
+
+        // Errors and client requests are handled by the same loop, so
+        // the error has been handled once the driver responds.
+        self.driver_client
+            .get_sync_status(Duration::from_secs(CLIENT_REQUEST_TIMEOUT_SECS))
+            .await
+            .unwrap();
+    }
+
+    /// Sets the global data summary advertised by peers
+    pub fn set_global_data_summary(&self, global_data_summary: GlobalDataSummary) {
+        *self.global_data_summary.lock() = global_data_summary;
+    }
+
+    /// Sends a sync request for the given version to the driver (as
+    /// consensus would), and returns a handle for the driver's response.
+    pub fn spawn_sync_to_target(
+        &self,
+        version: Version,
+    ) -> JoinHandle<Result<(), consensus_notifications::Error>> {
+        let consensus_notifier = self.consensus_notifier.clone();
+        tokio::spawn(async move {
+            consensus_notifier
+                .sync_to_target(create_ledger_info_at_version(version))
+                .await
+        })
+    }
+
+    /// Sends a sync request for the given version to the driver (as
+    /// consensus would), and waits for the driver's response.
+    pub async fn sync_to_target(
+        &self,
+        version: Version,
+    ) -> Result<(), consensus_notifications::Error> {
+        self.spawn_sync_to_target(version).await.unwrap()
+    }
+
+    /// Waits until the driver reports an active sync request for the given
+    /// version (i.e., the request has been received by the driver).
+    pub async fn wait_for_sync_target(&self, version: Version) {
+        loop {
+            let sync_status = self
+                .driver_client
+                .get_sync_status(Duration::from_secs(CLIENT_REQUEST_TIMEOUT_SECS))
+                .await
+                .unwrap();
+            if sync_status.active_sync_target == Some(version) {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    /// Advances the simulated time by the given duration (which must end at
+    /// the next progress check), and waits for the driver to complete the check.
+    async fn advance_and_wait_for_progress_check(&mut self, duration: Duration) {
+        // Advance the time and wait for the driver to start the check
+        self.drain_progress_check_reads();
+        self.time_service.advance_async(duration).await;
+        self.progress_check_receiver.next().await.unwrap();
+        self.time_until_progress_check = Duration::from_millis(PROGRESS_CHECK_INTERVAL_MS);
+
+        // The driver handles notifications in order, so the check is
+        // complete once the driver responds to a client request.
+        self.driver_client
+            .get_sync_status(Duration::from_secs(CLIENT_REQUEST_TIMEOUT_SECS))
+            .await
+            .unwrap();
+    }
+
+    /// Drops any pending data reads made outside of progress checks (e.g.,
+    /// while the driver waits for the synchronizer to drain for a sync request).
+    fn drain_progress_check_reads(&mut self) {
+        while let Ok(Some(())) = self.progress_check_receiver.try_next() {}
+    }
+}
+
+/// Creates a driver harness for a validator that auto-bootstraps immediately
+pub async fn create_bootstrapped_validator_harness() -> DriverHarness {
+    create_bootstrapped_validator_harness_with(|driver_harness_builder| driver_harness_builder)
+        .await
+}
+
+/// Creates a driver harness for a validator that auto-bootstraps immediately,
+/// after applying the given update to the harness builder
+pub async fn create_bootstrapped_validator_harness_with(
+    update_builder: impl FnOnce(DriverHarnessBuilder) -> DriverHarnessBuilder,
+) -> DriverHarness {
+    let driver_harness_builder = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0);
+    let driver_harness = update_builder(driver_harness_builder).build().await;
+    assert!(driver_harness.driver_client().is_bootstrapped());
+    driver_harness
+}
+
+/// Creates a mock data client that serves the given (shared) global data summary
+pub fn create_data_client(
+    global_data_summary: Arc<Mutex<GlobalDataSummary>>,
+) -> MockAptosDataClient {
+    let mut aptos_data_client = create_mock_aptos_data_client();
+    aptos_data_client
+        .expect_get_global_data_summary()
+        .returning(move || global_data_summary.lock().clone());
+    aptos_data_client
+}
+
+/// Creates a mock storage synchronizer for the driver. The synchronizer (and
+/// the clones held by the bootstrapper and continuous syncer) are always
+/// ready for new data. The driver reads the pending data of its synchronizer
+/// at the start of each progress check, so each read is sent along the given
+/// channel. Consensus commits are recorded in a shared committed version handle.
+/// The driver drains a clone of its synchronizer on shutdown, so the clones
+/// return the given shutdown summary.
+fn create_storage_synchronizer(
+    progress_check_sender: mpsc::UnboundedSender<()>,
+    shutdown_summary: ShutdownSummary,
+) -> MockStorageSynchronizer {
+    let mut storage_synchronizer = create_mock_storage_synchronizer();
+    storage_synchronizer
+        .expect_pending_storage_data()
+        .returning(move || {
+            let _ = progress_check_sender.unbounded_send(());
+            PendingDataSummary::default()
+        });
+    storage_synchronizer
+        .expect_committed_version_handle()
+        .return_const(CommittedVersionHandle::new(0, 0));
+    storage_synchronizer
+        .expect_finish_chunk_executor()
+        .return_const(());
+    storage_synchronizer
+        .expect_notify_stream_reset()
+        .return_const(());
+    storage_synchronizer
+        .expect_reset_pending_data()
+        .return_const(());
+    storage_synchronizer.expect_clone().returning(move || {
+        let mut storage_synchronizer = create_ready_storage_synchronizer(true);
+        storage_synchronizer
+            .expect_shutdown()
+            .return_const(shutdown_summary);
+        storage_synchronizer
+    });
+    storage_synchronizer
+}
+
+/// Creates a mock streaming client (and clones) that fails to create all
+/// data streams. Each stream request (and termination) is sent along the
+/// given channels.
+fn create_streaming_client(
+    stream_request_sender: mpsc::UnboundedSender<StreamRequest>,
+    stream_termination_sender: mpsc::UnboundedSender<(NotificationId, NotificationFeedback)>,
+) -> MockStreamingClient {
+    let mut streaming_client = create_mock_streaming_client();
+    let clone_request_sender = stream_request_sender.clone();
+    let clone_termination_sender = stream_termination_sender.clone();
+    streaming_client.expect_clone().returning(move || {
+        create_streaming_client(
+            clone_request_sender.clone(),
+            clone_termination_sender.clone(),
+        )
+    });
+
+    // Record each stream termination
+    streaming_client
+        .expect_terminate_stream_with_feedback()
+        .returning(move |notification_id, notification_feedback| {
+            let _ =
+                stream_termination_sender.unbounded_send((notification_id, notification_feedback));
+            Ok(())
+        });
+
+    // Record each stream request (and fail it)
+    let request_sender = stream_request_sender.clone();
+    streaming_client
+        .expect_get_all_epoch_ending_ledger_infos()
+        .returning(move |start_epoch| {
+            let stream_request =
+                StreamRequest::GetAllEpochEndingLedgerInfos(GetAllEpochEndingLedgerInfosRequest {
+                    start_epoch,
+                });
+            fail_stream_request(&request_sender, stream_request)
+        });
+    let request_sender = stream_request_sender.clone();
+    streaming_client
+        .expect_get_all_transaction_outputs()
+        .returning(move |start_version, end_version, proof_version| {
+            let stream_request =
+                StreamRequest::GetAllTransactionOutputs(GetAllTransactionOutputsRequest {
+                    start_version,
+                    end_version,
+                    proof_version,
+                });
+            fail_stream_request(&request_sender, stream_request)
+        });
+    let request_sender = stream_request_sender.clone();
+    streaming_client.expect_get_all_transactions().returning(
+        move |start_version, end_version, proof_version, include_events| {
+            let stream_request = StreamRequest::GetAllTransactions(GetAllTransactionsRequest {
+                start_version,
+                end_version,
+                proof_version,
+                include_events,
+            });
+            fail_stream_request(&request_sender, stream_request)
+        },
+    );
+    let request_sender = stream_request_sender.clone();
+    streaming_client
+        .expect_continuously_stream_transaction_outputs()
+        .returning(move |known_version, known_epoch, target| {
+            let stream_request = StreamRequest::ContinuouslyStreamTransactionOutputs(
+                ContinuouslyStreamTransactionOutputsRequest {
+                    known_version,
+                    known_epoch,
+                    target,
+                },
+            );
+            fail_stream_request(&request_sender, stream_request)
+        });
+    streaming_client
+        .expect_continuously_stream_transactions()
+        .returning(move |known_version, known_epoch, include_events, target| {
+            let stream_request = StreamRequest::ContinuouslyStreamTransactions(
+                ContinuouslyStreamTransactionsRequest {
+                    known_version,
+                    known_epoch,
+                    include_events,
+                    target,
+                },
+            );
+            fail_stream_request(&stream_request_sender, stream_request)
+        });
+
+    streaming_client
+}
+
+/// Sends the stream request along the given channel and fails the request
+fn fail_stream_request<T>(
+    stream_request_sender: &mpsc::UnboundedSender<StreamRequest>,
+    stream_request: StreamRequest,
+) -> Result<T, data_streaming_service::error::Error> {
+    let _ = stream_request_sender.unbounded_send(stream_request);
+    Err(data_streaming_service::error::Error::DataIsUnavailable(
+        "No data streams for harness drivers!".into(),
+    ))
+}
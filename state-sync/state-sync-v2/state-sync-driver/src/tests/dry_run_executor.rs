@@ -0,0 +1,77 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    dry_run_executor::DryRunChunkExecutor,
+    test_utils::fake_storage::FakeStorage,
+    tests::utils::{
+        create_ledger_info_at_version, create_output_list_with_proof,
+        create_transaction_list_with_proof,
+    },
+};
+use aptos_types::transaction::Transaction;
+use claim::{assert_err, assert_ok};
+use executor_types::ChunkExecutorTrait;
+use std::sync::Arc;
+
+#[test]
+fn test_dry_run_commit_chunks() {
+    // Create a dry-run chunk executor over a fake storage backend
+    let fake_storage = FakeStorage::new();
+    let chunk_executor = DryRunChunkExecutor::new(Arc::new(fake_storage.create_chunk_executor()));
+
+    // Execute and apply several chunks
+    let target_ledger_info = create_ledger_info_at_version(100);
+    let transaction_list_with_proof = create_transaction_list_with_proof();
+    let output_list_with_proof = create_output_list_with_proof();
+    let mut expected_transactions: Vec<Transaction> =
+        transaction_list_with_proof.transactions.clone();
+    expected_transactions.extend(
+        output_list_with_proof
+            .transactions_and_outputs
+            .iter()
+            .map(|(transaction, _)| transaction.clone()),
+    );
+    assert_ok!(chunk_executor.execute_chunk(
+        transaction_list_with_proof,
+        &target_ledger_info,
+        None
+    ));
+    assert_ok!(chunk_executor.apply_chunk(output_list_with_proof, &target_ledger_info, None));
+
+    // Commit the chunks and verify the synthetic notification holds the transactions
+    let commit_notification = chunk_executor.commit_chunks(2).unwrap();
+    assert_eq!(
+        commit_notification.committed_transactions,
+        expected_transactions
+    );
+    assert!(commit_notification.committed_events.is_empty());
+    assert!(!commit_notification.reconfiguration_occurred);
+
+    // Verify nothing was committed to storage
+    assert!(fake_storage.get_committed_chunks().is_empty());
+    assert_eq!(fake_storage.get_latest_version(), 0);
+
+    // Verify there's nothing left to commit
+    assert_err!(chunk_executor.commit_chunk());
+}
+
+#[test]
+fn test_dry_run_reset() {
+    // Create a dry-run chunk executor over a fake storage backend
+    let fake_storage = FakeStorage::new();
+    let chunk_executor = DryRunChunkExecutor::new(Arc::new(fake_storage.create_chunk_executor()));
+
+    // Execute a chunk and reset the executor
+    assert_ok!(chunk_executor.execute_chunk(
+        create_transaction_list_with_proof(),
+        &create_ledger_info_at_version(100),
+        None
+    ));
+    assert_ok!(chunk_executor.reset());
+
+    // Verify the executed chunk was dropped (and never committed)
+    assert_err!(chunk_executor.commit_chunk());
+    assert!(fake_storage.get_committed_chunks().is_empty());
+    assert_eq!(fake_storage.get_latest_version(), 0);
+}
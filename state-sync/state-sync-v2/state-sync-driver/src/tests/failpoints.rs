@@ -0,0 +1,262 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests that activate the failpoints of the driver and storage synchronizer
+//! (to inject errors and simulate crashes), and verify the node recovers.
+//!
+//! Note: failpoints are global to the process, so these tests are only
+//! compiled with the failpoints feature, and should be run separately, e.g.,
+//! `cargo test -p state-sync-driver --features failpoints failpoints`.
+
+use crate::{
+    test_utils::test_harness::TestHarness,
+    tests::{driver_harness::DriverHarnessBuilder, utils::create_transaction},
+};
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode, RoleType};
+use claim::{assert_err, assert_ok};
+use fail::FailScenario;
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::time::{sleep, timeout};
+
+// The timeout (of real time) for a crash to be injected at a failpoint
+const CRASH_TIMEOUT_SECS: u64 = 60;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_errors() {
+    let scenario = FailScenario::setup();
+
+    // Fail the first commits (before anything is written)
+    fail::cfg("state_sync::before_commit", "2*return").unwrap();
+
+    // Verify the node recovers and syncs the chain
+    let test_harness = create_output_syncing_harness();
+    sync_and_verify_chain(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_errors_after_write() {
+    let scenario = FailScenario::setup();
+
+    // Fail the first commit after the chunks have been written
+    fail::cfg("state_sync::after_commit", "1*return").unwrap();
+
+    // Verify the node recovers and syncs the chain (without rewriting
+    // the committed data).
+    let test_harness = create_output_syncing_harness();
+    sync_and_verify_chain(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crash_after_commit() {
+    let scenario = FailScenario::setup();
+
+    // Crash the node after the second commit has been written
+    let crashed = crash_at_failpoint("state_sync::after_commit", 1);
+    let mut test_harness = create_output_syncing_harness();
+    let chain = test_harness.get_chain();
+    test_harness.advertise_ledger_info(&chain.get_latest_ledger_info());
+    wait_for_crash(&crashed).await;
+
+    // Verify the node only synced part of the chain
+    let synced_version = test_harness.get_latest_version();
+    assert!(synced_version > 0 && synced_version < chain.get_latest_version());
+
+    // Restart the node and verify it recovers and syncs the chain
+    test_harness.restart_driver();
+    sync_and_verify_chain(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_consensus_response_errors() {
+    let scenario = FailScenario::setup();
+
+    // Create a bootstrapped validator
+    let driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .build()
+        .await;
+    assert!(driver_harness.driver_client().is_bootstrapped());
+
+    // Drop the first consensus response and verify consensus sees the error
+    fail::cfg("state_sync::before_consensus_response", "1*return").unwrap();
+    assert_err!(
+        driver_harness
+            .commit_transactions(vec![create_transaction()], vec![])
+            .await
+    );
+
+    // Verify the next commit is answered, and the driver has handled both commits
+    assert_ok!(
+        driver_harness
+            .commit_transactions(vec![create_transaction(), create_transaction()], vec![])
+            .await
+    );
+    let sync_status = driver_harness
+        .driver_client()
+        .get_sync_status(Duration::from_secs(10))
+        .await
+        .unwrap();
+    assert_eq!(sync_status.synced_version, Some(3));
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_crash_during_snapshot_finalization() {
+    let scenario = FailScenario::setup();
+
+    // Crash the node after the snapshot has been written to storage, but
+    // before the finalization marker has been removed.
+    let crashed = crash_at_failpoint("state_sync::before_snapshot_completion", 0);
+    let mut test_harness = create_snapshot_syncing_harness();
+    let chain = test_harness.get_chain();
+    test_harness.advertise_ledger_info(&chain.get_latest_ledger_info());
+    wait_for_crash(&crashed).await;
+
+    // Restart the node and verify it completes the finalization and syncs
+    // the rest of the chain.
+    test_harness.restart_driver();
+    sync_and_verify_snapshot(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_finalization_errors() {
+    let scenario = FailScenario::setup();
+
+    // Fail the first snapshot finalization (before anything is written)
+    fail::cfg("state_sync::before_snapshot_finalization", "1*return").unwrap();
+
+    // Verify the node recovers and finalizes the snapshot
+    let test_harness = create_snapshot_syncing_harness();
+    sync_and_verify_snapshot(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stalled_progress_checks() {
+    let scenario = FailScenario::setup();
+
+    // Skip all progress checks and advertise the chain
+    fail::cfg("state_sync::drive_progress", "return").unwrap();
+    let test_harness = create_output_syncing_harness();
+    let chain = test_harness.get_chain();
+    test_harness.advertise_ledger_info(&chain.get_latest_ledger_info());
+
+    // Verify the node makes no progress
+    sleep(Duration::from_secs(2)).await;
+    assert!(test_harness.get_stream_requests().is_empty());
+    assert_eq!(test_harness.get_latest_version(), 0);
+
+    // Resume the progress checks and verify the node syncs the chain
+    fail::remove("state_sync::drive_progress");
+    sync_and_verify_chain(&test_harness).await;
+
+    scenario.teardown();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stream_termination_errors() {
+    let scenario = FailScenario::setup();
+
+    // Fail the first stream termination (i.e., the end of the epoch ending stream)
+    fail::cfg("state_sync::before_stream_termination", "1*return").unwrap();
+
+    // Verify the node recovers and syncs the chain
+    let test_harness = create_output_syncing_harness();
+    sync_and_verify_chain(&test_harness).await;
+
+    scenario.teardown();
+}
+
+/// Configures the given failpoint to crash (i.e., panic) on the evaluation
+/// after the given number of evaluations. Returns a flag that is set once the
+/// crash has been injected.
+fn crash_at_failpoint(failpoint_name: &str, num_evaluations_before_crash: u64) -> Arc<AtomicBool> {
+    let crashed = Arc::new(AtomicBool::new(false));
+    let crash_flag = crashed.clone();
+    let num_evaluations = AtomicU64::new(0);
+    fail::cfg_callback(failpoint_name, move || {
+        if num_evaluations.fetch_add(1, Ordering::SeqCst) == num_evaluations_before_crash {
+            crash_flag.store(true, Ordering::SeqCst);
+            panic!("Injected crash at the failpoint!");
+        }
+    })
+    .unwrap();
+    crashed
+}
+
+/// Creates a harness for a fullnode that syncs the chain by applying outputs
+fn create_output_syncing_harness() -> TestHarness {
+    TestHarness::new(
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    )
+}
+
+/// Creates a harness for a fullnode that bootstraps by downloading the latest states
+fn create_snapshot_syncing_harness() -> TestHarness {
+    TestHarness::new(
+        BootstrappingMode::DownloadLatestStates,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    )
+}
+
+/// Advertises the entire chain, waits for the node to sync it and verifies
+/// the database matches the chain.
+async fn sync_and_verify_chain(test_harness: &TestHarness) {
+    let chain = test_harness.get_chain();
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(0, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+}
+
+/// Advertises the entire chain, waits for the node to bootstrap (at the last
+/// epoch end) and catch up, and verifies the state snapshot and the database.
+async fn sync_and_verify_snapshot(test_harness: &TestHarness) {
+    let chain = test_harness.get_chain();
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+    test_harness.wait_for_bootstrapping().await;
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    let snapshot_version = chain
+        .get_epoch_ending_ledger_info(chain.get_latest_epoch())
+        .ledger_info()
+        .version();
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(snapshot_version, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+    test_harness.verify_state_snapshot(snapshot_version);
+}
+
+/// Waits (with a timeout) until the given crash flag has been set
+async fn wait_for_crash(crashed: &AtomicBool) {
+    timeout(Duration::from_secs(CRASH_TIMEOUT_SECS), async {
+        while !crashed.load(Ordering::SeqCst) {
+            sleep(Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("The crash was never injected!");
+}
@@ -3,11 +3,15 @@
 
 use crate::metadata_storage::database_schema::{MetadataKey, MetadataSchema, MetadataValue};
 use crate::metadata_storage::{
-    MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotProgress,
+    MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotChunkProgress,
+    StateSnapshotFinalization, StateSnapshotProgress,
+};
+use crate::tests::utils::{
+    create_epoch_ending_ledger_info, create_ledger_info_at_version, create_output_list_with_proof,
 };
-use crate::tests::utils::{create_epoch_ending_ledger_info, create_ledger_info_at_version};
 use aptos_temppath::TempPath;
-use claim::{assert_err, assert_none};
+use aptos_types::ledger_info::LedgerInfoWithSignatures;
+use claim::{assert_err, assert_none, assert_some};
 use schemadb::schema::fuzzing::assert_encode_decode;
 
 #[test]
@@ -97,6 +101,21 @@ fn test_metadata_schema_encode_decode() {
             snapshot_sync_completed: false,
         }),
     );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::StateSnapshotFinalization,
+        &MetadataValue::StateSnapshotFinalization(create_snapshot_finalization(
+            &create_epoch_ending_ledger_info(),
+            5678,
+        )),
+    );
+    assert_encode_decode::<MetadataSchema>(
+        &MetadataKey::StateSnapshotChunkProgress,
+        &MetadataValue::StateSnapshotChunkProgress(StateSnapshotChunkProgress {
+            target_ledger_info: create_epoch_ending_ledger_info(),
+            last_committed_chunk_index: 12,
+            last_persisted_state_value_index: 5678,
+        }),
+    );
 }
 
 #[test]
@@ -144,6 +163,154 @@ fn test_multiple_reads_and_writes() {
     }
 }
 
+#[test]
+fn test_snapshot_chunk_progress() {
+    // Create a new metadata storage and verify there's no chunk progress
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_none!(metadata_storage.get_snapshot_chunk_progress().unwrap());
+
+    // Record the progress of several committed chunks
+    let target_ledger_info = create_ledger_info_at_version(1000);
+    for chunk_index in 0..5 {
+        metadata_storage
+            .update_snapshot_chunk_progress(StateSnapshotChunkProgress {
+                target_ledger_info: target_ledger_info.clone(),
+                last_committed_chunk_index: chunk_index,
+                last_persisted_state_value_index: chunk_index * 100,
+            })
+            .unwrap();
+    }
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Reopen the storage and verify the chunk progress and state value index
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_eq!(
+        metadata_storage.get_snapshot_chunk_progress().unwrap(),
+        Some(StateSnapshotChunkProgress {
+            target_ledger_info: target_ledger_info.clone(),
+            last_committed_chunk_index: 4,
+            last_persisted_state_value_index: 400,
+        })
+    );
+    assert_eq!(
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap(),
+        400
+    );
+    assert!(!metadata_storage
+        .is_snapshot_sync_complete(&target_ledger_info)
+        .unwrap());
+
+    // Verify the chunk progress can't be updated for a different target
+    assert_err!(
+        metadata_storage.update_snapshot_chunk_progress(StateSnapshotChunkProgress {
+            target_ledger_info: create_ledger_info_at_version(2000),
+            last_committed_chunk_index: 5,
+            last_persisted_state_value_index: 500,
+        })
+    );
+
+    // Finalize the snapshot and verify the chunk progress is removed
+    metadata_storage
+        .start_snapshot_finalization(create_snapshot_finalization(&target_ledger_info, 500))
+        .unwrap();
+    assert_some!(metadata_storage.get_snapshot_chunk_progress().unwrap());
+    metadata_storage
+        .complete_snapshot_finalization(&target_ledger_info)
+        .unwrap();
+    assert_none!(metadata_storage.get_snapshot_chunk_progress().unwrap());
+    assert!(metadata_storage
+        .is_snapshot_sync_complete(&target_ledger_info)
+        .unwrap());
+}
+
+#[test]
+fn test_snapshot_finalization() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Verify no finalization is pending and that it can't be completed
+    let target_ledger_info = create_ledger_info_at_version(1000);
+    assert_none!(metadata_storage.pending_snapshot_finalization().unwrap());
+    assert_err!(metadata_storage.complete_snapshot_finalization(&target_ledger_info));
+
+    // Persist some snapshot progress and then start the finalization
+    metadata_storage
+        .update_last_persisted_state_value_index(&target_ledger_info, 500, false)
+        .unwrap();
+    let snapshot_finalization = create_snapshot_finalization(&target_ledger_info, 999);
+    metadata_storage
+        .start_snapshot_finalization(snapshot_finalization.clone())
+        .unwrap();
+
+    // Drop the handle to the storage (mimic a reboot)
+    drop(metadata_storage);
+
+    // Reopen the storage and verify the finalization is pending (but not complete)
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_eq!(
+        Some(snapshot_finalization),
+        metadata_storage.pending_snapshot_finalization().unwrap()
+    );
+    assert_eq!(
+        999,
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap()
+    );
+    assert!(!metadata_storage
+        .is_snapshot_sync_complete(&target_ledger_info)
+        .unwrap());
+
+    // Verify the finalization can't be completed for a different target
+    assert_err!(
+        metadata_storage.complete_snapshot_finalization(&create_ledger_info_at_version(2000))
+    );
+
+    // Complete the finalization, reopen the storage and verify the snapshot sync is complete
+    metadata_storage
+        .complete_snapshot_finalization(&target_ledger_info)
+        .unwrap();
+    drop(metadata_storage);
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    assert_none!(metadata_storage.pending_snapshot_finalization().unwrap());
+    assert!(metadata_storage
+        .is_snapshot_sync_complete(&target_ledger_info)
+        .unwrap());
+    assert_eq!(
+        999,
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap()
+    );
+}
+
+#[test]
+fn test_snapshot_finalization_different_target() {
+    // Create a new metadata storage
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Write a new progress entry into the storage
+    let target_ledger_info = create_ledger_info_at_version(100);
+    metadata_storage
+        .update_last_persisted_state_value_index(&target_ledger_info, 10101, false)
+        .unwrap();
+
+    // Start a finalization with a different target and verify that it fails
+    let snapshot_finalization =
+        create_snapshot_finalization(&create_ledger_info_at_version(200), 10101);
+    metadata_storage
+        .start_snapshot_finalization(snapshot_finalization)
+        .unwrap_err();
+    assert_none!(metadata_storage.pending_snapshot_finalization().unwrap());
+}
+
 #[test]
 fn test_writes_to_different_targets() {
     // Create a new metadata storage
@@ -165,3 +332,16 @@ fn test_writes_to_different_targets() {
         .update_last_persisted_state_value_index(&target_ledger_info, 10101, false)
         .unwrap_err();
 }
+
+/// Creates a snapshot finalization marker for the given target and index
+fn create_snapshot_finalization(
+    target_ledger_info: &LedgerInfoWithSignatures,
+    last_persisted_state_value_index: u64,
+) -> StateSnapshotFinalization {
+    StateSnapshotFinalization {
+        target_ledger_info: target_ledger_info.clone(),
+        target_output_with_proof: create_output_list_with_proof(),
+        epoch_change_proofs: vec![target_ledger_info.clone()],
+        last_persisted_state_value_index,
+    }
+}
@@ -0,0 +1,331 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Golden tests for the metrics emitted by the driver in canonical scenarios.
+//! Dashboards and alerts depend on the names, labels and semantics of these
+//! metrics, so each scenario verifies that the series listed in its golden
+//! file (see `src/tests/goldens/metrics/`) are still emitted, and that the
+//! values of key series are consistent with the scenario.
+//!
+//! Note: golden files only list the series that must be emitted (series that
+//! depend on timing, e.g., slow chunk counters, should be left out). If a
+//! change to the metrics is intentional, the golden files can be regenerated
+//! (with every series emitted by each scenario) by running the tests with the
+//! UPDATE_GOLDENFILES environment variable set, e.g.,
+//! `UPDATE_GOLDENFILES=1 cargo test -p state-sync-driver metrics`. The
+//! regenerated files should be reviewed (and pruned) before checking them in.
+
+use crate::{
+    metrics::MetricSample,
+    notification_handlers::{ErrorSeverity, ErrorSource},
+    test_utils::test_harness::TestHarness,
+    tests::{
+        driver_harness::{
+            create_bootstrapped_validator_harness, DriverHarness, DriverHarnessBuilder,
+        },
+        utils::{create_error_notification, create_transaction},
+    },
+};
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode, NodeConfig, RoleType};
+use aptos_types::transaction::Transaction;
+use claim::{assert_err, assert_ok};
+use std::{collections::BTreeSet, env, fs, path::PathBuf, time::Duration};
+
+// The directory (relative to the crate root) that holds the golden files
+const GOLDEN_DIRECTORY: &str = "src/tests/goldens/metrics";
+
+// The environment variable that regenerates the golden files
+const UPDATE_GOLDENFILES: &str = "UPDATE_GOLDENFILES";
+
+#[tokio::test]
+async fn test_auto_bootstrap_metrics() {
+    // Create a driver for a validator with a waypoint at version 0
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::Validator).build().await;
+
+    // Pass the connection deadline and verify auto-bootstrapping occurs
+    let connection_deadline = Duration::from_secs(
+        NodeConfig::default()
+            .state_sync
+            .state_sync_driver
+            .max_connection_deadline_secs,
+    );
+    driver_harness.advance_time(connection_deadline).await;
+    assert!(driver_harness.driver_client().is_bootstrapped());
+
+    // Commit several batches of transactions (as consensus would)
+    let num_committed_transactions = commit_transaction_batches(&driver_harness, &[1, 3]).await;
+
+    // Verify the emitted series and the consensus commit metrics
+    let metric_samples = driver_harness.driver_metrics().scrape_metrics();
+    verify_golden_metrics("auto_bootstrap", &metric_samples);
+    verify_consensus_commit_metrics(&metric_samples, 2, num_committed_transactions);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_clean_bootstrap_metrics() {
+    // Create a fullnode that bootstraps by applying outputs from genesis
+    let test_harness = TestHarness::new(
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    );
+
+    // Advertise the entire chain and wait for the node to sync it
+    let chain = test_harness.get_chain();
+    test_harness.advertise_ledger_info(&chain.get_latest_ledger_info());
+    test_harness.wait_for_bootstrapping().await;
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    // Verify the emitted series
+    let metric_samples = test_harness.driver_metrics().scrape_metrics();
+    verify_golden_metrics("clean_bootstrap", &metric_samples);
+
+    // Verify every version (after genesis) was applied and committed
+    for operation in ["applied_transaction_outputs", "synced"] {
+        assert_eq!(
+            get_metric_value(
+                &metric_samples,
+                "aptos_state_sync_version",
+                &[("type", operation)]
+            ),
+            latest_version as f64
+        );
+    }
+
+    // Verify the concurrency gauges match the driver config
+    let driver_config = NodeConfig::default().state_sync.state_sync_driver;
+    for (label, value) in [
+        (
+            "max_pending_data_chunks",
+            driver_config.max_pending_data_chunks,
+        ),
+        (
+            "max_pending_executed_chunks",
+            driver_config.max_pending_executed_chunks,
+        ),
+        ("num_committer_threads", driver_config.num_committer_threads),
+        ("num_executor_threads", driver_config.num_executor_threads),
+    ] {
+        assert_eq!(
+            get_metric_value(
+                &metric_samples,
+                "aptos_state_sync_storage_synchronizer_concurrency",
+                &[("label", label)]
+            ),
+            value as f64
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_stream_error_recovery_metrics() {
+    // Create a bootstrapped validator and commit some transactions
+    let driver_harness = create_bootstrapped_validator_harness().await;
+    let mut num_committed_transactions = commit_transaction_batches(&driver_harness, &[2]).await;
+
+    // Fail a chunk of the continuous syncer stream (and resend the error)
+    for _ in 0..2 {
+        driver_harness
+            .notify_error(create_error_notification(
+                ErrorSource::ContinuousSyncer,
+                ErrorSeverity::Recoverable,
+                1,
+            ))
+            .await;
+    }
+
+    // Send a transient error for a later notification (the data was committed)
+    driver_harness
+        .notify_error(create_error_notification(
+            ErrorSource::ContinuousSyncer,
+            ErrorSeverity::Transient,
+            2,
+        ))
+        .await;
+
+    // Verify the node recovers and continues to handle consensus commits
+    num_committed_transactions += commit_transaction_batches(&driver_harness, &[1, 2]).await;
+
+    // Verify the emitted series and the consensus commit metrics
+    let metric_samples = driver_harness.driver_metrics().scrape_metrics();
+    verify_golden_metrics("stream_error_recovery", &metric_samples);
+    verify_consensus_commit_metrics(&metric_samples, 3, num_committed_transactions);
+
+    // Verify a single stream was terminated, and the other errors were counted
+    assert_eq!(
+        get_metric_value(
+            &metric_samples,
+            "aptos_state_sync_terminated_data_streams",
+            &[("component", "continuous_syncer"), ("stage", "executor")]
+        ),
+        1.0
+    );
+    for label in [
+        "duplicate_error_notification",
+        "transient_error_notification",
+    ] {
+        assert_eq!(
+            get_metric_value(
+                &metric_samples,
+                "aptos_state_sync_driver_counters",
+                &[("label", label)]
+            ),
+            1.0
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_sync_request_served_metrics() {
+    // Create a bootstrapped validator and commit some transactions
+    let driver_harness = create_bootstrapped_validator_harness().await;
+    let num_committed_transactions = commit_transaction_batches(&driver_harness, &[3]).await;
+
+    // Send a sync request for a future version and wait for the driver to receive it
+    let sync_request = driver_harness.spawn_sync_to_target(1000);
+    driver_harness.wait_for_sync_target(1000).await;
+
+    // Send sync requests for the synced version and verify they are served
+    // (and that the future sync request is superseded).
+    for _ in 0..2 {
+        assert_ok!(
+            driver_harness
+                .sync_to_target(num_committed_transactions)
+                .await
+        );
+    }
+    assert_err!(sync_request.await.unwrap());
+
+    // Verify the emitted series and the sync request metrics
+    let metric_samples = driver_harness.driver_metrics().scrape_metrics();
+    verify_golden_metrics("sync_request_served", &metric_samples);
+    verify_consensus_commit_metrics(&metric_samples, 1, num_committed_transactions);
+    assert_eq!(
+        get_metric_value(
+            &metric_samples,
+            "aptos_state_sync_driver_counters",
+            &[("label", "driver_consensus_sync_notification")]
+        ),
+        3.0
+    );
+}
+
+/// Commits batches of transactions (with the given sizes) as consensus
+/// would, and returns the total number of committed transactions.
+async fn commit_transaction_batches(driver_harness: &DriverHarness, batch_sizes: &[u64]) -> u64 {
+    for batch_size in batch_sizes {
+        let transactions: Vec<Transaction> =
+            (0..*batch_size).map(|_| create_transaction()).collect();
+        assert_ok!(
+            driver_harness
+                .commit_transactions(transactions, vec![])
+                .await
+        );
+    }
+    batch_sizes.iter().sum()
+}
+
+/// Formats the series of the given sample (i.e., the name and labels) as it
+/// appears in the golden files, e.g., `aptos_state_sync_version{type="synced"}`.
+fn format_series(metric_sample: &MetricSample) -> String {
+    if metric_sample.labels.is_empty() {
+        return metric_sample.name.clone();
+    }
+
+    let labels: Vec<String> = metric_sample
+        .labels
+        .iter()
+        .map(|(label_name, label_value)| format!("{}=\"{}\"", label_name, label_value))
+        .collect();
+    format!("{}{{{}}}", metric_sample.name, labels.join(","))
+}
+
+/// Returns the path of the golden file for the given scenario
+fn get_golden_path(scenario_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join(GOLDEN_DIRECTORY)
+        .join(format!("{}.txt", scenario_name))
+}
+
+/// Returns the value of the series with the given name and labels (the
+/// series must have been emitted).
+fn get_metric_value(metric_samples: &[MetricSample], name: &str, labels: &[(&str, &str)]) -> f64 {
+    metric_samples
+        .iter()
+        .find(|metric_sample| {
+            metric_sample.name == name
+                && metric_sample.labels.len() == labels.len()
+                && labels.iter().all(|(label_name, label_value)| {
+                    metric_sample.labels.get(*label_name).map(String::as_str) == Some(*label_value)
+                })
+        })
+        .unwrap_or_else(|| panic!("The series was not emitted: {} {:?}", name, labels))
+        .value
+}
+
+/// Verifies the consensus commit metrics match the given number of commit
+/// notifications and committed transactions.
+fn verify_consensus_commit_metrics(
+    metric_samples: &[MetricSample],
+    num_commit_notifications: u64,
+    num_committed_transactions: u64,
+) {
+    assert_eq!(
+        get_metric_value(
+            metric_samples,
+            "aptos_state_sync_driver_counters",
+            &[("label", "driver_consensus_commit_notification")]
+        ),
+        num_commit_notifications as f64
+    );
+    for operation in ["executed_transactions", "synced"] {
+        assert_eq!(
+            get_metric_value(
+                metric_samples,
+                "aptos_state_sync_version",
+                &[("type", operation)]
+            ),
+            num_committed_transactions as f64
+        );
+    }
+}
+
+/// Verifies every series in the golden file of the given scenario was
+/// emitted. If the golden files are being updated, the golden file is
+/// instead rewritten with all emitted series.
+fn verify_golden_metrics(scenario_name: &str, metric_samples: &[MetricSample]) {
+    let golden_path = get_golden_path(scenario_name);
+    let emitted_series: BTreeSet<String> = metric_samples.iter().map(format_series).collect();
+
+    // Rewrite the golden file (if requested)
+    if env::var(UPDATE_GOLDENFILES).is_ok() {
+        let mut golden_contents = format!(
+            "# The metric series emitted by the {} scenario (see tests/metrics.rs)\n",
+            scenario_name
+        );
+        for series in &emitted_series {
+            golden_contents.push_str(series);
+            golden_contents.push('\n');
+        }
+        fs::write(&golden_path, golden_contents).unwrap();
+        return;
+    }
+
+    // Verify all series in the golden file were emitted
+    let golden_contents = fs::read_to_string(&golden_path)
+        .unwrap_or_else(|error| panic!("Failed to read {:?}: {:?}", golden_path, error));
+    let missing_series: Vec<&str> = golden_contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter(|series| !emitted_series.contains(*series))
+        .collect();
+    assert!(
+        missing_series.is_empty(),
+        "The {} scenario no longer emits these metric series: {:#?}. If the change is \
+        intentional, rerun the test with {}=1 to update the golden files.",
+        scenario_name,
+        missing_series,
+        UPDATE_GOLDENFILES
+    );
+}
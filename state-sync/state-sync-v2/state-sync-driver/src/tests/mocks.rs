@@ -3,11 +3,21 @@
 
 use crate::tests::utils::{create_empty_epoch_state, create_epoch_ending_ledger_info};
 use crate::{
-    error::Error, metadata_storage::MetadataStorageInterface,
-    storage_synchronizer::StorageSynchronizerInterface, tests::utils::create_transaction_info,
+    error::Error,
+    metadata_storage::{
+        MetadataStorageInterface, StateSnapshotChunkProgress, StateSnapshotFinalization,
+    },
+    notification_handlers::{CommitSubscriber, ErrorSource},
+    storage_synchronizer::{
+        ApplyOrExecute, CommittedVersionHandle, PendingDataSummary, ShutdownSummary,
+        StorageSynchronizerInterface, TransactionChunkPayload,
+    },
+    tests::utils::create_transaction_info,
 };
 use anyhow::Result;
 use aptos_crypto::HashValue;
+use aptos_data_client::{AptosDataClient, GlobalDataSummary, Response};
+use aptos_infallible::Mutex;
 use aptos_types::epoch_state::EpochState;
 use aptos_types::{
     account_address::AccountAddress,
@@ -25,7 +35,7 @@ use aptos_types::{
         state_value::{StateValue, StateValueChunkWithProof},
     },
     transaction::{
-        AccountTransactionsWithProof, TransactionInfo, TransactionListWithProof,
+        AccountTransactionsWithProof, Transaction, TransactionInfo, TransactionListWithProof,
         TransactionOutputListWithProof, TransactionToCommit, TransactionWithProof, Version,
     },
 };
@@ -35,9 +45,17 @@ use data_streaming_service::{
     data_stream::DataStreamListener,
     streaming_client::{DataStreamingClient, Epoch, NotificationFeedback},
 };
-use executor_types::{ChunkCommitNotification, ChunkExecutorTrait};
+use executor_types::{CancellationToken, ChunkCommitNotification, ChunkExecutorTrait};
+use mempool_notifications::MempoolNotificationSender;
 use mockall::mock;
-use std::sync::Arc;
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use storage_interface::{
     state_delta::StateDelta, DbReader, DbReaderWriter, DbWriter, ExecutedTrees, Order,
     StateSnapshotReceiver,
@@ -47,6 +65,11 @@ use tokio::task::JoinHandle;
 // TODO(joshlind): if we see these as generally useful, we should
 // modify the definitions in the rest of the code.
 
+/// Creates a mock aptos data client
+pub fn create_mock_aptos_data_client() -> MockAptosDataClient {
+    MockAptosDataClient::new()
+}
+
 /// Creates a mock chunk executor
 pub fn create_mock_executor() -> MockChunkExecutor {
     MockChunkExecutor::new()
@@ -57,9 +80,12 @@ pub fn create_mock_db_reader() -> MockDatabaseReader {
     MockDatabaseReader::new()
 }
 
-/// Creates a mock database writer
+/// Creates a mock database writer (that allows pruning to be paused and resumed)
 pub fn create_mock_db_writer() -> MockDatabaseWriter {
-    MockDatabaseWriter::new()
+    let mut db_writer = MockDatabaseWriter::new();
+    db_writer.expect_pause_pruning().returning(|| Ok(()));
+    db_writer.expect_resume_pruning().returning(|| Ok(()));
+    db_writer
 }
 
 /// Creates a mock database reader writer
@@ -87,7 +113,11 @@ pub fn create_mock_reader_writer(
 
 /// Creates a mock state snapshot receiver
 pub fn create_mock_receiver() -> MockSnapshotReceiver {
-    MockSnapshotReceiver::new()
+    let mut snapshot_receiver = MockSnapshotReceiver::new();
+    snapshot_receiver
+        .expect_last_chunk_verification_duration()
+        .returning(|| None);
+    snapshot_receiver
 }
 
 /// Creates a mock data streaming client
@@ -106,6 +136,9 @@ pub fn create_ready_storage_synchronizer(expect_reset_executor: bool) -> MockSto
     let mut mock_storage_synchronizer = create_mock_storage_synchronizer();
     mock_storage_synchronizer
         .expect_pending_storage_data()
+        .return_const(PendingDataSummary::default());
+    mock_storage_synchronizer
+        .expect_verifies_state_value_chunks()
         .return_const(false);
     if expect_reset_executor {
         mock_storage_synchronizer
@@ -114,11 +147,69 @@ pub fn create_ready_storage_synchronizer(expect_reset_executor: bool) -> MockSto
         mock_storage_synchronizer
             .expect_reset_chunk_executor()
             .return_const(Ok(()));
+        mock_storage_synchronizer
+            .expect_finalize_pending_state_snapshot()
+            .return_const(Ok(false));
     }
 
     mock_storage_synchronizer
 }
 
+// This automatically creates a MockAptosDataClient.
+mock! {
+    pub AptosDataClient {}
+    #[async_trait]
+    impl AptosDataClient for AptosDataClient {
+        fn get_global_data_summary(&self) -> GlobalDataSummary;
+
+        async fn get_epoch_ending_ledger_infos(
+            &self,
+            start_epoch: Epoch,
+            expected_end_epoch: Epoch,
+        ) -> aptos_data_client::Result<Response<Vec<LedgerInfoWithSignatures>>>;
+
+        async fn get_new_transaction_outputs_with_proof(
+            &self,
+            known_version: Version,
+            known_epoch: Epoch,
+        ) -> aptos_data_client::Result<Response<(TransactionOutputListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn get_new_transactions_with_proof(
+            &self,
+            known_version: Version,
+            known_epoch: Epoch,
+            include_events: bool,
+        ) -> aptos_data_client::Result<Response<(TransactionListWithProof, LedgerInfoWithSignatures)>>;
+
+        async fn get_number_of_states(&self, version: Version) -> aptos_data_client::Result<Response<u64>>;
+
+        async fn get_state_values_with_proof(
+            &self,
+            version: u64,
+            start_index: u64,
+            end_index: u64,
+        ) -> aptos_data_client::Result<Response<StateValueChunkWithProof>>;
+
+        async fn get_transaction_outputs_with_proof(
+            &self,
+            proof_version: Version,
+            start_version: Version,
+            end_version: Version,
+        ) -> aptos_data_client::Result<Response<TransactionOutputListWithProof>>;
+
+        async fn get_transactions_with_proof(
+            &self,
+            proof_version: Version,
+            start_version: Version,
+            end_version: Version,
+            include_events: bool,
+        ) -> aptos_data_client::Result<Response<TransactionListWithProof>>;
+    }
+    impl Clone for AptosDataClient {
+        fn clone(&self) -> Self;
+    }
+}
+
 // This automatically creates a MockChunkExecutor.
 mock! {
     pub ChunkExecutor {}
@@ -152,6 +243,65 @@ mock! {
         ) -> Result<ChunkCommitNotification>;
 
         fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
+        fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification>;
+
+        fn reset(&self) -> Result<()>;
+
+        fn finish(&self);
+    }
+}
+
+// This automatically creates a MockCancellableChunkExecutor (i.e., a chunk
+// executor that also mocks the cancellable execute/apply calls).
+mock! {
+    pub CancellableChunkExecutor {}
+    impl ChunkExecutorTrait for CancellableChunkExecutor {
+        fn execute_chunk<'a>(
+            &self,
+            txn_list_with_proof: TransactionListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+        ) -> Result<()>;
+
+        fn apply_chunk<'a>(
+            &self,
+            txn_output_list_with_proof: TransactionOutputListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+        ) -> anyhow::Result<()>;
+
+        fn execute_chunk_cancellable<'a>(
+            &self,
+            txn_list_with_proof: TransactionListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+            cancellation_token: &CancellationToken,
+        ) -> Result<()>;
+
+        fn apply_chunk_cancellable<'a>(
+            &self,
+            txn_output_list_with_proof: TransactionOutputListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+            cancellation_token: &CancellationToken,
+        ) -> Result<()>;
+
+        fn execute_and_commit_chunk<'a>(
+            &self,
+            txn_list_with_proof: TransactionListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+        ) -> Result<ChunkCommitNotification>;
+
+        fn apply_and_commit_chunk<'a>(
+            &self,
+            txn_output_list_with_proof: TransactionOutputListWithProof,
+            verified_target_li: &LedgerInfoWithSignatures,
+            epoch_change_li: Option<&'a LedgerInfoWithSignatures>,
+        ) -> Result<ChunkCommitNotification>;
+
+        fn commit_chunk(&self) -> Result<ChunkCommitNotification>;
+        fn commit_chunks(&self, num_chunks: usize) -> Result<ChunkCommitNotification>;
 
         fn reset(&self) -> Result<()>;
 
@@ -227,6 +377,8 @@ mock! {
 
         fn get_latest_version(&self) -> Result<Version>;
 
+        fn get_first_txn_version(&self) -> Result<Option<Version>>;
+
         fn get_latest_commit_metadata(&self) -> Result<(Version, u64)>;
 
         fn get_account_transaction(
@@ -309,6 +461,10 @@ mock! {
             ledger_infos: &[LedgerInfoWithSignatures],
         ) -> Result<()>;
 
+        fn pause_pruning(&self) -> Result<()>;
+
+        fn resume_pruning(&self) -> Result<()>;
+
         fn save_transactions<'a>(
             &self,
             txns_to_commit: &[TransactionToCommit],
@@ -325,6 +481,11 @@ mock! {
 mock! {
     pub MetadataStorage {}
     impl MetadataStorageInterface for MetadataStorage {
+        fn complete_snapshot_finalization(
+            &self,
+            target_ledger_info: &LedgerInfoWithSignatures,
+        ) -> Result<(), Error>;
+
         fn is_snapshot_sync_complete(
             &self,
             target_ledger_info: &LedgerInfoWithSignatures,
@@ -335,8 +496,22 @@ mock! {
             target_ledger_info: &LedgerInfoWithSignatures,
         ) -> Result<u64, Error>;
 
+        fn get_snapshot_chunk_progress(&self) -> Result<Option<StateSnapshotChunkProgress>, Error>;
+
         fn previous_snapshot_sync_target(&self) -> Result<Option<LedgerInfoWithSignatures>, Error>;
 
+        fn pending_snapshot_finalization(&self) -> Result<Option<StateSnapshotFinalization>, Error>;
+
+        fn start_snapshot_finalization(
+            &self,
+            snapshot_finalization: StateSnapshotFinalization,
+        ) -> Result<(), Error>;
+
+        fn update_snapshot_chunk_progress(
+            &self,
+            snapshot_chunk_progress: StateSnapshotChunkProgress,
+        ) -> Result<(), Error>;
+
         fn update_last_persisted_state_value_index(
             &self,
             target_ledger_info: &LedgerInfoWithSignatures,
@@ -356,6 +531,8 @@ mock! {
     impl StateSnapshotReceiver<StateKey, StateValue> for SnapshotReceiver {
         fn add_chunk(&mut self, chunk: Vec<(StateKey, StateValue)>, proof: SparseMerkleRangeProof) -> Result<()>;
 
+        fn last_chunk_verification_duration(&self) -> Option<Duration>;
+
         fn finish(self) -> Result<()>;
 
         fn finish_box(self: Box<Self>) -> Result<()>;
@@ -423,22 +600,6 @@ mock! {
 mock! {
     pub StorageSynchronizer {}
     impl StorageSynchronizerInterface for StorageSynchronizer {
-        fn apply_transaction_outputs(
-            &mut self,
-            notification_id: NotificationId,
-            output_list_with_proof: TransactionOutputListWithProof,
-            target_ledger_info: LedgerInfoWithSignatures,
-            end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-        ) -> Result<(), crate::error::Error>;
-
-        fn execute_transactions(
-            &mut self,
-            notification_id: NotificationId,
-            transaction_list_with_proof: TransactionListWithProof,
-            target_ledger_info: LedgerInfoWithSignatures,
-            end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
-        ) -> Result<(), crate::error::Error>;
-
         fn initialize_state_synchronizer(
             &mut self,
             epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
@@ -446,12 +607,36 @@ mock! {
             target_output_with_proof: TransactionOutputListWithProof,
         ) -> Result<JoinHandle<()>, crate::error::Error>;
 
-        fn pending_storage_data(&self) -> bool;
+        fn finalize_pending_state_snapshot(&mut self) -> Result<bool, crate::error::Error>;
+
+        fn notify_stream_reset(&self);
+
+        fn pending_storage_data(&self) -> PendingDataSummary;
+
+        fn committed_version_handle(&self) -> CommittedVersionHandle;
 
-        fn save_state_values(
+        fn reset_pending_data(&self);
+
+        fn shutdown(&self) -> ShutdownSummary;
+
+        fn save_state_values_with_proof(
             &mut self,
             notification_id: NotificationId,
             state_value_chunk_with_proof: StateValueChunkWithProof,
+            verify: bool,
+        ) -> Result<(), crate::error::Error>;
+
+        fn verifies_state_value_chunks(&self) -> bool;
+
+        fn submit_transaction_chunk(
+            &mut self,
+            notification_id: NotificationId,
+            error_source: ErrorSource,
+            apply_or_execute: ApplyOrExecute,
+            chunk_payload: TransactionChunkPayload,
+            target_ledger_info: LedgerInfoWithSignatures,
+            end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+            ledger_info_verifier: Option<EpochState>,
         ) -> Result<(), crate::error::Error>;
 
         fn reset_chunk_executor(&self) -> Result<(), crate::error::Error>;
@@ -462,3 +647,165 @@ mock! {
         fn clone(&self) -> Self;
     }
 }
+
+/// A mempool notifier that fails (transiently) for the first `num_failures`
+/// notifications and succeeds thereafter. All delivered notifications are
+/// recorded so that tests can verify their contents and ordering.
+#[derive(Clone)]
+pub struct FlakyMempoolNotifier {
+    delivered_notifications: Arc<Mutex<Vec<Vec<Transaction>>>>,
+    delivered_timestamps: Arc<Mutex<Vec<u64>>>,
+    num_failures_remaining: Arc<AtomicU64>,
+}
+
+impl FlakyMempoolNotifier {
+    pub fn new(num_failures: u64) -> Self {
+        Self {
+            delivered_notifications: Arc::new(Mutex::new(vec![])),
+            delivered_timestamps: Arc::new(Mutex::new(vec![])),
+            num_failures_remaining: Arc::new(AtomicU64::new(num_failures)),
+        }
+    }
+
+    /// Returns all notifications that were successfully delivered (in order)
+    pub fn get_delivered_notifications(&self) -> Vec<Vec<Transaction>> {
+        self.delivered_notifications.lock().clone()
+    }
+
+    /// Returns the block timestamps of all delivered notifications (in order)
+    pub fn get_delivered_timestamps(&self) -> Vec<u64> {
+        self.delivered_timestamps.lock().clone()
+    }
+}
+
+#[async_trait]
+impl MempoolNotificationSender for FlakyMempoolNotifier {
+    async fn notify_new_commit(
+        &self,
+        committed_transactions: Vec<Transaction>,
+        block_timestamp_usecs: u64,
+        _notification_timeout_ms: u64,
+    ) -> Result<(), mempool_notifications::Error> {
+        // Fail the notification if we still have failures remaining
+        let num_failures_remaining = self.num_failures_remaining.load(Ordering::Relaxed);
+        if num_failures_remaining > 0 {
+            self.num_failures_remaining
+                .store(num_failures_remaining - 1, Ordering::Relaxed);
+            return Err(mempool_notifications::Error::TimeoutWaitingForMempool);
+        }
+
+        // Otherwise, record the delivered notification
+        self.delivered_notifications
+            .lock()
+            .push(committed_transactions);
+        self.delivered_timestamps.lock().push(block_timestamp_usecs);
+        Ok(())
+    }
+}
+
+/// A mempool notifier that responds to each notification according to a
+/// schedule of (delay, result) pairs. Once the schedule is exhausted, all
+/// notifications succeed immediately. All delivered notifications are recorded.
+#[derive(Clone)]
+pub struct ScheduledMempoolNotifier {
+    delivered_notifications: Arc<Mutex<Vec<Vec<Transaction>>>>,
+    num_notifications: Arc<AtomicU64>,
+    schedule: Arc<Mutex<VecDeque<(Duration, Result<(), mempool_notifications::Error>)>>>,
+}
+
+impl ScheduledMempoolNotifier {
+    pub fn new(schedule: Vec<(Duration, Result<(), mempool_notifications::Error>)>) -> Self {
+        Self {
+            delivered_notifications: Arc::new(Mutex::new(vec![])),
+            num_notifications: Arc::new(AtomicU64::new(0)),
+            schedule: Arc::new(Mutex::new(schedule.into())),
+        }
+    }
+
+    /// Returns all notifications that were successfully delivered (in order)
+    pub fn get_delivered_notifications(&self) -> Vec<Vec<Transaction>> {
+        self.delivered_notifications.lock().clone()
+    }
+
+    /// Returns the number of notifications received (including failures)
+    pub fn get_num_notifications(&self) -> u64 {
+        self.num_notifications.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl MempoolNotificationSender for ScheduledMempoolNotifier {
+    async fn notify_new_commit(
+        &self,
+        committed_transactions: Vec<Transaction>,
+        _block_timestamp_usecs: u64,
+        _notification_timeout_ms: u64,
+    ) -> Result<(), mempool_notifications::Error> {
+        self.num_notifications.fetch_add(1, Ordering::Relaxed);
+
+        // Respond using the next scheduled entry (if any)
+        let scheduled_response = self.schedule.lock().pop_front();
+        let result = match scheduled_response {
+            Some((delay, result)) => {
+                tokio::time::sleep(delay).await;
+                result
+            }
+            None => Ok(()),
+        };
+
+        // Record the notification if it was delivered
+        if result.is_ok() {
+            self.delivered_notifications
+                .lock()
+                .push(committed_transactions);
+        }
+        result
+    }
+}
+
+/// A commit subscriber that records all notified version ranges, and fails
+/// every notification if `fail_notifications` is true.
+#[derive(Clone)]
+pub struct RecordingCommitSubscriber {
+    fail_notifications: bool,
+    name: &'static str,
+    notified_versions: Arc<Mutex<Vec<(Version, Version)>>>,
+}
+
+impl RecordingCommitSubscriber {
+    pub fn new(name: &'static str, fail_notifications: bool) -> Self {
+        Self {
+            fail_notifications,
+            name,
+            notified_versions: Arc::new(Mutex::new(vec![])),
+        }
+    }
+
+    /// Returns all version ranges notified to the subscriber (in order)
+    pub fn get_notified_versions(&self) -> Vec<(Version, Version)> {
+        self.notified_versions.lock().clone()
+    }
+}
+
+#[async_trait]
+impl CommitSubscriber for RecordingCommitSubscriber {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn notify_commit(
+        &self,
+        first_version: Version,
+        last_version: Version,
+        _latest_synced_ledger_info: &LedgerInfoWithSignatures,
+    ) -> Result<()> {
+        self.notified_versions
+            .lock()
+            .push((first_version, last_version));
+        if self.fail_notifications {
+            Err(anyhow::format_err!("Failed to handle the commit!"))
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -2,10 +2,27 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bootstrapper;
+mod chaos;
 mod continuous_syncer;
 mod driver;
 mod driver_factory;
+mod driver_harness;
+mod dry_run_executor;
+#[cfg(feature = "failpoints")]
+mod failpoints;
 mod metadata_storage;
+mod metrics;
 mod mocks;
+mod notification_handlers;
+mod notification_ordering;
+mod output_fallback_handler;
+mod reconfig_events;
+mod scripted_storage_synchronizer;
+mod speculative_stream_state;
 mod storage_synchronizer;
+mod stream_initialization;
+mod sync_lag;
+mod sync_request_scenarios;
+mod synthetic_chain;
+mod test_harness;
 mod utils;
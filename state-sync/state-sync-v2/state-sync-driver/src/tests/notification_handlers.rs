@@ -0,0 +1,3281 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    metrics::{self, DriverMetrics},
+    notification_handlers::{
+        ClientSyncRequestHandler, CommitEvent, CommitNotification, CommitNotificationListener,
+        CommitOrigin, CommitState, CommitStateTracker, CommitSubscriber, CommitSubscriberRegistry,
+        CommitSummary, CommittedStateSnapshot, CommittedTransactions, ConsensusCommitNotifier,
+        ConsensusNotificationHandler, ErrorNotification, ErrorSeverity, ErrorSource,
+        EventNotificationHandler, MempoolNotificationHandler, NotifiedVersionTracker,
+        TerminatedStreamTracker,
+    },
+    storage_synchronizer::{FailedOperation, StorageSynchronizerStage},
+    tests::{
+        mocks::{
+            create_mock_db_reader, create_mock_reader_writer, FlakyMempoolNotifier,
+            RecordingCommitSubscriber, ScheduledMempoolNotifier,
+        },
+        utils::{
+            create_empty_epoch_state, create_epoch_state, create_event,
+            create_ledger_info_at_version, create_signed_ledger_info, create_transaction,
+            create_validator_signers,
+        },
+    },
+    utils,
+};
+use aptos_config::config::StateSyncDriverConfig;
+use aptos_crypto::HashValue;
+use aptos_infallible::{Mutex, RwLock};
+use aptos_time_service::TimeService;
+use aptos_types::{
+    account_address::AccountAddress,
+    block_metadata::BlockMetadata,
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    event::EventKey,
+    on_chain_config::{new_epoch_event_key, ON_CHAIN_CONFIG_REGISTRY},
+    transaction::{ChangeSet, Transaction, Version, WriteSetPayload},
+    validator_verifier::generate_validator_verifier,
+    write_set::WriteSetMut,
+};
+use aptos_vm::AptosVM;
+use aptosdb::AptosDB;
+use claim::{assert_matches, assert_none};
+use consensus_notifications::{ConsensusCommitNotification, ConsensusSyncNotification};
+use data_streaming_service::data_notification::NotificationId;
+use event_notifications::{
+    EventNotificationListener, EventSubscriptionService, ReconfigNotificationListener,
+};
+use executor_test_helpers::bootstrap_genesis;
+use futures::{
+    channel::{mpsc, oneshot},
+    FutureExt, StreamExt,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_interface::DbReaderWriter;
+use tokio::time::timeout;
+
+#[tokio::test]
+async fn test_mempool_notification_retry() {
+    // Create a mempool notification handler that fails the first two notifications
+    let mempool_notifier = FlakyMempoolNotifier::new(2);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(5, 10_000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of new transactions
+    let transactions = vec![create_transaction(), create_transaction()];
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            transactions.clone(),
+            0,
+            0,
+            1,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify the notification is eventually delivered
+    verify_delivered_notifications(&mempool_notifier, vec![transactions]).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_retry_backoff() {
+    // Create a mempool notification handler (with a mock time service and a
+    // long retry backoff) that fails the first notification.
+    let driver_config = StateSyncDriverConfig {
+        mempool_notification_retry_backoff_ms: 60_000,
+        ..create_retry_config(5, 600_000)
+    };
+    let mempool_notifier = FlakyMempoolNotifier::new(1);
+    let time_service = TimeService::mock();
+    let mut mempool_notification_handler = MempoolNotificationHandler::new_with_optional_sender(
+        driver_config,
+        Some(mempool_notifier.clone()),
+        time_service.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of new transactions
+    let transactions = vec![create_transaction()];
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            transactions.clone(),
+            0,
+            0,
+            0,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Wait until the retry task is waiting on the backoff
+    let mock_time_service = time_service.into_mock();
+    timeout(Duration::from_secs(10), async {
+        while mock_time_service.num_waiters() == 0 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .unwrap();
+
+    // Verify the notification isn't retried until the backoff has elapsed
+    mock_time_service.advance_ms(59_999);
+    tokio::task::yield_now().await;
+    assert!(mempool_notifier.get_delivered_notifications().is_empty());
+    mock_time_service.advance_ms(1);
+    verify_delivered_notifications(&mempool_notifier, vec![transactions]).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_retry_ordering() {
+    // Create a mempool notification handler that fails the first notification
+    let mempool_notifier = FlakyMempoolNotifier::new(1);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(5, 10_000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of several consecutive commits
+    let all_transactions = vec![
+        vec![create_transaction()],
+        vec![create_transaction(), create_transaction()],
+        vec![create_transaction()],
+    ];
+    let mut first_version = 0;
+    for transactions in all_transactions.clone() {
+        let last_version = first_version + transactions.len() as u64 - 1;
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                transactions,
+                0,
+                first_version,
+                last_version,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+        first_version = last_version + 1;
+    }
+
+    // Verify the notifications are delivered in order (even though the first failed)
+    verify_delivered_notifications(&mempool_notifier, all_transactions).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_retries_exhausted() {
+    // Create a mempool notification handler that fails the first three
+    // notification attempts (but only allows two retries).
+    let mempool_notifier = FlakyMempoolNotifier::new(3);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(2, 10_000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two consecutive commits
+    let dropped_transactions = vec![create_transaction()];
+    let delivered_transactions = vec![create_transaction()];
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            dropped_transactions,
+            0,
+            0,
+            0,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            delivered_transactions.clone(),
+            0,
+            1,
+            1,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify the first notification is dropped and the second is delivered
+    verify_delivered_notifications(&mempool_notifier, vec![delivered_transactions]).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_retry_deadline() {
+    // Create a mempool notification handler that fails the first notification
+    // attempt (and has a retry deadline that has always passed).
+    let mempool_notifier = FlakyMempoolNotifier::new(1);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(100, 0),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two consecutive commits
+    let dropped_transactions = vec![create_transaction()];
+    let delivered_transactions = vec![create_transaction()];
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            dropped_transactions,
+            0,
+            0,
+            0,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            delivered_transactions.clone(),
+            0,
+            1,
+            1,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify the first notification is dropped and the second is delivered
+    verify_delivered_notifications(&mempool_notifier, vec![delivered_transactions]).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_duplicates() {
+    // Create a mempool notification handler
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of the same commit twice and verify it's only delivered once
+    let transactions: Vec<Transaction> = (0..4).map(|_| create_transaction()).collect();
+    let num_duplicates = read_driver_counter(metrics::DUPLICATE_MEMPOOL_NOTIFICATION);
+    for _ in 0..2 {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                transactions[..2].to_vec(),
+                0,
+                0,
+                1,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..2].to_vec()]
+    );
+
+    // Notify mempool of a subset of the delivered commit and verify it's skipped
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![transactions[1].clone()],
+            0,
+            1,
+            1,
+            CommitOrigin::Consensus,
+        )
+        .await
+        .unwrap();
+    assert_eq!(mempool_notifier.get_delivered_notifications().len(), 1);
+    assert!(read_driver_counter(metrics::DUPLICATE_MEMPOOL_NOTIFICATION) >= num_duplicates + 2);
+
+    // Notify mempool of a partially overlapping commit and verify it's trimmed
+    let num_trimmed = read_driver_counter(metrics::TRIMMED_MEMPOOL_NOTIFICATION);
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            transactions[1..].to_vec(),
+            0,
+            1,
+            3,
+            CommitOrigin::Consensus,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..2].to_vec(), transactions[2..].to_vec()]
+    );
+    assert!(read_driver_counter(metrics::TRIMMED_MEMPOOL_NOTIFICATION) > num_trimmed);
+}
+
+#[tokio::test]
+async fn test_mempool_notification_retry_duplicates() {
+    // Create a mempool notification handler that fails the first notification
+    let mempool_notifier = FlakyMempoolNotifier::new(1);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(5, 10_000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of the same commit twice (the first notification will be
+    // retried, so the duplicate is queued behind it).
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+    for _ in 0..2 {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                transactions[..2].to_vec(),
+                0,
+                0,
+                1,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+
+    // Notify mempool of the next commit
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![transactions[2].clone()],
+            0,
+            2,
+            2,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify the duplicate was never delivered
+    verify_delivered_notifications(
+        &mempool_notifier,
+        vec![transactions[..2].to_vec(), vec![transactions[2].clone()]],
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_batching() {
+    // Create a mempool notification handler that batches up to 3 transactions
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_batching_config(60_000, 3),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two small commits and verify nothing is sent
+    let transactions = vec![
+        create_transaction(),
+        create_transaction(),
+        create_transaction(),
+    ];
+    for (index, transaction) in transactions.iter().take(2).enumerate() {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                vec![transaction.clone()],
+                (10 - index) as u64,
+                index as u64,
+                index as u64,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+    assert!(mempool_notifier.get_delivered_notifications().is_empty());
+
+    // Notify mempool of another commit (filling the batch)
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![transactions[2].clone()],
+            5,
+            2,
+            2,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify a single merged notification is sent with the highest timestamp
+    verify_delivered_notifications(&mempool_notifier, vec![transactions]).await;
+    assert_eq!(mempool_notifier.get_delivered_timestamps(), vec![10]);
+}
+
+#[tokio::test]
+async fn test_mempool_notification_batch_expiration() {
+    // Create a mempool notification handler that batches for a short time
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_batching_config(50, 1000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two small commits
+    let transactions = vec![create_transaction(), create_transaction()];
+    for (index, transaction) in transactions.iter().enumerate() {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                vec![transaction.clone()],
+                0,
+                index as u64,
+                index as u64,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+
+    // Verify the merged notification is sent once the batch expires
+    verify_delivered_notifications(&mempool_notifier, vec![transactions]).await;
+}
+
+#[tokio::test]
+async fn test_mempool_notification_batch_expiration_retry() {
+    // Create a mempool notification handler that batches for a short time
+    // and where the first notification is slow and fails transiently.
+    let mempool_notifier = ScheduledMempoolNotifier::new(vec![(
+        Duration::from_millis(500),
+        Err(mempool_notifications::Error::TimeoutWaitingForMempool),
+    )]);
+    let driver_config = StateSyncDriverConfig {
+        mempool_notification_batch_max_delay_ms: 50,
+        mempool_notification_batch_max_transactions: 2,
+        ..create_retry_config(5, 10_000)
+    };
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        driver_config,
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of a small commit and wait until the expired batch is sent
+    let transactions = vec![
+        create_transaction(),
+        create_transaction(),
+        create_transaction(),
+    ];
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![transactions[0].clone()],
+            0,
+            0,
+            0,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+    wait_for_mempool_notifications(&mempool_notifier, 1).await;
+
+    // Notify mempool of a newer commit (filling the batch) while the
+    // expired batch is still in flight.
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            transactions[1..].to_vec(),
+            0,
+            1,
+            2,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+
+    // Verify the expired batch is retried and delivered before the newer commit
+    for _ in 0..100 {
+        if mempool_notifier.get_delivered_notifications().len() >= 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..1].to_vec(), transactions[1..].to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn test_mempool_notification_batch_flush() {
+    // Create a mempool notification handler that batches for a long time
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_batching_config(60_000, 1000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two small commits and verify nothing is sent
+    let transactions = vec![create_transaction(), create_transaction()];
+    for (index, transaction) in transactions.iter().enumerate() {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                vec![transaction.clone()],
+                0,
+                index as u64,
+                index as u64,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+    assert!(mempool_notifier.get_delivered_notifications().is_empty());
+
+    // Flush the batch and verify the merged notification is sent immediately
+    mempool_notification_handler
+        .flush_batched_notifications()
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions]
+    );
+
+    // Verify flushing an empty batch is a no-op
+    mempool_notification_handler
+        .flush_batched_notifications()
+        .await
+        .unwrap();
+    assert_eq!(mempool_notifier.get_delivered_notifications().len(), 1);
+}
+
+#[tokio::test]
+async fn test_mempool_notification_batch_non_contiguous() {
+    // Create a mempool notification handler that batches for a long time
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_batching_config(60_000, 1000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of two contiguous commits and verify nothing is sent
+    let transactions = vec![
+        create_transaction(),
+        create_transaction(),
+        create_transaction(),
+    ];
+    for (version, transaction) in transactions.iter().take(2).enumerate() {
+        mempool_notification_handler
+            .notify_mempool_of_committed_transactions(
+                vec![transaction.clone()],
+                0,
+                version as u64,
+                version as u64,
+                CommitOrigin::StateSync,
+            )
+            .await
+            .unwrap();
+    }
+    assert!(mempool_notifier.get_delivered_notifications().is_empty());
+
+    // Notify mempool of a non-contiguous commit and verify the batch is sent
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![transactions[2].clone()],
+            0,
+            10,
+            10,
+            CommitOrigin::StateSync,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..2].to_vec()]
+    );
+
+    // Flush the batch and verify the non-contiguous commit is sent separately
+    mempool_notification_handler
+        .flush_batched_notifications()
+        .await
+        .unwrap();
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..2].to_vec(), transactions[2..].to_vec()]
+    );
+}
+
+#[tokio::test]
+async fn test_mempool_notification_metrics() {
+    // Create a mempool notification handler that times out on the first
+    // notification attempt and fails to send the second attempt.
+    let mempool_notifier = ScheduledMempoolNotifier::new(vec![
+        (
+            Duration::from_millis(0),
+            Err(mempool_notifications::Error::TimeoutWaitingForMempool),
+        ),
+        (
+            Duration::from_millis(0),
+            Err(mempool_notifications::Error::CommitNotificationError(
+                "Failed to send the notification!".into(),
+            )),
+        ),
+    ]);
+    let mut mempool_notification_handler = MempoolNotificationHandler::new(
+        create_retry_config(5, 10_000),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Read the current metric values (metrics are shared across tests)
+    let commit_origin = CommitOrigin::Consensus;
+    let num_successes =
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_SUCCESS);
+    let num_timeouts =
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_TIMEOUT);
+    let num_send_failures = read_mempool_notification_counter(
+        commit_origin,
+        metrics::MEMPOOL_NOTIFICATION_SEND_FAILURE,
+    );
+    let num_latency_samples = read_mempool_notification_latency_samples(commit_origin);
+
+    // Notify mempool of new transactions and wait for the retries to complete
+    mempool_notification_handler
+        .notify_mempool_of_committed_transactions(
+            vec![create_transaction()],
+            0,
+            0,
+            0,
+            commit_origin,
+        )
+        .await
+        .unwrap();
+    wait_for_mempool_notifications(&mempool_notifier, 3).await;
+
+    // Verify the result and latency metrics were updated for every attempt
+    assert!(
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_TIMEOUT)
+            > num_timeouts
+    );
+    assert!(
+        read_mempool_notification_counter(
+            commit_origin,
+            metrics::MEMPOOL_NOTIFICATION_SEND_FAILURE
+        ) > num_send_failures
+    );
+    assert!(
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_SUCCESS)
+            > num_successes
+    );
+    assert!(read_mempool_notification_latency_samples(commit_origin) >= num_latency_samples + 3);
+}
+
+#[tokio::test]
+async fn test_mempool_notification_in_flight_metrics() {
+    // Create a mempool notification handler that delays the first notification
+    let mempool_notifier =
+        ScheduledMempoolNotifier::new(vec![(Duration::from_millis(500), Ok(()))]);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+
+    // Notify mempool of new transactions (in the background)
+    let commit_origin = CommitOrigin::StateSync;
+    let num_successes =
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_SUCCESS);
+    let mut notification_handler = mempool_notification_handler.clone();
+    let join_handle = tokio::spawn(async move {
+        notification_handler
+            .notify_mempool_of_committed_transactions(
+                vec![create_transaction()],
+                0,
+                0,
+                0,
+                commit_origin,
+            )
+            .await
+    });
+
+    // Verify the notification is tracked as in flight while mempool is delayed
+    wait_for_mempool_notifications(&mempool_notifier, 1).await;
+    assert!(
+        DriverMetrics::default().read_gauge(
+            &metrics::MEMPOOL_NOTIFICATIONS_IN_FLIGHT,
+            commit_origin.get_label()
+        ) >= 1
+    );
+
+    // Verify the notification eventually succeeds
+    join_handle.await.unwrap().unwrap();
+    assert!(
+        read_mempool_notification_counter(commit_origin, metrics::MEMPOOL_NOTIFICATION_SUCCESS)
+            > num_successes
+    );
+}
+
+#[tokio::test]
+async fn test_mempool_notification_filters_non_user_transactions() {
+    // Create a mempool notification handler and event subscription service
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_notification_handler =
+        create_event_notification_handler(create_event_subscription_service());
+
+    // Create a list of mixed transactions
+    let user_transactions = vec![create_transaction(), create_transaction()];
+    let transactions = vec![
+        create_block_metadata_transaction(),
+        user_transactions[0].clone(),
+        Transaction::GenesisTransaction(WriteSetPayload::Direct(ChangeSet::new(
+            WriteSetMut::new(vec![]).freeze().unwrap(),
+            vec![],
+        ))),
+        user_transactions[1].clone(),
+        Transaction::StateCheckpoint(HashValue::random()),
+    ];
+
+    // Handle the transaction notification
+    let committed_transactions = CommittedTransactions::new(vec![], transactions, 0);
+    let last_version = committed_transactions.last_version;
+    let commit_summary = CommitNotification::handle_transaction_notification(
+        committed_transactions,
+        CommitOrigin::StateSync,
+        create_ledger_info_at_version(last_version),
+        mempool_notification_handler,
+        event_notification_handler,
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+    )
+    .await
+    .unwrap();
+
+    // Verify only the user transactions reach mempool
+    verify_delivered_notifications(&mempool_notifier, vec![user_transactions]).await;
+    assert_eq!(commit_summary.user_txns_notified, 2);
+    assert_eq!(commit_summary.events_notified, 0);
+}
+
+#[tokio::test]
+async fn test_commit_notification_without_mempool() {
+    // Create a mempool notification handler without a mempool
+    let mempool_notification_handler =
+        MempoolNotificationHandler::<FlakyMempoolNotifier>::new_without_mempool(
+            StateSyncDriverConfig::default(),
+            DriverMetrics::default(),
+        );
+    assert!(!mempool_notification_handler.mempool_enabled());
+
+    // Create an event notification handler and subscribe to the committed event
+    let event_subscription_service = create_event_subscription_service();
+    let event = create_event(None);
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event.key()])
+        .unwrap();
+    let event_notification_handler = create_event_notification_handler(event_subscription_service);
+
+    // Handle a commit and verify mempool is skipped (without any errors)
+    let num_skipped_notifications = read_skipped_mempool_notifications();
+    let notified_version_tracker = NotifiedVersionTracker::default();
+    let commit_summary = handle_commit(
+        vec![event.clone()],
+        vec![create_transaction(), create_transaction()],
+        0,
+        mempool_notification_handler.clone(),
+        event_notification_handler,
+        notified_version_tracker.clone(),
+    )
+    .await;
+    assert_eq!(commit_summary.user_txns_notified, 0);
+    assert_eq!(commit_summary.events_notified, 1);
+    assert!(read_skipped_mempool_notifications() > num_skipped_notifications);
+    assert_eq!(notified_version_tracker.highest_notified_version(), Some(1));
+
+    // Verify the event was still delivered to the subscriber
+    let event_notification = timeout(Duration::from_secs(10), event_listener.select_next_some())
+        .await
+        .unwrap();
+    assert_eq!(event_notification.subscribed_events, vec![event]);
+
+    // Verify flushing the (empty) batch is a no-op
+    let mut mempool_notification_handler = mempool_notification_handler;
+    mempool_notification_handler
+        .flush_batched_notifications()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_commit_subscribers() {
+    // Create the notification handlers
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_notification_handler =
+        create_event_notification_handler(create_event_subscription_service());
+
+    // Register two commit subscribers (where the first always fails)
+    let failing_subscriber = RecordingCommitSubscriber::new("failing_subscriber", true);
+    let healthy_subscriber = RecordingCommitSubscriber::new("healthy_subscriber", false);
+    let commit_subscriber_registry = CommitSubscriberRegistry::new(
+        vec![
+            Arc::new(failing_subscriber.clone()),
+            Arc::new(healthy_subscriber.clone()),
+        ],
+        DriverMetrics::default(),
+    );
+
+    // Handle two transaction notifications
+    let notified_version_tracker = NotifiedVersionTracker::default();
+    let num_failures = read_commit_subscriber_counter(&failing_subscriber, false);
+    let num_successes = read_commit_subscriber_counter(&healthy_subscriber, true);
+    for first_version in [0, 2] {
+        let committed_transactions = CommittedTransactions::new(
+            vec![],
+            vec![create_transaction(), create_transaction()],
+            first_version,
+        );
+        CommitNotification::handle_transaction_notification(
+            committed_transactions,
+            CommitOrigin::StateSync,
+            create_ledger_info_at_version(first_version + 1),
+            mempool_notification_handler.clone(),
+            event_notification_handler.clone(),
+            commit_subscriber_registry.clone(),
+            notified_version_tracker.clone(),
+        )
+        .await
+        .unwrap();
+    }
+
+    // Verify both subscribers were notified (despite the failures)
+    let expected_versions = vec![(0, 1), (2, 3)];
+    assert_eq!(
+        failing_subscriber.get_notified_versions(),
+        expected_versions
+    );
+    assert_eq!(
+        healthy_subscriber.get_notified_versions(),
+        expected_versions
+    );
+
+    // Verify the failures and successes were metered per subscriber
+    assert_eq!(
+        read_commit_subscriber_counter(&failing_subscriber, false),
+        num_failures + 2
+    );
+    assert_eq!(
+        read_commit_subscriber_counter(&healthy_subscriber, true),
+        num_successes + 2
+    );
+    assert_eq!(
+        read_commit_subscriber_counter(&healthy_subscriber, false),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_commit_event_subscribers() {
+    // Create a commit subscriber registry
+    let commit_subscriber_registry =
+        CommitSubscriberRegistry::new(vec![], DriverMetrics::default());
+
+    // Subscribe a fast and a slow subscriber (with room for a single event)
+    let (fast_sender, mut fast_receiver) = mpsc::channel(10);
+    let (slow_sender, mut slow_receiver) = mpsc::channel(0);
+    commit_subscriber_registry.subscribe_to_commit_events(fast_sender);
+    commit_subscriber_registry.subscribe_to_commit_events(slow_sender);
+    assert_eq!(commit_subscriber_registry.num_commit_event_subscribers(), 2);
+
+    // Notify the subscribers of several commit events
+    let commit_events: Vec<_> = (0..3).map(create_commit_event).collect();
+    for commit_event in commit_events.clone() {
+        commit_subscriber_registry.notify_commit_event_subscribers(commit_event);
+    }
+
+    // Verify the fast subscriber received all events (in order)
+    for commit_event in commit_events.clone() {
+        assert_eq!(fast_receiver.next().await.unwrap(), commit_event);
+    }
+
+    // Verify the slow subscriber received the first event and was then dropped
+    assert_eq!(slow_receiver.next().await.unwrap(), commit_events[0]);
+    assert_none!(slow_receiver.next().await);
+    assert_eq!(commit_subscriber_registry.num_commit_event_subscribers(), 1);
+
+    // Close the fast subscriber and verify it is removed on the next event
+    drop(fast_receiver);
+    commit_subscriber_registry.notify_commit_event_subscribers(create_commit_event(3));
+    assert_eq!(commit_subscriber_registry.num_commit_event_subscribers(), 0);
+}
+
+#[tokio::test]
+async fn test_commit_paths_notify_identically() {
+    // Create the committed transactions (with a subscribed and an unsubscribed event)
+    let subscribed_event = create_event(None);
+    let committed_transactions = CommittedTransactions::new(
+        vec![subscribed_event.clone(), create_event(None)],
+        vec![create_transaction(), create_transaction()],
+        5,
+    );
+
+    // Handle identical commits made by consensus and state sync
+    let mut downstream_notifications = vec![];
+    for commit_origin in [CommitOrigin::Consensus, CommitOrigin::StateSync] {
+        // Create the notification handlers and subscribe to the downstream notifications
+        let mempool_notifier = FlakyMempoolNotifier::new(0);
+        let mempool_notification_handler = MempoolNotificationHandler::new(
+            StateSyncDriverConfig::default(),
+            mempool_notifier.clone(),
+            DriverMetrics::default(),
+        );
+        let event_subscription_service = create_event_subscription_service();
+        let mut event_listener = event_subscription_service
+            .lock()
+            .subscribe_to_events(vec![*subscribed_event.key()])
+            .unwrap();
+        let event_notification_handler =
+            create_event_notification_handler(event_subscription_service);
+        let commit_subscriber = RecordingCommitSubscriber::new("commit_subscriber", false);
+        let commit_subscriber_registry = CommitSubscriberRegistry::new(
+            vec![Arc::new(commit_subscriber.clone())],
+            DriverMetrics::default(),
+        );
+        let (commit_event_sender, mut commit_event_receiver) = mpsc::channel(10);
+        commit_subscriber_registry.subscribe_to_commit_events(commit_event_sender);
+
+        // Handle the committed transactions
+        let commit_summary = utils::handle_committed_transactions(
+            committed_transactions.clone(),
+            commit_origin,
+            create_mock_reader_writer(None, None).reader,
+            mempool_notification_handler,
+            event_notification_handler,
+            CommitStateTracker::new(TimeService::mock()),
+            commit_subscriber_registry,
+            NotifiedVersionTracker::default(),
+            DriverMetrics::new_dry_run_instance(),
+        )
+        .await
+        .unwrap();
+
+        // Collect the downstream notifications
+        verify_delivered_notifications(
+            &mempool_notifier,
+            vec![committed_transactions.transactions.clone()],
+        )
+        .await;
+        let event_notification =
+            timeout(Duration::from_secs(10), event_listener.select_next_some())
+                .await
+                .unwrap();
+        let commit_event = commit_event_receiver.next().await.unwrap();
+        downstream_notifications.push((
+            (
+                commit_summary.user_txns_notified,
+                commit_summary.events_notified,
+                commit_summary.reconfig_events,
+            ),
+            mempool_notifier.get_delivered_notifications(),
+            (
+                event_notification.version,
+                event_notification.subscribed_events,
+            ),
+            commit_subscriber.get_notified_versions(),
+            commit_event,
+        ));
+    }
+
+    // Verify both commit paths sent identical downstream notifications
+    assert_eq!(downstream_notifications[0], downstream_notifications[1]);
+    assert_eq!(downstream_notifications[0].3, vec![(5, 6)]);
+}
+
+#[tokio::test]
+async fn test_commit_path_storage_error() {
+    // Create a storage reader that fails to fetch the latest ledger info
+    let mut mock_database_reader = create_mock_db_reader();
+    mock_database_reader
+        .expect_get_latest_ledger_info()
+        .returning(|| Err(anyhow::format_err!("Failed to read the ledger info!")));
+
+    // Handle a commit and verify the storage error is returned
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let commit_state_tracker = CommitStateTracker::new(TimeService::mock());
+    let result = utils::handle_committed_transactions(
+        CommittedTransactions::new(vec![], vec![create_transaction()], 0),
+        CommitOrigin::Consensus,
+        Arc::new(mock_database_reader),
+        MempoolNotificationHandler::new(
+            StateSyncDriverConfig::default(),
+            mempool_notifier.clone(),
+            DriverMetrics::default(),
+        ),
+        create_event_notification_handler(create_event_subscription_service()),
+        commit_state_tracker.clone(),
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+        DriverMetrics::new_dry_run_instance(),
+    )
+    .await;
+    assert_matches!(result, Err(Error::StorageError(_)));
+
+    // Verify nothing was recorded or notified downstream
+    assert_none!(commit_state_tracker.get_commit_state());
+    assert!(mempool_notifier.get_delivered_notifications().is_empty());
+}
+
+#[tokio::test]
+async fn test_synced_version_waiters() {
+    // Create a commit subscriber registry
+    let commit_subscriber_registry =
+        CommitSubscriberRegistry::new(vec![], DriverMetrics::default());
+
+    // Add several waiters for different versions
+    let (waiter_sender_1, waiter_receiver_1) = oneshot::channel();
+    let (waiter_sender_2, mut waiter_receiver_2) = oneshot::channel();
+    let (waiter_sender_3, _waiter_receiver_3) = oneshot::channel();
+    commit_subscriber_registry.add_synced_version_waiter(0, 5, waiter_sender_1);
+    commit_subscriber_registry.add_synced_version_waiter(1, 10, waiter_sender_2);
+    commit_subscriber_registry.add_synced_version_waiter(2, 10, waiter_sender_3);
+    assert_eq!(commit_subscriber_registry.num_synced_version_waiters(), 3);
+
+    // Remove a waiter (e.g., because the client timed out)
+    assert!(commit_subscriber_registry.remove_synced_version_waiter(2));
+    assert!(!commit_subscriber_registry.remove_synced_version_waiter(2));
+    assert_eq!(commit_subscriber_registry.num_synced_version_waiters(), 2);
+
+    // Sync to a version that only covers the first waiter
+    let ledger_info = create_ledger_info_at_version(7);
+    assert_eq!(
+        commit_subscriber_registry.notify_synced_version_waiters(&ledger_info),
+        1
+    );
+    assert_eq!(waiter_receiver_1.await.unwrap().unwrap(), ledger_info);
+    assert_none!(waiter_receiver_2.try_recv().unwrap());
+    assert_eq!(commit_subscriber_registry.num_synced_version_waiters(), 1);
+
+    // Sync beyond the remaining waiter and verify it receives the latest ledger info
+    let ledger_info = create_ledger_info_at_version(15);
+    assert_eq!(
+        commit_subscriber_registry.notify_synced_version_waiters(&ledger_info),
+        1
+    );
+    assert_eq!(waiter_receiver_2.await.unwrap().unwrap(), ledger_info);
+    assert_eq!(commit_subscriber_registry.num_synced_version_waiters(), 0);
+}
+
+#[tokio::test]
+async fn test_duplicate_commit_notifications() {
+    // Create the notification handlers and subscribe to the committed event
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_subscription_service = create_event_subscription_service();
+    let event = create_event(None);
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event.key()])
+        .unwrap();
+    let event_notification_handler = create_event_notification_handler(event_subscription_service);
+    let notified_version_tracker = NotifiedVersionTracker::default();
+
+    // Handle a consensus commit for versions 100 to 110
+    let transactions: Vec<Transaction> = (100..=110).map(|_| create_transaction()).collect();
+    let commit_summary = handle_commit(
+        vec![event.clone()],
+        transactions.clone(),
+        100,
+        mempool_notification_handler.clone(),
+        event_notification_handler.clone(),
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Handle a (late) synchronizer commit for versions 105 to 110
+    let duplicate_commit_summary = handle_commit(
+        vec![event.clone()],
+        transactions[5..].to_vec(),
+        105,
+        mempool_notification_handler,
+        event_notification_handler,
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Verify mempool and the event listener are only notified once
+    assert_eq!(commit_summary.user_txns_notified, 11);
+    assert_eq!(commit_summary.events_notified, 1);
+    assert_eq!(commit_summary.reconfig_events, 0);
+    assert_eq!(duplicate_commit_summary, CommitSummary::default());
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions]
+    );
+    let event_notification = event_listener.select_next_some().await;
+    assert_eq!(event_notification.subscribed_events, vec![event]);
+    assert!(event_listener.select_next_some().now_or_never().is_none());
+    assert_eq!(
+        notified_version_tracker.highest_notified_version(),
+        Some(110)
+    );
+}
+
+#[tokio::test]
+async fn test_overlapping_commit_notifications() {
+    // Create the notification handlers
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_notification_handler =
+        create_event_notification_handler(create_event_subscription_service());
+    let notified_version_tracker = NotifiedVersionTracker::default();
+
+    // Handle a consensus commit for versions 100 to 110
+    let transactions: Vec<Transaction> = (100..=115).map(|_| create_transaction()).collect();
+    handle_commit(
+        vec![],
+        transactions[..11].to_vec(),
+        100,
+        mempool_notification_handler.clone(),
+        event_notification_handler.clone(),
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Handle a synchronizer commit for versions 105 to 115
+    let commit_summary = handle_commit(
+        vec![],
+        transactions[5..].to_vec(),
+        105,
+        mempool_notification_handler,
+        event_notification_handler,
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Verify mempool is only notified of the new transactions
+    assert_eq!(commit_summary.user_txns_notified, 5);
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[..11].to_vec(), transactions[11..].to_vec()]
+    );
+    assert_eq!(
+        notified_version_tracker.highest_notified_version(),
+        Some(115)
+    );
+}
+
+#[tokio::test]
+async fn test_commit_notification_gap() {
+    // Create the notification handlers
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_notification_handler =
+        create_event_notification_handler(create_event_subscription_service());
+    let notified_version_tracker = NotifiedVersionTracker::default();
+
+    // Handle commits for versions 0 to 4 and (after a gap) 10 to 12
+    let transactions: Vec<Transaction> = (0..=12).map(|_| create_transaction()).collect();
+    for (first_version, last_version) in [(0, 4), (10, 12)] {
+        handle_commit(
+            vec![],
+            transactions[first_version..=last_version].to_vec(),
+            first_version as u64,
+            mempool_notification_handler.clone(),
+            event_notification_handler.clone(),
+            notified_version_tracker.clone(),
+        )
+        .await;
+    }
+
+    // Handle a stale commit for versions 8 to 12 and verify it is ignored
+    handle_commit(
+        vec![],
+        transactions[8..].to_vec(),
+        8,
+        mempool_notification_handler,
+        event_notification_handler,
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Verify mempool is notified of both (non-contiguous) commits exactly once
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[0..5].to_vec(), transactions[10..].to_vec()]
+    );
+    assert_eq!(
+        notified_version_tracker.highest_notified_version(),
+        Some(12)
+    );
+}
+
+#[tokio::test]
+async fn test_post_reset_commit_notification_gap() {
+    // Create the notification handlers
+    let mempool_notifier = FlakyMempoolNotifier::new(0);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notifier.clone(),
+        DriverMetrics::default(),
+    );
+    let event_notification_handler =
+        create_event_notification_handler(create_event_subscription_service());
+    let notified_version_tracker = NotifiedVersionTracker::default();
+
+    // Handle a commit for versions 0 to 4
+    let transactions: Vec<Transaction> = (0..=12).map(|_| create_transaction()).collect();
+    handle_commit(
+        vec![],
+        transactions[0..5].to_vec(),
+        0,
+        mempool_notification_handler.clone(),
+        event_notification_handler.clone(),
+        notified_version_tracker.clone(),
+    )
+    .await;
+
+    // Handle a (late) post-reset commit for versions 10 to 12
+    let mut committed_transactions =
+        CommittedTransactions::new(vec![], transactions[10..].to_vec(), 10);
+    committed_transactions.post_reset = true;
+    let commit_summary = CommitNotification::handle_transaction_notification(
+        committed_transactions,
+        CommitOrigin::StateSync,
+        create_ledger_info_at_version(12),
+        mempool_notification_handler,
+        event_notification_handler,
+        CommitSubscriberRegistry::default(),
+        notified_version_tracker.clone(),
+    )
+    .await
+    .unwrap();
+
+    // Verify the post-reset commit is still notified downstream
+    assert_eq!(commit_summary.user_txns_notified, 3);
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        vec![transactions[0..5].to_vec(), transactions[10..].to_vec()]
+    );
+    assert_eq!(
+        notified_version_tracker.highest_notified_version(),
+        Some(12)
+    );
+}
+
+#[test]
+fn test_commit_state_tracker() {
+    // Create a commit state tracker and verify nothing has been committed
+    let time_service = TimeService::mock();
+    let commit_state_tracker = CommitStateTracker::new(time_service.clone());
+    assert_eq!(commit_state_tracker.get_commit_state(), None);
+    assert_eq!(commit_state_tracker.time_since_last_commit(), None);
+
+    // Record a commit and verify the commit state
+    let mock_time_service = time_service.into_mock();
+    mock_time_service.advance_secs(1);
+    commit_state_tracker.update_commit_state(10, 2);
+    assert_eq!(
+        commit_state_tracker.get_commit_state(),
+        Some(CommitState {
+            last_commit_epoch: 2,
+            last_commit_timestamp_usecs: 1_000_000,
+            last_commit_version: 10,
+        })
+    );
+
+    // Record an older commit (e.g., a late notification) and verify the
+    // version and epoch don't go backwards (but the timestamp is updated).
+    mock_time_service.advance_secs(1);
+    commit_state_tracker.update_commit_state(5, 1);
+    assert_eq!(
+        commit_state_tracker.get_commit_state(),
+        Some(CommitState {
+            last_commit_epoch: 2,
+            last_commit_timestamp_usecs: 2_000_000,
+            last_commit_version: 10,
+        })
+    );
+
+    // Verify clones of the tracker share the same state
+    let commit_state_tracker_clone = commit_state_tracker.clone();
+    mock_time_service.advance_ms(500);
+    commit_state_tracker_clone.update_commit_state(11, 3);
+    let commit_state = commit_state_tracker.get_commit_state().unwrap();
+    assert_eq!(commit_state.last_commit_epoch, 3);
+    assert_eq!(commit_state.last_commit_version, 11);
+    assert_eq!(commit_state.last_commit_timestamp_usecs, 2_500_000);
+
+    // Verify the time since the last commit
+    mock_time_service.advance_secs(3);
+    assert_eq!(
+        commit_state_tracker.time_since_last_commit(),
+        Some(Duration::from_secs(3))
+    );
+}
+
+#[tokio::test]
+async fn test_commit_state_tracker_consensus_commits() {
+    // Create a consensus commit notifier with a commit state tracker
+    let time_service = TimeService::mock();
+    let commit_state_tracker = CommitStateTracker::new(time_service.clone());
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        FlakyMempoolNotifier::new(0),
+        DriverMetrics::default(),
+    );
+    let mut consensus_commit_notifier = ConsensusCommitNotifier::new(
+        StateSyncDriverConfig::default(),
+        create_mock_reader_writer(None, None).reader,
+        mempool_notification_handler,
+        create_event_notification_handler(create_event_subscription_service()),
+        commit_state_tracker.clone(),
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+        DriverMetrics::default(),
+    );
+
+    // Notify the commits (in order) and verify each is recorded
+    let mock_time_service = time_service.into_mock();
+    let mut last_commit_timestamp_usecs = 0;
+    for (first_version, expected_version) in [(0, 1), (2, 3), (0, 3)] {
+        mock_time_service.advance_secs(1);
+        let committed_transactions = CommittedTransactions::new(
+            vec![],
+            vec![create_transaction(), create_transaction()],
+            first_version,
+        );
+        consensus_commit_notifier
+            .notify_committed_transactions(committed_transactions, true)
+            .await
+            .unwrap();
+
+        // Verify the commit state only moves forward
+        let commit_state = commit_state_tracker.get_commit_state().unwrap();
+        assert_eq!(commit_state.last_commit_version, expected_version);
+        assert_eq!(commit_state.last_commit_epoch, 0);
+        assert!(commit_state.last_commit_timestamp_usecs > last_commit_timestamp_usecs);
+        last_commit_timestamp_usecs = commit_state.last_commit_timestamp_usecs;
+    }
+}
+
+#[tokio::test]
+async fn test_commit_state_tracker_epoch_changes() {
+    // Create a consensus commit notifier with a commit state tracker
+    let commit_state_tracker = CommitStateTracker::new(TimeService::mock());
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        FlakyMempoolNotifier::new(0),
+        DriverMetrics::default(),
+    );
+    let mut consensus_commit_notifier = ConsensusCommitNotifier::new(
+        StateSyncDriverConfig::default(),
+        create_mock_reader_writer(None, None).reader,
+        mempool_notification_handler,
+        create_event_notification_handler(create_event_subscription_service()),
+        commit_state_tracker.clone(),
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+        DriverMetrics::default(),
+    );
+
+    // Notify a commit that doesn't end the epoch and verify no epoch state is cached
+    let num_epoch_transitions = read_driver_counter(metrics::EPOCH_TRANSITION);
+    let committed_transactions = CommittedTransactions::new(vec![], vec![create_transaction()], 0);
+    assert!(!committed_transactions.ends_epoch);
+    consensus_commit_notifier
+        .notify_committed_transactions(committed_transactions, true)
+        .await
+        .unwrap();
+    assert_eq!(commit_state_tracker.get_epoch_state(1), None);
+
+    // Notify an epoch ending commit and verify the new epoch state is cached
+    let new_epoch_state = create_epoch_state(1);
+    let mut committed_transactions =
+        CommittedTransactions::new(vec![], vec![create_transaction()], 1);
+    committed_transactions.set_new_epoch_state(new_epoch_state.clone());
+    assert!(committed_transactions.ends_epoch);
+    consensus_commit_notifier
+        .notify_committed_transactions(committed_transactions.clone(), true)
+        .await
+        .unwrap();
+    assert_eq!(
+        commit_state_tracker.get_epoch_state(1),
+        Some(new_epoch_state.clone())
+    );
+    assert_eq!(commit_state_tracker.get_epoch_state(0), None);
+    assert!(read_driver_counter(metrics::EPOCH_TRANSITION) > num_epoch_transitions);
+
+    // Verify older (or duplicate) epoch states are ignored
+    assert!(!commit_state_tracker.update_epoch_state(new_epoch_state));
+    assert!(!commit_state_tracker.update_epoch_state(create_empty_epoch_state()));
+    assert!(commit_state_tracker.get_epoch_state(1).is_some());
+}
+
+#[tokio::test]
+async fn test_epoch_state_cache_invalidation() {
+    // Create a consensus commit notifier with a commit state tracker (the
+    // metrics use a unique instance, so that concurrent tests don't interfere).
+    let commit_state_tracker = CommitStateTracker::new(TimeService::mock());
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let storage = create_mock_reader_writer(None, None).reader;
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        FlakyMempoolNotifier::new(0),
+        DriverMetrics::default(),
+    );
+    let mut consensus_commit_notifier = ConsensusCommitNotifier::new(
+        StateSyncDriverConfig::default(),
+        storage.clone(),
+        mempool_notification_handler,
+        create_event_notification_handler(create_event_subscription_service()),
+        commit_state_tracker.clone(),
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+        driver_metrics,
+    );
+
+    // Fetch the latest epoch state and verify it's cached
+    let latest_epoch_state =
+        utils::fetch_latest_epoch_state(storage.clone(), &commit_state_tracker).unwrap();
+    assert_eq!(latest_epoch_state, create_empty_epoch_state());
+    assert_eq!(
+        commit_state_tracker.get_latest_epoch_state(),
+        Some(latest_epoch_state.clone())
+    );
+
+    // Notify a commit that doesn't end the epoch and verify the cache is kept
+    let committed_transactions = CommittedTransactions::new(vec![], vec![create_transaction()], 0);
+    consensus_commit_notifier
+        .notify_committed_transactions(committed_transactions, true)
+        .await
+        .unwrap();
+    assert_eq!(
+        commit_state_tracker.get_latest_epoch_state(),
+        Some(latest_epoch_state)
+    );
+
+    // Notify an epoch ending commit (twice, as commits might be notified
+    // multiple times) and verify the cache is invalidated exactly once.
+    let new_epoch_state = create_epoch_state(1);
+    let mut committed_transactions =
+        CommittedTransactions::new(vec![], vec![create_transaction()], 1);
+    committed_transactions.set_new_epoch_state(new_epoch_state.clone());
+    for _ in 0..2 {
+        consensus_commit_notifier
+            .notify_committed_transactions(committed_transactions.clone(), true)
+            .await
+            .unwrap();
+    }
+    assert_eq!(
+        driver_metrics
+            .counter(
+                &metrics::DRIVER_COUNTERS,
+                &[metrics::EPOCH_STATE_CACHE_INVALIDATION]
+            )
+            .get(),
+        1
+    );
+
+    // Verify the new epoch state is now returned (without reading storage)
+    assert_eq!(
+        utils::fetch_latest_epoch_state(storage, &commit_state_tracker).unwrap(),
+        new_epoch_state
+    );
+}
+
+#[test]
+fn test_epoch_transition_metrics() {
+    // Create a commit state tracker (the metrics use a unique instance, so
+    // that concurrent tests don't interfere).
+    let time_service = TimeService::mock();
+    let commit_state_tracker = CommitStateTracker::new(time_service.clone());
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let latest_synced_ledger_info = create_ledger_info_at_version(100);
+
+    // Handle the first epoch transition and verify the epoch gauge and cache
+    // are updated (the duration of the previous epoch is unknown).
+    let mut committed_transactions =
+        CommittedTransactions::new(vec![], vec![create_transaction()], 50);
+    committed_transactions.set_new_epoch_state(create_epoch_state(1));
+    utils::handle_epoch_transition(
+        &committed_transactions,
+        &latest_synced_ledger_info,
+        &commit_state_tracker,
+        &driver_metrics,
+    );
+    assert_eq!(read_synced_epoch(&driver_metrics), 1);
+    assert_eq!(
+        commit_state_tracker.get_epoch_state(1),
+        Some(create_epoch_state(1))
+    );
+    assert_eq!(read_epoch_duration_histogram(&driver_metrics), (0, 0.0));
+
+    // Handle the second epoch transition (after some time has passed) and
+    // verify the epoch gauge, cache and duration histogram are updated.
+    time_service.into_mock().advance_secs(120);
+    let mut committed_transactions =
+        CommittedTransactions::new(vec![], vec![create_transaction()], 100);
+    committed_transactions.set_new_epoch_state(create_epoch_state(2));
+    for _ in 0..2 {
+        // Handle the transition twice (commits might be notified multiple times)
+        utils::handle_epoch_transition(
+            &committed_transactions,
+            &latest_synced_ledger_info,
+            &commit_state_tracker,
+            &driver_metrics,
+        );
+    }
+    assert_eq!(read_synced_epoch(&driver_metrics), 2);
+    assert_eq!(
+        commit_state_tracker.get_epoch_state(2),
+        Some(create_epoch_state(2))
+    );
+    assert_eq!(read_epoch_duration_histogram(&driver_metrics), (1, 120.0));
+
+    // Verify each epoch transition was only counted once
+    assert_eq!(
+        driver_metrics
+            .counter(&metrics::DRIVER_COUNTERS, &[metrics::EPOCH_TRANSITION])
+            .get(),
+        2
+    );
+
+    // Verify older epoch transitions are ignored
+    assert_none!(commit_state_tracker.record_epoch_transition(1));
+    assert_none!(commit_state_tracker.record_epoch_transition(2));
+}
+
+#[test]
+fn test_committed_transactions_version_range() {
+    // Verify the version range is inclusive of all transactions
+    let committed_transactions = CommittedTransactions::new(vec![], vec![create_transaction()], 10);
+    assert_eq!(committed_transactions.first_version, 10);
+    assert_eq!(committed_transactions.last_version, 10);
+    let committed_transactions =
+        CommittedTransactions::new(vec![], (0..5).map(|_| create_transaction()).collect(), 10);
+    assert_eq!(committed_transactions.first_version, 10);
+    assert_eq!(committed_transactions.last_version, 14);
+
+    // Verify merged (chunk-aligned) notifications span both ranges
+    let mut commit_notification = CommitNotification::new_committed_state_snapshot(
+        vec![],
+        vec![create_transaction(), create_transaction()],
+        10,
+        0,
+        11,
+    );
+    commit_notification.merge(CommitNotification::new_committed_state_snapshot(
+        vec![],
+        vec![create_transaction()],
+        12,
+        1,
+        12,
+    ));
+    let committed_snapshot = unwrap_committed_state_snapshot(commit_notification);
+    let committed_transactions = committed_snapshot.committed_transaction;
+    assert_eq!(committed_transactions.first_version, 10);
+    assert_eq!(committed_transactions.last_version, 12);
+    assert_eq!(committed_transactions.transactions.len(), 3);
+}
+
+#[test]
+fn test_commit_notification_chunk_merge() {
+    // Verify merged chunk notifications accumulate the chunk progress
+    let proven_root_hash = HashValue::random();
+    let mut commit_notification = CommitNotification::new_committed_state_value_chunk(
+        Duration::from_millis(10),
+        99,
+        1000,
+        100,
+        HashValue::zero(),
+        Duration::from_millis(1),
+        50,
+    );
+    commit_notification.merge(CommitNotification::new_committed_state_value_chunk(
+        Duration::from_millis(20),
+        199,
+        2000,
+        100,
+        proven_root_hash,
+        Duration::from_millis(2),
+        50,
+    ));
+    match commit_notification.clone() {
+        CommitNotification::CommittedStateValueChunk(committed_chunk) => {
+            assert_eq!(committed_chunk.apply_duration, Duration::from_millis(30));
+            assert_eq!(committed_chunk.last_committed_state_index, 199);
+            assert_eq!(committed_chunk.num_bytes, 3000);
+            assert_eq!(committed_chunk.num_state_values, 200);
+            assert_eq!(committed_chunk.proven_root_hash, proven_root_hash);
+            assert_eq!(
+                committed_chunk.verification_duration,
+                Duration::from_millis(3)
+            );
+            assert_eq!(committed_chunk.version, 50);
+        }
+        commit_notification => panic!("Unexpected commit notification: {:?}", commit_notification),
+    }
+
+    // Verify a snapshot notification supersedes the chunk notifications
+    let transaction = create_transaction();
+    commit_notification.merge(CommitNotification::new_committed_state_snapshot(
+        vec![],
+        vec![transaction.clone()],
+        50,
+        299,
+        50,
+    ));
+    let committed_snapshot = unwrap_committed_state_snapshot(commit_notification.clone());
+    assert_eq!(committed_snapshot.last_committed_state_index, 299);
+    assert_eq!(
+        committed_snapshot.committed_transaction.transactions,
+        vec![transaction.clone()]
+    );
+
+    // Verify later chunk notifications don't modify the snapshot notification
+    commit_notification.merge(CommitNotification::new_committed_state_value_chunk(
+        Duration::from_millis(10),
+        399,
+        1000,
+        100,
+        HashValue::zero(),
+        Duration::from_millis(1),
+        50,
+    ));
+    let committed_snapshot = unwrap_committed_state_snapshot(commit_notification);
+    assert_eq!(committed_snapshot.last_committed_state_index, 299);
+    assert_eq!(
+        committed_snapshot.committed_transaction.transactions,
+        vec![transaction]
+    );
+}
+
+#[tokio::test]
+async fn test_commit_notification_coalescing() {
+    // Create a commit notification channel with a small capacity
+    let (mut commit_notification_sender, mut commit_notification_listener) =
+        CommitNotificationListener::new(1, DriverMetrics::default());
+
+    // Send more notifications than the channel can hold
+    let num_notifications = 10;
+    let mut events = vec![];
+    let mut transactions = vec![];
+    for version in 0..num_notifications {
+        let event = create_event(None);
+        let transaction = create_transaction();
+        commit_notification_sender
+            .send(CommitNotification::new_committed_state_snapshot(
+                vec![event.clone()],
+                vec![transaction.clone()],
+                version,
+                version,
+                version,
+            ))
+            .unwrap();
+        events.push(event);
+        transactions.push(transaction);
+    }
+
+    // Drain all notifications from the listener
+    let mut commit_notifications = vec![];
+    while let Some(Some(commit_notification)) = commit_notification_listener.next().now_or_never() {
+        commit_notifications.push(commit_notification);
+    }
+
+    // Verify the overflowing notifications were coalesced (with no lost data)
+    assert!(commit_notifications.len() < num_notifications as usize);
+    let mut received_events = vec![];
+    let mut received_transactions = vec![];
+    let mut next_version = 0;
+    for commit_notification in &commit_notifications {
+        let committed_snapshot = unwrap_committed_state_snapshot(commit_notification.clone());
+        let committed_transactions = &committed_snapshot.committed_transaction;
+        assert_eq!(committed_transactions.first_version, next_version);
+        assert_eq!(
+            committed_transactions.last_version - committed_transactions.first_version + 1,
+            committed_transactions.transactions.len() as u64
+        );
+        next_version = committed_transactions.last_version + 1;
+        received_events.extend(committed_snapshot.committed_transaction.events.clone());
+        received_transactions.extend(
+            committed_snapshot
+                .committed_transaction
+                .transactions
+                .clone(),
+        );
+    }
+    assert_eq!(received_events, events);
+    assert_eq!(received_transactions, transactions);
+    let last_snapshot =
+        unwrap_committed_state_snapshot(commit_notifications.last().unwrap().clone());
+    assert_eq!(last_snapshot.version, num_notifications - 1);
+    assert_eq!(
+        last_snapshot.last_committed_state_index,
+        num_notifications - 1
+    );
+
+    // Verify new notifications are no longer coalesced (the channel is empty)
+    let transaction = create_transaction();
+    commit_notification_sender
+        .send(CommitNotification::new_committed_state_snapshot(
+            vec![],
+            vec![transaction.clone()],
+            num_notifications,
+            num_notifications,
+            num_notifications,
+        ))
+        .unwrap();
+    let committed_snapshot =
+        unwrap_committed_state_snapshot(commit_notification_listener.next().await.unwrap());
+    assert_eq!(
+        committed_snapshot.committed_transaction.transactions,
+        vec![transaction]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_event_subscription_recovery() {
+    // Create the event notification handler (using a genesis database)
+    let event_subscription_service = create_genesis_event_subscription_service();
+    let mut event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+
+    // Subscribe to events and reconfigurations, and notify the initial configs
+    let event = create_event(None);
+    let reconfig_event = create_event(Some(new_epoch_event_key()));
+    let (event_listener, mut reconfig_listener) =
+        subscribe_to_events_and_reconfigurations(&event_subscription_service, *event.key());
+    event_subscription_service
+        .lock()
+        .notify_initial_configs(0)
+        .unwrap();
+    assert_eq!(reconfig_listener.select_next_some().await.version, 0);
+
+    // Close both subscribers mid-run
+    drop(event_listener);
+    drop(reconfig_listener);
+
+    // Verify the next notification succeeds and the closed subscriptions are metered
+    let num_closed_subscriptions = read_event_subscription_recovery_counter(false);
+    event_notification_handler
+        .notify_events(0, vec![event.clone(), reconfig_event.clone()])
+        .await
+        .unwrap();
+    assert!(read_event_subscription_recovery_counter(false) >= num_closed_subscriptions + 2);
+    assert!(!event_notification_handler.requires_notification(&[event.clone()]));
+
+    // Re-create the subscriptions and verify a resync is required
+    let (mut event_listener, mut reconfig_listener) =
+        subscribe_to_events_and_reconfigurations(&event_subscription_service, *event.key());
+    assert!(event_notification_handler.requires_notification(&[]));
+
+    // Notify a new event and verify both subscribers recover (i.e., the
+    // reconfig subscriber is resynced to the latest configs).
+    let num_resynced_subscriptions = read_event_subscription_recovery_counter(true);
+    event_notification_handler
+        .notify_events(0, vec![event.clone()])
+        .await
+        .unwrap();
+    let event_notification = timeout(Duration::from_secs(10), event_listener.select_next_some())
+        .await
+        .unwrap();
+    assert_eq!(event_notification.version, 0);
+    assert_eq!(event_notification.subscribed_events, vec![event]);
+    let reconfig_notification = timeout(
+        Duration::from_secs(10),
+        reconfig_listener.select_next_some(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(reconfig_notification.version, 0);
+    assert!(!event_notification_handler.requires_notification(&[]));
+
+    // Verify the resync was metered. Reconfigurations wait for delivery, so
+    // all previous notifications will have been handled.
+    event_notification_handler
+        .notify_events(0, vec![reconfig_event])
+        .await
+        .unwrap();
+    assert!(read_event_subscription_recovery_counter(true) >= num_resynced_subscriptions + 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_slow_event_subscriber() {
+    // Create the event notification handler and subscribe to events
+    let event_subscription_service = create_event_subscription_service();
+    let mut event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+    let event = create_event(None);
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event.key()])
+        .unwrap();
+
+    // Emulate a slow subscriber by holding the subscription service lock
+    let (locked_sender, locked_receiver) = std::sync::mpsc::channel();
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let slow_subscription_service = event_subscription_service.clone();
+    let slow_subscriber = std::thread::spawn(move || {
+        let _subscription_service = slow_subscription_service.lock();
+        locked_sender.send(()).unwrap();
+        let _ = release_receiver.recv();
+    });
+    locked_receiver.recv().unwrap();
+
+    // Verify regular events are enqueued without waiting for delivery
+    timeout(
+        Duration::from_secs(10),
+        event_notification_handler.notify_events(0, vec![event.clone()]),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    // Verify reconfiguration events wait for delivery
+    let reconfiguration_event = create_event(Some(new_epoch_event_key()));
+    let mut reconfiguration_handler = event_notification_handler.clone();
+    let mut reconfiguration_notification = tokio::spawn(async move {
+        reconfiguration_handler
+            .notify_events(1, vec![reconfiguration_event])
+            .await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!((&mut reconfiguration_notification).now_or_never().is_none());
+
+    // Release the slow subscriber and verify all notifications are delivered (in order)
+    release_sender.send(()).unwrap();
+    slow_subscriber.join().unwrap();
+    reconfiguration_notification.await.unwrap().unwrap();
+    let event_notification = event_listener.select_next_some().await;
+    assert_eq!(event_notification.version, 0);
+    assert_eq!(event_notification.subscribed_events, vec![event]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reconfiguration_events_take_slow_path() {
+    // Create an event notification handler without any event subscribers
+    let event_subscription_service = create_event_subscription_service();
+    let event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+
+    // Verify events without subscribers (or reconfigurations) are skipped
+    let event = create_event(None);
+    let reconfiguration_event = create_event(Some(new_epoch_event_key()));
+    assert!(!event_notification_handler.requires_notification(&[]));
+    assert!(!event_notification_handler.requires_notification(&[event.clone()]));
+
+    // Verify reconfiguration events always require notification
+    assert!(event_notification_handler.requires_notification(&[reconfiguration_event.clone()]));
+    assert!(event_notification_handler
+        .requires_notification(&[event.clone(), reconfiguration_event.clone()]));
+
+    // Verify new subscriptions are respected by existing handlers
+    let _event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event.key()])
+        .unwrap();
+    assert!(event_notification_handler.requires_notification(&[event]));
+
+    // Hold the subscription service lock to block event delivery
+    let (locked_sender, locked_receiver) = std::sync::mpsc::channel();
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let blocked_subscription_service = event_subscription_service.clone();
+    let blocked_subscriber = std::thread::spawn(move || {
+        let _subscription_service = blocked_subscription_service.lock();
+        locked_sender.send(()).unwrap();
+        let _ = release_receiver.recv();
+    });
+    locked_receiver.recv().unwrap();
+
+    // Verify a commit with a reconfiguration event waits for delivery
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        FlakyMempoolNotifier::new(0),
+        DriverMetrics::default(),
+    );
+    let mut reconfiguration_commit = tokio::spawn(handle_commit(
+        vec![reconfiguration_event],
+        vec![create_transaction()],
+        0,
+        mempool_notification_handler,
+        event_notification_handler,
+        NotifiedVersionTracker::default(),
+    ));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!((&mut reconfiguration_commit).now_or_never().is_none());
+
+    // Release the subscription service and verify the commit completes
+    release_sender.send(()).unwrap();
+    blocked_subscriber.join().unwrap();
+    let commit_summary = timeout(Duration::from_secs(10), reconfiguration_commit)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(commit_summary.events_notified, 1);
+    assert_eq!(commit_summary.reconfig_events, 1);
+    assert!(commit_summary.events_latency >= Duration::from_millis(200));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_commit_notifications() {
+    // Create a mempool notification handler that takes one second to respond
+    let mempool_delay = Duration::from_millis(1000);
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        ScheduledMempoolNotifier::new(vec![(mempool_delay, Ok(()))]),
+        DriverMetrics::default(),
+    );
+
+    // Create an event notification handler and block event delivery for half a second
+    let event_subscription_service = create_event_subscription_service();
+    let event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+    let (locked_sender, locked_receiver) = std::sync::mpsc::channel();
+    let blocked_subscriber = std::thread::spawn(move || {
+        let _subscription_service = event_subscription_service.lock();
+        locked_sender.send(()).unwrap();
+        std::thread::sleep(Duration::from_millis(500));
+    });
+    locked_receiver.recv().unwrap();
+
+    // Handle a commit containing a transaction and a reconfiguration event
+    let start_time = Instant::now();
+    let commit_summary = handle_commit_with_mempool(
+        vec![create_event(Some(new_epoch_event_key()))],
+        vec![create_transaction()],
+        mempool_notification_handler,
+        event_notification_handler,
+    )
+    .await
+    .unwrap();
+    let total_latency = start_time.elapsed();
+    blocked_subscriber.join().unwrap();
+
+    // Verify both latencies were recorded separately
+    assert_eq!(commit_summary.user_txns_notified, 1);
+    assert_eq!(commit_summary.reconfig_events, 1);
+    assert!(commit_summary.mempool_latency >= mempool_delay);
+    assert!(commit_summary.events_latency >= Duration::from_millis(400));
+
+    // Verify the total latency is (approximately) the max of both latencies, not the sum
+    let max_latency = commit_summary
+        .mempool_latency
+        .max(commit_summary.events_latency);
+    assert!(total_latency >= max_latency);
+    assert!(total_latency < max_latency + Duration::from_millis(250));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrent_commit_notification_errors() {
+    // Create an event notification handler and subscribe to events
+    let event_subscription_service = create_event_subscription_service();
+    let event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+    let event = create_event(None);
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event.key()])
+        .unwrap();
+
+    // Handle a commit where mempool fails (after a delay)
+    let mempool_notification_handler = create_failing_mempool_notification_handler();
+    let commit_result = handle_commit_with_mempool(
+        vec![event.clone()],
+        vec![create_transaction()],
+        mempool_notification_handler,
+        event_notification_handler,
+    )
+    .await;
+
+    // Verify the mempool error is returned, but the events are still delivered
+    assert_matches!(commit_result, Err(Error::NotifyMempoolError(_)));
+    let event_notification = timeout(Duration::from_secs(10), event_listener.select_next_some())
+        .await
+        .unwrap();
+    assert_eq!(event_notification.subscribed_events, vec![event]);
+
+    // Create an event notification handler that fails to read the on-chain
+    // configs (the genesis database doesn't contain the commit version).
+    let event_subscription_service = create_genesis_event_subscription_service();
+    let event_notification_handler =
+        create_event_notification_handler(event_subscription_service.clone());
+    let _reconfig_listener = event_subscription_service
+        .lock()
+        .subscribe_to_reconfigurations()
+        .unwrap();
+
+    // Handle a commit where both mempool and the event subscription service fail
+    let mempool_notification_handler = create_failing_mempool_notification_handler();
+    let commit_result = handle_commit_with_mempool(
+        vec![create_event(Some(new_epoch_event_key()))],
+        vec![create_transaction()],
+        mempool_notification_handler,
+        event_notification_handler,
+    )
+    .await;
+
+    // Verify both errors are returned
+    assert_matches!(commit_result, Err(Error::CommitNotificationErrors(_, _)));
+}
+
+#[test]
+fn test_terminated_stream_tracker() {
+    // Create a terminated stream tracker and verify nothing is terminated
+    let mut terminated_stream_tracker = TerminatedStreamTracker::default();
+    let error_notification = create_error_notification(ErrorSource::ContinuousSyncer, 10);
+    assert!(!terminated_stream_tracker.is_already_terminated(&error_notification));
+
+    // Fire three errors for the same notification and verify only the first terminates
+    let mut num_terminations = 0;
+    for _ in 0..3 {
+        if !terminated_stream_tracker.is_already_terminated(&error_notification) {
+            terminated_stream_tracker.mark_terminated(&error_notification);
+            num_terminations += 1;
+        }
+    }
+    assert_eq!(num_terminations, 1);
+
+    // Verify older notifications for the same component are also duplicates
+    assert!(terminated_stream_tracker
+        .is_already_terminated(&create_error_notification(ErrorSource::ContinuousSyncer, 5)));
+
+    // Verify newer notifications and other components are not duplicates
+    assert!(
+        !terminated_stream_tracker.is_already_terminated(&create_error_notification(
+            ErrorSource::ContinuousSyncer,
+            11
+        ))
+    );
+    assert!(!terminated_stream_tracker
+        .is_already_terminated(&create_error_notification(ErrorSource::Bootstrapper, 10)));
+}
+
+#[test]
+fn test_terminated_stream_tracker_version_range() {
+    // Create a terminated stream tracker and verify no versions are known
+    let mut terminated_stream_tracker = TerminatedStreamTracker::default();
+    assert_none!(terminated_stream_tracker.get_last_terminated_version_range());
+
+    // Terminate a stream due to a commit failure
+    let num_committer_terminations = read_terminated_streams(StorageSynchronizerStage::Committer);
+    let mut error_notification = create_error_notification(ErrorSource::Bootstrapper, 10);
+    error_notification.stage = StorageSynchronizerStage::Committer;
+    error_notification.version_range = Some((100, 149));
+    terminated_stream_tracker.mark_terminated(&error_notification);
+
+    // Verify the failed versions are recorded and the metrics are updated
+    assert_eq!(
+        terminated_stream_tracker.get_last_terminated_version_range(),
+        Some((100, 149))
+    );
+    assert!(
+        read_terminated_streams(StorageSynchronizerStage::Committer) > num_committer_terminations
+    );
+
+    // Terminate another stream with an unknown version range and verify it's recorded
+    let mut error_notification = create_error_notification(ErrorSource::Bootstrapper, 11);
+    error_notification.version_range = None;
+    terminated_stream_tracker.mark_terminated(&error_notification);
+    assert_none!(terminated_stream_tracker.get_last_terminated_version_range());
+}
+
+#[test]
+fn test_client_sync_request_success() {
+    // Create a client sync request handler and initialize a sync request
+    let mut client_sync_request_handler = ClientSyncRequestHandler::new(TimeService::mock());
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(10)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+    assert!(client_sync_request_handler.active_sync_request());
+    assert_eq!(
+        client_sync_request_handler.get_sync_request_target(),
+        Some(create_ledger_info_at_version(10))
+    );
+    assert_none!(client_sync_request_handler.get_halted_version());
+
+    // Verify the request isn't answered before the target is reached
+    client_sync_request_handler
+        .check_sync_request_progress(8)
+        .unwrap();
+    assert!(client_sync_request_handler.active_sync_request());
+    assert_none!(callback_receiver.try_recv().unwrap());
+
+    // Verify the request is answered once the target is reached (and the node halts)
+    client_sync_request_handler
+        .check_sync_request_progress(10)
+        .unwrap();
+    assert!(!client_sync_request_handler.active_sync_request());
+    assert_eq!(client_sync_request_handler.get_halted_version(), Some(10));
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Ok(())));
+}
+
+#[test]
+fn test_client_sync_request_already_at_target() {
+    // Create a client sync request handler
+    let mut client_sync_request_handler = ClientSyncRequestHandler::new(TimeService::mock());
+
+    // Verify a request for the latest synced version (without a ledger info)
+    // is answered immediately, and that the node halts at the target.
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            5,
+            None,
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+    assert!(!client_sync_request_handler.active_sync_request());
+    assert_eq!(client_sync_request_handler.get_halted_version(), Some(5));
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Ok(())));
+
+    // Verify a new request resumes syncing (i.e., the node is no longer halted)
+    let (callback_sender, _callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(10)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+    assert!(client_sync_request_handler.active_sync_request());
+    assert_none!(client_sync_request_handler.get_halted_version());
+}
+
+#[test]
+fn test_client_sync_request_rejected() {
+    // Create a client sync request handler
+    let mut client_sync_request_handler = ClientSyncRequestHandler::new(TimeService::mock());
+
+    // Verify old targets are rejected
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    let error = client_sync_request_handler
+        .try_initialize_sync_request(
+            3,
+            Some(create_ledger_info_at_version(3)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap_err();
+    assert_eq!(error, Error::OldSyncRequest(3, 5));
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Err(error)));
+
+    // Verify targets beyond the latest synced version require a ledger info
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    let error = client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            None,
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidSyncTarget(_));
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Err(error)));
+
+    // Verify the ledger info must match the target version
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    let error = client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(11)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidSyncTarget(_));
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Err(error)));
+    assert!(!client_sync_request_handler.active_sync_request());
+
+    // Initialize a valid sync request
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(10)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+
+    // Verify overlapping sync requests are rejected
+    let (new_callback_sender, mut new_callback_receiver) = oneshot::channel();
+    let error = client_sync_request_handler
+        .try_initialize_sync_request(
+            20,
+            Some(create_ledger_info_at_version(20)),
+            new_callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap_err();
+    assert_matches!(error, Error::ConflictingSyncRequest(_));
+    assert_eq!(new_callback_receiver.try_recv().unwrap(), Some(Err(error)));
+
+    // Verify the original request is still active and is aborted on shutdown
+    assert_eq!(
+        client_sync_request_handler.get_sync_request_target(),
+        Some(create_ledger_info_at_version(10))
+    );
+    assert_none!(callback_receiver.try_recv().unwrap());
+    drop(client_sync_request_handler);
+    assert_matches!(
+        callback_receiver.try_recv().unwrap(),
+        Some(Err(Error::SyncRequestAborted(_)))
+    );
+}
+
+#[test]
+fn test_client_sync_request_synced_beyond_target() {
+    // Create a client sync request handler and initialize a sync request
+    let mut client_sync_request_handler = ClientSyncRequestHandler::new(TimeService::mock());
+    let (callback_sender, mut callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(10)),
+            callback_sender,
+            create_ledger_info_at_version(2),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+
+    // Verify the request is answered with an error (and the node doesn't halt)
+    let error = client_sync_request_handler
+        .check_sync_request_progress(15)
+        .unwrap_err();
+    assert_matches!(error, Error::SyncedBeyondTarget(15, 10, _, 13));
+    assert!(!client_sync_request_handler.active_sync_request());
+    assert_none!(client_sync_request_handler.get_halted_version());
+    assert_eq!(callback_receiver.try_recv().unwrap(), Some(Err(error)));
+}
+
+#[test]
+fn test_client_sync_request_stale() {
+    // Create a client sync request handler and initialize a sync request
+    let mut client_sync_request_handler = ClientSyncRequestHandler::new(TimeService::mock());
+    let (callback_sender, callback_receiver) = oneshot::channel();
+    client_sync_request_handler
+        .try_initialize_sync_request(
+            10,
+            Some(create_ledger_info_at_version(10)),
+            callback_sender,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .unwrap();
+
+    // Verify the request isn't stale while the client is waiting
+    assert!(!client_sync_request_handler.clear_stale_sync_request());
+    assert!(client_sync_request_handler.active_sync_request());
+
+    // Drop the callback receiver and verify the stale request is cleared
+    drop(callback_receiver);
+    assert!(client_sync_request_handler.clear_stale_sync_request());
+    assert!(!client_sync_request_handler.active_sync_request());
+}
+
+#[tokio::test]
+async fn test_sync_request_success() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(5),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+    assert_eq!(
+        consensus_notification_handler.get_sync_request_target(),
+        Some(create_ledger_info_at_version(10))
+    );
+
+    // Verify the request isn't answered before the target is reached
+    consensus_notification_handler
+        .check_sync_request_progress(8)
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+
+    // Verify the request is answered successfully once the target is reached
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(callback_receiver.await.unwrap().result.is_ok());
+}
+
+#[tokio::test]
+async fn test_sync_request_slot_occupied() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, mut callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Verify a new sync request is rejected (and answered) while the slot is occupied
+    let (new_sync_notification, new_callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(20));
+    let error = consensus_notification_handler
+        .try_initialize_sync_request(
+            new_sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(error, Error::SyncRequestSlotOccupied(10));
+    assert!(new_callback_receiver.await.unwrap().result.is_err());
+
+    // Verify the original request is still active (and unanswered)
+    assert_eq!(
+        consensus_notification_handler.get_sync_request_target(),
+        Some(create_ledger_info_at_version(10))
+    );
+    assert!(callback_receiver.try_recv().unwrap().is_none());
+
+    // Answer the original request
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(callback_receiver.await.unwrap().result.is_ok());
+}
+
+#[tokio::test]
+async fn test_sync_request_superseded() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Supersede the request and verify it is answered with an error
+    consensus_notification_handler
+        .supersede_sync_request(20)
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(callback_receiver.await.unwrap().result.is_err());
+
+    // Verify the new request can now occupy the slot
+    let (new_sync_notification, new_callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(20));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            new_sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+    consensus_notification_handler
+        .check_sync_request_progress(20)
+        .await
+        .unwrap();
+    assert!(new_callback_receiver.await.unwrap().result.is_ok());
+}
+
+#[tokio::test]
+async fn test_sync_request_timeout() {
+    // Create a consensus notification handler with a short sync request timeout
+    // (using a mock time service, so the request can time out without sleeping).
+    let driver_config = StateSyncDriverConfig {
+        max_consensus_sync_request_duration_ms: 10,
+        ..Default::default()
+    };
+    let time_service = TimeService::mock();
+    let mut consensus_notification_handler =
+        create_consensus_notification_handler(driver_config, time_service.clone());
+
+    // Initialize a sync request and wait for it to time out
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Verify the request doesn't time out before the timeout has elapsed
+    let mock_time_service = time_service.into_mock();
+    mock_time_service.advance_ms(5);
+    consensus_notification_handler
+        .check_sync_request_progress(5)
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+
+    // Advance the time beyond the timeout
+    mock_time_service.advance_ms(45);
+
+    // Verify the request is answered with a timeout error
+    let error = consensus_notification_handler
+        .check_sync_request_progress(5)
+        .await
+        .unwrap_err();
+    match error {
+        Error::SyncRequestTimeout(sync_target_version, elapsed_time_ms, versions_advanced) => {
+            assert_eq!(sync_target_version, 10);
+            assert_eq!(elapsed_time_ms, 50);
+            assert_eq!(versions_advanced, 5);
+        }
+        error => panic!("Unexpected error: {:?}", error),
+    }
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(callback_receiver.await.unwrap().result.is_err());
+}
+
+#[tokio::test]
+async fn test_sync_request_synced_beyond_target() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(2),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Verify the error includes the elapsed time and the versions advanced
+    let error = consensus_notification_handler
+        .check_sync_request_progress(15)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::SyncedBeyondTarget(15, 10, _, 13));
+
+    // Answer the request (to avoid leaking the callback)
+    drop(consensus_notification_handler);
+    assert!(callback_receiver.await.unwrap().result.is_err());
+}
+
+#[tokio::test]
+async fn test_sync_request_target_verification() {
+    // Create the validator signers and the epoch state for the current epoch
+    let current_epoch = 5;
+    let validator_signers = create_validator_signers(0);
+    let epoch_state = EpochState {
+        epoch: current_epoch,
+        verifier: generate_validator_verifier(&validator_signers),
+    };
+
+    // Verify a sync target signed by the current validator set is accepted
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let sync_target = create_signed_ledger_info(&validator_signers, 10, current_epoch);
+    let (sync_notification, callback_receiver) = ConsensusSyncNotification::new(sync_target);
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            epoch_state.clone(),
+        )
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(callback_receiver.await.unwrap().result.is_ok());
+
+    // Verify a sync target signed by a different validator set is rejected
+    let forged_signers = create_validator_signers(100);
+    let forged_sync_target = create_signed_ledger_info(&forged_signers, 20, current_epoch);
+    let (sync_notification, callback_receiver) = ConsensusSyncNotification::new(forged_sync_target);
+    let error = consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            epoch_state.clone(),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidSyncTarget(_));
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(callback_receiver.await.unwrap().result.is_err());
+
+    // Verify a sync target from an older epoch is rejected
+    let old_sync_target = create_signed_ledger_info(&validator_signers, 20, current_epoch - 1);
+    let (sync_notification, callback_receiver) = ConsensusSyncNotification::new(old_sync_target);
+    let error = consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            epoch_state.clone(),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidSyncTarget(_));
+    assert!(callback_receiver.await.unwrap().result.is_err());
+
+    // Verify a sync target from a future epoch is accepted (it can't be verified yet)
+    let future_sync_target = create_signed_ledger_info(&forged_signers, 30, current_epoch + 1);
+    let (sync_notification, _callback_receiver) =
+        ConsensusSyncNotification::new(future_sync_target.clone());
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            epoch_state,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        consensus_notification_handler.get_sync_request_target(),
+        Some(future_sync_target)
+    );
+}
+
+#[tokio::test]
+async fn test_sync_request_future_epoch_target() {
+    // Create a consensus notification handler with a short sync request timeout
+    let driver_config = StateSyncDriverConfig {
+        max_consensus_sync_request_duration_ms: 100,
+        ..Default::default()
+    };
+    let time_service = TimeService::mock();
+    let mut consensus_notification_handler =
+        create_consensus_notification_handler(driver_config, time_service.clone());
+
+    // Initialize a sync request with a target two epochs ahead
+    let current_epoch = 3;
+    let sync_target =
+        create_signed_ledger_info(&create_validator_signers(0), 50, current_epoch + 2);
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(sync_target.clone());
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            create_epoch_state(current_epoch),
+        )
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.is_sync_target_verified());
+
+    // Verify the request doesn't time out while the target is unverified
+    let mock_time_service = time_service.into_mock();
+    mock_time_service.advance_ms(200);
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+
+    // Verify marking a different target as verified is ignored
+    consensus_notification_handler.mark_sync_target_verified(&create_ledger_info_at_version(50));
+    assert!(!consensus_notification_handler.is_sync_target_verified());
+
+    // Mark the target as verified and verify the timeout clock is reset
+    consensus_notification_handler.mark_sync_target_verified(&sync_target);
+    assert!(consensus_notification_handler.is_sync_target_verified());
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(consensus_notification_handler.active_sync_request());
+
+    // Verify the request now times out
+    mock_time_service.advance_ms(200);
+    let error = consensus_notification_handler
+        .check_sync_request_progress(20)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::SyncRequestTimeout(50, _, 10));
+    assert!(callback_receiver.await.unwrap().result.is_err());
+}
+
+#[tokio::test]
+async fn test_sync_request_rejected() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Reject the request and verify it is answered with an error
+    consensus_notification_handler
+        .reject_sync_request(Error::InvalidSyncTarget("Invalid target!".into()))
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(callback_receiver.await.unwrap().result.is_err());
+}
+
+#[tokio::test]
+async fn test_sync_request_shutdown() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Drop the handler (e.g., on shutdown) and verify the request is answered
+    drop(consensus_notification_handler);
+    assert!(callback_receiver.await.unwrap().result.is_err());
+}
+
+#[tokio::test]
+async fn test_sync_request_target_snapshots() {
+    // Create a consensus notification handler with a sync request timeout
+    // (using a mock time service, so the interleaving is deterministic).
+    let driver_config = StateSyncDriverConfig {
+        max_consensus_sync_request_duration_ms: 100,
+        ..Default::default()
+    };
+    let time_service = TimeService::mock();
+    let mut consensus_notification_handler =
+        create_consensus_notification_handler(driver_config, time_service.clone());
+    let mock_time_service = time_service.into_mock();
+
+    // Interleave the driver's updates (new requests superseding the active
+    // request, and progress checks) with reads of the sync request target
+    // (i.e., the snapshots handed to the continuous syncer). The total time
+    // exceeds the timeout, but no single request is active for that long.
+    let sync_target_versions: Vec<Version> = (10..=100).step_by(10).collect();
+    let mut callback_receivers = vec![];
+    let mut target_snapshots = vec![];
+    let mut synced_version = 0;
+    for sync_target_version in sync_target_versions.clone() {
+        // Supersede the active request (if any) with a new request
+        consensus_notification_handler
+            .supersede_sync_request(sync_target_version)
+            .await
+            .unwrap();
+        let (sync_notification, callback_receiver) =
+            ConsensusSyncNotification::new(create_ledger_info_at_version(sync_target_version));
+        consensus_notification_handler
+            .try_initialize_sync_request(
+                sync_notification,
+                create_ledger_info_at_version(synced_version),
+                create_empty_epoch_state(),
+            )
+            .await
+            .unwrap();
+        callback_receivers.push(callback_receiver);
+
+        // Take a snapshot of the target and make progress (without reaching it)
+        target_snapshots.push(consensus_notification_handler.get_sync_request_target());
+        mock_time_service.advance_ms(60);
+        synced_version = sync_target_version - 5;
+        consensus_notification_handler
+            .check_sync_request_progress(synced_version)
+            .await
+            .unwrap();
+        assert!(consensus_notification_handler.active_sync_request());
+    }
+
+    // Reach the target of the last request
+    consensus_notification_handler
+        .check_sync_request_progress(100)
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert_none!(consensus_notification_handler.get_sync_request_target());
+
+    // Verify the snapshots weren't modified by the later updates
+    for (target_snapshot, sync_target_version) in
+        target_snapshots.into_iter().zip(sync_target_versions)
+    {
+        assert_eq!(
+            target_snapshot,
+            Some(create_ledger_info_at_version(sync_target_version))
+        );
+    }
+
+    // Verify the superseded requests were answered with errors, and the last
+    // request was answered successfully.
+    let last_callback_receiver = callback_receivers.pop().unwrap();
+    for callback_receiver in callback_receivers {
+        assert!(callback_receiver.await.unwrap().result.is_err());
+    }
+    assert!(last_callback_receiver.await.unwrap().result.is_ok());
+}
+
+#[tokio::test]
+async fn test_commit_response_receiver_dropped() {
+    // Create a consensus notification handler
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+
+    // Create a commit notification and drop the response receiver
+    let (commit_notification, callback_receiver) =
+        ConsensusCommitNotification::new(vec![create_transaction()], vec![]);
+    drop(callback_receiver);
+
+    // Verify the response fails with a receiver dropped error (and is counted)
+    let num_receivers_dropped = read_consensus_receiver_dropped_counter();
+    let error = consensus_notification_handler
+        .respond_to_commit_notification(commit_notification, Ok(()))
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::ConsensusReceiverDropped(_));
+    assert!(read_consensus_receiver_dropped_counter() > num_receivers_dropped);
+}
+
+#[tokio::test]
+async fn test_stale_sync_request() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Verify the request isn't cleared while consensus is still waiting on it
+    assert!(!consensus_notification_handler.clear_stale_sync_request());
+    assert!(consensus_notification_handler.active_sync_request());
+
+    // Drop the response receiver (e.g., consensus restarted)
+    drop(callback_receiver);
+
+    // Verify the stale request is cleared (and counted) when the next consensus notification arrives
+    let num_stale_sync_requests = read_driver_counter(metrics::STALE_SYNC_REQUEST);
+    assert!(consensus_notification_handler.clear_stale_sync_request());
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert_eq!(
+        consensus_notification_handler.get_sync_request_target(),
+        None
+    );
+    assert!(read_driver_counter(metrics::STALE_SYNC_REQUEST) > num_stale_sync_requests);
+
+    // Verify a new sync request can now occupy the slot
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(20));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.clear_stale_sync_request());
+    assert_eq!(
+        consensus_notification_handler.get_sync_request_target(),
+        Some(create_ledger_info_at_version(20))
+    );
+
+    // Answer the new request
+    consensus_notification_handler
+        .check_sync_request_progress(20)
+        .await
+        .unwrap();
+    assert!(callback_receiver.await.unwrap().result.is_ok());
+}
+
+#[tokio::test]
+async fn test_sync_request_receiver_dropped() {
+    // Create a consensus notification handler and initialize a sync request
+    let mut consensus_notification_handler = create_consensus_notification_handler(
+        StateSyncDriverConfig::default(),
+        TimeService::mock(),
+    );
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(10));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(0),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+
+    // Drop the response receiver and verify the request is still cleared
+    // once the target is reached (without returning an error).
+    drop(callback_receiver);
+    let num_receivers_dropped = read_consensus_receiver_dropped_counter();
+    consensus_notification_handler
+        .check_sync_request_progress(10)
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+    assert!(read_consensus_receiver_dropped_counter() > num_receivers_dropped);
+
+    // Initialize another sync request and drop the response receiver
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(20));
+    consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap();
+    drop(callback_receiver);
+
+    // Verify the request can still be superseded (and the slot is freed)
+    consensus_notification_handler
+        .supersede_sync_request(30)
+        .await
+        .unwrap();
+    assert!(!consensus_notification_handler.active_sync_request());
+
+    // Verify a sync notification with a dropped receiver returns an error
+    // when it is answered directly (e.g., it is rejected immediately).
+    let (sync_notification, callback_receiver) =
+        ConsensusSyncNotification::new(create_ledger_info_at_version(5));
+    drop(callback_receiver);
+    let error = consensus_notification_handler
+        .try_initialize_sync_request(
+            sync_notification,
+            create_ledger_info_at_version(10),
+            create_empty_epoch_state(),
+        )
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::ConsensusReceiverDropped(_));
+    assert!(!consensus_notification_handler.active_sync_request());
+}
+
+/// Creates a consensus notification handler for testing
+fn create_consensus_notification_handler(
+    driver_config: StateSyncDriverConfig,
+    time_service: TimeService,
+) -> ConsensusNotificationHandler {
+    let (_, consensus_listener) = consensus_notifications::new_consensus_notifier_listener_pair(
+        StateSyncDriverConfig::default().commit_notification_timeout_ms,
+    );
+    ConsensusNotificationHandler::new(
+        driver_config,
+        consensus_listener,
+        time_service,
+        DriverMetrics::default(),
+    )
+}
+
+/// Creates an error notification for the given component and notification ID
+fn create_error_notification(
+    component: ErrorSource,
+    notification_id: NotificationId,
+) -> ErrorNotification {
+    ErrorNotification {
+        component,
+        error: Error::UnexpectedError("Failed to execute chunk!".into()),
+        expected_version: None,
+        failed_operation: FailedOperation::ExecuteChunk,
+        notification_id,
+        severity: ErrorSeverity::Recoverable,
+        stage: StorageSynchronizerStage::Executor,
+        version_range: Some((0, 0)),
+    }
+}
+
+/// Creates an event notification handler for testing
+fn create_event_notification_handler(
+    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+) -> EventNotificationHandler {
+    EventNotificationHandler::new(
+        event_subscription_service,
+        StateSyncDriverConfig::default().max_pending_event_notifications,
+        None,
+        DriverMetrics::default(),
+    )
+}
+
+/// Creates an event subscription service (backed by a genesis database) for testing
+fn create_genesis_event_subscription_service() -> Arc<Mutex<EventSubscriptionService>> {
+    // Create a test database
+    let db_path = aptos_temppath::TempPath::new();
+    db_path.create_as_dir().unwrap();
+    let (_, db_rw) = DbReaderWriter::wrap(AptosDB::new_for_test(db_path.path()));
+
+    // Bootstrap the genesis transaction
+    let (genesis, _) = vm_genesis::test_genesis_change_set_and_validators(Some(1));
+    let genesis_txn = Transaction::GenesisTransaction(WriteSetPayload::Direct(genesis));
+    bootstrap_genesis::<AptosVM>(&db_rw, &genesis_txn).unwrap();
+
+    Arc::new(Mutex::new(EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(db_rw)),
+    )))
+}
+
+/// Subscribes to the given event key and reconfigurations
+fn subscribe_to_events_and_reconfigurations(
+    event_subscription_service: &Arc<Mutex<EventSubscriptionService>>,
+    event_key: EventKey,
+) -> (EventNotificationListener, ReconfigNotificationListener) {
+    let mut event_subscription_service = event_subscription_service.lock();
+    let event_listener = event_subscription_service
+        .subscribe_to_events(vec![event_key])
+        .unwrap();
+    let reconfig_listener = event_subscription_service
+        .subscribe_to_reconfigurations()
+        .unwrap();
+    (event_listener, reconfig_listener)
+}
+
+/// Returns the number of closed (or resynced) event subscriptions
+fn read_event_subscription_recovery_counter(resynced: bool) -> u64 {
+    let label = if resynced {
+        metrics::RESYNCED_RECONFIG_SUBSCRIPTION
+    } else {
+        metrics::CLOSED_EVENT_SUBSCRIPTION
+    };
+    DriverMetrics::default()
+        .counter(&metrics::EVENT_SUBSCRIPTION_RECOVERY, &[label])
+        .get()
+}
+
+/// Creates an event subscription service for testing
+fn create_event_subscription_service() -> Arc<Mutex<EventSubscriptionService>> {
+    Arc::new(Mutex::new(EventSubscriptionService::new(
+        ON_CHAIN_CONFIG_REGISTRY,
+        Arc::new(RwLock::new(create_mock_reader_writer(None, None))),
+    )))
+}
+
+/// Handles a commit of the given events and transactions (starting at the
+/// specified version), verifies that no error is returned and returns the
+/// commit summary.
+async fn handle_commit(
+    events: Vec<ContractEvent>,
+    transactions: Vec<Transaction>,
+    first_version: Version,
+    mempool_notification_handler: MempoolNotificationHandler<FlakyMempoolNotifier>,
+    event_notification_handler: EventNotificationHandler,
+    notified_version_tracker: NotifiedVersionTracker,
+) -> CommitSummary {
+    let committed_transactions = CommittedTransactions::new(events, transactions, first_version);
+    let last_version = committed_transactions.last_version;
+    CommitNotification::handle_transaction_notification(
+        committed_transactions,
+        CommitOrigin::StateSync,
+        create_ledger_info_at_version(last_version),
+        mempool_notification_handler,
+        event_notification_handler,
+        CommitSubscriberRegistry::default(),
+        notified_version_tracker,
+    )
+    .await
+    .unwrap()
+}
+
+/// Handles a commit of the given events and transactions (at version 10)
+/// using the given scheduled mempool notifier, and returns the result.
+async fn handle_commit_with_mempool(
+    events: Vec<ContractEvent>,
+    transactions: Vec<Transaction>,
+    mempool_notification_handler: MempoolNotificationHandler<ScheduledMempoolNotifier>,
+    event_notification_handler: EventNotificationHandler,
+) -> Result<CommitSummary, Error> {
+    let committed_transactions = CommittedTransactions::new(events, transactions, 10);
+    let last_version = committed_transactions.last_version;
+    CommitNotification::handle_transaction_notification(
+        committed_transactions,
+        CommitOrigin::StateSync,
+        create_ledger_info_at_version(last_version),
+        mempool_notification_handler,
+        event_notification_handler,
+        CommitSubscriberRegistry::default(),
+        NotifiedVersionTracker::default(),
+    )
+    .await
+}
+
+/// Creates a mempool notification handler that fails (after a short delay)
+/// with a non-transient error, i.e., the notification isn't retried.
+fn create_failing_mempool_notification_handler(
+) -> MempoolNotificationHandler<ScheduledMempoolNotifier> {
+    MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        ScheduledMempoolNotifier::new(vec![(
+            Duration::from_millis(100),
+            Err(mempool_notifications::Error::UnexpectedErrorEncountered(
+                "Mempool is unavailable!".into(),
+            )),
+        )]),
+        DriverMetrics::default(),
+    )
+}
+
+/// Creates a block metadata transaction for testing
+fn create_block_metadata_transaction() -> Transaction {
+    Transaction::BlockMetadata(BlockMetadata::new(
+        HashValue::random(),
+        0,
+        0,
+        AccountAddress::random(),
+        vec![],
+        vec![],
+        0,
+    ))
+}
+
+/// Creates a commit event for the given version (for testing)
+fn create_commit_event(version: Version) -> CommitEvent {
+    CommitEvent {
+        dry_run: false,
+        epoch: 0,
+        first_version: version,
+        last_version: version,
+        reconfiguration_occurred: false,
+        timestamp_usecs: version,
+    }
+}
+
+/// Creates a driver config with the specified mempool notification batching values
+fn create_batching_config(
+    max_batch_delay_ms: u64,
+    max_batch_transactions: u64,
+) -> StateSyncDriverConfig {
+    StateSyncDriverConfig {
+        mempool_notification_batch_max_delay_ms: max_batch_delay_ms,
+        mempool_notification_batch_max_transactions: max_batch_transactions,
+        ..Default::default()
+    }
+}
+
+/// Returns the mempool notification counter for the given origin and result
+fn read_mempool_notification_counter(commit_origin: CommitOrigin, result: &str) -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::MEMPOOL_NOTIFICATIONS,
+            &[commit_origin.get_label(), result],
+        )
+        .get()
+}
+
+/// Returns the number of mempool notification latency samples for the given origin
+fn read_mempool_notification_latency_samples(commit_origin: CommitOrigin) -> u64 {
+    DriverMetrics::default()
+        .histogram(
+            &metrics::MEMPOOL_NOTIFICATION_LATENCY,
+            &[commit_origin.get_label()],
+        )
+        .get_sample_count()
+}
+
+/// Returns the commit subscriber notification counter for the given subscriber and result
+fn read_commit_subscriber_counter(
+    commit_subscriber: &RecordingCommitSubscriber,
+    success: bool,
+) -> u64 {
+    let result_label = if success {
+        metrics::COMMIT_SUBSCRIBER_SUCCESS
+    } else {
+        metrics::COMMIT_SUBSCRIBER_FAILURE
+    };
+    DriverMetrics::default()
+        .counter(
+            &metrics::COMMIT_SUBSCRIBER_NOTIFICATIONS,
+            &[commit_subscriber.name(), result_label],
+        )
+        .get()
+}
+
+/// Returns the number of commits for which mempool notifications were skipped
+fn read_skipped_mempool_notifications() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::SKIPPED_MEMPOOL_NOTIFICATION],
+        )
+        .get()
+}
+
+/// Returns the number of consensus responses that failed due to dropped receivers
+fn read_consensus_receiver_dropped_counter() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::CONSENSUS_RECEIVER_DROPPED],
+        )
+        .get()
+}
+
+/// Returns the value of the driver counter with the given label
+fn read_driver_counter(label: &str) -> u64 {
+    DriverMetrics::default()
+        .counter(&metrics::DRIVER_COUNTERS, &[label])
+        .get()
+}
+
+/// Returns the sample count and sum of the epoch duration histogram
+fn read_epoch_duration_histogram(driver_metrics: &DriverMetrics) -> (u64, f64) {
+    let histogram = driver_metrics.histogram(&metrics::EPOCH_DURATION, &[]);
+    (histogram.get_sample_count(), histogram.get_sample_sum())
+}
+
+/// Returns the synced epoch gauge value
+fn read_synced_epoch(driver_metrics: &DriverMetrics) -> i64 {
+    driver_metrics
+        .gauge(
+            &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
+            &[metrics::StorageSynchronizerOperations::SyncedEpoch.get_label()],
+        )
+        .get()
+}
+
+/// Returns the number of bootstrapper streams terminated due to failures at the given stage
+fn read_terminated_streams(stage: StorageSynchronizerStage) -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::TERMINATED_DATA_STREAMS,
+            &[ErrorSource::Bootstrapper.get_label(), stage.get_label()],
+        )
+        .get()
+}
+
+/// Creates a driver config with the specified mempool notification retry values
+fn create_retry_config(max_retries: u64, retry_deadline_ms: u64) -> StateSyncDriverConfig {
+    StateSyncDriverConfig {
+        max_mempool_notification_retries: max_retries,
+        mempool_notification_retry_backoff_ms: 10,
+        mempool_notification_retry_deadline_ms: retry_deadline_ms,
+        ..Default::default()
+    }
+}
+
+/// Verifies that the expected notifications are (eventually) delivered to mempool
+async fn verify_delivered_notifications(
+    mempool_notifier: &FlakyMempoolNotifier,
+    expected_notifications: Vec<Vec<Transaction>>,
+) {
+    let max_wait_time_secs = 10;
+    for _ in 0..max_wait_time_secs * 10 {
+        if mempool_notifier.get_delivered_notifications().len() >= expected_notifications.len() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(
+        mempool_notifier.get_delivered_notifications(),
+        expected_notifications
+    );
+}
+
+/// Waits until the mempool notifier has received the expected number of notifications
+async fn wait_for_mempool_notifications(
+    mempool_notifier: &ScheduledMempoolNotifier,
+    expected_num_notifications: u64,
+) {
+    let max_wait_time_secs = 10;
+    for _ in 0..max_wait_time_secs * 10 {
+        if mempool_notifier.get_num_notifications() >= expected_num_notifications {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    panic!(
+        "Mempool only received {:?} notifications! Expected: {:?}",
+        mempool_notifier.get_num_notifications(),
+        expected_num_notifications
+    );
+}
+
+/// Returns the committed state snapshot of the given notification (or panics
+/// if the notification is not a snapshot notification).
+fn unwrap_committed_state_snapshot(
+    commit_notification: CommitNotification,
+) -> CommittedStateSnapshot {
+    match commit_notification {
+        CommitNotification::CommittedStateSnapshot(committed_snapshot) => committed_snapshot,
+        commit_notification => panic!("Unexpected commit notification: {:?}", commit_notification),
+    }
+}
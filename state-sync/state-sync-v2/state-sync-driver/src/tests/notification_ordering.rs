@@ -0,0 +1,254 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    notification_handlers::{ErrorSeverity, ErrorSource},
+    tests::{
+        driver_harness::{DriverHarness, DriverHarnessBuilder},
+        utils::{create_error_notification, create_transaction},
+    },
+};
+use aptos_config::config::RoleType;
+use aptos_types::transaction::{Transaction, Version};
+use data_streaming_service::data_notification::NotificationId;
+use proptest::{collection::vec, prelude::*};
+use std::{collections::HashSet, time::Duration};
+use tokio::time::timeout;
+
+// The timeout (of real time) for the driver to respond to consensus
+const CONSENSUS_RESPONSE_TIMEOUT_SECS: u64 = 10;
+
+/// A single step in a sequence of notifications sent to the driver
+#[derive(Clone, Debug)]
+enum NotificationOperation {
+    AdvanceTime(u64),        // Advance the simulated time (in milliseconds)
+    CommitTransactions(u64), // Commit a batch of transactions (as consensus would)
+    NotifyDuplicateCommit,   // Resend the last commit notification (without committing)
+    NotifyError(ErrorSource, ErrorSeverity, NotificationId), // Send an error notification
+}
+
+/// The state observed by the invariant checks across a sequence of operations
+#[derive(Default)]
+struct InvariantState {
+    committed_transactions: Vec<Transaction>, // All transactions committed by consensus (in order)
+    last_committed_batch: Option<Vec<Transaction>>,
+    synced_version: Option<Version>,
+    terminated_notification_ids: HashSet<NotificationId>,
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn test_commit_and_error_interleavings(
+        operations in vec(arb_notification_operation(), 1..40)
+    ) {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(verify_notification_sequence(operations))?;
+    }
+}
+
+#[test]
+fn test_commit_and_error_interleaving_sequences() {
+    // Sequences that previously exercised interesting interleavings (failing
+    // sequences found by the property test should be added here).
+    let operation_sequences = vec![
+        vec![
+            NotificationOperation::NotifyDuplicateCommit, // No commit has happened yet
+            NotificationOperation::CommitTransactions(1),
+            NotificationOperation::NotifyDuplicateCommit,
+        ],
+        vec![
+            NotificationOperation::CommitTransactions(3),
+            NotificationOperation::NotifyError(
+                ErrorSource::ContinuousSyncer,
+                ErrorSeverity::Recoverable,
+                0,
+            ),
+            NotificationOperation::NotifyError(
+                ErrorSource::ContinuousSyncer,
+                ErrorSeverity::Recoverable,
+                0,
+            ),
+            NotificationOperation::CommitTransactions(2),
+        ],
+        vec![
+            NotificationOperation::NotifyError(ErrorSource::Bootstrapper, ErrorSeverity::Fatal, 1),
+            NotificationOperation::AdvanceTime(2500),
+            NotificationOperation::CommitTransactions(1),
+            NotificationOperation::NotifyError(
+                ErrorSource::StorageSynchronizer,
+                ErrorSeverity::Transient,
+                2,
+            ),
+            NotificationOperation::NotifyDuplicateCommit,
+            NotificationOperation::AdvanceTime(1000),
+        ],
+    ];
+
+    // Verify the invariants hold for each sequence
+    for operations in operation_sequences {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime
+            .block_on(verify_notification_sequence(operations))
+            .unwrap();
+    }
+}
+
+/// Drives the given operations through a bootstrapped validator harness, and
+/// verifies the driver invariants after each step.
+async fn verify_notification_sequence(
+    operations: Vec<NotificationOperation>,
+) -> Result<(), TestCaseError> {
+    // Create a bootstrapped validator
+    let mut driver_harness = DriverHarnessBuilder::new(RoleType::Validator)
+        .with_driver_config(|driver_config| driver_config.max_connection_deadline_secs = 0)
+        .build()
+        .await;
+    prop_assert!(driver_harness.driver_client().is_bootstrapped());
+
+    // Execute each operation and verify the invariants
+    let mut invariant_state = InvariantState::default();
+    for operation in operations {
+        match operation {
+            NotificationOperation::AdvanceTime(duration_ms) => {
+                driver_harness
+                    .advance_time(Duration::from_millis(duration_ms))
+                    .await;
+            }
+            NotificationOperation::CommitTransactions(num_transactions) => {
+                let transactions: Vec<Transaction> = (0..num_transactions)
+                    .map(|_| create_transaction())
+                    .collect();
+                let response = timeout(
+                    Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS),
+                    driver_harness.commit_transactions(transactions.clone(), vec![]),
+                )
+                .await;
+                prop_assert!(
+                    matches!(response, Ok(Ok(()))),
+                    "The commit notification wasn't answered successfully: {:?}",
+                    response
+                );
+                invariant_state
+                    .committed_transactions
+                    .extend(transactions.clone());
+                invariant_state.last_committed_batch = Some(transactions);
+            }
+            NotificationOperation::NotifyDuplicateCommit => {
+                // Only commits that have already happened can be duplicated
+                if let Some(transactions) = invariant_state.last_committed_batch.clone() {
+                    let response = timeout(
+                        Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS),
+                        driver_harness.notify_new_commit(transactions, vec![]),
+                    )
+                    .await;
+                    prop_assert!(
+                        matches!(response, Ok(Ok(()))),
+                        "The duplicate commit notification wasn't answered successfully: {:?}",
+                        response
+                    );
+                }
+            }
+            NotificationOperation::NotifyError(component, severity, notification_id) => {
+                driver_harness
+                    .notify_error(create_error_notification(
+                        component,
+                        severity,
+                        notification_id,
+                    ))
+                    .await;
+            }
+        }
+        verify_invariants(&mut driver_harness, &mut invariant_state).await?;
+    }
+
+    Ok(())
+}
+
+/// Verifies the driver invariants hold (given the state observed so far)
+async fn verify_invariants(
+    driver_harness: &mut DriverHarness,
+    invariant_state: &mut InvariantState,
+) -> Result<(), TestCaseError> {
+    // Verify the synced version never decreases (and matches storage). This
+    // also verifies the driver is still alive (i.e., it hasn't panicked).
+    let sync_status = driver_harness
+        .driver_client()
+        .get_sync_status(Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS))
+        .await
+        .map_err(|error| TestCaseError::fail(format!("The driver is gone: {:?}", error)))?;
+    prop_assert!(
+        sync_status.synced_version >= invariant_state.synced_version,
+        "The synced version decreased! Previous: {:?}, current: {:?}",
+        invariant_state.synced_version,
+        sync_status.synced_version
+    );
+    prop_assert_eq!(
+        sync_status.synced_version,
+        Some(driver_harness.get_storage().get_latest_version())
+    );
+    invariant_state.synced_version = sync_status.synced_version;
+
+    // Verify mempool never sees a transaction twice, and that the notified
+    // transactions are a prefix of the committed transactions (notifications
+    // may be batched, so the most recent commits might not be notified yet).
+    let notified_transactions: Vec<Transaction> = driver_harness
+        .get_mempool_notifications()
+        .into_iter()
+        .flatten()
+        .collect();
+    prop_assert!(notified_transactions.len() <= invariant_state.committed_transactions.len());
+    prop_assert_eq!(
+        &notified_transactions[..],
+        &invariant_state.committed_transactions[..notified_transactions.len()]
+    );
+
+    // Verify streams are never terminated twice
+    while let Some((notification_id, notification_feedback)) =
+        driver_harness.next_stream_termination()
+    {
+        prop_assert!(
+            invariant_state
+                .terminated_notification_ids
+                .insert(notification_id),
+            "The stream was terminated twice! Notification ID: {:?}, feedback: {:?}",
+            notification_id,
+            notification_feedback
+        );
+    }
+
+    Ok(())
+}
+
+/// Returns a strategy for a single (causally plausible) notification operation.
+/// Errors reference a small set of notification IDs, so that duplicate errors
+/// for the same notification are common.
+fn arb_notification_operation() -> impl Strategy<Value = NotificationOperation> {
+    let error_source = prop_oneof![
+        Just(ErrorSource::Bootstrapper),
+        Just(ErrorSource::ContinuousSyncer),
+        Just(ErrorSource::StorageSynchronizer),
+    ];
+    let error_severity = prop_oneof![
+        Just(ErrorSeverity::Transient),
+        Just(ErrorSeverity::Recoverable),
+        Just(ErrorSeverity::Fatal),
+    ];
+    prop_oneof![
+        1 => (0..2500u64).prop_map(NotificationOperation::AdvanceTime),
+        3 => (1..5u64).prop_map(NotificationOperation::CommitTransactions),
+        1 => Just(NotificationOperation::NotifyDuplicateCommit),
+        2 => (error_source, error_severity, 0..4u64).prop_map(
+            |(component, severity, notification_id)| {
+                NotificationOperation::NotifyError(component, severity, notification_id)
+            }
+        ),
+    ]
+}
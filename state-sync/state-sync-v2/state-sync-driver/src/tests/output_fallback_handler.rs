@@ -0,0 +1,161 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    metrics::{self, DriverMetrics},
+    utils::OutputFallbackHandler,
+};
+use aptos_config::config::StateSyncDriverConfig;
+use aptos_time_service::{MockTimeService, TimeService};
+
+#[test]
+fn test_fallback_after_repeated_failures() {
+    // Create an output fallback handler that falls back after three failures
+    let (output_fallback_handler, _, driver_metrics) = create_output_fallback_handler(3, 300, 0);
+
+    // Fail two different version ranges and verify there's no fallback
+    for _ in 0..2 {
+        output_fallback_handler.record_execution_failure((0, 99));
+        output_fallback_handler.record_execution_failure((100, 199));
+    }
+    assert!(!output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_STARTED),
+        0
+    );
+
+    // Fail the same version range again and verify the fallback starts
+    output_fallback_handler.record_execution_failure((100, 199));
+    assert!(output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_STARTED),
+        1
+    );
+
+    // Verify further failures don't restart the fallback
+    for _ in 0..3 {
+        output_fallback_handler.record_execution_failure((0, 99));
+    }
+    assert!(output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_STARTED),
+        1
+    );
+}
+
+#[test]
+fn test_fallback_failure_window() {
+    // Create an output fallback handler that falls back after two failures within 10 secs
+    let (output_fallback_handler, mock_time_service, _) = create_output_fallback_handler(2, 10, 0);
+
+    // Fail the same version range outside the window and verify there's no fallback
+    for _ in 0..5 {
+        output_fallback_handler.record_execution_failure((0, 99));
+        mock_time_service.advance_secs(10);
+    }
+    assert!(!output_fallback_handler.in_fallback_mode());
+
+    // Fail the version range twice within the window and verify the fallback starts
+    output_fallback_handler.record_execution_failure((0, 99));
+    mock_time_service.advance_secs(9);
+    output_fallback_handler.record_execution_failure((0, 99));
+    assert!(output_fallback_handler.in_fallback_mode());
+}
+
+#[test]
+fn test_fallback_cooldown_revert() {
+    // Create an output fallback handler with a cool-down of 60 secs
+    let (output_fallback_handler, mock_time_service, driver_metrics) =
+        create_output_fallback_handler(1, 300, 60);
+
+    // Fail a version range and verify the fallback starts
+    output_fallback_handler.record_execution_failure((0, 99));
+    assert!(output_fallback_handler.in_fallback_mode());
+
+    // Verify the fallback remains active until the cool-down expires
+    mock_time_service.advance_secs(59);
+    assert!(output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_REVERTED),
+        0
+    );
+
+    // Elapse the cool-down and verify the fallback is reverted (exactly once)
+    mock_time_service.advance_secs(1);
+    for _ in 0..3 {
+        assert!(!output_fallback_handler.in_fallback_mode());
+    }
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_REVERTED),
+        1
+    );
+
+    // Fail the version range again and verify the fallback restarts
+    output_fallback_handler.record_execution_failure((0, 99));
+    assert!(output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_STARTED),
+        2
+    );
+}
+
+#[test]
+fn test_fallback_without_cooldown() {
+    // Create an output fallback handler without a cool-down
+    let (output_fallback_handler, mock_time_service, _) = create_output_fallback_handler(1, 300, 0);
+
+    // Fail a version range and verify the fallback is never reverted
+    output_fallback_handler.record_execution_failure((0, 99));
+    for _ in 0..10 {
+        mock_time_service.advance_secs(3600);
+        assert!(output_fallback_handler.in_fallback_mode());
+    }
+}
+
+#[test]
+fn test_fallback_disabled() {
+    // Create an output fallback handler with the fallback disabled
+    let (output_fallback_handler, _, driver_metrics) = create_output_fallback_handler(0, 300, 60);
+
+    // Repeatedly fail a version range and verify there's no fallback
+    for _ in 0..10 {
+        output_fallback_handler.record_execution_failure((0, 99));
+    }
+    assert!(!output_fallback_handler.in_fallback_mode());
+    assert_eq!(
+        read_counter(&driver_metrics, metrics::OUTPUT_FALLBACK_STARTED),
+        0
+    );
+}
+
+/// Creates an output fallback handler with the given config (and a unique
+/// metrics instance, so that concurrent tests don't interfere).
+fn create_output_fallback_handler(
+    max_execution_failures_before_fallback: u64,
+    execution_failure_window_secs: u64,
+    output_fallback_cooldown_secs: u64,
+) -> (OutputFallbackHandler, MockTimeService, DriverMetrics) {
+    let driver_config = StateSyncDriverConfig {
+        execution_failure_window_secs,
+        max_execution_failures_before_fallback,
+        output_fallback_cooldown_secs,
+        ..Default::default()
+    };
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let time_service = TimeService::mock();
+    let output_fallback_handler =
+        OutputFallbackHandler::new(driver_config, driver_metrics, time_service.clone());
+
+    (
+        output_fallback_handler,
+        time_service.into_mock(),
+        driver_metrics,
+    )
+}
+
+/// Returns the value of the driver counter with the given label
+fn read_counter(driver_metrics: &DriverMetrics, label: &str) -> u64 {
+    driver_metrics
+        .counter(&metrics::DRIVER_COUNTERS, &[label])
+        .get()
+}
@@ -0,0 +1,135 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::utils::create_event, utils};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{NewEpochEvent, CORE_CODE_ADDRESS},
+    contract_event::ContractEvent,
+    event::EventKey,
+    on_chain_config::new_epoch_event_key,
+};
+use move_deps::move_core_types::{language_storage::TypeTag, move_resource::MoveStructType};
+
+#[test]
+fn test_extract_no_events() {
+    // Verify nothing is extracted from an empty set of events
+    let (reconfig_events, contains_new_epoch_event) = utils::extract_reconfig_events(&[]);
+    assert!(reconfig_events.is_empty());
+    assert!(!contains_new_epoch_event);
+}
+
+#[test]
+fn test_extract_no_reconfig_events() {
+    // Create several events with random keys
+    let events: Vec<ContractEvent> = (0..10).map(|_| create_event(None)).collect();
+
+    // Verify no reconfiguration events are extracted
+    let (reconfig_events, contains_new_epoch_event) = utils::extract_reconfig_events(&events);
+    assert!(reconfig_events.is_empty());
+    assert!(!contains_new_epoch_event);
+}
+
+#[test]
+fn test_extract_reconfig_event() {
+    // Create a single reconfiguration event amongst other events
+    let reconfig_event = create_event(Some(new_epoch_event_key()));
+    let events = vec![
+        create_event(None),
+        reconfig_event.clone(),
+        create_event(None),
+    ];
+
+    // Verify only the reconfiguration event is extracted
+    let (reconfig_events, contains_new_epoch_event) = utils::extract_reconfig_events(&events);
+    assert_eq!(reconfig_events, vec![reconfig_event.clone()]);
+    assert!(contains_new_epoch_event);
+
+    // Verify the event is extracted when it's the only event
+    let (reconfig_events, contains_new_epoch_event) =
+        utils::extract_reconfig_events(&[reconfig_event.clone()]);
+    assert_eq!(reconfig_events, vec![reconfig_event]);
+    assert!(contains_new_epoch_event);
+}
+
+#[test]
+fn test_extract_multiple_reconfig_events() {
+    // Create several reconfiguration events interleaved with other events
+    let reconfig_events: Vec<ContractEvent> = (0..3)
+        .map(|sequence_number| create_new_epoch_event(new_epoch_event_key(), sequence_number))
+        .collect();
+    let mut events = vec![];
+    for reconfig_event in &reconfig_events {
+        events.push(create_event(None));
+        events.push(reconfig_event.clone());
+    }
+
+    // Verify all reconfiguration events are extracted (in order)
+    let (extracted_events, contains_new_epoch_event) = utils::extract_reconfig_events(&events);
+    assert_eq!(extracted_events, reconfig_events);
+    assert!(contains_new_epoch_event);
+}
+
+#[test]
+fn test_extract_look_alike_events() {
+    // Create events that look like reconfigurations, but have the wrong keys
+    let new_epoch_event_key = new_epoch_event_key();
+    let look_alike_event_keys = vec![
+        EventKey::new(
+            new_epoch_event_key.get_creation_number(),
+            AccountAddress::random(),
+        ), // Wrong account address
+        EventKey::new(
+            new_epoch_event_key.get_creation_number() + 1,
+            CORE_CODE_ADDRESS,
+        ), // Wrong creation number
+        EventKey::new(0, CORE_CODE_ADDRESS), // Wrong creation number
+        EventKey::random(),                  // Random key
+    ];
+    let look_alike_events: Vec<ContractEvent> = look_alike_event_keys
+        .into_iter()
+        .map(|event_key| create_new_epoch_event(event_key, 0))
+        .collect();
+
+    // Verify none of the look-alike events are extracted
+    let (reconfig_events, contains_new_epoch_event) =
+        utils::extract_reconfig_events(&look_alike_events);
+    assert!(reconfig_events.is_empty());
+    assert!(!contains_new_epoch_event);
+
+    // Add a real reconfiguration event and verify only it is extracted
+    let reconfig_event = create_new_epoch_event(new_epoch_event_key, 0);
+    let mut events = look_alike_events;
+    events.insert(2, reconfig_event.clone());
+    let (reconfig_events, contains_new_epoch_event) = utils::extract_reconfig_events(&events);
+    assert_eq!(reconfig_events, vec![reconfig_event]);
+    assert!(contains_new_epoch_event);
+}
+
+#[test]
+fn test_extract_reconfig_events_by_key() {
+    // Create a reconfiguration event with an unexpected type (and payload)
+    let reconfig_event = create_event(Some(new_epoch_event_key()));
+    assert_ne!(
+        reconfig_event.type_tag(),
+        &TypeTag::Struct(NewEpochEvent::struct_tag())
+    );
+
+    // Verify the event is still extracted (reconfigurations are identified by
+    // key, as they are by the event subscription service).
+    let (reconfig_events, contains_new_epoch_event) =
+        utils::extract_reconfig_events(&[reconfig_event.clone()]);
+    assert_eq!(reconfig_events, vec![reconfig_event]);
+    assert!(contains_new_epoch_event);
+}
+
+/// Creates a new epoch event (i.e., with the new epoch type and payload)
+/// using the given event key and sequence number.
+fn create_new_epoch_event(event_key: EventKey, sequence_number: u64) -> ContractEvent {
+    ContractEvent::new(
+        event_key,
+        sequence_number,
+        TypeTag::Struct(NewEpochEvent::struct_tag()),
+        bcs::to_bytes(&sequence_number).unwrap(),
+    )
+}
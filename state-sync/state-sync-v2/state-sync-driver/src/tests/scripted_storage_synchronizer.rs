@@ -0,0 +1,855 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    notification_handlers::{
+        CommittedTransactions, ErrorNotification, ErrorNotificationListener, ErrorSeverity,
+        ErrorSource,
+    },
+    storage_synchronizer::{
+        ApplyOrExecute, CommittedVersionHandle, FailedOperation, PendingDataSummary,
+        ShutdownSummary, StorageSynchronizerInterface, StorageSynchronizerStage,
+        TransactionChunkPayload,
+    },
+    tests::utils::{
+        create_epoch_ending_ledger_info, create_transaction,
+        create_transaction_info_list_with_proof,
+    },
+};
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use aptos_types::{
+    contract_event::ContractEvent,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    state_store::state_value::StateValueChunkWithProof,
+    transaction::{Transaction, TransactionListWithProof, TransactionOutputListWithProof, Version},
+};
+use claim::{assert_matches, assert_none, assert_ok, assert_some};
+use data_streaming_service::data_notification::NotificationId;
+use futures::{channel::mpsc, FutureExt, StreamExt};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+/// The scripted behavior of the `ScriptedStorageSynchronizer` for a single
+/// submitted transaction chunk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChunkBehavior {
+    Commit,                     // Commit the chunk immediately (i.e., on submission)
+    CommitAfterDelay(Duration), // Commit the chunk once the (simulated) delay elapses
+    CommitOutOfOrder,           // Commit the chunk once a later chunk has been committed
+    CommitTwice,                // Commit the chunk immediately, and notify the commit twice
+    CommitWithTransientError, // Commit the chunk immediately, but notify a transient error (e.g., failed bookkeeping)
+    Fail(ErrorSeverity),      // Fail the chunk immediately (and notify the error)
+    Hold,                     // Hold the chunk in flight until it's released (or reset)
+}
+
+/// A transaction chunk submitted to the `ScriptedStorageSynchronizer`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubmittedChunk {
+    pub apply_or_execute: ApplyOrExecute,
+    pub end_of_epoch: bool, // Whether the chunk was submitted with an end of epoch ledger info
+    pub error_source: ErrorSource,
+    pub events: Vec<ContractEvent>,
+    pub first_version: Version,
+    pub notification_id: NotificationId,
+    pub num_stream_resets: u64, // The number of stream resets seen when the chunk was submitted
+    pub transactions: Vec<Transaction>,
+}
+
+impl SubmittedChunk {
+    /// Returns the version of the last transaction in the chunk
+    pub fn last_version(&self) -> Version {
+        self.first_version + (self.transactions.len() as u64).saturating_sub(1)
+    }
+}
+
+/// A storage synchronizer for tests that records every submitted transaction
+/// chunk, and commits (or fails) each chunk according to a script of chunk
+/// behaviors (see `ChunkBehavior`). Once the script is exhausted, all chunks
+/// are committed immediately. Commits are notified along the committed
+/// transactions channel (and update the committed version handle), and
+/// failures are notified along the error notification channel.
+///
+/// Note: chunks aren't executed or verified, and state snapshots aren't
+/// supported.
+#[derive(Clone)]
+pub struct ScriptedStorageSynchronizer {
+    committed_transactions_sender: mpsc::UnboundedSender<CommittedTransactions>,
+    committed_version_handle: CommittedVersionHandle,
+    error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
+    state: Arc<Mutex<ScriptedState>>,
+    time_service: TimeService,
+}
+
+/// The (shared) state of the scripted storage synchronizer
+#[derive(Default)]
+struct ScriptedState {
+    behaviors: VecDeque<ChunkBehavior>,
+    deferred_chunks: Vec<u64>, // The indices of the chunks waiting to commit out of order
+    held_chunks: Vec<u64>,     // The indices of the chunks held until they're released
+    in_flight_chunks: BTreeMap<u64, SubmittedChunk>, // The uncommitted chunks (keyed by submission index)
+    num_executor_resets: u64,
+    num_stream_resets: u64,
+    shutdown: bool,
+    submitted_chunks: Vec<SubmittedChunk>,
+}
+
+impl ScriptedStorageSynchronizer {
+    /// Creates a new scripted storage synchronizer (with the given behaviors)
+    /// that has committed up to the given version and epoch. Returns the
+    /// synchronizer, and the listeners for committed transactions and errors.
+    pub fn new(
+        behaviors: Vec<ChunkBehavior>,
+        committed_version: Version,
+        committed_epoch: u64,
+        time_service: TimeService,
+    ) -> (
+        Self,
+        mpsc::UnboundedReceiver<CommittedTransactions>,
+        ErrorNotificationListener,
+    ) {
+        let (committed_transactions_sender, committed_transactions_listener) = mpsc::unbounded();
+        let (error_notification_sender, error_notification_listener) =
+            ErrorNotificationListener::new();
+        let state = ScriptedState {
+            behaviors: behaviors.into(),
+            ..Default::default()
+        };
+        let storage_synchronizer = Self {
+            committed_transactions_sender,
+            committed_version_handle: CommittedVersionHandle::new(
+                committed_version,
+                committed_epoch,
+            ),
+            error_notification_sender,
+            state: Arc::new(Mutex::new(state)),
+            time_service,
+        };
+        (
+            storage_synchronizer,
+            committed_transactions_listener,
+            error_notification_listener,
+        )
+    }
+
+    /// Appends the given behaviors to the script
+    pub fn add_behaviors(&self, behaviors: Vec<ChunkBehavior>) {
+        self.state.lock().behaviors.extend(behaviors);
+    }
+
+    /// Verifies that every submitted chunk starts exactly one version past
+    /// the last version of the previously submitted chunk.
+    pub fn assert_chunks_contiguous(&self) {
+        let submitted_chunks = self.get_submitted_chunks();
+        for chunks in submitted_chunks.windows(2) {
+            assert_eq!(
+                chunks[1].first_version,
+                chunks[0].last_version() + 1,
+                "Submitted chunks are not contiguous! Previous chunk: {:?}, next chunk: {:?}",
+                chunks[0],
+                chunks[1]
+            );
+        }
+    }
+
+    /// Returns a future that resolves once the given version has been
+    /// committed (see `CommittedVersionHandle::await_version_committed`).
+    pub fn await_commit(&self, version: Version) -> impl Future<Output = Result<(), Error>> {
+        self.committed_version_handle
+            .await_version_committed(version)
+    }
+
+    /// Returns the number of times the chunk executor was reset
+    pub fn get_num_executor_resets(&self) -> u64 {
+        self.state.lock().num_executor_resets
+    }
+
+    /// Returns all submitted chunks (in submission order)
+    pub fn get_submitted_chunks(&self) -> Vec<SubmittedChunk> {
+        self.state.lock().submitted_chunks.clone()
+    }
+
+    /// Returns the (inclusive) version ranges of all submitted chunks
+    pub fn get_submitted_versions(&self) -> Vec<(Version, Version)> {
+        self.get_submitted_chunks()
+            .iter()
+            .map(|submitted_chunk| {
+                (
+                    submitted_chunk.first_version,
+                    submitted_chunk.last_version(),
+                )
+            })
+            .collect()
+    }
+
+    /// Commits all held chunks (in submission order). Returns the number of
+    /// chunks committed.
+    pub fn release_held_chunks(&self) -> usize {
+        let held_chunks = std::mem::take(&mut self.state.lock().held_chunks);
+        held_chunks
+            .into_iter()
+            .filter(|chunk_index| self.commit_in_flight_chunk(*chunk_index))
+            .count()
+    }
+
+    /// Commits the in-flight chunk with the given index (if it's still in
+    /// flight). Returns true iff the chunk was committed.
+    fn commit_in_flight_chunk(&self, chunk_index: u64) -> bool {
+        let submitted_chunk = self.state.lock().in_flight_chunks.remove(&chunk_index);
+        match submitted_chunk {
+            Some(submitted_chunk) => {
+                self.commit_chunk(submitted_chunk, 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Commits the given chunk and notifies the commit (the specified number
+    /// of times). Any chunks deferred to commit out of order are then committed.
+    fn commit_chunk(&self, submitted_chunk: SubmittedChunk, num_notifications: u64) {
+        // Update the committed version (and epoch)
+        let committed_epoch = if submitted_chunk.end_of_epoch {
+            self.committed_version_handle.get_committed_epoch() + 1
+        } else {
+            self.committed_version_handle.get_committed_epoch()
+        };
+        self.committed_version_handle
+            .update(submitted_chunk.last_version(), committed_epoch);
+
+        // Notify the commit
+        let num_stream_resets = self.state.lock().num_stream_resets;
+        let mut committed_transactions = CommittedTransactions::new(
+            submitted_chunk.events.clone(),
+            submitted_chunk.transactions.clone(),
+            submitted_chunk.first_version,
+        );
+        committed_transactions.post_reset = submitted_chunk.num_stream_resets < num_stream_resets;
+        for _ in 0..num_notifications {
+            let _ = self
+                .committed_transactions_sender
+                .unbounded_send(committed_transactions.clone());
+        }
+
+        // Commit any deferred chunks (now that a later chunk has committed)
+        let deferred_chunks = std::mem::take(&mut self.state.lock().deferred_chunks);
+        for chunk_index in deferred_chunks {
+            self.commit_in_flight_chunk(chunk_index);
+        }
+    }
+
+    /// Fails the given chunk and notifies the error
+    fn fail_chunk(&self, submitted_chunk: SubmittedChunk, severity: ErrorSeverity) {
+        let error_notification = ErrorNotification {
+            component: submitted_chunk.error_source,
+            error: Error::UnexpectedError("The scripted chunk failed!".into()),
+            expected_version: None,
+            failed_operation: FailedOperation::ExecuteChunk,
+            notification_id: submitted_chunk.notification_id,
+            severity,
+            stage: StorageSynchronizerStage::Executor,
+            version_range: Some((
+                submitted_chunk.first_version,
+                submitted_chunk.last_version(),
+            )),
+        };
+        let _ = self
+            .error_notification_sender
+            .unbounded_send(error_notification);
+    }
+}
+
+impl StorageSynchronizerInterface for ScriptedStorageSynchronizer {
+    fn initialize_state_synchronizer(
+        &mut self,
+        _epoch_change_proofs: Vec<LedgerInfoWithSignatures>,
+        _target_ledger_info: LedgerInfoWithSignatures,
+        _target_output_with_proof: TransactionOutputListWithProof,
+    ) -> Result<JoinHandle<()>, Error> {
+        Err(Error::UnexpectedError(
+            "State snapshots are not supported by the scripted storage synchronizer!".into(),
+        ))
+    }
+
+    fn finalize_pending_state_snapshot(&mut self) -> Result<bool, Error> {
+        Ok(false)
+    }
+
+    fn notify_stream_reset(&self) {
+        self.state.lock().num_stream_resets += 1;
+    }
+
+    fn pending_storage_data(&self) -> PendingDataSummary {
+        let state = self.state.lock();
+        let chunks_in_flight = state.in_flight_chunks.len() as u64;
+        PendingDataSummary {
+            chunks_executing: chunks_in_flight,
+            chunks_in_flight,
+            highest_version_in_flight: state
+                .in_flight_chunks
+                .values()
+                .map(|submitted_chunk| submitted_chunk.last_version())
+                .max(),
+            ..Default::default()
+        }
+    }
+
+    fn committed_version_handle(&self) -> CommittedVersionHandle {
+        self.committed_version_handle.clone()
+    }
+
+    fn reset_pending_data(&self) {
+        // Discard all in-flight chunks submitted before the latest stream reset
+        {
+            let mut state = self.state.lock();
+            let num_stream_resets = state.num_stream_resets;
+            state.in_flight_chunks.retain(|_, submitted_chunk| {
+                submitted_chunk.num_stream_resets == num_stream_resets
+            });
+        }
+
+        // Cancel all version waiters (the versions might never be committed)
+        self.committed_version_handle
+            .cancel_version_waiters("the pending data was reset");
+    }
+
+    fn shutdown(&self) -> ShutdownSummary {
+        // Stop accepting new chunks
+        let in_flight_chunks = {
+            let mut state = self.state.lock();
+            state.shutdown = true;
+            state.deferred_chunks.clear();
+            state.held_chunks.clear();
+            std::mem::take(&mut state.in_flight_chunks)
+        };
+
+        // Commit all in-flight chunks
+        let chunks_committed = in_flight_chunks.len() as u64;
+        for submitted_chunk in in_flight_chunks.into_values() {
+            self.commit_chunk(submitted_chunk, 1);
+        }
+        ShutdownSummary {
+            chunks_committed,
+            chunks_discarded: 0,
+            drained: true,
+        }
+    }
+
+    fn save_state_values_with_proof(
+        &mut self,
+        _notification_id: NotificationId,
+        _state_value_chunk_with_proof: StateValueChunkWithProof,
+        _verify: bool,
+    ) -> Result<(), Error> {
+        Err(Error::UnexpectedError(
+            "State snapshots are not supported by the scripted storage synchronizer!".into(),
+        ))
+    }
+
+    fn verifies_state_value_chunks(&self) -> bool {
+        false
+    }
+
+    fn submit_transaction_chunk(
+        &mut self,
+        notification_id: NotificationId,
+        error_source: ErrorSource,
+        apply_or_execute: ApplyOrExecute,
+        chunk_payload: TransactionChunkPayload,
+        _target_ledger_info: LedgerInfoWithSignatures,
+        end_of_epoch_ledger_info: Option<LedgerInfoWithSignatures>,
+        _ledger_info_verifier: Option<EpochState>,
+    ) -> Result<(), Error> {
+        // Extract the transactions and events from the payload
+        let first_version = chunk_payload.first_version().ok_or_else(|| {
+            Error::InvalidPayload("The submitted chunk has no first version!".into())
+        })?;
+        let (transactions, events) = match chunk_payload {
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof) => {
+                let (transactions, outputs): (Vec<_>, Vec<_>) = output_list_with_proof
+                    .transactions_and_outputs
+                    .into_iter()
+                    .unzip();
+                let events = outputs
+                    .iter()
+                    .flat_map(|output| output.events().to_vec())
+                    .collect();
+                (transactions, events)
+            }
+            TransactionChunkPayload::Transactions(transaction_list_with_proof) => {
+                let events = transaction_list_with_proof
+                    .events
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                (transaction_list_with_proof.transactions, events)
+            }
+        };
+
+        // Record the chunk and fetch its scripted behavior
+        let (chunk_index, submitted_chunk, behavior) = {
+            let mut state = self.state.lock();
+            if state.shutdown {
+                return Err(Error::ShuttingDown(
+                    "New data chunks are no longer accepted!".into(),
+                ));
+            }
+            let submitted_chunk = SubmittedChunk {
+                apply_or_execute,
+                end_of_epoch: end_of_epoch_ledger_info.is_some(),
+                error_source,
+                events,
+                first_version,
+                notification_id,
+                num_stream_resets: state.num_stream_resets,
+                transactions,
+            };
+            state.submitted_chunks.push(submitted_chunk.clone());
+            let chunk_index = state.submitted_chunks.len() as u64;
+            let behavior = state.behaviors.pop_front().unwrap_or(ChunkBehavior::Commit);
+            (chunk_index, submitted_chunk, behavior)
+        };
+
+        // Commit (or fail) the chunk according to the script
+        match behavior {
+            ChunkBehavior::Commit => self.commit_chunk(submitted_chunk, 1),
+            ChunkBehavior::CommitAfterDelay(delay) => {
+                self.state
+                    .lock()
+                    .in_flight_chunks
+                    .insert(chunk_index, submitted_chunk);
+
+                // Note: the sleep is registered before the task is spawned
+                // (so that the delay starts from the time of submission).
+                let sleep = self.time_service.sleep(delay);
+                let storage_synchronizer = self.clone();
+                tokio::spawn(async move {
+                    sleep.await;
+                    storage_synchronizer.commit_in_flight_chunk(chunk_index);
+                });
+            }
+            ChunkBehavior::CommitOutOfOrder => {
+                let mut state = self.state.lock();
+                state.in_flight_chunks.insert(chunk_index, submitted_chunk);
+                state.deferred_chunks.push(chunk_index);
+            }
+            ChunkBehavior::CommitTwice => self.commit_chunk(submitted_chunk, 2),
+            ChunkBehavior::CommitWithTransientError => {
+                self.commit_chunk(submitted_chunk.clone(), 1);
+                self.fail_chunk(submitted_chunk, ErrorSeverity::Transient);
+            }
+            ChunkBehavior::Fail(severity) => self.fail_chunk(submitted_chunk, severity),
+            ChunkBehavior::Hold => {
+                let mut state = self.state.lock();
+                state.in_flight_chunks.insert(chunk_index, submitted_chunk);
+                state.held_chunks.push(chunk_index);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn reset_chunk_executor(&self) -> Result<(), Error> {
+        self.state.lock().num_executor_resets += 1;
+        Ok(())
+    }
+
+    fn finish_chunk_executor(&self) {}
+}
+
+#[tokio::test]
+async fn test_scripted_commit() {
+    // Create a scripted storage synchronizer that commits immediately
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::Commit]);
+
+    // Submit two chunks (the second uses the default behavior)
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 3);
+    submit_transaction_chunk(&mut storage_synchronizer, 4, 2);
+
+    // Verify both chunks were committed (in order)
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 3), (4, 5)]);
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        5
+    );
+    assert!(storage_synchronizer.is_idle());
+    assert_ok!(storage_synchronizer.await_commit(5).now_or_never().unwrap());
+
+    // Verify the chunks were recorded
+    assert_eq!(
+        storage_synchronizer.get_submitted_versions(),
+        vec![(1, 3), (4, 5)]
+    );
+    storage_synchronizer.assert_chunks_contiguous();
+}
+
+#[tokio::test]
+async fn test_scripted_commit_after_delay() {
+    // Create a scripted storage synchronizer that delays the commit
+    let time_service = TimeService::mock();
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        ScriptedStorageSynchronizer::new(
+            vec![ChunkBehavior::CommitAfterDelay(Duration::from_secs(5))],
+            0,
+            0,
+            time_service.clone(),
+        );
+    let mock_time_service = time_service.into_mock();
+
+    // Submit a chunk and verify it's still in flight
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 10);
+    let commit_waiter = storage_synchronizer.await_commit(10);
+    let pending_data = storage_synchronizer.pending_storage_data();
+    assert_eq!(pending_data.chunks_in_flight, 1);
+    assert_eq!(pending_data.highest_version_in_flight, Some(10));
+
+    // Advance the time (but not past the delay) and verify nothing was committed
+    mock_time_service
+        .advance_async(Duration::from_secs(4))
+        .await;
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+    assert!(!storage_synchronizer.is_idle());
+
+    // Advance past the delay and verify the chunk was committed
+    mock_time_service
+        .advance_async(Duration::from_secs(1))
+        .await;
+    assert_ok!(commit_waiter.await);
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 10)]);
+    assert!(storage_synchronizer.is_idle());
+}
+
+#[tokio::test]
+async fn test_scripted_commit_out_of_order() {
+    // Create a scripted storage synchronizer that commits the first chunk out of order
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::CommitOutOfOrder]);
+
+    // Submit the first chunk and verify it's not committed
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 5);
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+    assert_eq!(
+        storage_synchronizer.pending_storage_data().chunks_in_flight,
+        1
+    );
+
+    // Submit the second chunk and verify both chunks are committed out of order
+    submit_transaction_chunk(&mut storage_synchronizer, 6, 5);
+    verify_committed_versions(&mut committed_transactions_listener, &[(6, 10), (1, 5)]);
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        10
+    );
+    assert!(storage_synchronizer.is_idle());
+}
+
+#[tokio::test]
+async fn test_scripted_commit_twice() {
+    // Create a scripted storage synchronizer that duplicates the commit notification
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::CommitTwice]);
+
+    // Submit a chunk and verify the commit was notified twice
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 2);
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 2), (1, 2)]);
+    assert_eq!(storage_synchronizer.get_submitted_chunks().len(), 1);
+}
+
+#[tokio::test]
+async fn test_scripted_commit_with_transient_error() {
+    // Create a scripted storage synchronizer that commits with a transient error
+    let (mut storage_synchronizer, mut committed_transactions_listener, mut error_listener) =
+        create_storage_synchronizer(vec![ChunkBehavior::CommitWithTransientError]);
+
+    // Submit a chunk and verify it was committed
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 3);
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 3)]);
+    assert!(storage_synchronizer.is_idle());
+
+    // Verify the transient error was notified
+    let error_notification = error_listener.next().await.unwrap();
+    assert_eq!(error_notification.severity, ErrorSeverity::Transient);
+    assert_eq!(error_notification.version_range, Some((1, 3)));
+}
+
+#[tokio::test]
+async fn test_scripted_failure() {
+    // Create a scripted storage synchronizer that fails the first chunk
+    let (mut storage_synchronizer, mut committed_transactions_listener, mut error_listener) =
+        create_storage_synchronizer(vec![ChunkBehavior::Fail(ErrorSeverity::Recoverable)]);
+
+    // Submit a chunk and verify the error notification
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 4);
+    let error_notification = error_listener.next().await.unwrap();
+    assert_eq!(error_notification.component, ErrorSource::ContinuousSyncer);
+    assert_eq!(error_notification.notification_id, 1);
+    assert_eq!(error_notification.severity, ErrorSeverity::Recoverable);
+    assert_eq!(error_notification.version_range, Some((1, 4)));
+
+    // Verify nothing was committed
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        0
+    );
+    assert!(storage_synchronizer.is_idle());
+}
+
+#[tokio::test]
+async fn test_scripted_hold_and_release() {
+    // Create a scripted storage synchronizer that holds two chunks
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::Hold, ChunkBehavior::Hold]);
+
+    // Submit the chunks and verify they're held
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 1);
+    submit_transaction_chunk(&mut storage_synchronizer, 2, 1);
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+    assert_eq!(
+        storage_synchronizer.pending_storage_data().chunks_in_flight,
+        2
+    );
+
+    // Release the chunks and verify they're committed (in order)
+    assert_eq!(storage_synchronizer.release_held_chunks(), 2);
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 1), (2, 2)]);
+    assert!(storage_synchronizer.is_idle());
+    assert_eq!(storage_synchronizer.release_held_chunks(), 0);
+}
+
+#[tokio::test]
+async fn test_scripted_reset_pending_data() {
+    // Create a scripted storage synchronizer that holds all chunks
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::Hold; 3]);
+
+    // Submit two chunks, reset the stream and submit another chunk
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 1);
+    submit_transaction_chunk(&mut storage_synchronizer, 2, 1);
+    let commit_waiter = storage_synchronizer.await_commit(2);
+    storage_synchronizer.notify_stream_reset();
+    submit_transaction_chunk(&mut storage_synchronizer, 3, 1);
+
+    // Reset the pending data and verify only the pre-reset chunks were discarded
+    storage_synchronizer.reset_pending_data();
+    assert_matches!(commit_waiter.await, Err(Error::VersionWaitCancelled(2, _)));
+    let pending_data = storage_synchronizer.pending_storage_data();
+    assert_eq!(pending_data.chunks_in_flight, 1);
+    assert_eq!(pending_data.highest_version_in_flight, Some(3));
+
+    // Release the held chunks and verify only the post-reset chunk is committed
+    assert_eq!(storage_synchronizer.release_held_chunks(), 1);
+    let committed_transactions = committed_transactions_listener.next().await.unwrap();
+    assert_eq!(committed_transactions.first_version, 3);
+    assert!(!committed_transactions.post_reset);
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+}
+
+#[tokio::test]
+async fn test_scripted_post_reset_commit() {
+    // Create a scripted storage synchronizer that holds the first chunk
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::Hold]);
+
+    // Submit a chunk, reset the stream and release the chunk
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 1);
+    storage_synchronizer.notify_stream_reset();
+    storage_synchronizer.release_held_chunks();
+
+    // Verify the commit is marked as post-reset
+    let committed_transactions = committed_transactions_listener.next().await.unwrap();
+    assert!(committed_transactions.post_reset);
+}
+
+#[tokio::test]
+async fn test_scripted_shutdown() {
+    // Create a scripted storage synchronizer that holds the first chunk
+    let (mut storage_synchronizer, mut committed_transactions_listener, _) =
+        create_storage_synchronizer(vec![ChunkBehavior::Hold]);
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 5);
+
+    // Shut down the synchronizer and verify the held chunk was drained
+    let shutdown_summary = storage_synchronizer.shutdown();
+    assert_eq!(
+        shutdown_summary,
+        ShutdownSummary {
+            chunks_committed: 1,
+            chunks_discarded: 0,
+            drained: true,
+        }
+    );
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 5)]);
+
+    // Verify new chunks are rejected
+    let result = storage_synchronizer.submit_transaction_chunk(
+        6,
+        ErrorSource::ContinuousSyncer,
+        ApplyOrExecute::Execute,
+        TransactionChunkPayload::Transactions(create_transaction_chunk(6, 1)),
+        create_epoch_ending_ledger_info(),
+        None,
+        None,
+    );
+    assert_matches!(result, Err(Error::ShuttingDown(_)));
+    assert_eq!(storage_synchronizer.get_submitted_chunks().len(), 1);
+}
+
+#[tokio::test]
+async fn test_scripted_behaviors_added() {
+    // Create a scripted storage synchronizer with an empty script
+    let (mut storage_synchronizer, mut committed_transactions_listener, mut error_listener) =
+        create_storage_synchronizer(vec![]);
+
+    // Submit a chunk and verify it's committed (by default)
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 1);
+    verify_committed_versions(&mut committed_transactions_listener, &[(1, 1)]);
+
+    // Add a failure to the script, reset the executor and submit another chunk
+    storage_synchronizer.add_behaviors(vec![ChunkBehavior::Fail(ErrorSeverity::Fatal)]);
+    storage_synchronizer.reset_chunk_executor().unwrap();
+    submit_transaction_chunk(&mut storage_synchronizer, 2, 1);
+
+    // Verify the chunk failed and the executor reset was recorded
+    let error_notification = error_listener.next().await.unwrap();
+    assert_eq!(error_notification.severity, ErrorSeverity::Fatal);
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+    assert_eq!(storage_synchronizer.get_num_executor_resets(), 1);
+}
+
+#[tokio::test]
+async fn test_scripted_end_of_epoch() {
+    // Create a scripted storage synchronizer
+    let (mut storage_synchronizer, _, _) = create_storage_synchronizer(vec![]);
+
+    // Submit a chunk that ends the epoch and verify the committed epoch
+    storage_synchronizer
+        .submit_transaction_chunk(
+            1,
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1, 1)),
+            create_epoch_ending_ledger_info(),
+            Some(create_epoch_ending_ledger_info()),
+            None,
+        )
+        .unwrap();
+    let committed_version_handle = storage_synchronizer.committed_version_handle();
+    assert_eq!(committed_version_handle.get_committed_version(), 1);
+    assert_eq!(committed_version_handle.get_committed_epoch(), 1);
+
+    // Verify the chunk was recorded
+    let submitted_chunk = assert_some!(storage_synchronizer.get_submitted_chunks().pop());
+    assert_eq!(submitted_chunk.apply_or_execute, ApplyOrExecute::Apply);
+    assert!(submitted_chunk.end_of_epoch);
+    assert_eq!(submitted_chunk.error_source, ErrorSource::Bootstrapper);
+}
+
+#[tokio::test]
+async fn test_assert_chunks_contiguous() {
+    // Submit contiguous chunks and verify the assertion passes
+    let (mut storage_synchronizer, _, _) = create_storage_synchronizer(vec![]);
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 2);
+    submit_transaction_chunk(&mut storage_synchronizer, 3, 4);
+    storage_synchronizer.assert_chunks_contiguous();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Submitted chunks are not contiguous!")]
+async fn test_assert_chunks_contiguous_gap() {
+    // Submit gapped chunks and verify the assertion fails
+    let (mut storage_synchronizer, _, _) = create_storage_synchronizer(vec![]);
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 2);
+    submit_transaction_chunk(&mut storage_synchronizer, 8, 1);
+    storage_synchronizer.assert_chunks_contiguous();
+}
+
+#[tokio::test]
+#[should_panic(expected = "Submitted chunks are not contiguous!")]
+async fn test_assert_chunks_contiguous_overlap() {
+    // Submit overlapping chunks and verify the assertion fails
+    let (mut storage_synchronizer, _, _) = create_storage_synchronizer(vec![]);
+    submit_transaction_chunk(&mut storage_synchronizer, 1, 5);
+    submit_transaction_chunk(&mut storage_synchronizer, 5, 1);
+    storage_synchronizer.assert_chunks_contiguous();
+}
+
+/// Creates a scripted storage synchronizer (with the given behaviors) that
+/// has committed the genesis version.
+fn create_storage_synchronizer(
+    behaviors: Vec<ChunkBehavior>,
+) -> (
+    ScriptedStorageSynchronizer,
+    mpsc::UnboundedReceiver<CommittedTransactions>,
+    ErrorNotificationListener,
+) {
+    ScriptedStorageSynchronizer::new(behaviors, 0, 0, TimeService::mock())
+}
+
+/// Creates a transaction chunk with the given number of transactions
+/// (starting at the specified version).
+fn create_transaction_chunk(
+    first_version: Version,
+    num_transactions: u64,
+) -> TransactionListWithProof {
+    let transactions = (0..num_transactions)
+        .map(|_| create_transaction())
+        .collect();
+    TransactionListWithProof::new(
+        transactions,
+        None,
+        Some(first_version),
+        create_transaction_info_list_with_proof(),
+    )
+}
+
+/// Submits a transaction chunk (for execution by the continuous syncer)
+/// using the first version as the notification ID.
+fn submit_transaction_chunk(
+    storage_synchronizer: &mut ScriptedStorageSynchronizer,
+    first_version: Version,
+    num_transactions: u64,
+) {
+    storage_synchronizer
+        .submit_transaction_chunk(
+            first_version,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(
+                first_version,
+                num_transactions,
+            )),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+}
+
+/// Verifies the next commit notifications cover the given version ranges (in order)
+fn verify_committed_versions(
+    committed_transactions_listener: &mut mpsc::UnboundedReceiver<CommittedTransactions>,
+    expected_versions: &[(Version, Version)],
+) {
+    for (first_version, last_version) in expected_versions {
+        let committed_transactions =
+            assert_some!(committed_transactions_listener.try_next().ok().flatten());
+        assert_eq!(committed_transactions.first_version, *first_version);
+        assert_eq!(committed_transactions.last_version, *last_version);
+    }
+    assert_none!(committed_transactions_listener.try_next().ok().flatten());
+}
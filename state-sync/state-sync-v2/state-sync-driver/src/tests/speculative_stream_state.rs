@@ -0,0 +1,367 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    tests::utils::{
+        create_epoch_state, create_ledger_info_at_version, create_signed_ledger_info,
+        create_validator_signers,
+    },
+    utils::SpeculativeStreamState,
+};
+use aptos_crypto::HashValue;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    epoch_state::EpochState,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+    validator_verifier::{generate_validator_verifier, ValidatorVerifier},
+};
+use claim::assert_matches;
+
+#[test]
+fn test_new_state() {
+    // Create a speculative stream state with a proof ledger info
+    let proof_ledger_info = create_ledger_info_at_version(500);
+    let speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(3), Some(proof_ledger_info.clone()), 100);
+
+    // Verify the initial state
+    assert_eq!(speculative_stream_state.get_epoch_state().epoch, 3);
+    assert_eq!(speculative_stream_state.get_synced_version(), 100);
+    assert_eq!(
+        speculative_stream_state.expected_next_version().unwrap(),
+        101
+    );
+    assert_eq!(
+        speculative_stream_state.get_proof_ledger_info(),
+        proof_ledger_info
+    );
+    assert!(speculative_stream_state
+        .get_highest_verified_ledger_info()
+        .is_none());
+}
+
+#[test]
+#[should_panic(expected = "Proof ledger info is missing!")]
+fn test_missing_proof_ledger_info() {
+    // Create a speculative stream state without a proof ledger info
+    let speculative_stream_state = SpeculativeStreamState::new(create_epoch_state(0), None, 0);
+
+    // Fetching the proof ledger info should panic
+    speculative_stream_state.get_proof_ledger_info();
+}
+
+#[test]
+fn test_expected_next_version_overflow() {
+    // Create a speculative stream state at the max version
+    let speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(0), None, Version::MAX);
+
+    // Verify the expected next version overflows
+    let error = speculative_stream_state
+        .expected_next_version()
+        .unwrap_err();
+    assert_matches!(error, Error::IntegerOverflow(_));
+}
+
+#[test]
+fn test_update_with_payloads() {
+    // Create a speculative stream state
+    let mut speculative_stream_state = SpeculativeStreamState::new(create_epoch_state(1), None, 99);
+
+    // Process several payloads in order and verify the synced version
+    for (payload_start_version, num_items, expected_synced_version) in
+        [(100, 10, 109), (110, 1, 110), (111, 89, 199)]
+    {
+        let synced_version = speculative_stream_state
+            .update_with_payload(payload_start_version, num_items, None)
+            .unwrap();
+        assert_eq!(synced_version, expected_synced_version);
+        assert_eq!(
+            speculative_stream_state.get_synced_version(),
+            expected_synced_version
+        );
+        assert_eq!(
+            speculative_stream_state.expected_next_version().unwrap(),
+            expected_synced_version + 1
+        );
+    }
+
+    // Verify the epoch state hasn't changed
+    assert_eq!(speculative_stream_state.get_epoch_state().epoch, 1);
+}
+
+#[test]
+fn test_update_with_out_of_order_payloads() {
+    // Create a speculative stream state
+    let mut speculative_stream_state = SpeculativeStreamState::new(create_epoch_state(1), None, 99);
+    speculative_stream_state
+        .update_with_payload(100, 50, None)
+        .unwrap();
+
+    // Verify that stale, repeated and future payloads are all rejected
+    for payload_start_version in [0, 99, 100, 149, 151, 1000] {
+        let error = speculative_stream_state
+            .update_with_payload(payload_start_version, 10, None)
+            .unwrap_err();
+        assert_matches!(error, Error::VerificationError(_));
+    }
+
+    // Verify the rejected payloads didn't change the state
+    assert_eq!(speculative_stream_state.get_synced_version(), 149);
+
+    // Verify the next payload is still accepted
+    speculative_stream_state
+        .update_with_payload(150, 10, None)
+        .unwrap();
+    assert_eq!(speculative_stream_state.get_synced_version(), 159);
+}
+
+#[test]
+fn test_update_with_payload_overflow() {
+    // Create a speculative stream state just below the max version
+    let mut speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(1), None, Version::MAX - 10);
+
+    // Verify a payload that overflows the synced version is rejected
+    let error = speculative_stream_state
+        .update_with_payload(Version::MAX - 9, 100, None)
+        .unwrap_err();
+    assert_matches!(error, Error::IntegerOverflow(_));
+    assert_eq!(
+        speculative_stream_state.get_synced_version(),
+        Version::MAX - 10
+    );
+}
+
+#[test]
+fn test_update_with_payload_epoch_bump() {
+    // Create a speculative stream state
+    let current_epoch = 5;
+    let mut speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(current_epoch), None, 99);
+
+    // Process a payload with a proof that ends the epoch beyond the payload
+    // and verify the epoch state doesn't change.
+    let epoch_ending_ledger_info =
+        create_epoch_ending_ledger_info(current_epoch, 199, create_epoch_state(current_epoch + 1));
+    speculative_stream_state
+        .update_with_payload(100, 50, Some(epoch_ending_ledger_info.clone()))
+        .unwrap();
+    assert_eq!(
+        speculative_stream_state.get_epoch_state().epoch,
+        current_epoch
+    );
+
+    // Process a payload with a proof that doesn't end the epoch and verify
+    // the epoch state doesn't change.
+    speculative_stream_state
+        .update_with_payload(150, 10, Some(create_ledger_info_at_version(159)))
+        .unwrap();
+    assert_eq!(
+        speculative_stream_state.get_epoch_state().epoch,
+        current_epoch
+    );
+
+    // Process the payload that reaches the end of the epoch and verify the
+    // epoch state moves to the next epoch.
+    speculative_stream_state
+        .update_with_payload(160, 40, Some(epoch_ending_ledger_info))
+        .unwrap();
+    assert_eq!(
+        speculative_stream_state.get_epoch_state().epoch,
+        current_epoch + 1
+    );
+    assert_eq!(speculative_stream_state.get_synced_version(), 199);
+}
+
+#[test]
+fn test_update_with_payload_stale_epoch() {
+    // Create a speculative stream state
+    let current_epoch = 5;
+    let mut speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(current_epoch), None, 99);
+
+    // Process a payload with a proof that "ends" the epoch with a stale epoch
+    // state and verify the update is rejected.
+    let epoch_ending_ledger_info =
+        create_epoch_ending_ledger_info(current_epoch, 109, create_epoch_state(current_epoch));
+    let error = speculative_stream_state
+        .update_with_payload(100, 10, Some(epoch_ending_ledger_info))
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+    assert_eq!(
+        speculative_stream_state.get_epoch_state().epoch,
+        current_epoch
+    );
+    assert_eq!(speculative_stream_state.get_synced_version(), 99);
+}
+
+#[test]
+fn test_update_epoch_state() {
+    // Create a speculative stream state
+    let current_epoch = 10;
+    let mut speculative_stream_state =
+        SpeculativeStreamState::new(create_epoch_state(current_epoch), None, 0);
+
+    // Verify that the same and older epoch states are rejected
+    for epoch in [0, current_epoch - 1, current_epoch] {
+        let error = speculative_stream_state
+            .update_epoch_state(create_epoch_state(epoch))
+            .unwrap_err();
+        assert_matches!(error, Error::VerificationError(_));
+        assert_eq!(
+            speculative_stream_state.get_epoch_state().epoch,
+            current_epoch
+        );
+    }
+
+    // Verify that higher epoch states are accepted
+    for epoch in [current_epoch + 1, current_epoch + 5] {
+        speculative_stream_state
+            .update_epoch_state(create_epoch_state(epoch))
+            .unwrap();
+        assert_eq!(speculative_stream_state.get_epoch_state().epoch, epoch);
+    }
+}
+
+#[test]
+fn test_verify_ledger_infos() {
+    // Create a speculative stream state for the current validator set
+    let current_epoch = 7;
+    let validator_signers = create_validator_signers(0);
+    let epoch_state = EpochState {
+        epoch: current_epoch,
+        verifier: generate_validator_verifier(&validator_signers),
+    };
+    let mut speculative_stream_state = SpeculativeStreamState::new(epoch_state, None, 0);
+
+    // Verify a ledger info signed by the current validator set is accepted
+    let ledger_info = create_signed_ledger_info(&validator_signers, 500, current_epoch);
+    speculative_stream_state
+        .verify_ledger_info_with_signatures(&ledger_info)
+        .unwrap();
+    assert_eq!(
+        speculative_stream_state.get_highest_verified_ledger_info(),
+        Some(ledger_info.clone())
+    );
+
+    // Verify a lower ledger info is accepted, but isn't the highest
+    let lower_ledger_info = create_signed_ledger_info(&validator_signers, 100, current_epoch);
+    speculative_stream_state
+        .verify_ledger_info_with_signatures(&lower_ledger_info)
+        .unwrap();
+    assert_eq!(
+        speculative_stream_state.get_highest_verified_ledger_info(),
+        Some(ledger_info.clone())
+    );
+
+    // Verify forged and wrong epoch ledger infos are rejected
+    let forged_ledger_info =
+        create_signed_ledger_info(&create_validator_signers(100), 1000, current_epoch);
+    let wrong_epoch_ledger_info =
+        create_signed_ledger_info(&validator_signers, 1000, current_epoch + 1);
+    for invalid_ledger_info in [forged_ledger_info, wrong_epoch_ledger_info] {
+        let error = speculative_stream_state
+            .verify_ledger_info_with_signatures(&invalid_ledger_info)
+            .unwrap_err();
+        assert_matches!(error, Error::VerificationError(_));
+    }
+    assert_eq!(
+        speculative_stream_state.get_highest_verified_ledger_info(),
+        Some(ledger_info)
+    );
+}
+
+#[test]
+fn test_verify_epoch_chase() {
+    // Create a speculative stream state for the current epoch
+    let current_epoch = 3;
+    let epoch_state = EpochState {
+        epoch: current_epoch,
+        verifier: ValidatorVerifier::new(vec![]),
+    };
+    let mut speculative_stream_state = SpeculativeStreamState::new(epoch_state, None, 0);
+
+    // Verify a chain of epoch ending ledger infos and move through the epochs
+    for (index, epoch) in (current_epoch..current_epoch + 3).enumerate() {
+        let next_epoch_state = EpochState {
+            epoch: epoch + 1,
+            verifier: ValidatorVerifier::new(vec![]),
+        };
+        let epoch_ending_ledger_info = create_epoch_ending_ledger_info(
+            epoch,
+            (index as u64 + 1) * 100,
+            next_epoch_state.clone(),
+        );
+        speculative_stream_state
+            .verify_ledger_info_with_signatures(&epoch_ending_ledger_info)
+            .unwrap();
+        speculative_stream_state
+            .update_epoch_state(next_epoch_state)
+            .unwrap();
+        assert_eq!(
+            speculative_stream_state.get_highest_verified_ledger_info(),
+            Some(epoch_ending_ledger_info)
+        );
+    }
+    assert_eq!(
+        speculative_stream_state.get_epoch_state().epoch,
+        current_epoch + 3
+    );
+
+    // Verify a ledger info from a previous epoch is now rejected
+    let stale_ledger_info =
+        create_epoch_ending_ledger_info(current_epoch, 100, create_epoch_state(current_epoch + 1));
+    let error = speculative_stream_state
+        .verify_ledger_info_with_signatures(&stale_ledger_info)
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+}
+
+#[test]
+fn test_reset_on_new_stream() {
+    // Create a speculative stream state and process several payloads
+    let mut speculative_stream_state = SpeculativeStreamState::new(create_epoch_state(1), None, 99);
+    speculative_stream_state
+        .update_with_payload(100, 100, None)
+        .unwrap();
+    speculative_stream_state
+        .update_epoch_state(create_epoch_state(2))
+        .unwrap();
+
+    // Reset the state (as done when a stream is terminated) by creating a
+    // new state from storage, and verify none of the old state remains.
+    let speculative_stream_state = SpeculativeStreamState::new(create_epoch_state(1), None, 149);
+    assert_eq!(speculative_stream_state.get_synced_version(), 149);
+    assert_eq!(
+        speculative_stream_state.expected_next_version().unwrap(),
+        150
+    );
+    assert_eq!(speculative_stream_state.get_epoch_state().epoch, 1);
+    assert!(speculative_stream_state
+        .get_highest_verified_ledger_info()
+        .is_none());
+}
+
+/// Creates an epoch ending ledger info for the given epoch and version
+/// that moves to the specified next epoch state.
+fn create_epoch_ending_ledger_info(
+    epoch: u64,
+    version: Version,
+    next_epoch_state: EpochState,
+) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::random(),
+        version,
+        0,
+        Some(next_epoch_state),
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
@@ -1,41 +1,85 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metadata_storage::PersistentMetadataStorage;
+use crate::metadata_storage::{
+    MetadataStorageInterface, PersistentMetadataStorage, StateSnapshotFinalization,
+};
 use crate::{
+    bootstrapper::GENESIS_TRANSACTION_VERSION,
+    dry_run_executor::DryRunChunkExecutor,
     error::Error,
+    metrics::{self, DriverMetrics},
     notification_handlers::{
-        CommitNotification, CommitNotificationListener, CommittedTransactions,
-        ErrorNotificationListener, MempoolNotificationHandler,
+        CommitNotification, CommitNotificationListener, CommitStateTracker,
+        CommitSubscriberRegistry, CommittedTransactions, ErrorNotification,
+        ErrorNotificationListener, ErrorSeverity, ErrorSource, EventNotificationHandler,
+        MempoolNotificationHandler, NotifiedVersionTracker,
+    },
+    storage_synchronizer::{
+        new_backpressure_channel, verify_concurrency_config, verify_state_value_chunk,
+        ApplyOrExecute, BackpressureListener, BackpressureNotifier, BackpressureState,
+        CommittedVersionHandle, FailedOperation, LedgerInfoVerifier, PendingDataSummary,
+        ShutdownSummary, StorageSynchronizer, StorageSynchronizerInterface,
+        StorageSynchronizerStage, TransactionChunkPayload, MAX_STAGE_CHANNEL_DEPTH,
+        MAX_STAGE_THREADS,
     },
-    storage_synchronizer::{StorageSynchronizer, StorageSynchronizerInterface},
+    test_utils::fake_storage::FakeStorage,
     tests::{
         mocks::{
-            create_mock_db_writer, create_mock_executor, create_mock_reader_writer,
-            create_mock_receiver, MockChunkExecutor,
+            create_mock_db_reader, create_mock_db_writer, create_mock_executor,
+            create_mock_reader_writer, create_mock_receiver, MockCancellableChunkExecutor,
+            MockChunkExecutor, MockDatabaseWriter, MockSnapshotReceiver,
         },
         utils::{
-            create_epoch_ending_ledger_info, create_event, create_output_list_with_proof,
-            create_state_value_chunk_with_proof, create_transaction,
-            create_transaction_list_with_proof, verify_mempool_and_event_notification,
+            create_empty_epoch_state, create_epoch_ending_ledger_info, create_event,
+            create_ledger_info_at_version, create_output_list_with_proof,
+            create_signed_ledger_info, create_state_value_chunk_with_proof, create_transaction,
+            create_transaction_info, create_transaction_info_list_with_proof,
+            create_transaction_list_with_proof, create_validator_signers,
+            verify_mempool_and_event_notification,
         },
     },
 };
 use anyhow::format_err;
 use aptos_config::config::StateSyncDriverConfig;
+use aptos_crypto::{
+    hash::{CryptoHash, EventAccumulatorHasher},
+    HashValue,
+};
 use aptos_infallible::{Mutex, RwLock};
-use aptos_types::transaction::{TransactionOutputListWithProof, Version};
+use aptos_metrics_core::HistogramVec;
+use aptos_temppath::TempPath;
+use aptos_time_service::TimeService;
+use aptos_types::transaction::{
+    ExecutionStatus, Transaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
+    TransactionOutputListWithProof, Version,
+};
 use aptos_types::{
-    ledger_info::LedgerInfoWithSignatures, on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::ON_CHAIN_CONFIG_REGISTRY,
+    proof::accumulator::InMemoryAccumulator,
+    state_store::state_value::StateValueChunkWithProof,
+    state_store::{state_key::StateKey, state_value::StateValue},
+    validator_signer::ValidatorSigner,
+    validator_verifier::generate_validator_verifier,
 };
-use claim::assert_matches;
+use claim::{assert_matches, assert_none};
 use data_streaming_service::data_notification::NotificationId;
 use event_notifications::EventSubscriptionService;
-use executor_types::ChunkCommitNotification;
-use futures::StreamExt;
+use executor_types::{ChunkCommitNotification, ChunkExecutorTrait};
+use futures::{FutureExt, StreamExt};
 use mempool_notifications::MempoolNotificationListener;
-use mockall::predicate::always;
-use std::{sync::Arc, time::Duration};
+use mockall::predicate::{always, eq};
+use once_cell::sync::Lazy;
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use storage_interface::DbReaderWriter;
 use tokio::task::JoinHandle;
 
@@ -72,11 +116,14 @@ async fn test_apply_transaction_outputs() {
 
     // Attempt to apply a chunk of outputs
     storage_synchronizer
-        .apply_transaction_outputs(
+        .submit_transaction_chunk(
             0,
-            create_output_list_with_proof(),
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(create_output_chunk(1)),
             create_epoch_ending_ledger_info(),
             None,
+            None,
         )
         .unwrap();
 
@@ -107,111 +154,446 @@ async fn test_apply_transaction_outputs_error() {
     // Attempt to apply a chunk of outputs
     let notification_id = 100;
     storage_synchronizer
-        .apply_transaction_outputs(
+        .submit_transaction_chunk(
             notification_id,
-            create_output_list_with_proof(),
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(create_output_chunk(1)),
             create_epoch_ending_ledger_info(),
             None,
+            None,
         )
         .unwrap();
 
-    // Verify we get an error notification and that there's no pending data
-    verify_error_notification(&mut error_listener, notification_id).await;
+    // Verify we get an error notification (from the executor) and that there's no pending data
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::Bootstrapper,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    assert_eq!(error_notification.stage, StorageSynchronizerStage::Executor);
+    assert_eq!(error_notification.version_range, Some((1, 1)));
     verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn test_commit_chunk_error() {
-    // Setup the mock executor
+async fn test_apply_transaction_outputs_event_root_hash_mismatch() {
+    // Create a valid output chunk and a chunk with a corrupted event
+    let valid_output_chunk = create_output_chunk_with_event_root_hashes(1);
+    let mut corrupted_output_chunk = create_output_chunk_with_event_root_hashes(2);
+    let (_, transaction_output) = &mut corrupted_output_chunk.transactions_and_outputs[0];
+    *transaction_output = TransactionOutput::new(
+        transaction_output.write_set().clone(),
+        vec![create_event(None)],
+        transaction_output.gas_used(),
+        transaction_output.status().clone(),
+    );
+
+    // Setup the mock executor (only the valid chunk should be applied)
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_execute_chunk()
+        .expect_apply_chunk()
         .with(always(), always(), always())
+        .times(1)
         .returning(|_, _, _| Ok(()));
     chunk_executor
         .expect_commit_chunk()
-        .return_once(|| Err(format_err!("Failed to commit chunk!")));
+        .return_once(|| Ok(create_commit_notification(&[])));
 
-    // Create the storage synchronizer
+    // Create the storage synchronizer (with event root hash verification enabled)
+    let driver_config = StateSyncDriverConfig {
+        verify_event_root_hashes: true,
+        ..Default::default()
+    };
     let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
-        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
 
-    // Attempt to execute a chunk of transactions
+    // Apply the valid chunk and verify it's committed
+    storage_synchronizer
+        .submit_transaction_chunk(
+            1,
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(valid_output_chunk),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+    assert_none!(error_listener.select_next_some().now_or_never());
+
+    // Attempt to apply the corrupted chunk
+    let num_event_root_hash_mismatches = read_event_root_hash_mismatches();
     let notification_id = 100;
     storage_synchronizer
-        .execute_transactions(
+        .submit_transaction_chunk(
             notification_id,
-            create_transaction_list_with_proof(),
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(corrupted_output_chunk),
             create_epoch_ending_ledger_info(),
             None,
+            None,
         )
         .unwrap();
 
-    // Verify we get an error notification and that there's no pending data
-    verify_error_notification(&mut error_listener, notification_id).await;
+    // Verify the chunk is rejected (as invalid payload data) before it's applied
+    let error_notification = error_listener.select_next_some().await;
+    assert_eq!(error_notification.notification_id, notification_id);
+    assert_eq!(error_notification.component, ErrorSource::Bootstrapper);
+    assert_eq!(error_notification.severity, ErrorSeverity::Recoverable);
+    assert_eq!(error_notification.stage, StorageSynchronizerStage::Executor);
+    assert_eq!(error_notification.version_range, Some((2, 2)));
+    assert_matches!(error_notification.error, Error::InvalidPayload(_));
+    assert!(read_event_root_hash_mismatches() > num_event_root_hash_mismatches);
     verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn test_execute_transactions() {
-    // Create test data
-    let transaction_to_commit = create_transaction();
-    let event_to_commit = create_event(None);
-
-    // Setup the mock executor
+async fn test_await_version_committed() {
+    // Setup the mock executor to apply and commit all chunks
     let mut chunk_executor = create_mock_executor();
     chunk_executor
-        .expect_execute_chunk()
+        .expect_apply_chunk()
         .with(always(), always(), always())
         .returning(|_, _, _| Ok(()));
-    let expected_commit_return = Ok(ChunkCommitNotification {
-        committed_events: vec![event_to_commit.clone()],
-        committed_transactions: vec![transaction_to_commit.clone()],
-        reconfiguration_occurred: false,
-    });
     chunk_executor
         .expect_commit_chunk()
-        .return_once(move || expected_commit_return);
+        .returning(|| Ok(create_commit_notification(&[])));
 
     // Create the storage synchronizer
-    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+    let (_, _, _, _, mut storage_synchronizer, _, _) =
         create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        0
+    );
 
-    // Subscribe to the expected event
-    let mut event_listener = event_subscription_service
-        .lock()
-        .subscribe_to_events(vec![*event_to_commit.key()])
-        .unwrap();
+    // Register waiters for versions after the committed version and verify they're pending
+    let mut waiter_1 = Box::pin(storage_synchronizer.await_version_committed(1));
+    let mut waiter_2 = Box::pin(storage_synchronizer.await_version_committed(2));
+    let mut waiter_3 = Box::pin(storage_synchronizer.await_version_committed(3));
+    for waiter in [&mut waiter_1, &mut waiter_2, &mut waiter_3] {
+        assert_none!(waiter.now_or_never());
+    }
 
-    // Attempt to execute a chunk of transactions
+    // Commit versions 1 and 2 and verify only the waiters for those versions resolve
+    for version in 1..=2 {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Apply,
+                TransactionChunkPayload::TransactionOutputs(create_output_chunk(version)),
+                create_signed_ledger_info(&create_validator_signers(0), version, 0),
+                None,
+                None,
+            )
+            .unwrap();
+        verify_no_pending_data(&storage_synchronizer);
+    }
+    waiter_1.await.unwrap();
+    waiter_2.await.unwrap();
+    assert_none!((&mut waiter_3).now_or_never());
+
+    // Verify waiters before and at the committed version resolve immediately
+    for version in 0..=2 {
+        storage_synchronizer
+            .await_version_committed(version)
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+    }
+
+    // Commit version 3 and verify the last waiter resolves
     storage_synchronizer
-        .execute_transactions(
-            0,
-            create_transaction_list_with_proof(),
-            create_epoch_ending_ledger_info(),
+        .submit_transaction_chunk(
+            3,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(create_output_chunk(3)),
+            create_signed_ledger_info(&create_validator_signers(0), 3, 0),
+            None,
             None,
         )
         .unwrap();
+    waiter_3.await.unwrap();
+}
 
-    // Verify we get a mempool and event notification. Also verify that there's no pending data.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_await_version_committed_reset() {
+    // Create the storage synchronizer
+    let (_, _, _, _, storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, None),
+    );
+
+    // Register waiters for uncommitted versions
+    let waiter_1 = storage_synchronizer.await_version_committed(1);
+    let waiter_10 = storage_synchronizer.await_version_committed(10);
+
+    // Reset the pending data and verify the waiters are cancelled
+    storage_synchronizer.reset_pending_data();
+    for (version, waiter) in vec![(1, waiter_1), (10, waiter_10)] {
+        let error = waiter.await.unwrap_err();
+        assert_eq!(
+            error,
+            Error::VersionWaitCancelled(version, "the pending data was reset".into())
+        );
+    }
+
+    // Verify waiters registered after the reset are unaffected
+    storage_synchronizer
+        .await_version_committed(0)
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+    let mut waiter = Box::pin(storage_synchronizer.await_version_committed(1));
+    assert_none!((&mut waiter).now_or_never());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_backpressure_state_changes() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to block all commits until released
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let (commit_release_sender, commit_release_receiver) = std::sync::mpsc::channel::<()>();
+    let commit_release_receiver = Mutex::new(commit_release_receiver);
+    chunk_executor.expect_commit_chunk().returning(move || {
+        let _ = commit_release_receiver.lock().recv();
+        Ok(create_commit_notification(&[]))
+    });
+
+    // Create the storage synchronizer with the water marks at 2 and 1 chunk(s)
+    let driver_config = StateSyncDriverConfig {
+        commit_backlog_high_water_mark: 2,
+        commit_backlog_low_water_mark: 1,
+        ..Default::default()
+    };
+    let (backpressure_notifier, backpressure_listener) = new_backpressure_channel();
+    let (_, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_backpressure(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+            backpressure_notifier,
+            create_metadata_storage(),
+            false,
+        );
+    assert_eq!(
+        *backpressure_listener.borrow(),
+        BackpressureState::Accepting
+    );
+
+    // Execute all chunks and verify the backlog pauses the synchronizer
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    verify_pending_data_stages(&storage_synchronizer, 0, 0, 2, 1);
+    verify_backpressure_state(&backpressure_listener, BackpressureState::Paused);
+
+    // Commit a chunk and verify the synchronizer remains paused (above the low water mark)
+    commit_release_sender.send(()).unwrap();
+    verify_pending_data_stages(&storage_synchronizer, 0, 0, 1, 1);
+    assert_eq!(*backpressure_listener.borrow(), BackpressureState::Paused);
+
+    // Commit another chunk and verify the synchronizer resumes (at the low water mark)
+    commit_release_sender.send(()).unwrap();
+    verify_pending_data_stages(&storage_synchronizer, 0, 0, 0, 1);
+    verify_backpressure_state(&backpressure_listener, BackpressureState::Accepting);
+
+    // Commit the last chunk and verify the synchronizer is still accepting data
+    commit_release_sender.send(()).unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+    assert_eq!(
+        *backpressure_listener.borrow(),
+        BackpressureState::Accepting
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_batch_delay_expired() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to commit the first chunk alone and the rest together
+    let (mut chunk_executor, release_sender) = create_gated_executor();
+    let first_notification = create_commit_notification(&transactions[..1]);
+    chunk_executor
+        .expect_commit_chunk()
+        .times(1)
+        .returning(move || Ok(first_notification.clone()));
+    let batch_notification = create_commit_notification(&transactions[1..]);
+    chunk_executor
+        .expect_commit_chunks()
+        .with(eq(2))
+        .times(1)
+        .returning(move |_| Ok(batch_notification.clone()));
+
+    // Create the storage synchronizer (with a short batch delay)
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            create_commit_batch_config(3, 100),
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Send all chunks and only execute the first
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    release_sender.send(()).unwrap();
+
+    // Verify the first chunk is committed once the batch delay expires
     verify_mempool_and_event_notification(
-        Some(&mut event_listener),
+        None,
         &mut mempool_listener,
-        vec![transaction_to_commit],
-        vec![event_to_commit],
+        transactions[..1].to_vec(),
+        vec![],
+    )
+    .await;
+
+    // Execute the remaining chunks and verify they're committed together
+    // (the batch is flushed once the pipeline drains).
+    release_sender.send(()).unwrap();
+    release_sender.send(()).unwrap();
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transactions[1..].to_vec(),
+        vec![],
     )
     .await;
     verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn test_execute_transactions_error() {
+async fn test_commit_batch_ledger_info_flush() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to commit the first two chunks together and the third alone
+    let (mut chunk_executor, release_sender) = create_gated_executor();
+    let batch_notification = create_commit_notification(&transactions[..2]);
+    chunk_executor
+        .expect_commit_chunks()
+        .with(eq(2))
+        .times(1)
+        .returning(move |_| Ok(batch_notification.clone()));
+    let last_notification = create_commit_notification(&transactions[2..]);
+    chunk_executor
+        .expect_commit_chunk()
+        .times(1)
+        .returning(move || Ok(last_notification.clone()));
+
+    // Create the storage synchronizer (with a large batch size and delay)
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            create_commit_batch_config(10, 10_000),
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Send all chunks (where the second chunk ends at the target) and execute them
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(2),
+    );
+    for _ in 0..transactions.len() {
+        release_sender.send(()).unwrap();
+    }
+
+    // Verify the batch is flushed at the target and the last chunk is committed alone
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transactions[..2].to_vec(),
+        vec![],
+    )
+    .await;
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transactions[2..].to_vec(),
+        vec![],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_batch_merged_notification() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to commit all chunks together
+    let (mut chunk_executor, release_sender) = create_gated_executor();
+    let batch_notification = create_commit_notification(&transactions);
+    chunk_executor
+        .expect_commit_chunks()
+        .with(eq(3))
+        .times(1)
+        .returning(move |_| Ok(batch_notification.clone()));
+
+    // Create the storage synchronizer (with a large batch delay)
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            create_commit_batch_config(3, 10_000),
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Send all chunks and execute them
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    for _ in 0..transactions.len() {
+        release_sender.send(()).unwrap();
+    }
+
+    // Verify a single (merged) notification is sent for all chunks
+    verify_mempool_and_event_notification(None, &mut mempool_listener, transactions, vec![]).await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_chunk_error() {
     // Setup the mock executor
     let mut chunk_executor = create_mock_executor();
     chunk_executor
         .expect_execute_chunk()
         .with(always(), always(), always())
-        .returning(|_, _, _| Err(format_err!("Failed to execute chunk!")));
+        .returning(|_, _, _| Ok(()));
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(|| Err(format_err!("Failed to commit chunk!")));
 
     // Create the storage synchronizer
     let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
@@ -220,76 +602,2166 @@ async fn test_execute_transactions_error() {
     // Attempt to execute a chunk of transactions
     let notification_id = 100;
     storage_synchronizer
-        .execute_transactions(
+        .submit_transaction_chunk(
             notification_id,
-            create_transaction_list_with_proof(),
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
             create_epoch_ending_ledger_info(),
             None,
+            None,
         )
         .unwrap();
 
-    // Verify we get an error notification and that there's no pending data
-    verify_error_notification(&mut error_listener, notification_id).await;
+    // Verify we get an error notification (from the committer) and that there's no pending data
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Fatal,
+    )
+    .await;
+    assert_eq!(
+        error_notification.stage,
+        StorageSynchronizerStage::Committer
+    );
+    assert_eq!(error_notification.version_range, Some((1, 1)));
     verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-#[should_panic]
-async fn test_initialize_state_synchronizer_missing_info() {
-    // Create test data that is missing transaction infos
-    let mut output_list_with_proof = create_output_list_with_proof();
-    output_list_with_proof.proof.transaction_infos = vec![]; // This is invalid!
+async fn test_commit_chunk_permanent_error() {
+    // Setup the mock executor to fail the commit with an untyped storage error
+    // (these are permanent, regardless of the error message).
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor.expect_commit_chunk().times(1).returning(|| {
+        Err(format_err!(
+            "Corruption: block checksum mismatch (IO error)"
+        ))
+    });
 
     // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
-        create_mock_executor(),
-        create_mock_reader_writer(None, None),
-    );
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
 
-    // Initialize the state synchronizer
-    let state_synchronizer_handle = storage_synchronizer
-        .initialize_state_synchronizer(
-            vec![create_epoch_ending_ledger_info()],
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
             create_epoch_ending_ledger_info(),
-            output_list_with_proof,
+            None,
+            None,
         )
         .unwrap();
 
-    // The handler should panic as it was given invalid data
-    state_synchronizer_handle.await.unwrap();
+    // Verify the error is escalated immediately (without any retries)
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Fatal,
+    )
+    .await;
+    assert_eq!(
+        error_notification.stage,
+        StorageSynchronizerStage::Committer
+    );
+    verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-#[should_panic]
-async fn test_initialize_state_synchronizer_receiver_error() {
-    // Setup the mock db writer. The db writer should always fail.
-    let mut db_writer = create_mock_db_writer();
-    db_writer
-        .expect_get_state_snapshot_receiver()
-        .returning(|_, _| Err(format_err!("Failed to get snapshot receiver!")));
+async fn test_commit_chunk_transient_error() {
+    // Create test data
+    let transactions = vec![create_transaction()];
 
-    // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
-        create_mock_executor(),
-        create_mock_reader_writer(None, Some(db_writer)),
+    // Setup the mock executor to fail the first two commits with transient errors
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let num_commit_attempts = Arc::new(AtomicU64::new(0));
+    let commit_notification = create_commit_notification(&transactions);
+    chunk_executor.expect_commit_chunk().times(3).returning({
+        let num_commit_attempts = num_commit_attempts.clone();
+        move || {
+            if num_commit_attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(io::Error::from(io::ErrorKind::TimedOut).into())
+            } else {
+                Ok(commit_notification.clone())
+            }
+        }
+    });
+
+    // Create the storage synchronizer (with a small retry backoff)
+    let driver_config = StateSyncDriverConfig {
+        commit_retry_backoff_ms: 1,
+        ..Default::default()
+    };
+    let (_, mut error_listener, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Execute the chunk of transactions
+    let num_commit_retries = read_commit_retries();
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
     );
 
-    // Initialize the state synchronizer
-    let state_synchronizer_handle = storage_synchronizer
-        .initialize_state_synchronizer(
-            vec![create_epoch_ending_ledger_info()],
-            create_epoch_ending_ledger_info(),
-            create_output_list_with_proof(),
-        )
-        .unwrap();
+    // Verify the chunk is committed and that no error notification is sent
+    verify_mempool_and_event_notification(None, &mut mempool_listener, transactions, vec![]).await;
+    verify_no_pending_data(&storage_synchronizer);
+    assert_none!(error_listener.select_next_some().now_or_never());
 
-    // The handler should panic as storage failed to return a snapshot receiver
-    state_synchronizer_handle.await.unwrap();
+    // Verify the commit was retried
+    assert_eq!(num_commit_attempts.load(Ordering::SeqCst), 3);
+    assert!(read_commit_retries() >= num_commit_retries + 2);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-async fn test_save_states_completion() {
-    // Create test data
+async fn test_commit_chunk_transient_error_retries_exhausted() {
+    // Setup the mock executor to always fail the commit with a transient error
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor
+        .expect_commit_chunk()
+        .times(3)
+        .returning(|| Err(io::Error::new(io::ErrorKind::WouldBlock, "Write stall").into()));
+
+    // Create the storage synchronizer (with two retries)
+    let driver_config = StateSyncDriverConfig {
+        commit_retry_backoff_ms: 1,
+        max_commit_retries: 2,
+        ..Default::default()
+    };
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Verify the error is escalated once the retries are exhausted
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Fatal,
+    )
+    .await;
+    assert_eq!(
+        error_notification.stage,
+        StorageSynchronizerStage::Committer
+    );
+    assert_eq!(error_notification.version_range, Some((1, 1)));
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_chunks_fake_storage() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Create a fake storage backend that fails the first commit with a transient error
+    let fake_storage = FakeStorage::new();
+    fake_storage.fail_next_commit(io::Error::from(io::ErrorKind::TimedOut).into());
+
+    // Create the storage synchronizer (with a small retry backoff)
+    let driver_config = StateSyncDriverConfig {
+        commit_retry_backoff_ms: 1,
+        ..Default::default()
+    };
+    let (_, mut error_listener, _, mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            fake_storage.create_chunk_executor(),
+            driver_config,
+            fake_storage.create_reader_writer(),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+    let _mempool_acknowledger = spawn_mempool_acknowledger(mempool_listener);
+
+    // Execute the chunks of transactions
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+
+    // Verify all chunks are committed (in order) and that no error notification is sent
+    verify_no_pending_data(&storage_synchronizer);
+    assert_none!(error_listener.select_next_some().now_or_never());
+    let expected_chunks: Vec<Vec<Transaction>> = transactions
+        .iter()
+        .map(|transaction| vec![transaction.clone()])
+        .collect();
+    assert_eq!(fake_storage.get_committed_chunks(), expected_chunks);
+    assert_eq!(fake_storage.get_latest_version(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_chunk_fake_storage_permanent_error() {
+    // Create a fake storage backend that fails the first commit with a permanent error
+    let fake_storage = FakeStorage::new();
+    fake_storage.fail_next_commit(format_err!("Corruption: block checksum mismatch"));
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            fake_storage.create_chunk_executor(),
+            StateSyncDriverConfig::default(),
+            fake_storage.create_reader_writer(),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Verify the error is escalated (without a retry) and nothing is committed
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Fatal,
+    )
+    .await;
+    assert_eq!(
+        error_notification.stage,
+        StorageSynchronizerStage::Committer
+    );
+    verify_no_pending_data(&storage_synchronizer);
+    assert!(fake_storage.get_committed_chunks().is_empty());
+    assert_eq!(fake_storage.get_latest_version(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_commit_notifications_after_stream_reset() {
+    // Create test data for three consecutive chunks
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to execute the first two chunks and fail the third
+    let mut chunk_executor = create_mock_executor();
+    let mut num_executed_chunks = 0;
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            num_executed_chunks += 1;
+            if num_executed_chunks <= 2 {
+                Ok(())
+            } else {
+                Err(format_err!("Failed to execute chunk!"))
+            }
+        });
+
+    // Block the first commit until the stream has been reset
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let release_receiver = Mutex::new(release_receiver);
+    let commit_notifications = Mutex::new(
+        transactions[..2]
+            .iter()
+            .map(|transaction| ChunkCommitNotification {
+                committed_events: vec![],
+                committed_transactions: vec![transaction.clone()],
+                reconfiguration_occurred: false,
+            })
+            .collect::<Vec<_>>(),
+    );
+    chunk_executor.expect_commit_chunk().returning(move || {
+        let _ = release_receiver.lock().recv();
+        Ok(commit_notifications.lock().remove(0))
+    });
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Execute all three chunks
+    for (version, transaction) in (1..).zip(transactions.iter()) {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    vec![transaction.clone()],
+                    None,
+                    Some(version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Verify we get an error notification for the third chunk
+    verify_error_notification(
+        &mut error_listener,
+        3,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+
+    // Reset the stream (as the driver would) and verify the pipeline hasn't drained
+    let num_post_reset_notifications = read_post_reset_commit_notifications();
+    storage_synchronizer.notify_stream_reset();
+    let pending_data_summary = storage_synchronizer.pending_storage_data();
+    assert!(!pending_data_summary.is_idle());
+    assert_eq!(pending_data_summary.bytes_queued, 0);
+
+    // Release the committer and verify both committed chunks are still notified
+    release_sender.send(()).unwrap();
+    release_sender.send(()).unwrap();
+    for transaction in &transactions[..2] {
+        verify_mempool_and_event_notification(
+            None,
+            &mut mempool_listener,
+            vec![transaction.clone()],
+            vec![],
+        )
+        .await;
+    }
+
+    // Verify the notifications were marked as post-reset and the pipeline drains
+    verify_no_pending_data(&storage_synchronizer);
+    assert!(read_post_reset_commit_notifications() >= num_post_reset_notifications + 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_commit_state_tracker_updated() {
+    // Setup the mock executor to execute and commit two chunks
+    let transactions: Vec<Transaction> = (0..2).map(|_| create_transaction()).collect();
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let commit_notifications = Mutex::new(
+        transactions
+            .iter()
+            .map(|transaction| ChunkCommitNotification {
+                committed_events: vec![],
+                committed_transactions: vec![transaction.clone()],
+                reconfiguration_occurred: false,
+            })
+            .collect::<Vec<_>>(),
+    );
+    chunk_executor
+        .expect_commit_chunk()
+        .returning(move || Ok(commit_notifications.lock().remove(0)));
+
+    // Create the storage synchronizer with a commit state tracker
+    let time_service = TimeService::mock();
+    let commit_state_tracker = CommitStateTracker::new(time_service.clone());
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            StateSyncDriverConfig::default(),
+            create_mock_reader_writer(None, None),
+            commit_state_tracker.clone(),
+        );
+    assert_eq!(commit_state_tracker.get_commit_state(), None);
+
+    // Execute and commit each chunk (advancing time in between)
+    let mut last_commit_timestamp_usecs = 0;
+    for (version, transaction) in (1..).zip(transactions.iter()) {
+        time_service.clone().into_mock().advance_secs(1);
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    vec![transaction.clone()],
+                    None,
+                    Some(version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+        verify_mempool_and_event_notification(
+            None,
+            &mut mempool_listener,
+            vec![transaction.clone()],
+            vec![],
+        )
+        .await;
+
+        // Verify the commit was recorded by the tracker
+        let commit_state = commit_state_tracker.get_commit_state().unwrap();
+        assert_eq!(commit_state.last_commit_version, version);
+        assert_eq!(commit_state.last_commit_epoch, 0);
+        assert!(commit_state.last_commit_timestamp_usecs > last_commit_timestamp_usecs);
+        last_commit_timestamp_usecs = commit_state.last_commit_timestamp_usecs;
+    }
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dry_run_commits_skip_storage() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Create a dry-run storage synchronizer over a fake storage backend
+    let fake_storage = FakeStorage::new();
+    let (backpressure_notifier, _) = new_backpressure_channel();
+    let (_, mut error_listener, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_backpressure(
+            DryRunChunkExecutor::new(Arc::new(fake_storage.create_chunk_executor())),
+            StateSyncDriverConfig::default(),
+            fake_storage.create_reader_writer(),
+            CommitStateTracker::new(TimeService::mock()),
+            backpressure_notifier,
+            create_metadata_storage(),
+            true,
+        );
+
+    // Execute the chunks of transactions
+    let driver_metrics = DriverMetrics::default();
+    let num_verified_versions = driver_metrics.read_counter(
+        &metrics::DRIVER_COUNTERS,
+        metrics::DRY_RUN_VERIFIED_VERSIONS,
+    );
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+
+    // Verify the chunks are verified and executed, but nothing is committed
+    verify_no_pending_data(&storage_synchronizer);
+    assert_none!(error_listener.select_next_some().now_or_never());
+    assert_none!(mempool_listener.select_next_some().now_or_never());
+    assert!(fake_storage.get_committed_chunks().is_empty());
+    assert_eq!(fake_storage.get_latest_version(), 0);
+
+    // Verify the dry-run verification metrics advanced
+    assert_eq!(
+        driver_metrics.read_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::DRY_RUN_VERIFIED_VERSIONS
+        ),
+        num_verified_versions + transactions.len() as u64
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_transactions() {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+    let event_to_commit = create_event(None);
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let expected_commit_return = Ok(ChunkCommitNotification {
+        committed_events: vec![event_to_commit.clone()],
+        committed_transactions: vec![transaction_to_commit.clone()],
+        reconfiguration_occurred: false,
+    });
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(move || expected_commit_return);
+
+    // Create the storage synchronizer
+    let (_, _, event_subscription_service, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Subscribe to the expected event
+    let mut event_listener = event_subscription_service
+        .lock()
+        .subscribe_to_events(vec![*event_to_commit.key()])
+        .unwrap();
+
+    // Attempt to execute a chunk of transactions
+    storage_synchronizer
+        .submit_transaction_chunk(
+            0,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Verify we get a mempool and event notification. Also verify that there's no pending data.
+    verify_mempool_and_event_notification(
+        Some(&mut event_listener),
+        &mut mempool_listener,
+        vec![transaction_to_commit],
+        vec![event_to_commit],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_transactions_epoch_ending() {
+    // Setup the mock executor to execute and commit two chunks
+    let transactions: Vec<Transaction> = (0..2).map(|_| create_transaction()).collect();
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let commit_notifications = Mutex::new(
+        transactions
+            .iter()
+            .map(|transaction| create_commit_notification(&[transaction.clone()]))
+            .collect::<Vec<_>>(),
+    );
+    chunk_executor
+        .expect_commit_chunk()
+        .returning(move || Ok(commit_notifications.lock().remove(0)));
+
+    // Create the storage synchronizer with a commit state tracker
+    let commit_state_tracker = CommitStateTracker::new(TimeService::mock());
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            StateSyncDriverConfig::default(),
+            create_mock_reader_writer(None, None),
+            commit_state_tracker.clone(),
+        );
+
+    // Execute a chunk that doesn't end the epoch and verify no epoch state is cached
+    let epoch_ending_ledger_info = create_epoch_ending_ledger_info();
+    let new_epoch_state = epoch_ending_ledger_info
+        .ledger_info()
+        .next_epoch_state()
+        .cloned()
+        .unwrap();
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions[..1],
+        create_ledger_info_at_version(100),
+    );
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transactions[..1].to_vec(),
+        vec![],
+    )
+    .await;
+    assert_eq!(
+        commit_state_tracker.get_epoch_state(new_epoch_state.epoch),
+        None
+    );
+
+    // Execute a synthetic epoch ending chunk (with an end of epoch ledger info)
+    let num_epoch_transitions = DriverMetrics::default()
+        .counter(&metrics::DRIVER_COUNTERS, &[metrics::EPOCH_TRANSITION])
+        .get();
+    storage_synchronizer
+        .submit_transaction_chunk(
+            2,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                vec![transactions[1].clone()],
+                None,
+                Some(2),
+                create_transaction_info_list_with_proof(),
+            )),
+            create_ledger_info_at_version(100),
+            Some(epoch_ending_ledger_info),
+            None,
+        )
+        .unwrap();
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transactions[1..].to_vec(),
+        vec![],
+    )
+    .await;
+
+    // Verify the new epoch state was cached and the epoch transition was counted
+    assert_eq!(
+        commit_state_tracker.get_epoch_state(new_epoch_state.epoch),
+        Some(new_epoch_state)
+    );
+    assert!(
+        DriverMetrics::default()
+            .counter(&metrics::DRIVER_COUNTERS, &[metrics::EPOCH_TRANSITION])
+            .get()
+            > num_epoch_transitions
+    );
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_transactions_error() {
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Err(format_err!("Failed to execute chunk!")));
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Attempt to execute a chunk of transactions
+    let notification_id = 100;
+    storage_synchronizer
+        .submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(1)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Verify we get an error notification and that there's no pending data
+    verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_executor_and_committer_errors_single_notification() {
+    // Setup the mock executor to fail the execution of the first chunk (after
+    // a delay, so that the second chunk is submitted while the first is still
+    // in-flight) and the commit of the second chunk.
+    let mut chunk_executor = create_mock_executor();
+    let num_executions = AtomicU64::new(0);
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .times(2)
+        .returning(move |_, _, _| {
+            if num_executions.fetch_add(1, Ordering::Relaxed) == 0 {
+                std::thread::sleep(Duration::from_millis(100));
+                Err(format_err!("Failed to execute chunk!"))
+            } else {
+                Ok(())
+            }
+        });
+    chunk_executor
+        .expect_commit_chunk()
+        .times(1)
+        .returning(|| Err(format_err!("Failed to commit chunk!")));
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Submit two chunks for the same notification (so both the executor
+    // and the committer fail for the same chunk identity).
+    let notification_id = 100;
+    for version in 1..=2 {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                notification_id,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(create_transaction_chunk(version)),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Verify we get a single error notification (for the first failing stage)
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    assert_eq!(error_notification.stage, StorageSynchronizerStage::Executor);
+    assert_eq!(error_notification.version_range, Some((1, 1)));
+
+    // Verify no other error notification is sent once the pipeline drains
+    verify_no_pending_data(&storage_synchronizer);
+    assert!(error_listener.select_next_some().now_or_never().is_none());
+}
+
+#[test]
+fn test_failed_operation_error_severity() {
+    // Verify that bookkeeping failures (after the data was committed) are transient
+    for failed_operation in [
+        FailedOperation::NotifyStateValueChunk,
+        FailedOperation::PersistStateValueIndex,
+    ] {
+        let error_severity = failed_operation.get_error_severity();
+        assert_eq!(error_severity, ErrorSeverity::Transient);
+        assert!(!error_severity.terminates_stream());
+    }
+
+    // Verify that failures to process the data (e.g., invalid proofs) are recoverable
+    for failed_operation in [
+        FailedOperation::CommitStateValues,
+        FailedOperation::ExecuteChunk,
+        FailedOperation::NotifyCommitter,
+        FailedOperation::VerifyChunkContinuity,
+        FailedOperation::VerifyChunkSize,
+        FailedOperation::VerifyEventRootHash,
+        FailedOperation::VerifyLedgerInfo,
+        FailedOperation::VerifyStateValues,
+    ] {
+        let error_severity = failed_operation.get_error_severity();
+        assert_eq!(error_severity, ErrorSeverity::Recoverable);
+        assert!(error_severity.terminates_stream());
+    }
+
+    // Verify that failures to commit or finalize storage are fatal
+    for failed_operation in [
+        FailedOperation::CommitChunk,
+        FailedOperation::FinalizeStateSnapshot,
+    ] {
+        let error_severity = failed_operation.get_error_severity();
+        assert_eq!(error_severity, ErrorSeverity::Fatal);
+        assert!(error_severity.terminates_stream());
+    }
+}
+
+#[test]
+fn test_failed_operation_stage() {
+    // Verify that transaction chunk failures are attributed to the executor and committer
+    assert_eq!(
+        FailedOperation::CommitChunk.get_stage(),
+        StorageSynchronizerStage::Committer
+    );
+    for failed_operation in [
+        FailedOperation::ExecuteChunk,
+        FailedOperation::NotifyCommitter,
+        FailedOperation::VerifyChunkContinuity,
+        FailedOperation::VerifyChunkSize,
+        FailedOperation::VerifyEventRootHash,
+        FailedOperation::VerifyLedgerInfo,
+    ] {
+        assert_eq!(
+            failed_operation.get_stage(),
+            StorageSynchronizerStage::Executor
+        );
+    }
+
+    // Verify that state value chunk failures are attributed to the state snapshot receiver
+    for failed_operation in [
+        FailedOperation::CommitStateValues,
+        FailedOperation::FinalizeStateSnapshot,
+        FailedOperation::NotifyStateValueChunk,
+        FailedOperation::PersistStateValueIndex,
+        FailedOperation::VerifyStateValues,
+    ] {
+        assert_eq!(
+            failed_operation.get_stage(),
+            StorageSynchronizerStage::StateSnapshotReceiver
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_finalize_pending_state_snapshot_after_crash() {
+    // Simulate a crash between each pair of finalization writes and verify recovery
+    for crash_point in [
+        FinalizationCrashPoint::BeforeFinalizationMarker,
+        FinalizationCrashPoint::BeforeStorageFinalization,
+        FinalizationCrashPoint::BeforeMetadataCompletion,
+        FinalizationCrashPoint::AfterMetadataCompletion,
+    ] {
+        // Create test data
+        let target_version = 1000;
+        let target_ledger_info = create_ledger_info_at_version(target_version);
+        let last_state_value_index = 999;
+        let snapshot_finalization = StateSnapshotFinalization {
+            target_ledger_info: target_ledger_info.clone(),
+            target_output_with_proof: create_output_list_with_proof(),
+            epoch_change_proofs: vec![target_ledger_info.clone()],
+            last_persisted_state_value_index: last_state_value_index,
+        };
+
+        // Crash the node at the specified point of the finalization
+        let tmp_dir = TempPath::new();
+        let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+        simulate_finalization_crash(
+            &metadata_storage,
+            snapshot_finalization.clone(),
+            crash_point,
+        );
+
+        // Determine what the restarted node should have to recover
+        let finalization_pending = matches!(
+            crash_point,
+            FinalizationCrashPoint::BeforeStorageFinalization
+                | FinalizationCrashPoint::BeforeMetadataCompletion
+        );
+        let storage_finalized = matches!(
+            crash_point,
+            FinalizationCrashPoint::BeforeMetadataCompletion
+                | FinalizationCrashPoint::AfterMetadataCompletion
+        );
+
+        // Setup the mock executor and db writer (storage should only be
+        // finalized if the crash happened before it was finalized).
+        let mut chunk_executor = create_mock_executor();
+        chunk_executor
+            .expect_reset()
+            .times(usize::from(finalization_pending))
+            .returning(|| Ok(()));
+        let mut db_writer = create_mock_db_writer();
+        let snapshot_finalization_clone = snapshot_finalization.clone();
+        db_writer
+            .expect_finalize_state_snapshot()
+            .times(usize::from(finalization_pending && !storage_finalized))
+            .withf(
+                move |version: &Version,
+                      output_with_proof: &TransactionOutputListWithProof,
+                      ledger_infos: &[LedgerInfoWithSignatures]| {
+                    version == &target_version
+                        && output_with_proof
+                            == &snapshot_finalization_clone.target_output_with_proof
+                        && ledger_infos == snapshot_finalization_clone.epoch_change_proofs
+                },
+            )
+            .returning(|_, _, _| Ok(()));
+
+        // Restart the storage synchronizer (using the same metadata storage)
+        let synced_version = if storage_finalized { target_version } else { 0 };
+        let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+            create_storage_synchronizer_with_metadata(
+                chunk_executor,
+                create_mock_reader_writer_at_version(synced_version, db_writer),
+                metadata_storage.clone(),
+            );
+
+        // Complete any pending finalization and verify the result
+        assert_eq!(
+            storage_synchronizer
+                .finalize_pending_state_snapshot()
+                .unwrap(),
+            finalization_pending
+        );
+        if finalization_pending {
+            match commit_listener.select_next_some().await {
+                CommitNotification::CommittedStateSnapshot(committed_snapshot) => {
+                    assert_eq!(committed_snapshot.version, target_version);
+                    assert_eq!(
+                        committed_snapshot.last_committed_state_index,
+                        last_state_value_index
+                    );
+                }
+                commit_notification => panic!(
+                    "Expected a state snapshot commit notification but got: {:?}",
+                    commit_notification
+                ),
+            }
+        }
+
+        // Verify the metadata storage is consistent (i.e., the snapshot is
+        // complete, or the state values will continue to be synced).
+        assert_none!(metadata_storage.pending_snapshot_finalization().unwrap());
+        assert_eq!(
+            metadata_storage
+                .is_snapshot_sync_complete(&target_ledger_info)
+                .unwrap(),
+            crash_point != FinalizationCrashPoint::BeforeFinalizationMarker
+        );
+
+        // Verify that recovery is idempotent (there's nothing left to finalize)
+        assert!(!storage_synchronizer
+            .finalize_pending_state_snapshot()
+            .unwrap());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic]
+async fn test_initialize_state_synchronizer_missing_info() {
+    // Create test data that is missing transaction infos
+    let mut output_list_with_proof = create_output_list_with_proof();
+    output_list_with_proof.proof.transaction_infos = vec![]; // This is invalid!
+
+    // Create the storage synchronizer
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, None),
+    );
+
+    // Initialize the state synchronizer
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            output_list_with_proof,
+        )
+        .unwrap();
+
+    // The handler should panic as it was given invalid data
+    state_synchronizer_handle.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic]
+async fn test_initialize_state_synchronizer_receiver_error() {
+    // Setup the mock db writer. The db writer should always fail.
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .returning(|_, _| Err(format_err!("Failed to get snapshot receiver!")));
+
+    // Create the storage synchronizer
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, Some(db_writer)),
+    );
+
+    // Initialize the state synchronizer
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+
+    // The handler should panic as storage failed to return a snapshot receiver
+    state_synchronizer_handle.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pending_storage_data() {
+    // Create test data for two consecutive chunks (versions 10 to 12, and 13 to 14)
+    let first_chunk: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+    let second_chunk: Vec<Transaction> = (0..2).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to block all commits until released
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let release_receiver = Mutex::new(release_receiver);
+    chunk_executor.expect_commit_chunk().returning(move || {
+        let _ = release_receiver.lock().recv();
+        Ok(ChunkCommitNotification {
+            committed_events: vec![],
+            committed_transactions: vec![],
+            reconfiguration_occurred: false,
+        })
+    });
+
+    // Create the storage synchronizer (synced to version 9) and verify it's idle
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        chunk_executor,
+        create_mock_reader_writer_at_version(9, create_mock_db_writer()),
+    );
+    assert!(storage_synchronizer.is_idle());
+    assert_eq!(
+        storage_synchronizer.pending_storage_data(),
+        PendingDataSummary::default()
+    );
+
+    // Execute both chunks
+    for (first_version, transactions) in [(10, first_chunk), (13, second_chunk)] {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                first_version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    transactions,
+                    None,
+                    Some(first_version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Verify both chunks are in flight and the highest version is tracked
+    let pending_data_summary = storage_synchronizer.pending_storage_data();
+    assert!(!storage_synchronizer.is_idle());
+    assert_eq!(pending_data_summary.chunks_in_flight, 2);
+    assert_eq!(pending_data_summary.highest_version_in_flight, Some(14));
+    assert_eq!(pending_data_summary.highest_known_version(12), 14);
+    assert_eq!(pending_data_summary.highest_known_version(20), 20);
+
+    // Release the committer and verify the pipeline drains completely
+    release_sender.send(()).unwrap();
+    release_sender.send(()).unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+    assert_eq!(
+        storage_synchronizer.pending_storage_data(),
+        PendingDataSummary::default()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pending_storage_data_stages() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to block all executions and commits until released
+    let (mut chunk_executor, execute_release_sender) = create_gated_executor();
+    let (commit_release_sender, commit_release_receiver) = std::sync::mpsc::channel::<()>();
+    let commit_release_receiver = Mutex::new(commit_release_receiver);
+    chunk_executor.expect_commit_chunk().returning(move || {
+        let _ = commit_release_receiver.lock().recv();
+        Ok(create_commit_notification(&[]))
+    });
+
+    // Create the storage synchronizer
+    let (_, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Send all chunks and verify the first chunk is being executed
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    verify_pending_data_stages(&storage_synchronizer, 2, 1, 0, 0);
+    assert!(storage_synchronizer.pending_storage_data().bytes_queued > 0);
+
+    // Execute the first chunk and verify it's being committed
+    execute_release_sender.send(()).unwrap();
+    verify_pending_data_stages(&storage_synchronizer, 1, 1, 0, 1);
+
+    // Execute the remaining chunks and verify they're queued for commit
+    execute_release_sender.send(()).unwrap();
+    execute_release_sender.send(()).unwrap();
+    verify_pending_data_stages(&storage_synchronizer, 0, 0, 2, 1);
+    assert_eq!(storage_synchronizer.pending_storage_data().bytes_queued, 0);
+
+    // Commit all chunks and verify the pipeline drains completely
+    for _ in 0..transactions.len() {
+        commit_release_sender.send(()).unwrap();
+    }
+    verify_no_pending_data(&storage_synchronizer);
+    assert_eq!(
+        storage_synchronizer.pending_storage_data(),
+        PendingDataSummary::default()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_pipelined_execution_and_commit() {
+    // Create test data
+    let num_chunks = 20;
+    let execution_latency = Duration::from_millis(50);
+    let commit_latency = Duration::from_millis(50);
+    let transactions: Vec<Transaction> = (0..num_chunks).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor with execution and commit latencies
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            std::thread::sleep(execution_latency);
+            Ok(())
+        });
+    let commit_notifications = Mutex::new(
+        transactions
+            .iter()
+            .map(|transaction| ChunkCommitNotification {
+                committed_events: vec![],
+                committed_transactions: vec![transaction.clone()],
+                reconfiguration_occurred: false,
+            })
+            .collect::<Vec<_>>(),
+    );
+    chunk_executor.expect_commit_chunk().returning(move || {
+        std::thread::sleep(commit_latency);
+        Ok(commit_notifications.lock().remove(0))
+    });
+
+    // Create the storage synchronizer
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Execute all chunks
+    let start_time = Instant::now();
+    for (version, transaction) in (1..).zip(transactions.iter()) {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    vec![transaction.clone()],
+                    None,
+                    Some(version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Verify all chunks are committed (in order)
+    for transaction in transactions {
+        verify_mempool_and_event_notification(
+            None,
+            &mut mempool_listener,
+            vec![transaction],
+            vec![],
+        )
+        .await;
+    }
+    let total_duration = start_time.elapsed();
+    verify_no_pending_data(&storage_synchronizer);
+
+    // Verify the executor and committer stages overlapped, i.e., the total time is
+    // well below the time it would take to execute and commit each chunk serially.
+    let serial_duration = (execution_latency + commit_latency) * num_chunks as u32;
+    let pipelined_duration = execution_latency * num_chunks as u32 + commit_latency;
+    assert!(total_duration >= pipelined_duration);
+    assert!(total_duration < (pipelined_duration + serial_duration) / 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reset_pending_data() {
+    // Create test data
+    let transactions: Vec<Transaction> = (0..3).map(|_| create_transaction()).collect();
+
+    // Setup the mock executor to block all executions and the (single) commit until released
+    let (mut chunk_executor, execute_release_sender) = create_gated_executor();
+    let (commit_release_sender, commit_release_receiver) = std::sync::mpsc::channel::<()>();
+    let commit_release_receiver = Mutex::new(commit_release_receiver);
+    let committed_transaction = transactions[0].clone();
+    chunk_executor
+        .expect_commit_chunk()
+        .times(1)
+        .returning(move || {
+            let _ = commit_release_receiver.lock().recv();
+            Ok(create_commit_notification(&[committed_transaction.clone()]))
+        });
+
+    // Create the storage synchronizer
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Send all chunks, execute the first chunk and verify it's being committed
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    execute_release_sender.send(()).unwrap();
+    verify_pending_data_stages(&storage_synchronizer, 1, 1, 0, 1);
+
+    // Reset the stream and the pending data (as the driver would)
+    storage_synchronizer.notify_stream_reset();
+    storage_synchronizer.reset_pending_data();
+
+    // Release the second execution (already in progress). The third chunk
+    // is discarded without being executed (otherwise it'd block forever).
+    execute_release_sender.send(()).unwrap();
+
+    // Release the in-progress commit and verify only the first chunk is notified
+    commit_release_sender.send(()).unwrap();
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        vec![transactions[0].clone()],
+        vec![],
+    )
+    .await;
+
+    // Verify the discarded chunks are never committed and the pipeline drains
+    verify_no_pending_data(&storage_synchronizer);
+    assert_eq!(
+        storage_synchronizer.pending_storage_data(),
+        PendingDataSummary::default()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reset_pending_data_cancels_execution() {
+    // Setup the mock executor to execute a long chunk (that checks for
+    // cancellation between each transaction).
+    let num_transactions = 1000;
+    let (execution_started_sender, execution_started_receiver) = std::sync::mpsc::channel::<()>();
+    let execution_started_sender = Mutex::new(execution_started_sender);
+    let mut chunk_executor = MockCancellableChunkExecutor::new();
+    chunk_executor
+        .expect_execute_chunk_cancellable()
+        .with(always(), always(), always(), always())
+        .times(1)
+        .returning(move |_, _, _, cancellation_token| {
+            let _ = execution_started_sender.lock().send(());
+            for _ in 0..num_transactions {
+                cancellation_token.ensure_not_cancelled()?;
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Ok(())
+        });
+
+    // Create the storage synchronizer (with a long cancellation wait)
+    let driver_config = StateSyncDriverConfig {
+        max_chunk_cancellation_wait_ms: 30_000,
+        ..Default::default()
+    };
+    let (backpressure_notifier, _) = new_backpressure_channel();
+    let (_, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_backpressure(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+            backpressure_notifier,
+            create_metadata_storage(),
+            false,
+        );
+
+    // Send the long chunk and wait for the execution to start
+    let transactions = vec![create_transaction()];
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    execution_started_receiver.recv().unwrap();
+
+    // Reset the stream and the pending data (as the driver would)
+    let num_cancelled_executions = read_cancelled_chunk_executions();
+    let reset_start_time = Instant::now();
+    storage_synchronizer.notify_stream_reset();
+    storage_synchronizer.reset_pending_data();
+    let reset_duration = reset_start_time.elapsed();
+
+    // Verify the reset returned quickly (i.e., well before the chunk would
+    // have completed, and without waiting for the cancellation timeout).
+    assert!(reset_duration < Duration::from_secs(2));
+
+    // Verify the execution was cancelled, the chunk was never committed and the pipeline drains
+    verify_no_pending_data(&storage_synchronizer);
+    assert!(read_cancelled_chunk_executions() > num_cancelled_executions);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reset_pending_data_does_not_wait_for_cancellation() {
+    // Setup the mock executor to block the execution until released (i.e.,
+    // the execution ignores the cancellation).
+    let (chunk_executor, execute_release_sender) = create_gated_executor();
+
+    // Create the storage synchronizer (with a long cancellation wait)
+    let driver_config = StateSyncDriverConfig {
+        max_chunk_cancellation_wait_ms: 30_000,
+        ..Default::default()
+    };
+    let (backpressure_notifier, _) = new_backpressure_channel();
+    let (_, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_backpressure(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+            backpressure_notifier,
+            create_metadata_storage(),
+            false,
+        );
+
+    // Send a chunk and wait for the execution to start
+    let transactions = vec![create_transaction()];
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_ledger_info_at_version(100),
+    );
+    verify_pending_data_stages(&storage_synchronizer, 0, 1, 0, 0);
+
+    // Reset the stream and the pending data, and verify the reset returned
+    // without waiting for the (still blocked) execution to stop.
+    let reset_start_time = Instant::now();
+    storage_synchronizer.notify_stream_reset();
+    storage_synchronizer.reset_pending_data();
+    assert!(reset_start_time.elapsed() < Duration::from_secs(10));
+    assert_eq!(
+        storage_synchronizer.pending_storage_data().chunks_executing,
+        1
+    );
+
+    // Release the execution and verify the discarded chunk leaves the pipeline
+    execute_release_sender.send(()).unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_apply_or_execute() {
+    // Create a transaction output payload (and the transactions it contains)
+    let output_list_with_proof = create_output_chunk(1);
+    let transactions: Vec<Transaction> = output_list_with_proof
+        .transactions_and_outputs
+        .iter()
+        .map(|(transaction, _)| transaction.clone())
+        .collect();
+
+    // Store the same payload using both instructions
+    for apply_or_execute in [ApplyOrExecute::Apply, ApplyOrExecute::Execute] {
+        // Setup the mock executor to expect only the instructed path
+        let mut chunk_executor = create_mock_executor();
+        let num_applies = (apply_or_execute == ApplyOrExecute::Apply) as usize;
+        chunk_executor
+            .expect_apply_chunk()
+            .times(num_applies)
+            .with(eq(output_list_with_proof.clone()), always(), always())
+            .returning(|_, _, _| Ok(()));
+        let transaction_list_with_proof = TransactionListWithProof::new(
+            transactions.clone(),
+            None,
+            output_list_with_proof.first_transaction_output_version,
+            output_list_with_proof.proof.clone(),
+        );
+        chunk_executor
+            .expect_execute_chunk()
+            .times(1 - num_applies)
+            .with(eq(transaction_list_with_proof), always(), always())
+            .returning(|_, _, _| Ok(()));
+        let committed_transactions = transactions.clone();
+        chunk_executor
+            .expect_commit_chunk()
+            .times(1)
+            .returning(move || Ok(create_commit_notification(&committed_transactions)));
+
+        // Create the storage synchronizer
+        let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+            create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+        // Submit the chunk
+        let num_committed_chunks = read_committed_transaction_chunks(apply_or_execute);
+        storage_synchronizer
+            .submit_transaction_chunk(
+                0,
+                ErrorSource::ContinuousSyncer,
+                apply_or_execute,
+                TransactionChunkPayload::TransactionOutputs(output_list_with_proof.clone()),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+
+        // Verify the same transactions are committed and the path is reported
+        verify_mempool_and_event_notification(
+            None,
+            &mut mempool_listener,
+            transactions.clone(),
+            vec![],
+        )
+        .await;
+        verify_no_pending_data(&storage_synchronizer);
+        assert!(read_committed_transaction_chunks(apply_or_execute) > num_committed_chunks);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_duplicate() {
+    // Setup the mock executor to execute and commit a single chunk
+    let transaction_list_with_proof = create_transaction_chunk(11);
+    let committed_transactions = transaction_list_with_proof.transactions.clone();
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .times(1)
+        .returning(|_, _, _| Ok(()));
+    chunk_executor
+        .expect_commit_chunk()
+        .times(1)
+        .returning(move || Ok(create_commit_notification(&committed_transactions)));
+
+    // Create the storage synchronizer (synced to version 10)
+    let (_, mut error_listener, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(
+            chunk_executor,
+            create_mock_reader_writer_at_version(10, create_mock_db_writer()),
+        );
+
+    // Submit the chunk and wait for it to be committed
+    let submit_chunk = |storage_synchronizer: &mut StorageSynchronizer<_, _>| {
+        storage_synchronizer.submit_transaction_chunk(
+            11,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(transaction_list_with_proof.clone()),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+    };
+    submit_chunk(&mut storage_synchronizer).unwrap();
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        transaction_list_with_proof.transactions.clone(),
+        vec![],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+
+    // Resubmit the exact same chunk and verify it's acknowledged as a no-op
+    let num_duplicate_chunks = read_duplicate_transaction_chunks();
+    submit_chunk(&mut storage_synchronizer).unwrap();
+    assert!(read_duplicate_transaction_chunks() > num_duplicate_chunks);
+    assert!(storage_synchronizer.is_idle());
+    assert!(error_listener.select_next_some().now_or_never().is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_gap() {
+    // Create the storage synchronizer (synced to version 10)
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer_at_version(10, create_mock_db_writer()),
+    );
+
+    // Submit a chunk that skips version 11 and verify it's rejected
+    let notification_id = 100;
+    let error = storage_synchronizer
+        .submit_transaction_chunk(
+            notification_id,
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(create_transaction_chunk(12)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert_matches!(error, Error::UnexpectedError(_));
+
+    // Verify the error notification identifies the chunk and the expected version
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::Bootstrapper,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    assert_eq!(error_notification.expected_version, Some(11));
+    assert_eq!(error_notification.version_range, Some((12, 12)));
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_invalid_apply() {
+    // Create the storage synchronizer
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, None),
+    );
+
+    // Verify that transactions (without outputs) can't be applied
+    let error = storage_synchronizer
+        .submit_transaction_chunk(
+            0,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::Transactions(create_transaction_list_with_proof()),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap_err();
+    assert_matches!(error, Error::InvalidPayload(_));
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_max_bytes() {
+    // Create test data (and identify its serialized size)
+    let output_list_with_proof = create_output_chunk(1);
+    let chunk_bytes =
+        bcs::serialized_size(&output_list_with_proof.transactions_and_outputs).unwrap() as u64;
+
+    // Submit the chunk with a max chunk size just under and just over the chunk size
+    for max_chunk_bytes in [chunk_bytes - 1, chunk_bytes + 1] {
+        // Setup the mock executor to only expect the chunk if it's under the limit
+        let chunk_fits = chunk_bytes <= max_chunk_bytes;
+        let mut chunk_executor = create_mock_executor();
+        chunk_executor
+            .expect_apply_chunk()
+            .times(chunk_fits as usize)
+            .returning(|_, _, _| Ok(()));
+        chunk_executor
+            .expect_commit_chunk()
+            .times(chunk_fits as usize)
+            .returning(|| Ok(create_commit_notification(&[])));
+
+        // Create the storage synchronizer
+        let driver_config = StateSyncDriverConfig {
+            max_chunk_bytes,
+            ..Default::default()
+        };
+        let (
+            _commit_listener,
+            mut error_listener,
+            _event_subscription_service,
+            _mempool_listener,
+            mut storage_synchronizer,
+            _,
+            _,
+        ) = create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+        // Submit the chunk and verify it's only rejected if it's over the limit
+        let notification_id = 100;
+        let result = storage_synchronizer.submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(output_list_with_proof.clone()),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        );
+        if chunk_fits {
+            result.unwrap();
+        } else {
+            assert_matches!(result, Err(Error::UnexpectedError(_)));
+            let error_notification = verify_error_notification(
+                &mut error_listener,
+                notification_id,
+                ErrorSource::ContinuousSyncer,
+                ErrorSeverity::Recoverable,
+            )
+            .await;
+            assert_eq!(error_notification.version_range, Some((1, 1)));
+        }
+        verify_no_pending_data(&storage_synchronizer);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_submit_transaction_chunk_overlap() {
+    // Create test data for a chunk (versions 10 to 11) that overlaps storage
+    let mut transaction_list_with_proof = create_transaction_chunk(10);
+    transaction_list_with_proof
+        .transactions
+        .push(create_transaction());
+
+    // Setup the mock executor to block the first chunk (so that it stays in flight)
+    let (mut chunk_executor, execute_release_sender) = create_gated_executor();
+    chunk_executor
+        .expect_commit_chunk()
+        .returning(|| Ok(create_commit_notification(&[])));
+
+    // Create the storage synchronizer (synced to version 10)
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        chunk_executor,
+        create_mock_reader_writer_at_version(10, create_mock_db_writer()),
+    );
+
+    // Submit a chunk that overlaps storage and verify it's rejected
+    storage_synchronizer
+        .submit_transaction_chunk(
+            0,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(transaction_list_with_proof),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap_err();
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        0,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    assert_eq!(error_notification.expected_version, Some(11));
+    assert_eq!(error_notification.version_range, Some((10, 11)));
+
+    // Submit a valid chunk, followed by a chunk that overlaps the in-flight chunk
+    for (notification_id, first_version) in [(1, 11), (2, 12)] {
+        let result = storage_synchronizer.submit_transaction_chunk(
+            notification_id,
+            ErrorSource::ContinuousSyncer,
+            ApplyOrExecute::Execute,
+            TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                vec![create_transaction(), create_transaction()],
+                None,
+                Some(first_version),
+                create_transaction_info_list_with_proof(),
+            )),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        );
+        assert_eq!(result.is_ok(), notification_id == 1);
+    }
+
+    // Verify the expected version accounts for the in-flight chunk
+    let error_notification = verify_error_notification(
+        &mut error_listener,
+        2,
+        ErrorSource::ContinuousSyncer,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+    assert_eq!(error_notification.expected_version, Some(13));
+    assert_eq!(error_notification.version_range, Some((12, 13)));
+
+    // Release the executor and verify the pipeline drains
+    drop(execute_release_sender);
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transaction_chunk_latency_metrics() {
+    for apply_or_execute in [ApplyOrExecute::Apply, ApplyOrExecute::Execute] {
+        // Create test data
+        let output_list_with_proof = create_output_chunk(1);
+        let transactions: Vec<Transaction> = output_list_with_proof
+            .transactions_and_outputs
+            .iter()
+            .map(|(transaction, _)| transaction.clone())
+            .collect();
+        let num_versions = transactions.len() as u64;
+
+        // Setup the mock executor to take time to execute/apply and commit the chunk
+        let stage_latency = Duration::from_millis(20);
+        let mut chunk_executor = create_mock_executor();
+        chunk_executor
+            .expect_apply_chunk()
+            .returning(move |_, _, _| {
+                std::thread::sleep(stage_latency);
+                Ok(())
+            });
+        chunk_executor
+            .expect_execute_chunk()
+            .returning(move |_, _, _| {
+                std::thread::sleep(stage_latency);
+                Ok(())
+            });
+        let committed_transactions = transactions.clone();
+        chunk_executor
+            .expect_commit_chunk()
+            .times(1)
+            .returning(move || {
+                std::thread::sleep(stage_latency);
+                Ok(create_commit_notification(&committed_transactions))
+            });
+
+        // Create the storage synchronizer (where the chunk is slow)
+        let driver_config = StateSyncDriverConfig {
+            slow_chunk_latency_threshold_ms: stage_latency.as_millis() as u64,
+            ..Default::default()
+        };
+        let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+            create_storage_synchronizer_with_tracker(
+                chunk_executor,
+                driver_config,
+                create_mock_reader_writer(None, None),
+                CommitStateTracker::new(TimeService::mock()),
+            );
+
+        // Read the metrics before storing the chunk
+        let num_execute_samples = read_transaction_chunk_latency_samples(
+            &metrics::TRANSACTION_CHUNK_EXECUTE_LATENCY,
+            apply_or_execute,
+            num_versions,
+        );
+        let num_commit_samples = read_transaction_chunk_latency_samples(
+            &metrics::TRANSACTION_CHUNK_COMMIT_LATENCY,
+            apply_or_execute,
+            num_versions,
+        );
+        let num_slow_chunks = read_slow_transaction_chunks();
+
+        // Submit the chunk and wait for it to be committed
+        storage_synchronizer
+            .submit_transaction_chunk(
+                0,
+                ErrorSource::ContinuousSyncer,
+                apply_or_execute,
+                TransactionChunkPayload::TransactionOutputs(output_list_with_proof),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+        verify_mempool_and_event_notification(None, &mut mempool_listener, transactions, vec![])
+            .await;
+        verify_no_pending_data(&storage_synchronizer);
+
+        // Verify latency samples were recorded for both stages (and the slow chunk was logged)
+        assert!(
+            read_transaction_chunk_latency_samples(
+                &metrics::TRANSACTION_CHUNK_EXECUTE_LATENCY,
+                apply_or_execute,
+                num_versions,
+            ) > num_execute_samples
+        );
+        assert!(
+            read_transaction_chunk_latency_samples(
+                &metrics::TRANSACTION_CHUNK_COMMIT_LATENCY,
+                apply_or_execute,
+                num_versions,
+            ) > num_commit_samples
+        );
+        assert!(read_slow_transaction_chunks() > num_slow_chunks);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_concurrency_config_extremes() {
+    // Create a config for each set of extreme concurrency values
+    let driver_configs = vec![
+        (1, 1, 1, 1),
+        (MAX_STAGE_THREADS, 1, MAX_STAGE_CHANNEL_DEPTH, 1),
+        (1, MAX_STAGE_THREADS, 1, MAX_STAGE_CHANNEL_DEPTH),
+    ]
+    .into_iter()
+    .map(
+        |(
+            num_executor_threads,
+            num_committer_threads,
+            max_pending_data_chunks,
+            max_pending_executed_chunks,
+        )| StateSyncDriverConfig {
+            max_pending_data_chunks,
+            max_pending_executed_chunks,
+            num_committer_threads,
+            num_executor_threads,
+            ..Default::default()
+        },
+    );
+
+    for driver_config in driver_configs {
+        verify_concurrency_config(&driver_config).unwrap();
+
+        // Create test data
+        let transactions: Vec<Transaction> = (0..5).map(|_| create_transaction()).collect();
+
+        // Setup the mock executor to commit each chunk in order
+        let mut chunk_executor = create_mock_executor();
+        chunk_executor
+            .expect_execute_chunk()
+            .with(always(), always(), always())
+            .returning(|_, _, _| Ok(()));
+        let transactions_clone = transactions.clone();
+        let mut num_committed_chunks = 0;
+        chunk_executor
+            .expect_commit_chunk()
+            .times(transactions.len())
+            .returning(move || {
+                let transaction = transactions_clone[num_committed_chunks].clone();
+                num_committed_chunks += 1;
+                Ok(create_commit_notification(&[transaction]))
+            });
+
+        // Create the storage synchronizer
+        let (_, mut error_listener, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+            create_storage_synchronizer_with_tracker(
+                chunk_executor,
+                driver_config,
+                create_mock_reader_writer(None, None),
+                CommitStateTracker::new(TimeService::mock()),
+            );
+
+        // Execute the chunk sequence and verify each chunk is committed in order.
+        // Note: each chunk is only sent once the previous chunk has been
+        // committed (so that the smallest channels never overflow).
+        let target_ledger_info = create_ledger_info_at_version(100);
+        for (version, transaction) in (1..).zip(transactions) {
+            storage_synchronizer
+                .submit_transaction_chunk(
+                    version,
+                    ErrorSource::ContinuousSyncer,
+                    ApplyOrExecute::Execute,
+                    TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                        vec![transaction.clone()],
+                        None,
+                        Some(version),
+                        create_transaction_info_list_with_proof(),
+                    )),
+                    target_ledger_info.clone(),
+                    None,
+                    None,
+                )
+                .unwrap();
+            verify_mempool_and_event_notification(
+                None,
+                &mut mempool_listener,
+                vec![transaction],
+                vec![],
+            )
+            .await;
+        }
+        verify_no_pending_data(&storage_synchronizer);
+        assert_none!(error_listener.select_next_some().now_or_never());
+    }
+}
+
+#[test]
+fn test_concurrency_config_verification() {
+    // Verify the default config is valid
+    let driver_config = StateSyncDriverConfig::default();
+    verify_concurrency_config(&driver_config).unwrap();
+
+    // Verify zero and overly large values are rejected for each setting
+    let invalid_configs = [
+        StateSyncDriverConfig {
+            num_executor_threads: 0,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            num_executor_threads: MAX_STAGE_THREADS + 1,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            num_committer_threads: 0,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            num_committer_threads: MAX_STAGE_THREADS + 1,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            max_pending_data_chunks: 0,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            max_pending_data_chunks: MAX_STAGE_CHANNEL_DEPTH + 1,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            max_pending_executed_chunks: 0,
+            ..driver_config
+        },
+        StateSyncDriverConfig {
+            max_pending_executed_chunks: MAX_STAGE_CHANNEL_DEPTH + 1,
+            ..driver_config
+        },
+    ];
+    for invalid_config in invalid_configs {
+        assert_matches!(
+            verify_concurrency_config(&invalid_config),
+            Err(Error::UnexpectedError(_))
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_committed_bytes_metrics() {
+    // Setup the mock executor to commit each applied chunk
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_apply_chunk()
+        .returning(|_, _, _| Ok(()));
+    chunk_executor.expect_commit_chunk().returning(|| {
+        let transactions: Vec<Transaction> = create_output_chunk(0)
+            .transactions_and_outputs
+            .into_iter()
+            .map(|(transaction, _)| transaction)
+            .collect();
+        Ok(create_commit_notification(&transactions))
+    });
+
+    // Create the storage synchronizer
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Apply several output chunks and verify the committed bytes grow with each commit
+    for version in 1..=3 {
+        // Read the metrics before committing the chunk
+        let transaction_bytes = read_committed_bytes(metrics::CommittedDataType::Transactions);
+        let output_bytes = read_committed_bytes(metrics::CommittedDataType::Outputs);
+        let num_transaction_commits =
+            read_commit_bytes_samples(metrics::CommittedDataType::Transactions);
+        let num_output_commits = read_commit_bytes_samples(metrics::CommittedDataType::Outputs);
+
+        // Submit the chunk and wait for it to be committed
+        let output_list_with_proof = create_output_chunk(version);
+        let transactions: Vec<Transaction> = output_list_with_proof
+            .transactions_and_outputs
+            .iter()
+            .map(|(transaction, _)| transaction.clone())
+            .collect();
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Apply,
+                TransactionChunkPayload::TransactionOutputs(output_list_with_proof),
+                create_epoch_ending_ledger_info(),
+                None,
+                None,
+            )
+            .unwrap();
+        verify_mempool_and_event_notification(None, &mut mempool_listener, transactions, vec![])
+            .await;
+        verify_no_pending_data(&storage_synchronizer);
+
+        // Verify the total bytes increased and the per-commit sizes were recorded
+        assert!(read_committed_bytes(metrics::CommittedDataType::Transactions) > transaction_bytes);
+        assert!(read_committed_bytes(metrics::CommittedDataType::Outputs) > output_bytes);
+        assert!(
+            read_commit_bytes_samples(metrics::CommittedDataType::Transactions)
+                > num_transaction_commits
+        );
+        assert!(
+            read_commit_bytes_samples(metrics::CommittedDataType::Outputs) > num_output_commits
+        );
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_committed_version_handle() {
+    // Setup the mock executor (each commit advances the synced version in storage)
+    let storage_synced_version = Arc::new(AtomicU64::new(0));
+    let storage_synced_version_clone = storage_synced_version.clone();
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_apply_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    chunk_executor.expect_commit_chunk().returning(move || {
+        storage_synced_version_clone.fetch_add(1, Ordering::SeqCst);
+        Ok(create_commit_notification(&[]))
+    });
+
+    // Setup the mock reader (that reads the synced version from storage)
+    let mut db_reader = create_mock_db_reader();
+    let storage_synced_version_clone = storage_synced_version.clone();
+    db_reader
+        .expect_get_latest_transaction_info_option()
+        .returning(move || {
+            let synced_version = storage_synced_version_clone.load(Ordering::SeqCst);
+            Ok(Some((synced_version, create_transaction_info())))
+        });
+
+    // Create the storage synchronizer and verify the handle is initialized from storage
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        chunk_executor,
+        create_mock_reader_writer(Some(db_reader), None),
+    );
+    let committed_version_handle = storage_synchronizer.committed_version_handle();
+    assert_eq!(committed_version_handle.get_committed_version(), 0);
+    assert_eq!(committed_version_handle.get_committed_epoch(), 0);
+
+    // Commit a series of chunks and verify the handle is consistent with storage
+    let epoch = 3;
+    for version in 1..=5 {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Apply,
+                TransactionChunkPayload::TransactionOutputs(create_output_chunk(version)),
+                create_signed_ledger_info(&create_validator_signers(0), version, epoch),
+                None,
+                None,
+            )
+            .unwrap();
+        verify_no_pending_data(&storage_synchronizer);
+        assert_eq!(
+            committed_version_handle.get_committed_version(),
+            storage_synced_version.load(Ordering::SeqCst)
+        );
+        assert_eq!(committed_version_handle.get_committed_version(), version);
+        assert_eq!(committed_version_handle.get_committed_epoch(), epoch);
+    }
+
+    // Verify the handle never moves backwards
+    committed_version_handle.update(2, 1);
+    assert_eq!(committed_version_handle.get_committed_version(), 5);
+    assert_eq!(committed_version_handle.get_committed_epoch(), epoch);
+}
+
+#[test]
+fn test_committed_version_handle_empty_storage() {
+    // Create a handle from an empty storage (i.e., pre-genesis)
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_latest_transaction_info_option()
+        .returning(|| Ok(None));
+    let committed_version_handle =
+        CommittedVersionHandle::new_from_storage(Arc::new(db_reader)).unwrap();
+
+    // Verify nothing has been committed
+    assert_eq!(
+        committed_version_handle.get_highest_committed_version(),
+        None
+    );
+    assert_eq!(
+        committed_version_handle.get_committed_version(),
+        GENESIS_TRANSACTION_VERSION
+    );
+
+    // Commit genesis and verify the handle reports the committed version
+    committed_version_handle.update(GENESIS_TRANSACTION_VERSION, 0);
+    assert_eq!(
+        committed_version_handle.get_highest_committed_version(),
+        Some(GENESIS_TRANSACTION_VERSION)
+    );
+
+    // Verify a handle created from a non-empty storage reports its version
+    let committed_version_handle = CommittedVersionHandle::new(10, 1);
+    assert_eq!(
+        committed_version_handle.get_highest_committed_version(),
+        Some(10)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_post_commit_hooks() {
+    // Create test data
+    let transaction_to_commit = create_transaction();
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_apply_chunk()
+        .with(always(), always(), always())
+        .returning(|_, _, _| Ok(()));
+    let expected_commit_return = Ok(create_commit_notification(&[transaction_to_commit.clone()]));
+    chunk_executor
+        .expect_commit_chunk()
+        .return_once(move || expected_commit_return);
+
+    // Create the storage synchronizer
+    let (_, _, _, mut mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Register a panicking hook, followed by a slow hook (that records the committed versions)
+    let panicking_hook_name = "test_post_commit_hooks_panicking";
+    let slow_hook_name = "test_post_commit_hooks_slow";
+    let slow_hook_duration = Duration::from_millis(200);
+    let committed_versions = Arc::new(Mutex::new(vec![]));
+    let committed_versions_clone = committed_versions.clone();
+    storage_synchronizer.register_post_commit_hook(
+        panicking_hook_name,
+        Box::new(|_| panic!("The post-commit hook failed!")),
+    );
+    storage_synchronizer.register_post_commit_hook(
+        slow_hook_name,
+        Box::new(move |committed_transactions| {
+            std::thread::sleep(slow_hook_duration);
+            committed_versions_clone.lock().push((
+                committed_transactions.first_version,
+                committed_transactions.last_version,
+            ));
+        }),
+    );
+
+    // Apply a chunk of outputs
+    storage_synchronizer
+        .submit_transaction_chunk(
+            0,
+            ErrorSource::Bootstrapper,
+            ApplyOrExecute::Apply,
+            TransactionChunkPayload::TransactionOutputs(create_output_chunk(1)),
+            create_epoch_ending_ledger_info(),
+            None,
+            None,
+        )
+        .unwrap();
+
+    // Verify mempool is still notified (despite the panicking hook)
+    verify_mempool_and_event_notification(
+        None,
+        &mut mempool_listener,
+        vec![transaction_to_commit],
+        vec![],
+    )
+    .await;
+    verify_no_pending_data(&storage_synchronizer);
+
+    // Verify the slow hook ran (before the notification was sent)
+    assert_eq!(committed_versions.lock().clone(), vec![(1, 1)]);
+
+    // Verify the hook metrics were updated
+    let panicking_hook_panics = DriverMetrics::default()
+        .counter(&metrics::POST_COMMIT_HOOK_PANICS, &[panicking_hook_name])
+        .get();
+    assert_eq!(panicking_hook_panics, 1);
+    let slow_hook_latency =
+        DriverMetrics::default().histogram(&metrics::POST_COMMIT_HOOK_LATENCY, &[slow_hook_name]);
+    assert_eq!(slow_hook_latency.get_sample_count(), 1);
+    assert!(slow_hook_latency.get_sample_sum() >= slow_hook_duration.as_secs_f64());
+    assert_eq!(
+        DriverMetrics::default()
+            .counter(&metrics::POST_COMMIT_HOOK_PANICS, &[slow_hook_name])
+            .get(),
+        0
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_completion() {
+    // Create test data
     let target_ledger_info = create_epoch_ending_ledger_info();
     let epoch_change_proofs = [
         create_epoch_ending_ledger_info(),
@@ -298,17 +2770,443 @@ async fn test_save_states_completion() {
     ];
     let output_list_with_proof = create_output_list_with_proof();
 
-    // Setup the mock snapshot receiver
+    // Setup the mock snapshot receiver
+    let mut snapshot_receiver = create_mock_receiver();
+    snapshot_receiver
+        .expect_add_chunk()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
+    snapshot_receiver.expect_finish_box().returning(|| Ok(()));
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor.expect_reset().returning(|| Ok(()));
+
+    // Setup the mock db writer
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+    let target_ledger_info_clone = target_ledger_info.clone();
+    let output_list_with_proof_clone = output_list_with_proof.clone();
+    let epoch_change_proofs_clone = epoch_change_proofs.clone();
+    db_writer
+        .expect_finalize_state_snapshot()
+        .withf(
+            move |version: &Version,
+                  output_with_proof: &TransactionOutputListWithProof,
+                  ledger_infos: &[LedgerInfoWithSignatures]| {
+                version == &target_ledger_info_clone.ledger_info().version()
+                    && output_with_proof == &output_list_with_proof_clone
+                    && ledger_infos == epoch_change_proofs_clone
+            },
+        )
+        .returning(|_, _, _| Ok(()));
+
+    // Create the storage synchronizer
+    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(
+            chunk_executor,
+            create_mock_reader_writer(None, Some(db_writer)),
+        );
+
+    // Subscribe to the expected event
+    let expected_event = output_list_with_proof.transactions_and_outputs[0]
+        .1
+        .events()[0]
+        .clone();
+
+    // Initialize the state synchronizer
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            epoch_change_proofs.to_vec(),
+            target_ledger_info,
+            output_list_with_proof.clone(),
+        )
+        .unwrap();
+
+    // Save multiple state chunks (including the last chunk)
+    storage_synchronizer
+        .save_state_values_with_proof(0, create_state_value_chunk_with_proof(false), false)
+        .unwrap();
+    storage_synchronizer
+        .save_state_values_with_proof(1, create_state_value_chunk_with_proof(true), false)
+        .unwrap();
+
+    // Verify we get a commit notification
+    let expected_transaction = output_list_with_proof.transactions_and_outputs[0].0.clone();
+    let expected_committed_transactions = CommittedTransactions {
+        committed_chunks: vec![],
+        ends_epoch: false,
+        events: vec![expected_event.clone()],
+        first_version: 0,
+        last_version: 0,
+        new_epoch_state: None,
+        post_reset: false,
+        transactions: vec![expected_transaction.clone()],
+    };
+
+    verify_snapshot_commit_notification(
+        &mut commit_listener,
+        expected_committed_transactions.clone(),
+    )
+    .await;
+
+    // The handler should return as we've finished writing all states
+    state_synchronizer_handle.await.unwrap();
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_pruning_pause() {
+    // Create test data
+    let target_ledger_info = create_epoch_ending_ledger_info();
+    let epoch_change_proofs = vec![target_ledger_info.clone()];
+    let output_list_with_proof = create_output_list_with_proof();
+
+    // Setup the mock snapshot receiver and db writer (that record all storage operations)
+    let storage_operations = Arc::new(Mutex::new(vec![]));
+    let mut snapshot_receiver = create_mock_receiver();
+    let storage_operations_clone = storage_operations.clone();
+    snapshot_receiver.expect_finish_box().returning(move || {
+        storage_operations_clone.lock().push("finish_box");
+        Ok(())
+    });
+    let mut db_writer = create_recording_db_writer(storage_operations.clone(), snapshot_receiver);
+    let storage_operations_clone = storage_operations.clone();
+    db_writer
+        .expect_finalize_state_snapshot()
+        .returning(move |_, _, _| {
+            storage_operations_clone
+                .lock()
+                .push("finalize_state_snapshot");
+            Ok(())
+        });
+
+    // Setup the mock executor
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor.expect_reset().returning(|| Ok(()));
+
+    // Create the storage synchronizer
+    let (_commit_listener, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        chunk_executor,
+        create_mock_reader_writer(None, Some(db_writer)),
+    );
+
+    // Initialize the state synchronizer and save all state chunks
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            epoch_change_proofs,
+            target_ledger_info,
+            output_list_with_proof,
+        )
+        .unwrap();
+    storage_synchronizer
+        .save_state_values_with_proof(0, create_state_value_chunk_with_proof(false), false)
+        .unwrap();
+    storage_synchronizer
+        .save_state_values_with_proof(1, create_state_value_chunk_with_proof(true), false)
+        .unwrap();
+    state_synchronizer_handle.await.unwrap();
+
+    // Verify pruning was paused for exactly the snapshot sync
+    assert_eq!(
+        storage_operations.lock().clone(),
+        vec![
+            "pause_pruning",
+            "get_state_snapshot_receiver",
+            "add_chunk",
+            "add_chunk",
+            "finish_box",
+            "finalize_state_snapshot",
+            "resume_pruning",
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_pruning_pause_watchdog() {
+    // Setup the mock db writer (that records all storage operations)
+    let storage_operations = Arc::new(Mutex::new(vec![]));
+    let db_writer = create_recording_db_writer(storage_operations.clone(), create_mock_receiver());
+
+    // Create a storage synchronizer that quickly releases idle pruning pauses
+    let driver_config = StateSyncDriverConfig {
+        max_pruning_pause_idle_secs: 1,
+        ..Default::default()
+    };
+    let (_commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            create_mock_executor(),
+            driver_config,
+            create_mock_reader_writer(None, Some(db_writer)),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Initialize the state synchronizer and verify pruning is resumed when no chunks arrive
+    let num_expired_pauses = read_expired_pruning_pauses();
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+    wait_for_storage_operations(&storage_operations, 3).await;
+    assert!(read_expired_pruning_pauses() > num_expired_pauses);
+
+    // Save a state chunk and verify pruning is paused again
+    storage_synchronizer
+        .save_state_values_with_proof(0, create_state_value_chunk_with_proof(false), false)
+        .unwrap();
+    wait_for_storage_operations(&storage_operations, 5).await;
+
+    // Abandon the snapshot sync and verify pruning is resumed
+    drop(storage_synchronizer);
+    state_synchronizer_handle.await.unwrap();
+    assert_eq!(
+        storage_operations.lock().clone(),
+        vec![
+            "pause_pruning",
+            "get_state_snapshot_receiver",
+            "resume_pruning",
+            "pause_pruning",
+            "add_chunk",
+            "resume_pruning",
+        ]
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_chunk_commit_notification() {
+    // Setup the mock snapshot receiver (that reports the chunk verification time)
+    let verification_duration = Duration::from_millis(25);
+    let mut snapshot_receiver = MockSnapshotReceiver::new();
+    snapshot_receiver
+        .expect_add_chunk()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
+    snapshot_receiver
+        .expect_last_chunk_verification_duration()
+        .returning(move || Some(verification_duration));
+
+    // Setup the mock db writer
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+
+    // Create the storage synchronizer
+    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(
+            create_mock_executor(),
+            create_mock_reader_writer(None, Some(db_writer)),
+        );
+
+    // Initialize the state synchronizer
+    let target_ledger_info = create_epoch_ending_ledger_info();
+    let _ = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            target_ledger_info.clone(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+
+    // Save a state chunk (that isn't the last chunk) with several state values
+    let mut states_with_proof = create_state_value_chunk_with_proof(false);
+    states_with_proof.raw_values = vec![
+        (StateKey::Raw(vec![0; 10]), StateValue::new(vec![1; 100])),
+        (StateKey::Raw(vec![2; 20]), StateValue::new(vec![3; 200])),
+    ];
+    let last_committed_state_index = states_with_proof.last_index;
+    let proven_root_hash = HashValue::random();
+    states_with_proof.root_hash = proven_root_hash;
+    storage_synchronizer
+        .save_state_values_with_proof(0, states_with_proof, false)
+        .unwrap();
+
+    // Verify we get a chunk commit notification with the expected fields
+    let commit_notification = commit_listener.select_next_some().await;
+    let committed_chunk = match commit_notification {
+        CommitNotification::CommittedStateValueChunk(committed_chunk) => committed_chunk,
+        commit_notification => panic!("Unexpected commit notification: {:?}", commit_notification),
+    };
+    assert_eq!(committed_chunk.num_state_values, 2);
+    assert_eq!(committed_chunk.num_bytes, 330);
+    assert_eq!(
+        committed_chunk.last_committed_state_index,
+        last_committed_state_index
+    );
+    assert_eq!(
+        committed_chunk.version,
+        target_ledger_info.ledger_info().version()
+    );
+    assert_eq!(committed_chunk.proven_root_hash, proven_root_hash);
+    assert_eq!(committed_chunk.verification_duration, verification_duration);
+    verify_no_pending_data(&storage_synchronizer);
+
+    // Verify the chunk verification time flows through to the metrics
+    let driver_metrics = DriverMetrics::default();
+    let verification_latency =
+        driver_metrics.histogram(&metrics::STATE_VALUE_CHUNK_VERIFICATION_LATENCY, &[]);
+    let num_verification_samples = verification_latency.get_sample_count();
+    let verification_secs = verification_latency.get_sample_sum();
+    driver_metrics.observe_state_value_chunk(
+        committed_chunk.num_state_values,
+        committed_chunk.num_bytes,
+        committed_chunk.apply_duration,
+        committed_chunk.verification_duration,
+    );
+    assert!(verification_latency.get_sample_count() > num_verification_samples);
+    assert!(
+        verification_latency.get_sample_sum() - verification_secs
+            >= verification_duration.as_secs_f64() * 0.99
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_chunk_progress_after_restart() {
+    // Create test data
+    let target_ledger_info = create_epoch_ending_ledger_info();
+    let last_committed_chunk_index = 3;
+    let tmp_dir = TempPath::new();
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+
+    // Start the storage synchronizer and commit chunks 0 to K
+    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_metadata(
+            create_mock_executor(),
+            create_mock_reader_writer(None, Some(create_snapshot_db_writer())),
+            metadata_storage.clone(),
+        );
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            target_ledger_info.clone(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+    for chunk_index in 0..=last_committed_chunk_index {
+        save_state_value_chunk(&mut storage_synchronizer, &mut commit_listener, chunk_index).await;
+    }
+
+    // Kill the storage synchronizer after chunk K (and wait for the receiver to stop)
+    drop(storage_synchronizer);
+    state_synchronizer_handle.await.unwrap();
+    drop(metadata_storage);
+
+    // Restart and verify the reported resume index is exactly K
+    let metadata_storage = PersistentMetadataStorage::new(tmp_dir.path());
+    let snapshot_chunk_progress = metadata_storage
+        .get_snapshot_chunk_progress()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        snapshot_chunk_progress.target_ledger_info,
+        target_ledger_info
+    );
+    assert_eq!(
+        snapshot_chunk_progress.last_committed_chunk_index,
+        last_committed_chunk_index
+    );
+    assert_eq!(
+        snapshot_chunk_progress.last_persisted_state_value_index,
+        get_last_state_value_index(last_committed_chunk_index)
+    );
+    assert_eq!(
+        metadata_storage
+            .get_last_persisted_state_value_index(&target_ledger_info)
+            .unwrap(),
+        get_last_state_value_index(last_committed_chunk_index)
+    );
+
+    // Resume the snapshot sync and verify the next chunk continues from K
+    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_metadata(
+            create_mock_executor(),
+            create_mock_reader_writer(None, Some(create_snapshot_db_writer())),
+            metadata_storage.clone(),
+        );
+    let _ = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            target_ledger_info.clone(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+    save_state_value_chunk(
+        &mut storage_synchronizer,
+        &mut commit_listener,
+        last_committed_chunk_index + 1,
+    )
+    .await;
+    assert_eq!(
+        metadata_storage
+            .get_snapshot_chunk_progress()
+            .unwrap()
+            .unwrap()
+            .last_committed_chunk_index,
+        last_committed_chunk_index + 1
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic]
+async fn test_save_states_dropped_error_listener() {
+    // Setup the mock snapshot receiver
+    let mut snapshot_receiver = create_mock_receiver();
+    snapshot_receiver
+        .expect_add_chunk()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
+
+    // Setup the mock db writer
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+
+    // Create the storage synchronizer (drop all listeners)
+    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, Some(db_writer)),
+    );
+
+    // Initialize the state synchronizer
+    let state_synchronizer_handle = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+
+    // Save the last state chunk
+    let notification_id = 0;
+    storage_synchronizer
+        .save_state_values_with_proof(
+            notification_id,
+            create_state_value_chunk_with_proof(true),
+            false,
+        )
+        .unwrap();
+
+    // The handler should panic as the commit listener was dropped
+    state_synchronizer_handle.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_invalid_chunk() {
+    // Setup the mock snapshot receiver to always return errors
     let mut snapshot_receiver = create_mock_receiver();
     snapshot_receiver
         .expect_add_chunk()
         .with(always(), always())
-        .returning(|_, _| Ok(()));
-    snapshot_receiver.expect_finish_box().returning(|| Ok(()));
-
-    // Setup the mock executor
-    let mut chunk_executor = create_mock_executor();
-    chunk_executor.expect_reset().returning(|| Ok(()));
+        .returning(|_, _| Err(format_err!("Invalid chunk!")));
 
     // Setup the mock db writer
     let mut db_writer = create_mock_db_writer();
@@ -316,78 +3214,160 @@ async fn test_save_states_completion() {
         .expect_get_state_snapshot_receiver()
         .with(always(), always())
         .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
-    let target_ledger_info_clone = target_ledger_info.clone();
-    let output_list_with_proof_clone = output_list_with_proof.clone();
-    let epoch_change_proofs_clone = epoch_change_proofs.clone();
-    db_writer
-        .expect_finalize_state_snapshot()
-        .withf(
-            move |version: &Version,
-                  output_with_proof: &TransactionOutputListWithProof,
-                  ledger_infos: &[LedgerInfoWithSignatures]| {
-                version == &target_ledger_info_clone.ledger_info().version()
-                    && output_with_proof == &output_list_with_proof_clone
-                    && ledger_infos == epoch_change_proofs_clone
-            },
-        )
-        .returning(|_, _, _| Ok(()));
 
     // Create the storage synchronizer
-    let (mut commit_listener, _, _, _, mut storage_synchronizer, _, _) =
-        create_storage_synchronizer(
-            chunk_executor,
-            create_mock_reader_writer(None, Some(db_writer)),
-        );
-
-    // Subscribe to the expected event
-    let expected_event = output_list_with_proof.transactions_and_outputs[0]
-        .1
-        .events()[0]
-        .clone();
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, Some(db_writer)),
+    );
 
     // Initialize the state synchronizer
-    let state_synchronizer_handle = storage_synchronizer
+    let _ = storage_synchronizer
         .initialize_state_synchronizer(
-            epoch_change_proofs.to_vec(),
-            target_ledger_info,
-            output_list_with_proof.clone(),
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            create_output_list_with_proof(),
         )
         .unwrap();
 
-    // Save multiple state chunks (including the last chunk)
-    storage_synchronizer
-        .save_state_values(0, create_state_value_chunk_with_proof(false))
-        .unwrap();
+    // Save a state chunk and verify we get an error notification
+    let notification_id = 0;
     storage_synchronizer
-        .save_state_values(1, create_state_value_chunk_with_proof(true))
+        .save_state_values_with_proof(
+            notification_id,
+            create_state_value_chunk_with_proof(false),
+            false,
+        )
         .unwrap();
+    verify_error_notification(
+        &mut error_listener,
+        notification_id,
+        ErrorSource::Bootstrapper,
+        ErrorSeverity::Recoverable,
+    )
+    .await;
+}
 
-    // Verify we get a commit notification
-    let expected_transaction = output_list_with_proof.transactions_and_outputs[0].0.clone();
-    let expected_committed_transactions = CommittedTransactions {
-        events: vec![expected_event.clone()],
-        transactions: vec![expected_transaction.clone()],
-    };
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_transient_error() {
+    // Setup the mock snapshot receiver to expect two chunks
+    let mut snapshot_receiver = create_mock_receiver();
+    snapshot_receiver
+        .expect_add_chunk()
+        .with(always(), always())
+        .times(2)
+        .returning(|_, _| Ok(()));
 
-    verify_snapshot_commit_notification(
-        &mut commit_listener,
-        expected_committed_transactions.clone(),
+    // Setup the mock db writer
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+
+    // Create the storage synchronizer (drop the commit listener so
+    // that all chunk commit notifications fail to send).
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
+        create_mock_executor(),
+        create_mock_reader_writer(None, Some(db_writer)),
+    );
+
+    // Initialize the state synchronizer
+    let _ = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            create_output_list_with_proof(),
+        )
+        .unwrap();
+
+    // Save a state chunk and verify we get a transient error notification
+    storage_synchronizer
+        .save_state_values_with_proof(0, create_state_value_chunk_with_proof(false), false)
+        .unwrap();
+    verify_error_notification(
+        &mut error_listener,
+        0,
+        ErrorSource::Bootstrapper,
+        ErrorSeverity::Transient,
     )
     .await;
 
-    // The handler should return as we've finished writing all states
-    state_synchronizer_handle.await.unwrap();
+    // Verify the state snapshot receiver is still alive and commits the next chunk
+    storage_synchronizer
+        .save_state_values_with_proof(1, create_state_value_chunk_with_proof(false), false)
+        .unwrap();
+    verify_error_notification(
+        &mut error_listener,
+        1,
+        ErrorSource::Bootstrapper,
+        ErrorSeverity::Transient,
+    )
+    .await;
     verify_no_pending_data(&storage_synchronizer);
 }
 
 #[tokio::test(flavor = "multi_thread")]
-#[should_panic]
-async fn test_save_states_dropped_error_listener() {
-    // Setup the mock snapshot receiver
+async fn test_save_states_verification_mismatch() {
+    for verify_in_storage_synchronizer in [false, true] {
+        // Setup the mock snapshot receiver (no chunks should be written)
+        let mut snapshot_receiver = create_mock_receiver();
+        snapshot_receiver.expect_add_chunk().times(0);
+
+        // Setup the mock db writer
+        let mut db_writer = create_mock_db_writer();
+        db_writer
+            .expect_get_state_snapshot_receiver()
+            .with(always(), always())
+            .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+
+        // Create the storage synchronizer
+        let driver_config = StateSyncDriverConfig {
+            verify_state_values_in_storage_synchronizer: verify_in_storage_synchronizer,
+            ..Default::default()
+        };
+        let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer_with_tracker(
+            create_mock_executor(),
+            driver_config,
+            create_mock_reader_writer(None, Some(db_writer)),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+        assert_eq!(
+            storage_synchronizer.verifies_state_value_chunks(),
+            verify_in_storage_synchronizer
+        );
+
+        // Initialize the state synchronizer
+        let _ = storage_synchronizer
+            .initialize_state_synchronizer(
+                vec![create_epoch_ending_ledger_info()],
+                create_epoch_ending_ledger_info(),
+                create_output_list_with_proof(),
+            )
+            .unwrap();
+
+        // Save a state chunk that would be verified twice (or never) and
+        // verify the chunk is rejected.
+        let error = storage_synchronizer
+            .save_state_values_with_proof(
+                0,
+                create_state_value_chunk_with_proof(false),
+                !verify_in_storage_synchronizer,
+            )
+            .unwrap_err();
+        assert_matches!(error, Error::UnexpectedError(_));
+        verify_no_pending_data(&storage_synchronizer);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_save_states_verified_by_storage_synchronizer() {
+    // Setup the mock snapshot receiver to expect only the valid chunk
     let mut snapshot_receiver = create_mock_receiver();
     snapshot_receiver
         .expect_add_chunk()
         .with(always(), always())
+        .times(1)
         .returning(|_, _| Ok(()));
 
     // Setup the mock db writer
@@ -397,85 +3377,406 @@ async fn test_save_states_dropped_error_listener() {
         .with(always(), always())
         .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
 
-    // Create the storage synchronizer (drop all listeners)
+    // Create the storage synchronizer (that verifies state value chunks)
+    let driver_config = StateSyncDriverConfig {
+        verify_state_values_in_storage_synchronizer: true,
+        ..Default::default()
+    };
+    let (mut commit_listener, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            create_mock_executor(),
+            driver_config,
+            create_mock_reader_writer(None, Some(db_writer)),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+
+    // Initialize the state synchronizer
+    let output_list_with_proof = create_output_list_with_proof();
+    let expected_root_hash = output_list_with_proof.proof.transaction_infos[0]
+        .ensure_state_checkpoint_hash()
+        .unwrap();
+    let _ = storage_synchronizer
+        .initialize_state_synchronizer(
+            vec![create_epoch_ending_ledger_info()],
+            create_epoch_ending_ledger_info(),
+            output_list_with_proof,
+        )
+        .unwrap();
+
+    // Save an invalid state chunk and verify it's rejected before it's written
+    storage_synchronizer
+        .save_state_values_with_proof(0, create_state_value_chunk_with_proof(false), true)
+        .unwrap();
+    let error_notification = error_listener.select_next_some().await;
+    assert_eq!(error_notification.notification_id, 0);
+    assert_eq!(error_notification.severity, ErrorSeverity::Recoverable);
+    assert_matches!(error_notification.error, Error::VerificationError(_));
+
+    // Save a valid state chunk and verify it's written (exactly once)
+    storage_synchronizer
+        .save_state_values_with_proof(
+            1,
+            create_verifiable_state_value_chunk(expected_root_hash),
+            true,
+        )
+        .unwrap();
+    match commit_listener.select_next_some().await {
+        CommitNotification::CommittedStateValueChunk(committed_chunk) => {
+            assert_eq!(committed_chunk.proven_root_hash, expected_root_hash);
+        }
+        commit_notification => panic!("Unexpected commit notification: {:?}", commit_notification),
+    }
+    verify_no_pending_data(&storage_synchronizer);
+}
+
+#[test]
+#[should_panic]
+fn test_save_states_without_initialize() {
+    // Create the storage synchronizer
     let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
         create_mock_executor(),
-        create_mock_reader_writer(None, Some(db_writer)),
+        create_mock_reader_writer(None, None),
+    );
+
+    // Attempting to save the states should panic as the state
+    // synchronizer was not initialized!
+    let _ = storage_synchronizer.save_state_values_with_proof(
+        0,
+        create_state_value_chunk_with_proof(false),
+        false,
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pending_data_notifications() {
+    // Setup the mock executor with an execution latency
+    let num_chunks = 3;
+    let transactions: Vec<Transaction> = (0..num_chunks).map(|_| create_transaction()).collect();
+    let chunk_executor = create_executor_with_latencies(
+        &transactions,
+        Duration::from_millis(20),
+        Duration::from_millis(0),
+        Arc::new(AtomicU64::new(0)),
+    );
+
+    // Create the storage synchronizer (and ack all mempool notifications)
+    let (_, _, _, mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+    spawn_mempool_acknowledger(mempool_listener);
+
+    // Subscribe to pending data notifications and submit all chunks
+    let mut pending_data_listener = storage_synchronizer.subscribe_to_pending_data();
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_epoch_ending_ledger_info(),
+    );
+
+    // Verify a notification is received whenever a chunk leaves the
+    // pipeline, until the storage synchronizer is idle.
+    while !storage_synchronizer.is_idle() {
+        tokio::time::timeout(Duration::from_secs(10), pending_data_listener.changed())
+            .await
+            .unwrap()
+            .unwrap();
+    }
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        num_chunks
     );
+}
+
+/// Creates a storage synchronizer for testing
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shutdown_drains_pending_chunks() {
+    // Setup the mock executor with an execution latency
+    let num_chunks = 5;
+    let transactions: Vec<Transaction> = (0..num_chunks).map(|_| create_transaction()).collect();
+    let chunk_executor = create_executor_with_latencies(
+        &transactions,
+        Duration::from_millis(20),
+        Duration::from_millis(0),
+        Arc::new(AtomicU64::new(0)),
+    );
+
+    // Create the storage synchronizer (and ack all mempool notifications)
+    let (_, _, _, mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+    spawn_mempool_acknowledger(mempool_listener);
+
+    // Submit all chunks and shut down the storage synchronizer
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_epoch_ending_ledger_info(),
+    );
+    let shutdown_summary = storage_synchronizer.shutdown();
+
+    // Verify all chunks were committed before the shutdown completed
+    assert_eq!(
+        shutdown_summary,
+        ShutdownSummary {
+            chunks_committed: num_chunks,
+            chunks_discarded: 0,
+            drained: true,
+        }
+    );
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        num_chunks
+    );
+    assert!(storage_synchronizer.is_idle());
+
+    // Verify new chunks are rejected
+    let result = storage_synchronizer.submit_transaction_chunk(
+        num_chunks + 1,
+        ErrorSource::ContinuousSyncer,
+        ApplyOrExecute::Execute,
+        TransactionChunkPayload::Transactions(create_transaction_chunk(num_chunks + 1)),
+        create_epoch_ending_ledger_info(),
+        None,
+        None,
+    );
+    assert_matches!(result, Err(Error::ShuttingDown(_)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_shutdown_drain_timeout() {
+    // Setup the mock executor with a commit latency (and count the commits)
+    let num_chunks = 5;
+    let transactions: Vec<Transaction> = (0..num_chunks).map(|_| create_transaction()).collect();
+    let num_commits = Arc::new(AtomicU64::new(0));
+    let chunk_executor = create_executor_with_latencies(
+        &transactions,
+        Duration::from_millis(0),
+        Duration::from_millis(100),
+        num_commits.clone(),
+    );
+
+    // Create the storage synchronizer with a drain timeout shorter than the
+    // time it takes to commit all chunks.
+    let driver_config = StateSyncDriverConfig {
+        max_shutdown_drain_ms: 250,
+        ..Default::default()
+    };
+    let (_, _, _, mempool_listener, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer_with_tracker(
+            chunk_executor,
+            driver_config,
+            create_mock_reader_writer(None, None),
+            CommitStateTracker::new(TimeService::mock()),
+        );
+    spawn_mempool_acknowledger(mempool_listener);
+
+    // Submit all chunks and shut down the storage synchronizer mid-pipeline
+    execute_transaction_chunks(
+        &mut storage_synchronizer,
+        &transactions,
+        create_epoch_ending_ledger_info(),
+    );
+    let shutdown_summary = storage_synchronizer.shutdown();
+
+    // Verify some chunks were committed, and the rest were discarded
+    assert!(!shutdown_summary.drained);
+    assert!(shutdown_summary.chunks_committed > 0);
+    assert!(shutdown_summary.chunks_discarded > 0);
+    assert_eq!(
+        shutdown_summary.chunks_committed + shutdown_summary.chunks_discarded,
+        num_chunks
+    );
+    assert!(storage_synchronizer.is_idle());
+
+    // Verify storage is consistent, i.e., exactly the committed chunks were
+    // written (in order) and nothing was committed after the shutdown.
+    assert_eq!(
+        num_commits.load(Ordering::Relaxed),
+        shutdown_summary.chunks_committed
+    );
+    assert_eq!(
+        storage_synchronizer
+            .committed_version_handle()
+            .get_committed_version(),
+        shutdown_summary.chunks_committed
+    );
+    std::thread::sleep(Duration::from_millis(200));
+    assert_eq!(
+        num_commits.load(Ordering::Relaxed),
+        shutdown_summary.chunks_committed
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_verify_ledger_infos_invalid_chunk() {
+    // Create test data (the second chunk is signed by unknown validators)
+    let epoch = 5;
+    let validator_signers = create_validator_signers(0);
+    let forged_signers = create_validator_signers(10);
+    let epoch_state = create_validator_epoch_state(&validator_signers, epoch);
+
+    // Setup the mock executor to block all executions until released (so
+    // that all chunks are queued and verified together).
+    let mut chunk_executor = create_mock_executor();
+    let (execute_release_sender, execute_release_receiver) = std::sync::mpsc::channel::<()>();
+    let execute_release_receiver = Mutex::new(execute_release_receiver);
+    chunk_executor
+        .expect_execute_chunk()
+        .times(3)
+        .returning(move |_, _, _| {
+            let _ = execute_release_receiver.lock().recv();
+            Ok(())
+        });
+    chunk_executor
+        .expect_commit_chunk()
+        .returning(|| Ok(create_commit_notification(&[])));
+
+    // Create the storage synchronizer
+    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) =
+        create_storage_synchronizer(chunk_executor, create_mock_reader_writer(None, None));
+
+    // Submit a chunk to block the executor, followed by three (queued) chunks
+    for version in 1..5 {
+        let ledger_info = if version == 3 {
+            create_signed_ledger_info(&forged_signers, version, epoch)
+        } else {
+            create_signed_ledger_info(&validator_signers, version, epoch)
+        };
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    vec![create_transaction()],
+                    None,
+                    Some(version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                ledger_info,
+                None,
+                Some(epoch_state.clone()),
+            )
+            .unwrap();
+    }
+
+    // Release the executor for all valid chunks
+    for _ in 0..3 {
+        execute_release_sender.send(()).unwrap();
+    }
+
+    // Verify only the chunk with the invalid ledger info fails
+    let error_notification = error_listener.select_next_some().await;
+    assert_eq!(error_notification.notification_id, 3);
+    assert_eq!(error_notification.component, ErrorSource::ContinuousSyncer);
+    assert_eq!(error_notification.severity, ErrorSeverity::Recoverable);
+    assert_eq!(error_notification.stage, StorageSynchronizerStage::Executor);
+    assert_eq!(error_notification.version_range, Some((3, 3)));
+    assert_matches!(error_notification.error, Error::VerificationError(_));
+
+    // Verify the pipeline drains and no other chunks failed
+    verify_no_pending_data(&storage_synchronizer);
+    assert!(error_listener.select_next_some().now_or_never().is_none());
+}
 
-    // Initialize the state synchronizer
-    let state_synchronizer_handle = storage_synchronizer
-        .initialize_state_synchronizer(
-            vec![create_epoch_ending_ledger_info()],
-            create_epoch_ending_ledger_info(),
-            create_output_list_with_proof(),
-        )
-        .unwrap();
+#[test]
+fn test_verify_ledger_infos_epoch_boundaries() {
+    // Create validator sets and ledger infos for two consecutive epochs
+    let first_signers = create_validator_signers(0);
+    let second_signers = create_validator_signers(10);
+    let first_epoch_state = create_validator_epoch_state(&first_signers, 1);
+    let second_epoch_state = create_validator_epoch_state(&second_signers, 2);
+    let first_ledger_info = create_signed_ledger_info(&first_signers, 10, 1);
+    let second_ledger_info = create_signed_ledger_info(&second_signers, 20, 2);
 
-    // Save the last state chunk
-    let notification_id = 0;
-    storage_synchronizer
-        .save_state_values(notification_id, create_state_value_chunk_with_proof(true))
-        .unwrap();
+    // Verify each ledger info against its own epoch state, and against the other
+    let ledger_info_verifier = LedgerInfoVerifier::new(2);
+    let verification_results = ledger_info_verifier.verify_ledger_infos(&[
+        (&first_epoch_state, &first_ledger_info),
+        (&second_epoch_state, &second_ledger_info),
+        (&first_epoch_state, &second_ledger_info),
+        (&second_epoch_state, &first_ledger_info),
+    ]);
 
-    // The handler should panic as the commit listener was dropped
-    state_synchronizer_handle.await.unwrap();
+    // Verify only the ledger infos checked against the wrong epoch fail
+    assert_eq!(verification_results.len(), 4);
+    assert!(verification_results[0].is_ok());
+    assert!(verification_results[1].is_ok());
+    assert_matches!(verification_results[2], Err(Error::VerificationError(_)));
+    assert_matches!(verification_results[3], Err(Error::VerificationError(_)));
 }
 
-#[tokio::test(flavor = "multi_thread")]
-async fn test_save_states_invalid_chunk() {
-    // Setup the mock snapshot receiver to always return errors
-    let mut snapshot_receiver = create_mock_receiver();
-    snapshot_receiver
-        .expect_add_chunk()
-        .with(always(), always())
-        .returning(|_, _| Err(format_err!("Invalid chunk!")));
+#[test]
+fn test_verify_state_value_chunk() {
+    // Verify a valid chunk passes verification
+    let root_hash = HashValue::random();
+    let states_with_proof = create_verifiable_state_value_chunk(root_hash);
+    verify_state_value_chunk(&states_with_proof, root_hash).unwrap();
 
-    // Setup the mock db writer
-    let mut db_writer = create_mock_db_writer();
-    db_writer
-        .expect_get_state_snapshot_receiver()
-        .with(always(), always())
-        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+    // Verify a chunk with the wrong root hash fails verification
+    let error = verify_state_value_chunk(&states_with_proof, HashValue::random()).unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
 
-    // Create the storage synchronizer
-    let (_, mut error_listener, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
-        create_mock_executor(),
-        create_mock_reader_writer(None, Some(db_writer)),
-    );
+    // Verify an unsorted chunk fails verification
+    let mut unsorted_states_with_proof = states_with_proof.clone();
+    unsorted_states_with_proof.raw_values.swap(1, 2);
+    let error = verify_state_value_chunk(&unsorted_states_with_proof, root_hash).unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
 
-    // Initialize the state synchronizer
-    let _ = storage_synchronizer
-        .initialize_state_synchronizer(
-            vec![create_epoch_ending_ledger_info()],
-            create_epoch_ending_ledger_info(),
-            create_output_list_with_proof(),
-        )
-        .unwrap();
+    // Verify a chunk with keys outside the proven keys fails verification
+    let mut truncated_states_with_proof = states_with_proof;
+    truncated_states_with_proof.raw_values.pop();
+    let error = verify_state_value_chunk(&truncated_states_with_proof, root_hash).unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
 
-    // Save a state chunk and verify we get an error notification
-    let notification_id = 0;
-    storage_synchronizer
-        .save_state_values(notification_id, create_state_value_chunk_with_proof(false))
-        .unwrap();
-    verify_error_notification(&mut error_listener, notification_id).await;
+    // Verify an empty chunk fails verification
+    let mut empty_states_with_proof = create_verifiable_state_value_chunk(root_hash);
+    empty_states_with_proof.raw_values = vec![];
+    let error = verify_state_value_chunk(&empty_states_with_proof, root_hash).unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
 }
 
 #[test]
-#[should_panic]
-fn test_save_states_without_initialize() {
-    // Create the storage synchronizer
-    let (_, _, _, _, mut storage_synchronizer, _, _) = create_storage_synchronizer(
-        create_mock_executor(),
-        create_mock_reader_writer(None, None),
-    );
+fn test_verify_ledger_infos_thread_counts() {
+    // Create many ledger infos, where every fifth ledger info is signed by
+    // the wrong validator set.
+    let epoch = 1;
+    let validator_signers = create_validator_signers(30);
+    let other_validator_signers = create_validator_signers(40);
+    let epoch_state = create_validator_epoch_state(&validator_signers, epoch);
+    let ledger_infos: Vec<LedgerInfoWithSignatures> = (0..50)
+        .map(|version| {
+            if version % 5 == 0 {
+                create_signed_ledger_info(&other_validator_signers, version, epoch)
+            } else {
+                create_signed_ledger_info(&validator_signers, version, epoch)
+            }
+        })
+        .collect();
+    let ledger_infos_to_verify: Vec<(&EpochState, &LedgerInfoWithSignatures)> = ledger_infos
+        .iter()
+        .map(|ledger_info| (&epoch_state, ledger_info))
+        .collect();
 
-    // Attempting to save the states should panic as the state
-    // synchronizer was not initialized!
-    let _ = storage_synchronizer.save_state_values(0, create_state_value_chunk_with_proof(false));
+    // Verify the results are the same (and in order) for every thread count
+    // (the speedup is measured by the ledger_info_verification benchmark).
+    for num_threads in [1, 2, 4] {
+        let ledger_info_verifier = LedgerInfoVerifier::new(num_threads);
+        let verification_results =
+            ledger_info_verifier.verify_ledger_infos(&ledger_infos_to_verify);
+        assert_eq!(verification_results.len(), ledger_infos.len());
+        for (index, verification_result) in verification_results.iter().enumerate() {
+            if index % 5 == 0 {
+                assert_matches!(verification_result, Err(Error::VerificationError(_)));
+            } else {
+                assert!(verification_result.is_ok());
+            }
+        }
+    }
 }
 
-/// Creates a storage synchronizer for testing
 fn create_storage_synchronizer(
     mock_chunk_executor: MockChunkExecutor,
     mock_reader_writer: DbReaderWriter,
@@ -487,12 +3788,164 @@ fn create_storage_synchronizer(
     StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
     JoinHandle<()>,
     JoinHandle<()>,
+) {
+    create_storage_synchronizer_with_tracker(
+        mock_chunk_executor,
+        StateSyncDriverConfig::default(),
+        mock_reader_writer,
+        CommitStateTracker::new(TimeService::mock()),
+    )
+}
+
+/// Creates a mock chunk executor that executes and commits a single-transaction
+/// chunk for each of the given transactions (with the specified latencies). The
+/// number of commits is recorded in the given counter.
+fn create_executor_with_latencies(
+    transactions: &[Transaction],
+    execution_latency: Duration,
+    commit_latency: Duration,
+    num_commits: Arc<AtomicU64>,
+) -> MockChunkExecutor {
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            std::thread::sleep(execution_latency);
+            Ok(())
+        });
+    let commit_notifications = Mutex::new(
+        transactions
+            .iter()
+            .map(|transaction| create_commit_notification(&[transaction.clone()]))
+            .collect::<Vec<_>>(),
+    );
+    chunk_executor.expect_commit_chunk().returning(move || {
+        std::thread::sleep(commit_latency);
+        num_commits.fetch_add(1, Ordering::Relaxed);
+        Ok(commit_notifications.lock().remove(0))
+    });
+    chunk_executor
+}
+
+/// Creates a mock db writer that returns the given snapshot receiver, and
+/// records all storage operations (i.e., pruning pauses and state chunk
+/// writes) in the given list.
+fn create_recording_db_writer(
+    storage_operations: Arc<Mutex<Vec<&'static str>>>,
+    mut snapshot_receiver: MockSnapshotReceiver,
+) -> MockDatabaseWriter {
+    let storage_operations_clone = storage_operations.clone();
+    snapshot_receiver.expect_add_chunk().returning(move |_, _| {
+        storage_operations_clone.lock().push("add_chunk");
+        Ok(())
+    });
+
+    let mut db_writer = MockDatabaseWriter::new();
+    let storage_operations_clone = storage_operations.clone();
+    db_writer.expect_pause_pruning().returning(move || {
+        storage_operations_clone.lock().push("pause_pruning");
+        Ok(())
+    });
+    let storage_operations_clone = storage_operations.clone();
+    db_writer.expect_resume_pruning().returning(move || {
+        storage_operations_clone.lock().push("resume_pruning");
+        Ok(())
+    });
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .return_once(move |_, _| {
+            storage_operations
+                .lock()
+                .push("get_state_snapshot_receiver");
+            Ok(Box::new(snapshot_receiver))
+        });
+    db_writer
+}
+
+/// Creates a storage synchronizer (that uses the given driver config and
+/// commit state tracker) for testing.
+fn create_storage_synchronizer_with_tracker<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    mock_chunk_executor: ChunkExecutor,
+    driver_config: StateSyncDriverConfig,
+    mock_reader_writer: DbReaderWriter,
+    commit_state_tracker: CommitStateTracker,
+) -> (
+    CommitNotificationListener,
+    ErrorNotificationListener,
+    Arc<Mutex<EventSubscriptionService>>,
+    MempoolNotificationListener,
+    StorageSynchronizer<ChunkExecutor, PersistentMetadataStorage>,
+    JoinHandle<()>,
+    JoinHandle<()>,
+) {
+    let (backpressure_notifier, _) = new_backpressure_channel();
+    create_storage_synchronizer_with_backpressure(
+        mock_chunk_executor,
+        driver_config,
+        mock_reader_writer,
+        commit_state_tracker,
+        backpressure_notifier,
+        create_metadata_storage(),
+        false,
+    )
+}
+
+/// Creates a storage synchronizer (that uses the given metadata storage) for
+/// testing.
+fn create_storage_synchronizer_with_metadata(
+    mock_chunk_executor: MockChunkExecutor,
+    mock_reader_writer: DbReaderWriter,
+    metadata_storage: PersistentMetadataStorage,
+) -> (
+    CommitNotificationListener,
+    ErrorNotificationListener,
+    Arc<Mutex<EventSubscriptionService>>,
+    MempoolNotificationListener,
+    StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
+    JoinHandle<()>,
+    JoinHandle<()>,
+) {
+    let (backpressure_notifier, _) = new_backpressure_channel();
+    create_storage_synchronizer_with_backpressure(
+        mock_chunk_executor,
+        StateSyncDriverConfig::default(),
+        mock_reader_writer,
+        CommitStateTracker::new(TimeService::mock()),
+        backpressure_notifier,
+        metadata_storage,
+        false,
+    )
+}
+
+/// Creates a storage synchronizer (that uses the given driver config, commit
+/// state tracker and backpressure notifier) for testing. If `dry_run` is set,
+/// the storage synchronizer never commits to storage.
+fn create_storage_synchronizer_with_backpressure<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    mock_chunk_executor: ChunkExecutor,
+    driver_config: StateSyncDriverConfig,
+    mock_reader_writer: DbReaderWriter,
+    commit_state_tracker: CommitStateTracker,
+    backpressure_notifier: BackpressureNotifier,
+    metadata_storage: PersistentMetadataStorage,
+    dry_run: bool,
+) -> (
+    CommitNotificationListener,
+    ErrorNotificationListener,
+    Arc<Mutex<EventSubscriptionService>>,
+    MempoolNotificationListener,
+    StorageSynchronizer<ChunkExecutor, PersistentMetadataStorage>,
+    JoinHandle<()>,
+    JoinHandle<()>,
 ) {
     aptos_logger::Logger::init_for_testing();
 
     // Create the notification channels
     let (commit_notification_sender, commit_notification_listener) =
-        CommitNotificationListener::new();
+        CommitNotificationListener::new(
+            StateSyncDriverConfig::default().max_pending_commit_notifications,
+            DriverMetrics::default(),
+        );
     let (error_notification_sender, error_notification_listener) = ErrorNotificationListener::new();
 
     // Create the event subscription service
@@ -504,23 +3957,34 @@ fn create_storage_synchronizer(
     // Create the mempool notification handler
     let (mempool_notification_sender, mempool_notification_listener) =
         mempool_notifications::new_mempool_notifier_listener_pair();
-    let mempool_notification_handler = MempoolNotificationHandler::new(mempool_notification_sender);
-
-    // Create the metadata storage
-    let db_path = aptos_temppath::TempPath::new();
-    let metadata_storage = PersistentMetadataStorage::new(db_path.path());
+    let mempool_notification_handler = MempoolNotificationHandler::new(
+        StateSyncDriverConfig::default(),
+        mempool_notification_sender,
+        DriverMetrics::default(),
+    );
 
     // Create the storage synchronizer
     let (storage_synchronizer, executor_handle, committer_handle) = StorageSynchronizer::new(
-        StateSyncDriverConfig::default(),
+        driver_config,
+        backpressure_notifier,
         Arc::new(mock_chunk_executor),
         commit_notification_sender,
         error_notification_sender,
-        event_subscription_service.clone(),
+        EventNotificationHandler::new(
+            event_subscription_service.clone(),
+            StateSyncDriverConfig::default().max_pending_event_notifications,
+            None,
+            DriverMetrics::default(),
+        ),
+        commit_state_tracker,
+        CommitSubscriberRegistry::default(),
         mempool_notification_handler,
         metadata_storage,
+        NotifiedVersionTracker::default(),
         mock_reader_writer,
         None,
+        DriverMetrics::default(),
+        dry_run,
     );
 
     (
@@ -534,38 +3998,501 @@ fn create_storage_synchronizer(
     )
 }
 
+/// Creates a new (empty) metadata storage for testing
+fn create_metadata_storage() -> PersistentMetadataStorage {
+    let db_path = TempPath::new();
+    PersistentMetadataStorage::new(db_path.path())
+}
+
+/// Creates a mock db writer that accepts all state value chunks
+fn create_snapshot_db_writer() -> MockDatabaseWriter {
+    let mut snapshot_receiver = create_mock_receiver();
+    snapshot_receiver
+        .expect_add_chunk()
+        .with(always(), always())
+        .returning(|_, _| Ok(()));
+    let mut db_writer = create_mock_db_writer();
+    db_writer
+        .expect_get_state_snapshot_receiver()
+        .with(always(), always())
+        .return_once(move |_, _| Ok(Box::new(snapshot_receiver)));
+    db_writer
+}
+
+/// Returns the last state value index of the specified (test) state value chunk
+fn get_last_state_value_index(chunk_index: u64) -> u64 {
+    (chunk_index + 1) * 10 - 1
+}
+
+/// Saves the specified (non-final) state value chunk and waits for it to be committed
+async fn save_state_value_chunk(
+    storage_synchronizer: &mut StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
+    commit_listener: &mut CommitNotificationListener,
+    chunk_index: u64,
+) {
+    let mut states_with_proof = create_state_value_chunk_with_proof(false);
+    states_with_proof.first_index = chunk_index * 10;
+    states_with_proof.last_index = get_last_state_value_index(chunk_index);
+    storage_synchronizer
+        .save_state_values_with_proof(chunk_index, states_with_proof, false)
+        .unwrap();
+    match commit_listener.select_next_some().await {
+        CommitNotification::CommittedStateValueChunk(committed_chunk) => {
+            assert_eq!(
+                committed_chunk.last_committed_state_index,
+                get_last_state_value_index(chunk_index)
+            );
+        }
+        commit_notification => panic!("Unexpected commit notification: {:?}", commit_notification),
+    }
+}
+
+/// Creates a mock database reader writer where the latest synced version is
+/// the given version.
+fn create_mock_reader_writer_at_version(
+    synced_version: Version,
+    db_writer: MockDatabaseWriter,
+) -> DbReaderWriter {
+    let mut db_reader = create_mock_db_reader();
+    db_reader
+        .expect_get_latest_transaction_info_option()
+        .returning(move || Ok(Some((synced_version, create_transaction_info()))));
+    db_reader
+        .expect_get_latest_epoch_state()
+        .returning(|| Ok(create_empty_epoch_state()));
+    db_reader
+        .expect_get_latest_ledger_info()
+        .returning(|| Ok(create_epoch_ending_ledger_info()));
+    DbReaderWriter {
+        reader: Arc::new(db_reader),
+        writer: Arc::new(db_writer),
+    }
+}
+
+/// The points at which a crash is simulated during state snapshot finalization
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum FinalizationCrashPoint {
+    BeforeFinalizationMarker, // All states were written, but no marker was persisted
+    BeforeStorageFinalization, // The marker was persisted, but storage wasn't finalized
+    BeforeMetadataCompletion, // Storage was finalized, but the metadata wasn't completed
+    AfterMetadataCompletion,  // All finalization writes completed
+}
+
+/// Writes the metadata that would have been persisted had the node crashed at
+/// the given point of the state snapshot finalization.
+fn simulate_finalization_crash(
+    metadata_storage: &PersistentMetadataStorage,
+    snapshot_finalization: StateSnapshotFinalization,
+    crash_point: FinalizationCrashPoint,
+) {
+    // Persist the progress of the state value chunks committed before the last chunk
+    let target_ledger_info = snapshot_finalization.target_ledger_info.clone();
+    metadata_storage
+        .update_last_persisted_state_value_index(
+            &target_ledger_info,
+            snapshot_finalization.last_persisted_state_value_index / 2,
+            false,
+        )
+        .unwrap();
+    if crash_point == FinalizationCrashPoint::BeforeFinalizationMarker {
+        return;
+    }
+
+    // Persist the finalization marker (storage finalization is handled by the mock db)
+    metadata_storage
+        .start_snapshot_finalization(snapshot_finalization)
+        .unwrap();
+    if crash_point == FinalizationCrashPoint::AfterMetadataCompletion {
+        metadata_storage
+            .complete_snapshot_finalization(&target_ledger_info)
+            .unwrap();
+    }
+}
+
+/// Creates a driver config that batches up to the given number of executed
+/// chunks (for at most the given delay) before committing them.
+/// Returns an epoch state at the given epoch for the specified validators
+fn create_validator_epoch_state(validator_signers: &[ValidatorSigner], epoch: u64) -> EpochState {
+    EpochState {
+        epoch,
+        verifier: generate_validator_verifier(validator_signers),
+    }
+}
+
+/// Returns a transaction output chunk (with a single output) at the given version
+fn create_output_chunk(version: Version) -> TransactionOutputListWithProof {
+    let mut output_list_with_proof = create_output_list_with_proof();
+    output_list_with_proof.first_transaction_output_version = Some(version);
+    output_list_with_proof
+}
+
+/// Returns an output chunk at the given version, where the transaction infos
+/// carry the event root hashes of the output events.
+fn create_output_chunk_with_event_root_hashes(version: Version) -> TransactionOutputListWithProof {
+    let mut output_list_with_proof = create_output_chunk(version);
+    output_list_with_proof.proof.transaction_infos = output_list_with_proof
+        .transactions_and_outputs
+        .iter()
+        .map(|(_, transaction_output)| {
+            let event_hashes: Vec<_> = transaction_output
+                .events()
+                .iter()
+                .map(CryptoHash::hash)
+                .collect();
+            let event_root_hash =
+                InMemoryAccumulator::<EventAccumulatorHasher>::from_leaves(&event_hashes)
+                    .root_hash();
+            TransactionInfo::new(
+                HashValue::random(),
+                HashValue::random(),
+                event_root_hash,
+                Some(HashValue::random()),
+                0,
+                ExecutionStatus::Success,
+            )
+        })
+        .collect();
+    output_list_with_proof
+}
+
+/// Returns a state value chunk (that isn't the last chunk) that is consistent
+/// with its proof at the given root hash (i.e., that passes verification).
+fn create_verifiable_state_value_chunk(root_hash: HashValue) -> StateValueChunkWithProof {
+    let mut raw_values: Vec<(StateKey, StateValue)> = (0..10)
+        .map(|index| (StateKey::Raw(vec![index]), StateValue::new(vec![index; 10])))
+        .collect();
+    raw_values.sort_by_key(|(state_key, _)| state_key.hash());
+
+    let mut states_with_proof = create_state_value_chunk_with_proof(false);
+    states_with_proof.first_key = raw_values.first().unwrap().0.hash();
+    states_with_proof.last_key = raw_values.last().unwrap().0.hash();
+    states_with_proof.raw_values = raw_values;
+    states_with_proof.root_hash = root_hash;
+    states_with_proof
+}
+
+/// Returns a transaction chunk (with a single transaction) at the given version
+fn create_transaction_chunk(version: Version) -> TransactionListWithProof {
+    let mut transaction_list_with_proof = create_transaction_list_with_proof();
+    transaction_list_with_proof.first_transaction_version = Some(version);
+    transaction_list_with_proof
+}
+
+fn create_commit_batch_config(max_chunks: u64, max_delay_ms: u64) -> StateSyncDriverConfig {
+    StateSyncDriverConfig {
+        commit_batch_max_chunks: max_chunks,
+        commit_batch_max_delay_ms: max_delay_ms,
+        ..Default::default()
+    }
+}
+
+/// Creates a mock executor that only executes a chunk once a message is
+/// received on the returned release channel.
+fn create_gated_executor() -> (MockChunkExecutor, std::sync::mpsc::Sender<()>) {
+    let (release_sender, release_receiver) = std::sync::mpsc::channel::<()>();
+    let release_receiver = Mutex::new(release_receiver);
+    let mut chunk_executor = create_mock_executor();
+    chunk_executor
+        .expect_execute_chunk()
+        .with(always(), always(), always())
+        .returning(move |_, _, _| {
+            let _ = release_receiver.lock().recv();
+            Ok(())
+        });
+    (chunk_executor, release_sender)
+}
+
+/// Sends each transaction (as a single chunk, starting at version 1, i.e.,
+/// after genesis) to the storage synchronizer for execution.
+fn execute_transaction_chunks<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    storage_synchronizer: &mut StorageSynchronizer<ChunkExecutor, PersistentMetadataStorage>,
+    transactions: &[Transaction],
+    target_ledger_info: LedgerInfoWithSignatures,
+) {
+    for (version, transaction) in (1..).zip(transactions.iter()) {
+        storage_synchronizer
+            .submit_transaction_chunk(
+                version,
+                ErrorSource::ContinuousSyncer,
+                ApplyOrExecute::Execute,
+                TransactionChunkPayload::Transactions(TransactionListWithProof::new(
+                    vec![transaction.clone()],
+                    None,
+                    Some(version),
+                    create_transaction_info_list_with_proof(),
+                )),
+                target_ledger_info.clone(),
+                None,
+                None,
+            )
+            .unwrap();
+    }
+}
+
+/// Spawns a task that acknowledges all mempool notifications (so that the
+/// committer never waits on mempool).
+fn spawn_mempool_acknowledger(mut mempool_listener: MempoolNotificationListener) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        while let Some(mempool_notification) = mempool_listener.next().await {
+            let _ = mempool_listener.ack_commit_notification(mempool_notification);
+        }
+    })
+}
+
+/// Returns a commit notification for the given transactions
+fn create_commit_notification(transactions: &[Transaction]) -> ChunkCommitNotification {
+    ChunkCommitNotification {
+        committed_events: vec![],
+        committed_transactions: transactions.to_vec(),
+        reconfiguration_occurred: false,
+    }
+}
+
+fn read_cancelled_chunk_executions() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::CANCELLED_CHUNK_EXECUTION],
+        )
+        .get()
+}
+
+fn read_commit_retries() -> u64 {
+    DriverMetrics::default()
+        .counter(&metrics::DRIVER_COUNTERS, &[metrics::COMMIT_RETRY])
+        .get()
+}
+
+fn read_commit_bytes_samples(data_type: metrics::CommittedDataType) -> u64 {
+    DriverMetrics::default()
+        .histogram(&metrics::COMMIT_BYTES, &[data_type.get_label()])
+        .get_sample_count()
+}
+
+fn read_committed_bytes(data_type: metrics::CommittedDataType) -> u64 {
+    DriverMetrics::default()
+        .counter(&metrics::COMMITTED_BYTES, &[data_type.get_label()])
+        .get()
+}
+
+fn read_committed_transaction_chunks(apply_or_execute: ApplyOrExecute) -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::COMMITTED_TRANSACTION_CHUNKS,
+            &[apply_or_execute.get_label()],
+        )
+        .get()
+}
+
+fn read_duplicate_transaction_chunks() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::DUPLICATE_TRANSACTION_CHUNK],
+        )
+        .get()
+}
+
+fn read_event_root_hash_mismatches() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::EVENT_ROOT_HASH_MISMATCH],
+        )
+        .get()
+}
+
+fn read_expired_pruning_pauses() -> u64 {
+    DriverMetrics::default()
+        .counter(&metrics::DRIVER_COUNTERS, &[metrics::PRUNING_PAUSE_EXPIRED])
+        .get()
+}
+
+fn read_slow_transaction_chunks() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::SLOW_TRANSACTION_CHUNK],
+        )
+        .get()
+}
+
+fn read_transaction_chunk_latency_samples(
+    histogram: &Lazy<HistogramVec>,
+    apply_or_execute: ApplyOrExecute,
+    num_versions: u64,
+) -> u64 {
+    DriverMetrics::default()
+        .histogram(
+            histogram,
+            &[
+                apply_or_execute.get_label(),
+                metrics::get_chunk_size_bucket(num_versions),
+            ],
+        )
+        .get_sample_count()
+}
+
+/// Returns the number of post-reset commit notifications handled by the driver
+fn read_post_reset_commit_notifications() -> u64 {
+    DriverMetrics::default()
+        .counter(
+            &metrics::DRIVER_COUNTERS,
+            &[metrics::POST_RESET_COMMIT_NOTIFICATION],
+        )
+        .get()
+}
+
 /// Verifies that the expected snapshot commit notification is received by the listener
+/// (after any state value chunk notifications).
 async fn verify_snapshot_commit_notification(
     commit_listener: &mut CommitNotificationListener,
     expected_committed_transactions: CommittedTransactions,
 ) {
-    let CommitNotification::CommittedStateSnapshot(committed_snapshot) =
-        commit_listener.select_next_some().await;
-    assert_eq!(
-        committed_snapshot.committed_transaction,
-        expected_committed_transactions
-    );
+    loop {
+        match commit_listener.select_next_some().await {
+            CommitNotification::CommittedStateSnapshot(committed_snapshot) => {
+                assert_eq!(
+                    committed_snapshot.committed_transaction,
+                    expected_committed_transactions
+                );
+                return;
+            }
+            CommitNotification::CommittedStateValueChunk(_) => {
+                continue; // Wait for the snapshot notification
+            }
+        }
+    }
 }
 
 /// Verifies that the expected error notification is received by the listener
+/// and returns it (so that callers can verify the failed stage and versions).
 async fn verify_error_notification(
     error_listener: &mut ErrorNotificationListener,
     expected_notification_id: NotificationId,
-) {
+    expected_component: ErrorSource,
+    expected_severity: ErrorSeverity,
+) -> ErrorNotification {
     let error_notification = error_listener.select_next_some().await;
     assert_eq!(error_notification.notification_id, expected_notification_id);
+    assert_eq!(error_notification.component, expected_component);
+    assert_eq!(error_notification.severity, expected_severity);
     assert_matches!(error_notification.error, Error::UnexpectedError(_));
+    error_notification
+}
+
+/// Waits until the given number of storage operations have been recorded
+async fn wait_for_storage_operations(
+    storage_operations: &Arc<Mutex<Vec<&'static str>>>,
+    expected_num_operations: usize,
+) {
+    let max_wait_time_secs = 10;
+    let start_time = Instant::now();
+    while storage_operations.lock().len() < expected_num_operations {
+        if start_time.elapsed() > Duration::from_secs(max_wait_time_secs) {
+            panic!(
+                "Timed-out waiting for {:?} storage operations! Recorded: {:?}",
+                expected_num_operations,
+                storage_operations.lock()
+            );
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Verifies that the backpressure state reaches the expected state
+fn verify_backpressure_state(
+    backpressure_listener: &BackpressureListener,
+    expected_state: BackpressureState,
+) {
+    let max_wait_time_secs = 10;
+    let start_time = Instant::now();
+    while *backpressure_listener.borrow() != expected_state {
+        if start_time.elapsed() > Duration::from_secs(max_wait_time_secs) {
+            panic!(
+                "Timed-out waiting for the backpressure state: {:?}",
+                expected_state
+            );
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Verifies that the storage synchronizer eventually holds the given number
+/// of chunks in each pipeline stage, and that the gauges account for them.
+/// Note: the gauges are shared by all tests, so they're only lower bounded.
+fn verify_pending_data_stages(
+    storage_synchronizer: &StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
+    queued_for_execution: u64,
+    executing: u64,
+    queued_for_commit: u64,
+    committing: u64,
+) {
+    let expected_stages = [
+        (
+            metrics::STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_EXECUTION,
+            queued_for_execution,
+        ),
+        (metrics::STORAGE_SYNCHRONIZER_CHUNKS_EXECUTING, executing),
+        (
+            metrics::STORAGE_SYNCHRONIZER_CHUNKS_QUEUED_FOR_COMMIT,
+            queued_for_commit,
+        ),
+        (metrics::STORAGE_SYNCHRONIZER_CHUNKS_COMMITTING, committing),
+    ];
+
+    // Wait for the chunks to reach the expected stages
+    let max_wait_time_secs = 10;
+    let start_time = Instant::now();
+    loop {
+        let pending_data_summary = storage_synchronizer.pending_storage_data();
+        let stages = [
+            pending_data_summary.chunks_queued_for_execution,
+            pending_data_summary.chunks_executing,
+            pending_data_summary.chunks_queued_for_commit,
+            pending_data_summary.chunks_committing,
+        ];
+        if stages == expected_stages.map(|(_, num_chunks)| num_chunks) {
+            assert_eq!(
+                pending_data_summary.chunks_in_flight,
+                stages.iter().sum::<u64>()
+            );
+            break;
+        }
+        if start_time.elapsed() > Duration::from_secs(max_wait_time_secs) {
+            panic!(
+                "Timed-out waiting for the pipeline stages! Summary: {:?}",
+                pending_data_summary
+            );
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    // Verify the gauges
+    for (label, num_chunks) in expected_stages {
+        let gauge_value = DriverMetrics::default()
+            .gauge(&metrics::STORAGE_SYNCHRONIZER_GAUGES, &[label])
+            .get();
+        assert!(gauge_value >= num_chunks as i64);
+    }
+    let bytes_queued = DriverMetrics::default()
+        .gauge(
+            &metrics::STORAGE_SYNCHRONIZER_GAUGES,
+            &[metrics::STORAGE_SYNCHRONIZER_BYTES_QUEUED],
+        )
+        .get();
+    assert!(bytes_queued >= storage_synchronizer.pending_storage_data().bytes_queued as i64);
 }
 
 /// Verifies that no pending data remains in the storage synchronizer.
 /// Note: due to asynchronous execution, we might need to wait some
 /// time for the pipelines to drain.
-fn verify_no_pending_data(
-    storage_synchronizer: &StorageSynchronizer<MockChunkExecutor, PersistentMetadataStorage>,
+fn verify_no_pending_data<ChunkExecutor: ChunkExecutorTrait + 'static>(
+    storage_synchronizer: &StorageSynchronizer<ChunkExecutor, PersistentMetadataStorage>,
 ) {
     let max_drain_time_secs = 10;
     for _ in 0..max_drain_time_secs {
-        if !storage_synchronizer.pending_storage_data() {
+        if storage_synchronizer.is_idle() {
             return;
         }
         std::thread::sleep(Duration::from_secs(1));
@@ -0,0 +1,187 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    error::Error,
+    metrics::{self, DriverMetrics},
+    tests::{mocks::create_mock_streaming_client, utils::create_data_stream_listener},
+    utils::{self, StreamRetryPolicy},
+};
+use claim::assert_matches;
+use data_streaming_service::streaming_client::{
+    GetAllEpochEndingLedgerInfosRequest, GetAllTransactionOutputsRequest, StreamRequest,
+};
+use mockall::{predicate::eq, Sequence};
+
+#[tokio::test]
+async fn test_initialize_stream_after_transient_failures() {
+    // Create a mock streaming client that fails the first two attempts
+    let mut mock_streaming_client = create_mock_streaming_client();
+    let mut expectation_sequence = Sequence::new();
+    mock_streaming_client
+        .expect_get_all_transaction_outputs()
+        .times(2)
+        .with(eq(10), eq(20), eq(30))
+        .returning(|_, _, _| {
+            Err(data_streaming_service::error::Error::DataIsUnavailable(
+                "Data is temporarily unavailable!".into(),
+            ))
+        })
+        .in_sequence(&mut expectation_sequence);
+    let (_notification_sender, data_stream_listener) = create_data_stream_listener();
+    mock_streaming_client
+        .expect_get_all_transaction_outputs()
+        .times(1)
+        .with(eq(10), eq(20), eq(30))
+        .return_once(move |_, _, _| Ok(data_stream_listener))
+        .in_sequence(&mut expectation_sequence);
+
+    // Initialize the data stream and verify it succeeds on the third attempt
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let stream_request = create_transaction_outputs_request();
+    let active_stream = utils::initialize_data_stream(
+        &mock_streaming_client,
+        stream_request.clone(),
+        StreamRetryPolicy::new(2, 1),
+        &driver_metrics,
+    )
+    .await
+    .unwrap();
+    assert_eq!(active_stream.get_num_attempts(), 3);
+    assert_eq!(active_stream.get_stream_request(), &stream_request);
+
+    // Verify the attempt counters
+    let request_type = stream_request.get_label();
+    assert_eq!(
+        read_initialization_counter(
+            &driver_metrics,
+            request_type,
+            metrics::STREAM_INITIALIZATION_FAILURE
+        ),
+        2
+    );
+    assert_eq!(
+        read_initialization_counter(
+            &driver_metrics,
+            request_type,
+            metrics::STREAM_INITIALIZATION_SUCCESS
+        ),
+        1
+    );
+    assert_eq!(
+        driver_metrics.read_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::STREAM_INITIALIZATION_RETRY
+        ),
+        2
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_stream_retries_exhausted() {
+    // Create a mock streaming client that always fails with a transient error
+    let mut mock_streaming_client = create_mock_streaming_client();
+    mock_streaming_client
+        .expect_get_all_transaction_outputs()
+        .times(2)
+        .returning(|_, _, _| {
+            Err(data_streaming_service::error::Error::AptosDataClientError(
+                "No peers are connected!".into(),
+            ))
+        });
+
+    // Initialize the data stream (with a single retry) and verify it fails
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let stream_request = create_transaction_outputs_request();
+    let error = utils::initialize_data_stream(
+        &mock_streaming_client,
+        stream_request.clone(),
+        StreamRetryPolicy::new(1, 1),
+        &driver_metrics,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_matches!(error, Error::UnexpectedError(_));
+
+    // Verify the attempt counters
+    let request_type = stream_request.get_label();
+    assert_eq!(
+        read_initialization_counter(
+            &driver_metrics,
+            request_type,
+            metrics::STREAM_INITIALIZATION_FAILURE
+        ),
+        2
+    );
+    assert_eq!(
+        read_initialization_counter(
+            &driver_metrics,
+            request_type,
+            metrics::STREAM_INITIALIZATION_SUCCESS
+        ),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_initialize_stream_permanent_failure() {
+    // Create a mock streaming client that fails with a permanent error
+    let mut mock_streaming_client = create_mock_streaming_client();
+    mock_streaming_client
+        .expect_get_all_epoch_ending_ledger_infos()
+        .times(1)
+        .with(eq(5))
+        .returning(|_| {
+            Err(data_streaming_service::error::Error::IntegerOverflow(
+                "The end epoch has overflown!".into(),
+            ))
+        });
+
+    // Initialize the data stream and verify the error isn't retried
+    let driver_metrics = DriverMetrics::new_dry_run_instance();
+    let stream_request =
+        StreamRequest::GetAllEpochEndingLedgerInfos(GetAllEpochEndingLedgerInfosRequest {
+            start_epoch: 5,
+        });
+    let error = utils::initialize_data_stream(
+        &mock_streaming_client,
+        stream_request,
+        StreamRetryPolicy::new(3, 1),
+        &driver_metrics,
+    )
+    .await
+    .err()
+    .unwrap();
+    assert_matches!(error, Error::UnexpectedError(_));
+    assert_eq!(
+        driver_metrics.read_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::STREAM_INITIALIZATION_RETRY
+        ),
+        0
+    );
+}
+
+/// Creates a request to stream the transaction outputs used by the tests
+fn create_transaction_outputs_request() -> StreamRequest {
+    StreamRequest::GetAllTransactionOutputs(GetAllTransactionOutputsRequest {
+        start_version: 10,
+        end_version: 20,
+        proof_version: 30,
+    })
+}
+
+/// Reads the data stream initialization counter for the given request type and result
+fn read_initialization_counter(
+    driver_metrics: &DriverMetrics,
+    request_type: &str,
+    result: &str,
+) -> u64 {
+    driver_metrics
+        .counter(
+            &metrics::DATA_STREAM_INITIALIZATIONS,
+            &[request_type, result],
+        )
+        .get()
+}
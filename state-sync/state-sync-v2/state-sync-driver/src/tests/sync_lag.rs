@@ -0,0 +1,245 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::{
+    calculate_ledger_info_age, calculate_sync_lag, get_ledger_info_timestamp_usecs, SyncLag,
+};
+use aptos_crypto::HashValue;
+use aptos_data_client::GlobalDataSummary;
+use aptos_time_service::TimeService;
+use aptos_types::{
+    aggregate_signature::AggregateSignature,
+    block_info::BlockInfo,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    transaction::Version,
+};
+use proptest::prelude::*;
+use std::time::Duration;
+use storage_service_types::{responses::CompleteDataRange, Epoch};
+
+#[test]
+fn test_sync_lag_empty_summary() {
+    // Verify no lag is calculated for an empty summary
+    let global_data_summary = GlobalDataSummary::empty();
+    assert_eq!(
+        calculate_sync_lag(&global_data_summary, Some(10), Some(1)),
+        None
+    );
+    assert_eq!(calculate_sync_lag(&global_data_summary, None, None), None);
+}
+
+#[test]
+fn test_sync_lag_empty_storage() {
+    // Verify the node lags behind all advertised data (including genesis)
+    let global_data_summary = create_global_summary(100, 5, None);
+    assert_eq!(
+        calculate_sync_lag(&global_data_summary, None, None),
+        Some(SyncLag {
+            ahead_of_network: false,
+            epoch_lag: 6,
+            version_lag: 101,
+        })
+    );
+}
+
+#[test]
+fn test_sync_lag_malformed_summary() {
+    // Verify no lag is calculated if a higher epoch was ended than the synced ledger info
+    for highest_ended_epoch in [5, 10] {
+        let global_data_summary = create_global_summary(100, 5, Some(highest_ended_epoch));
+        assert_eq!(
+            calculate_sync_lag(&global_data_summary, Some(50), Some(2)),
+            None
+        );
+    }
+
+    // Verify the lag is calculated if the advertisements are consistent
+    let global_data_summary = create_global_summary(100, 5, Some(4));
+    assert_eq!(
+        calculate_sync_lag(&global_data_summary, Some(50), Some(2)),
+        Some(SyncLag {
+            ahead_of_network: false,
+            epoch_lag: 3,
+            version_lag: 50,
+        })
+    );
+}
+
+#[test]
+fn test_ledger_info_timestamp() {
+    // Verify the commit timestamp is extracted from the ledger info
+    for timestamp_usecs in [0, 1, 1_650_000_000_000_000, u64::MAX] {
+        let ledger_info = create_ledger_info_at_timestamp(timestamp_usecs);
+        assert_eq!(
+            get_ledger_info_timestamp_usecs(&ledger_info),
+            timestamp_usecs
+        );
+    }
+}
+
+#[test]
+fn test_ledger_info_age() {
+    // Create a mock clock (at 1000 seconds past the unix epoch)
+    let time_service = TimeService::mock();
+    time_service.clone().into_mock().advance_secs(1000);
+
+    // Verify the age of a ledger info committed 600 seconds ago
+    let ledger_info = create_ledger_info_at_timestamp(400_000_000);
+    assert_eq!(
+        calculate_ledger_info_age(&ledger_info, &time_service),
+        Some(Duration::from_secs(600))
+    );
+
+    // Advance the clock and verify the age increases
+    time_service.clone().into_mock().advance_ms(1500);
+    assert_eq!(
+        calculate_ledger_info_age(&ledger_info, &time_service),
+        Some(Duration::from_millis(601_500))
+    );
+
+    // Verify a ledger info committed at the current time has no age
+    let ledger_info = create_ledger_info_at_timestamp(1_001_500_000);
+    assert_eq!(
+        calculate_ledger_info_age(&ledger_info, &time_service),
+        Some(Duration::from_secs(0))
+    );
+}
+
+#[test]
+fn test_ledger_info_age_future() {
+    // Create a mock clock (at 1000 seconds past the unix epoch)
+    let time_service = TimeService::mock();
+    time_service.clone().into_mock().advance_secs(1000);
+
+    // Verify ledger infos from the future (e.g., due to clock skew) have no age
+    for timestamp_usecs in [1_000_000_001, 5_000_000_000, u64::MAX] {
+        let ledger_info = create_ledger_info_at_timestamp(timestamp_usecs);
+        assert_eq!(
+            calculate_ledger_info_age(&ledger_info, &time_service),
+            Some(Duration::from_secs(0))
+        );
+    }
+}
+
+#[test]
+fn test_ledger_info_age_genesis() {
+    // Create a mock clock (at roughly the current time)
+    let time_service = TimeService::mock();
+    time_service.clone().into_mock().advance_secs(1_650_000_000);
+
+    // Verify the genesis ledger info (with a zero timestamp) has no age
+    let genesis_ledger_info = create_ledger_info_at_timestamp(0);
+    assert_eq!(
+        calculate_ledger_info_age(&genesis_ledger_info, &time_service),
+        None
+    );
+
+    // Verify a (non-genesis) ledger info committed 10 seconds ago has an age
+    let ledger_info = create_ledger_info_at_timestamp(1_649_999_990_000_000);
+    assert_eq!(
+        calculate_ledger_info_age(&ledger_info, &time_service),
+        Some(Duration::from_secs(10))
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn test_sync_lag_clamping(
+        advertised_version in any::<Version>(),
+        advertised_epoch in any::<Epoch>(),
+        synced_version in any::<Version>(),
+        synced_epoch in any::<Epoch>(),
+    ) {
+        // Calculate the sync lag
+        let global_data_summary =
+            create_global_summary(advertised_version, advertised_epoch, None);
+        let sync_lag =
+            calculate_sync_lag(&global_data_summary, Some(synced_version), Some(synced_epoch))
+                .unwrap();
+
+        // Verify the lags never exceed the advertised values and are clamped at zero
+        prop_assert!(sync_lag.version_lag <= advertised_version);
+        prop_assert!(sync_lag.epoch_lag <= advertised_epoch);
+        if synced_version >= advertised_version {
+            prop_assert_eq!(sync_lag.version_lag, 0);
+        } else {
+            prop_assert_eq!(sync_lag.version_lag, advertised_version - synced_version);
+        }
+        if synced_epoch >= advertised_epoch {
+            prop_assert_eq!(sync_lag.epoch_lag, 0);
+        } else {
+            prop_assert_eq!(sync_lag.epoch_lag, advertised_epoch - synced_epoch);
+        }
+    }
+
+    #[test]
+    fn test_sync_lag_ahead_of_network(
+        advertised_version in any::<Version>(),
+        advertised_epoch in any::<Epoch>(),
+        synced_version in any::<Version>(),
+        synced_epoch in any::<Epoch>(),
+    ) {
+        // Calculate the sync lag
+        let global_data_summary =
+            create_global_summary(advertised_version, advertised_epoch, None);
+        let sync_lag =
+            calculate_sync_lag(&global_data_summary, Some(synced_version), Some(synced_epoch))
+                .unwrap();
+
+        // Verify the node is only ahead of the network if it has synced past the advertised data
+        let ahead_of_network =
+            synced_version > advertised_version || synced_epoch > advertised_epoch;
+        prop_assert_eq!(sync_lag.ahead_of_network, ahead_of_network);
+        if ahead_of_network {
+            prop_assert!(sync_lag.version_lag == 0 || sync_lag.epoch_lag == 0);
+        }
+    }
+}
+
+/// Creates a global data summary that advertises a synced ledger info at the
+/// given version and epoch (and optionally, the highest ended epoch).
+fn create_global_summary(
+    version: Version,
+    epoch: Epoch,
+    highest_ended_epoch: Option<Epoch>,
+) -> GlobalDataSummary {
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        version,
+        0,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+
+    let mut global_data_summary = GlobalDataSummary::empty();
+    let advertised_data = &mut global_data_summary.advertised_data;
+    advertised_data.synced_ledger_infos = vec![LedgerInfoWithSignatures::new(
+        ledger_info,
+        AggregateSignature::empty(),
+    )];
+    if let Some(highest_ended_epoch) = highest_ended_epoch {
+        advertised_data.epoch_ending_ledger_infos =
+            vec![CompleteDataRange::new(0, highest_ended_epoch).unwrap()];
+    }
+    global_data_summary
+}
+
+/// Creates a ledger info with the given commit timestamp (in microseconds)
+fn create_ledger_info_at_timestamp(timestamp_usecs: u64) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        1,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        100,
+        timestamp_usecs,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::zero());
+    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+}
@@ -0,0 +1,427 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+//! Scenario tests that replay the characteristic sequences of consensus
+//! notifications sent by validators (e.g., repeated sync requests during
+//! recovery, sync requests followed by commits, and sync requests superseded
+//! by newer ones) against a bootstrapped validator. After each step, the
+//! scenarios verify the responses sent to consensus, the data stream of the
+//! continuous syncer (which must be bounded by the sync target) and whether
+//! consensus is executing. These tests define the expected behaviour of the
+//! consensus sync request slot.
+
+use crate::{
+    metrics::{self, ExecutingComponent},
+    tests::{
+        driver_harness::{create_bootstrapped_validator_harness_with, DriverHarness},
+        utils::{create_global_summary, create_transaction},
+    },
+};
+use aptos_types::transaction::{Transaction, Version};
+use claim::{assert_none, assert_ok};
+use data_streaming_service::streaming_client::StreamRequest;
+use futures::FutureExt;
+use std::time::Duration;
+use tokio::{task::JoinHandle, time::timeout};
+
+// The timeout (of real time) for the driver to respond to consensus
+const CONSENSUS_RESPONSE_TIMEOUT_SECS: u64 = 10;
+
+/// A single step of a sync request scenario
+#[derive(Clone, Copy, Debug)]
+enum ScenarioStep {
+    Commit(u64),     // Commit a batch of transactions (as consensus would)
+    SyncTo(Version), // Send a sync request for the given version
+}
+
+/// The response to a consensus sync request (as observed by consensus)
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum SyncResponse {
+    Failure, // The request was answered with an error
+    Pending, // The request hasn't been answered yet
+    Success, // The request was answered successfully
+}
+
+/// A scenario step and the state of the driver expected once it completes
+struct ExpectedStep {
+    step: ScenarioStep,
+    sync_responses: Vec<(Version, SyncResponse)>, // The responses to all sync requests sent so far (by target)
+    consensus_executing: bool, // Whether consensus is executing (at the next progress check)
+}
+
+impl ExpectedStep {
+    fn new(
+        step: ScenarioStep,
+        sync_responses: &[(Version, SyncResponse)],
+        consensus_executing: bool,
+    ) -> Self {
+        Self {
+            step,
+            sync_responses: sync_responses.to_vec(),
+            consensus_executing,
+        }
+    }
+
+    /// Returns the target of the sync request expected to be active
+    fn get_active_sync_target(&self) -> Option<Version> {
+        self.sync_responses
+            .iter()
+            .find(|(_, sync_response)| *sync_response == SyncResponse::Pending)
+            .map(|(target_version, _)| *target_version)
+    }
+}
+
+/// A sync request sent by a scenario (and the response, once received)
+struct SyncRequest {
+    join_handle: Option<JoinHandle<Result<(), consensus_notifications::Error>>>,
+    sync_response: Option<SyncResponse>,
+    target_version: Version,
+}
+
+impl SyncRequest {
+    fn new(
+        target_version: Version,
+        join_handle: JoinHandle<Result<(), consensus_notifications::Error>>,
+    ) -> Self {
+        Self {
+            join_handle: Some(join_handle),
+            sync_response: None,
+            target_version,
+        }
+    }
+
+    /// Returns the response to the request (without waiting for it)
+    fn poll_response(&mut self) -> SyncResponse {
+        if let Some(join_handle) = self.join_handle.as_mut() {
+            match join_handle.now_or_never() {
+                Some(result) => self.set_response(result.unwrap()),
+                None => return SyncResponse::Pending,
+            }
+        }
+        self.sync_response.unwrap()
+    }
+
+    /// Waits (with a timeout) for the response to the request
+    async fn wait_for_response(&mut self) -> SyncResponse {
+        if let Some(join_handle) = self.join_handle.take() {
+            let result = timeout(
+                Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS),
+                join_handle,
+            )
+            .await
+            .expect("The sync request was never answered!")
+            .unwrap();
+            self.set_response(result);
+        }
+        self.sync_response.unwrap()
+    }
+
+    fn set_response(&mut self, result: Result<(), consensus_notifications::Error>) {
+        self.join_handle = None;
+        self.sync_response = Some(if result.is_ok() {
+            SyncResponse::Success
+        } else {
+            SyncResponse::Failure
+        });
+    }
+}
+
+#[tokio::test]
+async fn test_commits_after_sync_request() {
+    // Consensus sends a sync request and then commits the blocks up to the
+    // target (asynchronously), which fulfills the request.
+    let steps = vec![
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(20),
+            &[(20, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::Commit(10),
+            &[(20, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::Commit(10),
+            &[(20, SyncResponse::Success)],
+            true,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::Commit(5),
+            &[(20, SyncResponse::Success)],
+            true,
+        ),
+    ];
+    run_scenario(steps).await;
+}
+
+#[tokio::test]
+async fn test_duplicate_notification_regression() {
+    // Consensus commits versions 100 to 110, and then sends a sync request
+    // for version 105. The request must be rejected (without notifying
+    // mempool of the committed transactions again), and a sync request for
+    // the synced version must be answered immediately.
+    let steps = vec![
+        ExpectedStep::new(ScenarioStep::Commit(99), &[], true),
+        ExpectedStep::new(ScenarioStep::Commit(11), &[], true),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(105),
+            &[(105, SyncResponse::Failure)],
+            true,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(110),
+            &[(105, SyncResponse::Failure), (110, SyncResponse::Success)],
+            true,
+        ),
+    ];
+    run_scenario(steps).await;
+}
+
+#[tokio::test]
+async fn test_increasing_sync_targets_during_recovery() {
+    // Consensus (recovering) sends sync requests with increasing targets,
+    // each of which supersedes the previous request. The last request is
+    // fulfilled once consensus commits up to its target.
+    let steps = vec![
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(10),
+            &[(10, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(20),
+            &[(10, SyncResponse::Failure), (20, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(30),
+            &[
+                (10, SyncResponse::Failure),
+                (20, SyncResponse::Failure),
+                (30, SyncResponse::Pending),
+            ],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::Commit(30),
+            &[
+                (10, SyncResponse::Failure),
+                (20, SyncResponse::Failure),
+                (30, SyncResponse::Success),
+            ],
+            true,
+        ),
+    ];
+    run_scenario(steps).await;
+}
+
+#[tokio::test]
+async fn test_sync_request_superseded_by_newer_request() {
+    // Consensus sends sync requests for future versions, and then cancels
+    // them with a sync request for the synced version (which is answered
+    // immediately, handing control back to consensus).
+    let steps = vec![
+        ExpectedStep::new(ScenarioStep::Commit(5), &[], true),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(50),
+            &[(50, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(60),
+            &[(50, SyncResponse::Failure), (60, SyncResponse::Pending)],
+            false,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::SyncTo(5),
+            &[
+                (50, SyncResponse::Failure),
+                (60, SyncResponse::Failure),
+                (5, SyncResponse::Success),
+            ],
+            true,
+        ),
+        ExpectedStep::new(
+            ScenarioStep::Commit(5),
+            &[
+                (50, SyncResponse::Failure),
+                (60, SyncResponse::Failure),
+                (5, SyncResponse::Success),
+            ],
+            true,
+        ),
+    ];
+    run_scenario(steps).await;
+}
+
+/// Runs the given scenario steps (in order) against a bootstrapped validator.
+/// After each step, the driver completes a progress check, and the driver
+/// state is verified against the expected state of the step.
+async fn run_scenario(steps: Vec<ExpectedStep>) {
+    // Once bootstrapped, peers advertise data (otherwise progress checks
+    // stop before the executing component is identified).
+    let mut driver_harness = create_bootstrapped_validator_harness_with(|driver_harness_builder| {
+        driver_harness_builder.with_bootstrapped_global_data_summary(create_global_summary(0))
+    })
+    .await;
+    let mut committed_batches: Vec<Vec<Transaction>> = vec![];
+    let mut sync_requests: Vec<SyncRequest> = vec![];
+
+    for (step_index, expected_step) in steps.iter().enumerate() {
+        // Execute the step
+        match expected_step.step {
+            ScenarioStep::Commit(num_transactions) => {
+                let transactions: Vec<Transaction> = (0..num_transactions)
+                    .map(|_| create_transaction())
+                    .collect();
+                assert_ok!(
+                    driver_harness
+                        .commit_transactions(transactions.clone(), vec![])
+                        .await
+                );
+                committed_batches.push(transactions);
+            }
+            ScenarioStep::SyncTo(target_version) => {
+                let join_handle = driver_harness.spawn_sync_to_target(target_version);
+                let mut sync_request = SyncRequest::new(target_version, join_handle);
+                wait_for_sync_request(&driver_harness, &mut sync_request).await;
+                sync_requests.push(sync_request);
+            }
+        }
+
+        // Complete a progress check and verify the driver state
+        verify_progress_check(&mut driver_harness, expected_step, step_index).await;
+        verify_sync_responses(
+            &driver_harness,
+            &mut sync_requests,
+            expected_step,
+            step_index,
+        )
+        .await;
+    }
+
+    // Verify mempool was notified of each committed batch exactly once (in order)
+    assert_eq!(
+        driver_harness.get_mempool_notifications(),
+        committed_batches
+    );
+}
+
+/// Completes a progress check, and verifies whether consensus is executing.
+/// If consensus isn't executing, the continuous syncer must request a data
+/// stream from the synced version, bounded by the active sync target.
+async fn verify_progress_check(
+    driver_harness: &mut DriverHarness,
+    expected_step: &ExpectedStep,
+    step_index: usize,
+) {
+    // Drop any stream requests sent before the progress check
+    while driver_harness.next_stream_request().is_some() {}
+
+    // Complete the progress check
+    let driver_metrics = driver_harness.driver_metrics();
+    let num_consensus_executing = driver_metrics.read_counter(
+        &metrics::EXECUTING_COMPONENT,
+        ExecutingComponent::Consensus.get_label(),
+    );
+    driver_harness.advance_to_next_progress_check().await;
+
+    // Verify whether consensus is executing
+    let consensus_executing = driver_metrics.read_counter(
+        &metrics::EXECUTING_COMPONENT,
+        ExecutingComponent::Consensus.get_label(),
+    ) > num_consensus_executing;
+    assert_eq!(
+        consensus_executing, expected_step.consensus_executing,
+        "Unexpected executing component after step {}: {:?}",
+        step_index, expected_step.step
+    );
+
+    // Verify the stream request (if any)
+    let stream_request = driver_harness.next_stream_request();
+    if expected_step.consensus_executing {
+        assert_none!(stream_request);
+        return;
+    }
+    let synced_version = driver_harness.get_storage().get_latest_version();
+    match stream_request {
+        Some(StreamRequest::ContinuouslyStreamTransactionOutputs(request)) => {
+            assert_eq!(request.known_version, synced_version);
+            assert_eq!(
+                request.target.map(|target| target.ledger_info().version()),
+                expected_step.get_active_sync_target(),
+                "The stream isn't bounded by the sync target after step {}: {:?}",
+                step_index,
+                expected_step.step
+            );
+        }
+        stream_request => panic!(
+            "Unexpected stream request after step {}: {:?}",
+            step_index, stream_request
+        ),
+    }
+}
+
+/// Verifies the responses to all sync requests sent so far (and the active
+/// sync target reported by the driver) match the expected state of the step
+async fn verify_sync_responses(
+    driver_harness: &DriverHarness,
+    sync_requests: &mut [SyncRequest],
+    expected_step: &ExpectedStep,
+    step_index: usize,
+) {
+    // Verify the active sync target
+    let sync_status = driver_harness
+        .driver_client()
+        .get_sync_status(Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS))
+        .await
+        .unwrap();
+    assert_eq!(
+        sync_status.active_sync_target,
+        expected_step.get_active_sync_target(),
+        "Unexpected active sync target after step {}: {:?}",
+        step_index,
+        expected_step.step
+    );
+
+    // Verify the responses to the sync requests
+    assert_eq!(sync_requests.len(), expected_step.sync_responses.len());
+    for sync_request in sync_requests.iter_mut() {
+        let expected_response = expected_step
+            .sync_responses
+            .iter()
+            .find(|(target_version, _)| *target_version == sync_request.target_version)
+            .map(|(_, sync_response)| *sync_response)
+            .unwrap();
+        let sync_response = if expected_response == SyncResponse::Pending {
+            sync_request.poll_response()
+        } else {
+            sync_request.wait_for_response().await
+        };
+        assert_eq!(
+            sync_response, expected_response,
+            "Unexpected response for the sync request to version {} after step {}: {:?}",
+            sync_request.target_version, step_index, expected_step.step
+        );
+    }
+}
+
+/// Waits until the driver has handled the given sync request, i.e., the
+/// request has been answered or it has become the active sync request.
+async fn wait_for_sync_request(driver_harness: &DriverHarness, sync_request: &mut SyncRequest) {
+    loop {
+        if sync_request.poll_response() != SyncResponse::Pending {
+            return;
+        }
+        let sync_status = driver_harness
+            .driver_client()
+            .get_sync_status(Duration::from_secs(CONSENSUS_RESPONSE_TIMEOUT_SECS))
+            .await
+            .unwrap();
+        if sync_status.active_sync_target == Some(sync_request.target_version) {
+            return;
+        }
+        tokio::task::yield_now().await;
+    }
+}
@@ -0,0 +1,341 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bootstrapper::VerifiedEpochStates,
+    test_utils::synthetic_chain::{
+        NotificationFault, SyntheticChain, SyntheticChainConfig, SyntheticStreamingClient,
+    },
+};
+use aptos_types::{epoch_change::Verifier, transaction::TransactionListWithProof};
+use claim::{assert_err, assert_matches, assert_ok};
+use data_streaming_service::{
+    data_notification::{DataNotification, DataPayload},
+    data_stream::DataStreamListener,
+    error::Error,
+    streaming_client::{
+        DataStreamingClient, GetAllEpochEndingLedgerInfosRequest, NotificationFeedback,
+        StreamRequest,
+    },
+};
+use futures::{FutureExt, StreamExt};
+use std::sync::Arc;
+
+#[test]
+fn test_chain_epoch_ending_ledger_infos_verify() {
+    // Create a synthetic chain
+    let chain = SyntheticChain::new(SyntheticChainConfig::default());
+    let latest_epoch = chain.get_latest_epoch();
+
+    // Verify the genesis ledger info matches the genesis waypoint
+    let genesis_ledger_info = chain.get_epoch_ending_ledger_info(0);
+    assert_ok!(chain
+        .get_genesis_waypoint()
+        .verify(genesis_ledger_info.ledger_info()));
+
+    // Verify the signature chain (each epoch is verified by the previous epoch state)
+    let mut epoch_state = genesis_ledger_info
+        .ledger_info()
+        .next_epoch_state()
+        .unwrap()
+        .clone();
+    for epoch in 1..=latest_epoch {
+        let epoch_ending_ledger_info = chain.get_epoch_ending_ledger_info(epoch);
+        assert_ok!(epoch_state.verify(&epoch_ending_ledger_info));
+        assert_eq!(
+            epoch_ending_ledger_info.ledger_info().version(),
+            epoch * chain.get_config().transactions_per_epoch
+        );
+        epoch_state = epoch_ending_ledger_info
+            .ledger_info()
+            .next_epoch_state()
+            .unwrap()
+            .clone();
+    }
+    assert_eq!(epoch_state, chain.get_epoch_state(latest_epoch + 1));
+
+    // Verify the chain using the bootstrapper's verified epoch states (with
+    // a waypoint at the latest epoch).
+    let waypoint = chain.get_waypoint(latest_epoch);
+    let mut verified_epoch_states = VerifiedEpochStates::new(chain.get_epoch_state(1));
+    for epoch_ending_ledger_info in chain.get_epoch_ending_ledger_infos(1, latest_epoch) {
+        assert!(!verified_epoch_states.verified_waypoint());
+        verified_epoch_states
+            .verify_epoch_ending_ledger_info(&epoch_ending_ledger_info, &waypoint)
+            .unwrap();
+    }
+    assert!(verified_epoch_states.verified_waypoint());
+}
+
+#[test]
+fn test_chain_forged_ledger_infos_fail() {
+    // Create a synthetic chain
+    let chain = SyntheticChain::new(SyntheticChainConfig::default());
+
+    // Verify forged ledger infos fail verification
+    for epoch in 1..=chain.get_latest_epoch() {
+        let forged_ledger_info = chain.create_forged_ledger_info(epoch);
+        assert_err!(chain.get_epoch_state(epoch).verify(&forged_ledger_info));
+    }
+
+    // Verify the validators rotate every epoch (i.e., a ledger info can't be
+    // verified by the validators of another epoch).
+    let epoch_ending_ledger_info = chain.get_epoch_ending_ledger_info(2);
+    assert_err!(epoch_ending_ledger_info.verify_signatures(&chain.get_epoch_state(1).verifier));
+    assert_ok!(epoch_ending_ledger_info.verify_signatures(&chain.get_epoch_state(2).verifier));
+}
+
+#[test]
+fn test_chain_transactions_verify() {
+    // Create a synthetic chain
+    let chain = SyntheticChain::new(SyntheticChainConfig {
+        num_epochs: 2,
+        transactions_per_epoch: 7,
+        ..Default::default()
+    });
+    let latest_version = chain.get_latest_version();
+    assert_eq!(latest_version, 14);
+
+    // Verify every range of transactions (and outputs) verifies against every
+    // ledger info that proves it.
+    for proof_epoch in 0..=chain.get_latest_epoch() {
+        let ledger_info = chain.get_epoch_ending_ledger_info(proof_epoch);
+        let proof_version = ledger_info.ledger_info().version();
+        for start_version in 0..=proof_version {
+            for end_version in start_version..=proof_version {
+                let transactions_with_proof = chain.get_transactions_with_proof(
+                    start_version,
+                    end_version,
+                    proof_version,
+                    true,
+                );
+                assert_ok!(
+                    transactions_with_proof.verify(ledger_info.ledger_info(), Some(start_version))
+                );
+                let outputs_with_proof = chain.get_transaction_outputs_with_proof(
+                    start_version,
+                    end_version,
+                    proof_version,
+                );
+                assert_ok!(
+                    outputs_with_proof.verify(ledger_info.ledger_info(), Some(start_version))
+                );
+            }
+        }
+    }
+
+    // Verify proofs fail against the wrong ledger info
+    let transactions_with_proof = chain.get_transactions_with_proof(1, 7, 7, false);
+    let ledger_info = chain.get_epoch_ending_ledger_info(2);
+    assert_err!(transactions_with_proof.verify(ledger_info.ledger_info(), Some(1)));
+}
+
+#[tokio::test]
+async fn test_streaming_client_epoch_ending_ledger_infos() {
+    // Create a synthetic chain with several epochs
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig {
+        num_epochs: 5,
+        transactions_per_chunk: 2,
+        ..Default::default()
+    }));
+    let streaming_client = SyntheticStreamingClient::new(chain.clone());
+
+    // Stream the epoch ending ledger infos and verify the signature chain
+    let mut data_stream_listener = streaming_client
+        .get_all_epoch_ending_ledger_infos(1)
+        .await
+        .unwrap();
+    let waypoint = chain.get_waypoint(chain.get_latest_epoch());
+    let mut verified_epoch_states = VerifiedEpochStates::new(chain.get_epoch_state(1));
+    let data_notifications = drain_data_stream(&mut data_stream_listener);
+    assert_eq!(data_notifications.len(), 4); // Three chunks and the end of the stream
+    for data_notification in &data_notifications[..3] {
+        match &data_notification.data_payload {
+            DataPayload::EpochEndingLedgerInfos(ledger_infos) => {
+                for ledger_info in ledger_infos {
+                    verified_epoch_states
+                        .verify_epoch_ending_ledger_info(ledger_info, &waypoint)
+                        .unwrap();
+                }
+            }
+            data_payload => panic!("Unexpected payload: {:?}", data_payload),
+        }
+    }
+    assert_matches!(data_notifications[3].data_payload, DataPayload::EndOfStream);
+    assert!(verified_epoch_states.verified_waypoint());
+
+    // Verify the stream request was recorded
+    assert_eq!(
+        streaming_client.get_stream_requests(),
+        vec![StreamRequest::GetAllEpochEndingLedgerInfos(
+            GetAllEpochEndingLedgerInfosRequest { start_epoch: 1 }
+        )]
+    );
+}
+
+#[tokio::test]
+async fn test_streaming_client_transactions() {
+    // Create a synthetic chain
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig::default()));
+    let streaming_client = SyntheticStreamingClient::new(chain.clone());
+
+    // Stream all transactions (proven at the latest version)
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    let latest_version = chain.get_latest_version();
+    let mut data_stream_listener = streaming_client
+        .get_all_transactions(1, latest_version, latest_version, true)
+        .await
+        .unwrap();
+
+    // Verify the chunks are contiguous and verify against the ledger info
+    let mut expected_start_version = 1;
+    for data_notification in drain_data_stream(&mut data_stream_listener) {
+        match data_notification.data_payload {
+            DataPayload::TransactionsWithProof(transactions_with_proof) => {
+                assert_ok!(transactions_with_proof.verify(
+                    latest_ledger_info.ledger_info(),
+                    Some(expected_start_version)
+                ));
+                expected_start_version += transactions_with_proof.transactions.len() as u64;
+            }
+            DataPayload::EndOfStream => {
+                assert_eq!(expected_start_version, latest_version + 1);
+            }
+            data_payload => panic!("Unexpected payload: {:?}", data_payload),
+        }
+    }
+
+    // Verify unprovable ranges are rejected
+    assert_matches!(
+        streaming_client
+            .get_all_transaction_outputs(1, 10, 11)
+            .await,
+        Err(Error::DataIsUnavailable(_))
+    );
+    assert_matches!(
+        streaming_client.get_all_state_values(0, None).await,
+        Err(Error::UnsupportedRequestEncountered(_))
+    );
+}
+
+#[tokio::test]
+async fn test_streaming_client_continuous_outputs() {
+    // Create a synthetic chain
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig::default()));
+    let streaming_client = SyntheticStreamingClient::new(chain.clone());
+
+    // Continuously stream outputs to a target in epoch 2
+    let target = chain.get_epoch_ending_ledger_info(2);
+    let mut data_stream_listener = streaming_client
+        .continuously_stream_transaction_outputs(0, 1, Some(target.clone()))
+        .await
+        .unwrap();
+
+    // Verify the chunks don't cross epochs and verify against the attached ledger infos
+    let mut expected_start_version = 1;
+    for data_notification in drain_data_stream(&mut data_stream_listener) {
+        match data_notification.data_payload {
+            DataPayload::ContinuousTransactionOutputsWithProof(ledger_info, outputs_with_proof) => {
+                assert_ok!(outputs_with_proof
+                    .verify(ledger_info.ledger_info(), Some(expected_start_version)));
+                let end_version = expected_start_version
+                    + outputs_with_proof.transactions_and_outputs.len() as u64
+                    - 1;
+                assert_eq!(
+                    chain.get_epoch_at_version(expected_start_version),
+                    chain.get_epoch_at_version(end_version)
+                );
+                assert_eq!(
+                    ledger_info,
+                    chain.get_epoch_ending_ledger_info_at_version(end_version)
+                );
+                expected_start_version = end_version + 1;
+            }
+            data_payload => panic!("Unexpected payload: {:?}", data_payload),
+        }
+    }
+    assert_eq!(expected_start_version, target.ledger_info().version() + 1);
+}
+
+#[tokio::test]
+async fn test_streaming_client_faults() {
+    // Create a synthetic chain and a streaming client with injected faults
+    let chain = Arc::new(SyntheticChain::new(SyntheticChainConfig::default()));
+    let streaming_client = SyntheticStreamingClient::new(chain.clone());
+    streaming_client.inject_fault(0, NotificationFault::CorruptProof);
+    streaming_client.inject_fault(1, NotificationFault::Drop);
+    streaming_client.inject_fault(2, NotificationFault::StaleRange);
+
+    // Stream the transactions of the first epoch
+    let proof_version = chain
+        .get_epoch_ending_ledger_info(1)
+        .ledger_info()
+        .version();
+    let mut data_stream_listener = streaming_client
+        .get_all_transactions(1, proof_version, proof_version, false)
+        .await
+        .unwrap();
+    let data_notifications = drain_data_stream(&mut data_stream_listener);
+
+    // Verify the first notification has a corrupt proof
+    let ledger_info = chain.get_epoch_ending_ledger_info(1);
+    let transactions_with_proof = get_transactions_with_proof(&data_notifications[0]);
+    assert_eq!(data_notifications[0].notification_id, 0);
+    assert_err!(transactions_with_proof.verify(ledger_info.ledger_info(), Some(1)));
+
+    // Verify the second notification was dropped, and the third is stale
+    // (i.e., it serves the range of the dropped notification).
+    assert_eq!(data_notifications[1].notification_id, 2);
+    let transactions_with_proof = get_transactions_with_proof(&data_notifications[1]);
+    assert_eq!(transactions_with_proof.first_transaction_version, Some(11));
+    assert_ok!(transactions_with_proof.verify(ledger_info.ledger_info(), Some(11)));
+
+    // Verify the stale notification replaced the last range (and the stream ended)
+    assert_eq!(data_notifications.len(), 3);
+    assert_eq!(data_notifications[2].notification_id, 3);
+    assert_matches!(data_notifications[2].data_payload, DataPayload::EndOfStream);
+
+    // Verify notification IDs continue across streams, and corrupt ledger infos fail verification
+    streaming_client.inject_fault(4, NotificationFault::CorruptProof);
+    let mut data_stream_listener = streaming_client
+        .get_all_epoch_ending_ledger_infos(1)
+        .await
+        .unwrap();
+    let data_notification = data_stream_listener.next().await.unwrap();
+    assert_eq!(data_notification.notification_id, 4);
+    match data_notification.data_payload {
+        DataPayload::EpochEndingLedgerInfos(ledger_infos) => {
+            assert_err!(chain.get_epoch_state(1).verify(&ledger_infos[0]));
+        }
+        data_payload => panic!("Unexpected payload: {:?}", data_payload),
+    }
+
+    // Terminate the stream and verify the feedback is recorded
+    streaming_client
+        .terminate_stream_with_feedback(4, NotificationFeedback::PayloadProofFailed)
+        .await
+        .unwrap();
+    assert_eq!(
+        streaming_client.get_terminated_streams(),
+        vec![(4, NotificationFeedback::PayloadProofFailed)]
+    );
+}
+
+/// Returns all notifications currently pending on the given data stream
+fn drain_data_stream(data_stream_listener: &mut DataStreamListener) -> Vec<DataNotification> {
+    let mut data_notifications = vec![];
+    while let Some(Some(data_notification)) = data_stream_listener.next().now_or_never() {
+        data_notifications.push(data_notification);
+    }
+    data_notifications
+}
+
+/// Returns the transactions with proof in the given notification
+fn get_transactions_with_proof(data_notification: &DataNotification) -> TransactionListWithProof {
+    match &data_notification.data_payload {
+        DataPayload::TransactionsWithProof(transactions_with_proof) => {
+            transactions_with_proof.clone()
+        }
+        data_payload => panic!("Unexpected payload: {:?}", data_payload),
+    }
+}
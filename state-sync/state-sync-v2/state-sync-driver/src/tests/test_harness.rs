@@ -0,0 +1,178 @@
+// Copyright (c) Aptos
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::test_utils::test_harness::TestHarness;
+use aptos_config::config::{BootstrappingMode, ContinuousSyncingMode};
+use claim::assert_matches;
+use data_streaming_service::streaming_client::{
+    GetAllStatesRequest, GetAllTransactionOutputsRequest, StreamRequest,
+};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_output_bootstrap_from_genesis() {
+    // Create a fullnode that bootstraps by applying outputs from genesis
+    let test_harness = TestHarness::new(
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    );
+    let chain = test_harness.get_chain();
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+
+    // Wait for the node to bootstrap (to the last epoch end) and catch up
+    test_harness.wait_for_bootstrapping().await;
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    // Verify the database matches the chain (including the state snapshots
+    // persisted at the end of each epoch).
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(0, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+    for epoch in 1..=chain.get_latest_epoch() {
+        let epoch_ending_ledger_info = chain.get_epoch_ending_ledger_info(epoch);
+        test_harness.verify_state_snapshot(epoch_ending_ledger_info.ledger_info().version());
+    }
+
+    // Verify the first epoch was bootstrapped using outputs (proven by the
+    // highest known epoch ending ledger info).
+    let stream_requests = test_harness.get_stream_requests();
+    assert_matches!(
+        stream_requests[0],
+        StreamRequest::GetAllEpochEndingLedgerInfos(_)
+    );
+    let last_epoch_ending_ledger_info =
+        chain.get_epoch_ending_ledger_info(chain.get_latest_epoch());
+    assert!(
+        stream_requests.contains(&StreamRequest::GetAllTransactionOutputs(
+            GetAllTransactionOutputsRequest {
+                start_version: 1,
+                end_version: chain
+                    .get_epoch_ending_ledger_info(1)
+                    .ledger_info()
+                    .version(),
+                proof_version: last_epoch_ending_ledger_info.ledger_info().version(),
+            }
+        ))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snapshot_bootstrap() {
+    // Create a fullnode that bootstraps by downloading the latest states
+    let test_harness = TestHarness::new(
+        BootstrappingMode::DownloadLatestStates,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    );
+    let chain = test_harness.get_chain();
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+
+    // Wait for the node to bootstrap (at the last epoch end) and catch up
+    test_harness.wait_for_bootstrapping().await;
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    // Verify the database matches the chain from the snapshot onwards
+    let snapshot_version = chain
+        .get_epoch_ending_ledger_info(chain.get_latest_epoch())
+        .ledger_info()
+        .version();
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(snapshot_version, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+    test_harness.verify_state_snapshot(snapshot_version);
+
+    // Verify the states were synced at the snapshot version
+    let stream_requests = test_harness.get_stream_requests();
+    assert!(
+        stream_requests.contains(&StreamRequest::GetAllStates(GetAllStatesRequest {
+            version: snapshot_version,
+            start_index: 0,
+        }))
+    );
+    assert!(!stream_requests.iter().any(|stream_request| matches!(
+        stream_request,
+        StreamRequest::GetAllTransactionOutputs(request) if request.start_version < snapshot_version
+    )));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_continuous_sync_across_reconfigurations() {
+    // Create a fullnode that executes transactions to stay up-to-date
+    let test_harness = TestHarness::new(
+        BootstrappingMode::ExecuteTransactionsFromGenesis,
+        ContinuousSyncingMode::ExecuteTransactions,
+    );
+    let chain = test_harness.get_chain();
+
+    // Advertise genesis and wait for the node to bootstrap (there's nothing to sync)
+    test_harness.advertise_ledger_info(&chain.get_epoch_ending_ledger_info(0));
+    test_harness.wait_for_bootstrapping().await;
+
+    // Advertise the rest of the chain and wait for the node to catch up
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    // Verify the database matches the chain (including the epoch changes)
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(0, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+    for epoch in 1..=chain.get_latest_epoch() {
+        let epoch_ending_ledger_info = chain.get_epoch_ending_ledger_info(epoch);
+        test_harness.verify_state_snapshot(epoch_ending_ledger_info.ledger_info().version());
+    }
+
+    // Verify all data was synced by the continuous syncer (from genesis)
+    let stream_requests = test_harness.get_stream_requests();
+    assert!(matches!(
+        &stream_requests[0],
+        StreamRequest::ContinuouslyStreamTransactions(request) if request.known_version == 0
+    ));
+    assert!(stream_requests.iter().all(|stream_request| matches!(
+        stream_request,
+        StreamRequest::ContinuouslyStreamTransactions(_)
+    )));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_restart_resumes_sync() {
+    // Create a fullnode that bootstraps by applying outputs from genesis
+    let mut test_harness = TestHarness::new(
+        BootstrappingMode::ApplyTransactionOutputsFromGenesis,
+        ContinuousSyncingMode::ApplyTransactionOutputs,
+    );
+    let chain = test_harness.get_chain();
+
+    // Advertise the end of the second epoch and wait for the node to sync it
+    let epoch_ending_ledger_info = chain.get_epoch_ending_ledger_info(2);
+    let epoch_ending_version = epoch_ending_ledger_info.ledger_info().version();
+    test_harness.advertise_ledger_info(&epoch_ending_ledger_info);
+    test_harness.wait_for_version(epoch_ending_version).await;
+
+    // Restart the driver, advertise the rest of the chain and wait for the
+    // node to catch up.
+    let num_stream_requests = test_harness.get_stream_requests().len();
+    test_harness.restart_driver();
+    let latest_ledger_info = chain.get_latest_ledger_info();
+    test_harness.advertise_ledger_info(&latest_ledger_info);
+    test_harness.wait_for_bootstrapping().await;
+    let latest_version = chain.get_latest_version();
+    test_harness.wait_for_version(latest_version).await;
+
+    // Verify the database matches the chain
+    test_harness.verify_synced_ledger_info(&latest_ledger_info);
+    test_harness.verify_transaction_infos(0, latest_version);
+    test_harness.verify_epoch_ending_ledger_infos();
+
+    // Verify the restarted driver didn't resync the data that was already committed
+    let stream_requests = test_harness.get_stream_requests();
+    assert!(!stream_requests[num_stream_requests..]
+        .iter()
+        .any(|stream_request| matches!(
+            stream_request,
+            StreamRequest::GetAllTransactionOutputs(request) if request.start_version <= epoch_ending_version
+        )));
+}
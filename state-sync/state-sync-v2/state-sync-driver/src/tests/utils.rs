@@ -1,44 +1,51 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::driver::DriverConfiguration;
-use aptos_config::config::{RoleType, StateSyncDriverConfig};
-use aptos_crypto::{
-    ed25519::{Ed25519PrivateKey, Ed25519Signature},
-    HashValue, PrivateKey, Uniform,
+use crate::{
+    driver::DriverConfiguration,
+    error::Error,
+    metrics::DriverMetrics,
+    notification_handlers::{ErrorNotification, ErrorSeverity, ErrorSource},
+    storage_synchronizer::{FailedOperation, StorageSynchronizerStage},
+    utils::OutputFallbackHandler,
 };
-use aptos_data_client::GlobalDataSummary;
+use aptos_config::config::{RoleType, StateSyncDriverConfig};
+use aptos_crypto::HashValue;
+use aptos_time_service::TimeService;
 use aptos_types::aggregate_signature::AggregateSignature;
-use aptos_types::on_chain_config::ValidatorSet;
 use aptos_types::{
-    account_address::AccountAddress,
     block_info::BlockInfo,
-    chain_id::ChainId,
     contract_event::ContractEvent,
     epoch_state::EpochState,
-    event::EventKey,
-    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    ledger_info::{generate_ledger_info_with_sig, LedgerInfo, LedgerInfoWithSignatures},
     proof::{
         SparseMerkleRangeProof, TransactionAccumulatorRangeProof, TransactionInfoListWithProof,
     },
     state_store::state_value::StateValueChunkWithProof,
     transaction::{
-        ExecutionStatus, RawTransaction, Script, SignedTransaction, Transaction, TransactionInfo,
-        TransactionListWithProof, TransactionOutput, TransactionOutputListWithProof,
-        TransactionPayload, TransactionStatus, Version,
+        ExecutionStatus, Transaction, TransactionListWithProof, TransactionOutput,
+        TransactionOutputListWithProof, TransactionStatus, Version,
     },
+    validator_signer::ValidatorSigner,
     waypoint::Waypoint,
     write_set::WriteSet,
 };
 use channel::{aptos_channel, aptos_channel::Sender, message_queues::QueueStyle};
 use data_streaming_service::{
-    data_notification::DataNotification, data_stream::DataStreamListener, streaming_client::Epoch,
+    data_notification::{DataNotification, NotificationId},
+    data_stream::DataStreamListener,
+    streaming_client::Epoch,
 };
 use event_notifications::EventNotificationListener;
 use futures::StreamExt;
 use mempool_notifications::{CommittedTransaction, MempoolNotificationListener};
-use move_deps::move_core_types::language_storage::TypeTag;
-use storage_service_types::responses::CompleteDataRange;
+
+// The generators shared with the benchmarks live in the test utilities
+pub use crate::test_utils::{
+    create_epoch_ending_ledger_info, create_epoch_ending_waypoint, create_event,
+    create_global_summary, create_global_summary_at_ledger_info, create_ledger_info_at_version,
+    create_transaction, create_transaction_info,
+};
 
 /// Creates a new data stream listener and notification sender pair
 pub fn create_data_stream_listener() -> (Sender<(), DataNotification>, DataStreamListener) {
@@ -49,18 +56,6 @@ pub fn create_data_stream_listener() -> (Sender<(), DataNotification>, DataStrea
     (notification_sender, data_stream_listener)
 }
 
-/// Creates a test epoch ending ledger info
-pub fn create_epoch_ending_ledger_info() -> LedgerInfoWithSignatures {
-    let ledger_info = LedgerInfo::genesis(HashValue::zero(), ValidatorSet::empty());
-    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
-}
-
-/// Creates a single test event
-pub fn create_event(event_key: Option<EventKey>) -> ContractEvent {
-    let event_key = event_key.unwrap_or_else(EventKey::random);
-    ContractEvent::new(event_key, 0, TypeTag::Bool, bcs::to_bytes(&0).unwrap())
-}
-
 /// Creates a test driver configuration for full nodes
 pub fn create_full_node_driver_configuration() -> DriverConfiguration {
     let config = StateSyncDriverConfig::default();
@@ -69,25 +64,39 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
 
     DriverConfiguration {
         config,
+        dry_run: false,
+        metrics: DriverMetrics::default(),
         role,
+        tooling: false,
         waypoint,
     }
 }
 
-/// Creates a global data summary with the highest ended epoch
-pub fn create_global_summary(highest_ended_epoch: Epoch) -> GlobalDataSummary {
-    let mut global_data_summary = GlobalDataSummary::empty();
-    global_data_summary
-        .advertised_data
-        .epoch_ending_ledger_infos = vec![CompleteDataRange::new(0, highest_ended_epoch).unwrap()];
-    global_data_summary
+/// Creates an output fallback handler (using the default config) for testing
+pub fn create_output_fallback_handler() -> OutputFallbackHandler {
+    OutputFallbackHandler::new(
+        StateSyncDriverConfig::default(),
+        DriverMetrics::default(),
+        TimeService::real(),
+    )
 }
 
-/// Creates a new ledger info with signatures at the specified version
-pub fn create_ledger_info_at_version(version: Version) -> LedgerInfoWithSignatures {
-    let block_info = BlockInfo::new(0, 0, HashValue::zero(), HashValue::zero(), version, 0, None);
-    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
-    LedgerInfoWithSignatures::new(ledger_info, AggregateSignature::empty())
+/// Creates an error notification for a failed chunk with the given values
+pub fn create_error_notification(
+    component: ErrorSource,
+    severity: ErrorSeverity,
+    notification_id: NotificationId,
+) -> ErrorNotification {
+    ErrorNotification {
+        component,
+        error: Error::UnexpectedError("Failed to execute chunk!".into()),
+        expected_version: None,
+        failed_operation: FailedOperation::ExecuteChunk,
+        notification_id,
+        severity,
+        stage: StorageSynchronizerStage::Executor,
+        version_range: None,
+    }
 }
 
 /// Creates a test transaction output list with proof
@@ -131,6 +140,25 @@ pub fn create_epoch_state(epoch: u64) -> EpochState {
     epoch_state
 }
 
+/// Creates a ledger info at the given version and epoch, signed by all validator signers
+pub fn create_signed_ledger_info(
+    validator_signers: &[ValidatorSigner],
+    version: Version,
+    epoch: u64,
+) -> LedgerInfoWithSignatures {
+    let block_info = BlockInfo::new(
+        epoch,
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        version,
+        0,
+        None,
+    );
+    let ledger_info = LedgerInfo::new(block_info, HashValue::random());
+    generate_ledger_info_with_sig(validator_signers, ledger_info)
+}
+
 /// Creates a test state value chunk with proof
 pub fn create_state_value_chunk_with_proof(last_chunk: bool) -> StateValueChunkWithProof {
     let right_siblings = if last_chunk {
@@ -149,42 +177,6 @@ pub fn create_state_value_chunk_with_proof(last_chunk: bool) -> StateValueChunkW
     }
 }
 
-/// Creates a single test transaction
-pub fn create_transaction() -> Transaction {
-    let private_key = Ed25519PrivateKey::generate_for_testing();
-    let public_key = private_key.public_key();
-
-    let transaction_payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
-    let raw_transaction = RawTransaction::new(
-        AccountAddress::random(),
-        0,
-        transaction_payload,
-        0,
-        0,
-        0,
-        ChainId::new(10),
-    );
-    let signed_transaction = SignedTransaction::new(
-        raw_transaction,
-        public_key,
-        Ed25519Signature::dummy_signature(),
-    );
-
-    Transaction::UserTransaction(signed_transaction)
-}
-
-/// Creates a test transaction info
-pub fn create_transaction_info() -> TransactionInfo {
-    TransactionInfo::new(
-        HashValue::random(),
-        HashValue::random(),
-        HashValue::random(),
-        Some(HashValue::random()),
-        0,
-        ExecutionStatus::Success,
-    )
-}
-
 /// Creates a test transaction info list with proof
 pub fn create_transaction_info_list_with_proof() -> TransactionInfoListWithProof {
     TransactionInfoListWithProof::new(
@@ -214,6 +206,13 @@ pub fn create_transaction_output() -> TransactionOutput {
     )
 }
 
+/// Creates a set of validator signers (deterministically derived from the seed offset)
+pub fn create_validator_signers(seed_offset: u8) -> Vec<ValidatorSigner> {
+    (0..4)
+        .map(|index| ValidatorSigner::random([seed_offset + index; 32]))
+        .collect()
+}
+
 /// Verifies that mempool is notified about the committed transactions and
 /// verifies that the event listener is notified about the committed
 /// events (if it exists).
@@ -4,41 +4,64 @@
 use crate::{
     error::Error,
     logging::{LogEntry, LogSchema},
-    metrics,
+    metrics::{self, DriverMetrics},
     notification_handlers::{
-        CommitNotification, CommittedTransactions, MempoolNotificationHandler,
+        CommitNotification, CommitOrigin, CommitStateTracker, CommitSubscriberRegistry,
+        CommitSummary, CommittedTransactionChunk, CommittedTransactions, EpochTransition,
+        EventNotificationHandler, MempoolNotificationHandler, NotifiedVersionTracker,
+    },
+    storage_synchronizer::{
+        ApplyOrExecute, BackpressureListener, BackpressureState, TransactionChunkPayload,
     },
 };
+use aptos_config::config::StateSyncDriverConfig;
+use aptos_data_client::GlobalDataSummary;
 use aptos_infallible::Mutex;
-use aptos_logger::prelude::*;
+use aptos_logger::{prelude::*, sample, sample::SampleRate};
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
-    epoch_change::Verifier, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
-    transaction::Version,
+    contract_event::ContractEvent,
+    epoch_change::Verifier,
+    epoch_state::EpochState,
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
+    waypoint::Waypoint,
 };
 use data_streaming_service::{
     data_notification::{DataNotification, DataPayload, NotificationId},
     data_stream::DataStreamListener,
-    streaming_client::{DataStreamingClient, NotificationFeedback},
+    streaming_client::{DataStreamingClient, NotificationFeedback, StreamRequest},
 };
-use event_notifications::EventSubscriptionService;
+use event_notifications::is_reconfiguration_event;
+use fail::fail_point;
 use futures::StreamExt;
 use mempool_notifications::MempoolNotificationSender;
-use std::{sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use storage_interface::DbReader;
-use tokio::time::timeout;
+use tokio::time::{sleep, timeout};
 
 // TODO(joshlind): make these configurable!
+const COMMIT_SUMMARY_LOG_FREQ_SECS: u64 = 5;
 const MAX_NUM_DATA_STREAM_TIMEOUTS: u64 = 3;
+const MAX_STREAM_INITIALIZATION_BACKOFF_DOUBLINGS: u64 = 10;
 pub const PENDING_DATA_LOG_FREQ_SECS: u64 = 3;
-
-// TODO(joshlind): add unit tests to the speculative stream state.
+pub const STORAGE_EMPTY_LOG_FREQ_SECS: u64 = 10;
 
 /// The speculative state that tracks a data stream of transactions or outputs.
 /// This assumes all data is valid and allows the driver to speculatively verify
 /// payloads flowing along the stream without having to block on the executor or
 /// storage. Thus, increasing syncing performance.
+///
+/// The state is shared by the bootstrapper and the continuous syncer, and only
+/// lives as long as the stream: it must be discarded whenever the stream is
+/// terminated or reset (the next stream starts from the state in storage).
 pub struct SpeculativeStreamState {
     epoch_state: EpochState,
+    highest_verified_ledger_info: Option<LedgerInfoWithSignatures>,
     proof_ledger_info: Option<LedgerInfoWithSignatures>,
     synced_version: Version,
 }
@@ -51,6 +74,7 @@ impl SpeculativeStreamState {
     ) -> Self {
         Self {
             epoch_state,
+            highest_verified_ledger_info: None,
             proof_ledger_info,
             synced_version,
         }
@@ -63,6 +87,16 @@ impl SpeculativeStreamState {
         })
     }
 
+    /// Returns the epoch state that the stream's ledger infos are verified against
+    pub fn get_epoch_state(&self) -> EpochState {
+        self.epoch_state.clone()
+    }
+
+    /// Returns the highest ledger info verified along the stream (if any)
+    pub fn get_highest_verified_ledger_info(&self) -> Option<LedgerInfoWithSignatures> {
+        self.highest_verified_ledger_info.clone()
+    }
+
     /// Returns the proof ledger info that all data along the stream should have
     /// proofs relative to. This assumes the proof ledger info exists!
     pub fn get_proof_ledger_info(&self) -> LedgerInfoWithSignatures {
@@ -72,25 +106,73 @@ impl SpeculativeStreamState {
             .clone()
     }
 
-    /// Updates the currently synced version of the stream
-    pub fn update_synced_version(&mut self, synced_version: Version) {
-        self.synced_version = synced_version;
+    /// Returns the highest version processed along the stream (i.e., the
+    /// highest version sent to the storage synchronizer).
+    pub fn get_synced_version(&self) -> Version {
+        self.synced_version
     }
 
-    /// Updates the epoch state if we've hit the specified target ledger
-    /// info version and the ledger info has a new epoch state.
-    pub fn maybe_update_epoch_state(
+    /// Updates the state with a payload of transactions or outputs that has
+    /// been processed (i.e., sent to the storage synchronizer) and returns the
+    /// new synced version. Payloads must arrive in order, so any payload that
+    /// doesn't start at the expected next version is rejected. If the given
+    /// proof ledger info ends the epoch at the new synced version, the epoch
+    /// state moves to the next epoch.
+    pub fn update_with_payload(
         &mut self,
-        ledger_info_with_signatures: LedgerInfoWithSignatures,
-    ) {
-        if let Some(epoch_state) = ledger_info_with_signatures.ledger_info().next_epoch_state() {
-            if ledger_info_with_signatures.ledger_info().version() == self.synced_version {
-                self.epoch_state = epoch_state.clone();
+        payload_start_version: Version,
+        num_transactions_or_outputs: usize,
+        ledger_info_with_signatures: Option<LedgerInfoWithSignatures>,
+    ) -> Result<Version, Error> {
+        // Verify the payload is the next one along the stream
+        let expected_next_version = self.expected_next_version()?;
+        if payload_start_version != expected_next_version {
+            return Err(Error::VerificationError(format!(
+                "The payload start version does not match the expected version! Start: {:?}, expected: {:?}",
+                payload_start_version, expected_next_version
+            )));
+        }
+
+        // Calculate the new synced version (synced_version = start + num txns/outputs - 1)
+        let synced_version = payload_start_version
+            .checked_add(num_transactions_or_outputs as u64)
+            .and_then(|version| version.checked_sub(1))
+            .ok_or_else(|| Error::IntegerOverflow("The synced version has overflown!".into()))?;
+
+        // Update the epoch state if the ledger info ends the epoch at the synced version
+        if let Some(ledger_info_with_signatures) = ledger_info_with_signatures {
+            let ledger_info = ledger_info_with_signatures.ledger_info();
+            if let Some(next_epoch_state) = ledger_info.next_epoch_state() {
+                if ledger_info.version() == synced_version {
+                    self.update_epoch_state(next_epoch_state.clone())?;
+                }
             }
         }
+
+        // Update the synced version
+        self.synced_version = synced_version;
+
+        Ok(synced_version)
+    }
+
+    /// Updates the epoch state (e.g., once an epoch ending ledger info is
+    /// verified). Epoch states must move forward, so any epoch state that
+    /// isn't for a higher epoch is rejected.
+    pub fn update_epoch_state(&mut self, epoch_state: EpochState) -> Result<(), Error> {
+        if epoch_state.epoch <= self.epoch_state.epoch {
+            return Err(Error::VerificationError(format!(
+                "The new epoch state must be for a higher epoch! New epoch: {:?}, current epoch: {:?}",
+                epoch_state.epoch, self.epoch_state.epoch
+            )));
+        }
+        self.epoch_state = epoch_state;
+
+        Ok(())
     }
 
-    /// Verifies the given ledger info with signatures against the current epoch state
+    /// Verifies the given ledger info with signatures against the current
+    /// epoch state. If verification succeeds, the ledger info is tracked as
+    /// the highest verified ledger info (if it's higher than the last).
     pub fn verify_ledger_info_with_signatures(
         &mut self,
         ledger_info_with_signatures: &LedgerInfoWithSignatures,
@@ -99,7 +181,463 @@ impl SpeculativeStreamState {
             .verify(ledger_info_with_signatures)
             .map_err(|error| {
                 Error::VerificationError(format!("Ledger info failed verification: {:?}", error))
-            })
+            })?;
+        self.update_highest_verified_ledger_info(ledger_info_with_signatures.clone());
+
+        Ok(())
+    }
+
+    /// Updates the highest verified ledger info (if the given ledger info is higher)
+    fn update_highest_verified_ledger_info(
+        &mut self,
+        ledger_info_with_signatures: LedgerInfoWithSignatures,
+    ) {
+        let is_higher =
+            self.highest_verified_ledger_info
+                .as_ref()
+                .map_or(true, |highest_ledger_info| {
+                    ledger_info_with_signatures.ledger_info().version()
+                        > highest_ledger_info.ledger_info().version()
+                });
+        if is_higher {
+            self.highest_verified_ledger_info = Some(ledger_info_with_signatures);
+        }
+    }
+}
+
+/// Falls back from executing transactions to applying transaction outputs if
+/// the same chunk repeatedly fails execution (e.g., due to a deterministic
+/// execution failure, such as a local VM or feature mismatch). Otherwise, the
+/// node would loop on the failing chunk forever. Execution failures are counted
+/// per version range, and the fallback ends once the cool-down expires (if one
+/// is configured). The handler is shared by the bootstrapper and the
+/// continuous syncer, which switch their syncing modes accordingly.
+#[derive(Clone)]
+pub struct OutputFallbackHandler {
+    driver_config: StateSyncDriverConfig,
+    driver_metrics: DriverMetrics,
+    execution_failures: Arc<Mutex<HashMap<(Version, Version), Vec<Instant>>>>,
+    fallback_start_time: Arc<Mutex<Option<Instant>>>,
+    time_service: TimeService,
+}
+
+impl OutputFallbackHandler {
+    pub fn new(
+        driver_config: StateSyncDriverConfig,
+        driver_metrics: DriverMetrics,
+        time_service: TimeService,
+    ) -> Self {
+        Self {
+            driver_config,
+            driver_metrics,
+            execution_failures: Arc::new(Mutex::new(HashMap::new())),
+            fallback_start_time: Arc::new(Mutex::new(None)),
+            time_service,
+        }
+    }
+
+    /// Records an execution failure for the given (inclusive) version range.
+    /// If the range has failed too many times within the failure window, the
+    /// output fallback is started.
+    pub fn record_execution_failure(&self, version_range: (Version, Version)) {
+        let max_execution_failures = self.driver_config.max_execution_failures_before_fallback;
+        if max_execution_failures == 0 {
+            return; // The fallback is disabled
+        }
+
+        // Record the failure (ignoring any failures outside the window)
+        let now = self.time_service.now();
+        let failure_window = Duration::from_secs(self.driver_config.execution_failure_window_secs);
+        let num_failures = {
+            let mut execution_failures = self.execution_failures.lock();
+            execution_failures.retain(|_, failure_times| {
+                failure_times.retain(|failure_time| {
+                    now.saturating_duration_since(*failure_time) < failure_window
+                });
+                !failure_times.is_empty()
+            });
+            let failure_times = execution_failures.entry(version_range).or_default();
+            failure_times.push(now);
+            failure_times.len() as u64
+        };
+
+        // Start the fallback if the range has failed too many times
+        if num_failures >= max_execution_failures {
+            self.start_fallback(version_range, num_failures, now);
+        }
+    }
+
+    /// Returns true iff the node should apply transaction outputs instead of
+    /// executing transactions. If the cool-down has expired, the fallback ends.
+    pub fn in_fallback_mode(&self) -> bool {
+        let mut fallback_start_time = self.fallback_start_time.lock();
+        let start_time = match *fallback_start_time {
+            Some(start_time) => start_time,
+            None => return false,
+        };
+
+        // Check if the cool-down has expired (a cool-down of 0 never expires)
+        let cooldown_secs = self.driver_config.output_fallback_cooldown_secs;
+        let elapsed_time = self
+            .time_service
+            .now()
+            .saturating_duration_since(start_time);
+        if cooldown_secs == 0 || elapsed_time < Duration::from_secs(cooldown_secs) {
+            return true;
+        }
+
+        // End the fallback
+        *fallback_start_time = None;
+        info!(LogSchema::new(LogEntry::Driver).message(&format!(
+            "The output fallback cool-down of {:?} secs has expired! Reverting to executing transactions.",
+            cooldown_secs
+        )));
+        self.driver_metrics
+            .increment_counter(&metrics::DRIVER_COUNTERS, metrics::OUTPUT_FALLBACK_REVERTED);
+        false
+    }
+
+    /// Starts the output fallback (if it isn't already active)
+    fn start_fallback(&self, version_range: (Version, Version), num_failures: u64, now: Instant) {
+        let mut fallback_start_time = self.fallback_start_time.lock();
+        if fallback_start_time.is_some() {
+            return; // The fallback is already active
+        }
+        *fallback_start_time = Some(now);
+        self.execution_failures.lock().clear();
+
+        warn!(LogSchema::new(LogEntry::Driver).message(&format!(
+            "Execution of versions {:?} failed {:?} times within {:?} secs! Falling back to \
+            applying transaction outputs (the cool-down is {:?} secs).",
+            version_range,
+            num_failures,
+            self.driver_config.execution_failure_window_secs,
+            self.driver_config.output_fallback_cooldown_secs
+        )));
+        self.driver_metrics
+            .increment_counter(&metrics::DRIVER_COUNTERS, metrics::OUTPUT_FALLBACK_STARTED);
+    }
+}
+
+/// The lag of the node behind the highest data advertised by its peers. If
+/// the node is ahead of the network (e.g., the advertisements are stale), the
+/// lags are clamped to zero and `ahead_of_network` is set instead.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SyncLag {
+    pub ahead_of_network: bool, // True iff the node is ahead of the advertised version or epoch
+    pub epoch_lag: u64,         // The number of epochs the node is behind the advertised epoch
+    pub version_lag: u64,       // The number of versions the node is behind the advertised version
+}
+
+/// Calculates the sync lag of the node (given the latest synced version and
+/// epoch) using the highest data advertised in the global data summary. If
+/// nothing has been synced (i.e., storage is empty), the node lags behind all
+/// advertised data (including genesis). Returns None if the summary doesn't
+/// advertise a synced ledger info, or if the advertisements are malformed
+/// (i.e., a higher epoch was ended than the highest synced ledger info).
+pub fn calculate_sync_lag(
+    global_data_summary: &GlobalDataSummary,
+    synced_version: Option<Version>,
+    synced_epoch: Option<u64>,
+) -> Option<SyncLag> {
+    // Identify the highest advertised version and epoch
+    let advertised_data = &global_data_summary.advertised_data;
+    let highest_synced_ledger_info = advertised_data.highest_synced_ledger_info()?;
+    let advertised_version = highest_synced_ledger_info.ledger_info().version();
+    let advertised_epoch = highest_synced_ledger_info.ledger_info().next_block_epoch();
+
+    // Verify the advertisements are consistent with one another
+    if let Some(highest_ended_epoch) = advertised_data.highest_epoch_ending_ledger_info() {
+        if highest_ended_epoch >= advertised_epoch {
+            return None;
+        }
+    }
+
+    // Calculate the lags (clamping them at zero if we're ahead of the network)
+    let (version_lag, version_ahead) = calculate_lag(advertised_version, synced_version);
+    let (epoch_lag, epoch_ahead) = calculate_lag(advertised_epoch, synced_epoch);
+    Some(SyncLag {
+        ahead_of_network: version_ahead || epoch_ahead,
+        epoch_lag,
+        version_lag,
+    })
+}
+
+/// Returns the (clamped) lag of the synced value behind the advertised
+/// value, and whether the synced value is ahead of the advertised value.
+fn calculate_lag(advertised_value: u64, synced_value: Option<u64>) -> (u64, bool) {
+    match synced_value {
+        Some(synced_value) => (
+            advertised_value.saturating_sub(synced_value),
+            synced_value > advertised_value,
+        ),
+        None => (advertised_value.saturating_add(1), false), // Nothing has been synced
+    }
+}
+
+/// Returns the commit timestamp (in microseconds) of the given ledger info
+pub fn get_ledger_info_timestamp_usecs(ledger_info: &LedgerInfoWithSignatures) -> u64 {
+    ledger_info.ledger_info().timestamp_usecs()
+}
+
+/// Calculates the age of the given ledger info (i.e., the time elapsed since
+/// its block was committed) using the given time service. Returns None for
+/// the genesis ledger info, as it has no meaningful timestamp (i.e., zero).
+/// If the ledger info is from the future (e.g., due to clock skew), the age
+/// is clamped to zero.
+pub fn calculate_ledger_info_age(
+    ledger_info: &LedgerInfoWithSignatures,
+    time_service: &TimeService,
+) -> Option<Duration> {
+    let timestamp_usecs = get_ledger_info_timestamp_usecs(ledger_info);
+    if timestamp_usecs == 0 {
+        return None; // This is the genesis ledger info
+    }
+    let current_time = time_service.now_unix_time();
+    Some(current_time.saturating_sub(Duration::from_micros(timestamp_usecs)))
+}
+
+/// Returns true iff the storage synchronizer is backpressured, i.e., its
+/// commit backlog is too deep and no new data should be fed into it.
+pub fn is_backpressured(backpressure_listener: &BackpressureListener) -> bool {
+    let backpressured = *backpressure_listener.borrow() == BackpressureState::Paused;
+    if backpressured {
+        sample!(
+            SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
+            info!("Pausing stream notifications until the commit backlog drains!")
+        );
+    }
+    backpressured
+}
+
+/// The policy used to retry transient failures when initializing data streams
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct StreamRetryPolicy {
+    max_retries: u64,      // The max number of retries (0 disables retries)
+    retry_backoff_ms: u64, // The initial backoff (ms) between retries (doubled on each retry)
+}
+
+impl StreamRetryPolicy {
+    pub fn new(max_retries: u64, retry_backoff_ms: u64) -> Self {
+        Self {
+            max_retries,
+            retry_backoff_ms,
+        }
+    }
+
+    /// Creates a new retry policy using the given driver config
+    pub fn from_config(driver_config: &StateSyncDriverConfig) -> Self {
+        Self::new(
+            driver_config.max_stream_initialization_retries,
+            driver_config.stream_initialization_retry_backoff_ms,
+        )
+    }
+
+    /// Returns the backoff to wait after the given number of failed attempts
+    fn get_backoff(&self, num_failed_attempts: u64) -> Duration {
+        let num_doublings = num_failed_attempts
+            .saturating_sub(1)
+            .min(MAX_STREAM_INITIALIZATION_BACKOFF_DOUBLINGS);
+        Duration::from_millis(self.retry_backoff_ms.saturating_mul(1 << num_doublings))
+    }
+}
+
+/// A newly initialized data stream, along with the request that created it
+/// and the number of attempts it took to initialize.
+#[derive(Debug)]
+pub struct ActiveStream {
+    data_stream_listener: DataStreamListener,
+    num_attempts: u64,
+    stream_request: StreamRequest,
+}
+
+impl ActiveStream {
+    /// Returns the number of attempts it took to initialize the stream
+    pub fn get_num_attempts(&self) -> u64 {
+        self.num_attempts
+    }
+
+    /// Returns the request that created the stream
+    pub fn get_stream_request(&self) -> &StreamRequest {
+        &self.stream_request
+    }
+
+    /// Consumes the handle and returns the underlying data stream listener
+    pub fn into_data_stream_listener(self) -> DataStreamListener {
+        self.data_stream_listener
+    }
+}
+
+/// Initializes a new data stream for the given request. Transient failures of
+/// the streaming service (e.g., the data is temporarily unavailable) are
+/// retried with a bounded backoff, as specified by the retry policy. All other
+/// failures, and failures that persist once all retries are exhausted, are
+/// returned.
+pub async fn initialize_data_stream<StreamingClient: DataStreamingClient>(
+    streaming_client: &StreamingClient,
+    stream_request: StreamRequest,
+    retry_policy: StreamRetryPolicy,
+    driver_metrics: &DriverMetrics,
+) -> Result<ActiveStream, Error> {
+    let request_type = stream_request.get_label();
+    let request_range = get_stream_request_range(&stream_request);
+
+    let mut num_attempts: u64 = 0;
+    loop {
+        num_attempts += 1;
+        let error = match send_stream_request(streaming_client, &stream_request).await {
+            Ok(data_stream_listener) => {
+                driver_metrics.increment_stream_initialization(
+                    request_type,
+                    metrics::STREAM_INITIALIZATION_SUCCESS,
+                );
+                info!(LogSchema::new(LogEntry::Driver).message(&format!(
+                    "Initialized a new data stream! Request type: {:?}, range: {}, attempts: {:?}",
+                    request_type, request_range, num_attempts
+                )));
+                return Ok(ActiveStream {
+                    data_stream_listener,
+                    num_attempts,
+                    stream_request,
+                });
+            }
+            Err(error) => error,
+        };
+        driver_metrics
+            .increment_stream_initialization(request_type, metrics::STREAM_INITIALIZATION_FAILURE);
+
+        // Only retry transient errors (and only until the retries are exhausted)
+        let error_is_transient = matches!(
+            error,
+            data_streaming_service::error::Error::AptosDataClientError(_)
+                | data_streaming_service::error::Error::DataIsUnavailable(_)
+        );
+        let error: Error = error.into();
+        if !error_is_transient || num_attempts > retry_policy.max_retries {
+            warn!(LogSchema::new(LogEntry::Driver)
+                .error(&error)
+                .message(&format!(
+                    "Failed to initialize a new data stream! Request type: {:?}, range: {}, attempts: {:?}",
+                    request_type, request_range, num_attempts
+                )));
+            return Err(error);
+        }
+
+        // Backoff before retrying
+        let backoff = retry_policy.get_backoff(num_attempts);
+        warn!(LogSchema::new(LogEntry::Driver)
+            .error(&error)
+            .message(&format!(
+                "Failed to initialize a new data stream due to a transient error! \
+                Request type: {:?}, range: {}, attempts: {:?}, retrying in: {:?}",
+                request_type, request_range, num_attempts, backoff
+            )));
+        driver_metrics.increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::STREAM_INITIALIZATION_RETRY,
+        );
+        sleep(backoff).await;
+    }
+}
+
+/// Sends the given stream request to the streaming service
+async fn send_stream_request<StreamingClient: DataStreamingClient>(
+    streaming_client: &StreamingClient,
+    stream_request: &StreamRequest,
+) -> Result<DataStreamListener, data_streaming_service::error::Error> {
+    match stream_request.clone() {
+        StreamRequest::ContinuouslyStreamTransactionOutputs(request) => {
+            streaming_client
+                .continuously_stream_transaction_outputs(
+                    request.known_version,
+                    request.known_epoch,
+                    request.target,
+                )
+                .await
+        }
+        StreamRequest::ContinuouslyStreamTransactions(request) => {
+            streaming_client
+                .continuously_stream_transactions(
+                    request.known_version,
+                    request.known_epoch,
+                    request.include_events,
+                    request.target,
+                )
+                .await
+        }
+        StreamRequest::GetAllEpochEndingLedgerInfos(request) => {
+            streaming_client
+                .get_all_epoch_ending_ledger_infos(request.start_epoch)
+                .await
+        }
+        StreamRequest::GetAllStates(request) => {
+            streaming_client
+                .get_all_state_values(request.version, Some(request.start_index))
+                .await
+        }
+        StreamRequest::GetAllTransactionOutputs(request) => {
+            streaming_client
+                .get_all_transaction_outputs(
+                    request.start_version,
+                    request.end_version,
+                    request.proof_version,
+                )
+                .await
+        }
+        StreamRequest::GetAllTransactions(request) => {
+            streaming_client
+                .get_all_transactions(
+                    request.start_version,
+                    request.end_version,
+                    request.proof_version,
+                    request.include_events,
+                )
+                .await
+        }
+        StreamRequest::TerminateStream(request) => Err(
+            data_streaming_service::error::Error::UnsupportedRequestEncountered(format!(
+                "Stream termination requests cannot initialize a data stream! Request: {:?}",
+                request
+            )),
+        ),
+    }
+}
+
+/// Returns a summary of the data range targeted by the given stream request
+fn get_stream_request_range(stream_request: &StreamRequest) -> String {
+    match stream_request {
+        StreamRequest::ContinuouslyStreamTransactionOutputs(request) => format!(
+            "versions ({:?}, {:?}]",
+            request.known_version,
+            request
+                .target
+                .as_ref()
+                .map(|target| target.ledger_info().version())
+        ),
+        StreamRequest::ContinuouslyStreamTransactions(request) => format!(
+            "versions ({:?}, {:?}]",
+            request.known_version,
+            request
+                .target
+                .as_ref()
+                .map(|target| target.ledger_info().version())
+        ),
+        StreamRequest::GetAllEpochEndingLedgerInfos(request) => {
+            format!("epochs [{:?}, ..)", request.start_epoch)
+        }
+        StreamRequest::GetAllStates(request) => format!(
+            "states at version {:?} from index {:?}",
+            request.version, request.start_index
+        ),
+        StreamRequest::GetAllTransactionOutputs(request) => format!(
+            "versions [{:?}, {:?}]",
+            request.start_version, request.end_version
+        ),
+        StreamRequest::GetAllTransactions(request) => format!(
+            "versions [{:?}, {:?}]",
+            request.start_version, request.end_version
+        ),
+        StreamRequest::TerminateStream(_) => "none".into(),
     }
 }
 
@@ -151,6 +689,14 @@ pub async fn terminate_stream_with_feedback<StreamingClient: DataStreamingClient
         notification_feedback, notification_id
     )));
 
+    // Failpoint "state_sync::before_stream_termination": fails the termination
+    // before the streaming service is notified.
+    fail_point!("state_sync::before_stream_termination", |_| {
+        Err(Error::UnexpectedError(
+            "Injected error before stream termination!".into(),
+        ))
+    });
+
     streaming_client
         .terminate_stream_with_feedback(notification_id, notification_feedback)
         .await
@@ -184,8 +730,48 @@ pub async fn handle_end_of_stream_or_invalid_payload<
     }
 }
 
-/// Fetches the latest epoch state from the specified storage
-pub fn fetch_latest_epoch_state(storage: Arc<dyn DbReader>) -> Result<EpochState, Error> {
+/// Returns the transaction chunk payload required to apply or execute a
+/// chunk (as instructed). Applying requires the transaction outputs, while
+/// executing requires the transactions.
+pub fn get_transaction_chunk_payload(
+    apply_or_execute: ApplyOrExecute,
+    transaction_list_with_proof: Option<TransactionListWithProof>,
+    transaction_outputs_with_proof: Option<TransactionOutputListWithProof>,
+) -> Result<TransactionChunkPayload, Error> {
+    match apply_or_execute {
+        ApplyOrExecute::Apply => transaction_outputs_with_proof
+            .map(TransactionChunkPayload::TransactionOutputs)
+            .ok_or_else(|| {
+                Error::InvalidPayload("Did not receive transaction outputs with proof!".into())
+            }),
+        ApplyOrExecute::Execute => transaction_list_with_proof
+            .map(TransactionChunkPayload::Transactions)
+            .ok_or_else(|| {
+                Error::InvalidPayload("Did not receive transactions with proof!".into())
+            }),
+    }
+}
+
+/// Fetches the latest epoch state. The epoch state cached by the commit state
+/// tracker is used (if one exists). Otherwise, the epoch state is read from
+/// storage and cached until an epoch ending commit invalidates it.
+pub fn fetch_latest_epoch_state(
+    storage: Arc<dyn DbReader>,
+    commit_state_tracker: &CommitStateTracker,
+) -> Result<EpochState, Error> {
+    if let Some(latest_epoch_state) = commit_state_tracker.get_latest_epoch_state() {
+        return Ok(latest_epoch_state);
+    }
+
+    let latest_epoch_state = fetch_latest_epoch_state_from_storage(storage)?;
+    commit_state_tracker.update_epoch_state(latest_epoch_state.clone());
+    Ok(latest_epoch_state)
+}
+
+/// Fetches the latest epoch state from the specified storage (bypassing any cache)
+pub fn fetch_latest_epoch_state_from_storage(
+    storage: Arc<dyn DbReader>,
+) -> Result<EpochState, Error> {
     storage.get_latest_epoch_state().map_err(|error| {
         Error::StorageError(format!(
             "Failed to get the latest epoch state from storage: {:?}",
@@ -206,8 +792,10 @@ pub fn fetch_latest_synced_ledger_info(
     })
 }
 
-/// Fetches the latest synced version from the specified storage
-pub fn fetch_latest_synced_version(storage: Arc<dyn DbReader>) -> Result<Version, Error> {
+/// Fetches the latest synced version from the specified storage. Returns
+/// None if storage is empty (i.e., a new node that hasn't yet committed
+/// genesis), in which case the synced version is unknown.
+pub fn fetch_latest_synced_version(storage: Arc<dyn DbReader>) -> Result<Option<Version>, Error> {
     let latest_transaction_info =
         storage
             .get_latest_transaction_info_option()
@@ -217,23 +805,131 @@ pub fn fetch_latest_synced_version(storage: Arc<dyn DbReader>) -> Result<Version
                     error
                 ))
             })?;
-    latest_transaction_info
-        .ok_or_else(|| Error::StorageError("Latest transaction info is missing!".into()))
-        .map(|(latest_synced_version, _)| latest_synced_version)
+    Ok(latest_transaction_info.map(|(latest_synced_version, _)| latest_synced_version))
+}
+
+/// Fetches the lowest version still available in the specified storage (i.e.,
+/// the lowest version that hasn't been pruned). Returns None if storage is
+/// empty. If the database has no pruning metadata, nothing has been pruned,
+/// so all versions (from genesis) are available.
+pub fn fetch_lowest_available_version(
+    storage: Arc<dyn DbReader>,
+) -> Result<Option<Version>, Error> {
+    // If storage is empty, there's nothing available
+    let latest_synced_version = match fetch_latest_synced_version(storage.clone())? {
+        Some(latest_synced_version) => latest_synced_version,
+        None => return Ok(None),
+    };
+
+    // Fetch the lowest readable version (the pruner may be ahead of the
+    // synced version, e.g., after a state snapshot sync).
+    let first_transaction_version = storage.get_first_txn_version().map_err(|error| {
+        Error::StorageError(format!(
+            "Failed to get the first transaction version from storage: {:?}",
+            error
+        ))
+    })?;
+    let lowest_available_version = first_transaction_version.unwrap_or(0);
+    Ok(Some(lowest_available_version.min(latest_synced_version)))
+}
+
+/// Verifies that the given waypoint is consistent with the ledger history in
+/// storage (e.g., to catch a waypoint for another network at startup). If
+/// storage is empty, or hasn't yet synced to the waypoint version, there's
+/// nothing to verify (the bootstrapper verifies the waypoint once reached).
+pub fn verify_waypoint_against_storage(
+    storage: Arc<dyn DbReader>,
+    waypoint: &Waypoint,
+) -> Result<(), Error> {
+    // Fetch the latest ledger info (an empty storage has no history to check)
+    let latest_ledger_info = match storage.get_latest_ledger_info_option().map_err(|error| {
+        Error::StorageError(format!(
+            "Failed to get the latest ledger info from storage: {:?}",
+            error
+        ))
+    })? {
+        Some(latest_ledger_info) => latest_ledger_info,
+        None => return Ok(()),
+    };
+
+    // Check if storage has synced to the waypoint version
+    let waypoint_version = waypoint.version();
+    if latest_ledger_info.ledger_info().version() < waypoint_version {
+        return Ok(());
+    }
+
+    // Verify the waypoint matches the stored epoch ending ledger info
+    let epoch_ending_ledger_info = storage
+        .get_epoch_ending_ledger_info(waypoint_version)
+        .map_err(|error| {
+            Error::WaypointMismatch(
+                waypoint.to_string(),
+                format!(
+                    "no epoch ending ledger info at version {}: {:?}",
+                    waypoint_version, error
+                ),
+            )
+        })?;
+    let stored_waypoint = Waypoint::new_any(epoch_ending_ledger_info.ledger_info());
+    if stored_waypoint != *waypoint {
+        return Err(Error::WaypointMismatch(
+            waypoint.to_string(),
+            stored_waypoint.to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Logs (and counts) the committed transaction chunks that took longer than
+/// the given threshold to execute/apply and commit.
+pub fn log_slow_transaction_chunks(
+    committed_chunks: &[CommittedTransactionChunk],
+    slow_chunk_latency_threshold: Duration,
+    driver_metrics: &DriverMetrics,
+) {
+    for committed_chunk in committed_chunks {
+        if committed_chunk.get_total_duration() > slow_chunk_latency_threshold {
+            driver_metrics
+                .increment_counter(&metrics::DRIVER_COUNTERS, metrics::SLOW_TRANSACTION_CHUNK);
+            let last_version = committed_chunk.first_version.map(|first_version| {
+                first_version + committed_chunk.num_versions.saturating_sub(1)
+            });
+            warn!(
+                LogSchema::new(LogEntry::StorageSynchronizer).message(&format!(
+                    "Found a slow transaction chunk! Versions: {:?} to {:?}, instruction: {:?}, \
+                execute/apply time: {:?}, commit time: {:?}, commit bytes: {:?}.",
+                    committed_chunk.first_version,
+                    last_version,
+                    committed_chunk.apply_or_execute,
+                    committed_chunk.execute_duration,
+                    committed_chunk.commit_duration,
+                    committed_chunk.commit_bytes,
+                ))
+            );
+        }
+    }
 }
 
 /// Initializes all relevant metric gauges (e.g., after a reboot
 /// or after a state snapshot has been restored).
-pub fn initialize_sync_gauges(storage: Arc<dyn DbReader>) -> Result<(), Error> {
-    // Update the latest synced versions
-    let highest_synced_version = fetch_latest_synced_version(storage.clone())?;
+pub fn initialize_sync_gauges(
+    storage: Arc<dyn DbReader>,
+    driver_metrics: &DriverMetrics,
+) -> Result<(), Error> {
+    // Update the latest synced versions (there's nothing to update if
+    // storage is empty, i.e., before genesis).
+    let highest_synced_version = match fetch_latest_synced_version(storage.clone())? {
+        Some(highest_synced_version) => highest_synced_version,
+        None => return Ok(()),
+    };
     let metrics = [
         metrics::StorageSynchronizerOperations::AppliedTransactionOutputs,
         metrics::StorageSynchronizerOperations::ExecutedTransactions,
         metrics::StorageSynchronizerOperations::Synced,
     ];
     for metric in metrics {
-        metrics::set_gauge(
+        driver_metrics.set_gauge(
             &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
             metric.get_label(),
             highest_synced_version,
@@ -241,8 +937,8 @@ pub fn initialize_sync_gauges(storage: Arc<dyn DbReader>) -> Result<(), Error> {
     }
 
     // Update the latest synced epoch
-    let highest_synced_epoch = fetch_latest_epoch_state(storage)?.epoch;
-    metrics::set_gauge(
+    let highest_synced_epoch = fetch_latest_epoch_state_from_storage(storage)?.epoch;
+    driver_metrics.set_gauge(
         &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
         metrics::StorageSynchronizerOperations::SyncedEpoch.get_label(),
         highest_synced_epoch,
@@ -251,84 +947,238 @@ pub fn initialize_sync_gauges(storage: Arc<dyn DbReader>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Handles a notification for committed transactions by
-/// notifying mempool and the event subscription service.
+/// Extracts the reconfiguration events from the given events, and returns
+/// them along with whether or not a new epoch event was found (i.e., whether
+/// the events ended the epoch). An event is only considered a reconfiguration
+/// event if its key matches the new epoch event key exactly (i.e., both the
+/// account address and the creation number), so look-alike events are
+/// filtered out. All commit paths must identify reconfigurations using this
+/// helper, otherwise config updates could be missed on some paths.
+pub fn extract_reconfig_events(events: &[ContractEvent]) -> (Vec<ContractEvent>, bool) {
+    let reconfig_events: Vec<ContractEvent> = events
+        .iter()
+        .filter(|event| is_reconfiguration_event(event))
+        .cloned()
+        .collect();
+    let contains_new_epoch_event = !reconfig_events.is_empty();
+    (reconfig_events, contains_new_epoch_event)
+}
+
+/// Handles a notification for committed transactions (made by either
+/// consensus or state sync) by recording the commit, handling any epoch
+/// transition and notifying mempool, the event subscription service and any
+/// registered commit subscribers. Returns a summary of the notifications sent.
+/// All commit paths share this routine, so callers should only add their
+/// source-specific behaviour (e.g., responding to consensus) and handle errors.
 pub async fn handle_committed_transactions<M: MempoolNotificationSender>(
     committed_transactions: CommittedTransactions,
+    commit_origin: CommitOrigin,
     storage: Arc<dyn DbReader>,
     mempool_notification_handler: MempoolNotificationHandler<M>,
-    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
-) {
-    // Fetch the latest synced version and ledger info from storage
-    let (latest_synced_version, latest_synced_ledger_info) =
-        match fetch_latest_synced_version(storage.clone()) {
-            Ok(latest_synced_version) => match fetch_latest_synced_ledger_info(storage.clone()) {
-                Ok(latest_synced_ledger_info) => (latest_synced_version, latest_synced_ledger_info),
-                Err(error) => {
-                    error!(LogSchema::new(LogEntry::SynchronizerNotification)
-                        .error(&error)
-                        .message("Failed to fetch latest synced ledger info!"));
-                    return;
-                }
-            },
-            Err(error) => {
-                error!(LogSchema::new(LogEntry::SynchronizerNotification)
-                    .error(&error)
-                    .message("Failed to fetch latest synced version!"));
-                return;
-            }
-        };
+    event_notification_handler: EventNotificationHandler,
+    commit_state_tracker: CommitStateTracker,
+    commit_subscriber_registry: CommitSubscriberRegistry,
+    notified_version_tracker: NotifiedVersionTracker,
+    driver_metrics: DriverMetrics,
+) -> Result<CommitSummary, Error> {
+    // Fetch the latest synced ledger info from storage
+    let latest_synced_ledger_info = fetch_latest_synced_ledger_info(storage)?;
+
+    // Record the commit (this is done before handling the notification, as
+    // the transactions have been committed, even if they were already notified).
+    let (first_version, last_version) = (
+        committed_transactions.first_version,
+        committed_transactions.last_version,
+    );
+    commit_state_tracker.update_commit_state(
+        last_version,
+        latest_synced_ledger_info.ledger_info().epoch(),
+    );
+    commit_subscriber_registry.notify_synced_version_waiters(&latest_synced_ledger_info);
+
+    // Count the committed chunks by how they were stored (i.e., applied or executed)
+    for committed_chunk in &committed_transactions.committed_chunks {
+        driver_metrics.increment_counter(
+            &metrics::COMMITTED_TRANSACTION_CHUNKS,
+            committed_chunk.apply_or_execute.get_label(),
+        );
+    }
+
+    // If the commit ended the epoch, handle the epoch transition
+    if committed_transactions.ends_epoch {
+        handle_epoch_transition(
+            &committed_transactions,
+            &latest_synced_ledger_info,
+            &commit_state_tracker,
+            &driver_metrics,
+        );
+    }
 
     // Handle the commit notification
-    if let Err(error) = CommitNotification::handle_transaction_notification(
-        committed_transactions.events,
-        committed_transactions.transactions,
-        latest_synced_version,
+    let commit_summary = CommitNotification::handle_transaction_notification(
+        committed_transactions,
+        commit_origin,
         latest_synced_ledger_info,
         mempool_notification_handler,
-        event_subscription_service,
+        event_notification_handler,
+        commit_subscriber_registry,
+        notified_version_tracker,
     )
-    .await
-    {
-        error!(LogSchema::new(LogEntry::SynchronizerNotification)
-            .error(&error)
-            .message("Failed to handle a transaction commit notification!"));
+    .await?;
+    update_commit_summary_metrics(commit_origin, &commit_summary, &driver_metrics);
+    sample!(
+        SampleRate::Duration(Duration::from_secs(COMMIT_SUMMARY_LOG_FREQ_SECS)),
+        info!(
+            LogSchema::new(LogEntry::SynchronizerNotification).message(&format!(
+                "Handled a {:?} commit notification for versions {:?} to {:?}. Summary: {:?}",
+                commit_origin, first_version, last_version, commit_summary
+            ))
+        )
+    );
+
+    Ok(commit_summary)
+}
+
+/// Logs an error encountered while handling a notification for the committed
+/// transactions at the given (inclusive) version range.
+pub fn log_commit_notification_error(
+    log_entry: LogEntry,
+    commit_origin: CommitOrigin,
+    first_version: Version,
+    last_version: Version,
+    error: &Error,
+) {
+    error!(LogSchema::new(log_entry).error(error).message(&format!(
+        "Failed to handle a {:?} commit notification for versions {:?} to {:?}!",
+        commit_origin, first_version, last_version
+    )));
+}
+
+/// Updates the commit metrics using the given commit summary
+fn update_commit_summary_metrics(
+    commit_origin: CommitOrigin,
+    commit_summary: &CommitSummary,
+    driver_metrics: &DriverMetrics,
+) {
+    let commit_origin = commit_origin.get_label();
+    let counters = [
+        (
+            metrics::COMMIT_SUMMARY_USER_TRANSACTIONS,
+            commit_summary.user_txns_notified,
+        ),
+        (
+            metrics::COMMIT_SUMMARY_EVENTS,
+            commit_summary.events_notified,
+        ),
+        (
+            metrics::COMMIT_SUMMARY_RECONFIG_EVENTS,
+            commit_summary.reconfig_events,
+        ),
+    ];
+    for (label, count) in counters {
+        driver_metrics
+            .counter(&metrics::COMMIT_SUMMARY_COUNTERS, &[commit_origin, label])
+            .inc_by(count as u64);
+    }
+
+    let latencies = [
+        (
+            metrics::COMMIT_SUMMARY_MEMPOOL,
+            commit_summary.mempool_latency,
+        ),
+        (
+            metrics::COMMIT_SUMMARY_EVENTS,
+            commit_summary.events_latency,
+        ),
+    ];
+    for (label, latency) in latencies {
+        driver_metrics
+            .histogram(&metrics::COMMIT_SUMMARY_LATENCY, &[commit_origin, label])
+            .observe(latency.as_secs_f64());
     }
 }
 
-/// Updates the metrics to handle an epoch change event
-pub fn update_new_epoch_metrics(storage: Arc<dyn DbReader>) {
-    // Increment the epoch
-    metrics::increment_gauge(
+/// Handles an epoch transition for the given (epoch ending) committed
+/// transactions. This refreshes the cached epoch state and, if the transition
+/// hasn't already been handled (commits might be notified multiple times),
+/// updates the epoch metrics and logs the transition. This is invoked for all
+/// epoch ending commits (i.e., by consensus, the bootstrapper and the
+/// continuous syncer).
+pub fn handle_epoch_transition(
+    committed_transactions: &CommittedTransactions,
+    latest_synced_ledger_info: &LedgerInfoWithSignatures,
+    commit_state_tracker: &CommitStateTracker,
+    driver_metrics: &DriverMetrics,
+) {
+    // Invalidate the cached epoch state (if it's now stale)
+    let latest_epoch = latest_synced_ledger_info.ledger_info().next_block_epoch();
+    if commit_state_tracker.invalidate_epoch_state(latest_epoch) {
+        driver_metrics.increment_counter(
+            &metrics::DRIVER_COUNTERS,
+            metrics::EPOCH_STATE_CACHE_INVALIDATION,
+        );
+    }
+
+    // Cache the new epoch state (if it's known)
+    let new_epoch_state = committed_transactions.new_epoch_state.clone();
+    if let Some(new_epoch_state) = new_epoch_state.clone() {
+        commit_state_tracker.update_epoch_state(new_epoch_state);
+    }
+
+    // Record the transition and update the epoch metrics (only once)
+    let new_epoch = new_epoch_state
+        .as_ref()
+        .map_or(latest_epoch, |new_epoch_state| new_epoch_state.epoch);
+    if let Some(epoch_transition) = commit_state_tracker.record_epoch_transition(new_epoch) {
+        driver_metrics.increment_counter(&metrics::DRIVER_COUNTERS, metrics::EPOCH_TRANSITION);
+        update_new_epoch_metrics(epoch_transition, new_epoch_state, driver_metrics);
+        info!(LogSchema::new(LogEntry::EpochTransition).message(&format!(
+            "Entered a new epoch! Epoch: {:?}, version: {:?}, timestamp (usecs): {:?}, \
+                previous epoch duration: {:?}",
+            epoch_transition.epoch,
+            committed_transactions.last_version,
+            latest_synced_ledger_info.ledger_info().timestamp_usecs(),
+            epoch_transition.previous_epoch_duration,
+        )));
+    }
+}
+
+/// Updates the metrics to handle an epoch transition (where the state of the
+/// new epoch is optional, as it might be unknown to the committer).
+pub fn update_new_epoch_metrics(
+    epoch_transition: EpochTransition,
+    new_epoch_state: Option<EpochState>,
+    driver_metrics: &DriverMetrics,
+) {
+    // Update the epoch
+    driver_metrics.set_gauge(
         &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
         metrics::StorageSynchronizerOperations::SyncedEpoch.get_label(),
-        1,
+        epoch_transition.epoch,
     );
 
+    // Update the duration of the previous epoch (if known)
+    if let Some(previous_epoch_duration) = epoch_transition.previous_epoch_duration {
+        driver_metrics
+            .histogram(&metrics::EPOCH_DURATION, &[])
+            .observe(previous_epoch_duration.as_secs_f64());
+    }
+
     // Update the validator set accounts in the epoch
-    match fetch_latest_epoch_state(storage) {
-        Ok(latest_epoch_state) => {
-            let epoch = metrics::read_gauge(
-                &metrics::STORAGE_SYNCHRONIZER_OPERATIONS,
-                metrics::StorageSynchronizerOperations::SyncedEpoch.get_label(),
-            );
-            let validator_verifier = latest_epoch_state.verifier;
-            for validator_address in validator_verifier.get_ordered_account_addresses_iter() {
-                let validator_weight = validator_verifier
-                    .get_voting_power(&validator_address)
-                    .unwrap_or(0);
-                metrics::set_epoch_state_gauge(
-                    &epoch.to_string(),
-                    &validator_address.to_string(),
-                    &validator_weight.to_string(),
-                );
-            }
-        }
-        Err(error) => {
-            error!(LogSchema::new(LogEntry::Driver).message(&format!(
-                "Failed to get the latest epoch state from storage! Error: {:?}",
-                error
-            )));
-        }
+    let new_epoch_state = match new_epoch_state {
+        Some(new_epoch_state) => new_epoch_state,
+        None => return,
+    };
+    let epoch = new_epoch_state.epoch.to_string();
+    let validator_verifier = new_epoch_state.verifier;
+    for validator_address in validator_verifier.get_ordered_account_addresses_iter() {
+        let validator_weight = validator_verifier
+            .get_voting_power(&validator_address)
+            .unwrap_or(0);
+        driver_metrics.set_epoch_state_gauge(
+            &epoch,
+            &validator_address.to_string(),
+            &validator_weight.to_string(),
+        );
     }
 }
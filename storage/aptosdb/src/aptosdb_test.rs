@@ -1,9 +1,15 @@
 // Copyright (c) Aptos
 // SPDX-License-Identifier: Apache-2.0
 
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use aptos_config::config::{LedgerPrunerConfig, StateMerklePrunerConfig};
+use aptos_config::config::{
+    LedgerPrunerConfig, PrunerConfig, RocksdbConfigs, StateMerklePrunerConfig,
+    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD, TARGET_SNAPSHOT_SIZE,
+};
 use proptest::prelude::*;
 
 use crate::{
@@ -23,8 +29,10 @@ use aptos_types::{
     proof::SparseMerkleLeafNode,
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::{ExecutionStatus, TransactionInfo},
+    write_set::WriteSet,
 };
-use storage_interface::{DbReader, ExecutedTrees, Order};
+use schemadb::SchemaBatch;
+use storage_interface::{DbReader, DbWriter, ExecutedTrees, Order};
 use test_helper::{test_save_blocks_impl, test_sync_transactions_impl};
 
 proptest! {
@@ -160,6 +168,73 @@ fn test_error_if_version_is_pruned() {
     assert!(error_if_version_is_pruned(&ledger_pruner, "Transaction", 10).is_ok());
 }
 
+#[test]
+fn test_pause_and_resume_pruning() {
+    let tmp_dir = TempPath::new();
+    let aptos_db = AptosDB::open(
+        &tmp_dir,
+        false, /* readonly */
+        PrunerConfig {
+            ledger_pruner_config: LedgerPrunerConfig {
+                enable: true,
+                prune_window: 0,
+                batch_size: 1,
+                user_pruning_window_offset: 0,
+            },
+            state_merkle_pruner_config: StateMerklePrunerConfig {
+                enable: false,
+                prune_window: 0,
+                batch_size: 1,
+                user_pruning_window_offset: 0,
+            },
+        },
+        RocksdbConfigs::default(),
+        false, /* enable_indexer */
+        TARGET_SNAPSHOT_SIZE,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    )
+    .unwrap();
+
+    // Write a few write sets to the ledger db
+    let num_write_sets = 10;
+    let mut batch = SchemaBatch::new();
+    for version in 0..num_write_sets {
+        aptos_db
+            .transaction_store
+            .put_write_set(version, &WriteSet::default(), &mut batch)
+            .unwrap();
+    }
+    aptos_db.ledger_db.write_schemas(batch).unwrap();
+
+    // Pause pruning and move the pruner target (both via the db and directly via the pruner)
+    aptos_db.pause_pruning().unwrap();
+    aptos_db.set_pruner_target_version(num_write_sets);
+    aptos_db
+        .ledger_pruner
+        .set_pruner_target_db_version(num_write_sets);
+
+    // Give the pruner worker a chance to run and verify that nothing was pruned
+    std::thread::sleep(Duration::from_millis(500));
+    assert_eq!(aptos_db.ledger_pruner.get_min_readable_version(), 0);
+    for version in 0..num_write_sets {
+        aptos_db.transaction_store.get_write_set(version).unwrap();
+    }
+
+    // Resume pruning and verify that the write sets are eventually pruned
+    aptos_db.resume_pruning().unwrap();
+    let timeout = Instant::now() + Duration::from_secs(10);
+    while aptos_db.ledger_pruner.get_min_readable_version() < num_write_sets {
+        assert!(Instant::now() < timeout, "Timeout waiting for the pruner!");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    for version in 0..num_write_sets {
+        assert!(aptos_db.transaction_store.get_write_set(version).is_err());
+    }
+
+    // Resuming pruning again (without a pause) should fail
+    assert!(aptos_db.resume_pruning().is_err());
+}
+
 #[test]
 fn test_get_latest_executed_trees() {
     let tmp_dir = TempPath::new();
@@ -51,7 +51,7 @@ use crate::{
     state_store::StateStore,
     transaction_store::TransactionStore,
 };
-use anyhow::{bail, ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 #[cfg(any(test, feature = "fuzzing"))]
 use aptos_config::config::DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD;
 use aptos_config::config::{
@@ -263,6 +263,9 @@ pub struct AptosDB {
     transaction_store: Arc<TransactionStore>,
     state_pruner: StatePrunerManager,
     ledger_pruner: LedgerPrunerManager,
+    /// The number of outstanding pruning pauses. The pruner workers are paused
+    /// (and the pruner target versions aren't updated) unless this is zero.
+    pruning_pause_count: Mutex<u64>,
     _rocksdb_property_reporter: RocksdbPropertyReporter,
     ledger_commit_lock: std::sync::Mutex<()>,
     indexer: Option<Indexer>,
@@ -305,6 +308,7 @@ impl AptosDB {
             transaction_store: Arc::new(TransactionStore::new(Arc::clone(&arc_ledger_rocksdb))),
             state_pruner,
             ledger_pruner,
+            pruning_pause_count: Mutex::new(0),
             _rocksdb_property_reporter: RocksdbPropertyReporter::new(
                 Arc::clone(&arc_ledger_rocksdb),
                 Arc::clone(&arc_state_merkle_rocksdb),
@@ -812,6 +816,9 @@ impl AptosDB {
     }
 
     fn set_pruner_target_version(&self, latest_version: Version) {
+        if *self.pruning_pause_count.lock() > 0 {
+            return; // Pruning is paused
+        }
         self.state_pruner
             .maybe_set_pruner_target_db_version(latest_version);
         self.ledger_pruner
@@ -1665,6 +1672,31 @@ impl DbWriter for AptosDB {
             Ok(())
         })
     }
+
+    fn pause_pruning(&self) -> Result<()> {
+        let mut pause_count = self.pruning_pause_count.lock();
+        if *pause_count == 0 {
+            // This waits for any in-flight pruning batches to complete
+            self.state_pruner.pause_pruner_worker();
+            self.ledger_pruner.pause_pruner_worker();
+        }
+        *pause_count += 1;
+        info!(pause_count = *pause_count, "Pruning paused.");
+        Ok(())
+    }
+
+    fn resume_pruning(&self) -> Result<()> {
+        let mut pause_count = self.pruning_pause_count.lock();
+        *pause_count = pause_count
+            .checked_sub(1)
+            .ok_or_else(|| format_err!("Pruning was resumed without being paused!"))?;
+        if *pause_count == 0 {
+            self.state_pruner.resume_pruner_worker();
+            self.ledger_pruner.resume_pruner_worker();
+        }
+        info!(pause_count = *pause_count, "Pruning resumed.");
+        Ok(())
+    }
 }
 
 // Convert requested range and order to a range in ascending order.
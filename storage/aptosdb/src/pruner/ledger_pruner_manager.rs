@@ -91,6 +91,14 @@ impl PrunerManager for LedgerPrunerManager {
             .set_target_db_version(latest_version.saturating_sub(self.prune_window));
     }
 
+    fn pause_pruner_worker(&self) {
+        self.pruner_worker.pause_pruning();
+    }
+
+    fn resume_pruner_worker(&self) {
+        self.pruner_worker.resume_pruning();
+    }
+
     #[cfg(test)]
     fn wake_and_wait_pruner(&self, latest_version: Version) -> anyhow::Result<()> {
         use std::{
@@ -3,6 +3,7 @@
 use crate::pruner::db_pruner::DBPruner;
 use crate::pruner::ledger_store::ledger_store_pruner::LedgerPruner;
 use aptos_config::config::LedgerPrunerConfig;
+use aptos_infallible::Mutex;
 use aptos_logger::{
     error,
     prelude::{sample, SampleRate},
@@ -28,6 +29,9 @@ pub struct LedgerPrunerWorker {
     /// Indicates whether the pruning loop should be running. Will only be set to true on pruner
     /// destruction.
     quit_worker: AtomicBool,
+    /// Indicates whether the worker is paused (i.e., nothing is pruned until the worker is
+    /// resumed). The lock is held while pruning each batch.
+    paused: Mutex<bool>,
 }
 
 impl LedgerPrunerWorker {
@@ -40,15 +44,23 @@ impl LedgerPrunerWorker {
             pruner: ledger_pruner,
             max_versions_to_prune_per_batch: ledger_pruner_config.batch_size as u64,
             quit_worker: AtomicBool::new(false),
+            paused: Mutex::new(false),
         }
     }
 
     // Loop that does the real pruning job.
     pub(crate) fn work(&self) {
         while !self.quit_worker.load(Ordering::Relaxed) {
+            let paused = self.paused.lock();
+            if *paused {
+                drop(paused);
+                sleep(Duration::from_millis(self.pruning_time_interval_in_ms));
+                continue;
+            }
             let pruner_result = self
                 .pruner
                 .prune(self.max_versions_to_prune_per_batch as usize);
+            drop(paused);
             if pruner_result.is_err() {
                 sample!(
                     SampleRate::Duration(Duration::from_secs(1)),
@@ -72,4 +84,13 @@ impl LedgerPrunerWorker {
     pub fn stop_pruning(&self) {
         self.quit_worker.store(true, Ordering::Relaxed);
     }
+
+    /// Pauses the worker. This waits for the batch currently being pruned (if any) to complete.
+    pub fn pause_pruning(&self) {
+        *self.paused.lock() = true;
+    }
+
+    pub fn resume_pruning(&self) {
+        *self.paused.lock() = false;
+    }
 }
@@ -26,6 +26,13 @@ pub trait PrunerManager: Debug + Sync {
 
     fn set_pruner_target_db_version(&self, latest_version: Version);
 
+    /// Pauses the worker thread (if any). Nothing is pruned until the worker is resumed, even if
+    /// the target version moves.
+    fn pause_pruner_worker(&self);
+
+    /// Resumes the worker thread (if any).
+    fn resume_pruner_worker(&self);
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
@@ -93,6 +93,14 @@ impl PrunerManager for StatePrunerManager {
             .set_target_db_version(latest_version.saturating_sub(self.prune_window));
     }
 
+    fn pause_pruner_worker(&self) {
+        self.pruner_worker.pause_pruning();
+    }
+
+    fn resume_pruner_worker(&self) {
+        self.pruner_worker.resume_pruning();
+    }
+
     /// (For tests only.) Notifies the worker thread and waits for it to finish its job by polling
     /// an internal counter.
     #[cfg(test)]
@@ -30,7 +30,13 @@ use aptos_types::{
 };
 use itertools::Itertools;
 use mirai_annotations::*;
-use std::{cmp::Eq, collections::HashMap, hash::Hash, sync::Arc};
+use std::{
+    cmp::Eq,
+    collections::HashMap,
+    hash::Hash,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use storage_interface::StateSnapshotReceiver;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -161,6 +167,10 @@ struct JellyfishMerkleRestore<K> {
 
     /// When the restoration process finishes, we expect the tree to have this root hash.
     expected_root_hash: HashValue,
+
+    /// The time spent verifying the proof of the most recently added chunk (if the chunk
+    /// was verified, i.e., it wasn't skipped entirely).
+    last_verification_duration: Option<Duration>,
 }
 
 impl<K> JellyfishMerkleRestore<K>
@@ -197,6 +207,7 @@ where
             previous_leaf,
             num_keys_received: 0,
             expected_root_hash,
+            last_verification_duration: None,
         })
     }
 
@@ -213,6 +224,7 @@ where
             previous_leaf: None,
             num_keys_received: 0,
             expected_root_hash,
+            last_verification_duration: None,
         })
     }
 
@@ -298,6 +310,7 @@ where
         mut chunk: Vec<(&K, HashValue)>,
         proof: SparseMerkleRangeProof,
     ) -> Result<()> {
+        self.last_verification_duration = None;
         if let Some(prev_leaf) = &self.previous_leaf {
             let skip_until = chunk
                 .iter()
@@ -340,7 +353,9 @@ where
         }
 
         // Verify what we have added so far is all correct.
+        let verification_start_time = Instant::now();
         self.verify(proof)?;
+        self.last_verification_duration = Some(verification_start_time.elapsed());
 
         // Write the frozen nodes to storage.
         self.store.write_node_batch(&self.frozen_nodes)?;
@@ -804,6 +819,10 @@ impl<K: crate::Key + CryptoHash + Hash + Eq, V: crate::Value> StateSnapshotRecei
         Ok(())
     }
 
+    fn last_chunk_verification_duration(&self) -> Option<Duration> {
+        self.tree_restore.last_verification_duration
+    }
+
     fn finish(self) -> Result<()> {
         self.kv_restore.finish()?;
         self.tree_restore.finish_impl()
@@ -37,6 +37,8 @@ pub use rocksdb::{
     BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, ReadOptions,
     SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
+/// The error type returned by RocksDB (and the kinds used to classify the errors)
+pub use rocksdb::{Error as RocksDbError, ErrorKind as RocksDbErrorKind};
 pub type ColumnFamilyName = &'static str;
 
 #[derive(Debug)]
@@ -34,7 +34,7 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use thiserror::Error;
 
 pub mod async_proof_fetcher;
@@ -54,6 +54,12 @@ pub use executed_trees::ExecutedTrees;
 pub trait StateSnapshotReceiver<K, V>: Send {
     fn add_chunk(&mut self, chunk: Vec<(K, V)>, proof: SparseMerkleRangeProof) -> Result<()>;
 
+    /// Returns the time spent verifying the proof of the most recently added
+    /// chunk, if known (e.g., chunks that are skipped entirely aren't verified).
+    fn last_chunk_verification_duration(&self) -> Option<Duration> {
+        None
+    }
+
     fn finish(self) -> Result<()>;
 
     fn finish_box(self: Box<Self>) -> Result<()>;
@@ -592,6 +598,21 @@ pub trait DbWriter: Send + Sync {
         unimplemented!()
     }
 
+    /// Pauses pruning, i.e., the pruner target versions stop moving until
+    /// pruning is resumed. This is used while restoring a state snapshot at an
+    /// old version. Pauses are counted, so each pause must be paired with a
+    /// call to `resume_pruning()`. Writers that wrap another writer must
+    /// forward the call (the default returns an error, so that pruning is
+    /// never silently left running).
+    fn pause_pruning(&self) -> Result<()> {
+        Err(format_err!("Pausing pruning isn't supported by this writer!"))
+    }
+
+    /// Resumes pruning previously paused by `pause_pruning()`.
+    fn resume_pruning(&self) -> Result<()> {
+        Err(format_err!("Resuming pruning isn't supported by this writer!"))
+    }
+
     /// Persist transactions. Called by the executor module when either syncing nodes or committing
     /// blocks during normal operation.
     /// See [`AptosDB::save_transactions`].
@@ -1272,6 +1272,34 @@ impl TransactionOutputListWithProof {
         Self::new(vec![], None, TransactionInfoListWithProof::new_empty())
     }
 
+    /// Verifies that the events of each transaction output match the event root
+    /// hash carried on the corresponding transaction info. Note: this doesn't
+    /// verify that the transaction infos themselves are proven (see `verify()`).
+    pub fn verify_event_root_hashes(&self) -> Result<()> {
+        ensure!(
+            self.proof.transaction_infos.len() == self.transactions_and_outputs.len(),
+            "The number of TransactionInfo objects ({}) does not match the number of \
+             transactions and outputs ({}).",
+            self.proof.transaction_infos.len(),
+            self.transactions_and_outputs.len(),
+        );
+        for (index, ((_, txn_output), txn_info)) in self
+            .transactions_and_outputs
+            .iter()
+            .zip(self.proof.transaction_infos.iter())
+            .enumerate()
+        {
+            verify_events_against_root_hash(&txn_output.events, txn_info).map_err(|error| {
+                format_err!(
+                    "Event root hash mismatch for the transaction output at index {}: {}",
+                    index,
+                    error
+                )
+            })?;
+        }
+        Ok(())
+    }
+
     /// Verifies the transaction output list with proof using the given `ledger_info`.
     /// This method will ensure:
     /// 1. All transaction infos exist on the given `ledger_info`.